@@ -19,7 +19,7 @@ pub enum Error {
 
 /// Clear Auth Settings
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema), schema(as = nut21::Settings))]
 pub struct Settings {
     /// Openid discovery
     pub openid_discovery: String,
@@ -41,7 +41,7 @@ pub enum Error {
 
 /// Blind auth settings
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema), schema(as = nut22::Settings))]
 pub struct Settings {
     /// Max number of blind auth tokens that can be minted per request
     pub bat_max_mint: u64,
@@ -25,6 +25,7 @@ pub mod nut19;
 pub mod nut20;
 pub mod nut23;
 pub mod nut25;
+pub mod nut29;
 
 #[cfg(feature = "auth")]
 mod auth;
@@ -64,8 +65,12 @@ pub use nut18::{
     PaymentRequest, PaymentRequestBuilder, PaymentRequestPayload, Transport, TransportBuilder,
     TransportType,
 };
+pub use nut20::{MintQuotesByPubkeyRequest, MintQuotesByPubkeyResponse};
 pub use nut23::{
     MeltOptions, MeltQuoteBolt11Request, MeltQuoteBolt11Response, MintQuoteBolt11Request,
     MintQuoteBolt11Response, QuoteState as MintQuoteState,
 };
 pub use nut25::{MeltQuoteBolt12Request, MintQuoteBolt12Request, MintQuoteBolt12Response};
+pub use nut29::{
+    BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, Settings as NUT29Settings,
+};
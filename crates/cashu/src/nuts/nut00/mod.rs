@@ -555,7 +555,6 @@ where
 /// Currency Unit
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
 pub enum CurrencyUnit {
     /// Sat
     #[default]
@@ -629,6 +628,23 @@ impl Serialize for CurrencyUnit {
     }
 }
 
+/// Serialized as a lowercase string
+#[cfg(feature = "swagger")]
+impl utoipa::PartialSchema for CurrencyUnit {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(
+                "Currency Unit, `sat`, `msat`, `usd`, `eur`, `auth` or a custom unit",
+            ))
+            .examples(["sat"])
+            .into()
+    }
+}
+
+#[cfg(feature = "swagger")]
+impl utoipa::ToSchema for CurrencyUnit {}
+
 impl<'de> Deserialize<'de> for CurrencyUnit {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -641,7 +657,6 @@ impl<'de> Deserialize<'de> for CurrencyUnit {
 
 /// Payment Method
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
 pub enum PaymentMethod {
     /// Bolt11 payment type
     #[default]
@@ -682,6 +697,23 @@ impl Serialize for PaymentMethod {
     }
 }
 
+/// Serialized as a lowercase string
+#[cfg(feature = "swagger")]
+impl utoipa::PartialSchema for PaymentMethod {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(
+                "Payment Method, `bolt11`, `bolt12` or a custom method",
+            ))
+            .examples(["bolt11"])
+            .into()
+    }
+}
+
+#[cfg(feature = "swagger")]
+impl utoipa::ToSchema for PaymentMethod {}
+
 impl<'de> Deserialize<'de> for PaymentMethod {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
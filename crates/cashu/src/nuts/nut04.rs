@@ -108,11 +108,32 @@ impl Serialize for MintMethodSettings {
         }
 
         let mut description_in_top_level = false;
-        if let Some(MintMethodOptions::Bolt11 { description }) = &self.options {
+        let mut description_hash_in_top_level = false;
+        let mut min_expiry = None;
+        let mut max_expiry = None;
+        if let Some(MintMethodOptions::Bolt11 {
+            description,
+            description_hash,
+            min_expiry: options_min_expiry,
+            max_expiry: options_max_expiry,
+        }) = &self.options
+        {
             if *description {
                 num_fields += 1;
                 description_in_top_level = true;
             }
+            if *description_hash {
+                num_fields += 1;
+                description_hash_in_top_level = true;
+            }
+            if options_min_expiry.is_some() {
+                num_fields += 1;
+                min_expiry = *options_min_expiry;
+            }
+            if options_max_expiry.is_some() {
+                num_fields += 1;
+                max_expiry = *options_max_expiry;
+            }
         }
 
         let mut state = serializer.serialize_struct("MintMethodSettings", num_fields)?;
@@ -133,6 +154,19 @@ impl Serialize for MintMethodSettings {
             state.serialize_field("description", &true)?;
         }
 
+        // Same for the description hash flag and the quote expiry bounds
+        if description_hash_in_top_level {
+            state.serialize_field("description_hash", &true)?;
+        }
+
+        if let Some(min_expiry) = min_expiry {
+            state.serialize_field("min_expiry", &min_expiry)?;
+        }
+
+        if let Some(max_expiry) = max_expiry {
+            state.serialize_field("max_expiry", &max_expiry)?;
+        }
+
         state.end()
     }
 }
@@ -155,6 +189,9 @@ impl<'de> Visitor<'de> for MintMethodSettingsVisitor {
         let mut min_amount: Option<Amount> = None;
         let mut max_amount: Option<Amount> = None;
         let mut description: Option<bool> = None;
+        let mut description_hash: Option<bool> = None;
+        let mut min_expiry: Option<u64> = None;
+        let mut max_expiry: Option<u64> = None;
 
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
@@ -188,19 +225,49 @@ impl<'de> Visitor<'de> for MintMethodSettingsVisitor {
                     }
                     description = Some(map.next_value()?);
                 }
+                "description_hash" => {
+                    if description_hash.is_some() {
+                        return Err(de::Error::duplicate_field("description_hash"));
+                    }
+                    description_hash = Some(map.next_value()?);
+                }
+                "min_expiry" => {
+                    if min_expiry.is_some() {
+                        return Err(de::Error::duplicate_field("min_expiry"));
+                    }
+                    min_expiry = Some(map.next_value()?);
+                }
+                "max_expiry" => {
+                    if max_expiry.is_some() {
+                        return Err(de::Error::duplicate_field("max_expiry"));
+                    }
+                    max_expiry = Some(map.next_value()?);
+                }
                 "options" => {
-                    // If there are explicit options, they take precedence, except the description
-                    // field which we will handle specially
+                    // If there are explicit options, they take precedence, except the fields
+                    // also found at the top level which we will handle specially
                     let options: Option<MintMethodOptions> = map.next_value()?;
 
                     if let Some(MintMethodOptions::Bolt11 {
                         description: desc_from_options,
+                        description_hash: desc_hash_from_options,
+                        min_expiry: min_expiry_from_options,
+                        max_expiry: max_expiry_from_options,
                     }) = options
                     {
-                        // If we already found a top-level description, use that instead
+                        // If we already found a top-level value, use that instead
                         if description.is_none() {
                             description = Some(desc_from_options);
                         }
+                        if description_hash.is_none() {
+                            description_hash = Some(desc_hash_from_options);
+                        }
+                        if min_expiry.is_none() {
+                            min_expiry = min_expiry_from_options;
+                        }
+                        if max_expiry.is_none() {
+                            max_expiry = max_expiry_from_options;
+                        }
                     }
                 }
                 _ => {
@@ -213,9 +280,18 @@ impl<'de> Visitor<'de> for MintMethodSettingsVisitor {
         let method = method.ok_or_else(|| de::Error::missing_field("method"))?;
         let unit = unit.ok_or_else(|| de::Error::missing_field("unit"))?;
 
-        // Create options based on the method and the description flag
-        let options = if method == PaymentMethod::Bolt11 {
-            description.map(|description| MintMethodOptions::Bolt11 { description })
+        // Create options based on the method and the bolt11 flags
+        let has_options = description.is_some()
+            || description_hash.is_some()
+            || min_expiry.is_some()
+            || max_expiry.is_some();
+        let options = if method == PaymentMethod::Bolt11 && has_options {
+            Some(MintMethodOptions::Bolt11 {
+                description: description.unwrap_or_default(),
+                description_hash: description_hash.unwrap_or_default(),
+                min_expiry,
+                max_expiry,
+            })
         } else {
             None
         };
@@ -248,9 +324,57 @@ pub enum MintMethodOptions {
     Bolt11 {
         /// Mint supports setting bolt11 description
         description: bool,
+        /// Mint supports setting bolt11 description hash
+        #[serde(default)]
+        description_hash: bool,
+        /// Min quote expiry in seconds a wallet can request
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_expiry: Option<u64>,
+        /// Max quote expiry in seconds a wallet can request
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_expiry: Option<u64>,
     },
 }
 
+impl MintMethodOptions {
+    /// Mint supports setting bolt11 description
+    pub fn description(&self) -> bool {
+        match self {
+            Self::Bolt11 { description, .. } => *description,
+        }
+    }
+
+    /// Mint supports setting bolt11 description hash
+    pub fn description_hash(&self) -> bool {
+        match self {
+            Self::Bolt11 {
+                description_hash, ..
+            } => *description_hash,
+        }
+    }
+
+    /// Max quote expiry in seconds a wallet can request
+    pub fn max_expiry(&self) -> Option<u64> {
+        match self {
+            Self::Bolt11 { max_expiry, .. } => *max_expiry,
+        }
+    }
+
+    /// Check a requested quote expiry in seconds is within the allowed bounds
+    pub fn expiry_allowed(&self, expiry_secs: u64) -> bool {
+        match self {
+            Self::Bolt11 {
+                min_expiry,
+                max_expiry,
+                ..
+            } => {
+                min_expiry.is_none_or(|min| expiry_secs >= min)
+                    && max_expiry.is_none_or(|max| expiry_secs <= max)
+            }
+        }
+    }
+}
+
 /// Mint Settings
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema), schema(as = nut04::Settings))]
@@ -332,7 +456,7 @@ mod tests {
         assert_eq!(settings.max_amount, Some(Amount::from(10000)));
 
         match settings.options {
-            Some(MintMethodOptions::Bolt11 { description }) => {
+            Some(MintMethodOptions::Bolt11 { description, .. }) => {
                 assert!(description);
             }
             _ => panic!("Expected Bolt11 options with description = true"),
@@ -364,10 +488,49 @@ mod tests {
         let settings: MintMethodSettings = from_str(json_str).unwrap();
 
         match settings.options {
-            Some(MintMethodOptions::Bolt11 { description }) => {
+            Some(MintMethodOptions::Bolt11 { description, .. }) => {
                 assert!(description, "Top-level description should take precedence");
             }
             _ => panic!("Expected Bolt11 options with description = true"),
         }
     }
+
+    #[test]
+    fn test_mint_method_settings_description_hash_and_expiry() {
+        let json_str = r#"{
+            "method": "bolt11",
+            "unit": "sat",
+            "min_amount": 0,
+            "max_amount": 10000,
+            "description_hash": true,
+            "min_expiry": 60,
+            "max_expiry": 3600
+        }"#;
+
+        let settings: MintMethodSettings = from_str(json_str).unwrap();
+
+        let options = settings.options.clone().unwrap();
+        assert_eq!(
+            options,
+            MintMethodOptions::Bolt11 {
+                description: false,
+                description_hash: true,
+                min_expiry: Some(60),
+                max_expiry: Some(3600),
+            }
+        );
+        assert!(!options.expiry_allowed(59));
+        assert!(options.expiry_allowed(60));
+        assert!(options.expiry_allowed(3600));
+        assert!(!options.expiry_allowed(3601));
+
+        // Serialize it back, the options stay at the top level
+        let serialized = to_string(&settings).unwrap();
+        let parsed: serde_json::Value = from_str(&serialized).unwrap();
+
+        assert_eq!(parsed["description_hash"], json!(true));
+        assert_eq!(parsed["min_expiry"], json!(60));
+        assert_eq!(parsed["max_expiry"], json!(3600));
+        assert!(parsed.get("description").is_none());
+    }
 }
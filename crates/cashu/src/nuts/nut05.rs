@@ -416,6 +416,18 @@ impl Settings {
     pub fn supported_units(&self) -> Vec<&CurrencyUnit> {
         self.methods.iter().map(|s| &s.unit).collect()
     }
+
+    /// Whether paying amountless bolt11 invoices is supported for unit
+    pub fn amountless_supported(&self, unit: &CurrencyUnit) -> bool {
+        self.methods.iter().any(|s| {
+            s.method == PaymentMethod::Bolt11
+                && s.unit.eq(unit)
+                && matches!(
+                    s.options,
+                    Some(MeltMethodOptions::Bolt11 { amountless: true })
+                )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -483,4 +495,31 @@ mod tests {
             _ => panic!("Expected Bolt11 options with amountless = true"),
         }
     }
+
+    #[test]
+    fn test_amountless_supported() {
+        let settings = Settings::new(
+            vec![
+                MeltMethodSettings {
+                    method: PaymentMethod::Bolt11,
+                    unit: CurrencyUnit::Sat,
+                    min_amount: None,
+                    max_amount: None,
+                    options: Some(MeltMethodOptions::Bolt11 { amountless: true }),
+                },
+                MeltMethodSettings {
+                    method: PaymentMethod::Bolt11,
+                    unit: CurrencyUnit::Usd,
+                    min_amount: None,
+                    max_amount: None,
+                    options: Some(MeltMethodOptions::Bolt11 { amountless: false }),
+                },
+            ],
+            false,
+        );
+
+        assert!(settings.amountless_supported(&CurrencyUnit::Sat));
+        assert!(!settings.amountless_supported(&CurrencyUnit::Usd));
+        assert!(!settings.amountless_supported(&CurrencyUnit::Msat));
+    }
 }
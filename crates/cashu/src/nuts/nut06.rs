@@ -11,7 +11,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use super::nut01::PublicKey;
 use super::nut17::SupportedMethods;
 use super::nut19::CachedEndpoint;
-use super::{nut04, nut05, nut15, nut19, MppMethodSettings};
+use super::{nut04, nut05, nut15, nut19, nut29, MppMethodSettings};
 #[cfg(feature = "auth")]
 use super::{AuthRequired, BlindAuthSettings, ClearAuthSettings, ProtectedEndpoint};
 use crate::CurrencyUnit;
@@ -337,6 +337,11 @@ pub struct Nuts {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[cfg(feature = "auth")]
     pub nut22: Option<BlindAuthSettings>,
+    /// NUT29 Settings
+    #[serde(default)]
+    #[serde(rename = "29")]
+    #[serde(skip_serializing_if = "nut29::Settings::is_empty")]
+    pub nut29: nut29::Settings,
 }
 
 impl Nuts {
@@ -430,7 +435,10 @@ impl Nuts {
     /// Nut17 settings
     pub fn nut17(self, supported: Vec<SupportedMethods>) -> Self {
         Self {
-            nut17: super::nut17::SupportedSettings { supported },
+            nut17: super::nut17::SupportedSettings {
+                supported,
+                sse: self.nut17.sse,
+            },
             ..self
         }
     }
@@ -454,6 +462,14 @@ impl Nuts {
         }
     }
 
+    /// Nut29 settings
+    pub fn nut29(self, nut29_settings: nut29::Settings) -> Self {
+        Self {
+            nut29: nut29_settings,
+            ..self
+        }
+    }
+
     /// Units where minting is supported
     pub fn supported_mint_units(&self) -> Vec<&CurrencyUnit> {
         self.nut04
@@ -673,7 +689,13 @@ mod tests {
 
         let t = t.options.unwrap();
 
-        matches!(t, MintMethodOptions::Bolt11 { description: true });
+        matches!(
+            t,
+            MintMethodOptions::Bolt11 {
+                description: true,
+                ..
+            }
+        );
 
         assert_eq!(info, mint_info);
     }
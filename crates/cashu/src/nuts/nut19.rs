@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 /// Mint settings
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema), schema(as = nut19::Settings))]
 pub struct Settings {
     /// Number of seconds the responses are cached for
     pub ttl: Option<u64>,
@@ -61,4 +61,7 @@ pub enum Path {
     /// Bolt12 Melt
     #[serde(rename = "/v1/melt/bolt12")]
     MeltBolt12,
+    /// Bolt11 Batch Mint
+    #[serde(rename = "/v1/mint/bolt11/batch")]
+    MintBolt11Batch,
 }
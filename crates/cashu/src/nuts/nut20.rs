@@ -3,9 +3,14 @@
 use std::str::FromStr;
 
 use bitcoin::secp256k1::schnorr::Signature;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{MintRequest, PublicKey, SecretKey};
+use super::{MintQuoteBolt11Response, MintRequest, PublicKey, SecretKey};
+
+/// Seconds a [`MintQuotesByPubkeyRequest`] stays valid around its timestamp
+pub const MINT_QUOTES_REQUEST_VALIDITY: u64 = 300;
 
 /// Nut19 Error
 #[derive(Debug, Error)]
@@ -16,6 +21,9 @@ pub enum Error {
     /// Quote signature invalid signature
     #[error("Quote signature invalid signature")]
     InvalidSignature,
+    /// Request timestamp too far from the current time
+    #[error("Request timestamp out of range")]
+    TimestampOutOfRange,
     /// Nut01 error
     #[error(transparent)]
     NUT01(#[from] crate::nuts::nut01::Error),
@@ -71,6 +79,68 @@ where
     }
 }
 
+/// Request for the mint quotes locked to a pubkey
+///
+/// Signed with the secret key of the pubkey, so only its owner can look up quotes it did not
+/// create itself, such as the quotes the mint creates for its lightning addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+pub struct MintQuotesByPubkeyRequest {
+    /// Pubkey the quotes are locked to
+    pub pubkey: PublicKey,
+    /// Unix time the request was signed at
+    pub timestamp: u64,
+    /// Signature of the pubkey and timestamp
+    pub signature: String,
+}
+
+impl MintQuotesByPubkeyRequest {
+    /// Create a request signed with `secret_key` at `timestamp`
+    pub fn new(secret_key: &SecretKey, timestamp: u64) -> Result<Self, Error> {
+        let pubkey = secret_key.public_key();
+        let signature: Signature = secret_key.sign(&Self::msg(&pubkey, timestamp))?;
+
+        Ok(Self {
+            pubkey,
+            timestamp,
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Message signed by the request
+    ///
+    /// Format: `"mint_quotes" || pubkey || timestamp`, with the pubkey as hex and the
+    /// timestamp in decimal, all as UTF-8 bytes.
+    fn msg(pubkey: &PublicKey, timestamp: u64) -> Vec<u8> {
+        format!("mint_quotes{}{}", pubkey.to_hex(), timestamp).into_bytes()
+    }
+
+    /// Verify the signature and that the request was signed within
+    /// [`MINT_QUOTES_REQUEST_VALIDITY`] of `now`
+    pub fn verify(&self, now: u64) -> Result<(), Error> {
+        if self.timestamp.abs_diff(now) > MINT_QUOTES_REQUEST_VALIDITY {
+            return Err(Error::TimestampOutOfRange);
+        }
+
+        let signature =
+            Signature::from_str(&self.signature).map_err(|_| Error::InvalidSignature)?;
+
+        self.pubkey
+            .verify(&Self::msg(&self.pubkey, self.timestamp), &signature)?;
+
+        Ok(())
+    }
+}
+
+/// Mint quotes locked to the pubkey of a [`MintQuotesByPubkeyRequest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[serde(bound = "Q: Serialize + DeserializeOwned")]
+pub struct MintQuotesByPubkeyResponse<Q> {
+    /// Quotes
+    pub quotes: Vec<MintQuoteBolt11Response<Q>>,
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -149,4 +219,33 @@ mod tests {
         // Signature is on a different quote id verification should fail
         assert!(request.verify_signature(pubkey).is_err());
     }
+
+    #[test]
+    fn test_mint_quotes_by_pubkey_request() {
+        let secret = SecretKey::generate();
+        let request = MintQuotesByPubkeyRequest::new(&secret, 1_000_000).unwrap();
+
+        assert_eq!(request.pubkey, secret.public_key());
+        assert!(request.verify(1_000_000).is_ok());
+        assert!(request
+            .verify(1_000_000 + MINT_QUOTES_REQUEST_VALIDITY)
+            .is_ok());
+        assert!(matches!(
+            request.verify(1_000_000 + MINT_QUOTES_REQUEST_VALIDITY + 1),
+            Err(Error::TimestampOutOfRange)
+        ));
+
+        // Signature does not cover another pubkey or timestamp
+        let other = MintQuotesByPubkeyRequest {
+            pubkey: SecretKey::generate().public_key(),
+            ..request.clone()
+        };
+        assert!(other.verify(1_000_000).is_err());
+
+        let later = MintQuotesByPubkeyRequest {
+            timestamp: 1_000_001,
+            ..request
+        };
+        assert!(later.verify(1_000_000).is_err());
+    }
 }
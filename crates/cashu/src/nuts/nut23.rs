@@ -3,6 +3,7 @@
 use std::fmt;
 use std::str::FromStr;
 
+use bitcoin::hashes::sha256::Hash as Sha256;
 use lightning_invoice::Bolt11Invoice;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -39,6 +40,13 @@ pub struct MintQuoteBolt11Request {
     /// Memo to create the invoice with
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Hash of the description to commit to in the invoice instead of a memo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "swagger", schema(value_type = Option<String>))]
+    pub description_hash: Option<Sha256>,
+    /// Requested time in seconds until the quote expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_secs: Option<u64>,
     /// NUT-19 Pubkey
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pubkey: Option<PublicKey>,
@@ -0,0 +1,123 @@
+//! NUT-29: Batch Minting
+//!
+//! Checks the state of many mint quotes and mints several paid quotes in a single request.
+//! Every quote of a batch mint keeps its own outputs and [NUT-20] signature, and the whole batch
+//! succeeds or fails together.
+
+#[cfg(feature = "mint")]
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::nut00::PaymentMethod;
+use super::{MintRequest, MintResponse};
+#[cfg(feature = "mint")]
+use crate::quote_id::QuoteId;
+#[cfg(feature = "mint")]
+use crate::quote_id::QuoteIdError;
+
+/// Batch check mint quote request [NUT-29]
+///
+/// The response lists the state of the known quotes, unknown quotes are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[serde(bound = "Q: Serialize + DeserializeOwned")]
+pub struct BatchCheckMintQuoteRequest<Q> {
+    /// Quote ids
+    #[cfg_attr(feature = "swagger", schema(max_items = 1_000))]
+    pub quotes: Vec<Q>,
+}
+
+#[cfg(feature = "mint")]
+impl TryFrom<BatchCheckMintQuoteRequest<String>> for BatchCheckMintQuoteRequest<QuoteId> {
+    type Error = QuoteIdError;
+
+    fn try_from(value: BatchCheckMintQuoteRequest<String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            quotes: value
+                .quotes
+                .iter()
+                .map(|quote| QuoteId::from_str(quote))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Batch mint request [NUT-29]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[serde(bound = "Q: Serialize + DeserializeOwned")]
+pub struct BatchMintRequest<Q> {
+    /// Mint requests, one per quote
+    #[cfg_attr(feature = "swagger", schema(max_items = 1_000))]
+    pub requests: Vec<MintRequest<Q>>,
+}
+
+#[cfg(feature = "mint")]
+impl TryFrom<BatchMintRequest<String>> for BatchMintRequest<QuoteId> {
+    type Error = QuoteIdError;
+
+    fn try_from(value: BatchMintRequest<String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            requests: value
+                .requests
+                .into_iter()
+                .map(MintRequest::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Batch mint response [NUT-29]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+pub struct BatchMintResponse {
+    /// Mint responses, in the order of the requests
+    pub responses: Vec<MintResponse>,
+}
+
+/// Batch minting settings
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema), schema(as = nut29::Settings))]
+pub struct Settings {
+    /// Payment methods supporting batch checks and batch minting
+    #[serde(default)]
+    pub methods: Vec<PaymentMethod>,
+    /// Maximum number of quotes in a batch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_size: Option<u64>,
+}
+
+impl Settings {
+    /// Check if methods is empty
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+
+    /// Whether batches are supported for the payment method
+    pub fn supports(&self, method: &PaymentMethod) -> bool {
+        self.methods.contains(method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_serde() {
+        let settings = Settings {
+            methods: vec![PaymentMethod::Bolt11],
+            max_batch_size: Some(100),
+        };
+
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(json, r#"{"methods":["bolt11"],"max_batch_size":100}"#);
+
+        let parsed: Settings = serde_json::from_str(r#"{"methods":["bolt11"]}"#).unwrap();
+        assert!(parsed.supports(&PaymentMethod::Bolt11));
+        assert!(!parsed.supports(&PaymentMethod::Bolt12));
+        assert_eq!(parsed.max_batch_size, None);
+    }
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use axum::extract::{FromRequestParts, State};
+use axum::extract::{FromRequestParts, Path, State};
 use axum::http::request::Parts;
 use axum::http::StatusCode;
 use axum::response::Response;
@@ -9,7 +9,7 @@ use axum::{Json, Router};
 #[cfg(feature = "swagger")]
 use cdk::error::ErrorResponse;
 use cdk::nuts::{
-    AuthToken, BlindAuthToken, KeysResponse, KeysetResponse, MintAuthRequest, MintResponse,
+    AuthToken, BlindAuthToken, Id, KeysResponse, KeysetResponse, MintAuthRequest, MintResponse,
 };
 use serde::{Deserialize, Serialize};
 
@@ -128,6 +128,26 @@ pub async fn get_blind_auth_keys(
     Ok(Json(pubkeys))
 }
 
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    context_path = "/v1/auth/blind",
+    path = "/keys/{keyset_id}",
+    params(
+        ("keyset_id" = String, description = "The keyset ID"),
+    ),
+    responses(
+        (status = 200, description = "Successful response", body = KeysResponse, content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+/// Get the public keys of a specific blind auth keyset
+pub async fn get_blind_auth_keyset_pubkeys(
+    state: State<MintState>,
+    keyset_id: Path<Id>,
+) -> Result<Json<KeysResponse>, Response> {
+    get_keyset_pubkeys(state, keyset_id).await
+}
+
 /// Mint tokens by paying a BOLT11 Lightning invoice.
 ///
 /// Requests the minting of tokens belonging to a paid payment request.
@@ -140,6 +160,7 @@ pub async fn get_blind_auth_keys(
     request_body(content = MintAuthRequest, description = "Request params", content_type = "application/json"),
     responses(
         (status = 200, description = "Successful response", body = MintResponse, content_type = "application/json"),
+        (status = 400, description = "Invalid request or missing clear auth", body = ErrorResponse, content_type = "application/json"),
         (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
     )
 ))]
@@ -182,7 +203,7 @@ pub fn create_auth_router(state: MintState) -> Router<MintState> {
             Router::new()
                 .route("/keys", get(get_blind_auth_keys))
                 .route("/keysets", get(get_auth_keysets))
-                .route("/keys/{keyset_id}", get(get_keyset_pubkeys))
+                .route("/keys/{keyset_id}", get(get_blind_auth_keyset_pubkeys))
                 .route("/mint", post(post_mint_auth)),
         )
         .with_state(state)
@@ -25,14 +25,16 @@ post_cache_wrapper!(
 );
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
+    post,
     context_path = "/v1",
     path = "/mint/quote/bolt12",
+    request_body(content = MintQuoteBolt12Request, description = "Request params", content_type = "application/json"),
     responses(
-        (status = 200, description = "Successful response", body = MintQuoteBolt12Response<String>, content_type = "application/json")
+        (status = 200, description = "Successful response", body = MintQuoteBolt12Response<String>, content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
     )
 ))]
-/// Get mint bolt12 quote
+/// Request a mint bolt12 quote
 #[instrument(skip_all, fields(amount = ?payload.amount))]
 pub async fn post_mint_bolt12_quote(
     #[cfg(feature = "auth")] auth: AuthHeader,
@@ -117,6 +119,11 @@ pub async fn post_mint_bolt12(
     State(state): State<MintState>,
     Json(payload): Json<MintRequest<QuoteId>>,
 ) -> Result<Json<MintResponse>, Response> {
+    state
+        .limits
+        .check_outputs(payload.outputs.len())
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -151,6 +158,7 @@ pub async fn post_mint_bolt12(
         (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
     )
 ))]
+/// Request a quote for melting tokens to a bolt12 offer
 pub async fn post_melt_bolt12_quote(
     #[cfg(feature = "auth")] auth: AuthHeader,
     State(state): State<MintState>,
@@ -177,6 +185,46 @@ pub async fn post_melt_bolt12_quote(
     Ok(Json(quote))
 }
 
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/melt/quote/bolt12/{quote_id}",
+    params(
+        ("quote_id" = String, description = "The quote ID"),
+    ),
+    responses(
+        (status = 200, description = "Successful response", body = MeltQuoteBolt11Response<String>, content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+/// Get melt bolt12 quote
+#[instrument(skip_all, fields(quote_id = ?quote_id))]
+pub async fn get_check_melt_bolt12_quote(
+    #[cfg(feature = "auth")] auth: AuthHeader,
+    State(state): State<MintState>,
+    Path(quote_id): Path<QuoteId>,
+) -> Result<Json<MeltQuoteBolt11Response<QuoteId>>, Response> {
+    #[cfg(feature = "auth")]
+    {
+        state
+            .mint
+            .verify_auth(
+                auth.into(),
+                &ProtectedEndpoint::new(Method::Get, RoutePath::MeltQuoteBolt12),
+            )
+            .await
+            .map_err(into_response)?;
+    }
+
+    let quote = state
+        .mint
+        .check_melt_quote(&quote_id)
+        .await
+        .map_err(into_response)?;
+
+    Ok(Json(quote))
+}
+
 #[cfg_attr(feature = "swagger", utoipa::path(
     post,
     context_path = "/v1",
@@ -195,6 +243,15 @@ pub async fn post_melt_bolt12(
     State(state): State<MintState>,
     Json(payload): Json<MeltRequest<QuoteId>>,
 ) -> Result<Json<MeltQuoteBolt11Response<QuoteId>>, Response> {
+    state
+        .limits
+        .check_inputs(payload.inputs().len())
+        .map_err(into_response)?;
+    state
+        .limits
+        .check_outputs(payload.outputs().as_ref().map_or(0, Vec::len))
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -15,6 +15,7 @@ use axum::routing::{get, post};
 use axum::Router;
 use cache::HttpCache;
 use cdk::mint::Mint;
+use limits::RateLimiter;
 use router_handlers::*;
 
 mod metrics;
@@ -23,7 +24,10 @@ mod metrics;
 mod auth;
 mod bolt12_router;
 pub mod cache;
+pub mod limits;
+mod lnurl_pay;
 mod router_handlers;
+mod sse;
 mod ws;
 
 #[cfg(feature = "swagger")]
@@ -45,21 +49,30 @@ mod swagger_imports {
     pub use cdk::nuts::nut12::{BlindSignatureDleq, ProofDleq};
     pub use cdk::nuts::nut14::HTLCWitness;
     pub use cdk::nuts::nut15::{Mpp, MppMethodSettings};
+    pub use cdk::nuts::nut20::{MintQuotesByPubkeyRequest, MintQuotesByPubkeyResponse};
     pub use cdk::nuts::nut23::{
         MeltQuoteBolt11Request, MeltQuoteBolt11Response, MintQuoteBolt11Request,
         MintQuoteBolt11Response,
     };
+    pub use cdk::nuts::nut25::{
+        MeltQuoteBolt12Request, MintQuoteBolt12Request, MintQuoteBolt12Response,
+    };
+    pub use cdk::nuts::nut29::{BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse};
     #[cfg(feature = "auth")]
     pub use cdk::nuts::MintAuthRequest;
-    pub use cdk::nuts::{nut04, nut05, nut15, MeltQuoteState, MintQuoteState};
+    pub use cdk::nuts::{nut04, nut05, nut15, nut29, MeltQuoteState, MintQuoteState};
 }
 
 #[cfg(feature = "swagger")]
 use swagger_imports::*;
 
 use crate::bolt12_router::{
-    cache_post_melt_bolt12, cache_post_mint_bolt12, get_check_mint_bolt12_quote,
-    post_melt_bolt12_quote, post_mint_bolt12_quote,
+    cache_post_melt_bolt12, cache_post_mint_bolt12, get_check_melt_bolt12_quote,
+    get_check_mint_bolt12_quote, post_melt_bolt12_quote, post_mint_bolt12_quote,
+};
+pub use crate::lnurl_pay::{
+    create_lnurl_pay_router, LnurlErrorResponse, LnurlPayCallbackParams, LnurlPayCallbackResponse,
+    LnurlPayRequest, LnurlPaySettings,
 };
 
 /// CDK Mint State
@@ -67,6 +80,8 @@ use crate::bolt12_router::{
 pub struct MintState {
     mint: Arc<Mint>,
     cache: Arc<cache::HttpCache>,
+    limits: Arc<limits::Limits>,
+    sse_streams: sse::SseStreams,
 }
 
 #[cfg(feature = "swagger")]
@@ -92,17 +107,34 @@ macro_rules! define_api_doc {
                 post_mint_bolt11_quote,
                 get_check_mint_bolt11_quote,
                 post_mint_bolt11,
+                post_check_mint_bolt11_quotes,
+                post_mint_bolt11_batch,
                 post_melt_bolt11_quote,
                 get_check_melt_bolt11_quote,
                 post_melt_bolt11,
                 post_swap,
                 post_check,
-                post_restore
+                post_restore,
+                ws_handler,
+                sse::sse_handler,
+                sse::post_sse_request,
+                bolt12_router::post_mint_bolt12_quote,
+                bolt12_router::get_check_mint_bolt12_quote,
+                bolt12_router::post_mint_bolt12,
+                bolt12_router::post_melt_bolt12_quote,
+                bolt12_router::get_check_melt_bolt12_quote,
+                bolt12_router::post_melt_bolt12,
+                lnurl_pay::get_lnurl_pay_request,
+                lnurl_pay::get_lnurl_pay_callback,
+                lnurl_pay::post_lnurl_pay_quotes
                 $(,$($path,)*)?
                 $(,$($auth_path,)*)?
             )
         )]
         /// Swagger api docs
+        ///
+        /// Covers every route served by [`create_mint_router`], including the optional bolt12
+        /// routes, and the [`create_lnurl_pay_router`] routes.
         pub struct ApiDoc;
     };
 }
@@ -112,6 +144,9 @@ macro_rules! define_api_doc {
 define_api_doc! {
     schemas: [
         Amount,
+        BatchCheckMintQuoteRequest<String>,
+        BatchMintRequest<String>,
+        BatchMintResponse,
         BlindedMessage,
         BlindSignature,
         BlindSignatureDleq,
@@ -127,9 +162,13 @@ define_api_doc! {
         KeysetResponse,
         KeySet,
         KeySetInfo,
+        LnurlErrorResponse,
+        LnurlPayCallbackResponse,
+        LnurlPayRequest,
         MeltRequest<String>,
         MeltQuoteBolt11Request,
         MeltQuoteBolt11Response<String>,
+        MeltQuoteBolt12Request,
         MeltQuoteState,
         MeltMethodSettings,
         MintRequest<String>,
@@ -137,6 +176,10 @@ define_api_doc! {
         MintInfo,
         MintQuoteBolt11Request,
         MintQuoteBolt11Response<String>,
+        MintQuoteBolt12Request,
+        MintQuoteBolt12Response<String>,
+        MintQuotesByPubkeyRequest,
+        MintQuotesByPubkeyResponse<String>,
         MintQuoteState,
         MintMethodSettings,
         MintVersion,
@@ -159,7 +202,8 @@ define_api_doc! {
         Witness,
         nut04::Settings,
         nut05::Settings,
-        nut15::Settings
+        nut15::Settings,
+        nut29::Settings
     ]
 }
 
@@ -168,6 +212,9 @@ define_api_doc! {
 define_api_doc! {
     schemas: [
         Amount,
+        BatchCheckMintQuoteRequest<String>,
+        BatchMintRequest<String>,
+        BatchMintResponse,
         BlindedMessage,
         BlindSignature,
         BlindSignatureDleq,
@@ -183,9 +230,13 @@ define_api_doc! {
         KeysetResponse,
         KeySet,
         KeySetInfo,
+        LnurlErrorResponse,
+        LnurlPayCallbackResponse,
+        LnurlPayRequest,
         MeltRequest<String>,
         MeltQuoteBolt11Request,
         MeltQuoteBolt11Response<String>,
+        MeltQuoteBolt12Request,
         MeltQuoteState,
         MeltMethodSettings,
         MintRequest<String>,
@@ -193,6 +244,10 @@ define_api_doc! {
         MintInfo,
         MintQuoteBolt11Request,
         MintQuoteBolt11Response<String>,
+        MintQuoteBolt12Request,
+        MintQuoteBolt12Response<String>,
+        MintQuotesByPubkeyRequest,
+        MintQuotesByPubkeyResponse<String>,
         MintQuoteState,
         MintMethodSettings,
         MintVersion,
@@ -215,12 +270,14 @@ define_api_doc! {
         Witness,
         nut04::Settings,
         nut05::Settings,
-        nut15::Settings
+        nut15::Settings,
+        nut29::Settings
     ],
     auth_schemas: [MintAuthRequest],
     auth_paths: [
         crate::auth::get_auth_keysets,
         crate::auth::get_blind_auth_keys,
+        crate::auth::get_blind_auth_keyset_pubkeys,
         crate::auth::post_mint_auth
     ]
 }
@@ -280,10 +337,26 @@ pub async fn create_mint_router_with_custom_cache(
     mint: Arc<Mint>,
     cache: HttpCache,
     include_bolt12: bool,
+) -> Result<Router> {
+    create_mint_router_with_limits(mint, cache, include_bolt12, Default::default()).await
+}
+
+/// Create mint [`Router`] with required endpoints for cashu mint with a custom
+/// backend for cache, per-client rate limits and max batch sizes
+///
+/// The IP of the clients is only known when the router is served with
+/// [`Router::into_make_service_with_connect_info`].
+pub async fn create_mint_router_with_limits(
+    mint: Arc<Mint>,
+    cache: HttpCache,
+    include_bolt12: bool,
+    limits: limits::Limits,
 ) -> Result<Router> {
     let state = MintState {
         mint,
         cache: Arc::new(cache),
+        limits: Arc::new(limits),
+        sse_streams: Default::default(),
     };
 
     let v1_router = Router::new()
@@ -296,9 +369,16 @@ pub async fn create_mint_router_with_custom_cache(
             "/mint/quote/bolt11/{quote_id}",
             get(get_check_mint_bolt11_quote),
         )
+        .route(
+            "/mint/quote/bolt11/check",
+            post(post_check_mint_bolt11_quotes),
+        )
         .route("/mint/bolt11", post(cache_post_mint_bolt11))
+        .route("/mint/bolt11/batch", post(cache_post_mint_bolt11_batch))
         .route("/melt/quote/bolt11", post(post_melt_bolt11_quote))
         .route("/ws", get(ws_handler))
+        .route("/sse", post(sse::sse_handler))
+        .route("/sse/{stream_id}", post(sse::post_sse_request))
         .route(
             "/melt/quote/bolt11/{quote_id}",
             get(get_check_melt_bolt11_quote),
@@ -324,6 +404,15 @@ pub async fn create_mint_router_with_custom_cache(
         mint_router
     };
 
+    let mint_router = if state.limits.is_rate_limited() {
+        mint_router.route_layer(axum::middleware::from_fn_with_state(
+            RateLimiter::new(state.limits.clone(), state.mint.clone()),
+            limits::rate_limit_middleware,
+        ))
+    } else {
+        mint_router
+    };
+
     #[cfg(feature = "prometheus")]
     let mint_router = mint_router.layer(axum::middleware::from_fn_with_state(
         state.clone(),
@@ -341,7 +430,7 @@ fn create_bolt12_router(state: MintState) -> Router<MintState> {
         .route("/melt/quote/bolt12", post(post_melt_bolt12_quote))
         .route(
             "/melt/quote/bolt12/{quote_id}",
-            get(get_check_melt_bolt11_quote),
+            get(get_check_melt_bolt12_quote),
         )
         .route("/melt/bolt12", post(cache_post_melt_bolt12))
         .route("/mint/quote/bolt12", post(post_mint_bolt12_quote))
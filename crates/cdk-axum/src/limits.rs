@@ -0,0 +1,315 @@
+//! Per-client rate limiting and request batch sizes
+//!
+//! Every client has a budget of requests per route, enforced with the generic cell rate algorithm
+//! (GCRA): a budget of `requests` per `per_secs` seconds allows bursts of `requests` and then one
+//! request every `per_secs / requests` seconds.
+//!
+//! Clients are keyed by their IP, or with [`ClientKey::Auth`] by the subject of their NUT-21 clear
+//! auth token once the token is verified. Blind auth tokens are unlinkable, so the requests paying
+//! with one are keyed by their IP like unauthenticated requests.
+//!
+//! Websocket connections are bounded by [`WsLimits`]: the number of subscriptions and filters of a
+//! connection, the notifications queued for a slow client, and how long a silent client is kept.
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+#[cfg(feature = "auth")]
+use bitcoin::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+#[cfg(feature = "auth")]
+use bitcoin::base64::Engine;
+use cdk::error::Error;
+use cdk::mint::Mint;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+
+use crate::router_handlers::into_response;
+
+/// Max number of clients tracked at once
+const MAX_TRACKED_CLIENTS: u64 = 100_000;
+
+/// Budget of requests of a client on a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteBudget {
+    /// Number of requests allowed in the period
+    pub requests: u32,
+    /// Period in seconds
+    pub per_secs: u64,
+}
+
+impl RouteBudget {
+    /// Time between two requests once the burst is used
+    fn emission_interval(&self) -> Duration {
+        Duration::from_secs(self.per_secs) / self.requests.max(1)
+    }
+}
+
+/// Identity the budgets of a client are keyed by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientKey {
+    /// IP address of the client
+    #[default]
+    Ip,
+    /// Subject of a verified NUT-21 clear auth token, falling back to the IP for other requests
+    Auth,
+}
+
+/// What to do with the notifications of a websocket client that does not read them fast enough
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+    /// Drop the notifications that do not fit in the send queue
+    #[default]
+    Drop,
+    /// Close the connection once the send queue is full
+    Disconnect,
+}
+
+/// Limits of the NUT-17 websocket connections
+///
+/// Server-sent event streams share the subscription caps, send queue and overflow policy, and
+/// are kept alive with a comment every `ping_interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WsLimits {
+    /// Max number of subscriptions of a connection
+    pub max_subscriptions: Option<usize>,
+    /// Max number of filters of all the subscriptions of a connection
+    pub max_filters: Option<usize>,
+    /// Number of notifications queued for a connection before the overflow policy applies
+    pub send_queue: usize,
+    /// What to do once the send queue is full
+    pub overflow: OverflowPolicy,
+    /// Seconds between two pings, 0 disables pings
+    pub ping_interval: u64,
+    /// Seconds without any frame from the client, or without it accepting one, before the
+    /// connection is closed, 0 disables the timeout
+    pub idle_timeout: u64,
+}
+
+impl Default for WsLimits {
+    fn default() -> Self {
+        Self {
+            max_subscriptions: None,
+            max_filters: None,
+            send_queue: 100,
+            overflow: OverflowPolicy::Drop,
+            ping_interval: 30,
+            idle_timeout: 90,
+        }
+    }
+}
+
+/// Rate limits and max batch sizes of the mint router
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    /// Identity the budgets are keyed by
+    pub key: ClientKey,
+    /// Use the last address of the `X-Forwarded-For` header, the one appended by the reverse
+    /// proxy, as the IP of the client
+    ///
+    /// Only enable behind a single reverse proxy that appends the address of its client to the
+    /// header, as the addresses before it are set by the client.
+    pub trust_forwarded_for: bool,
+    /// Budget of the routes without their own budget, unlimited if `None`
+    pub default_budget: Option<RouteBudget>,
+    /// Budgets by route path, such as `/v1/mint/quote/bolt11` or
+    /// `/v1/mint/quote/bolt11/{quote_id}`
+    pub routes: HashMap<String, RouteBudget>,
+    /// Max number of inputs of a swap or melt
+    pub max_inputs: Option<usize>,
+    /// Max number of outputs of a swap, mint, melt or restore
+    pub max_outputs: Option<usize>,
+    /// Max number of Ys of a state check
+    pub max_ys: Option<usize>,
+    /// Limits of the websocket connections and server-sent event streams
+    pub websocket: WsLimits,
+}
+
+impl Limits {
+    /// Whether any route has a budget
+    pub fn is_rate_limited(&self) -> bool {
+        self.default_budget.is_some() || !self.routes.is_empty()
+    }
+
+    /// Check the number of inputs of a request
+    pub(crate) fn check_inputs(&self, count: usize) -> Result<(), Error> {
+        check_batch(count, self.max_inputs)
+    }
+
+    /// Check the number of outputs of a request
+    pub(crate) fn check_outputs(&self, count: usize) -> Result<(), Error> {
+        check_batch(count, self.max_outputs)
+    }
+
+    /// Check the number of Ys of a request
+    pub(crate) fn check_ys(&self, count: usize) -> Result<(), Error> {
+        check_batch(count, self.max_ys)
+    }
+}
+
+fn check_batch(count: usize, max: Option<usize>) -> Result<(), Error> {
+    match max {
+        Some(max) if count > max => Err(Error::MaxBatchSizeExceeded(count, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Tracks the budgets used by the clients
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    limits: Arc<Limits>,
+    /// Verifies the clear auth tokens the clients are keyed by
+    #[cfg_attr(not(feature = "auth"), allow(dead_code))]
+    mint: Arc<Mint>,
+    /// Theoretical arrival time of the next request of each client on each route
+    arrivals: Cache<(String, String), Arc<Mutex<Instant>>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: Arc<Limits>, mint: Arc<Mint>) -> Self {
+        let longest_period = limits
+            .routes
+            .values()
+            .chain(limits.default_budget.iter())
+            .map(|budget| budget.per_secs)
+            .max()
+            .unwrap_or_default();
+
+        Self {
+            limits,
+            mint,
+            // A client idle for a full period is back to its whole budget
+            arrivals: Cache::builder()
+                .max_capacity(MAX_TRACKED_CLIENTS)
+                .time_to_idle(Duration::from_secs(longest_period.max(1)))
+                .build(),
+        }
+    }
+
+    /// Consume a request from the budget of the client, returns how long to wait if exhausted
+    async fn acquire(&self, route: &str, client: String) -> Result<(), Duration> {
+        let Some(budget) = self
+            .limits
+            .routes
+            .get(route)
+            .or(self.limits.default_budget.as_ref())
+        else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        let arrival = self
+            .arrivals
+            .get_with((route.to_string(), client), async move {
+                Arc::new(Mutex::new(now))
+            })
+            .await;
+
+        let mut arrival = arrival
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let interval = budget.emission_interval();
+        let tolerance = Duration::from_secs(budget.per_secs).saturating_sub(interval);
+        let theoretical = (*arrival).max(now);
+
+        match theoretical.checked_duration_since(now) {
+            Some(ahead) if ahead > tolerance => Err(ahead - tolerance),
+            _ => {
+                *arrival = theoretical + interval;
+                Ok(())
+            }
+        }
+    }
+
+    /// Identity of the client of the request
+    async fn client_key(&self, headers: &HeaderMap, peer: Option<SocketAddr>) -> String {
+        #[cfg(feature = "auth")]
+        if self.limits.key == ClientKey::Auth {
+            if let Some(cat) = headers.get("Clear-auth").and_then(|cat| cat.to_str().ok()) {
+                match self.mint.verify_clear_auth(cat.to_string()).await {
+                    Ok(()) => {
+                        if let Some(subject) = cat_subject(cat) {
+                            return format!("cat:{subject}");
+                        }
+                    }
+                    Err(err) => tracing::debug!("Keying request with invalid cat by IP: {}", err),
+                }
+            }
+        }
+
+        if self.limits.trust_forwarded_for {
+            if let Some(ip) = forwarded_for(headers) {
+                return format!("ip:{ip}");
+            }
+        }
+
+        match peer {
+            Some(addr) => format!("ip:{}", addr.ip()),
+            None => "unknown".to_string(),
+        }
+    }
+}
+
+/// Subject of a clear auth token, which must be verified first
+#[cfg(feature = "auth")]
+fn cat_subject(cat: &str) -> Option<String> {
+    let payload = cat.split('.').nth(1)?;
+    let claims: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+
+    claims.get("sub")?.as_str().map(str::to_string)
+}
+
+/// Last address of the `X-Forwarded-For` header, appended by the reverse proxy
+///
+/// A client sending the header itself gets its address appended to it, so only the last address
+/// of the last header is trusted.
+fn forwarded_for(headers: &HeaderMap) -> Option<String> {
+    let value = headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .next_back()?
+        .to_str()
+        .ok()?;
+    let ip = value.rsplit(',').next()?.trim();
+
+    (!ip.is_empty()).then(|| ip.to_string())
+}
+
+/// Middleware rejecting the requests of clients over their budget
+pub(crate) async fn rate_limit_middleware(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    let client = limiter.client_key(request.headers(), peer).await;
+
+    if let Err(retry_after) = limiter.acquire(&route, client.clone()).await {
+        tracing::debug!("Rate limited {} on {}", client, route);
+
+        let mut response = into_response(Error::RateLimited);
+        let retry_after = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+            response.headers_mut().insert("Retry-After", value);
+        }
+        return response;
+    }
+
+    next.run(request).await
+}
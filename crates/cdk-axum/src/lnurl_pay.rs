@@ -0,0 +1,329 @@
+//! LNURL-pay server
+//!
+//! Serves lightning addresses (LUD-16) for users registered with the mint.
+//! Every payment creates a mint quote locked to the user's NUT-20 pubkey, so only
+//! the recipient can mint the received ecash. The recipient finds the paid quotes with a
+//! request signed by the same key.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use bitcoin::hashes::{sha256, Hash};
+use cdk::amount::to_unit;
+use cdk::mint::Mint;
+use cdk::nuts::{
+    CurrencyUnit, MintQuoteBolt11Request, MintQuoteBolt11Response, MintQuotesByPubkeyRequest,
+    MintQuotesByPubkeyResponse, PaymentMethod, PublicKey,
+};
+use cdk::util::unix_time;
+use cdk::Amount;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+/// LNURL-pay server settings
+#[derive(Debug, Clone)]
+pub struct LnurlPaySettings {
+    /// Public url of the mint, used to build the callback url
+    pub mint_url: String,
+    /// Domain of the lightning addresses
+    pub domain: String,
+    /// Unit of the mint quotes
+    pub unit: CurrencyUnit,
+    /// Min amount in msat that can be sent
+    pub min_sendable: Amount,
+    /// Max amount in msat that can be sent
+    pub max_sendable: Amount,
+    /// Registered usernames and the NUT-20 pubkey their quotes are locked to
+    pub users: HashMap<String, PublicKey>,
+}
+
+/// Most quotes returned by a single quote lookup
+const MAX_QUOTES: u64 = 100;
+
+#[derive(Clone)]
+pub(crate) struct LnurlPayState {
+    mint: Arc<Mint>,
+    settings: Arc<LnurlPaySettings>,
+}
+
+/// LNURL-pay request (LUD-06)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LnurlPayRequest {
+    /// Callback url to request the invoice from
+    pub callback: String,
+    /// Min amount in msat that can be sent
+    pub min_sendable: u64,
+    /// Max amount in msat that can be sent
+    pub max_sendable: u64,
+    /// Metadata committed to by the invoice description hash
+    pub metadata: String,
+    /// Request tag
+    pub tag: String,
+}
+
+/// LNURL-pay callback query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+pub struct LnurlPayCallbackParams {
+    /// Amount in msat
+    pub amount: u64,
+}
+
+/// LNURL-pay callback response (LUD-06)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+pub struct LnurlPayCallbackResponse {
+    /// Bolt11 payment request
+    pub pr: String,
+    /// Routes, always empty
+    pub routes: Vec<String>,
+}
+
+/// LNURL error response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+pub struct LnurlErrorResponse {
+    /// Always `ERROR`
+    pub status: String,
+    /// Reason of the error
+    pub reason: String,
+}
+
+/// Create [`Router`] serving lightning addresses for the registered users
+///
+/// Serves `/.well-known/lnurlp/{user}`, the `/lnurlp/{user}/callback` callback and
+/// `/lnurlp/quotes`, where users look up their paid quotes.
+/// Fails if the mint does not allow setting the invoice description hash on bolt11 mint
+/// quotes of the configured unit, as every callback would be rejected.
+pub async fn create_lnurl_pay_router(
+    mint: Arc<Mint>,
+    mut settings: LnurlPaySettings,
+) -> Result<Router> {
+    let mint_info = mint.mint_info().await?;
+    let description_hash = mint_info
+        .nuts
+        .nut04
+        .get_settings(&settings.unit, &PaymentMethod::Bolt11)
+        .and_then(|method| method.options)
+        .is_some_and(|options| options.description_hash());
+
+    if !description_hash {
+        bail!(
+            "Lnurl pay requires bolt11 mint quotes in {} with an invoice description hash",
+            settings.unit
+        );
+    }
+
+    // Lightning address usernames are case insensitive
+    settings.users = settings
+        .users
+        .into_iter()
+        .map(|(user, pubkey)| (user.to_lowercase(), pubkey))
+        .collect();
+
+    let state = LnurlPayState {
+        mint,
+        settings: Arc::new(settings),
+    };
+
+    Ok(Router::new()
+        .route("/.well-known/lnurlp/{user}", get(get_lnurl_pay_request))
+        .route("/lnurlp/{user}/callback", get(get_lnurl_pay_callback))
+        .route("/lnurlp/quotes", post(post_lnurl_pay_quotes))
+        .with_state(state))
+}
+
+fn lnurl_error(status: StatusCode, reason: impl Into<String>) -> Response {
+    (
+        status,
+        Json(LnurlErrorResponse {
+            status: "ERROR".to_string(),
+            reason: reason.into(),
+        }),
+    )
+        .into_response()
+}
+
+impl LnurlPaySettings {
+    fn metadata(&self, user: &str) -> String {
+        let identifier = format!("{}@{}", user, self.domain);
+
+        serde_json::json!([
+            ["text/plain", format!("Payment to {identifier}")],
+            ["text/identifier", identifier],
+        ])
+        .to_string()
+    }
+
+    fn user_pubkey(&self, user: &str) -> Option<(String, PublicKey)> {
+        let user = user.to_lowercase();
+        let pubkey = *self.users.get(&user)?;
+
+        Some((user, pubkey))
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/.well-known/lnurlp/{user}",
+    params(
+        ("user" = String, description = "The username of the lightning address"),
+    ),
+    responses(
+        (status = 200, description = "Successful response", body = LnurlPayRequest, content_type = "application/json"),
+        (status = 404, description = "Unknown user", body = LnurlErrorResponse, content_type = "application/json")
+    )
+))]
+/// Get LNURL-pay request for a user
+#[instrument(skip(state))]
+pub(crate) async fn get_lnurl_pay_request(
+    State(state): State<LnurlPayState>,
+    Path(user): Path<String>,
+) -> Result<Json<LnurlPayRequest>, Response> {
+    let settings = &state.settings;
+    let (user, _) = settings
+        .user_pubkey(&user)
+        .ok_or_else(|| lnurl_error(StatusCode::NOT_FOUND, "Unknown user"))?;
+
+    Ok(Json(LnurlPayRequest {
+        callback: format!(
+            "{}/lnurlp/{}/callback",
+            settings.mint_url.trim_end_matches('/'),
+            user
+        ),
+        min_sendable: settings.min_sendable.into(),
+        max_sendable: settings.max_sendable.into(),
+        metadata: settings.metadata(&user),
+        tag: "payRequest".to_string(),
+    }))
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/lnurlp/{user}/callback",
+    params(
+        ("user" = String, description = "The username of the lightning address"),
+        ("amount" = u64, Query, description = "Amount in msat"),
+    ),
+    responses(
+        (status = 200, description = "Successful response", body = LnurlPayCallbackResponse, content_type = "application/json"),
+        (status = 400, description = "Invalid amount", body = LnurlErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Unknown user", body = LnurlErrorResponse, content_type = "application/json")
+    )
+))]
+/// Create a mint quote locked to the user's pubkey and return its invoice
+#[instrument(skip(state))]
+pub(crate) async fn get_lnurl_pay_callback(
+    State(state): State<LnurlPayState>,
+    Path(user): Path<String>,
+    Query(params): Query<LnurlPayCallbackParams>,
+) -> Result<Json<LnurlPayCallbackResponse>, Response> {
+    let settings = &state.settings;
+    let (user, pubkey) = settings
+        .user_pubkey(&user)
+        .ok_or_else(|| lnurl_error(StatusCode::NOT_FOUND, "Unknown user"))?;
+
+    let amount_msat = Amount::from(params.amount);
+    if amount_msat < settings.min_sendable || amount_msat > settings.max_sendable {
+        return Err(lnurl_error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Amount must be between {} and {} msat",
+                settings.min_sendable, settings.max_sendable
+            ),
+        ));
+    }
+
+    let amount = to_unit(amount_msat, &CurrencyUnit::Msat, &settings.unit)
+        .map_err(|err| lnurl_error(StatusCode::BAD_REQUEST, err.to_string()))?;
+
+    // Only whole units can be minted
+    let amount_back = to_unit(amount, &settings.unit, &CurrencyUnit::Msat)
+        .map_err(|err| lnurl_error(StatusCode::BAD_REQUEST, err.to_string()))?;
+    if amount_back != amount_msat {
+        return Err(lnurl_error(
+            StatusCode::BAD_REQUEST,
+            format!("Amount must be a whole number of {}", settings.unit),
+        ));
+    }
+
+    let metadata = settings.metadata(&user);
+
+    let request = MintQuoteBolt11Request {
+        amount,
+        unit: settings.unit.clone(),
+        description: None,
+        pubkey: Some(pubkey),
+        description_hash: Some(sha256::Hash::hash(metadata.as_bytes())),
+        expiry_secs: None,
+    };
+
+    let quote: MintQuoteBolt11Response<String> = state
+        .mint
+        .get_mint_quote(request.into())
+        .await
+        .map_err(|err| {
+            tracing::error!("Could not create lnurl pay mint quote: {}", err);
+            lnurl_error(StatusCode::BAD_REQUEST, err.to_string())
+        })?
+        .into();
+
+    Ok(Json(LnurlPayCallbackResponse {
+        pr: quote.request,
+        routes: vec![],
+    }))
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    path = "/lnurlp/quotes",
+    request_body(content = MintQuotesByPubkeyRequest, description = "Request signed by the user's key", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Successful response", body = MintQuotesByPubkeyResponse<String>, content_type = "application/json"),
+        (status = 401, description = "Invalid signature or timestamp", body = LnurlErrorResponse, content_type = "application/json"),
+        (status = 404, description = "Unknown pubkey", body = LnurlErrorResponse, content_type = "application/json")
+    )
+))]
+/// Get the paid quotes of a user, newest first
+///
+/// The request is signed with the user's NUT-20 key, so the quotes are only revealed to the
+/// recipient who can mint them.
+#[instrument(skip_all)]
+pub(crate) async fn post_lnurl_pay_quotes(
+    State(state): State<LnurlPayState>,
+    Json(request): Json<MintQuotesByPubkeyRequest>,
+) -> Result<Json<MintQuotesByPubkeyResponse<String>>, Response> {
+    let settings = &state.settings;
+    if !settings
+        .users
+        .values()
+        .any(|pubkey| *pubkey == request.pubkey)
+    {
+        return Err(lnurl_error(StatusCode::NOT_FOUND, "Unknown pubkey"));
+    }
+
+    request
+        .verify(unix_time())
+        .map_err(|err| lnurl_error(StatusCode::UNAUTHORIZED, err.to_string()))?;
+
+    let quotes = state
+        .mint
+        .paid_mint_quotes_by_pubkey(&request.pubkey, &settings.unit, MAX_QUOTES)
+        .await
+        .map_err(|err| {
+            tracing::error!("Could not look up lnurl pay quotes: {}", err);
+            lnurl_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        })?;
+
+    Ok(Json(MintQuotesByPubkeyResponse {
+        quotes: quotes.into_iter().map(Into::into).collect(),
+    }))
+}
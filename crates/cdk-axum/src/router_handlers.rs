@@ -9,8 +9,9 @@ use cdk::mint::QuoteId;
 #[cfg(feature = "auth")]
 use cdk::nuts::nut21::{Method, ProtectedEndpoint, RoutePath};
 use cdk::nuts::{
-    CheckStateRequest, CheckStateResponse, Id, KeysResponse, KeysetResponse,
-    MeltQuoteBolt11Request, MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
+    BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, CheckStateRequest,
+    CheckStateResponse, Id, KeysResponse, KeysetResponse, MeltQuoteBolt11Request,
+    MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
     MintQuoteBolt11Response, MintRequest, MintResponse, RestoreRequest, RestoreResponse,
     SwapRequest, SwapResponse,
 };
@@ -145,6 +146,11 @@ macro_rules! post_cache_wrapper_with_prefer {
 
 post_cache_wrapper!(post_swap, SwapRequest, SwapResponse);
 post_cache_wrapper!(post_mint_bolt11, MintRequest<QuoteId>, MintResponse);
+post_cache_wrapper!(
+    post_mint_bolt11_batch,
+    BatchMintRequest<QuoteId>,
+    BatchMintResponse
+);
 post_cache_wrapper_with_prefer!(
     post_melt_bolt11,
     MeltRequest<QuoteId>,
@@ -299,6 +305,65 @@ pub(crate) async fn get_check_mint_bolt11_quote(
     Ok(Json(quote.try_into().map_err(into_response)?))
 }
 
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    context_path = "/v1",
+    path = "/mint/quote/bolt11/check",
+    request_body(content = BatchCheckMintQuoteRequest<String>, description = "Quote ids", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Successful response", body = [MintQuoteBolt11Response<String>], content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+/// Check many mint quotes
+///
+/// Get the state of many mint quotes at once. Unknown quotes are left out of the response.
+#[instrument(skip_all, fields(quotes = payload.quotes.len()))]
+pub(crate) async fn post_check_mint_bolt11_quotes(
+    #[cfg(feature = "auth")] auth: AuthHeader,
+    State(state): State<MintState>,
+    Json(payload): Json<BatchCheckMintQuoteRequest<QuoteId>>,
+) -> Result<Json<Vec<MintQuoteBolt11Response<QuoteId>>>, Response> {
+    #[cfg(feature = "auth")]
+    {
+        state
+            .mint
+            .verify_auth(
+                auth.into(),
+                &ProtectedEndpoint::new(Method::Get, RoutePath::MintQuoteBolt11),
+            )
+            .await
+            .map_err(into_response)?;
+    }
+
+    let quotes = state
+        .mint
+        .check_mint_quotes(&payload.quotes)
+        .await
+        .map_err(|err| {
+            tracing::error!("Could not check mint quotes: {}", err);
+            into_response(err)
+        })?;
+
+    // Quotes of other payment methods are unknown to this endpoint
+    Ok(Json(
+        quotes
+            .into_iter()
+            .filter_map(|quote| quote.try_into().ok())
+            .collect(),
+    ))
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    context_path = "/v1",
+    path = "/ws",
+    responses(
+        (status = 101, description = "Switching to the NUT-17 websocket protocol"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+/// Subscribe to NUT-17 notifications over a websocket
 #[instrument(skip_all)]
 pub(crate) async fn ws_handler(
     #[cfg(feature = "auth")] auth: AuthHeader,
@@ -341,6 +406,11 @@ pub(crate) async fn post_mint_bolt11(
     State(state): State<MintState>,
     Json(payload): Json<MintRequest<QuoteId>>,
 ) -> Result<Json<MintResponse>, Response> {
+    state
+        .limits
+        .check_outputs(payload.outputs.len())
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -365,6 +435,61 @@ pub(crate) async fn post_mint_bolt11(
     Ok(Json(res))
 }
 
+/// Mint tokens of many paid BOLT11 quotes at once.
+///
+/// Every request brings the outputs and signature of its quote, and either all the quotes of
+/// the batch are minted or none is.
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    context_path = "/v1",
+    path = "/mint/bolt11/batch",
+    request_body(content = BatchMintRequest<String>, description = "Request params", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Successful response", body = BatchMintResponse, content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+#[instrument(skip_all, fields(quotes = payload.requests.len()))]
+pub(crate) async fn post_mint_bolt11_batch(
+    #[cfg(feature = "auth")] auth: AuthHeader,
+    State(state): State<MintState>,
+    Json(payload): Json<BatchMintRequest<QuoteId>>,
+) -> Result<Json<BatchMintResponse>, Response> {
+    state
+        .limits
+        .check_outputs(
+            payload
+                .requests
+                .iter()
+                .map(|request| request.outputs.len())
+                .sum(),
+        )
+        .map_err(into_response)?;
+
+    #[cfg(feature = "auth")]
+    {
+        state
+            .mint
+            .verify_auth(
+                auth.into(),
+                &ProtectedEndpoint::new(Method::Post, RoutePath::MintBolt11),
+            )
+            .await
+            .map_err(into_response)?;
+    }
+
+    let res = state
+        .mint
+        .process_batch_mint_request(payload)
+        .await
+        .map_err(|err| {
+            tracing::error!("Could not process batch mint: {}", err);
+            into_response(err)
+        })?;
+
+    Ok(Json(res))
+}
+
 #[cfg_attr(feature = "swagger", utoipa::path(
     post,
     context_path = "/v1",
@@ -468,6 +593,15 @@ pub(crate) async fn post_melt_bolt11(
     State(state): State<MintState>,
     Json(payload): Json<MeltRequest<QuoteId>>,
 ) -> Result<Json<MeltQuoteBolt11Response<QuoteId>>, Response> {
+    state
+        .limits
+        .check_inputs(payload.inputs().len())
+        .map_err(into_response)?;
+    state
+        .limits
+        .check_outputs(payload.outputs().as_ref().map_or(0, Vec::len))
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -514,6 +648,11 @@ pub(crate) async fn post_check(
     State(state): State<MintState>,
     Json(payload): Json<CheckStateRequest>,
 ) -> Result<Json<CheckStateResponse>, Response> {
+    state
+        .limits
+        .check_ys(payload.ys.len())
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -582,6 +721,15 @@ pub(crate) async fn post_swap(
     State(state): State<MintState>,
     Json(payload): Json<SwapRequest>,
 ) -> Result<Json<SwapResponse>, Response> {
+    state
+        .limits
+        .check_inputs(payload.inputs().len())
+        .map_err(into_response)?;
+    state
+        .limits
+        .check_outputs(payload.outputs().len())
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -623,6 +771,11 @@ pub(crate) async fn post_restore(
     State(state): State<MintState>,
     Json(payload): Json<RestoreRequest>,
 ) -> Result<Json<RestoreResponse>, Response> {
+    state
+        .limits
+        .check_outputs(payload.outputs.len())
+        .map_err(into_response)?;
+
     #[cfg(feature = "auth")]
     {
         state
@@ -668,6 +821,8 @@ where
         | ErrorCode::AmountOutofLimitRange
         | ErrorCode::WitnessMissingOrInvalid
         | ErrorCode::DuplicateSignature
+        | ErrorCode::MaxBatchSizeExceeded
+        | ErrorCode::SubscriptionLimitExceeded
         | ErrorCode::DuplicateInputs
         | ErrorCode::DuplicateOutputs
         | ErrorCode::MultipleUnits
@@ -675,6 +830,9 @@ where
         | ErrorCode::ClearAuthRequired
         | ErrorCode::BlindAuthRequired => StatusCode::BAD_REQUEST,
 
+        // Too many requests from the client (429 Too Many Requests)
+        ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+
         // Auth failures (401 Unauthorized)
         ErrorCode::ClearAuthFailed | ErrorCode::BlindAuthFailed => StatusCode::UNAUTHORIZED,
 
@@ -0,0 +1,182 @@
+//! Server-sent events transport of the NUT-17 notifications
+//!
+//! An alternative to the websocket for clients behind proxies that do not forward websockets.
+//! A client opens a stream by posting the JSON list of its initial NUT-17 subscription params to
+//! `/v1/sse`. The first event of the stream is a `stream` event whose data is the id of the
+//! stream, and every following event is a notification whose data is the `{"subId", "payload"}`
+//! object of a websocket notification.
+//!
+//! The subscriptions of an open stream are changed by posting the NUT-17 JSON-RPC subscribe and
+//! unsubscribe requests of the websocket to `/v1/sse/{stream_id}`, which answers them like the
+//! websocket does. The random stream id is only known to the client of the stream.
+//!
+//! The stream is protected like the websocket, a blind auth token pays for the whole stream.
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use cdk::error::{ErrorCode, ErrorResponse};
+#[cfg(feature = "auth")]
+use cdk::nuts::nut21::{Method, ProtectedEndpoint, RoutePath};
+use cdk::subscription::Params;
+use cdk::ws::{NotificationInner, WsErrorBody, WsRequest};
+use futures::StreamExt;
+use uuid::Uuid;
+
+#[cfg(feature = "auth")]
+use crate::auth::AuthHeader;
+use crate::ws::{self, WsContext};
+use crate::{into_response, MintState};
+
+/// Name of the first event of a stream, whose data is the id of the stream
+pub const STREAM_EVENT: &str = "stream";
+
+/// Open event streams by id
+pub(crate) type SseStreams = Arc<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<WsContext>>>>>;
+
+/// Removes an event stream once its client goes away
+struct OpenStream {
+    id: Uuid,
+    streams: SseStreams,
+}
+
+impl Drop for OpenStream {
+    fn drop(&mut self) {
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.remove(&self.id);
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    context_path = "/v1",
+    path = "/sse",
+    request_body(content = Vec<Object>, description = "Initial NUT-17 subscription params", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Stream of NUT-17 notifications, after the `stream` event with the id of the stream", body = String, content_type = "text/event-stream"),
+        (status = 400, description = "Invalid subscriptions", body = ErrorResponse, content_type = "application/json"),
+        (status = 500, description = "Server error", body = ErrorResponse, content_type = "application/json")
+    )
+))]
+/// Open a stream of NUT-17 notifications as server-sent events
+pub(crate) async fn sse_handler(
+    #[cfg(feature = "auth")] auth: AuthHeader,
+    State(state): State<MintState>,
+    Json(subscriptions): Json<Vec<Params>>,
+) -> Result<Response, Response> {
+    let mut sub_ids = HashSet::new();
+    if !subscriptions
+        .iter()
+        .all(|params| sub_ids.insert(params.id.clone()))
+    {
+        return Err((StatusCode::BAD_REQUEST, "Duplicate subscription id").into_response());
+    }
+
+    #[cfg(feature = "auth")]
+    state
+        .mint
+        .verify_auth(
+            auth.into(),
+            &ProtectedEndpoint::new(Method::Get, RoutePath::Ws),
+        )
+        .await
+        .map_err(into_response)?;
+
+    let limits = state.limits.websocket;
+    let streams = state.sse_streams.clone();
+    let (mut context, receiver) = WsContext::new(state);
+    let overflowed = context.overflowed();
+
+    for params in subscriptions {
+        ws::subscribe(&mut context, params).await.map_err(|err| {
+            let body: WsErrorBody = err.into();
+            match u16::try_from(body.code) {
+                Ok(code) => {
+                    into_response(ErrorResponse::new(ErrorCode::from_code(code), body.message))
+                }
+                Err(_) => (StatusCode::BAD_REQUEST, body.message).into_response(),
+            }
+        })?;
+    }
+
+    let stream = OpenStream {
+        id: Uuid::new_v4(),
+        streams,
+    };
+    stream
+        .streams
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .insert(stream.id, Arc::new(tokio::sync::Mutex::new(context)));
+
+    let first = Event::default()
+        .event(STREAM_EVENT)
+        .data(stream.id.to_string());
+    let notifications = futures::stream::unfold(
+        (receiver, overflowed, stream),
+        |(mut receiver, overflowed, stream)| async move {
+            let (sub_id, payload) = tokio::select! {
+                Some(notification) = receiver.recv() => notification,
+                _ = overflowed.notified() => {
+                    tracing::debug!("sse send queue full, closing stream");
+                    return None;
+                }
+                else => return None,
+            };
+
+            let event = Event::default().json_data(NotificationInner { sub_id, payload });
+
+            Some((event, (receiver, overflowed, stream)))
+        },
+    );
+    let events = futures::stream::once(async { Ok(first) }).chain(notifications);
+
+    let sse = Sse::new(events);
+    Ok(if limits.ping_interval > 0 {
+        sse.keep_alive(KeepAlive::new().interval(Duration::from_secs(limits.ping_interval)))
+            .into_response()
+    } else {
+        sse.into_response()
+    })
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    context_path = "/v1",
+    path = "/sse/{stream_id}",
+    params(
+        ("stream_id" = String, description = "Id of the event stream, from its `stream` event"),
+    ),
+    request_body(content = Object, description = "NUT-17 JSON-RPC subscribe or unsubscribe request", content_type = "application/json"),
+    responses(
+        (status = 200, description = "NUT-17 JSON-RPC response", body = Object, content_type = "application/json"),
+        (status = 404, description = "Unknown event stream", body = String, content_type = "text/plain")
+    )
+))]
+/// Subscribe or unsubscribe on an open event stream
+pub(crate) async fn post_sse_request(
+    State(state): State<MintState>,
+    Path(stream_id): Path<Uuid>,
+    Json(request): Json<WsRequest>,
+) -> Result<Json<serde_json::Value>, Response> {
+    let context = state
+        .sse_streams
+        .lock()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .get(&stream_id)
+        .cloned()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Unknown event stream").into_response())?;
+
+    let mut context = context.lock().await;
+    let response = ws::process(&mut context, request)
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response())?;
+
+    Ok(Json(response))
+}
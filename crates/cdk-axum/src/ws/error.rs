@@ -1,3 +1,4 @@
+use cdk::error::{Error, ErrorResponse};
 use cdk::nuts::nut17::ws::WsErrorBody;
 use serde::{Deserialize, Serialize};
 
@@ -32,3 +33,10 @@ impl From<WsError> for WsErrorBody {
         WsErrorBody { code: id, message }
     }
 }
+
+impl From<Error> for WsError {
+    fn from(err: Error) -> Self {
+        let response = ErrorResponse::from(err);
+        WsError::ServerError(response.code.to_code().into(), response.detail)
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::future::pending;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
 use cdk::mint::QuoteId;
 use cdk::nuts::nut17::NotificationPayload;
 use cdk::subscription::SubId;
@@ -9,16 +11,22 @@ use cdk::ws::{
     notification_to_ws_message, NotificationInner, WsErrorBody, WsMessageOrResponse,
     WsMethodRequest, WsRequest,
 };
+#[cfg(feature = "prometheus")]
+use cdk_prometheus::global;
 use futures::StreamExt;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, sleep_until, timeout, Instant, MissedTickBehavior};
 
+use crate::limits::WsLimits;
 use crate::MintState;
 
 mod error;
 mod subscribe;
 mod unsubscribe;
 
-async fn process(
+/// Process a subscribe or unsubscribe request of a websocket or an event stream
+pub(crate) async fn process(
     context: &mut WsContext,
     body: WsRequest,
 ) -> Result<serde_json::Value, serde_json::Error> {
@@ -34,11 +42,105 @@ async fn process(
 }
 
 pub use error::WsError;
+pub(crate) use subscribe::handle as subscribe;
 
 pub struct WsContext {
     state: MintState,
-    subscriptions: HashMap<Arc<SubId>, tokio::task::JoinHandle<()>>,
+    subscriptions: HashMap<Arc<SubId>, ActiveSubscription>,
     publisher: mpsc::Sender<(Arc<SubId>, NotificationPayload<QuoteId>)>,
+    /// Notified by a subscription whose notification did not fit in the send queue
+    overflowed: Arc<Notify>,
+}
+
+/// Receiver of the notifications of the subscriptions of a [`WsContext`]
+pub(crate) type NotificationReceiver = mpsc::Receiver<(Arc<SubId>, NotificationPayload<QuoteId>)>;
+
+impl WsContext {
+    /// Context of a new connection, with the receiver of its notifications
+    pub(crate) fn new(state: MintState) -> (Self, NotificationReceiver) {
+        let (publisher, subscriber) = mpsc::channel(state.limits.websocket.send_queue.max(1));
+        let context = Self {
+            state,
+            subscriptions: HashMap::new(),
+            publisher,
+            overflowed: Arc::new(Notify::new()),
+        };
+
+        (context, subscriber)
+    }
+
+    /// Notified once the send queue of the connection is full with the disconnect policy
+    pub(crate) fn overflowed(&self) -> Arc<Notify> {
+        self.overflowed.clone()
+    }
+}
+
+/// Subscription of a connection, stopped when dropped
+struct ActiveSubscription {
+    task: JoinHandle<()>,
+    filters: usize,
+}
+
+impl ActiveSubscription {
+    fn new(task: JoinHandle<()>, filters: usize) -> Self {
+        #[cfg(feature = "prometheus")]
+        global::add_ws_subscriptions(1);
+
+        Self { task, filters }
+    }
+}
+
+impl Drop for ActiveSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+
+        #[cfg(feature = "prometheus")]
+        global::add_ws_subscriptions(-1);
+    }
+}
+
+/// Why the mint closes a connection
+#[derive(Debug, Clone, Copy)]
+enum CloseReason {
+    /// The client sent nothing or accepted nothing for the idle timeout
+    Idle,
+    /// The send queue was full with the disconnect policy
+    Overflow,
+    /// The socket failed
+    SendFailed,
+}
+
+impl CloseReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Overflow => "overflow",
+            Self::SendFailed => "send_failed",
+        }
+    }
+}
+
+/// Send a message, giving up once the client has not accepted it for the idle timeout
+async fn send(
+    socket: &mut WebSocket,
+    message: Message,
+    limits: &WsLimits,
+) -> Result<(), CloseReason> {
+    let result = if limits.idle_timeout == 0 {
+        socket.send(message).await
+    } else {
+        timeout(
+            Duration::from_secs(limits.idle_timeout),
+            socket.send(message),
+        )
+        .await
+        .map_err(|_| CloseReason::Idle)?
+    };
+
+    result.map_err(|err| {
+        tracing::debug!("Could not send websocket message: {}", err);
+        CloseReason::SendFailed
+    })
 }
 
 /// Main function for websocket connections
@@ -47,15 +149,41 @@ pub struct WsContext {
 ///
 /// For simplicity sake this function will spawn tasks for each subscription and
 /// keep them in a hashmap, and will have a single subscriber for all of them.
+///
+/// Notifications go through a send queue bounded by the [`WsLimits`] of the mint, the client is
+/// pinged every `ping_interval` and disconnected once silent for the `idle_timeout`.
 pub async fn main_websocket(mut socket: WebSocket, state: MintState) {
-    let (publisher, mut subscriber) = mpsc::channel(100);
-    let mut context = WsContext {
-        state,
-        subscriptions: HashMap::new(),
-        publisher,
-    };
+    let limits = state.limits.websocket;
+    let (mut context, mut subscriber) = WsContext::new(state);
+
+    let mut ping = (limits.ping_interval > 0).then(|| {
+        let period = Duration::from_secs(limits.ping_interval);
+        let mut ping = interval_at(Instant::now() + period, period);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ping
+    });
+    let mut last_seen = Instant::now();
+
+    #[cfg(feature = "prometheus")]
+    global::inc_ws_connections();
+
+    let close_reason = loop {
+        let idle_deadline = last_seen + Duration::from_secs(limits.idle_timeout);
+        let idle = async move {
+            if limits.idle_timeout == 0 {
+                pending::<()>().await;
+            }
+            sleep_until(idle_deadline).await;
+        };
+        let ping_tick = async {
+            match ping.as_mut() {
+                Some(ping) => {
+                    ping.tick().await;
+                }
+                None => pending::<()>().await,
+            }
+        };
 
-    loop {
         tokio::select! {
             Some((sub_id, payload)) = subscriber.recv() => {
                 if !context.subscriptions.contains_key(&sub_id) {
@@ -75,26 +203,45 @@ pub async fn main_websocket(mut socket: WebSocket, state: MintState) {
                     }
                 };
 
-                if let Err(err)= socket.send(Message::Text(message.into())).await {
-                    tracing::error!("Could not send websocket message: {}", err);
-                    break;
+                if let Err(reason) = send(&mut socket, Message::Text(message.into()), &limits).await {
+                    break Some(reason);
                 }
             }
 
-            Some(from_ws) = socket.next() => {
+            _ = context.overflowed.notified() => {
+                tracing::debug!("ws send queue full, closing connection");
+                break Some(CloseReason::Overflow);
+            }
+
+            _ = ping_tick => {
+                if let Err(reason) = send(&mut socket, Message::Ping(Default::default()), &limits).await {
+                    break Some(reason);
+                }
+            }
+
+            _ = idle => {
+                tracing::debug!("ws client idle, closing connection");
+                break Some(CloseReason::Idle);
+            }
+
+            from_ws = socket.next() => {
+                let Some(from_ws) = from_ws else {
+                    break None;
+                };
+                last_seen = Instant::now();
+
                 let text = match from_ws {
                     Ok(Message::Text(text)) => text.to_string(),
                     Ok(Message::Binary(bin)) => String::from_utf8_lossy(&bin).to_string(),
                     Ok(Message::Ping(payload)) => {
                         // Reply with Pong with same payload
-                        if let Err(e) = socket.send(Message::Pong(payload)).await {
-                            tracing::error!("failed to send pong: {e}");
-                            break;
+                        if let Err(reason) = send(&mut socket, Message::Pong(payload), &limits).await {
+                            break Some(reason);
                         }
                         continue;
                     },
                     Ok(Message::Pong(_payload)) => {
-                        tracing::error!("Unexpected pong");
+                        // Answer to our ping, the client is alive
                         continue;
                     },
                     Ok(Message::Close(frame)) => {
@@ -105,14 +252,14 @@ pub async fn main_websocket(mut socket: WebSocket, state: MintState) {
                         }
 
                         let _ = socket.send(Message::Close(Some(CloseFrame {
-                            code: axum::extract::ws::close_code::NORMAL,
+                            code: close_code::NORMAL,
                             reason: "bye!".into(),
                         }))).await;
-                        break;
+                        break None;
                     }
                     Err(err) => {
                         tracing::error!("ws-error: {err}");
-                        break;
+                        break None;
                     }
                 };
 
@@ -127,25 +274,38 @@ pub async fn main_websocket(mut socket: WebSocket, state: MintState) {
 
                 match process(&mut context, request).await {
                     Ok(result) => {
-                        if let Err(err) = socket
-                            .send(Message::Text(result.to_string().into()))
-                            .await
-                        {
-                            tracing::error!("Could not send request: {}", err);
-                            break;
+                        if let Err(reason) = send(&mut socket, Message::Text(result.to_string().into()), &limits).await {
+                            break Some(reason);
                         }
                     }
                     Err(err) => {
                         tracing::error!("Error serializing response: {}", err);
-                        break;
+                        break None;
                     }
                 }
             }
-            else =>  {
-                // Unexpected, we should exit the loop
-                tracing::warn!("Unexpected event, closing ws");
-                break;
-            }
+        }
+    };
+
+    if let Some(reason) = close_reason {
+        tracing::debug!("Closing ws connection: {}", reason.as_str());
+
+        #[cfg(feature = "prometheus")]
+        global::record_ws_disconnect(reason.as_str());
+
+        if !matches!(reason, CloseReason::SendFailed) {
+            let _ = send(
+                &mut socket,
+                Message::Close(Some(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: reason.as_str().into(),
+                })),
+                &limits,
+            )
+            .await;
         }
     }
+
+    #[cfg(feature = "prometheus")]
+    global::dec_ws_connections();
 }
@@ -1,7 +1,12 @@
+use cdk::error::Error;
 use cdk::subscription::Params;
 use cdk::ws::{WsResponseResult, WsSubscribeResponse};
+#[cfg(feature = "prometheus")]
+use cdk_prometheus::global;
+use tokio::sync::mpsc::error::TrySendError;
 
-use super::{WsContext, WsError};
+use super::{ActiveSubscription, WsContext, WsError};
+use crate::limits::OverflowPolicy;
 
 /// The `handle` method is called when a client sends a subscription request
 pub(crate) async fn handle(
@@ -15,6 +20,25 @@ pub(crate) async fn handle(
         return Err(WsError::InvalidParams);
     }
 
+    let limits = context.state.limits.websocket;
+    if let Some(max) = limits.max_subscriptions {
+        if context.subscriptions.len() >= max {
+            return Err(Error::MaxSubscriptionsExceeded(max).into());
+        }
+    }
+    if let Some(max) = limits.max_filters {
+        let filters = context
+            .subscriptions
+            .values()
+            .map(|subscription| subscription.filters)
+            .sum::<usize>()
+            + params.filters.len();
+        if filters > max {
+            return Err(Error::MaxFiltersExceeded(max).into());
+        }
+    }
+
+    let filters = params.filters.len();
     let mut subscription = context
         .state
         .mint
@@ -23,15 +47,35 @@ pub(crate) async fn handle(
         .map_err(|_| WsError::ParseError)?;
 
     let publisher = context.publisher.clone();
+    let overflowed = context.overflowed.clone();
     let sub_id_for_sender = sub_id.clone();
-    context.subscriptions.insert(
-        sub_id.clone(),
-        tokio::spawn(async move {
-            while let Some(response) = subscription.recv().await {
-                let _ = publisher.try_send((sub_id_for_sender.clone(), response.into_inner()));
+    let task = tokio::spawn(async move {
+        while let Some(response) = subscription.recv().await {
+            match publisher.try_send((sub_id_for_sender.clone(), response.into_inner())) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    #[cfg(feature = "prometheus")]
+                    global::record_ws_notification_dropped();
+
+                    if limits.overflow == OverflowPolicy::Disconnect {
+                        overflowed.notify_one();
+                        break;
+                    }
+
+                    tracing::debug!(
+                        "Send queue full, dropped notification of {:?}",
+                        sub_id_for_sender
+                    );
+                }
+                Err(TrySendError::Closed(_)) => break,
             }
-        }),
-    );
+        }
+    });
+
+    context
+        .subscriptions
+        .insert(sub_id.clone(), ActiveSubscription::new(task, filters));
+
     Ok(WsSubscribeResponse {
         status: "OK".to_string(),
         sub_id,
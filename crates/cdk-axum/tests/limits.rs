@@ -0,0 +1,264 @@
+//! Rate limit and batch size tests
+//!
+//! Serves the router of a fake wallet mint with limits and checks the budgets of the clients, how
+//! they are keyed and the max batch sizes.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk::error::{Error, ErrorResponse};
+use cdk::mint::{MintBuilder, MintMeltLimits};
+use cdk::nuts::{CurrencyUnit, PaymentMethod, SecretKey};
+use cdk::types::FeeReserve;
+use cdk_axum::cache::HttpCache;
+use cdk_axum::create_mint_router_with_limits;
+use cdk_axum::limits::{ClientKey, Limits, RouteBudget};
+use cdk_fake_wallet::FakeWallet;
+use reqwest::StatusCode;
+
+/// Serves the mint router with the limits and returns its url
+async fn serve(limits: Limits) -> String {
+    let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("db"));
+    let mut mint_builder = MintBuilder::new(localstore.clone());
+
+    let fake_wallet = FakeWallet::new(
+        FeeReserve {
+            min_fee_reserve: 1.into(),
+            percent_fee_reserve: 1.0,
+        },
+        HashMap::default(),
+        HashSet::default(),
+        0,
+        CurrencyUnit::Sat,
+    );
+    mint_builder
+        .add_payment_processor(
+            CurrencyUnit::Sat,
+            PaymentMethod::Bolt11,
+            MintMeltLimits::new(1, 10_000),
+            Arc::new(fake_wallet),
+        )
+        .await
+        .expect("payment processor");
+
+    let mint = mint_builder
+        .with_urls(vec!["http://127.0.0.1".to_string()])
+        .build_with_seed(localstore, &[1; 64])
+        .await
+        .expect("mint");
+    mint.start().await.expect("start mint");
+
+    let router =
+        create_mint_router_with_limits(Arc::new(mint), HttpCache::default(), false, limits)
+            .await
+            .expect("router");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let url = format!("http://{}", listener.local_addr().expect("address"));
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+    });
+
+    url
+}
+
+fn keys_budget(requests: u32, per_secs: u64) -> Limits {
+    Limits {
+        routes: HashMap::from([("/v1/keys".to_string(), RouteBudget { requests, per_secs })]),
+        ..Default::default()
+    }
+}
+
+async fn get_keys(url: &str, headers: &[(&str, &str)]) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(format!("{url}/v1/keys"));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    request.send().await.expect("request")
+}
+
+/// Test: A client gets a burst of its budget, then one request per emission interval
+#[tokio::test]
+async fn test_budget_burst_and_refill() {
+    let url = serve(keys_budget(2, 1)).await;
+
+    assert_eq!(get_keys(&url, &[]).await.status(), StatusCode::OK);
+    assert_eq!(get_keys(&url, &[]).await.status(), StatusCode::OK);
+
+    let limited = get_keys(&url, &[]).await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        limited
+            .headers()
+            .get("Retry-After")
+            .and_then(|value| value.to_str().ok()),
+        Some("1")
+    );
+    let error: ErrorResponse = limited.json().await.expect("error response");
+    assert_eq!(error.code.to_code(), 50001);
+    assert!(matches!(Error::from(error), Error::RateLimited));
+
+    // Routes without a budget are not limited
+    for _ in 0..5 {
+        let info = reqwest::get(format!("{url}/v1/info")).await.expect("info");
+        assert_eq!(info.status(), StatusCode::OK);
+    }
+
+    // One request is back after the emission interval
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert_eq!(get_keys(&url, &[]).await.status(), StatusCode::OK);
+    assert_eq!(
+        get_keys(&url, &[]).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+/// Test: Behind a proxy, clients are keyed by the address the proxy appended
+#[tokio::test]
+async fn test_forwarded_for_uses_proxy_address() {
+    let url = serve(Limits {
+        trust_forwarded_for: true,
+        ..keys_budget(1, 60)
+    })
+    .await;
+
+    let forwarded = |value| [("X-Forwarded-For", value)];
+
+    assert_eq!(
+        get_keys(&url, &forwarded("1.1.1.1, 10.0.0.1"))
+            .await
+            .status(),
+        StatusCode::OK
+    );
+    // Addresses set by the client do not buy a new budget
+    assert_eq!(
+        get_keys(&url, &forwarded("2.2.2.2, 10.0.0.1"))
+            .await
+            .status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        get_keys(&url, &forwarded("10.0.0.2")).await.status(),
+        StatusCode::OK
+    );
+}
+
+/// Test: Unverified clear auth and blind auth tokens are keyed by the IP of the client
+#[tokio::test]
+async fn test_auth_key_falls_back_to_ip() {
+    let url = serve(Limits {
+        key: ClientKey::Auth,
+        trust_forwarded_for: true,
+        ..keys_budget(1, 60)
+    })
+    .await;
+
+    // The mint cannot verify the tokens, whose subjects are forged
+    let cat = |subject: &str| {
+        use bitcoin::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use bitcoin::base64::Engine;
+
+        let claims = serde_json::json!({ "sub": subject }).to_string();
+        format!("e30.{}.sig", URL_SAFE_NO_PAD.encode(claims))
+    };
+
+    let first = cat("alice");
+    let second = cat("bob");
+    assert_eq!(
+        get_keys(
+            &url,
+            &[("Clear-auth", &first), ("X-Forwarded-For", "10.0.0.1")]
+        )
+        .await
+        .status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        get_keys(
+            &url,
+            &[("Clear-auth", &second), ("X-Forwarded-For", "10.0.0.1")]
+        )
+        .await
+        .status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // A client exhausting its budget with blind auth does not exhaust the budget of others
+    let bat = "authA".to_string();
+    assert_eq!(
+        get_keys(
+            &url,
+            &[("Blind-auth", &bat), ("X-Forwarded-For", "10.0.0.2")]
+        )
+        .await
+        .status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        get_keys(
+            &url,
+            &[("Blind-auth", &bat), ("X-Forwarded-For", "10.0.0.2")]
+        )
+        .await
+        .status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        get_keys(
+            &url,
+            &[("Blind-auth", &bat), ("X-Forwarded-For", "10.0.0.3")]
+        )
+        .await
+        .status(),
+        StatusCode::OK
+    );
+}
+
+/// Test: Requests with more Ys than allowed are refused with their sizes
+#[tokio::test]
+async fn test_max_batch_size() {
+    let url = serve(Limits {
+        max_ys: Some(1),
+        ..Default::default()
+    })
+    .await;
+
+    let ys = |count: usize| {
+        serde_json::json!({
+            "Ys": (0..count)
+                .map(|_| SecretKey::generate().public_key())
+                .collect::<Vec<_>>(),
+        })
+    };
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/v1/checkstate"))
+        .json(&ys(1))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .post(format!("{url}/v1/checkstate"))
+        .json(&ys(2))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: ErrorResponse = response.json().await.expect("error response");
+    assert_eq!(error.code.to_code(), 50002);
+    assert!(matches!(
+        Error::from(error),
+        Error::MaxBatchSizeExceeded(2, 1)
+    ));
+}
@@ -0,0 +1,284 @@
+//! LNURL-pay router tests
+//!
+//! Serves the lightning address routes of a fake wallet mint and checks the pay request, the
+//! callback, the quote lookup of the recipient and the checks made when the router is created.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::hashes::{sha256, Hash};
+use cdk::amount::SplitTarget;
+use cdk::lightning_invoice::Bolt11InvoiceDescriptionRef;
+use cdk::mint::{Bolt11MintQuoteSettings, Mint, MintBuilder, MintMeltLimits};
+use cdk::nuts::{
+    CurrencyUnit, MintQuoteState, MintQuotesByPubkeyRequest, PaymentMethod, PublicKey, SecretKey,
+};
+use cdk::types::FeeReserve;
+use cdk::util::unix_time;
+use cdk::wallet::Wallet;
+use cdk::Bolt11Invoice;
+use cdk_axum::{
+    create_lnurl_pay_router, create_mint_router, LnurlErrorResponse, LnurlPayCallbackResponse,
+    LnurlPayRequest, LnurlPaySettings,
+};
+use cdk_fake_wallet::FakeWallet;
+use reqwest::StatusCode;
+
+async fn create_mint(bolt11_settings: Bolt11MintQuoteSettings) -> Arc<Mint> {
+    let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("db"));
+    let mut mint_builder =
+        MintBuilder::new(localstore.clone()).with_bolt11_mint_quote_settings(bolt11_settings);
+
+    let fee_reserve = FeeReserve {
+        min_fee_reserve: 1.into(),
+        percent_fee_reserve: 1.0,
+    };
+    let fake_wallet = FakeWallet::new(
+        fee_reserve,
+        HashMap::default(),
+        HashSet::default(),
+        0,
+        CurrencyUnit::Sat,
+    );
+
+    mint_builder
+        .add_payment_processor(
+            CurrencyUnit::Sat,
+            PaymentMethod::Bolt11,
+            MintMeltLimits::new(1, 10_000),
+            Arc::new(fake_wallet),
+        )
+        .await
+        .expect("payment processor");
+
+    let mint = mint_builder
+        .with_urls(vec!["http://127.0.0.1".to_string()])
+        .build_with_seed(localstore, &[1; 64])
+        .await
+        .expect("mint");
+    mint.start().await.expect("start mint");
+
+    Arc::new(mint)
+}
+
+fn lnurl_settings(mint_url: &str, pubkey: PublicKey) -> LnurlPaySettings {
+    LnurlPaySettings {
+        mint_url: mint_url.to_string(),
+        domain: "mint.example.com".to_string(),
+        unit: CurrencyUnit::Sat,
+        min_sendable: 1_000.into(),
+        max_sendable: 1_000_000.into(),
+        users: HashMap::from([("Alice".to_string(), pubkey)]),
+    }
+}
+
+/// Serves the mint and lnurl pay routers and returns their url
+async fn serve(pubkey: PublicKey) -> String {
+    let mint = create_mint(Bolt11MintQuoteSettings::default()).await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let url = format!("http://{}", listener.local_addr().expect("address"));
+
+    let router = create_mint_router(mint.clone(), false)
+        .await
+        .expect("mint router")
+        .merge(
+            create_lnurl_pay_router(mint, lnurl_settings(&url, pubkey))
+                .await
+                .expect("router"),
+        );
+
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    url
+}
+
+async fn get_error(url: &str) -> (StatusCode, LnurlErrorResponse) {
+    let response = reqwest::get(url).await.expect("request");
+    let status = response.status();
+
+    (status, response.json().await.expect("error response"))
+}
+
+/// Test: The pay request of a registered user points to its callback
+#[tokio::test]
+async fn test_lnurl_pay_request() {
+    let url = serve(SecretKey::generate().public_key()).await;
+
+    let pay_request: LnurlPayRequest = reqwest::get(format!("{url}/.well-known/lnurlp/ALICE"))
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("pay request");
+
+    assert_eq!(pay_request.tag, "payRequest");
+    assert_eq!(pay_request.callback, format!("{url}/lnurlp/alice/callback"));
+    assert_eq!(pay_request.min_sendable, 1_000);
+    assert_eq!(pay_request.max_sendable, 1_000_000);
+    assert!(pay_request
+        .metadata
+        .contains("[\"text/identifier\",\"alice@mint.example.com\"]"));
+
+    let (status, error) = get_error(&format!("{url}/.well-known/lnurlp/bob")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error.status, "ERROR");
+}
+
+/// Test: The callback returns an invoice committing to the metadata
+#[tokio::test]
+async fn test_lnurl_pay_callback() {
+    let url = serve(SecretKey::generate().public_key()).await;
+
+    let pay_request: LnurlPayRequest = reqwest::get(format!("{url}/.well-known/lnurlp/alice"))
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("pay request");
+
+    let callback: LnurlPayCallbackResponse =
+        reqwest::get(format!("{}?amount=8000", pay_request.callback))
+            .await
+            .expect("request")
+            .json()
+            .await
+            .expect("callback response");
+
+    let invoice = Bolt11Invoice::from_str(&callback.pr).expect("invoice");
+    assert_eq!(invoice.amount_milli_satoshis(), Some(8_000));
+
+    match invoice.description() {
+        Bolt11InvoiceDescriptionRef::Hash(hash) => {
+            assert_eq!(hash.0, sha256::Hash::hash(pay_request.metadata.as_bytes()))
+        }
+        Bolt11InvoiceDescriptionRef::Direct(_) => panic!("Expected description hash"),
+    }
+}
+
+/// Test: The callback rejects unknown users and invalid amounts
+#[tokio::test]
+async fn test_lnurl_pay_callback_rejected() {
+    let url = serve(SecretKey::generate().public_key()).await;
+
+    let (status, _) = get_error(&format!("{url}/lnurlp/bob/callback?amount=8000")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    for amount in [999, 1_000_001, 1_500] {
+        let (status, error) =
+            get_error(&format!("{url}/lnurlp/alice/callback?amount={amount}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.status, "ERROR");
+    }
+}
+
+async fn post_quotes(url: &str, request: &MintQuotesByPubkeyRequest) -> StatusCode {
+    reqwest::Client::new()
+        .post(format!("{url}/lnurlp/quotes"))
+        .json(request)
+        .send()
+        .await
+        .expect("request")
+        .status()
+}
+
+/// Test: The recipient finds the quote paid to its address and mints it
+#[tokio::test]
+async fn test_lnurl_pay_recipient_mints() {
+    let secret_key = SecretKey::generate();
+    let url = serve(secret_key.public_key()).await;
+
+    // The fake wallet pays every invoice it creates
+    let callback: LnurlPayCallbackResponse =
+        reqwest::get(format!("{url}/lnurlp/alice/callback?amount=8000"))
+            .await
+            .expect("request")
+            .json()
+            .await
+            .expect("callback response");
+
+    let localstore = Arc::new(cdk_sqlite::wallet::memory::empty().await.expect("db"));
+    let wallet = Wallet::new(&url, CurrencyUnit::Sat, localstore, [2; 64], None).expect("wallet");
+
+    let mut quotes = Vec::new();
+    for _ in 0..50 {
+        quotes = wallet
+            .fetch_lnurl_pay_quotes(&secret_key)
+            .await
+            .expect("lnurl pay quotes");
+        if !quotes.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(quotes.len(), 1);
+    let quote = &quotes[0];
+    assert_eq!(quote.request, callback.pr);
+    assert_eq!(quote.state, MintQuoteState::Paid);
+    assert_eq!(quote.secret_key, Some(secret_key.clone()));
+
+    wallet
+        .mint(&quote.id, SplitTarget::default(), None)
+        .await
+        .expect("mint");
+    assert_eq!(wallet.total_balance().await.expect("balance"), 8.into());
+
+    // Issued quotes are not returned again
+    assert!(wallet
+        .fetch_lnurl_pay_quotes(&secret_key)
+        .await
+        .expect("lnurl pay quotes")
+        .is_empty());
+}
+
+/// Test: Quotes are only looked up with a fresh request signed for a registered user
+#[tokio::test]
+async fn test_lnurl_pay_quotes_rejected() {
+    let secret_key = SecretKey::generate();
+    let url = serve(secret_key.public_key()).await;
+
+    let request = MintQuotesByPubkeyRequest::new(&secret_key, unix_time()).expect("request");
+    assert_eq!(post_quotes(&url, &request).await, StatusCode::OK);
+
+    let unknown =
+        MintQuotesByPubkeyRequest::new(&SecretKey::generate(), unix_time()).expect("request");
+    assert_eq!(post_quotes(&url, &unknown).await, StatusCode::NOT_FOUND);
+
+    let stale = MintQuotesByPubkeyRequest::new(&secret_key, unix_time() - 3_600).expect("request");
+    assert_eq!(post_quotes(&url, &stale).await, StatusCode::UNAUTHORIZED);
+
+    let forged = MintQuotesByPubkeyRequest {
+        signature: unknown.signature,
+        ..request
+    };
+    assert_eq!(post_quotes(&url, &forged).await, StatusCode::UNAUTHORIZED);
+}
+
+/// Test: The router is not created when the mint cannot set description hashes
+#[tokio::test]
+async fn test_lnurl_pay_requires_description_hash() {
+    let pubkey = SecretKey::generate().public_key();
+
+    let mint = create_mint(Bolt11MintQuoteSettings {
+        allow_description_hash: false,
+        ..Default::default()
+    })
+    .await;
+    assert!(
+        create_lnurl_pay_router(mint, lnurl_settings("http://127.0.0.1", pubkey))
+            .await
+            .is_err()
+    );
+
+    let mint = create_mint(Bolt11MintQuoteSettings::default()).await;
+    let settings = LnurlPaySettings {
+        unit: CurrencyUnit::Usd,
+        ..lnurl_settings("http://127.0.0.1", pubkey)
+    };
+    assert!(create_lnurl_pay_router(mint, settings).await.is_err());
+}
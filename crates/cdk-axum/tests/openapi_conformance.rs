@@ -0,0 +1,663 @@
+//! Conformance of the OpenAPI document with the mint router and the wallet [`HttpClient`]
+//!
+//! Serves the mint router of a fake wallet mint, drives the [`HttpClient`] and [`Wallet`] through
+//! every mint API and checks that every request was routed, that every route hit is documented,
+//! that every JSON body sent and answered matches the schema documented for it and that every
+//! documented route is used.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::middleware::{from_fn_with_state, Next};
+use axum::response::Response;
+use cdk::amount::SplitTarget;
+use cdk::mint::{MintBuilder, MintMeltLimits};
+use cdk::mint_url::MintUrl;
+use cdk::nuts::nut00::ProofsMethods;
+use cdk::nuts::nut17::ws::{WsMessageOrResponse, WsMethodRequest};
+use cdk::nuts::nut17::{Kind, Params};
+use cdk::nuts::{
+    BatchCheckMintQuoteRequest, CurrencyUnit, MintQuoteState, PaymentMethod, SecretKey,
+};
+use cdk::types::{FeeReserve, QuoteTTL};
+use cdk::wallet::{HttpClient, MintConnector, Wallet};
+use cdk_axum::{create_lnurl_pay_router, ApiDoc, LnurlPaySettings};
+use cdk_fake_wallet::{create_fake_invoice, FakeWallet};
+use futures::StreamExt;
+use serde_json::Value;
+use utoipa::OpenApi;
+
+/// Request served by the test mint, with its JSON bodies
+#[derive(Debug, Clone)]
+struct Exchange {
+    method: Method,
+    path: String,
+    status: StatusCode,
+    request: Option<Value>,
+    response: Option<Value>,
+}
+
+/// Requests served by the test mint
+type Recorded = Arc<Mutex<Vec<Exchange>>>;
+
+/// Status of the requests that matched no route
+const UNROUTED: StatusCode = StatusCode::IM_A_TEAPOT;
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+async fn record(State(recorded): State<Recorded>, req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    let (parts, body) = req.into_parts();
+    let mut request = None;
+    let body = if is_json(&parts.headers) {
+        let bytes = to_bytes(body, usize::MAX).await.expect("request body");
+        request = Some(serde_json::from_slice(&bytes).expect("JSON request body"));
+        Body::from(bytes)
+    } else {
+        body
+    };
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    // Event streams are never buffered
+    let (parts, body) = response.into_parts();
+    let mut response = None;
+    let body = if is_json(&parts.headers) {
+        let bytes = to_bytes(body, usize::MAX).await.expect("response body");
+        response = Some(serde_json::from_slice(&bytes).expect("JSON response body"));
+        Body::from(bytes)
+    } else {
+        body
+    };
+
+    recorded.lock().expect("lock").push(Exchange {
+        method,
+        path,
+        status: parts.status,
+        request,
+        response,
+    });
+
+    Response::from_parts(parts, body)
+}
+
+async fn start_mint() -> (MintUrl, Recorded) {
+    let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("db"));
+    let mut mint_builder = MintBuilder::new(localstore.clone());
+
+    let fee_reserve = FeeReserve {
+        min_fee_reserve: 1.into(),
+        percent_fee_reserve: 1.0,
+    };
+    let fake_wallet = Arc::new(FakeWallet::new(
+        fee_reserve,
+        HashMap::default(),
+        HashSet::default(),
+        0,
+        CurrencyUnit::Sat,
+    ));
+
+    for method in [PaymentMethod::Bolt11, PaymentMethod::Bolt12] {
+        mint_builder
+            .add_payment_processor(
+                CurrencyUnit::Sat,
+                method,
+                MintMeltLimits::new(1, 10_000),
+                fake_wallet.clone(),
+            )
+            .await
+            .expect("payment processor");
+    }
+
+    let mint = mint_builder
+        .with_urls(vec!["http://127.0.0.1".to_string()])
+        .with_batch_minting(PaymentMethod::Bolt11, Some(10))
+        .build_with_seed(localstore, &[1; 64])
+        .await
+        .expect("mint");
+    mint.set_quote_ttl(QuoteTTL::new(10_000, 10_000))
+        .await
+        .expect("quote ttl");
+    mint.start().await.expect("start mint");
+    let mint = Arc::new(mint);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let mint_url = MintUrl::from_str(&format!(
+        "http://{}",
+        listener.local_addr().expect("address")
+    ))
+    .expect("mint url");
+
+    let lnurl_settings = LnurlPaySettings {
+        mint_url: mint_url.to_string(),
+        domain: "127.0.0.1".to_string(),
+        unit: CurrencyUnit::Sat,
+        min_sendable: 1_000.into(),
+        max_sendable: 1_000_000.into(),
+        users: HashMap::from([("alice".to_string(), alice_key().public_key())]),
+    };
+
+    let recorded = Recorded::default();
+    let router = cdk_axum::create_mint_router(mint.clone(), true)
+        .await
+        .expect("router")
+        .merge(
+            create_lnurl_pay_router(mint, lnurl_settings)
+                .await
+                .expect("lnurl pay router"),
+        )
+        .fallback(|| async { UNROUTED })
+        .layer(from_fn_with_state(recorded.clone(), record));
+
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    (mint_url, recorded)
+}
+
+/// NUT-20 key of the lightning address `alice`
+fn alice_key() -> SecretKey {
+    SecretKey::from_slice(&[7; 32]).expect("secret key")
+}
+
+fn http_client(mint_url: &MintUrl) -> HttpClient {
+    #[cfg(feature = "auth")]
+    return HttpClient::new(mint_url.clone(), None);
+    #[cfg(not(feature = "auth"))]
+    return HttpClient::new(mint_url.clone());
+}
+
+async fn new_wallet(mint_url: &MintUrl, seed: [u8; 64]) -> Wallet {
+    let localstore = Arc::new(cdk_sqlite::wallet::memory::empty().await.expect("db"));
+    Wallet::new(
+        &mint_url.to_string(),
+        CurrencyUnit::Sat,
+        localstore,
+        seed,
+        None,
+    )
+    .expect("wallet")
+}
+
+async fn wait_for_bolt11_paid(wallet: &Wallet, quote_id: &str) {
+    for _ in 0..50 {
+        let quote = wallet
+            .mint_quote_state(quote_id)
+            .await
+            .expect("quote state");
+        if quote.state == MintQuoteState::Paid {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Mint quote {quote_id} was not paid");
+}
+
+async fn wait_for_bolt12_paid(wallet: &Wallet, quote_id: &str) {
+    for _ in 0..50 {
+        let quote = wallet
+            .mint_bolt12_quote_state(quote_id)
+            .await
+            .expect("quote state");
+        if quote.amount_paid > quote.amount_issued {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Mint quote {quote_id} was not paid");
+}
+
+/// Whether a request path matches a documented path template
+fn matches_template(template: &str, path: &str) -> bool {
+    let template: Vec<_> = template.split('/').collect();
+    let path: Vec<_> = path.split('/').collect();
+
+    template.len() == path.len()
+        && template.iter().zip(&path).all(|(template, segment)| {
+            (template.starts_with('{') && template.ends_with('}') && !segment.is_empty())
+                || template == segment
+        })
+}
+
+/// Checks a value against an OpenAPI schema of the document, pushing the mismatches found
+///
+/// Covers the keywords utoipa generates, formats and bounds are not checked.
+fn validate(doc: &Value, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference
+            .strip_prefix("#/components/schemas/")
+            .expect("schema ref");
+        return validate(doc, &doc["components"]["schemas"][name], value, at, errors);
+    }
+
+    if schema["nullable"] == true && value.is_null() {
+        return;
+    }
+
+    if let Some(all) = schema["allOf"].as_array() {
+        for schema in all {
+            validate(doc, schema, value, at, errors);
+        }
+    }
+
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(any) = schema[keyword].as_array() {
+            let matches = any.iter().any(|schema| {
+                let mut mismatches = Vec::new();
+                validate(doc, schema, value, at, &mut mismatches);
+                mismatches.is_empty()
+            });
+            if !matches {
+                errors.push(format!("{at}: {value} matches no schema of {keyword}"));
+            }
+        }
+    }
+
+    if let Some(values) = schema["enum"].as_array() {
+        if !values.contains(value) {
+            errors.push(format!("{at}: {value} is not one of {values:?}"));
+        }
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let type_matches = |name: &str| match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    };
+    if !types.is_empty() && !types.iter().any(|name| type_matches(name)) {
+        errors.push(format!("{at}: {value} is not of type {types:?}"));
+        return;
+    }
+
+    match value {
+        Value::Object(map) => {
+            for required in schema["required"].as_array().into_iter().flatten() {
+                let required = required.as_str().expect("required property name");
+                if !map.contains_key(required) {
+                    errors.push(format!("{at}: missing required property `{required}`"));
+                }
+            }
+
+            let properties = schema["properties"].as_object();
+            for (name, property) in map {
+                let at = format!("{at}/{name}");
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(schema) => validate(doc, schema, property, &at, errors),
+                    None => match &schema["additionalProperties"] {
+                        Value::Bool(false) => errors.push(format!("{at}: undocumented property")),
+                        additional @ Value::Object(_) => {
+                            validate(doc, additional, property, &at, errors)
+                        }
+                        _ => (),
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if schema["items"].is_object() {
+                for (index, item) in items.iter().enumerate() {
+                    validate(
+                        doc,
+                        &schema["items"],
+                        item,
+                        &format!("{at}/{index}"),
+                        errors,
+                    );
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Mismatches of the JSON bodies of an exchange with the schemas of its documented operation
+fn validate_exchange(doc: &Value, template: &str, exchange: &Exchange) -> Vec<String> {
+    let operation = &doc["paths"][template][exchange.method.as_str().to_lowercase()];
+    let context = format!(
+        "{} {} ({})",
+        exchange.method, exchange.path, exchange.status
+    );
+    let mut errors = Vec::new();
+
+    if let Some(request) = &exchange.request {
+        let schema = &operation["requestBody"]["content"]["application/json"]["schema"];
+        if schema.is_null() {
+            errors.push(format!("{context}: undocumented JSON request body"));
+        } else {
+            validate(doc, schema, request, "request", &mut errors);
+        }
+    }
+
+    if let Some(response) = &exchange.response {
+        let schema = &operation["responses"][exchange.status.as_str()]["content"]
+            ["application/json"]["schema"];
+        if schema.is_null() {
+            errors.push(format!("{context}: undocumented JSON response"));
+        } else {
+            validate(doc, schema, response, "response", &mut errors);
+        }
+    }
+
+    errors
+        .into_iter()
+        .map(|error| {
+            if error.starts_with(&context) {
+                error
+            } else {
+                format!("{context}: {error}")
+            }
+        })
+        .collect()
+}
+
+/// Every `$ref` in the document
+fn collect_refs<'a>(value: &'a Value, refs: &mut BTreeSet<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => {
+                        refs.insert(reference);
+                    }
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => (),
+    }
+}
+
+#[test]
+fn test_openapi_refs_resolve() {
+    let doc = serde_json::to_value(ApiDoc::openapi()).expect("openapi json");
+    let schemas = doc["components"]["schemas"]
+        .as_object()
+        .expect("components schemas");
+
+    let mut refs = BTreeSet::new();
+    collect_refs(&doc, &mut refs);
+
+    let dangling: Vec<_> = refs
+        .into_iter()
+        .filter(|reference| {
+            reference
+                .strip_prefix("#/components/schemas/")
+                .is_none_or(|name| !schemas.contains_key(name))
+        })
+        .collect();
+
+    assert!(dangling.is_empty(), "Dangling schema refs: {dangling:?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_client_conforms_to_openapi() {
+    let (mint_url, recorded) = start_mint().await;
+    let client = http_client(&mint_url);
+    let seed = [7; 64];
+    let wallet = new_wallet(&mint_url, seed).await;
+
+    // Info, keys and keysets
+    wallet.load_mint_info().await.expect("mint info");
+    client.get_mint_keys().await.expect("keys");
+    let keysets = client.get_mint_keysets().await.expect("keysets");
+    let keyset_id = keysets.keysets[0].id;
+    client.get_mint_keyset(keyset_id).await.expect("keyset");
+
+    // Bolt11 minting, single and batched
+    let quote = wallet
+        .mint_quote(64.into(), None)
+        .await
+        .expect("mint quote");
+    wait_for_bolt11_paid(&wallet, &quote.id).await;
+    wallet
+        .mint(&quote.id, SplitTarget::default(), None)
+        .await
+        .expect("mint");
+
+    let mut quote_ids = vec![];
+    for _ in 0..2 {
+        let quote = wallet
+            .mint_quote(16.into(), None)
+            .await
+            .expect("mint quote");
+        wait_for_bolt11_paid(&wallet, &quote.id).await;
+        quote_ids.push(quote.id);
+    }
+    let quotes = client
+        .post_check_mint_quotes(BatchCheckMintQuoteRequest {
+            quotes: quote_ids.clone(),
+        })
+        .await
+        .expect("check mint quotes");
+    assert_eq!(quotes.len(), 2);
+    wallet
+        .mint_batch(&quote_ids, SplitTarget::default(), None)
+        .await
+        .expect("batch mint");
+
+    // Bolt12 minting
+    let quote = wallet
+        .mint_bolt12_quote(Some(64.into()), None)
+        .await
+        .expect("bolt12 mint quote");
+    wait_for_bolt12_paid(&wallet, &quote.id).await;
+    wallet
+        .mint_bolt12(&quote.id, None, SplitTarget::default(), None)
+        .await
+        .expect("bolt12 mint");
+
+    // Swap and proof states
+    let proofs = wallet.get_unspent_proofs().await.expect("proofs");
+    let proofs = wallet
+        .swap(Some(16.into()), SplitTarget::default(), proofs, None, false)
+        .await
+        .expect("swap")
+        .expect("swapped proofs");
+    let sent = proofs.total_amount().expect("amount");
+    wallet
+        .check_proofs_spent(proofs)
+        .await
+        .expect("proof states");
+
+    // Bolt11 melting
+    let invoice = create_fake_invoice(10_000, String::new());
+    let quote = wallet
+        .melt_quote(invoice.to_string(), None)
+        .await
+        .expect("melt quote");
+    wallet
+        .melt_quote_status(&quote.id)
+        .await
+        .expect("melt quote status");
+    wallet.melt(&quote.id).await.expect("melt");
+
+    // Bolt12 melting, paying the offer of a new mint quote
+    let offer = wallet
+        .mint_bolt12_quote(Some(8.into()), None)
+        .await
+        .expect("bolt12 mint quote")
+        .request;
+    let quote = wallet
+        .melt_bolt12_quote(offer, None)
+        .await
+        .expect("bolt12 melt quote");
+    client
+        .get_melt_bolt12_quote_status(&quote.id)
+        .await
+        .expect("bolt12 melt quote status");
+    wallet.melt(&quote.id).await.expect("bolt12 melt");
+
+    // Restore
+    let restored = new_wallet(&mint_url, seed).await;
+    restored.restore().await.expect("restore");
+    assert_eq!(
+        restored.total_balance().await.expect("balance"),
+        wallet.total_balance().await.expect("balance") + sent
+    );
+
+    // Lightning address
+    let pay_request = client
+        .fetch_lnurl_pay_request(&format!("{mint_url}/.well-known/lnurlp/alice"))
+        .await
+        .expect("lnurl pay request");
+    client
+        .fetch_lnurl_invoice(&format!("{}?amount=8000", pay_request.callback))
+        .await
+        .expect("lnurl invoice");
+    wallet
+        .fetch_lnurl_pay_quotes(&alice_key())
+        .await
+        .expect("lnurl pay quotes");
+
+    // Event stream, whose subscriptions are changed by its id
+    let mut events = client.post_sse(vec![]).await.expect("event stream");
+    let first = events
+        .next()
+        .await
+        .expect("stream event")
+        .expect("event bytes");
+    let first = String::from_utf8(first).expect("utf-8 event");
+    let stream_id = first
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .expect("stream id");
+    let response = client
+        .post_sse_request(
+            stream_id,
+            (
+                WsMethodRequest::Subscribe(Params {
+                    kind: Kind::ProofState,
+                    filters: vec![SecretKey::generate().public_key().to_hex()],
+                    id: "proofs".to_string(),
+                }),
+                0,
+            )
+                .into(),
+        )
+        .await
+        .expect("subscribe on the stream");
+    assert!(matches!(response, WsMessageOrResponse::Response(_)));
+
+    // The mint has no blind auth configured, the routes only have to exist
+    #[cfg(feature = "auth")]
+    {
+        use cdk::nuts::MintAuthRequest;
+        use cdk::wallet::{AuthHttpClient, AuthMintConnector};
+
+        let auth_client = AuthHttpClient::new(mint_url.clone(), None);
+        let _ = auth_client.get_mint_blind_auth_keysets().await;
+        let _ = auth_client.get_mint_blind_auth_keyset(keyset_id).await;
+        let _ = auth_client
+            .post_mint_blind_auth(MintAuthRequest { outputs: vec![] })
+            .await;
+    }
+
+    // Routes without a wallet client
+    let http = reqwest::Client::new();
+    let mut raw_paths = vec!["v1/ws"];
+    if cfg!(feature = "auth") {
+        raw_paths.push("v1/auth/blind/keys");
+    }
+    for path in raw_paths {
+        http.get(format!("{mint_url}/{path}"))
+            .send()
+            .await
+            .expect("request");
+    }
+
+    let doc = serde_json::to_value(ApiDoc::openapi()).expect("openapi json");
+    let documented: Vec<(Method, String)> = doc["paths"]
+        .as_object()
+        .expect("paths")
+        .iter()
+        .flat_map(|(path, item)| {
+            item.as_object()
+                .expect("path item")
+                .keys()
+                .filter_map(|method| Method::from_str(&method.to_uppercase()).ok())
+                .map(|method| (method, path.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let recorded = recorded.lock().expect("lock").clone();
+
+    let unrouted: Vec<_> = recorded
+        .iter()
+        .filter(|exchange| {
+            exchange.status == UNROUTED || exchange.status == StatusCode::METHOD_NOT_ALLOWED
+        })
+        .collect();
+    assert!(
+        unrouted.is_empty(),
+        "Requests without a route: {unrouted:?}"
+    );
+
+    // The most literal template of each request, `/v1/x/check` before `/v1/x/{id}`
+    let template_of = |exchange: &Exchange| {
+        documented
+            .iter()
+            .filter(|(method, template)| {
+                *method == exchange.method && matches_template(template, &exchange.path)
+            })
+            .map(|(_, template)| template)
+            .min_by_key(|template| template.matches('{').count())
+    };
+
+    let undocumented: Vec<_> = recorded
+        .iter()
+        .filter(|exchange| template_of(exchange).is_none())
+        .collect();
+    assert!(
+        undocumented.is_empty(),
+        "Requests to undocumented routes: {undocumented:?}"
+    );
+
+    let mismatches: Vec<_> = recorded
+        .iter()
+        .flat_map(|exchange| {
+            let template = template_of(exchange).expect("documented route");
+            validate_exchange(&doc, template, exchange)
+        })
+        .collect();
+    assert!(
+        mismatches.is_empty(),
+        "Bodies not matching their schemas: {mismatches:#?}"
+    );
+
+    let unused: Vec<_> = documented
+        .iter()
+        .filter(|(documented_method, template)| {
+            !recorded.iter().any(|exchange| {
+                *documented_method == exchange.method && matches_template(template, &exchange.path)
+            })
+        })
+        .collect();
+    assert!(
+        unused.is_empty(),
+        "Documented routes never used: {unused:?}"
+    );
+}
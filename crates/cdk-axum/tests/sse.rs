@@ -0,0 +1,300 @@
+//! NUT-17 server-sent events tests
+//!
+//! Serves the router of a fake wallet mint, opens event streams with plain HTTP requests and
+//! changes their subscriptions with the JSON-RPC requests of the websocket.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk::error::ErrorResponse;
+use cdk::mint::{MintBuilder, MintMeltLimits};
+use cdk::nuts::{CurrencyUnit, PaymentMethod, SecretKey};
+use cdk::types::FeeReserve;
+use cdk_axum::cache::HttpCache;
+use cdk_axum::create_mint_router_with_limits;
+use cdk_axum::limits::{Limits, WsLimits};
+use cdk_fake_wallet::FakeWallet;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+/// Serves a fake wallet mint with the limits and returns its url
+async fn serve(limits: Limits) -> String {
+    let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("db"));
+    let mut mint_builder = MintBuilder::new(localstore.clone());
+
+    let fake_wallet = FakeWallet::new(
+        FeeReserve {
+            min_fee_reserve: 1.into(),
+            percent_fee_reserve: 1.0,
+        },
+        HashMap::default(),
+        HashSet::default(),
+        0,
+        CurrencyUnit::Sat,
+    );
+    mint_builder
+        .add_payment_processor(
+            CurrencyUnit::Sat,
+            PaymentMethod::Bolt11,
+            MintMeltLimits::new(1, 10_000),
+            Arc::new(fake_wallet),
+        )
+        .await
+        .expect("payment processor");
+
+    let mint = mint_builder
+        .with_urls(vec!["http://127.0.0.1".to_string()])
+        .build_with_seed(localstore, &[1; 64])
+        .await
+        .expect("mint");
+    mint.start().await.expect("start mint");
+
+    let router =
+        create_mint_router_with_limits(Arc::new(mint), HttpCache::default(), false, limits)
+            .await
+            .expect("router");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let url = format!("http://{}", listener.local_addr().expect("address"));
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+    });
+
+    url
+}
+
+fn proof_state(sub_id: &str) -> Value {
+    json!({
+        "kind": "proof_state",
+        "filters": [SecretKey::generate().public_key().to_hex()],
+        "subId": sub_id,
+    })
+}
+
+/// Reads the events of an open stream
+struct Events {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl Events {
+    /// Next event as its name and data, skipping keep-alive comments
+    async fn next(&mut self) -> (String, String) {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let event = self.buffer.drain(..end + 2).collect::<String>();
+                let mut name = "message".to_string();
+                let mut data = Vec::new();
+                for line in event.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        name = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.trim_start().to_string());
+                    }
+                }
+                if !data.is_empty() {
+                    return (name, data.join("\n"));
+                }
+                continue;
+            }
+
+            let chunk = tokio::time::timeout(Duration::from_secs(10), self.response.chunk())
+                .await
+                .expect("event in time")
+                .expect("chunk")
+                .expect("open stream");
+            self.buffer
+                .push_str(std::str::from_utf8(&chunk).expect("utf-8"));
+        }
+    }
+}
+
+/// Opens a stream with the subscriptions and returns it with its id
+async fn open(url: &str, subscriptions: Value) -> (Events, String) {
+    let response = reqwest::Client::new()
+        .post(format!("{url}/v1/sse"))
+        .json(&subscriptions)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut events = Events {
+        response,
+        buffer: String::new(),
+    };
+    let (name, stream_id) = events.next().await;
+    assert_eq!(name, "stream");
+
+    (events, stream_id)
+}
+
+async fn post_request(url: &str, stream_id: &str, request: Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{url}/v1/sse/{stream_id}"))
+        .json(&request)
+        .send()
+        .await
+        .expect("request")
+}
+
+/// Test: Subscriptions added to an open stream are notified on it
+#[tokio::test]
+async fn test_subscribe_on_open_stream() {
+    let url = serve(Limits::default()).await;
+    let (mut events, stream_id) = open(&url, json!([proof_state("proofs")])).await;
+
+    let quote: Value = reqwest::Client::new()
+        .post(format!("{url}/v1/mint/quote/bolt11"))
+        .json(&json!({ "amount": 10, "unit": "sat" }))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("mint quote");
+
+    let response = post_request(
+        &url,
+        &stream_id,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "subscribe",
+            "params": {
+                "kind": "bolt11_mint_quote",
+                "filters": [quote["quote"]],
+                "subId": "quote",
+            },
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let answer: Value = response.json().await.expect("answer");
+    assert_eq!(answer["id"], 1);
+    assert_eq!(answer["result"]["status"], "OK");
+    assert_eq!(answer["result"]["subId"], "quote");
+
+    // The current state of the quote is sent on the stream
+    let (name, data) = events.next().await;
+    assert_eq!(name, "message");
+    let notification: Value = serde_json::from_str(&data).expect("notification");
+    assert_eq!(notification["subId"], "quote");
+    assert_eq!(notification["payload"]["quote"], quote["quote"]);
+
+    let response = post_request(
+        &url,
+        &stream_id,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "unsubscribe",
+            "params": { "subId": "quote" },
+        }),
+    )
+    .await;
+    let answer: Value = response.json().await.expect("answer");
+    assert_eq!(answer["result"]["status"], "OK");
+}
+
+/// Test: Requests for unknown streams are refused, and streams are forgotten once closed
+#[tokio::test]
+async fn test_unknown_and_closed_streams() {
+    let url = serve(Limits {
+        websocket: WsLimits {
+            ping_interval: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "subscribe",
+        "params": proof_state("proofs"),
+    });
+
+    let response = post_request(&url, &uuid::Uuid::new_v4().to_string(), subscribe.clone()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let (events, stream_id) = open(&url, json!([])).await;
+    assert_eq!(
+        post_request(&url, &stream_id, subscribe.clone())
+            .await
+            .status(),
+        StatusCode::OK
+    );
+
+    // The mint notices the closed stream on its next keep-alive
+    drop(events);
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while post_request(&url, &stream_id, subscribe.clone())
+            .await
+            .status()
+            != StatusCode::NOT_FOUND
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("stream removed");
+}
+
+/// Test: Streams are opened within the subscription limits of the websocket
+#[tokio::test]
+async fn test_stream_subscription_limits() {
+    let url = serve(Limits {
+        websocket: WsLimits {
+            max_subscriptions: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/v1/sse"))
+        .json(&json!([proof_state("first"), proof_state("second")]))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: ErrorResponse = response.json().await.expect("error response");
+    assert_eq!(error.code.to_code(), 50003);
+
+    let response = client
+        .post(format!("{url}/v1/sse"))
+        .json(&json!([proof_state("same"), proof_state("same")]))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // The limit also applies to the subscriptions added later
+    let (_events, stream_id) = open(&url, json!([proof_state("first")])).await;
+    let answer: Value = post_request(
+        &url,
+        &stream_id,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "subscribe",
+            "params": proof_state("second"),
+        }),
+    )
+    .await
+    .json()
+    .await
+    .expect("answer");
+    assert_eq!(answer["error"]["code"], 50003);
+}
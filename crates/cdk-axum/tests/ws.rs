@@ -0,0 +1,256 @@
+//! NUT-17 websocket tests
+//!
+//! Serves the router of a fake wallet mint and talks to its websocket with plain JSON-RPC
+//! messages, as any NUT-17 wallet does.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use cdk::mint::{Mint, MintBuilder, MintMeltLimits};
+use cdk::nuts::{CurrencyUnit, PaymentMethod, SecretKey};
+use cdk::types::FeeReserve;
+use cdk_axum::cache::HttpCache;
+use cdk_axum::create_mint_router_with_limits;
+use cdk_axum::limits::{Limits, WsLimits};
+use cdk_fake_wallet::FakeWallet;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Builds a fake wallet mint, with blind auth on the websocket if `ws_blind_auth`
+async fn create_mint(ws_blind_auth: bool) -> Mint {
+    let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("db"));
+    let mut mint_builder = MintBuilder::new(localstore.clone());
+
+    let fake_wallet = FakeWallet::new(
+        FeeReserve {
+            min_fee_reserve: 1.into(),
+            percent_fee_reserve: 1.0,
+        },
+        HashMap::default(),
+        HashSet::default(),
+        0,
+        CurrencyUnit::Sat,
+    );
+    mint_builder
+        .add_payment_processor(
+            CurrencyUnit::Sat,
+            PaymentMethod::Bolt11,
+            MintMeltLimits::new(1, 10_000),
+            Arc::new(fake_wallet),
+        )
+        .await
+        .expect("payment processor");
+
+    #[cfg(feature = "auth")]
+    if ws_blind_auth {
+        use cdk::cdk_database::MintAuthDatabase;
+        use cdk::nuts::{AuthRequired, Method, ProtectedEndpoint, RoutePath};
+        use cdk_sqlite::mint::MintSqliteAuthDatabase;
+
+        let ws = ProtectedEndpoint::new(Method::Get, RoutePath::Ws);
+        let auth_localstore = Arc::new(
+            MintSqliteAuthDatabase::new(":memory:")
+                .await
+                .expect("auth db"),
+        );
+        let mut tx = auth_localstore
+            .begin_transaction()
+            .await
+            .expect("transaction");
+        tx.add_protected_endpoints(HashMap::from([(ws, AuthRequired::Blind)]))
+            .await
+            .expect("protected endpoints");
+        tx.commit().await.expect("commit");
+
+        mint_builder = mint_builder
+            .with_auth(
+                auth_localstore,
+                "http://127.0.0.1:1/.well-known/openid-configuration".to_string(),
+                "cashu-client".to_string(),
+                vec![],
+            )
+            .with_blind_auth(10, vec![ws]);
+    }
+    #[cfg(not(feature = "auth"))]
+    assert!(!ws_blind_auth, "blind auth requires the auth feature");
+
+    let mint = mint_builder
+        .with_urls(vec!["http://127.0.0.1".to_string()])
+        .build_with_seed(localstore, &[1; 64])
+        .await
+        .expect("mint");
+    mint.start().await.expect("start mint");
+
+    mint
+}
+
+/// Serves the mint router with the limits and returns the url of its websocket
+async fn serve(mint: Arc<Mint>, limits: Limits) -> String {
+    let router = create_mint_router_with_limits(mint, HttpCache::default(), false, limits)
+        .await
+        .expect("router");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("listener");
+    let url = format!("ws://{}/v1/ws", listener.local_addr().expect("address"));
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+    });
+
+    url
+}
+
+/// Sends a subscribe request and returns the answer of the mint
+async fn subscribe(socket: &mut Socket, id: usize, filters: usize) -> Value {
+    let filters = (0..filters)
+        .map(|_| SecretKey::generate().public_key().to_hex())
+        .collect::<Vec<_>>();
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "subscribe",
+        "params": {
+            "kind": "proof_state",
+            "filters": filters,
+            "subId": format!("sub-{id}"),
+        },
+    });
+    socket
+        .send(Message::Text(request.to_string().into()))
+        .await
+        .expect("send");
+
+    loop {
+        match socket.next().await.expect("answer").expect("message") {
+            Message::Text(text) => {
+                let answer: Value = serde_json::from_str(&text).expect("json");
+                if answer["id"] == id {
+                    return answer;
+                }
+            }
+            Message::Ping(_) | Message::Pong(_) => continue,
+            other => panic!("Unexpected message {other:?}"),
+        }
+    }
+}
+
+/// Test: Subscriptions and filters past the limits of the connection are refused
+#[tokio::test]
+async fn test_subscription_limits() {
+    let url = serve(
+        Arc::new(create_mint(false).await),
+        Limits {
+            websocket: WsLimits {
+                max_subscriptions: Some(2),
+                max_filters: Some(3),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await;
+    let (mut socket, _) = tokio_tungstenite::connect_async(&url)
+        .await
+        .expect("connect");
+
+    assert_eq!(subscribe(&mut socket, 0, 2).await["result"]["status"], "OK");
+
+    let answer = subscribe(&mut socket, 1, 2).await;
+    assert_eq!(answer["error"]["code"], 50003);
+    assert_eq!(
+        answer["error"]["message"],
+        "Connection exceeds the maximum of `3` filters"
+    );
+
+    assert_eq!(subscribe(&mut socket, 2, 1).await["result"]["status"], "OK");
+
+    let answer = subscribe(&mut socket, 3, 0).await;
+    assert_eq!(answer["error"]["code"], 50003);
+    assert_eq!(
+        answer["error"]["message"],
+        "Connection exceeds the maximum of `2` subscriptions"
+    );
+}
+
+/// Test: A blind auth token pays for the connection and all of its subscriptions
+#[cfg(feature = "auth")]
+#[tokio::test]
+async fn test_blind_auth_once_per_connection() {
+    use cdk::dhke::{blind_message, unblind_message};
+    use cdk::error::{ErrorCode, ErrorResponse};
+    use cdk::nuts::{AuthProof, BlindAuthToken, BlindedMessage};
+    use cdk::secret::Secret;
+    use cdk::Amount;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Error as WsError;
+
+    let mint = Arc::new(create_mint(true).await);
+    let url = serve(mint.clone(), Limits::default()).await;
+
+    // Sign a blind auth token with the auth keyset of the mint
+    let keyset = mint
+        .auth_pubkeys()
+        .expect("auth keys")
+        .keysets
+        .pop()
+        .expect("auth keyset");
+    let key = keyset.keys.amount_key(Amount::ONE).expect("auth key");
+    let secret = Secret::generate();
+    let (blinded, r) = blind_message(&secret.to_bytes(), None).expect("blind");
+    let signature = mint
+        .auth_blind_sign(&BlindedMessage::new(Amount::ONE, keyset.id, blinded))
+        .await
+        .expect("blind signature");
+    let bat = BlindAuthToken::new(AuthProof {
+        keyset_id: keyset.id,
+        secret,
+        c: unblind_message(&signature.c, &r, &key).expect("unblind"),
+        dleq: None,
+    });
+
+    let request = |bat: Option<&BlindAuthToken>| {
+        let mut request = url.as_str().into_client_request().expect("request");
+        if let Some(bat) = bat {
+            request
+                .headers_mut()
+                .insert("Blind-auth", bat.to_string().parse().expect("header value"));
+        }
+        request
+    };
+
+    match tokio_tungstenite::connect_async(request(None)).await {
+        Err(WsError::Http(response)) => {
+            let error: ErrorResponse =
+                serde_json::from_slice(response.body().as_deref().expect("body")).expect("error");
+            assert_eq!(error.code, ErrorCode::BlindAuthRequired);
+        }
+        other => panic!("Connected without auth: {other:?}"),
+    }
+
+    // Subscribe requests do not carry any auth
+    let (mut socket, _) = tokio_tungstenite::connect_async(request(Some(&bat)))
+        .await
+        .expect("connect");
+    for id in 0..3 {
+        assert_eq!(
+            subscribe(&mut socket, id, 1).await["result"]["status"],
+            "OK"
+        );
+    }
+
+    // The token was spent on the upgrade
+    assert!(tokio_tungstenite::connect_async(request(Some(&bat)))
+        .await
+        .is_err());
+}
@@ -1,7 +1,8 @@
 use std::fs;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{bail, Result};
 use bip39::rand::{thread_rng, Rng};
@@ -20,7 +21,10 @@ use tracing::Level;
 use tracing_subscriber::EnvFilter;
 use url::Url;
 
+use crate::output::OutputFormat;
+
 mod nostr_storage;
+mod output;
 mod sub_commands;
 mod token_storage;
 mod utils;
@@ -58,6 +62,9 @@ struct Cli {
     /// Currency unit to use for the wallet
     #[arg(short, long, default_value = "sat")]
     unit: String,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
     /// Use Tor transport (only when built with --features tor). Defaults to 'on' when feature is enabled.
     #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
     #[arg(long = "tor", value_enum, default_value_t = TorToggle::On)]
@@ -109,20 +116,30 @@ enum Commands {
     CatLogin(sub_commands::cat_login::CatLoginSubCommand),
     /// Cat login with device code flow
     CatDeviceLogin(sub_commands::cat_device_login::CatDeviceLoginSubCommand),
+    /// Cat login in the browser with the authorization code flow
+    CatBrowserLogin(sub_commands::cat_browser_login::CatBrowserLoginSubCommand),
+    /// Interactive terminal wallet
+    Tui(sub_commands::tui::TuiSubCommand),
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let args: Cli = Cli::parse();
+    let output = args.output;
+
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => output.error(&err),
+    }
+}
+
+async fn run(args: Cli) -> Result<()> {
     let default_filter = args.log_level;
 
     let filter = "rustls=warn,hyper_util=warn,reqwest=warn";
 
     let env_filter = EnvFilter::new(format!("{default_filter},{filter}"));
 
-    // Parse input
-    tracing_subscriber::fmt().with_env_filter(env_filter).init();
-
     let work_dir = match &args.work_dir {
         Some(work_dir) => work_dir.clone(),
         None => {
@@ -136,6 +153,26 @@ async fn main() -> Result<()> {
         fs::create_dir_all(&work_dir)?;
     }
 
+    // The TUI owns the terminal, so it logs to a file in the work dir
+    if matches!(args.command, Commands::Tui(_)) {
+        let log_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(work_dir.join("cdk-cli.log"))?;
+
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter)
+            .with_ansi(false)
+            .with_writer(Mutex::new(log_file))
+            .init();
+    } else {
+        // Logs go to stderr so they never mix with the command output
+        tracing_subscriber::fmt()
+            .with_env_filter(env_filter)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
     let localstore: Arc<dyn WalletDatabase<Err = cdk_database::Error> + Send + Sync> =
         match args.engine.as_str() {
             "sqlite" => {
@@ -230,74 +267,114 @@ async fn main() -> Result<()> {
 
     match &args.command {
         Commands::DecodeToken(sub_command_args) => {
-            sub_commands::decode_token::decode_token(sub_command_args)
+            sub_commands::decode_token::decode_token(sub_command_args, args.output)
         }
-        Commands::Balance => sub_commands::balance::balance(&multi_mint_wallet).await,
+        Commands::Balance => sub_commands::balance::balance(&multi_mint_wallet, args.output).await,
         Commands::Melt(sub_command_args) => {
-            sub_commands::melt::pay(&multi_mint_wallet, sub_command_args).await
+            sub_commands::melt::pay(&multi_mint_wallet, sub_command_args, args.output).await
         }
         Commands::Receive(sub_command_args) => {
-            sub_commands::receive::receive(&multi_mint_wallet, sub_command_args, &work_dir).await
+            sub_commands::receive::receive(
+                &multi_mint_wallet,
+                sub_command_args,
+                &work_dir,
+                args.output,
+            )
+            .await
         }
         Commands::Send(sub_command_args) => {
-            sub_commands::send::send(&multi_mint_wallet, sub_command_args).await
+            sub_commands::send::send(&multi_mint_wallet, sub_command_args, args.output).await
         }
         Commands::Transfer(sub_command_args) => {
-            sub_commands::transfer::transfer(&multi_mint_wallet, sub_command_args).await
+            sub_commands::transfer::transfer(&multi_mint_wallet, sub_command_args, args.output)
+                .await
         }
         Commands::CheckPending => {
-            sub_commands::check_pending::check_pending(&multi_mint_wallet).await
+            sub_commands::check_pending::check_pending(&multi_mint_wallet, args.output).await
         }
         Commands::MintInfo(sub_command_args) => {
-            sub_commands::mint_info::mint_info(args.proxy, sub_command_args).await
+            sub_commands::mint_info::mint_info(args.proxy, sub_command_args, args.output).await
         }
         Commands::Mint(sub_command_args) => {
-            sub_commands::mint::mint(&multi_mint_wallet, sub_command_args).await
+            sub_commands::mint::mint(&multi_mint_wallet, sub_command_args, args.output).await
         }
         Commands::MintPending => {
-            sub_commands::pending_mints::mint_pending(&multi_mint_wallet).await
+            sub_commands::pending_mints::mint_pending(&multi_mint_wallet, args.output).await
         }
         Commands::Burn(sub_command_args) => {
-            sub_commands::burn::burn(&multi_mint_wallet, sub_command_args).await
+            sub_commands::burn::burn(&multi_mint_wallet, sub_command_args, args.output).await
         }
         Commands::Restore(sub_command_args) => {
-            sub_commands::restore::restore(&multi_mint_wallet, sub_command_args).await
+            sub_commands::restore::restore(&multi_mint_wallet, sub_command_args, args.output).await
         }
         Commands::UpdateMintUrl(sub_command_args) => {
-            sub_commands::update_mint_url::update_mint_url(&multi_mint_wallet, sub_command_args)
-                .await
+            sub_commands::update_mint_url::update_mint_url(
+                &multi_mint_wallet,
+                sub_command_args,
+                args.output,
+            )
+            .await
         }
         Commands::ListMintProofs => {
-            sub_commands::list_mint_proofs::proofs(&multi_mint_wallet).await
+            sub_commands::list_mint_proofs::proofs(&multi_mint_wallet, args.output).await
         }
         Commands::DecodeRequest(sub_command_args) => {
-            sub_commands::decode_request::decode_payment_request(sub_command_args)
+            sub_commands::decode_request::decode_payment_request(sub_command_args, args.output)
         }
         Commands::PayRequest(sub_command_args) => {
-            sub_commands::pay_request::pay_request(&multi_mint_wallet, sub_command_args).await
+            sub_commands::pay_request::pay_request(
+                &multi_mint_wallet,
+                sub_command_args,
+                args.output,
+            )
+            .await
         }
         Commands::CreateRequest(sub_command_args) => {
-            sub_commands::create_request::create_request(&multi_mint_wallet, sub_command_args).await
+            sub_commands::create_request::create_request(
+                &multi_mint_wallet,
+                sub_command_args,
+                args.output,
+            )
+            .await
         }
         Commands::MintBlindAuth(sub_command_args) => {
             sub_commands::mint_blind_auth::mint_blind_auth(
                 &multi_mint_wallet,
                 sub_command_args,
                 &work_dir,
+                args.output,
             )
             .await
         }
         Commands::CatLogin(sub_command_args) => {
-            sub_commands::cat_login::cat_login(&multi_mint_wallet, sub_command_args, &work_dir)
-                .await
+            sub_commands::cat_login::cat_login(
+                &multi_mint_wallet,
+                sub_command_args,
+                &work_dir,
+                args.output,
+            )
+            .await
         }
         Commands::CatDeviceLogin(sub_command_args) => {
             sub_commands::cat_device_login::cat_device_login(
                 &multi_mint_wallet,
                 sub_command_args,
                 &work_dir,
+                args.output,
+            )
+            .await
+        }
+        Commands::CatBrowserLogin(sub_command_args) => {
+            sub_commands::cat_browser_login::cat_browser_login(
+                &multi_mint_wallet,
+                sub_command_args,
+                &work_dir,
+                args.output,
             )
             .await
         }
+        Commands::Tui(sub_command_args) => {
+            sub_commands::tui::tui(&multi_mint_wallet, sub_command_args).await
+        }
     }
 }
@@ -0,0 +1,366 @@
+//! Command output
+//!
+//! Commands print their result as human readable text or as a single JSON document on stdout.
+//! Failures are reported with an [`ErrorKind`], which also sets the process exit code.
+
+use std::fmt::Display;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Format of the command output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// JSON document on stdout, progress messages on stderr
+    Json,
+}
+
+impl OutputFormat {
+    /// Print a progress message
+    ///
+    /// In JSON mode stdout only carries the result, so messages go to stderr.
+    pub fn info(self, message: impl Display) {
+        match self {
+            Self::Text => println!("{message}"),
+            Self::Json => eprintln!("{message}"),
+        }
+    }
+
+    /// Print the result of a command
+    ///
+    /// `text` prints the human readable form, JSON mode serializes `value` instead.
+    pub fn result<T, F>(self, value: &T, text: F) -> Result<()>
+    where
+        T: Serialize,
+        F: FnOnce(&T),
+    {
+        match self {
+            Self::Text => text(value),
+            Self::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        }
+
+        Ok(())
+    }
+
+    /// Fail in JSON mode instead of showing an interactive prompt
+    ///
+    /// JSON output is meant for scripts, which cannot answer a prompt. `reason` tells the
+    /// caller which argument to pass instead.
+    pub fn forbid_prompt(self, reason: &'static str) -> Result<()> {
+        match self {
+            Self::Text => Ok(()),
+            Self::Json => Err(PromptUnavailable(reason).into()),
+        }
+    }
+
+    /// Report a failed command and return its exit code
+    pub fn error(self, err: &anyhow::Error) -> ExitCode {
+        let kind = ErrorKind::from(err);
+
+        match self {
+            Self::Text => eprintln!("Error: {err:?}"),
+            Self::Json => {
+                let output = ErrorOutput {
+                    error: ErrorObject {
+                        kind,
+                        message: format!("{err:#}"),
+                        exit_code: kind.exit_code(),
+                    },
+                };
+
+                match serde_json::to_string_pretty(&output) {
+                    Ok(output) => println!("{output}"),
+                    Err(_) => eprintln!("Error: {err:?}"),
+                }
+            }
+        }
+
+        ExitCode::from(kind.exit_code())
+    }
+}
+
+/// Command would prompt for input in JSON mode
+#[derive(Debug)]
+pub struct PromptUnavailable(pub &'static str);
+
+impl Display for PromptUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for PromptUnavailable {}
+
+/// Kind of failure, each with its own exit code
+///
+/// Exit code 2 is left to clap for invalid command lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Failure not covered by another kind
+    Internal,
+    /// Invalid token, invoice, url or amount
+    InvalidInput,
+    /// Not enough funds
+    InsufficientFunds,
+    /// Mint is not in the wallet
+    UnknownMint,
+    /// Proofs are already spent or pending
+    ProofsSpent,
+    /// Quote is unknown, expired, unpaid or already used
+    Quote,
+    /// Lightning payment failed or is still pending
+    Payment,
+    /// Mint refused the operation
+    MintUnavailable,
+    /// Mint or relay could not be reached
+    Network,
+    /// Authentication with the mint failed
+    Auth,
+    /// Wallet database failed
+    Database,
+}
+
+impl ErrorKind {
+    /// Process exit code
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Internal => 1,
+            Self::InvalidInput => 3,
+            Self::InsufficientFunds => 4,
+            Self::UnknownMint => 5,
+            Self::ProofsSpent => 6,
+            Self::Quote => 7,
+            Self::Payment => 8,
+            Self::MintUnavailable => 9,
+            Self::Network => 10,
+            Self::Auth => 11,
+            Self::Database => 12,
+        }
+    }
+}
+
+impl From<&anyhow::Error> for ErrorKind {
+    fn from(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(err) = cause.downcast_ref::<cdk::Error>() {
+                return err.into();
+            }
+
+            if cause.is::<cdk::cdk_database::Error>() {
+                return Self::Database;
+            }
+
+            if cause.is::<reqwest::Error>() {
+                return Self::Network;
+            }
+
+            if cause.is::<PromptUnavailable>()
+                || cause.is::<cdk::nuts::nut00::Error>()
+                || cause.is::<cdk::nuts::nut18::Error>()
+                || cause.is::<cdk::mint_url::Error>()
+                || cause.is::<cdk::lightning_invoice::ParseOrSemanticError>()
+                || cause.is::<std::num::ParseIntError>()
+                || cause.is::<url::ParseError>()
+            {
+                return Self::InvalidInput;
+            }
+        }
+
+        Self::Internal
+    }
+}
+
+impl From<&cdk::Error> for ErrorKind {
+    fn from(err: &cdk::Error) -> Self {
+        use cdk::Error;
+
+        match err {
+            Error::InsufficientFunds => Self::InsufficientFunds,
+            Error::UnknownMint { .. }
+            | Error::IncorrectMint
+            | Error::UnknownWallet(_)
+            | Error::IncorrectWallet(_) => Self::UnknownMint,
+            Error::TokenAlreadySpent | Error::TokenPending | Error::UnexpectedProofState => {
+                Self::ProofsSpent
+            }
+            Error::UnknownQuote
+            | Error::ExpiredQuote(_, _)
+            | Error::UnpaidQuote
+            | Error::PendingQuote
+            | Error::IssuedQuote
+            | Error::PaidQuote
+            | Error::DuplicateQuote
+            | Error::RequestAlreadyPaid
+            | Error::IncorrectQuoteAmount
+            | Error::QuoteExpiryOutOfRange(_)
+            | Error::AmountOutofLimitRange(_, _, _) => Self::Quote,
+            Error::PaymentFailed
+            | Error::PaymentPending
+            | Error::UnknownPaymentState
+            | Error::InvalidPaymentPreimage
+            | Error::MaxFeeExceeded => Self::Payment,
+            Error::MintingDisabled
+            | Error::MeltingDisabled
+            | Error::IssuanceLimitReached
+            | Error::RateLimited
+            | Error::UnsupportedUnit
+            | Error::UnsupportedPaymentMethod
+            | Error::MppUnitMethodNotSupported(_, _)
+            | Error::AmountlessInvoiceNotSupported(_, _)
+            | Error::InvoiceDescriptionUnsupported
+            | Error::InvoiceDescriptionHashUnsupported => Self::MintUnavailable,
+            Error::HttpError(_, _)
+            | Error::Timeout
+            | Error::TransferTimeout { .. }
+            | Error::CouldNotGetMintInfo
+            | Error::UnknownErrorResponse(_)
+            | Error::LightningAddressRequest(_) => Self::Network,
+            Error::ClearAuthRequired
+            | Error::BlindAuthRequired
+            | Error::ClearAuthFailed
+            | Error::BlindAuthFailed
+            | Error::AuthSettingsUndefined
+            | Error::InsufficientBlindAuthTokens
+            | Error::AuthLocalstoreUndefined
+            | Error::CatNotSet
+            | Error::OidcNotSet => Self::Auth,
+            Error::Database(_) => Self::Database,
+            Error::InvalidPaymentRequest
+            | Error::InvoiceAmountUndefined
+            | Error::InvalidInvoice
+            | Error::Invoice(_)
+            | Error::InvalidPaymentMethod
+            | Error::AmountUndefined
+            | Error::AmountlessAmountMismatch(_, _)
+            | Error::InvoiceDescriptionAndHash
+            | Error::LightningAddressParse(_)
+            | Error::InvalidSpendConditions(_)
+            | Error::PubkeyRequired
+            | Error::PreimageNotProvided
+            | Error::DleqProofNotProvided
+            | Error::CouldNotVerifyDleq
+            | Error::MultiMintTokenNotSupported
+            | Error::MultiMintCurrencyUnitMismatch { .. }
+            | Error::UnitMismatch
+            | Error::MultipleUnits
+            | Error::ParseInt(_)
+            | Error::UrlParseError(_)
+            | Error::CashuUrl(_)
+            | Error::NUT00(_)
+            | Error::NUT18(_) => Self::InvalidInput,
+            _ => Self::Internal,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorOutput {
+    error: ErrorObject,
+}
+
+#[derive(Serialize)]
+struct ErrorObject {
+    kind: ErrorKind,
+    message: String,
+    exit_code: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use anyhow::anyhow;
+    use cdk::mint_url::MintUrl;
+
+    use super::*;
+
+    const ALL_KINDS: [ErrorKind; 11] = [
+        ErrorKind::Internal,
+        ErrorKind::InvalidInput,
+        ErrorKind::InsufficientFunds,
+        ErrorKind::UnknownMint,
+        ErrorKind::ProofsSpent,
+        ErrorKind::Quote,
+        ErrorKind::Payment,
+        ErrorKind::MintUnavailable,
+        ErrorKind::Network,
+        ErrorKind::Auth,
+        ErrorKind::Database,
+    ];
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let codes: HashSet<u8> = ALL_KINDS.iter().map(|kind| kind.exit_code()).collect();
+
+        assert_eq!(codes.len(), ALL_KINDS.len());
+        assert!(!codes.contains(&0));
+        // Reserved for clap
+        assert!(!codes.contains(&2));
+        assert_eq!(ErrorKind::Internal.exit_code(), 1);
+        assert_eq!(ErrorKind::InsufficientFunds.exit_code(), 4);
+    }
+
+    #[test]
+    fn test_kind_from_cdk_error() {
+        let cases = [
+            (cdk::Error::InsufficientFunds, ErrorKind::InsufficientFunds),
+            (cdk::Error::IncorrectMint, ErrorKind::UnknownMint),
+            (cdk::Error::TokenAlreadySpent, ErrorKind::ProofsSpent),
+            (cdk::Error::UnknownQuote, ErrorKind::Quote),
+            (cdk::Error::PaymentFailed, ErrorKind::Payment),
+            (cdk::Error::MeltingDisabled, ErrorKind::MintUnavailable),
+            (cdk::Error::ClearAuthRequired, ErrorKind::Auth),
+            (cdk::Error::InvalidPaymentRequest, ErrorKind::InvalidInput),
+            (cdk::Error::Custom("other".to_string()), ErrorKind::Internal),
+        ];
+
+        for (err, kind) in cases {
+            assert_eq!(ErrorKind::from(&err), kind, "{err}");
+            assert_eq!(ErrorKind::from(&anyhow::Error::new(err)), kind);
+        }
+    }
+
+    #[test]
+    fn test_kind_from_error_with_context() {
+        let err = anyhow::Error::new(cdk::Error::InsufficientFunds)
+            .context("Total balance: 1, Required: 2")
+            .context("Could not send");
+
+        assert_eq!(ErrorKind::from(&err), ErrorKind::InsufficientFunds);
+    }
+
+    #[test]
+    fn test_kind_from_parse_errors() {
+        let err = anyhow::Error::new("abc".parse::<u64>().unwrap_err());
+        assert_eq!(ErrorKind::from(&err), ErrorKind::InvalidInput);
+
+        let err = anyhow::Error::new(MintUrl::from_str("not a url").unwrap_err());
+        assert_eq!(ErrorKind::from(&err), ErrorKind::InvalidInput);
+
+        let err = anyhow::Error::new(cdk::nuts::Token::from_str("cashuBnope").unwrap_err());
+        assert_eq!(ErrorKind::from(&err), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_forbid_prompt() {
+        assert!(OutputFormat::Text.forbid_prompt("--mint-url").is_ok());
+
+        let err = OutputFormat::Json
+            .forbid_prompt("--mint-url is required with --output json")
+            .unwrap_err();
+        assert_eq!(ErrorKind::from(&err), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "--mint-url is required with --output json");
+    }
+
+    #[test]
+    fn test_kind_from_other_error() {
+        assert_eq!(ErrorKind::from(&anyhow!("unexpected")), ErrorKind::Internal);
+    }
+}
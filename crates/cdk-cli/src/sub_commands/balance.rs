@@ -5,41 +5,57 @@ use cdk::mint_url::MintUrl;
 use cdk::nuts::CurrencyUnit;
 use cdk::wallet::MultiMintWallet;
 use cdk::Amount;
+use serde::Serialize;
 
-pub async fn balance(multi_mint_wallet: &MultiMintWallet) -> Result<()> {
-    // Show individual mint balances
-    let mint_balances = mint_balances(multi_mint_wallet, multi_mint_wallet.unit()).await?;
+use crate::output::OutputFormat;
+
+#[derive(Serialize)]
+struct BalanceOutput {
+    unit: CurrencyUnit,
+    total: Amount,
+    mints: Vec<MintBalance>,
+}
+
+#[derive(Serialize)]
+struct MintBalance {
+    mint_url: MintUrl,
+    balance: Amount,
+}
+
+pub async fn balance(multi_mint_wallet: &MultiMintWallet, output: OutputFormat) -> Result<()> {
+    let mints = mint_balances(multi_mint_wallet).await?;
 
     // Show total balance using the new unified interface
     let total = multi_mint_wallet.total_balance().await?;
-    if !mint_balances.is_empty() {
-        println!();
-        println!(
-            "Total balance across all wallets: {} {}",
-            total,
-            multi_mint_wallet.unit()
-        );
-    }
-
-    Ok(())
+
+    let balance = BalanceOutput {
+        unit: multi_mint_wallet.unit().clone(),
+        total,
+        mints,
+    };
+
+    output.result(&balance, |balance| {
+        // Show individual mint balances
+        for (i, mint) in balance.mints.iter().enumerate() {
+            println!("{i}: {} {} {}", mint.mint_url, mint.balance, balance.unit);
+        }
+
+        if !balance.mints.is_empty() {
+            println!();
+            println!(
+                "Total balance across all wallets: {} {}",
+                balance.total, balance.unit
+            );
+        }
+    })
 }
 
-pub async fn mint_balances(
-    multi_mint_wallet: &MultiMintWallet,
-    unit: &CurrencyUnit,
-) -> Result<Vec<(MintUrl, Amount)>> {
+async fn mint_balances(multi_mint_wallet: &MultiMintWallet) -> Result<Vec<MintBalance>> {
     let wallets: BTreeMap<MintUrl, Amount> = multi_mint_wallet.get_balances().await?;
 
-    let mut wallets_vec = Vec::with_capacity(wallets.len());
-
-    for (i, (mint_url, amount)) in wallets
-        .iter()
-        .filter(|(_, a)| a > &&Amount::ZERO)
-        .enumerate()
-    {
-        let mint_url = mint_url.clone();
-        println!("{i}: {mint_url} {amount} {unit}");
-        wallets_vec.push((mint_url, *amount))
-    }
-    Ok(wallets_vec)
+    Ok(wallets
+        .into_iter()
+        .filter(|(_, balance)| balance > &Amount::ZERO)
+        .map(|(mint_url, balance)| MintBalance { mint_url, balance })
+        .collect())
 }
@@ -3,6 +3,9 @@ use cdk::mint_url::MintUrl;
 use cdk::wallet::MultiMintWallet;
 use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
 
 #[derive(Args)]
 pub struct BurnSubCommand {
@@ -10,15 +13,27 @@ pub struct BurnSubCommand {
     mint_url: Option<MintUrl>,
 }
 
+#[derive(Serialize)]
+struct BurnOutput {
+    burned: Amount,
+}
+
 pub async fn burn(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &BurnSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     let mut total_burnt = Amount::ZERO;
 
     match &sub_command_args.mint_url {
         Some(mint_url) => {
-            let wallet = multi_mint_wallet.get_wallet(mint_url).await.unwrap();
+            let wallet =
+                multi_mint_wallet
+                    .get_wallet(mint_url)
+                    .await
+                    .ok_or(cdk::Error::UnknownMint {
+                        mint_url: mint_url.to_string(),
+                    })?;
             total_burnt = wallet.check_all_pending_proofs().await?;
         }
         None => {
@@ -29,6 +44,10 @@ pub async fn burn(
         }
     }
 
-    println!("{total_burnt} burned");
-    Ok(())
+    output.result(
+        &BurnOutput {
+            burned: total_burnt,
+        },
+        |burn| println!("{} burned", burn.burned),
+    )
 }
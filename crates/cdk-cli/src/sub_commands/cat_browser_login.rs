@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use cdk::mint_url::MintUrl;
+use cdk::wallet::{LoopbackRedirect, MultiMintWallet};
+use cdk::{OidcClient, PkceChallenge};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use super::cat_login::save_tokens;
+use crate::output::OutputFormat;
+
+#[derive(Args, Serialize, Deserialize)]
+pub struct CatBrowserLoginSubCommand {
+    /// Mint url
+    mint_url: MintUrl,
+    /// Client ID for OIDC authentication
+    #[arg(default_value = "cashu-client")]
+    #[arg(long)]
+    client_id: String,
+    /// Port of the loopback redirect listener, a free port is picked by default
+    #[arg(long, default_value_t = 0)]
+    port: u16,
+    /// Only print the login url instead of opening it in the browser
+    #[arg(long)]
+    no_browser: bool,
+}
+
+pub async fn cat_browser_login(
+    multi_mint_wallet: &MultiMintWallet,
+    sub_command_args: &CatBrowserLoginSubCommand,
+    work_dir: &Path,
+    output: OutputFormat,
+) -> Result<()> {
+    let mint_url = sub_command_args.mint_url.clone();
+
+    // Ensure the mint exists
+    if !multi_mint_wallet.has_mint(&mint_url).await {
+        multi_mint_wallet.add_mint(mint_url.clone()).await?;
+    }
+
+    let mint_info = multi_mint_wallet
+        .fetch_mint_info(&mint_url)
+        .await?
+        .ok_or(anyhow!("Mint info not found"))?;
+
+    let openid_discovery = mint_info
+        .nuts
+        .nut21
+        .ok_or(cdk::Error::AuthSettingsUndefined)
+        .map_err(|err| anyhow::Error::new(err).context("Mint does not support clear auth"))?
+        .openid_discovery;
+
+    let oidc_client = OidcClient::new(openid_discovery, None);
+    let loopback = LoopbackRedirect::bind(sub_command_args.port).await?;
+    let redirect_uri = loopback.redirect_uri().to_string();
+    let pkce = PkceChallenge::new();
+
+    let authorization_url = oidc_client
+        .authorization_url(
+            &sub_command_args.client_id,
+            &redirect_uri,
+            &pkce,
+            loopback.state(),
+        )
+        .await?;
+
+    output.info(format!("\nTo login, visit: {authorization_url}\n"));
+    if !sub_command_args.no_browser && !open_browser(authorization_url.as_str()) {
+        output.info("Could not open the browser, open the url above to continue");
+    }
+    output.info("Waiting for the login to complete...");
+
+    let code = loopback.wait_for_code().await?;
+
+    let token_response = oidc_client
+        .exchange_authorization_code(
+            sub_command_args.client_id.clone(),
+            code,
+            redirect_uri,
+            &pkce,
+        )
+        .await?;
+
+    let access_token = token_response.access_token;
+    let refresh_token = token_response
+        .refresh_token
+        .ok_or(anyhow!("No refresh token in response"))?;
+
+    // Save tokens to file in work directory
+    save_tokens(work_dir, &mint_url, &access_token, &refresh_token, output).await
+}
+
+/// Opens the url in the default browser, returns whether it could be launched
+fn open_browser(url: &str) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command.arg(url).spawn().is_ok()
+}
@@ -10,7 +10,8 @@ use clap::Args;
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
-use crate::token_storage;
+use super::cat_login::save_tokens;
+use crate::output::OutputFormat;
 
 #[derive(Args, Serialize, Deserialize)]
 pub struct CatDeviceLoginSubCommand {
@@ -26,6 +27,7 @@ pub async fn cat_device_login(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &CatDeviceLoginSubCommand,
     work_dir: &Path,
+    output: OutputFormat,
 ) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
 
@@ -40,27 +42,17 @@ pub async fn cat_device_login(
         .ok_or(anyhow!("Mint info not found"))?;
 
     let (access_token, refresh_token) =
-        get_device_code_token(&mint_info, &sub_command_args.client_id).await;
+        get_device_code_token(&mint_info, &sub_command_args.client_id, output).await;
 
     // Save tokens to file in work directory
-    if let Err(e) =
-        token_storage::save_tokens(work_dir, &mint_url, &access_token, &refresh_token).await
-    {
-        println!("Warning: Failed to save tokens to file: {e}");
-    } else {
-        println!("Tokens saved to work directory");
-    }
-
-    // Print a cute ASCII cat
-    println!("\nAuthentication successful! 🎉\n");
-    println!("\nYour tokens:");
-    println!("access_token: {access_token}");
-    println!("refresh_token: {refresh_token}");
-
-    Ok(())
+    save_tokens(work_dir, &mint_url, &access_token, &refresh_token, output).await
 }
 
-async fn get_device_code_token(mint_info: &MintInfo, client_id: &str) -> (String, String) {
+async fn get_device_code_token(
+    mint_info: &MintInfo,
+    client_id: &str,
+    output: OutputFormat,
+) -> (String, String) {
     let openid_discovery = mint_info
         .nuts
         .nut21
@@ -111,11 +103,13 @@ async fn get_device_code_token(mint_info: &MintInfo, client_id: &str) -> (String
 
     let interval = device_code_data["interval"].as_u64().unwrap_or(5);
 
-    println!("\nTo login, visit: {verification_uri}");
-    println!("And enter code: {user_code}\n");
+    output.info(format!("\nTo login, visit: {verification_uri}"));
+    output.info(format!("And enter code: {user_code}\n"));
 
     if verification_uri_complete != verification_uri {
-        println!("Or visit this URL directly: {verification_uri_complete}\n");
+        output.info(format!(
+            "Or visit this URL directly: {verification_uri_complete}\n"
+        ));
     }
 
     // Poll for the token
@@ -166,7 +160,7 @@ async fn get_device_code_token(mint_info: &MintInfo, client_id: &str) -> (String
                     // If we're polling too fast, slow down
                     sleep(Duration::from_secs(interval + 5)).await;
                 }
-                println!("Waiting for user to complete authentication...");
+                output.info("Waiting for user to complete authentication...");
                 continue;
             } else {
                 // For other errors, exit with an error message
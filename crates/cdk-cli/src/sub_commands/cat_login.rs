@@ -8,6 +8,7 @@ use cdk::OidcClient;
 use clap::Args;
 use serde::{Deserialize, Serialize};
 
+use crate::output::OutputFormat;
 use crate::token_storage;
 
 #[derive(Args, Serialize, Deserialize)]
@@ -24,10 +25,20 @@ pub struct CatLoginSubCommand {
     client_id: String,
 }
 
+#[derive(Serialize)]
+struct CatLoginOutput<'a> {
+    mint_url: &'a MintUrl,
+    access_token: &'a str,
+    refresh_token: &'a str,
+    /// Whether the tokens were saved to the work directory
+    saved: bool,
+}
+
 pub async fn cat_login(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &CatLoginSubCommand,
     work_dir: &Path,
+    output: OutputFormat,
 ) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
 
@@ -50,20 +61,42 @@ pub async fn cat_login(
     .await;
 
     // Save tokens to file in work directory
-    if let Err(e) =
-        token_storage::save_tokens(work_dir, &mint_url, &access_token, &refresh_token).await
-    {
-        println!("Warning: Failed to save tokens to file: {e}");
-    } else {
-        println!("Tokens saved to work directory");
-    }
-
-    println!("\nAuthentication successful! 🎉\n");
-    println!("\nYour tokens:");
-    println!("access_token: {access_token}");
-    println!("refresh_token: {refresh_token}");
+    save_tokens(work_dir, &mint_url, &access_token, &refresh_token, output).await
+}
 
-    Ok(())
+/// Save the tokens to the work directory and print them
+pub(crate) async fn save_tokens(
+    work_dir: &Path,
+    mint_url: &MintUrl,
+    access_token: &str,
+    refresh_token: &str,
+    output: OutputFormat,
+) -> Result<()> {
+    let saved =
+        match token_storage::save_tokens(work_dir, mint_url, access_token, refresh_token).await {
+            Ok(()) => {
+                output.info("Tokens saved to work directory");
+                true
+            }
+            Err(e) => {
+                output.info(format!("Warning: Failed to save tokens to file: {e}"));
+                false
+            }
+        };
+
+    let login = CatLoginOutput {
+        mint_url,
+        access_token,
+        refresh_token,
+        saved,
+    };
+
+    output.result(&login, |login| {
+        println!("\nAuthentication successful! 🎉\n");
+        println!("\nYour tokens:");
+        println!("access_token: {}", login.access_token);
+        println!("refresh_token: {}", login.refresh_token);
+    })
 }
 
 async fn get_access_token(
@@ -1,33 +1,77 @@
 use anyhow::Result;
+use cdk::mint_url::MintUrl;
 use cdk::nuts::nut00::ProofsMethods;
 use cdk::wallet::multi_mint_wallet::MultiMintWallet;
+use cdk::Amount;
+use serde::Serialize;
 
-pub async fn check_pending(multi_mint_wallet: &MultiMintWallet) -> Result<()> {
+use crate::output::OutputFormat;
+
+#[derive(Serialize)]
+struct CheckPendingOutput {
+    mints: Vec<MintPending>,
+}
+
+#[derive(Serialize)]
+struct MintPending {
+    mint_url: MintUrl,
+    pending_proofs: usize,
+    amount: Amount,
+    /// Error reclaiming the proofs, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn check_pending(
+    multi_mint_wallet: &MultiMintWallet,
+    output: OutputFormat,
+) -> Result<()> {
     let wallets = multi_mint_wallet.get_wallets().await;
+    let mut mints = Vec::with_capacity(wallets.len());
 
     for (i, wallet) in wallets.iter().enumerate() {
         let mint_url = wallet.mint_url.clone();
-        println!("{i}: {mint_url}");
+        output.info(format!("{i}: {mint_url}"));
 
         // Get all pending proofs
         let pending_proofs = wallet.get_pending_proofs().await?;
         if pending_proofs.is_empty() {
-            println!("No pending proofs found");
+            output.info("No pending proofs found");
+            mints.push(MintPending {
+                mint_url,
+                pending_proofs: 0,
+                amount: Amount::ZERO,
+                error: None,
+            });
             continue;
         }
 
-        println!(
-            "Found {} pending proofs with {} {}",
-            pending_proofs.len(),
-            pending_proofs.total_amount()?,
+        let count = pending_proofs.len();
+        let amount = pending_proofs.total_amount()?;
+        output.info(format!(
+            "Found {count} pending proofs with {amount} {}",
             wallet.unit
-        );
+        ));
 
         // Try to reclaim any proofs that are no longer pending
-        match wallet.reclaim_unspent(pending_proofs).await {
-            Ok(()) => println!("Successfully reclaimed pending proofs"),
-            Err(e) => println!("Error reclaimed pending proofs: {e}"),
-        }
+        let error = match wallet.reclaim_unspent(pending_proofs).await {
+            Ok(()) => {
+                output.info("Successfully reclaimed pending proofs");
+                None
+            }
+            Err(e) => {
+                output.info(format!("Error reclaimed pending proofs: {e}"));
+                Some(e.to_string())
+            }
+        };
+
+        mints.push(MintPending {
+            mint_url,
+            pending_proofs: count,
+            amount,
+            error,
+        });
     }
-    Ok(())
+
+    output.result(&CheckPendingOutput { mints }, |_| {})
 }
@@ -1,6 +1,11 @@
 use anyhow::Result;
+use cdk::nuts::PaymentRequest;
 use cdk::wallet::{payment_request as pr, MultiMintWallet};
+use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
 
 #[derive(Args)]
 pub struct CreateRequestSubCommand {
@@ -38,9 +43,21 @@ pub struct CreateRequestSubCommand {
     nostr_relay: Option<Vec<String>>,
 }
 
+#[derive(Serialize)]
+struct CreateRequestOutput {
+    /// Encoded payment request
+    request: String,
+    /// Decoded payment request
+    payment_request: PaymentRequest,
+    /// Amount received over Nostr, if the request was listened for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    received: Option<Amount>,
+}
+
 pub async fn create_request(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &CreateRequestSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     // Gather parameters for library call
     let params = pr::CreateRequestParams {
@@ -58,15 +75,27 @@ pub async fn create_request(
 
     let (req, nostr_wait) = multi_mint_wallet.create_request(params).await?;
 
-    // Print the request to stdout
-    println!("{}", req);
+    // The request has to be shared before the payment can arrive
+    output.info(&req);
 
     // If we set up Nostr transport, optionally wait for payment and receive it
-    if let Some(info) = nostr_wait {
-        println!("Listening for payment via Nostr...");
-        let amount = multi_mint_wallet.wait_for_nostr_payment(info).await?;
-        println!("Received {}", amount);
-    }
+    let received = match nostr_wait {
+        Some(info) => {
+            output.info("Listening for payment via Nostr...");
+            Some(multi_mint_wallet.wait_for_nostr_payment(info).await?)
+        }
+        None => None,
+    };
+
+    let created = CreateRequestOutput {
+        request: req.to_string(),
+        payment_request: req,
+        received,
+    };
 
-    Ok(())
+    output.result(&created, |created| {
+        if let Some(amount) = created.received {
+            println!("Received {}", amount);
+        }
+    })
 }
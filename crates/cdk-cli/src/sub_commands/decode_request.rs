@@ -5,15 +5,22 @@ use cdk::nuts::PaymentRequest;
 use cdk::util::serialize_to_cbor_diag;
 use clap::Args;
 
+use crate::output::OutputFormat;
+
 #[derive(Args)]
 pub struct DecodePaymentRequestSubCommand {
     /// Payment request
     payment_request: String,
 }
 
-pub fn decode_payment_request(sub_command_args: &DecodePaymentRequestSubCommand) -> Result<()> {
+pub fn decode_payment_request(
+    sub_command_args: &DecodePaymentRequestSubCommand,
+    output: OutputFormat,
+) -> Result<()> {
     let payment_request = PaymentRequest::from_str(&sub_command_args.payment_request)?;
 
-    println!("{:}", serialize_to_cbor_diag(&payment_request)?);
-    Ok(())
+    let diag = serialize_to_cbor_diag(&payment_request)?;
+
+    // JSON output uses the NUT-18 JSON encoding of the request
+    output.result(&payment_request, |_| println!("{diag}"))
 }
@@ -1,9 +1,14 @@
 use std::str::FromStr;
 
 use anyhow::Result;
-use cdk::nuts::Token;
+use cdk::mint_url::MintUrl;
+use cdk::nuts::{CurrencyUnit, Token};
 use cdk::util::serialize_to_cbor_diag;
+use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
 
 #[derive(Args)]
 pub struct DecodeTokenSubCommand {
@@ -11,9 +16,27 @@ pub struct DecodeTokenSubCommand {
     token: String,
 }
 
-pub fn decode_token(sub_command_args: &DecodeTokenSubCommand) -> Result<()> {
+#[derive(Serialize)]
+struct DecodeTokenOutput {
+    mint_url: MintUrl,
+    unit: Option<CurrencyUnit>,
+    memo: Option<String>,
+    value: Amount,
+    proof_count: usize,
+}
+
+pub fn decode_token(sub_command_args: &DecodeTokenSubCommand, output: OutputFormat) -> Result<()> {
     let token = Token::from_str(&sub_command_args.token)?;
 
-    println!("{:}", serialize_to_cbor_diag(&token)?);
-    Ok(())
+    let decoded = DecodeTokenOutput {
+        mint_url: token.mint_url()?,
+        unit: token.unit(),
+        memo: token.memo().clone(),
+        value: token.value()?,
+        proof_count: token.token_secrets().len(),
+    };
+
+    let diag = serialize_to_cbor_diag(&token)?;
+
+    output.result(&decoded, |_| println!("{diag}"))
 }
@@ -1,67 +1,104 @@
 use anyhow::Result;
 use cdk::mint_url::MintUrl;
-use cdk::nuts::{CurrencyUnit, Proof};
+use cdk::nuts::{CurrencyUnit, Id, Proof};
+use cdk::secret::Secret;
 use cdk::wallet::multi_mint_wallet::MultiMintWallet;
+use cdk::Amount;
+use serde::Serialize;
 
-pub async fn proofs(multi_mint_wallet: &MultiMintWallet) -> Result<()> {
-    list_proofs(multi_mint_wallet).await?;
-    Ok(())
+use crate::output::OutputFormat;
+
+#[derive(Serialize)]
+struct ListProofsOutput {
+    mints: Vec<MintProofs>,
+}
+
+#[derive(Serialize)]
+struct MintProofs {
+    mint_url: MintUrl,
+    unit: CurrencyUnit,
+    proofs: Vec<ProofOutput>,
+}
+
+#[derive(Serialize)]
+struct ProofOutput {
+    amount: Amount,
+    keyset_id: Id,
+    state: &'static str,
+    secret: Secret,
+    dleq: bool,
+}
+
+impl ProofOutput {
+    fn new(proof: Proof, state: &'static str) -> Self {
+        Self {
+            amount: proof.amount,
+            keyset_id: proof.keyset_id,
+            state,
+            secret: proof.secret,
+            dleq: proof.dleq.is_some(),
+        }
+    }
 }
 
-async fn list_proofs(
-    multi_mint_wallet: &MultiMintWallet,
-) -> Result<Vec<(MintUrl, (Vec<Proof>, CurrencyUnit))>> {
-    let mut proofs_vec = Vec::new();
+pub async fn proofs(multi_mint_wallet: &MultiMintWallet, output: OutputFormat) -> Result<()> {
+    let mints = list_proofs(multi_mint_wallet).await?;
 
+    output.result(&ListProofsOutput { mints }, |list| {
+        for (i, mint) in list.mints.iter().enumerate() {
+            println!("{i}: {}", mint.mint_url);
+            println!("|   Amount | Unit | State    | Secret                                                           | DLEQ proof included");
+            println!("|----------|------|----------|------------------------------------------------------------------|--------------------");
+
+            for proof in &mint.proofs {
+                println!(
+                    "| {:8} | {:4} | {:8} | {:64} | {}",
+                    proof.amount, mint.unit, proof.state, proof.secret, proof.dleq
+                );
+            }
+
+            println!();
+        }
+    })
+}
+
+async fn list_proofs(multi_mint_wallet: &MultiMintWallet) -> Result<Vec<MintProofs>> {
     let wallets = multi_mint_wallet.get_wallets().await;
+    let mut mints = Vec::with_capacity(wallets.len());
 
-    for (i, wallet) in wallets.iter().enumerate() {
-        let mint_url = wallet.mint_url.clone();
-        println!("{i}: {mint_url}");
-        println!("|   Amount | Unit | State    | Secret                                                           | DLEQ proof included");
-        println!("|----------|------|----------|------------------------------------------------------------------|--------------------");
+    for wallet in wallets.iter() {
+        let mut proofs = Vec::new();
 
         // Unspent proofs
         let unspent_proofs = wallet.get_unspent_proofs().await?;
-        for proof in unspent_proofs.iter() {
-            println!(
-                "| {:8} | {:4} | {:8} | {:64} | {}",
-                proof.amount,
-                wallet.unit,
-                "unspent",
-                proof.secret,
-                proof.dleq.is_some()
-            );
-        }
+        proofs.extend(
+            unspent_proofs
+                .into_iter()
+                .map(|proof| ProofOutput::new(proof, "unspent")),
+        );
 
         // Pending proofs
         let pending_proofs = wallet.get_pending_proofs().await?;
-        for proof in pending_proofs {
-            println!(
-                "| {:8} | {:4} | {:8} | {:64} | {}",
-                proof.amount,
-                wallet.unit,
-                "pending",
-                proof.secret,
-                proof.dleq.is_some()
-            );
-        }
+        proofs.extend(
+            pending_proofs
+                .into_iter()
+                .map(|proof| ProofOutput::new(proof, "pending")),
+        );
 
         // Reserved proofs
         let reserved_proofs = wallet.get_reserved_proofs().await?;
-        for proof in reserved_proofs {
-            println!(
-                "| {:8} | {:4} | {:8} | {:64} | {}",
-                proof.amount,
-                wallet.unit,
-                "reserved",
-                proof.secret,
-                proof.dleq.is_some()
-            );
-        }
+        proofs.extend(
+            reserved_proofs
+                .into_iter()
+                .map(|proof| ProofOutput::new(proof, "reserved")),
+        );
 
-        println!();
-        proofs_vec.push((mint_url, (unspent_proofs, wallet.unit.clone())));
+        mints.push(MintProofs {
+            mint_url: wallet.mint_url.clone(),
+            unit: wallet.unit.clone(),
+            proofs,
+        });
     }
-    Ok(proofs_vec)
+
+    Ok(mints)
 }
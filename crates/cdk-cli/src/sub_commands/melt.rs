@@ -3,12 +3,15 @@ use std::str::FromStr;
 use anyhow::{bail, Result};
 use cdk::amount::{amount_for_offer, Amount, MSAT_IN_SAT};
 use cdk::mint_url::MintUrl;
-use cdk::nuts::{CurrencyUnit, MeltOptions};
+use cdk::nuts::{CurrencyUnit, MeltOptions, MeltQuoteState};
+use cdk::types::Melted;
 use cdk::wallet::MultiMintWallet;
 use cdk::Bolt11Invoice;
 use clap::{Args, ValueEnum};
 use lightning::offers::offer::Offer;
+use serde::Serialize;
 
+use crate::output::OutputFormat;
 use crate::utils::{get_number_input, get_user_input};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -34,6 +37,40 @@ pub struct MeltSubCommand {
     method: PaymentType,
 }
 
+#[derive(Serialize)]
+struct MeltOutput {
+    payments: Vec<MeltPayment>,
+    total_paid: Amount,
+    total_fees: Amount,
+}
+
+/// Payment made by a single mint
+#[derive(Serialize)]
+struct MeltPayment {
+    /// Not known when the wallet selects the mint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mint_url: Option<MintUrl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_id: Option<String>,
+    state: MeltQuoteState,
+    amount: Amount,
+    fee_paid: Amount,
+    preimage: Option<String>,
+}
+
+impl MeltPayment {
+    fn new(mint_url: Option<MintUrl>, quote_id: Option<String>, melted: Melted) -> Self {
+        Self {
+            mint_url,
+            quote_id,
+            state: melted.state,
+            amount: melted.amount,
+            fee_paid: melted.fee_paid,
+            preimage: melted.preimage,
+        }
+    }
+}
+
 /// Helper function to check if there are enough funds and create appropriate MeltOptions
 fn create_melt_options(
     available_funds: u64,
@@ -44,7 +81,8 @@ fn create_melt_options(
         Some(amount) => {
             // Payment has a specified amount
             if amount > available_funds {
-                bail!("Not enough funds; payment requires {} msats", amount);
+                return Err(anyhow::Error::new(cdk::Error::InsufficientFunds)
+                    .context(format!("Payment requires {amount} msats")));
             }
             Ok(None) // Use default options
         }
@@ -53,7 +91,7 @@ fn create_melt_options(
             let user_amount = get_number_input::<u64>(prompt)? * MSAT_IN_SAT;
 
             if user_amount > available_funds {
-                bail!("Not enough funds");
+                return Err(cdk::Error::InsufficientFunds.into());
             }
 
             Ok(Some(MeltOptions::new_amountless(user_amount)))
@@ -64,19 +102,25 @@ fn create_melt_options(
 pub async fn pay(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &MeltSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     // Check total balance across all wallets
     let total_balance = multi_mint_wallet.total_balance().await?;
     if total_balance == Amount::ZERO {
-        bail!("No funds available");
+        return Err(anyhow::Error::new(cdk::Error::InsufficientFunds).context("No funds available"));
     }
 
     // Determine which mint to use for melting BEFORE processing payment (unless using MPP)
     let selected_mint = if sub_command_args.mpp {
+        output.forbid_prompt(
+            "--mpp selects mints interactively and cannot be used with --output json",
+        )?;
         None // MPP mode handles mint selection differently
     } else if let Some(mint_url) = &sub_command_args.mint_url {
         Some(MintUrl::from_str(mint_url)?)
     } else {
+        output.forbid_prompt("--mint-url is required with --output json")?;
+
         // Display all mints with their balances and let user select
         let balances_map = multi_mint_wallet.get_balances().await?;
         if balances_map.is_empty() {
@@ -85,9 +129,9 @@ pub async fn pay(
 
         let balances_vec: Vec<(MintUrl, Amount)> = balances_map.into_iter().collect();
 
-        println!("\nAvailable mints and balances:");
+        eprintln!("\nAvailable mints and balances:");
         for (index, (mint_url, balance)) in balances_vec.iter().enumerate() {
-            println!(
+            eprintln!(
                 "  {}: {} - {} {}",
                 index,
                 mint_url,
@@ -95,7 +139,7 @@ pub async fn pay(
                 multi_mint_wallet.unit()
             );
         }
-        println!("  {}: Any mint (auto-select best)", balances_vec.len());
+        eprintln!("  {}: Any mint (auto-select best)", balances_vec.len());
 
         let selection = loop {
             let selection: usize =
@@ -109,13 +153,13 @@ pub async fn pay(
                 break Some(mint_url.clone());
             }
 
-            println!("Invalid selection, please try again.");
+            eprintln!("Invalid selection, please try again.");
         };
 
         selection
     };
 
-    if sub_command_args.mpp {
+    let payments = if sub_command_args.mpp {
         // Manual MPP - user specifies which mints and amounts to use
         if !matches!(sub_command_args.method, PaymentType::Bolt11) {
             bail!("MPP is only supported for BOLT11 invoices");
@@ -126,9 +170,9 @@ pub async fn pay(
 
         // Show available mints and balances
         let balances = multi_mint_wallet.get_balances().await?;
-        println!("\nAvailable mints and balances:");
+        eprintln!("\nAvailable mints and balances:");
         for (i, (mint_url, balance)) in balances.iter().enumerate() {
-            println!(
+            eprintln!(
                 "  {}: {} - {} {}",
                 i,
                 mint_url,
@@ -165,16 +209,19 @@ pub async fn pay(
         }
 
         // Get quotes for each mint
-        println!("\nGetting melt quotes...");
+        output.info("\nGetting melt quotes...");
         let quotes = multi_mint_wallet
             .mpp_melt_quote(bolt11_str, mint_amounts)
             .await?;
 
         // Display quotes
-        println!("\nMelt quotes obtained:");
+        output.info("\nMelt quotes obtained:");
         for (mint_url, quote) in &quotes {
-            println!("  {} - Quote ID: {}", mint_url, quote.id);
-            println!("    Amount: {}, Fee: {}", quote.amount, quote.fee_reserve);
+            output.info(format!("  {} - Quote ID: {}", mint_url, quote.id));
+            output.info(format!(
+                "    Amount: {}, Fee: {}",
+                quote.amount, quote.fee_reserve
+            ));
         }
 
         // Execute the melts
@@ -183,29 +230,22 @@ pub async fn pay(
             .map(|(url, quote)| (url.clone(), quote.id.clone()))
             .collect();
 
-        println!("\nExecuting MPP payment...");
-        let results = multi_mint_wallet.mpp_melt(quotes_to_execute).await?;
-
-        // Display results
-        println!("\nPayment results:");
-        let mut total_paid = Amount::ZERO;
-        let mut total_fees = Amount::ZERO;
-
-        for (mint_url, melted) in results {
-            println!(
-                "  {} - Paid: {}, Fee: {}",
-                mint_url, melted.amount, melted.fee_paid
-            );
-            total_paid += melted.amount;
-            total_fees += melted.fee_paid;
-
-            if let Some(preimage) = melted.preimage {
-                println!("    Preimage: {}", preimage);
-            }
-        }
+        output.info("\nExecuting MPP payment...");
+        let results = multi_mint_wallet
+            .mpp_melt(quotes_to_execute.clone())
+            .await?;
 
-        println!("\nTotal paid: {} {}", total_paid, multi_mint_wallet.unit());
-        println!("Total fees: {} {}", total_fees, multi_mint_wallet.unit());
+        results
+            .into_iter()
+            .map(|(mint_url, melted)| {
+                let quote_id = quotes_to_execute
+                    .iter()
+                    .find(|(url, _)| *url == mint_url)
+                    .map(|(_, quote_id)| quote_id.clone());
+
+                MeltPayment::new(Some(mint_url), quote_id, melted)
+            })
+            .collect()
     } else {
         let available_funds = <cdk::Amount as Into<u64>>::into(total_balance) * MSAT_IN_SAT;
 
@@ -225,30 +265,31 @@ pub async fn pay(
                     create_melt_options(available_funds, bolt11.amount_milli_satoshis(), &prompt)?;
 
                 // Use selected mint or auto-select
-                let melted = if let Some(mint_url) = selected_mint {
+                let payment = if let Some(mint_url) = selected_mint {
                     // User selected a specific mint - use the new mint-specific functions
                     let quote = multi_mint_wallet
                         .melt_quote(&mint_url, bolt11_str.clone(), options)
                         .await?;
 
-                    println!("Melt quote created:");
-                    println!("  Quote ID: {}", quote.id);
-                    println!("  Amount: {}", quote.amount);
-                    println!("  Fee Reserve: {}", quote.fee_reserve);
+                    output.info("Melt quote created:");
+                    output.info(format!("  Quote ID: {}", quote.id));
+                    output.info(format!("  Amount: {}", quote.amount));
+                    output.info(format!("  Fee Reserve: {}", quote.fee_reserve));
 
                     // Execute the melt
-                    multi_mint_wallet
+                    let melted = multi_mint_wallet
                         .melt_with_mint(&mint_url, &quote.id)
-                        .await?
+                        .await?;
+
+                    MeltPayment::new(Some(mint_url), Some(quote.id), melted)
                 } else {
                     // User selected "Any" - let the wallet auto-select the best mint
-                    multi_mint_wallet.melt(&bolt11_str, options, None).await?
+                    let melted = multi_mint_wallet.melt(&bolt11_str, options, None).await?;
+
+                    MeltPayment::new(None, None, melted)
                 };
 
-                println!("Payment successful: {:?}", melted);
-                if let Some(preimage) = melted.preimage {
-                    println!("Payment preimage: {}", preimage);
-                }
+                vec![payment]
             }
             PaymentType::Bolt12 => {
                 // Process BOLT12 payment (offer)
@@ -285,28 +326,25 @@ pub async fn pay(
                 let wallet = multi_mint_wallet
                     .get_wallet(&mint_url)
                     .await
-                    .ok_or_else(|| anyhow::anyhow!("Mint {} not found", mint_url))?;
+                    .ok_or_else(|| cdk::Error::UnknownMint {
+                        mint_url: mint_url.to_string(),
+                    })?;
 
                 // Get melt quote for BOLT12
                 let quote = wallet.melt_bolt12_quote(offer_str, options).await?;
 
                 // Display quote info
-                println!("Melt quote created:");
-                println!("  Quote ID: {}", quote.id);
-                println!("  Amount: {}", quote.amount);
-                println!("  Fee Reserve: {}", quote.fee_reserve);
-                println!("  State: {}", quote.state);
-                println!("  Expiry: {}", quote.expiry);
+                output.info("Melt quote created:");
+                output.info(format!("  Quote ID: {}", quote.id));
+                output.info(format!("  Amount: {}", quote.amount));
+                output.info(format!("  Fee Reserve: {}", quote.fee_reserve));
+                output.info(format!("  State: {}", quote.state));
+                output.info(format!("  Expiry: {}", quote.expiry));
 
                 // Execute the melt
                 let melted = wallet.melt(&quote.id).await?;
-                println!(
-                    "Payment successful: Paid {} with fee {}",
-                    melted.amount, melted.fee_paid
-                );
-                if let Some(preimage) = melted.preimage {
-                    println!("Payment preimage: {}", preimage);
-                }
+
+                vec![MeltPayment::new(Some(mint_url), Some(quote.id), melted)]
             }
             PaymentType::Bip353 => {
                 let bip353_addr = get_user_input("Enter Bip353 address")?;
@@ -335,7 +373,9 @@ pub async fn pay(
                 let wallet = multi_mint_wallet
                     .get_wallet(&mint_url)
                     .await
-                    .ok_or_else(|| anyhow::anyhow!("Mint {} not found", mint_url))?;
+                    .ok_or_else(|| cdk::Error::UnknownMint {
+                        mint_url: mint_url.to_string(),
+                    })?;
 
                 // Get melt quote for BIP353 address (internally resolves and gets BOLT12 quote)
                 let quote = wallet
@@ -346,25 +386,53 @@ pub async fn pay(
                     .await?;
 
                 // Display quote info
-                println!("Melt quote created:");
-                println!("  Quote ID: {}", quote.id);
-                println!("  Amount: {}", quote.amount);
-                println!("  Fee Reserve: {}", quote.fee_reserve);
-                println!("  State: {}", quote.state);
-                println!("  Expiry: {}", quote.expiry);
+                output.info("Melt quote created:");
+                output.info(format!("  Quote ID: {}", quote.id));
+                output.info(format!("  Amount: {}", quote.amount));
+                output.info(format!("  Fee Reserve: {}", quote.fee_reserve));
+                output.info(format!("  State: {}", quote.state));
+                output.info(format!("  Expiry: {}", quote.expiry));
 
                 // Execute the melt
                 let melted = wallet.melt(&quote.id).await?;
-                println!(
+
+                vec![MeltPayment::new(Some(mint_url), Some(quote.id), melted)]
+            }
+        }
+    };
+
+    let total_paid = Amount::try_sum(payments.iter().map(|payment| payment.amount))?;
+    let total_fees = Amount::try_sum(payments.iter().map(|payment| payment.fee_paid))?;
+
+    let melt = MeltOutput {
+        payments,
+        total_paid,
+        total_fees,
+    };
+
+    output.result(&melt, |melt| {
+        let unit = multi_mint_wallet.unit();
+
+        for payment in &melt.payments {
+            match &payment.mint_url {
+                Some(mint_url) => println!(
+                    "Payment successful from {}: Paid {} with fee {}",
+                    mint_url, payment.amount, payment.fee_paid
+                ),
+                None => println!(
                     "Payment successful: Paid {} with fee {}",
-                    melted.amount, melted.fee_paid
-                );
-                if let Some(preimage) = melted.preimage {
-                    println!("Payment preimage: {}", preimage);
-                }
+                    payment.amount, payment.fee_paid
+                ),
+            }
+
+            if let Some(preimage) = &payment.preimage {
+                println!("Payment preimage: {}", preimage);
             }
         }
-    }
 
-    Ok(())
+        if melt.payments.len() > 1 {
+            println!("\nTotal paid: {} {}", melt.total_paid, unit);
+            println!("Total fees: {} {}", melt.total_fees, unit);
+        }
+    })
 }
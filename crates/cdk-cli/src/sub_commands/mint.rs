@@ -10,6 +10,7 @@ use cdk::{Amount, StreamExt};
 use clap::Args;
 use serde::{Deserialize, Serialize};
 
+use crate::output::OutputFormat;
 use crate::utils::get_or_create_wallet;
 
 #[derive(Args, Serialize, Deserialize)]
@@ -38,9 +39,18 @@ pub struct MintSubCommand {
     wait_duration: u64,
 }
 
+#[derive(Serialize)]
+struct MintOutput {
+    mint_url: MintUrl,
+    quote_id: String,
+    request: String,
+    minted: Amount,
+}
+
 pub async fn mint(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &MintSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
     let description: Option<String> = sub_command_args.description.clone();
@@ -57,22 +67,22 @@ pub async fn mint(
                     .ok_or(anyhow!("Amount must be defined"))?;
                 let quote = wallet.mint_quote(Amount::from(amount), description).await?;
 
-                println!("Quote: {quote:#?}");
+                output.info(format!("Quote: {quote:#?}"));
 
-                println!("Please pay: {}", quote.request);
+                output.info(format!("Please pay: {}", quote.request));
 
                 quote
             }
             PaymentMethod::Bolt12 => {
                 let amount = sub_command_args.amount;
-                println!("{:?}", sub_command_args.single_use);
+                output.info(format!("{:?}", sub_command_args.single_use));
                 let quote = wallet
                     .mint_bolt12_quote(amount.map(|a| a.into()), description)
                     .await?;
 
-                println!("Quote: {quote:#?}");
+                output.info(format!("Quote: {quote:#?}"));
 
-                println!("Please pay: {}", quote.request);
+                output.info(format!("Please pay: {}", quote.request));
 
                 quote
             }
@@ -90,6 +100,8 @@ pub async fn mint(
     tracing::debug!("Attempting mint for: {}", payment_method);
 
     let mut amount_minted = Amount::ZERO;
+    let quote_id = quote.id.clone();
+    let request = quote.request.clone();
 
     let mut proof_streams = wallet.proof_stream(quote, SplitTarget::default(), None);
 
@@ -104,7 +116,14 @@ pub async fn mint(
         amount_minted += proofs.total_amount()?;
     }
 
-    println!("Received {amount_minted} from mint {mint_url}");
+    let minted = MintOutput {
+        mint_url,
+        quote_id,
+        request,
+        minted: amount_minted,
+    };
 
-    Ok(())
+    output.result(&minted, |minted| {
+        println!("Received {} from mint {}", minted.minted, minted.mint_url)
+    })
 }
@@ -8,6 +8,7 @@ use cdk::{Amount, OidcClient};
 use clap::Args;
 use serde::{Deserialize, Serialize};
 
+use crate::output::OutputFormat;
 use crate::token_storage;
 
 #[derive(Args, Serialize, Deserialize)]
@@ -21,10 +22,17 @@ pub struct MintBlindAuthSubCommand {
     cat: Option<String>,
 }
 
+#[derive(Serialize)]
+struct MintBlindAuthOutput {
+    mint_url: MintUrl,
+    proofs: usize,
+}
+
 pub async fn mint_blind_auth(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &MintBlindAuthSubCommand,
     work_dir: &Path,
+    output: OutputFormat,
 ) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
 
@@ -42,7 +50,7 @@ pub async fn mint_blind_auth(
             // Try to load from file
             match token_storage::get_token_for_mint(work_dir, &mint_url).await {
                 Ok(Some(token_data)) => {
-                    println!("Using access token from cashu_tokens.json");
+                    output.info("Using access token from cashu_tokens.json");
                     token_data.access_token
                 }
                 Ok(None) => {
@@ -66,13 +74,13 @@ pub async fn mint_blind_auth(
 
         // Try to refresh the token if we have a refresh token
         if let Ok(Some(token_data)) = token_storage::get_token_for_mint(work_dir, &mint_url).await {
-            println!("Attempting to refresh the access token...");
+            output.info("Attempting to refresh the access token...");
 
             // Get the mint info to access OIDC configuration
             if let Some(mint_info) = multi_mint_wallet.fetch_mint_info(&mint_url).await? {
                 match refresh_access_token(&mint_info, &token_data.refresh_token).await {
                     Ok((new_access_token, new_refresh_token)) => {
-                        println!("Successfully refreshed access token");
+                        output.info("Successfully refreshed access token");
 
                         // Save the new tokens
                         if let Err(e) = token_storage::save_tokens(
@@ -83,7 +91,7 @@ pub async fn mint_blind_auth(
                         )
                         .await
                         {
-                            println!("Warning: Failed to save refreshed tokens: {e}");
+                            output.info(format!("Warning: Failed to save refreshed tokens: {e}"));
                         }
 
                         // Try setting the new access token
@@ -91,9 +99,8 @@ pub async fn mint_blind_auth(
                             multi_mint_wallet.set_cat(&mint_url, new_access_token).await
                         {
                             tracing::error!("Could not set refreshed cat: {}", err);
-                            return Err(anyhow::anyhow!(
-                                "Authentication failed even after token refresh"
-                            ));
+                            return Err(anyhow::Error::new(cdk::Error::ClearAuthFailed)
+                                .context("Authentication failed even after token refresh"));
                         }
 
                         // Set the refresh token
@@ -108,9 +115,8 @@ pub async fn mint_blind_auth(
                 }
             }
         } else {
-            return Err(anyhow::anyhow!(
-                "Authentication failed and no refresh token available"
-            ));
+            return Err(anyhow::Error::new(cdk::Error::ClearAuthFailed)
+                .context("Authentication failed and no refresh token available"));
         }
     } else {
         // If we have a refresh token, set it
@@ -123,7 +129,7 @@ pub async fn mint_blind_auth(
         }
     }
 
-    println!("Attempting to mint blind auth");
+    output.info("Attempting to mint blind auth");
 
     let amount = match sub_command_args.amount {
         Some(amount) => amount,
@@ -142,9 +148,17 @@ pub async fn mint_blind_auth(
         .mint_blind_auth(&mint_url, Amount::from(amount))
         .await?;
 
-    println!("Received {} auth proofs for mint {mint_url}", proofs.len());
+    let minted = MintBlindAuthOutput {
+        mint_url,
+        proofs: proofs.len(),
+    };
 
-    Ok(())
+    output.result(&minted, |minted| {
+        println!(
+            "Received {} auth proofs for mint {}",
+            minted.proofs, minted.mint_url
+        )
+    })
 }
 
 async fn refresh_access_token(
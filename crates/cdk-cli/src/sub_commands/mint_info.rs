@@ -5,12 +5,18 @@ use cdk::HttpClient;
 use clap::Args;
 use url::Url;
 
+use crate::output::OutputFormat;
+
 #[derive(Args)]
 pub struct MintInfoSubcommand {
     mint_url: MintUrl,
 }
 
-pub async fn mint_info(proxy: Option<Url>, sub_command_args: &MintInfoSubcommand) -> Result<()> {
+pub async fn mint_info(
+    proxy: Option<Url>,
+    sub_command_args: &MintInfoSubcommand,
+    output: OutputFormat,
+) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
     let client = match proxy {
         Some(proxy) => HttpClient::with_proxy(mint_url, proxy, None, true)?,
@@ -19,7 +25,5 @@ pub async fn mint_info(proxy: Option<Url>, sub_command_args: &MintInfoSubcommand
 
     let info = client.get_mint_info().await?;
 
-    println!("{info:#?}");
-
-    Ok(())
+    output.result(&info, |info| println!("{info:#?}"))
 }
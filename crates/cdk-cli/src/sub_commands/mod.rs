@@ -1,5 +1,6 @@
 pub mod balance;
 pub mod burn;
+pub mod cat_browser_login;
 pub mod cat_device_login;
 pub mod cat_login;
 pub mod check_pending;
@@ -17,4 +18,5 @@ pub mod receive;
 pub mod restore;
 pub mod send;
 pub mod transfer;
+pub mod tui;
 pub mod update_mint_url;
@@ -1,10 +1,19 @@
 use anyhow::Result;
 use cdk::wallet::MultiMintWallet;
+use cdk::Amount;
+use serde::Serialize;
 
-pub async fn mint_pending(multi_mint_wallet: &MultiMintWallet) -> Result<()> {
-    let amount = multi_mint_wallet.check_all_mint_quotes(None).await?;
+use crate::output::OutputFormat;
 
-    println!("Amount: {amount}");
+#[derive(Serialize)]
+struct MintPendingOutput {
+    minted: Amount,
+}
+
+pub async fn mint_pending(multi_mint_wallet: &MultiMintWallet, output: OutputFormat) -> Result<()> {
+    let minted = multi_mint_wallet.check_all_mint_quotes(None).await?;
 
-    Ok(())
+    output.result(&MintPendingOutput { minted }, |pending| {
+        println!("Amount: {}", pending.minted)
+    })
 }
@@ -13,8 +13,10 @@ use cdk::Amount;
 use clap::Args;
 use nostr_sdk::nips::nip04;
 use nostr_sdk::{Filter, Keys, Kind, Timestamp};
+use serde::Serialize;
 
 use crate::nostr_storage;
+use crate::output::OutputFormat;
 use crate::utils::get_or_create_wallet;
 
 #[derive(Args)]
@@ -44,10 +46,19 @@ pub struct ReceiveSubCommand {
     transfer_to: Option<String>,
 }
 
+#[derive(Serialize)]
+struct ReceiveOutput {
+    received: Amount,
+    /// Tokens fetched from nostr that could not be received
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
 pub async fn receive(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &ReceiveSubCommand,
     work_dir: &Path,
+    output: OutputFormat,
 ) -> Result<()> {
     let mut signing_keys = Vec::new();
 
@@ -68,6 +79,8 @@ pub async fn receive(
         signing_keys.append(&mut s_keys);
     }
 
+    let mut errors = Vec::new();
+
     let amount = match &sub_command_args.token {
         Some(token_str) => {
             receive_token(
@@ -126,7 +139,8 @@ pub async fn receive(
                         total_amount += amount;
                     }
                     Err(err) => {
-                        println!("{err}");
+                        output.info(&err);
+                        errors.push(err.to_string());
                     }
                 }
             }
@@ -135,9 +149,14 @@ pub async fn receive(
         }
     };
 
-    println!("Received: {amount}");
+    let received = ReceiveOutput {
+        received: amount,
+        errors,
+    };
 
-    Ok(())
+    output.result(&received, |received| {
+        println!("Received: {}", received.received)
+    })
 }
 
 async fn receive_token(
@@ -1,7 +1,11 @@
 use anyhow::Result;
 use cdk::mint_url::MintUrl;
 use cdk::wallet::MultiMintWallet;
+use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
 
 #[derive(Args)]
 pub struct RestoreSubCommand {
@@ -9,9 +13,16 @@ pub struct RestoreSubCommand {
     mint_url: MintUrl,
 }
 
+#[derive(Serialize)]
+struct RestoreOutput {
+    mint_url: MintUrl,
+    restored: Amount,
+}
+
 pub async fn restore(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &RestoreSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     let mint_url = sub_command_args.mint_url.clone();
 
@@ -27,9 +38,9 @@ pub async fn restore(
         }
     };
 
-    let amount = wallet.restore().await?;
-
-    println!("Restored {amount}");
+    let restored = wallet.restore().await?;
 
-    Ok(())
+    output.result(&RestoreOutput { mint_url, restored }, |restore| {
+        println!("Restored {}", restore.restored)
+    })
 }
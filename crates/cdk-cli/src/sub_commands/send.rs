@@ -7,11 +7,16 @@ use cdk::wallet::types::SendKind;
 use cdk::wallet::{MultiMintWallet, SendMemo, SendOptions};
 use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
 
+use crate::output::OutputFormat;
 use crate::utils::get_number_input;
 
 #[derive(Args)]
 pub struct SendSubCommand {
+    /// Amount to send (optional - will prompt if not provided)
+    #[arg(short, long)]
+    amount: Option<u64>,
     /// Token Memo
     #[arg(short, long)]
     memo: Option<String>,
@@ -60,14 +65,25 @@ pub struct SendSubCommand {
     excluded_mints: Vec<String>,
 }
 
+#[derive(Serialize)]
+struct SendOutput {
+    mint_url: MintUrl,
+    amount: Amount,
+    fee: Amount,
+    token: String,
+}
+
 pub async fn send(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &SendSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     // Determine which mint to use for sending BEFORE asking for amount
     let selected_mint = if let Some(mint_url) = &sub_command_args.mint_url {
         Some(MintUrl::from_str(mint_url)?)
     } else {
+        output.forbid_prompt("--mint-url is required with --output json")?;
+
         // Display all mints with their balances and let user select
         let balances_map = multi_mint_wallet.get_balances().await?;
         if balances_map.is_empty() {
@@ -76,9 +92,9 @@ pub async fn send(
 
         let balances_vec: Vec<(MintUrl, Amount)> = balances_map.into_iter().collect();
 
-        println!("\nAvailable mints and balances:");
+        eprintln!("\nAvailable mints and balances:");
         for (index, (mint_url, balance)) in balances_vec.iter().enumerate() {
-            println!(
+            eprintln!(
                 "  {}: {} - {} {}",
                 index,
                 mint_url,
@@ -86,7 +102,7 @@ pub async fn send(
                 multi_mint_wallet.unit()
             );
         }
-        println!("  {}: Any mint (auto-select best)", balances_vec.len());
+        eprintln!("  {}: Any mint (auto-select best)", balances_vec.len());
 
         let selection = loop {
             let selection: usize =
@@ -100,25 +116,28 @@ pub async fn send(
                 break Some(mint_url.clone());
             }
 
-            println!("Invalid selection, please try again.");
+            eprintln!("Invalid selection, please try again.");
         };
 
         selection
     };
 
-    let token_amount = Amount::from(get_number_input::<u64>(&format!(
-        "Enter value of token in {}",
-        multi_mint_wallet.unit()
-    ))?);
+    let token_amount = match sub_command_args.amount {
+        Some(amount) => Amount::from(amount),
+        None => Amount::from(get_number_input::<u64>(&format!(
+            "Enter value of token in {}",
+            multi_mint_wallet.unit()
+        ))?),
+    };
 
     // Check total balance across all wallets
     let total_balance = multi_mint_wallet.total_balance().await?;
     if total_balance < token_amount {
-        return Err(anyhow!(
-            "Insufficient funds. Total balance: {}, Required: {}",
-            total_balance,
-            token_amount
-        ));
+        return Err(
+            anyhow::Error::new(cdk::Error::InsufficientFunds).context(format!(
+                "Total balance: {total_balance}, Required: {token_amount}"
+            )),
+        );
     }
 
     let conditions = match (&sub_command_args.preimage, &sub_command_args.hash) {
@@ -262,58 +281,49 @@ pub async fn send(
         .collect();
     let excluded_mints = excluded_mints?;
 
-    // Prepare and confirm the send based on mint selection
-    let token = if let Some(specific_mint) = selected_mint {
-        // User selected a specific mint
-        let multi_mint_options = cdk::wallet::multi_mint_wallet::MultiMintSendOptions {
-            allow_transfer: sub_command_args.allow_transfer,
-            max_transfer_amount: sub_command_args.max_transfer_amount.map(Amount::from),
-            allowed_mints: vec![specific_mint.clone()], // Use selected mint as the only allowed mint
-            excluded_mints,
-            send_options: send_options.clone(),
-        };
+    // Send from the selected mint, or find the first mint with sufficient balance
+    let mint_url = match selected_mint {
+        Some(mint_url) => mint_url,
+        None => {
+            let balances = multi_mint_wallet.get_balances().await?;
+            balances
+                .into_iter()
+                .find(|(_, balance)| *balance >= token_amount)
+                .map(|(mint_url, _)| mint_url)
+                .ok_or_else(|| {
+                    anyhow::Error::new(cdk::Error::InsufficientFunds)
+                        .context("No mint has sufficient balance for the requested amount")
+                })?
+        }
+    };
 
-        let prepared = multi_mint_wallet
-            .prepare_send(specific_mint, token_amount, multi_mint_options)
-            .await?;
+    let multi_mint_options = cdk::wallet::multi_mint_wallet::MultiMintSendOptions {
+        allow_transfer: sub_command_args.allow_transfer,
+        max_transfer_amount: sub_command_args.max_transfer_amount.map(Amount::from),
+        allowed_mints: vec![mint_url.clone()], // Use the selected mint as the only allowed mint
+        excluded_mints,
+        send_options: send_options.clone(),
+    };
 
-        let memo = send_options.memo.clone();
-        prepared.confirm(memo).await?
-    } else {
-        // User selected "Any" - find the first mint with sufficient balance
-        let balances = multi_mint_wallet.get_balances().await?;
-        let best_mint = balances
-            .into_iter()
-            .find(|(_, balance)| *balance >= token_amount)
-            .map(|(mint_url, _)| mint_url)
-            .ok_or_else(|| anyhow!("No mint has sufficient balance for the requested amount"))?;
-
-        let multi_mint_options = cdk::wallet::multi_mint_wallet::MultiMintSendOptions {
-            allow_transfer: sub_command_args.allow_transfer,
-            max_transfer_amount: sub_command_args.max_transfer_amount.map(Amount::from),
-            allowed_mints: vec![best_mint.clone()], // Use the best mint as the only allowed mint
-            excluded_mints,
-            send_options: send_options.clone(),
-        };
+    let prepared = multi_mint_wallet
+        .prepare_send(mint_url.clone(), token_amount, multi_mint_options)
+        .await?;
+    let fee = prepared.fee();
 
-        let prepared = multi_mint_wallet
-            .prepare_send(best_mint, token_amount, multi_mint_options)
-            .await?;
+    let memo = send_options.memo.clone();
+    let token = prepared.confirm(memo).await?;
 
-        let memo = send_options.memo.clone();
-        prepared.confirm(memo).await?
+    let token = match sub_command_args.v3 {
+        true => token.to_v3_string(),
+        false => token.to_string(),
     };
 
-    match sub_command_args.v3 {
-        true => {
-            let token = token;
-
-            println!("{}", token.to_v3_string());
-        }
-        false => {
-            println!("{token}");
-        }
-    }
+    let sent = SendOutput {
+        mint_url,
+        amount: token_amount,
+        fee,
+        token,
+    };
 
-    Ok(())
+    output.result(&sent, |sent| println!("{}", sent.token))
 }
@@ -6,7 +6,9 @@ use cdk::wallet::multi_mint_wallet::TransferMode;
 use cdk::wallet::MultiMintWallet;
 use cdk::Amount;
 use clap::Args;
+use serde::Serialize;
 
+use crate::output::OutputFormat;
 use crate::utils::get_number_input;
 
 #[derive(Args)]
@@ -25,6 +27,17 @@ pub struct TransferSubCommand {
     full_balance: bool,
 }
 
+#[derive(Serialize)]
+struct TransferOutput {
+    source_mint: MintUrl,
+    target_mint: MintUrl,
+    amount_sent: Amount,
+    amount_received: Amount,
+    fees_paid: Amount,
+    source_balance_after: Amount,
+    target_balance_after: Amount,
+}
+
 /// Helper function to select a mint from available mints
 async fn select_mint(
     multi_mint_wallet: &MultiMintWallet,
@@ -43,9 +56,9 @@ async fn select_mint(
         bail!("No available mints found");
     }
 
-    println!("\nAvailable mints:");
+    eprintln!("\nAvailable mints:");
     for (i, (mint_url, balance)) in available_mints.iter().enumerate() {
-        println!(
+        eprintln!(
             "  {}: {} - {} {}",
             i,
             mint_url,
@@ -64,11 +77,12 @@ async fn select_mint(
 pub async fn transfer(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &TransferSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     // Check total balance across all wallets
     let total_balance = multi_mint_wallet.total_balance().await?;
     if total_balance == Amount::ZERO {
-        bail!("No funds available");
+        return Err(anyhow::Error::new(cdk::Error::InsufficientFunds).context("No funds available"));
     }
 
     // Get source mint URL either from args or by prompting user
@@ -76,10 +90,10 @@ pub async fn transfer(
         let url = MintUrl::from_str(source_mint)?;
         // Verify the mint is in the wallet
         if !multi_mint_wallet.has_mint(&url).await {
-            bail!(
-                "Source mint {} is not in the wallet. Please add it first.",
-                url
-            );
+            return Err(anyhow::Error::new(cdk::Error::UnknownMint {
+                mint_url: url.to_string(),
+            })
+            .context("Source mint is not in the wallet. Please add it first."));
         }
         url
     } else {
@@ -97,10 +111,10 @@ pub async fn transfer(
         let url = MintUrl::from_str(target_mint)?;
         // Verify the mint is in the wallet
         if !multi_mint_wallet.has_mint(&url).await {
-            bail!(
-                "Target mint {} is not in the wallet. Please add it first.",
-                url
-            );
+            return Err(anyhow::Error::new(cdk::Error::UnknownMint {
+                mint_url: url.to_string(),
+            })
+            .context("Target mint is not in the wallet. Please add it first."));
         }
         url
     } else {
@@ -126,18 +140,19 @@ pub async fn transfer(
         .unwrap_or(Amount::ZERO);
 
     if source_balance == Amount::ZERO {
-        bail!("Source mint has no balance to transfer");
+        return Err(anyhow::Error::new(cdk::Error::InsufficientFunds)
+            .context("Source mint has no balance to transfer"));
     }
 
     // Determine transfer mode based on user input
     let transfer_mode = if sub_command_args.full_balance {
-        println!(
+        output.info(format!(
             "\nTransferring full balance ({} {}) from {} to {}...",
             source_balance,
             multi_mint_wallet.unit(),
             source_mint_url,
             target_mint_url
-        );
+        ));
         TransferMode::FullBalance
     } else {
         let amount = match sub_command_args.amount {
@@ -149,22 +164,24 @@ pub async fn transfer(
         };
 
         if source_balance < amount {
-            bail!(
-                "Insufficient funds in source mint. Available: {} {}, Required: {} {}",
-                source_balance,
-                multi_mint_wallet.unit(),
-                amount,
-                multi_mint_wallet.unit()
+            return Err(
+                anyhow::Error::new(cdk::Error::InsufficientFunds).context(format!(
+                    "Insufficient funds in source mint. Available: {} {}, Required: {} {}",
+                    source_balance,
+                    multi_mint_wallet.unit(),
+                    amount,
+                    multi_mint_wallet.unit()
+                )),
             );
         }
 
-        println!(
+        output.info(format!(
             "\nTransferring {} {} from {} to {}...",
             amount,
             multi_mint_wallet.unit(),
             source_mint_url,
             target_mint_url
-        );
+        ));
         TransferMode::ExactReceive(amount)
     };
 
@@ -173,37 +190,33 @@ pub async fn transfer(
         .transfer(&source_mint_url, &target_mint_url, transfer_mode)
         .await?;
 
-    println!("\nTransfer completed successfully!");
-    println!(
-        "Amount sent: {} {}",
-        transfer_result.amount_sent,
-        multi_mint_wallet.unit()
-    );
-    println!(
-        "Amount received: {} {}",
-        transfer_result.amount_received,
-        multi_mint_wallet.unit()
-    );
-    if transfer_result.fees_paid > Amount::ZERO {
+    let transfer = TransferOutput {
+        source_mint: source_mint_url,
+        target_mint: target_mint_url,
+        amount_sent: transfer_result.amount_sent,
+        amount_received: transfer_result.amount_received,
+        fees_paid: transfer_result.fees_paid,
+        source_balance_after: transfer_result.source_balance_after,
+        target_balance_after: transfer_result.target_balance_after,
+    };
+
+    output.result(&transfer, |transfer| {
+        let unit = multi_mint_wallet.unit();
+
+        println!("\nTransfer completed successfully!");
+        println!("Amount sent: {} {}", transfer.amount_sent, unit);
+        println!("Amount received: {} {}", transfer.amount_received, unit);
+        if transfer.fees_paid > Amount::ZERO {
+            println!("Fees paid: {} {}", transfer.fees_paid, unit);
+        }
+        println!("\nUpdated balances:");
         println!(
-            "Fees paid: {} {}",
-            transfer_result.fees_paid,
-            multi_mint_wallet.unit()
+            "  Source mint ({}): {} {}",
+            transfer.source_mint, transfer.source_balance_after, unit
         );
-    }
-    println!("\nUpdated balances:");
-    println!(
-        "  Source mint ({}): {} {}",
-        source_mint_url,
-        transfer_result.source_balance_after,
-        multi_mint_wallet.unit()
-    );
-    println!(
-        "  Target mint ({}): {} {}",
-        target_mint_url,
-        transfer_result.target_balance_after,
-        multi_mint_wallet.unit()
-    );
-
-    Ok(())
+        println!(
+            "  Target mint ({}): {} {}",
+            transfer.target_mint, transfer.target_balance_after, unit
+        );
+    })
 }
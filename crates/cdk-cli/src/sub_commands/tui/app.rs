@@ -0,0 +1,604 @@
+//! TUI state and event handling
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::str::FromStr;
+
+use anyhow::Result;
+use cdk::amount::SplitTarget;
+use cdk::mint_url::MintUrl;
+use cdk::nuts::nut00::ProofsMethods;
+use cdk::nuts::MeltQuoteState;
+use cdk::wallet::types::Transaction;
+use cdk::wallet::{
+    MeltQuote, MintQuote, MultiMintReceiveOptions, MultiMintSendOptions, MultiMintWallet,
+};
+use cdk::{Amount, StreamExt};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::{ListState, TableState};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::qr;
+
+/// Events driving the TUI
+pub enum Event {
+    /// Key pressed
+    Key(KeyEvent),
+    /// Text pasted into the terminal
+    Paste(String),
+    /// Terminal resized
+    Resize,
+    /// Wallet state should be reloaded
+    Tick,
+    /// Wallet state reloaded
+    Loaded(Box<Snapshot>),
+    /// Background action finished
+    Status(Status),
+    /// Data to show as a QR code
+    Qr {
+        /// Popup title
+        title: String,
+        /// Encoded data
+        data: String,
+    },
+    /// Melt quote to confirm before paying it
+    ConfirmMelt {
+        /// Mint of the quote
+        mint_url: MintUrl,
+        /// Quote to pay
+        quote: MeltQuote,
+    },
+    /// A quote is no longer watched
+    Unwatched(String),
+}
+
+/// Message shown in the status line
+pub enum Status {
+    /// Progress or result of an action
+    Info(String),
+    /// Failed action
+    Error(String),
+}
+
+/// Wallet state shown in the panes
+#[derive(Default)]
+pub struct Snapshot {
+    /// Balance of every mint
+    pub balances: Vec<(MintUrl, Amount)>,
+    /// Total balance
+    pub total: Amount,
+    /// Transactions, newest first
+    pub transactions: Vec<Transaction>,
+    /// Mint quotes that are not issued nor expired
+    pub mint_quotes: Vec<MintQuote>,
+    /// Melt quotes waiting on the payment
+    pub melt_quotes: Vec<MeltQuote>,
+}
+
+/// Pane receiving the navigation keys
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    /// Mints and their balances
+    Mints,
+    /// Pending mint quotes
+    MintQuotes,
+    /// Transaction history
+    Transactions,
+}
+
+/// Action asking for a line of input
+pub enum PromptAction {
+    /// Add a mint by url
+    AddMint,
+    /// Request a mint quote for an amount
+    Mint(MintUrl),
+    /// Pay a bolt11 invoice
+    Melt(MintUrl),
+    /// Send an amount as a token
+    Send(MintUrl),
+    /// Receive a token
+    Receive,
+}
+
+impl PromptAction {
+    /// Popup title
+    pub fn title(&self) -> String {
+        match self {
+            Self::AddMint => "Mint url".to_string(),
+            Self::Mint(mint_url) => format!("Amount to mint from {mint_url}"),
+            Self::Melt(mint_url) => format!("Invoice to pay from {mint_url}"),
+            Self::Send(mint_url) => format!("Amount to send from {mint_url}"),
+            Self::Receive => "Token to receive".to_string(),
+        }
+    }
+}
+
+/// Popup drawn over the panes
+pub enum Popup {
+    /// Line of input for an action
+    Prompt {
+        /// Action run on enter
+        action: PromptAction,
+        /// Text typed so far
+        input: String,
+    },
+    /// QR code with its data
+    Qr {
+        /// Popup title
+        title: String,
+        /// Encoded data
+        data: String,
+        /// Rendered code, `None` if the data does not fit in one
+        code: Option<String>,
+    },
+    /// Melt quote paid on enter or `y`
+    ConfirmMelt {
+        /// Mint of the quote
+        mint_url: MintUrl,
+        /// Quote to pay
+        quote: MeltQuote,
+    },
+}
+
+/// TUI state
+pub struct App {
+    wallet: MultiMintWallet,
+    events: UnboundedSender<Event>,
+    /// Latest wallet state
+    pub snapshot: Snapshot,
+    /// Pane receiving the navigation keys
+    pub focus: Pane,
+    /// Selected mint
+    pub mints: ListState,
+    /// Selected mint quote
+    pub mint_quotes: TableState,
+    /// Selected transaction
+    pub transactions: TableState,
+    /// Open popup
+    pub popup: Option<Popup>,
+    /// Status line
+    pub status: Option<Status>,
+    /// Quotes with a running proof or payment stream
+    watched: HashSet<String>,
+    loading: bool,
+    quit: bool,
+}
+
+impl App {
+    /// Create a new [`App`]
+    pub fn new(wallet: MultiMintWallet, events: UnboundedSender<Event>) -> Self {
+        Self {
+            wallet,
+            events,
+            snapshot: Snapshot::default(),
+            focus: Pane::Mints,
+            mints: ListState::default(),
+            mint_quotes: TableState::default(),
+            transactions: TableState::default(),
+            popup: None,
+            status: None,
+            watched: HashSet::new(),
+            loading: false,
+            quit: false,
+        }
+    }
+
+    /// Wallet currency unit
+    pub fn unit(&self) -> String {
+        self.wallet.unit().to_string()
+    }
+
+    /// Whether the user asked to quit
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Update the state for an event
+    pub fn handle(&mut self, event: Event) {
+        match event {
+            Event::Key(key) => self.on_key(key),
+            Event::Paste(text) => {
+                if let Some(Popup::Prompt { input, .. }) = &mut self.popup {
+                    input.push_str(text.trim());
+                }
+            }
+            Event::Resize => {}
+            Event::Tick => self.reload(),
+            Event::Loaded(snapshot) => self.on_loaded(*snapshot),
+            Event::Status(status) => self.status = Some(status),
+            Event::Qr { title, data } => self.show_qr(title, data),
+            Event::ConfirmMelt { mint_url, quote } => {
+                self.popup = Some(Popup::ConfirmMelt { mint_url, quote });
+            }
+            Event::Unwatched(quote_id) => {
+                self.watched.remove(&quote_id);
+            }
+        }
+    }
+
+    fn selected_mint(&self) -> Option<MintUrl> {
+        self.mints
+            .selected()
+            .and_then(|index| self.snapshot.balances.get(index))
+            .map(|(mint_url, _)| mint_url.clone())
+    }
+
+    fn show_qr(&mut self, title: String, data: String) {
+        let code = qr::render(&data).ok();
+        self.popup = Some(Popup::Qr { title, data, code });
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match self.popup.take() {
+            Some(Popup::Prompt { action, mut input }) => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Enter => self.submit(action, input.trim().to_string()),
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.popup = Some(Popup::Prompt { action, input });
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    self.popup = Some(Popup::Prompt { action, input });
+                }
+                _ => self.popup = Some(Popup::Prompt { action, input }),
+            },
+            // Any key closes the QR code
+            Some(Popup::Qr { .. }) => {}
+            // Any other key cancels the payment
+            Some(Popup::ConfirmMelt { mint_url, quote }) => {
+                if matches!(key.code, KeyCode::Enter | KeyCode::Char('y')) {
+                    self.pay(mint_url, quote);
+                }
+            }
+            None => self.on_pane_key(key),
+        }
+    }
+
+    fn on_pane_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Pane::Mints => Pane::MintQuotes,
+                    Pane::MintQuotes => Pane::Transactions,
+                    Pane::Transactions => Pane::Mints,
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => match self.focus {
+                Pane::Mints => self.mints.select_next(),
+                Pane::MintQuotes => self.mint_quotes.select_next(),
+                Pane::Transactions => self.transactions.select_next(),
+            },
+            KeyCode::Up | KeyCode::Char('k') => match self.focus {
+                Pane::Mints => self.mints.select_previous(),
+                Pane::MintQuotes => self.mint_quotes.select_previous(),
+                Pane::Transactions => self.transactions.select_previous(),
+            },
+            KeyCode::Enter if self.focus == Pane::MintQuotes => {
+                let quote = self
+                    .mint_quotes
+                    .selected()
+                    .and_then(|index| self.snapshot.mint_quotes.get(index));
+
+                if let Some(quote) = quote {
+                    let title = format!("Mint quote {}", quote.id);
+                    let request = quote.request.clone();
+                    self.show_qr(title, request);
+                }
+            }
+            KeyCode::Char('a') => self.prompt(PromptAction::AddMint),
+            KeyCode::Char('r') => self.prompt(PromptAction::Receive),
+            KeyCode::Char('m') | KeyCode::Char('p') | KeyCode::Char('s') => {
+                let Some(mint_url) = self.selected_mint() else {
+                    self.status = Some(Status::Error("Add a mint first".to_string()));
+                    return;
+                };
+
+                self.prompt(match key.code {
+                    KeyCode::Char('m') => PromptAction::Mint(mint_url),
+                    KeyCode::Char('p') => PromptAction::Melt(mint_url),
+                    _ => PromptAction::Send(mint_url),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn prompt(&mut self, action: PromptAction) {
+        self.popup = Some(Popup::Prompt {
+            action,
+            input: String::new(),
+        });
+    }
+
+    fn submit(&mut self, action: PromptAction, input: String) {
+        if input.is_empty() {
+            return;
+        }
+
+        let wallet = self.wallet.clone();
+        let events = self.events.clone();
+        let unit = self.unit();
+
+        match action {
+            PromptAction::AddMint => {
+                let mint_url = match MintUrl::from_str(&input) {
+                    Ok(mint_url) => mint_url,
+                    Err(err) => return self.fail(err),
+                };
+
+                self.spawn(format!("Adding {mint_url}"), async move {
+                    wallet.add_mint(mint_url.clone()).await?;
+
+                    Ok(Status::Info(format!("Added {mint_url}")))
+                });
+            }
+            PromptAction::Mint(mint_url) => {
+                let amount = match input.parse::<u64>() {
+                    Ok(amount) => Amount::from(amount),
+                    Err(err) => return self.fail(err),
+                };
+
+                self.spawn(
+                    format!("Requesting a quote for {amount} {unit}"),
+                    async move {
+                        let quote = wallet.mint_quote(&mint_url, amount, None).await?;
+
+                        let _ = events.send(Event::Qr {
+                            title: format!("Pay to mint {amount} {unit}"),
+                            data: quote.request,
+                        });
+
+                        Ok(Status::Info(format!("Waiting for payment of {}", quote.id)))
+                    },
+                );
+            }
+            PromptAction::Melt(mint_url) => {
+                self.spawn("Requesting a melt quote".to_string(), async move {
+                    let quote = wallet.melt_quote(&mint_url, input, None).await?;
+                    let quote_id = quote.id.clone();
+
+                    let _ = events.send(Event::ConfirmMelt { mint_url, quote });
+
+                    Ok(Status::Info(format!("Confirm the payment of {quote_id}")))
+                });
+            }
+            PromptAction::Send(mint_url) => {
+                let amount = match input.parse::<u64>() {
+                    Ok(amount) => Amount::from(amount),
+                    Err(err) => return self.fail(err),
+                };
+
+                self.spawn(format!("Sending {amount} {unit}"), async move {
+                    let prepared = wallet
+                        .prepare_send(mint_url, amount, MultiMintSendOptions::default())
+                        .await?;
+                    let fee = prepared.fee();
+                    let token = prepared.confirm(None).await?;
+
+                    let _ = events.send(Event::Qr {
+                        title: format!("Token for {amount} {unit}"),
+                        data: token.to_string(),
+                    });
+
+                    Ok(Status::Info(format!(
+                        "Sent {amount} {unit} with {fee} {unit} fee"
+                    )))
+                });
+            }
+            PromptAction::Receive => {
+                self.spawn("Receiving token".to_string(), async move {
+                    let amount = wallet
+                        .receive(&input, MultiMintReceiveOptions::default())
+                        .await?;
+
+                    Ok(Status::Info(format!("Received {amount} {unit}")))
+                });
+            }
+        }
+    }
+
+    /// Pays a confirmed melt quote, pending payments are watched once the state is reloaded
+    fn pay(&mut self, mint_url: MintUrl, quote: MeltQuote) {
+        let wallet = self.wallet.clone();
+        let unit = self.unit();
+
+        self.spawn(format!("Paying {}", quote.id), async move {
+            let melted = wallet.melt_with_mint(&mint_url, &quote.id).await?;
+
+            Ok(match melted.state {
+                MeltQuoteState::Paid => Status::Info(format!(
+                    "Paid {} {unit} with {} {unit} fee",
+                    melted.amount, melted.fee_paid
+                )),
+                MeltQuoteState::Pending => {
+                    Status::Info(format!("Payment of {} is pending", quote.id))
+                }
+                state => Status::Error(format!("Payment {}: {state}", quote.id)),
+            })
+        });
+    }
+
+    fn fail(&mut self, err: impl std::fmt::Display) {
+        self.status = Some(Status::Error(err.to_string()));
+    }
+
+    /// Runs an action in the background, reloading the wallet state once it is done
+    fn spawn<F>(&mut self, progress: String, action: F)
+    where
+        F: Future<Output = Result<Status>> + Send + 'static,
+    {
+        self.status = Some(Status::Info(progress));
+
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            let status = action
+                .await
+                .unwrap_or_else(|err| Status::Error(err.to_string()));
+
+            let _ = events.send(Event::Status(status));
+            let _ = events.send(Event::Tick);
+        });
+    }
+
+    fn reload(&mut self) {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+
+        let wallet = self.wallet.clone();
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            let event = match load(&wallet).await {
+                Ok(snapshot) => Event::Loaded(Box::new(snapshot)),
+                Err(err) => Event::Status(Status::Error(err.to_string())),
+            };
+
+            let _ = events.send(event);
+        });
+    }
+
+    fn on_loaded(&mut self, snapshot: Snapshot) {
+        self.loading = false;
+
+        for quote in &snapshot.mint_quotes {
+            if self.watched.insert(quote.id.clone()) {
+                watch_mint_quote(self.wallet.clone(), quote.clone(), self.events.clone());
+            }
+        }
+
+        for quote in &snapshot.melt_quotes {
+            if self.watched.insert(quote.id.clone()) {
+                watch_melt_quote(self.wallet.clone(), quote.clone(), self.events.clone());
+            }
+        }
+
+        if self.mints.selected().is_none() && !snapshot.balances.is_empty() {
+            self.mints.select(Some(0));
+        }
+
+        self.snapshot = snapshot;
+    }
+}
+
+/// Reads the wallet state shown in the panes
+async fn load(wallet: &MultiMintWallet) -> Result<Snapshot> {
+    let balances: Vec<_> = wallet.get_balances().await?.into_iter().collect();
+    let total = wallet.total_balance().await?;
+    let transactions = wallet.list_transactions(None).await?;
+
+    let mut mint_quotes = Vec::new();
+    let mut melt_quotes = Vec::new();
+    let mut melt_quote_ids = HashSet::new();
+
+    for mint_wallet in wallet.get_wallets().await {
+        mint_quotes.extend(
+            mint_wallet
+                .get_active_mint_quotes()
+                .await?
+                .into_iter()
+                .filter(|quote| &quote.unit == wallet.unit()),
+        );
+
+        // Melt quotes do not record their mint, every wallet of the database returns them all
+        for quote in mint_wallet.get_pending_melt_quotes().await? {
+            if &quote.unit == wallet.unit() && melt_quote_ids.insert(quote.id.clone()) {
+                melt_quotes.push(quote);
+            }
+        }
+    }
+
+    Ok(Snapshot {
+        balances,
+        total,
+        transactions,
+        mint_quotes,
+        melt_quotes,
+    })
+}
+
+/// Mints the proofs of a quote as soon as it is paid
+fn watch_mint_quote(wallet: MultiMintWallet, quote: MintQuote, events: UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let quote_id = quote.id.clone();
+        let mint_url = quote.mint_url.clone();
+
+        if let Some(mint_wallet) = wallet.get_wallet(&mint_url).await {
+            let mut proofs = mint_wallet.proof_stream(quote, SplitTarget::default(), None);
+
+            while let Some(result) = proofs.next().await {
+                let amount = match result.map(|proofs| proofs.total_amount()) {
+                    Ok(Ok(amount)) => amount,
+                    Ok(Err(err)) => {
+                        tracing::warn!("Could not sum proofs of quote {}: {}", quote_id, err);
+                        continue;
+                    }
+                    Err(err) => {
+                        tracing::warn!("Stopped watching mint quote {}: {}", quote_id, err);
+                        break;
+                    }
+                };
+
+                let _ = events.send(Event::Status(Status::Info(format!(
+                    "Minted {amount} from {mint_url}"
+                ))));
+                let _ = events.send(Event::Tick);
+            }
+        }
+
+        let _ = events.send(Event::Unwatched(quote_id));
+    });
+}
+
+/// Settles a pending melt once the mint reports a final state
+fn watch_melt_quote(wallet: MultiMintWallet, quote: MeltQuote, events: UnboundedSender<Event>) {
+    tokio::spawn(async move {
+        let quote_id = quote.id.clone();
+
+        // Melt quotes do not record their mint, only the mint of the quote knows its state
+        let mut state = None;
+        for mint_wallet in wallet.get_wallets().await {
+            if let Ok(response) = mint_wallet.melt_quote_status(&quote_id).await {
+                state = Some((mint_wallet, response.state));
+                break;
+            }
+        }
+
+        let status = match state {
+            Some((mint_wallet, MeltQuoteState::Pending)) => {
+                let mut payments = mint_wallet.payment_stream(&quote);
+
+                match payments.next().await {
+                    Some(Ok(_)) => match mint_wallet.melt_quote_status(&quote_id).await {
+                        Ok(response) => Some(Status::Info(format!(
+                            "Payment of {}: {}",
+                            quote_id, response.state
+                        ))),
+                        Err(err) => Some(Status::Error(err.to_string())),
+                    },
+                    Some(Err(err)) => Some(Status::Error(err.to_string())),
+                    None => None,
+                }
+            }
+            Some((_, state)) => Some(Status::Info(format!("Payment of {quote_id}: {state}"))),
+            None => {
+                // Stays watched, so it is not asked to every mint on each reload
+                tracing::warn!("No mint knows the melt quote {}", quote_id);
+                return;
+            }
+        };
+
+        if let Some(status) = status {
+            let _ = events.send(Event::Status(status));
+            let _ = events.send(Event::Tick);
+        }
+        let _ = events.send(Event::Unwatched(quote_id));
+    });
+}
@@ -0,0 +1,99 @@
+//! Interactive terminal wallet
+//!
+//! Shows the balance of every mint, the transaction history and the pending quotes, and runs
+//! mint, melt, send and receive from key bindings. Melts show the amount and fee reserve of their
+//! quote before paying it. Pending mint quotes are minted as soon as they are paid, and pending
+//! melts are followed until the mint settles them.
+
+use std::io::stdout;
+use std::time::Duration;
+
+use anyhow::Result;
+use cdk::wallet::MultiMintWallet;
+use clap::Args;
+use crossterm::event::{self, DisableBracketedPaste, EnableBracketedPaste, KeyEventKind};
+use crossterm::execute;
+use ratatui::DefaultTerminal;
+use tokio::sync::mpsc;
+
+mod app;
+mod qr;
+mod ui;
+
+use app::{App, Event};
+
+#[derive(Args)]
+pub struct TuiSubCommand {
+    /// Seconds between reloads of balances, transactions and quotes
+    #[arg(long, default_value = "10")]
+    refresh_interval: u64,
+}
+
+pub async fn tui(
+    multi_mint_wallet: &MultiMintWallet,
+    sub_command_args: &TuiSubCommand,
+) -> Result<()> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut app = App::new(multi_mint_wallet.clone(), sender.clone());
+
+    // Reading terminal events blocks, so it gets its own thread
+    let terminal_events = sender.clone();
+    std::thread::spawn(move || loop {
+        let event = match event::read() {
+            Ok(event::Event::Key(key)) if key.kind == KeyEventKind::Press => Event::Key(key),
+            Ok(event::Event::Paste(text)) => Event::Paste(text),
+            Ok(event::Event::Resize(_, _)) => Event::Resize,
+            Ok(_) => continue,
+            Err(err) => {
+                tracing::error!("Could not read terminal event: {}", err);
+                break;
+            }
+        };
+
+        if terminal_events.send(event).is_err() {
+            break;
+        }
+    });
+
+    let refresh_interval = Duration::from_secs(sub_command_args.refresh_interval.max(1));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(refresh_interval);
+
+        loop {
+            interval.tick().await;
+
+            if sender.send(Event::Tick).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut terminal = ratatui::init();
+
+    let result = match execute!(stdout(), EnableBracketedPaste) {
+        Ok(()) => run(&mut terminal, &mut app, &mut receiver).await,
+        Err(err) => Err(err.into()),
+    };
+
+    let _ = execute!(stdout(), DisableBracketedPaste);
+    ratatui::restore();
+
+    result
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    receiver: &mut mpsc::UnboundedReceiver<Event>,
+) -> Result<()> {
+    while !app.should_quit() {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        match receiver.recv().await {
+            Some(event) => app.handle(event),
+            None => break,
+        }
+    }
+
+    Ok(())
+}
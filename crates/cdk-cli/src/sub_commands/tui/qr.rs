@@ -0,0 +1,19 @@
+//! QR code rendering
+
+use anyhow::Result;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+
+/// Render data as a QR code of unicode half blocks
+///
+/// Colors are inverted so the code scans on dark terminal backgrounds.
+pub fn render(data: &str) -> Result<String> {
+    let code = QrCode::new(data.as_bytes())?;
+
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
@@ -0,0 +1,262 @@
+//! TUI rendering
+
+use cdk::util::unix_time;
+use cdk::wallet::types::TransactionDirection;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use super::app::{App, Pane, Popup, Status};
+
+const KEYS: &str =
+    "a add mint  m mint  p melt  s send  r receive  tab focus  enter show quote  q quit";
+
+/// Draw the whole screen
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [panes, status, keys] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [top, bottom] =
+        Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(panes);
+    let [mints, transactions] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(top);
+    let [mint_quotes, melt_quotes] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+
+    draw_mints(frame, app, mints);
+    draw_transactions(frame, app, transactions);
+    draw_mint_quotes(frame, app, mint_quotes);
+    draw_melt_quotes(frame, app, melt_quotes);
+
+    let status_line = match &app.status {
+        Some(Status::Info(message)) => Line::from(message.as_str()),
+        Some(Status::Error(message)) => Line::from(message.as_str()).red(),
+        None => Line::default(),
+    };
+    frame.render_widget(status_line, status);
+    frame.render_widget(Line::from(KEYS).dark_gray(), keys);
+
+    match &app.popup {
+        Some(Popup::Prompt { action, input }) => {
+            let area = centered(
+                frame.area(),
+                Constraint::Percentage(70),
+                Constraint::Length(5),
+            );
+            let prompt = Paragraph::new(format!("{input}_"))
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(format!(" {} ", action.title())));
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(prompt, area);
+        }
+        Some(Popup::Qr { title, data, code }) => {
+            let area = centered(
+                frame.area(),
+                Constraint::Percentage(90),
+                Constraint::Percentage(90),
+            );
+            let block = Block::bordered().title(format!(" {title} "));
+            let inner = block.inner(area);
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(block, area);
+
+            let code = code
+                .as_deref()
+                .unwrap_or("Too long to show as a QR code")
+                .to_string();
+            let code_height = code.lines().count() as u16;
+            let [code_area, data_area] =
+                Layout::vertical([Constraint::Length(code_height), Constraint::Min(0)])
+                    .areas(inner);
+
+            frame.render_widget(Paragraph::new(code).centered(), code_area);
+            frame.render_widget(
+                Paragraph::new(data.as_str()).wrap(Wrap { trim: false }),
+                data_area,
+            );
+        }
+        Some(Popup::ConfirmMelt { mint_url, quote }) => {
+            let area = centered(
+                frame.area(),
+                Constraint::Percentage(70),
+                Constraint::Length(8),
+            );
+            let unit = app.unit();
+            let lines = vec![
+                Line::from(format!("Amount       {} {unit}", quote.amount)),
+                Line::from(format!("Fee reserve  {} {unit}", quote.fee_reserve)),
+                Line::from(format!("Mint         {mint_url}")),
+                Line::default(),
+                Line::from("enter/y pay  any other key cancel").dark_gray(),
+            ];
+            let confirm = Paragraph::new(lines)
+                .block(Block::bordered().title(format!(" Pay melt quote {} ", quote.id)));
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(confirm, area);
+        }
+        None => {}
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(format!(" {title} "));
+
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn draw_mints(frame: &mut Frame, app: &mut App, area: Rect) {
+    let unit = app.unit();
+    let items: Vec<ListItem> = app
+        .snapshot
+        .balances
+        .iter()
+        .map(|(mint_url, amount)| ListItem::new(format!("{mint_url}  {amount} {unit}")))
+        .collect();
+
+    let title = format!("Mints ({} {unit})", app.snapshot.total);
+    let list = List::new(items)
+        .block(pane(&title, app.focus == Pane::Mints))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut app.mints);
+}
+
+fn draw_transactions(frame: &mut Frame, app: &mut App, area: Rect) {
+    let now = unix_time();
+    let rows: Vec<Row> = app
+        .snapshot
+        .transactions
+        .iter()
+        .map(|transaction| {
+            let amount = match transaction.direction {
+                TransactionDirection::Incoming => {
+                    Span::from(format!("+{}", transaction.amount)).green()
+                }
+                TransactionDirection::Outgoing => {
+                    Span::from(format!("-{}", transaction.amount)).red()
+                }
+            };
+
+            Row::new(vec![
+                Line::from(age(now, transaction.timestamp)),
+                Line::from(amount),
+                Line::from(transaction.fee.to_string()),
+                Line::from(transaction.mint_url.to_string()),
+                Line::from(transaction.memo.clone().unwrap_or_default()),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Length(5),
+            Constraint::Percentage(50),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(["Age", "Amount", "Fee", "Mint", "Memo"]).bold())
+    .block(pane("Transactions", app.focus == Pane::Transactions))
+    .row_highlight_style(Style::new().reversed());
+
+    frame.render_stateful_widget(table, area, &mut app.transactions);
+}
+
+fn draw_mint_quotes(frame: &mut Frame, app: &mut App, area: Rect) {
+    let rows: Vec<Row> = app
+        .snapshot
+        .mint_quotes
+        .iter()
+        .map(|quote| {
+            Row::new(vec![
+                quote.id.clone(),
+                quote
+                    .amount
+                    .map(|amount| amount.to_string())
+                    .unwrap_or_default(),
+                quote.state.to_string(),
+                quote.mint_url.to_string(),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(["Quote", "Amount", "State", "Mint"]).bold())
+    .block(pane("Pending mint quotes", app.focus == Pane::MintQuotes))
+    .row_highlight_style(Style::new().reversed());
+
+    frame.render_stateful_widget(table, area, &mut app.mint_quotes);
+}
+
+fn draw_melt_quotes(frame: &mut Frame, app: &App, area: Rect) {
+    let rows: Vec<Row> = app
+        .snapshot
+        .melt_quotes
+        .iter()
+        .map(|quote| {
+            Row::new(vec![
+                quote.id.clone(),
+                quote.amount.to_string(),
+                quote.fee_reserve.to_string(),
+                quote.request.clone(),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(["Quote", "Amount", "Reserve", "Request"]).bold())
+    .block(pane("Pending melt quotes", false));
+
+    frame.render_widget(table, area);
+}
+
+/// Short age of a timestamp, e.g. `5m`
+fn age(now: u64, timestamp: u64) -> String {
+    let seconds = now.saturating_sub(timestamp);
+
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3_600 => format!("{}m", seconds / 60),
+        3_600..86_400 => format!("{}h", seconds / 3_600),
+        _ => format!("{}d", seconds / 86_400),
+    }
+}
+
+fn centered(area: Rect, width: Constraint, height: Constraint) -> Rect {
+    let [area] = Layout::horizontal([width]).flex(Flex::Center).areas(area);
+    let [area] = Layout::vertical([height]).flex(Flex::Center).areas(area);
+
+    area
+}
@@ -1,7 +1,10 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use cdk::mint_url::MintUrl;
 use cdk::wallet::MultiMintWallet;
 use clap::Args;
+use serde::Serialize;
+
+use crate::output::OutputFormat;
 
 #[derive(Args)]
 pub struct UpdateMintUrlSubCommand {
@@ -11,9 +14,16 @@ pub struct UpdateMintUrlSubCommand {
     new_mint_url: MintUrl,
 }
 
+#[derive(Serialize)]
+struct UpdateMintUrlOutput<'a> {
+    old_mint_url: &'a MintUrl,
+    new_mint_url: &'a MintUrl,
+}
+
 pub async fn update_mint_url(
     multi_mint_wallet: &MultiMintWallet,
     sub_command_args: &UpdateMintUrlSubCommand,
+    output: OutputFormat,
 ) -> Result<()> {
     let UpdateMintUrlSubCommand {
         old_mint_url,
@@ -23,12 +33,22 @@ pub async fn update_mint_url(
     let mut wallet = multi_mint_wallet
         .get_wallet(&sub_command_args.old_mint_url)
         .await
-        .ok_or(anyhow!("Unknown mint url"))?
+        .ok_or(cdk::Error::UnknownMint {
+            mint_url: old_mint_url.to_string(),
+        })?
         .clone();
 
     wallet.update_mint_url(new_mint_url.clone()).await?;
 
-    println!("Mint Url changed from {old_mint_url} to {new_mint_url}");
+    let updated = UpdateMintUrlOutput {
+        old_mint_url,
+        new_mint_url,
+    };
 
-    Ok(())
+    output.result(&updated, |updated| {
+        println!(
+            "Mint Url changed from {} to {}",
+            updated.old_mint_url, updated.new_mint_url
+        )
+    })
 }
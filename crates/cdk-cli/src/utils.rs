@@ -6,10 +6,12 @@ use cdk::mint_url::MintUrl;
 use cdk::wallet::multi_mint_wallet::MultiMintWallet;
 
 /// Helper function to get user input with a prompt
+///
+/// The prompt goes to stderr so stdout only carries the command output.
 pub fn get_user_input(prompt: &str) -> Result<String> {
-    println!("{prompt}");
+    eprintln!("{prompt}");
     let mut user_input = String::new();
-    io::stdout().flush()?;
+    io::stderr().flush()?;
     io::stdin().read_line(&mut user_input)?;
     Ok(user_input.trim().to_string())
 }
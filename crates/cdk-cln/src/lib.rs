@@ -85,6 +85,7 @@ impl MintPayment for Cln {
             mpp: true,
             unit: CurrencyUnit::Msat,
             invoice_description: true,
+            invoice_description_hash: false,
             amountless: true,
             bolt12: true,
         })?)
@@ -312,7 +313,7 @@ impl MintPayment for Cln {
                             if let Some(invoice_amount) =
                                 bolt11_options.bolt11.amount_milli_satoshis()
                             {
-                                if !invoice_amount == u64::from(amount_msat) {
+                                if invoice_amount != u64::from(amount_msat) {
                                     return Err(payment::Error::AmountMismatch);
                                 }
                             }
@@ -536,9 +537,15 @@ impl MintPayment for Cln {
         match options {
             IncomingPaymentOptions::Bolt11(Bolt11IncomingPaymentOptions {
                 description,
+                description_hash,
                 amount,
                 unix_expiry,
             }) => {
+                // CLN can only hash the description it is given, not commit to a raw hash
+                if description_hash.is_some() {
+                    return Err(payment::Error::UnsupportedPaymentOption);
+                }
+
                 let time_now = unix_time();
 
                 let mut cln_client = self.cln_client().await?;
@@ -9,8 +9,8 @@ use cashu::Amount;
 use super::Error;
 use crate::mint::{self, MintKeySetInfo, MintQuote as MintMintQuote, Operation};
 use crate::nuts::{
-    BlindSignature, BlindedMessage, CurrencyUnit, Id, MeltQuoteState, Proof, Proofs, PublicKey,
-    State,
+    BlindSignature, BlindedMessage, CurrencyUnit, Id, MeltQuoteState, MintQuoteState,
+    PaymentMethod, Proof, Proofs, PublicKey, State,
 };
 use crate::payment::PaymentIdentifier;
 
@@ -92,6 +92,41 @@ pub struct MeltRequestInfo {
     pub change_outputs: Vec<BlindedMessage>,
 }
 
+/// Filter for listing mint quotes, unset fields match every quote
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MintQuoteFilter {
+    /// Currency unit of the quote
+    pub unit: Option<CurrencyUnit>,
+    /// State of the quote
+    pub state: Option<MintQuoteState>,
+    /// Payment method of the quote
+    pub payment_method: Option<PaymentMethod>,
+    /// Quotes created at or after this unix time
+    pub created_after: Option<u64>,
+    /// Quotes created before this unix time
+    pub created_before: Option<u64>,
+    /// NUT-20 pubkey the quote is locked to
+    pub pubkey: Option<PublicKey>,
+}
+
+/// Filter for listing melt quotes, unset fields match every quote
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeltQuoteFilter {
+    /// Currency unit of the quote
+    pub unit: Option<CurrencyUnit>,
+    /// State of the quote
+    pub state: Option<MeltQuoteState>,
+}
+
+/// Filter for listing audit log entries, unset fields match every entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLogFilter {
+    /// Action of the entry
+    pub action: Option<String>,
+    /// Only match entries created at or after this unix timestamp
+    pub created_after: Option<u64>,
+}
+
 /// KeysDatabaseWriter
 #[async_trait]
 pub trait KeysDatabaseTransaction<'a, Error>: DbTransactionFinalizer<Err = Error> {
@@ -243,13 +278,36 @@ pub trait QuotesDatabase {
     ) -> Result<Option<MintMintQuote>, Self::Err>;
     /// Get Mint Quotes
     async fn get_mint_quotes(&self) -> Result<Vec<MintMintQuote>, Self::Err>;
+    /// Get a page of the [`MintMintQuote`]s matching `filter`, newest first
+    ///
+    /// Returns the page and the number of matching quotes.
+    async fn list_mint_quotes(
+        &self,
+        filter: &MintQuoteFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<MintMintQuote>, u64), Self::Err>;
     /// Get [`mint::MeltQuote`]
     async fn get_melt_quote(
         &self,
         quote_id: &QuoteId,
     ) -> Result<Option<mint::MeltQuote>, Self::Err>;
+    /// Get [`mint::MeltQuote`] by the lookup id of its outgoing payment
+    async fn get_melt_quote_by_request_lookup_id(
+        &self,
+        request_lookup_id: &PaymentIdentifier,
+    ) -> Result<Option<mint::MeltQuote>, Self::Err>;
     /// Get all [`mint::MeltQuote`]s
     async fn get_melt_quotes(&self) -> Result<Vec<mint::MeltQuote>, Self::Err>;
+    /// Get a page of the [`mint::MeltQuote`]s matching `filter`, oldest first
+    ///
+    /// Returns the page and the number of matching quotes.
+    async fn list_melt_quotes(
+        &self,
+        filter: &MeltQuoteFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::MeltQuote>, u64), Self::Err>;
 }
 
 /// Mint Proof Transaction trait
@@ -401,6 +459,44 @@ pub trait SagaDatabase {
         &self,
         operation_kind: mint::OperationKind,
     ) -> Result<Vec<mint::Saga>, Self::Err>;
+
+    /// Get a page of the incomplete sagas, of every kind when `operation_kind` is `None`,
+    /// oldest first
+    ///
+    /// Returns the page and the number of matching sagas.
+    async fn list_incomplete_sagas(
+        &self,
+        operation_kind: Option<mint::OperationKind>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::Saga>, u64), Self::Err>;
+}
+
+#[async_trait]
+/// Audit Log Transaction trait
+pub trait AuditLogTransaction<'a> {
+    /// Audit Log Database Error
+    type Err: Into<Error> + From<Error>;
+
+    /// Add audit log entry
+    async fn add_audit_log_entry(&mut self, entry: &mint::AuditLogEntry) -> Result<(), Self::Err>;
+}
+
+#[async_trait]
+/// Audit Log Database trait
+pub trait AuditLogDatabase {
+    /// Audit Log Database Error
+    type Err: Into<Error> + From<Error>;
+
+    /// Get a page of the audit log entries matching `filter`, newest first
+    ///
+    /// Returns the page and the number of matching entries.
+    async fn list_audit_log(
+        &self,
+        filter: &AuditLogFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::AuditLogEntry>, u64), Self::Err>;
 }
 
 #[async_trait]
@@ -460,6 +556,7 @@ pub trait Transaction<'a, Error>:
     + ProofsTransaction<'a, Err = Error>
     + KVStoreTransaction<'a, Error>
     + SagaTransaction<'a, Err = Error>
+    + AuditLogTransaction<'a, Err = Error>
 {
 }
 
@@ -505,6 +602,7 @@ pub trait Database<Error>:
     + ProofsDatabase<Err = Error>
     + SignaturesDatabase<Err = Error>
     + SagaDatabase<Err = Error>
+    + AuditLogDatabase<Err = Error>
 {
     /// Begins a transaction
     async fn begin_transaction<'a>(
@@ -3,12 +3,15 @@
 use std::str::FromStr;
 
 use cashu::quote_id::QuoteId;
-use cashu::{Amount, Id, SecretKey};
+use cashu::{Amount, Id, MeltQuoteState, MintQuoteState, SecretKey};
 
 use crate::database::mint::test::unique_string;
-use crate::database::mint::{Database, Error, KeysDatabase};
+use crate::database::mint::{Database, Error, KeysDatabase, MeltQuoteFilter, MintQuoteFilter};
 use crate::database::MintSignaturesDatabase;
-use crate::mint::{MeltPaymentRequest, MeltQuote, MintQuote, Operation};
+use crate::mint::{
+    MeltPaymentRequest, MeltQuote, MeltSagaState, MintQuote, Operation, OperationKind, Saga,
+    SwapSagaState,
+};
 use crate::payment::PaymentIdentifier;
 
 /// Add a mint quote
@@ -600,3 +603,234 @@ where
     assert!(retrieved.is_none());
     tx3.commit().await.unwrap();
 }
+
+/// Find a melt quote by the lookup id of its payment
+pub async fn get_melt_quote_by_request_lookup_id<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    let request_lookup_id = PaymentIdentifier::CustomId(unique_string());
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    let quote = MeltQuote::new(MeltPaymentRequest::Bolt11 { bolt11: "lnbc330n1p5d85skpp5344v3ktclujsjl3h09wgsfm7zytumr7h7zhrl857f5w8nv0a52zqdqqcqzzsxqyz5vqrzjqvueefmrckfdwyyu39m0lf24sqzcr9vcrmxrvgfn6empxz7phrjxvrttncqq0lcqqyqqqqlgqqqqqqgq2qsp5j3rrg8kvpemqxtf86j8tjm90wq77c7ende4e5qmrerq4xsg02vhq9qxpqysgqjltywgyk6uc5qcgwh8xnzmawl2tjlhz8d28tgp3yx8xwtz76x0jqkfh6mmq70hervjxs0keun7ur0spldgll29l0dnz3md50d65sfqqqwrwpsu".parse().unwrap() }, cashu::CurrencyUnit::Sat, 33.into(), Amount::ZERO, 0, Some(request_lookup_id.clone()), None, cashu::PaymentMethod::Bolt11);
+    tx.add_melt_quote(quote.clone()).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let found = db
+        .get_melt_quote_by_request_lookup_id(&request_lookup_id)
+        .await
+        .unwrap()
+        .expect("melt quote");
+    assert_eq!(found.id, quote.id);
+
+    let missing = db
+        .get_melt_quote_by_request_lookup_id(&PaymentIdentifier::CustomId(unique_string()))
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
+/// Mint quotes are filtered and paged by the database, newest first
+pub async fn list_mint_quotes<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    let quote = |unit: cashu::CurrencyUnit, method: cashu::PaymentMethod, created_time: u64| {
+        MintQuote::new(
+            None,
+            "".to_owned(),
+            unit,
+            None,
+            0,
+            PaymentIdentifier::CustomId(unique_string()),
+            None,
+            0.into(),
+            0.into(),
+            method,
+            created_time,
+            vec![],
+            vec![],
+        )
+    };
+
+    let unpaid = quote(cashu::CurrencyUnit::Sat, cashu::PaymentMethod::Bolt11, 100);
+    let pubkey = SecretKey::generate().public_key();
+    let mut paid = quote(cashu::CurrencyUnit::Sat, cashu::PaymentMethod::Bolt11, 200);
+    paid.pubkey = Some(pubkey);
+    let issued = quote(cashu::CurrencyUnit::Usd, cashu::PaymentMethod::Bolt12, 300);
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    for quote in [&unpaid, &paid, &issued] {
+        tx.add_mint_quote(quote.clone()).await.unwrap();
+    }
+    for quote in [&paid, &issued] {
+        tx.increment_mint_quote_amount_paid(&quote.id, 10.into(), unique_string())
+            .await
+            .unwrap();
+    }
+    tx.increment_mint_quote_amount_issued(&issued.id, 10.into())
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let ids = |quotes: Vec<MintQuote>| quotes.into_iter().map(|q| q.id).collect::<Vec<_>>();
+
+    let (quotes, total) = db
+        .list_mint_quotes(&MintQuoteFilter::default(), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(
+        ids(quotes),
+        vec![issued.id.clone(), paid.id.clone(), unpaid.id.clone()]
+    );
+
+    let (quotes, total) = db
+        .list_mint_quotes(&MintQuoteFilter::default(), 1, 1)
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(ids(quotes), vec![paid.id.clone()]);
+
+    for (state, expected) in [
+        (MintQuoteState::Unpaid, &unpaid),
+        (MintQuoteState::Paid, &paid),
+        (MintQuoteState::Issued, &issued),
+    ] {
+        let filter = MintQuoteFilter {
+            state: Some(state),
+            ..Default::default()
+        };
+        let (quotes, total) = db.list_mint_quotes(&filter, 0, 10).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(quotes[0].id, expected.id);
+        assert_eq!(quotes[0].state(), state);
+    }
+
+    let filter = MintQuoteFilter {
+        unit: Some(cashu::CurrencyUnit::Sat),
+        payment_method: Some(cashu::PaymentMethod::Bolt11),
+        created_after: Some(150),
+        ..Default::default()
+    };
+    let (quotes, total) = db.list_mint_quotes(&filter, 0, 10).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(ids(quotes), vec![paid.id.clone()]);
+
+    let filter = MintQuoteFilter {
+        pubkey: Some(pubkey),
+        ..Default::default()
+    };
+    let (quotes, total) = db.list_mint_quotes(&filter, 0, 10).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(ids(quotes), vec![paid.id.clone()]);
+
+    let filter = MintQuoteFilter {
+        created_before: Some(300),
+        ..Default::default()
+    };
+    let (quotes, total) = db.list_mint_quotes(&filter, 0, 10).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(ids(quotes), vec![paid.id, unpaid.id]);
+}
+
+/// Melt quotes are filtered and paged by the database
+pub async fn list_melt_quotes<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    let quote = |unit: cashu::CurrencyUnit| {
+        MeltQuote::new(
+            MeltPaymentRequest::Bolt11 { bolt11: "lnbc330n1p5d85skpp5344v3ktclujsjl3h09wgsfm7zytumr7h7zhrl857f5w8nv0a52zqdqqcqzzsxqyz5vqrzjqvueefmrckfdwyyu39m0lf24sqzcr9vcrmxrvgfn6empxz7phrjxvrttncqq0lcqqyqqqqlgqqqqqqgq2qsp5j3rrg8kvpemqxtf86j8tjm90wq77c7ende4e5qmrerq4xsg02vhq9qxpqysgqjltywgyk6uc5qcgwh8xnzmawl2tjlhz8d28tgp3yx8xwtz76x0jqkfh6mmq70hervjxs0keun7ur0spldgll29l0dnz3md50d65sfqqqwrwpsu".parse().unwrap() },
+            unit,
+            33.into(),
+            Amount::ZERO,
+            0,
+            Some(PaymentIdentifier::CustomId(unique_string())),
+            None,
+            cashu::PaymentMethod::Bolt11,
+        )
+    };
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    for unit in [
+        cashu::CurrencyUnit::Sat,
+        cashu::CurrencyUnit::Sat,
+        cashu::CurrencyUnit::Usd,
+    ] {
+        tx.add_melt_quote(quote(unit)).await.unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let (quotes, total) = db
+        .list_melt_quotes(&MeltQuoteFilter::default(), 0, 2)
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(quotes.len(), 2);
+
+    let (quotes, total) = db
+        .list_melt_quotes(&MeltQuoteFilter::default(), 2, 2)
+        .await
+        .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(quotes.len(), 1);
+
+    let filter = MeltQuoteFilter {
+        unit: Some(cashu::CurrencyUnit::Sat),
+        state: Some(MeltQuoteState::Unpaid),
+    };
+    let (quotes, total) = db.list_melt_quotes(&filter, 0, 10).await.unwrap();
+    assert_eq!(total, 2);
+    assert!(quotes
+        .iter()
+        .all(|q| q.unit == cashu::CurrencyUnit::Sat && q.state == MeltQuoteState::Unpaid));
+
+    let filter = MeltQuoteFilter {
+        state: Some(MeltQuoteState::Pending),
+        ..Default::default()
+    };
+    let (quotes, total) = db.list_melt_quotes(&filter, 0, 10).await.unwrap();
+    assert_eq!(total, 0);
+    assert!(quotes.is_empty());
+}
+
+/// Incomplete sagas are filtered by kind and paged by the database
+pub async fn list_incomplete_sagas<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    let swap = Saga::new_swap(
+        uuid::Uuid::new_v4(),
+        SwapSagaState::SetupComplete,
+        vec![],
+        vec![],
+    );
+    let melt = Saga::new_melt(
+        uuid::Uuid::new_v4(),
+        MeltSagaState::SetupComplete,
+        vec![],
+        vec![],
+        unique_string(),
+    );
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    tx.add_saga(&swap).await.unwrap();
+    tx.add_saga(&melt).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let (sagas, total) = db.list_incomplete_sagas(None, 0, 10).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(sagas.len(), 2);
+
+    let (sagas, total) = db.list_incomplete_sagas(None, 1, 1).await.unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(sagas.len(), 1);
+
+    let (sagas, total) = db
+        .list_incomplete_sagas(Some(OperationKind::Swap), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(sagas, vec![swap]);
+}
@@ -217,6 +217,73 @@ fn base62_encode(mut x: u128) -> String {
     String::from_utf8_lossy(&buf[i..]).into_owned()
 }
 
+/// Audit log entries are persisted and listed newest first, paged and filtered
+pub async fn audit_log<DB>(db: DB)
+where
+    DB: Database<crate::database::Error>,
+{
+    let mut first = crate::mint::AuditLogEntry::new(
+        "update_motd".to_string(),
+        Some("\"old motd\"".to_string()),
+        Some("\"new motd\"".to_string()),
+        Some("sha256:abcd".to_string()),
+    );
+    first.created_at -= 10;
+    let second = crate::mint::AuditLogEntry::new(
+        "rotate_next_keyset".to_string(),
+        None,
+        Some("\"00916bbf7ef91a36\"".to_string()),
+        None,
+    );
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    tx.add_audit_log_entry(&first).await.unwrap();
+    tx.add_audit_log_entry(&second).await.unwrap();
+    tx.commit().await.unwrap();
+
+    // Rolled back entries are not persisted
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    tx.add_audit_log_entry(&crate::mint::AuditLogEntry::new(
+        "update_name".to_string(),
+        None,
+        None,
+        None,
+    ))
+    .await
+    .unwrap();
+    tx.rollback().await.unwrap();
+
+    let (entries, total) = db
+        .list_audit_log(&AuditLogFilter::default(), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(entries, vec![second.clone(), first.clone()]);
+    assert_eq!(total, 2);
+
+    let (entries, total) = db
+        .list_audit_log(&AuditLogFilter::default(), 1, 10)
+        .await
+        .unwrap();
+    assert_eq!(entries, vec![first.clone()]);
+    assert_eq!(total, 2);
+
+    let filter = AuditLogFilter {
+        action: Some("update_motd".to_string()),
+        ..Default::default()
+    };
+    let (entries, total) = db.list_audit_log(&filter, 0, 10).await.unwrap();
+    assert_eq!(entries, vec![first]);
+    assert_eq!(total, 1);
+
+    let filter = AuditLogFilter {
+        created_after: Some(second.created_at),
+        ..Default::default()
+    };
+    let (entries, total) = db.list_audit_log(&filter, 0, 10).await.unwrap();
+    assert_eq!(entries, vec![second]);
+    assert_eq!(total, 1);
+}
+
 /// Unit test that is expected to be passed for a correct database implementation
 #[macro_export]
 macro_rules! mint_db_test {
@@ -241,7 +308,12 @@ macro_rules! mint_db_test {
             add_melt_request_unique_blinded_messages,
             reject_melt_duplicate_blinded_signature,
             reject_duplicate_blinded_message_db_constraint,
-            cleanup_melt_request_after_processing
+            cleanup_melt_request_after_processing,
+            get_melt_quote_by_request_lookup_id,
+            list_mint_quotes,
+            list_melt_quotes,
+            list_incomplete_sagas,
+            audit_log
         );
     };
     ($make_db_fn:ident, $($name:ident),+ $(,)?) => {
@@ -3,7 +3,7 @@
 #[cfg(feature = "mint")]
 pub mod mint;
 #[cfg(feature = "wallet")]
-mod wallet;
+pub mod wallet;
 
 #[cfg(feature = "mint")]
 pub use mint::{
@@ -0,0 +1,577 @@
+//! Macro with default tests
+//!
+//! This set is generic and checks the default and expected behaviour for a wallet database
+//! implementation
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use cashu::nuts::nut10;
+use cashu::secret::Secret;
+use cashu::{Amount, ProofDleq, SecretKey};
+
+use super::*;
+use crate::database::Error;
+use crate::nuts::{MeltQuoteState, MintQuoteState, PaymentMethod, Proof};
+use crate::wallet::{MeltQuote, MintQuote};
+
+fn mint_url(url: &str) -> MintUrl {
+    MintUrl::from_str(url).unwrap()
+}
+
+fn keyset_info(id: &str, unit: CurrencyUnit) -> KeySetInfo {
+    KeySetInfo {
+        id: Id::from_str(id).unwrap(),
+        unit,
+        active: true,
+        input_fee_ppk: 0,
+        final_expiry: None,
+    }
+}
+
+fn mint_quote(id: &str, mint_url: MintUrl, payment_method: PaymentMethod) -> MintQuote {
+    MintQuote {
+        id: id.to_owned(),
+        mint_url,
+        payment_method,
+        amount: Some(Amount::from(100)),
+        unit: CurrencyUnit::Sat,
+        request: "test_request".to_owned(),
+        state: MintQuoteState::Unpaid,
+        expiry: 1_000_000_000,
+        secret_key: Some(SecretKey::generate()),
+        amount_issued: Amount::ZERO,
+        amount_paid: Amount::ZERO,
+    }
+}
+
+fn melt_quote(id: &str) -> MeltQuote {
+    MeltQuote {
+        id: id.to_owned(),
+        unit: CurrencyUnit::Sat,
+        amount: Amount::from(100),
+        request: "test_request".to_owned(),
+        fee_reserve: Amount::from(2),
+        state: MeltQuoteState::Unpaid,
+        expiry: 1_000_000_000,
+        payment_preimage: None,
+        payment_method: PaymentMethod::Bolt11,
+    }
+}
+
+fn proof_info(
+    amount: u64,
+    secret: Secret,
+    mint_url: MintUrl,
+    state: State,
+    unit: CurrencyUnit,
+) -> ProofInfo {
+    let proof = Proof::new(
+        Amount::from(amount),
+        Id::from_str("00916bbf7ef91a36").unwrap(),
+        secret,
+        SecretKey::generate().public_key(),
+    );
+
+    ProofInfo::new(proof, mint_url, state, unit).unwrap()
+}
+
+fn transaction(
+    mint_url: MintUrl,
+    direction: TransactionDirection,
+    unit: CurrencyUnit,
+) -> Transaction {
+    Transaction {
+        mint_url,
+        direction,
+        amount: Amount::from(64),
+        fee: Amount::from(1),
+        unit,
+        ys: vec![SecretKey::generate().public_key()],
+        timestamp: 1_700_000_000,
+        memo: Some("memo".to_owned()),
+        metadata: HashMap::from([("key".to_owned(), "value".to_owned())]),
+        quote_id: Some("quote".to_owned()),
+        payment_request: None,
+        payment_proof: None,
+    }
+}
+
+/// Mints can be added, read, listed and removed
+pub async fn add_and_get_mint<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let first = mint_url("https://mint1.example.com");
+    let second = mint_url("https://mint2.example.com");
+    let mint_info = MintInfo::new().description("test");
+
+    db.add_mint(first.clone(), Some(mint_info.clone()))
+        .await
+        .unwrap();
+    db.add_mint(second.clone(), None).await.unwrap();
+
+    assert_eq!(db.get_mint(first.clone()).await.unwrap(), Some(mint_info));
+
+    let mints = db.get_mints().await.unwrap();
+    assert_eq!(mints.len(), 2);
+    assert!(mints.contains_key(&first));
+    assert!(mints.contains_key(&second));
+
+    db.remove_mint(first.clone()).await.unwrap();
+    let mints = db.get_mints().await.unwrap();
+    assert_eq!(mints.len(), 1);
+    assert!(!mints.contains_key(&first));
+}
+
+/// Updating a mint url moves its mint quotes and proofs
+pub async fn update_mint_url<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let old = mint_url("https://old.example.com");
+    let new = mint_url("https://new.example.com");
+
+    db.add_mint(old.clone(), None).await.unwrap();
+    db.add_mint_quote(mint_quote("quote", old.clone(), PaymentMethod::Bolt11))
+        .await
+        .unwrap();
+    let proof = proof_info(
+        8,
+        Secret::generate(),
+        old.clone(),
+        State::Unspent,
+        CurrencyUnit::Sat,
+    );
+    db.update_proofs(vec![proof.clone()], vec![]).await.unwrap();
+
+    db.update_mint_url(old.clone(), new.clone()).await.unwrap();
+
+    let quote = db.get_mint_quote("quote").await.unwrap().unwrap();
+    assert_eq!(quote.mint_url, new);
+
+    assert!(db
+        .get_proofs(Some(old), None, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+    let proofs = db.get_proofs(Some(new), None, None, None).await.unwrap();
+    assert_eq!(proofs.len(), 1);
+    assert_eq!(proofs[0].y, proof.y);
+}
+
+/// Keysets are stored per mint and updated when added again
+pub async fn add_and_get_keysets<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint_url = mint_url("https://mint.example.com");
+    let sat = keyset_info("00916bbf7ef91a36", CurrencyUnit::Sat);
+    let usd = keyset_info("00deadbeef123456", CurrencyUnit::Usd);
+
+    assert_eq!(db.get_mint_keysets(mint_url.clone()).await.unwrap(), None);
+
+    db.add_mint(mint_url.clone(), None).await.unwrap();
+    db.add_mint_keysets(mint_url.clone(), vec![sat.clone(), usd.clone()])
+        .await
+        .unwrap();
+
+    let keysets = db
+        .get_mint_keysets(mint_url.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(keysets.len(), 2);
+    assert!(keysets.contains(&sat));
+    assert!(keysets.contains(&usd));
+    assert_eq!(
+        db.get_keyset_by_id(&sat.id).await.unwrap(),
+        Some(sat.clone())
+    );
+
+    let inactive = KeySetInfo {
+        active: false,
+        input_fee_ppk: 100,
+        ..sat.clone()
+    };
+    db.add_mint_keysets(mint_url.clone(), vec![inactive.clone()])
+        .await
+        .unwrap();
+    assert_eq!(db.get_keyset_by_id(&sat.id).await.unwrap(), Some(inactive));
+    assert_eq!(
+        db.get_mint_keysets(mint_url).await.unwrap().unwrap().len(),
+        2
+    );
+}
+
+/// Mint quotes round trip with every payment method and can be removed
+pub async fn add_and_get_mint_quotes<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint_url = mint_url("https://mint.example.com");
+    let payment_methods = [
+        PaymentMethod::Bolt11,
+        PaymentMethod::Bolt12,
+        PaymentMethod::Custom("custom".to_owned()),
+    ];
+
+    for (i, payment_method) in payment_methods.iter().enumerate() {
+        let quote = mint_quote(
+            &format!("test_quote_{i}"),
+            mint_url.clone(),
+            payment_method.clone(),
+        );
+        db.add_mint_quote(quote.clone()).await.unwrap();
+        assert_eq!(db.get_mint_quote(&quote.id).await.unwrap(), Some(quote));
+    }
+    assert_eq!(db.get_mint_quotes().await.unwrap().len(), 3);
+
+    let mut paid = mint_quote("test_quote_0", mint_url, PaymentMethod::Bolt11);
+    paid.state = MintQuoteState::Paid;
+    paid.amount_paid = Amount::from(100);
+    db.add_mint_quote(paid.clone()).await.unwrap();
+    assert_eq!(db.get_mint_quote(&paid.id).await.unwrap(), Some(paid));
+
+    db.remove_mint_quote("test_quote_0").await.unwrap();
+    assert_eq!(db.get_mint_quote("test_quote_0").await.unwrap(), None);
+    assert_eq!(db.get_mint_quotes().await.unwrap().len(), 2);
+}
+
+/// Melt quotes round trip and can be removed
+pub async fn add_and_get_melt_quotes<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let quote = melt_quote("melt_quote_1");
+    let mut paid = melt_quote("melt_quote_2");
+    paid.state = MeltQuoteState::Paid;
+    paid.payment_preimage = Some("preimage".to_owned());
+    paid.payment_method = PaymentMethod::Bolt12;
+
+    db.add_melt_quote(quote.clone()).await.unwrap();
+    db.add_melt_quote(paid.clone()).await.unwrap();
+
+    assert_eq!(db.get_melt_quote(&quote.id).await.unwrap(), Some(quote));
+    assert_eq!(db.get_melt_quote(&paid.id).await.unwrap(), Some(paid));
+    assert_eq!(db.get_melt_quotes().await.unwrap().len(), 2);
+
+    db.remove_melt_quote("melt_quote_1").await.unwrap();
+    assert_eq!(db.get_melt_quote("melt_quote_1").await.unwrap(), None);
+    assert_eq!(db.get_melt_quotes().await.unwrap().len(), 1);
+}
+
+/// Keys round trip, are removed and are rejected when they do not match the keyset id
+pub async fn add_and_get_keys<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let keys = Keys::new(
+        (0..8)
+            .map(|i| (Amount::from(1 << i), SecretKey::generate().public_key()))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    let keyset = KeySet {
+        id: Id::v1_from_keys(&keys),
+        unit: CurrencyUnit::Sat,
+        keys: keys.clone(),
+        final_expiry: None,
+    };
+
+    db.add_keys(keyset.clone()).await.unwrap();
+    assert_eq!(db.get_keys(&keyset.id).await.unwrap(), Some(keys.clone()));
+
+    db.remove_keys(&keyset.id).await.unwrap();
+    assert_eq!(db.get_keys(&keyset.id).await.unwrap(), None);
+
+    let wrong_id = KeySet {
+        id: Id::from_str("00916bbf7ef91a36").unwrap(),
+        ..keyset
+    };
+    assert!(db.add_keys(wrong_id.clone()).await.is_err());
+    assert_eq!(db.get_keys(&wrong_id.id).await.unwrap(), None);
+}
+
+/// Proofs are filtered by mint, unit, state and spending conditions
+pub async fn add_and_filter_proofs<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint1 = mint_url("https://mint1.example.com");
+    let mint2 = mint_url("https://mint2.example.com");
+    let conditions = SpendingConditions::new_p2pk(SecretKey::generate().public_key(), None);
+    let locked_secret: Secret = nut10::Secret::from(conditions.clone()).try_into().unwrap();
+
+    let unspent = proof_info(
+        8,
+        Secret::generate(),
+        mint1.clone(),
+        State::Unspent,
+        CurrencyUnit::Sat,
+    );
+    let pending = proof_info(
+        16,
+        Secret::generate(),
+        mint1.clone(),
+        State::Pending,
+        CurrencyUnit::Sat,
+    );
+    let usd = proof_info(
+        32,
+        Secret::generate(),
+        mint2.clone(),
+        State::Unspent,
+        CurrencyUnit::Usd,
+    );
+    let locked = proof_info(
+        64,
+        locked_secret,
+        mint2.clone(),
+        State::Unspent,
+        CurrencyUnit::Sat,
+    );
+
+    db.update_proofs(
+        vec![
+            unspent.clone(),
+            pending.clone(),
+            usd.clone(),
+            locked.clone(),
+        ],
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        db.get_proofs(None, None, None, None).await.unwrap().len(),
+        4
+    );
+    assert_eq!(
+        db.get_proofs(Some(mint1.clone()), None, None, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        db.get_proofs(None, Some(CurrencyUnit::Usd), None, None)
+            .await
+            .unwrap(),
+        vec![usd.clone()]
+    );
+    assert_eq!(
+        db.get_proofs(Some(mint1.clone()), None, Some(vec![State::Pending]), None)
+            .await
+            .unwrap(),
+        vec![pending.clone()]
+    );
+    assert_eq!(
+        db.get_proofs(None, None, None, Some(vec![conditions]))
+            .await
+            .unwrap(),
+        vec![locked.clone()]
+    );
+
+    assert_eq!(db.get_balance(None, None, None).await.unwrap(), 120);
+    assert_eq!(
+        db.get_balance(None, Some(CurrencyUnit::Sat), Some(vec![State::Unspent]))
+            .await
+            .unwrap(),
+        72
+    );
+    assert_eq!(
+        db.get_balance(Some(mint2.clone()), None, None)
+            .await
+            .unwrap(),
+        96
+    );
+
+    db.update_proofs_state(vec![unspent.y, usd.y], State::Reserved)
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_balance(None, None, Some(vec![State::Reserved]))
+            .await
+            .unwrap(),
+        40
+    );
+
+    db.update_proofs(vec![], vec![pending.y, locked.y])
+        .await
+        .unwrap();
+    let proofs = db.get_proofs(None, None, None, None).await.unwrap();
+    assert_eq!(proofs.len(), 2);
+    assert!(proofs.iter().all(|p| p.state == State::Reserved));
+}
+
+/// Proofs keep their DLEQ proof
+pub async fn proof_with_dleq<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint_url = mint_url("https://mint.example.com");
+    let mut proof = proof_info(
+        64,
+        Secret::generate(),
+        mint_url.clone(),
+        State::Unspent,
+        CurrencyUnit::Sat,
+    );
+    let dleq = ProofDleq::new(
+        SecretKey::generate(),
+        SecretKey::generate(),
+        SecretKey::generate(),
+    );
+    proof.proof.dleq = Some(dleq.clone());
+
+    db.update_proofs(vec![proof.clone()], vec![]).await.unwrap();
+
+    let proofs = db
+        .get_proofs(
+            Some(mint_url),
+            Some(CurrencyUnit::Sat),
+            Some(vec![State::Unspent]),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(proofs.len(), 1);
+
+    let stored = proofs[0].proof.dleq.as_ref().unwrap();
+    assert_eq!(stored.e.to_string(), dleq.e.to_string());
+    assert_eq!(stored.s.to_string(), dleq.s.to_string());
+    assert_eq!(stored.r.to_string(), dleq.r.to_string());
+}
+
+/// Keyset counters start at zero and are incremented per keyset
+pub async fn increment_keyset_counter<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint_url = mint_url("https://mint.example.com");
+    let first = keyset_info("00916bbf7ef91a36", CurrencyUnit::Sat);
+    let second = keyset_info("00deadbeef123456", CurrencyUnit::Usd);
+    db.add_mint(mint_url.clone(), None).await.unwrap();
+    db.add_mint_keysets(mint_url, vec![first.clone(), second.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(db.increment_keyset_counter(&first.id, 5).await.unwrap(), 5);
+    assert_eq!(db.increment_keyset_counter(&first.id, 3).await.unwrap(), 8);
+    assert_eq!(db.increment_keyset_counter(&second.id, 1).await.unwrap(), 1);
+    assert_eq!(db.increment_keyset_counter(&first.id, 0).await.unwrap(), 8);
+}
+
+/// Transactions round trip, are filtered and can be removed
+pub async fn add_and_list_transactions<DB>(db: DB)
+where
+    DB: Database<Err = Error>,
+{
+    let mint1 = mint_url("https://mint1.example.com");
+    let mint2 = mint_url("https://mint2.example.com");
+    let incoming = transaction(
+        mint1.clone(),
+        TransactionDirection::Incoming,
+        CurrencyUnit::Sat,
+    );
+    let outgoing = transaction(
+        mint1.clone(),
+        TransactionDirection::Outgoing,
+        CurrencyUnit::Sat,
+    );
+    let usd = transaction(
+        mint2.clone(),
+        TransactionDirection::Incoming,
+        CurrencyUnit::Usd,
+    );
+
+    for tx in [&incoming, &outgoing, &usd] {
+        db.add_transaction(tx.clone()).await.unwrap();
+    }
+
+    assert_eq!(
+        db.get_transaction(incoming.id()).await.unwrap(),
+        Some(incoming.clone())
+    );
+    assert_eq!(
+        db.list_transactions(None, None, None).await.unwrap().len(),
+        3
+    );
+    assert_eq!(
+        db.list_transactions(Some(mint1), None, None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        db.list_transactions(None, Some(TransactionDirection::Outgoing), None)
+            .await
+            .unwrap(),
+        vec![outgoing.clone()]
+    );
+    assert_eq!(
+        db.list_transactions(Some(mint2), None, Some(CurrencyUnit::Usd))
+            .await
+            .unwrap(),
+        vec![usd]
+    );
+
+    db.remove_transaction(outgoing.id()).await.unwrap();
+    assert_eq!(db.get_transaction(outgoing.id()).await.unwrap(), None);
+    assert_eq!(
+        db.list_transactions(None, None, None).await.unwrap().len(),
+        2
+    );
+}
+
+/// Unique id of a test, for databases that are shared between runs
+pub fn test_id(test_name: &str) -> String {
+    let now = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .expect("Time went backwards");
+
+    format!("test_{}_{}", now.as_nanos(), test_name)
+}
+
+/// Unit test that is expected to be passed for a correct wallet database implementation
+///
+/// The tests are `#[tokio::test]`s unless another test attribute is given first, e.g.
+/// `wallet_db_test!(#[wasm_bindgen_test] provide_db)`.
+#[macro_export]
+macro_rules! wallet_db_test {
+    (#[$test:meta] $make_db_fn:ident) => {
+        wallet_db_test!(
+            #[$test]
+            $make_db_fn,
+            add_and_get_mint,
+            update_mint_url,
+            add_and_get_keysets,
+            add_and_get_mint_quotes,
+            add_and_get_melt_quotes,
+            add_and_get_keys,
+            add_and_filter_proofs,
+            proof_with_dleq,
+            increment_keyset_counter,
+            add_and_list_transactions
+        );
+    };
+    (#[$test:meta] $make_db_fn:ident, $($name:ident),+ $(,)?) => {
+        $(
+            #[$test]
+            async fn $name() {
+                cdk_common::database::wallet::test::$name(
+                    $make_db_fn(cdk_common::database::wallet::test::test_id(stringify!($name)))
+                        .await,
+                )
+                .await;
+            }
+        )+
+    };
+    ($make_db_fn:ident) => {
+        wallet_db_test!(#[tokio::test] $make_db_fn);
+    };
+    ($make_db_fn:ident, $($name:ident),+ $(,)?) => {
+        wallet_db_test!(#[tokio::test] $make_db_fn, $($name),+);
+    };
+}
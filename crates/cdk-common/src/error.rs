@@ -92,6 +92,9 @@ pub enum Error {
     /// Multi-Part Payment not supported for unit and method
     #[error("Amountless invoices are not supported for unit `{0}` and method `{1}`")]
     AmountlessInvoiceNotSupported(CurrencyUnit, PaymentMethod),
+    /// Amountless option given for an invoice with a different amount
+    #[error("Amountless amount `{0}` msat does not match invoice amount `{1}` msat")]
+    AmountlessAmountMismatch(Amount, Amount),
     /// Duplicate Payment id
     #[error("Payment id seen for mint")]
     DuplicatePaymentId,
@@ -148,6 +151,27 @@ pub enum Error {
     /// Minting is disabled
     #[error("Minting is disabled")]
     MintingDisabled,
+    /// The signatory refuses to sign past the issuance limit of a keyset
+    #[error("Issuance limit of the keyset reached")]
+    IssuanceLimitReached,
+    /// Client sent too many requests
+    #[error("Rate limit exceeded, retry later")]
+    RateLimited,
+    /// Request has more inputs, outputs or Ys than allowed
+    #[error("Batch of `{0}` exceeds the maximum size of `{1}`")]
+    MaxBatchSizeExceeded(usize, usize),
+    /// Websocket connection has more subscriptions than allowed
+    #[error("Connection exceeds the maximum of `{0}` subscriptions")]
+    MaxSubscriptionsExceeded(usize),
+    /// Websocket connection has more filters than allowed
+    #[error("Connection exceeds the maximum of `{0}` filters")]
+    MaxFiltersExceeded(usize),
+    /// Batch request without any request
+    #[error("Batch is empty")]
+    EmptyBatch,
+    /// Batch request has the same quote more than once
+    #[error("Duplicate quote in batch")]
+    DuplicateQuote,
     /// Quote is not known
     #[error("Unknown quote")]
     UnknownQuote,
@@ -172,6 +196,9 @@ pub enum Error {
     /// Payment state is unknown
     #[error("Payment state is unknown")]
     UnknownPaymentState,
+    /// Payment preimage does not match the payment hash of the request
+    #[error("Invalid payment preimage")]
+    InvalidPaymentPreimage,
     /// Melting is disabled
     #[error("Minting is disabled")]
     MeltingDisabled,
@@ -292,6 +319,9 @@ pub enum Error {
     /// Unexpected proof state
     #[error("Unexpected proof state")]
     UnexpectedProofState,
+    /// Batch response does not have one entry per request
+    #[error("Batch response does not match the request")]
+    BatchResponseMismatch,
     /// No active keyset
     #[error("No active keyset")]
     NoActiveKeyset,
@@ -301,6 +331,15 @@ pub enum Error {
     /// Invoice Description not supported
     #[error("Invoice Description not supported")]
     InvoiceDescriptionUnsupported,
+    /// Invoice Description hash not supported
+    #[error("Invoice Description hash not supported")]
+    InvoiceDescriptionHashUnsupported,
+    /// Both invoice description and description hash provided
+    #[error("Invoice Description and Description hash cannot both be set")]
+    InvoiceDescriptionAndHash,
+    /// Requested quote expiry is outside of allowed range
+    #[error("Quote expiry `{0}` seconds is outside of the allowed range")]
+    QuoteExpiryOutOfRange(u64),
     /// Invalid transaction direction
     #[error("Invalid transaction direction")]
     InvalidTransactionDirection,
@@ -594,6 +633,18 @@ impl From<Error> for ErrorResponse {
                 code: ErrorCode::DuplicateSignature,
                 detail: err.to_string(),
             },
+            Error::RateLimited => ErrorResponse {
+                code: ErrorCode::RateLimited,
+                detail: err.to_string(),
+            },
+            Error::MaxBatchSizeExceeded(_, _) => ErrorResponse {
+                code: ErrorCode::MaxBatchSizeExceeded,
+                detail: err.to_string(),
+            },
+            Error::MaxSubscriptionsExceeded(_) | Error::MaxFiltersExceeded(_) => ErrorResponse {
+                code: ErrorCode::SubscriptionLimitExceeded,
+                detail: err.to_string(),
+            },
             _ => ErrorResponse {
                 code: ErrorCode::Unknown(9999),
                 detail: err.to_string(),
@@ -651,14 +702,47 @@ impl From<ErrorResponse> for Error {
             ErrorCode::ClearAuthRequired => Self::ClearAuthRequired,
             ErrorCode::BlindAuthRequired => Self::BlindAuthRequired,
             ErrorCode::DuplicateSignature => Self::DuplicateSignatureError,
+            ErrorCode::RateLimited => Self::RateLimited,
+            ErrorCode::MaxBatchSizeExceeded => match batch_sizes(&err.detail) {
+                Some((size, max)) => Self::MaxBatchSizeExceeded(size, max),
+                None => Self::UnknownErrorResponse(err.to_string()),
+            },
+            ErrorCode::SubscriptionLimitExceeded => match subscription_limit(&err.detail) {
+                Some(limit) => limit,
+                None => Self::UnknownErrorResponse(err.to_string()),
+            },
             _ => Self::UnknownErrorResponse(err.to_string()),
         }
     }
 }
 
+/// Size and max size of a batch from the detail of [`Error::MaxBatchSizeExceeded`]
+fn batch_sizes(detail: &str) -> Option<(usize, usize)> {
+    let mut sizes = detail.split('`').skip(1).step_by(2);
+    let size = sizes.next()?.parse().ok()?;
+    let max = sizes.next()?.parse().ok()?;
+
+    Some((size, max))
+}
+
+/// Subscription limit of a websocket connection from the detail of
+/// [`ErrorCode::SubscriptionLimitExceeded`]
+fn subscription_limit(detail: &str) -> Option<Error> {
+    let max = detail.split('`').nth(1)?.parse().ok()?;
+
+    if detail.ends_with("subscriptions") {
+        Some(Error::MaxSubscriptionsExceeded(max))
+    } else if detail.ends_with("filters") {
+        Some(Error::MaxFiltersExceeded(max))
+    } else {
+        None
+    }
+}
+
 /// Possible Error Codes
+///
+/// Codes from 50000 are not assigned by the NUTs and are specific to CDK mints.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
 pub enum ErrorCode {
     /// Token is already spent
     TokenAlreadySpent,
@@ -712,6 +796,12 @@ pub enum ErrorCode {
     BlindAuthFailed,
     /// Duplicate signature from same pubkey
     DuplicateSignature,
+    /// Too many requests from the client (CDK specific)
+    RateLimited,
+    /// Too many inputs, outputs or Ys in the request (CDK specific)
+    MaxBatchSizeExceeded,
+    /// Too many subscriptions or filters on a websocket connection (CDK specific)
+    SubscriptionLimitExceeded,
     /// Unknown error code
     Unknown(u16),
 }
@@ -746,6 +836,9 @@ impl ErrorCode {
             30002 => Self::ClearAuthFailed,
             31001 => Self::BlindAuthRequired,
             31002 => Self::BlindAuthFailed,
+            50001 => Self::RateLimited,
+            50002 => Self::MaxBatchSizeExceeded,
+            50003 => Self::SubscriptionLimitExceeded,
             _ => Self::Unknown(code),
         }
     }
@@ -779,11 +872,31 @@ impl ErrorCode {
             Self::ClearAuthFailed => 30002,
             Self::BlindAuthRequired => 31001,
             Self::BlindAuthFailed => 31002,
+            Self::RateLimited => 50001,
+            Self::MaxBatchSizeExceeded => 50002,
+            Self::SubscriptionLimitExceeded => 50003,
             Self::Unknown(code) => *code,
         }
     }
 }
 
+/// Serialized as its numeric code
+#[cfg(feature = "swagger")]
+impl utoipa::PartialSchema for ErrorCode {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::Integer)
+            .description(Some(
+                "Error code of the NUTs, or from 50000 specific to CDK mints",
+            ))
+            .examples([11001])
+            .into()
+    }
+}
+
+#[cfg(feature = "swagger")]
+impl utoipa::ToSchema for ErrorCode {}
+
 impl Serialize for ErrorCode {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -19,6 +19,8 @@ pub mod melt;
 pub mod mint;
 #[cfg(feature = "mint")]
 pub mod payment;
+#[cfg(feature = "mint")]
+pub mod price_oracle;
 pub mod pub_sub;
 #[cfg(feature = "mint")]
 pub mod state;
@@ -230,6 +230,42 @@ impl Saga {
     }
 }
 
+/// Administrative action recorded in the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    /// Entry ID
+    pub id: Uuid,
+    /// Action performed (e.g. `update_motd`)
+    pub action: String,
+    /// Value before the action
+    pub old_value: Option<String>,
+    /// Value after the action
+    pub new_value: Option<String>,
+    /// Identity of the caller that performed the action
+    pub caller: Option<String>,
+    /// Unix timestamp when the action was performed
+    pub created_at: u64,
+}
+
+impl AuditLogEntry {
+    /// Create new audit log entry
+    pub fn new(
+        action: String,
+        old_value: Option<String>,
+        new_value: Option<String>,
+        caller: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            action,
+            old_value,
+            new_value,
+            caller,
+            created_at: unix_time(),
+        }
+    }
+}
+
 /// Operation
 pub enum Operation {
     /// Mint
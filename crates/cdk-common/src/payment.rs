@@ -1,22 +1,30 @@
 //! CDK Mint Lightning
 
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
 use cashu::util::hex;
 use cashu::{Bolt11Invoice, MeltOptions};
 #[cfg(feature = "prometheus")]
 use cdk_prometheus::METRICS;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use lightning::offers::offer::Offer;
 use lightning_invoice::ParseOrSemanticError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
+use tokio::sync::RwLock;
 
+use crate::amount::to_unit;
+use crate::database::mint::DynMintKVStore;
 use crate::mint::MeltPaymentRequest;
 use crate::nuts::{CurrencyUnit, MeltQuoteState};
+use crate::price_oracle::{is_fiat_unit, ConversionDirection, FiatConverter};
 use crate::Amount;
 
 /// CDK Lightning Error
@@ -64,6 +72,9 @@ pub enum Error {
     /// NUT23 Error
     #[error(transparent)]
     NUT23(#[from] crate::nuts::nut23::Error),
+    /// Price oracle error
+    #[error(transparent)]
+    PriceOracle(#[from] crate::price_oracle::Error),
     /// Hex error
     #[error("Hex error")]
     Hex(#[from] hex::Error),
@@ -156,6 +167,8 @@ impl std::fmt::Display for PaymentIdentifier {
 pub struct Bolt11IncomingPaymentOptions {
     /// Optional description for the payment request
     pub description: Option<String>,
+    /// Optional description hash to commit to instead of a description
+    pub description_hash: Option<Sha256>,
     /// Amount for the payment request in sats
     pub amount: Amount,
     /// Optional expiry time as Unix timestamp in seconds
@@ -318,6 +331,11 @@ pub trait MintPayment {
         &self,
         payment_identifier: &PaymentIdentifier,
     ) -> Result<MakePaymentResponse, Self::Err>;
+
+    /// Get the inbound and outbound liquidity of the backend
+    async fn get_liquidity(&self) -> Result<LiquidityResponse, Self::Err> {
+        Err(Error::UnsupportedPaymentOption.into())
+    }
 }
 
 /// An event emitted which should be handled by the mint
@@ -325,6 +343,10 @@ pub trait MintPayment {
 pub enum Event {
     /// A payment has been received.
     PaymentReceived(WaitPaymentResponse),
+    /// An outgoing payment has changed state.
+    OutgoingPaymentUpdate(MakePaymentResponse),
+    /// The settings of the payment backend have changed.
+    SettingsChanged(Bolt11Settings),
 }
 
 impl Default for Event {
@@ -381,6 +403,17 @@ pub struct MakePaymentResponse {
     pub unit: CurrencyUnit,
 }
 
+/// Liquidity response
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidityResponse {
+    /// Amount that can be sent
+    pub outbound: Amount,
+    /// Amount that can be received
+    pub inbound: Amount,
+    /// Unit of `outbound` and `inbound`
+    pub unit: CurrencyUnit,
+}
+
 /// Payment quote response
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaymentQuoteResponse {
@@ -405,6 +438,9 @@ pub struct Bolt11Settings {
     pub unit: CurrencyUnit,
     /// Invoice Description supported
     pub invoice_description: bool,
+    /// Invoice Description hash supported
+    #[serde(default)]
+    pub invoice_description_hash: bool,
     /// Paying amountless invoices supported
     pub amountless: bool,
     /// Bolt12 supported
@@ -466,6 +502,14 @@ where
 {
     type Err = T::Err;
 
+    async fn start(&self) -> Result<(), Self::Err> {
+        self.inner.start().await
+    }
+
+    async fn stop(&self) -> Result<(), Self::Err> {
+        self.inner.stop().await
+    }
+
     async fn get_settings(&self) -> Result<serde_json::Value, Self::Err> {
         let start = std::time::Instant::now();
         METRICS.inc_in_flight_requests("get_settings");
@@ -611,7 +655,676 @@ where
 
         result
     }
+
+    async fn get_liquidity(&self) -> Result<LiquidityResponse, Self::Err> {
+        let start = std::time::Instant::now();
+        METRICS.inc_in_flight_requests("get_liquidity");
+
+        let result = self.inner.get_liquidity().await;
+
+        let duration = start.elapsed().as_secs_f64();
+        METRICS.record_mint_operation_histogram("get_liquidity", result.is_ok(), duration);
+        METRICS.dec_in_flight_requests("get_liquidity");
+
+        result
+    }
+}
+
+const FIAT_KV_PRIMARY_NAMESPACE: &str = "cdk_fiat_mint_payment";
+const FIAT_KV_INCOMING_NAMESPACE: &str = "incoming";
+const FIAT_KV_OUTGOING_NAMESPACE: &str = "outgoing";
+
+/// Fiat unit a payment was requested in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FiatPayment {
+    /// Fiat unit of the quote
+    unit: CurrencyUnit,
+    /// Amount of the incoming payment request in `unit`
+    amount: Option<Amount>,
+    /// Amount of the incoming payment request the backend was asked for in msat
+    amount_msat: Option<Amount>,
+}
+
+/// Payments in fiat units handled by a [`FiatMintPayment`]
+#[derive(Clone)]
+struct FiatPayments {
+    converter: FiatConverter,
+    kv_store: Option<DynMintKVStore>,
+    payments: Arc<RwLock<HashMap<(&'static str, PaymentIdentifier), FiatPayment>>>,
+}
+
+impl FiatPayments {
+    /// Key of a payment identifier in the kv store
+    fn kv_key(payment_identifier: &PaymentIdentifier) -> String {
+        let id = format!("{}:{}", payment_identifier.kind(), payment_identifier);
+        Sha256::hash(id.as_bytes()).to_string()
+    }
+
+    async fn insert(
+        &self,
+        namespace: &'static str,
+        payment_identifier: &PaymentIdentifier,
+        payment: FiatPayment,
+    ) -> Result<(), Error> {
+        if let Some(kv_store) = &self.kv_store {
+            let value = serde_json::to_vec(&payment)?;
+            let mut tx = kv_store
+                .begin_transaction()
+                .await
+                .map_err(|e| Error::Custom(e.to_string()))?;
+            tx.kv_write(
+                FIAT_KV_PRIMARY_NAMESPACE,
+                namespace,
+                &Self::kv_key(payment_identifier),
+                &value,
+            )
+            .await
+            .map_err(|e| Error::Custom(e.to_string()))?;
+            tx.commit()
+                .await
+                .map_err(|e| Error::Custom(e.to_string()))?;
+        }
+
+        self.payments
+            .write()
+            .await
+            .insert((namespace, payment_identifier.clone()), payment);
+
+        Ok(())
+    }
+
+    async fn get(
+        &self,
+        namespace: &'static str,
+        payment_identifier: &PaymentIdentifier,
+    ) -> Result<Option<FiatPayment>, Error> {
+        let key = (namespace, payment_identifier.clone());
+        if let Some(payment) = self.payments.read().await.get(&key) {
+            return Ok(Some(payment.clone()));
+        }
+
+        let Some(kv_store) = &self.kv_store else {
+            return Ok(None);
+        };
+
+        // Payments created before a restart are only in the kv store
+        let Some(value) = kv_store
+            .kv_read(
+                FIAT_KV_PRIMARY_NAMESPACE,
+                namespace,
+                &Self::kv_key(payment_identifier),
+            )
+            .await
+            .map_err(|e| Error::Custom(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let payment: FiatPayment = serde_json::from_slice(&value)?;
+        self.payments.write().await.insert(key, payment.clone());
+
+        Ok(Some(payment))
+    }
+
+    /// Convert a received payment to the fiat unit it was requested in
+    async fn incoming_to_fiat(
+        &self,
+        mut payment: WaitPaymentResponse,
+    ) -> Result<WaitPaymentResponse, Error> {
+        let Some(fiat_payment) = self
+            .get(FIAT_KV_INCOMING_NAMESPACE, &payment.payment_identifier)
+            .await?
+        else {
+            return Ok(payment);
+        };
+
+        let amount_msat = to_unit(payment.payment_amount, &payment.unit, &CurrencyUnit::Msat)?;
+
+        payment.payment_amount = match (fiat_payment.amount, fiat_payment.amount_msat) {
+            // Credit the quoted amount pro rata so a rate change after the quote does not matter
+            (Some(amount), Some(requested_msat)) if requested_msat > Amount::ZERO => {
+                let amount = u128::from(u64::from(amount)) * u128::from(u64::from(amount_msat))
+                    / u128::from(u64::from(requested_msat));
+
+                Amount::from(
+                    u64::try_from(amount).map_err(|_| crate::amount::Error::AmountOverflow)?,
+                )
+            }
+            _ => {
+                self.converter
+                    .convert(
+                        amount_msat,
+                        &CurrencyUnit::Msat,
+                        &fiat_payment.unit,
+                        ConversionDirection::Incoming,
+                    )
+                    .await?
+            }
+        };
+        payment.unit = fiat_payment.unit;
+
+        Ok(payment)
+    }
+
+    /// Convert an outgoing payment to the fiat unit it was quoted in
+    async fn outgoing_to_fiat(
+        &self,
+        payment_identifier: &PaymentIdentifier,
+        mut response: MakePaymentResponse,
+    ) -> Result<MakePaymentResponse, Error> {
+        let Some(fiat_payment) = self
+            .get(FIAT_KV_OUTGOING_NAMESPACE, payment_identifier)
+            .await?
+        else {
+            return Ok(response);
+        };
+
+        response.total_spent = self
+            .converter
+            .convert(
+                response.total_spent,
+                &response.unit,
+                &fiat_payment.unit,
+                ConversionDirection::Outgoing,
+            )
+            .await?;
+        response.unit = fiat_payment.unit;
+
+        Ok(response)
+    }
+
+    async fn event_to_fiat(&self, event: Event) -> Result<Event, Error> {
+        Ok(match event {
+            Event::PaymentReceived(payment) => {
+                Event::PaymentReceived(self.incoming_to_fiat(payment).await?)
+            }
+            Event::OutgoingPaymentUpdate(response) => {
+                let payment_identifier = response.payment_lookup_id.clone();
+                Event::OutgoingPaymentUpdate(
+                    self.outgoing_to_fiat(&payment_identifier, response).await?,
+                )
+            }
+            event => event,
+        })
+    }
+
+    /// Convert the amounts of outgoing payment options to msat
+    async fn outgoing_options_to_msat(
+        &self,
+        unit: &CurrencyUnit,
+        options: OutgoingPaymentOptions,
+    ) -> Result<OutgoingPaymentOptions, Error> {
+        let max_fee_amount = match &options {
+            OutgoingPaymentOptions::Bolt11(options) => options.max_fee_amount,
+            OutgoingPaymentOptions::Bolt12(options) => options.max_fee_amount,
+        };
+
+        let max_fee_amount = match max_fee_amount {
+            Some(max_fee_amount) => Some(
+                self.converter
+                    .convert(
+                        max_fee_amount,
+                        unit,
+                        &CurrencyUnit::Msat,
+                        ConversionDirection::Outgoing,
+                    )
+                    .await?,
+            ),
+            None => None,
+        };
+
+        Ok(match options {
+            OutgoingPaymentOptions::Bolt11(mut options) => {
+                options.max_fee_amount = max_fee_amount;
+                OutgoingPaymentOptions::Bolt11(options)
+            }
+            OutgoingPaymentOptions::Bolt12(mut options) => {
+                options.max_fee_amount = max_fee_amount;
+                OutgoingPaymentOptions::Bolt12(options)
+            }
+        })
+    }
+}
+
+/// Fiat conversion wrapper for MintPayment implementations
+///
+/// This wrapper lets any backend serve fiat units. Requests in a fiat unit are
+/// passed to the inner backend in msat, and the amounts it reports are converted
+/// back to the unit of the quote with a [`FiatConverter`]. Requests in bitcoin
+/// units are passed through unchanged.
+#[derive(Clone)]
+pub struct FiatMintPayment<T> {
+    inner: T,
+    payments: FiatPayments,
+}
+
+impl<T> FiatMintPayment<T>
+where
+    T: MintPayment,
+{
+    /// Create a new fiat wrapper around a MintPayment implementation
+    pub fn new(inner: T, converter: FiatConverter) -> Self {
+        Self {
+            inner,
+            payments: FiatPayments {
+                converter,
+                kv_store: None,
+                payments: Arc::new(RwLock::new(HashMap::new())),
+            },
+        }
+    }
+
+    /// Keep the fiat unit of each payment in `kv_store` so payments are still
+    /// converted after a restart
+    pub fn with_kv_store(mut self, kv_store: DynMintKVStore) -> Self {
+        self.payments.kv_store = Some(kv_store);
+        self
+    }
+
+    /// Get reference to the underlying implementation
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T> MintPayment for FiatMintPayment<T>
+where
+    T: MintPayment + Send + Sync,
+{
+    type Err = T::Err;
+
+    async fn start(&self) -> Result<(), Self::Err> {
+        self.inner.start().await
+    }
+
+    async fn stop(&self) -> Result<(), Self::Err> {
+        self.inner.stop().await
+    }
+
+    async fn get_settings(&self) -> Result<serde_json::Value, Self::Err> {
+        self.inner.get_settings().await
+    }
+
+    async fn create_incoming_payment_request(
+        &self,
+        unit: &CurrencyUnit,
+        options: IncomingPaymentOptions,
+    ) -> Result<CreateIncomingPaymentResponse, Self::Err> {
+        if !is_fiat_unit(unit) {
+            return self
+                .inner
+                .create_incoming_payment_request(unit, options)
+                .await;
+        }
+
+        let converter = &self.payments.converter;
+        let to_msat = |amount| {
+            converter.convert(
+                amount,
+                unit,
+                &CurrencyUnit::Msat,
+                ConversionDirection::Incoming,
+            )
+        };
+
+        let (options, amount, amount_msat) = match options {
+            IncomingPaymentOptions::Bolt11(mut options) => {
+                let amount = options.amount;
+                options.amount = to_msat(amount).await.map_err(Error::from)?;
+                let amount_msat = options.amount;
+
+                (
+                    IncomingPaymentOptions::Bolt11(options),
+                    Some(amount),
+                    Some(amount_msat),
+                )
+            }
+            IncomingPaymentOptions::Bolt12(mut options) => {
+                let amount = options.amount;
+                options.amount = match amount {
+                    Some(amount) => Some(to_msat(amount).await.map_err(Error::from)?),
+                    None => None,
+                };
+                let amount_msat = options.amount;
+
+                (IncomingPaymentOptions::Bolt12(options), amount, amount_msat)
+            }
+        };
+
+        let response = self
+            .inner
+            .create_incoming_payment_request(&CurrencyUnit::Msat, options)
+            .await?;
+
+        self.payments
+            .insert(
+                FIAT_KV_INCOMING_NAMESPACE,
+                &response.request_lookup_id,
+                FiatPayment {
+                    unit: unit.clone(),
+                    amount,
+                    amount_msat,
+                },
+            )
+            .await?;
+
+        Ok(response)
+    }
+
+    async fn get_payment_quote(
+        &self,
+        unit: &CurrencyUnit,
+        options: OutgoingPaymentOptions,
+    ) -> Result<PaymentQuoteResponse, Self::Err> {
+        if !is_fiat_unit(unit) {
+            return self.inner.get_payment_quote(unit, options).await;
+        }
+
+        let options = self
+            .payments
+            .outgoing_options_to_msat(unit, options)
+            .await?;
+        let mut quote = self
+            .inner
+            .get_payment_quote(&CurrencyUnit::Msat, options)
+            .await?;
+
+        let converter = &self.payments.converter;
+        quote.amount = converter
+            .convert(
+                quote.amount,
+                &quote.unit,
+                unit,
+                ConversionDirection::Outgoing,
+            )
+            .await
+            .map_err(Error::from)?;
+        quote.fee = converter
+            .convert(quote.fee, &quote.unit, unit, ConversionDirection::Outgoing)
+            .await
+            .map_err(Error::from)?;
+        quote.unit = unit.clone();
+
+        if let Some(request_lookup_id) = &quote.request_lookup_id {
+            self.payments
+                .insert(
+                    FIAT_KV_OUTGOING_NAMESPACE,
+                    request_lookup_id,
+                    FiatPayment {
+                        unit: unit.clone(),
+                        amount: None,
+                        amount_msat: None,
+                    },
+                )
+                .await?;
+        }
+
+        Ok(quote)
+    }
+
+    async fn make_payment(
+        &self,
+        unit: &CurrencyUnit,
+        options: OutgoingPaymentOptions,
+    ) -> Result<MakePaymentResponse, Self::Err> {
+        if !is_fiat_unit(unit) {
+            return self.inner.make_payment(unit, options).await;
+        }
+
+        let options = self
+            .payments
+            .outgoing_options_to_msat(unit, options)
+            .await?;
+        let response = self
+            .inner
+            .make_payment(&CurrencyUnit::Msat, options)
+            .await?;
+
+        let payment_identifier = response.payment_lookup_id.clone();
+        self.payments
+            .insert(
+                FIAT_KV_OUTGOING_NAMESPACE,
+                &payment_identifier,
+                FiatPayment {
+                    unit: unit.clone(),
+                    amount: None,
+                    amount_msat: None,
+                },
+            )
+            .await?;
+
+        Ok(self
+            .payments
+            .outgoing_to_fiat(&payment_identifier, response)
+            .await?)
+    }
+
+    async fn wait_payment_event(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Event> + Send>>, Self::Err> {
+        let stream = self.inner.wait_payment_event().await?;
+        let payments = self.payments.clone();
+
+        Ok(stream
+            .then(move |event| {
+                let payments = payments.clone();
+                async move {
+                    match payments.event_to_fiat(event.clone()).await {
+                        Ok(event) => event,
+                        Err(err) => {
+                            tracing::error!("Could not convert payment event to fiat: {}", err);
+                            event
+                        }
+                    }
+                }
+            })
+            .boxed())
+    }
+
+    fn is_wait_invoice_active(&self) -> bool {
+        self.inner.is_wait_invoice_active()
+    }
+
+    fn cancel_wait_invoice(&self) {
+        self.inner.cancel_wait_invoice()
+    }
+
+    async fn check_incoming_payment_status(
+        &self,
+        payment_identifier: &PaymentIdentifier,
+    ) -> Result<Vec<WaitPaymentResponse>, Self::Err> {
+        let payments = self
+            .inner
+            .check_incoming_payment_status(payment_identifier)
+            .await?;
+
+        let mut converted = Vec::with_capacity(payments.len());
+        for payment in payments {
+            converted.push(self.payments.incoming_to_fiat(payment).await?);
+        }
+
+        Ok(converted)
+    }
+
+    async fn check_outgoing_payment(
+        &self,
+        payment_identifier: &PaymentIdentifier,
+    ) -> Result<MakePaymentResponse, Self::Err> {
+        let response = self
+            .inner
+            .check_outgoing_payment(payment_identifier)
+            .await?;
+
+        Ok(self
+            .payments
+            .outgoing_to_fiat(payment_identifier, response)
+            .await?)
+    }
+
+    async fn get_liquidity(&self) -> Result<LiquidityResponse, Self::Err> {
+        self.inner.get_liquidity().await
+    }
 }
 
 /// Type alias for Mint Payment trait
 pub type DynMintPayment = std::sync::Arc<dyn MintPayment<Err = Error> + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::price_oracle::StaticPriceOracle;
+
+    /// Backend that records the unit and amount of the last incoming payment request
+    #[derive(Default)]
+    struct MockBackend {
+        requested: parking_lot::Mutex<Option<(CurrencyUnit, Amount)>>,
+    }
+
+    #[async_trait]
+    impl MintPayment for MockBackend {
+        type Err = Error;
+
+        async fn get_settings(&self) -> Result<serde_json::Value, Self::Err> {
+            Ok(Value::Null)
+        }
+
+        async fn create_incoming_payment_request(
+            &self,
+            unit: &CurrencyUnit,
+            options: IncomingPaymentOptions,
+        ) -> Result<CreateIncomingPaymentResponse, Self::Err> {
+            let IncomingPaymentOptions::Bolt11(options) = options else {
+                return Err(Error::UnsupportedPaymentOption);
+            };
+            *self.requested.lock() = Some((unit.clone(), options.amount));
+
+            Ok(CreateIncomingPaymentResponse {
+                request_lookup_id: PaymentIdentifier::CustomId("invoice".to_string()),
+                request: "invoice".to_string(),
+                expiry: None,
+            })
+        }
+
+        async fn get_payment_quote(
+            &self,
+            unit: &CurrencyUnit,
+            _options: OutgoingPaymentOptions,
+        ) -> Result<PaymentQuoteResponse, Self::Err> {
+            Ok(PaymentQuoteResponse {
+                request_lookup_id: Some(PaymentIdentifier::CustomId("payment".to_string())),
+                amount: to_unit(5_000_000u64, &CurrencyUnit::Msat, unit)?,
+                fee: to_unit(100_000u64, &CurrencyUnit::Msat, unit)?,
+                unit: unit.clone(),
+                state: MeltQuoteState::Unpaid,
+            })
+        }
+
+        async fn make_payment(
+            &self,
+            _unit: &CurrencyUnit,
+            _options: OutgoingPaymentOptions,
+        ) -> Result<MakePaymentResponse, Self::Err> {
+            Err(Error::UnsupportedPaymentOption)
+        }
+
+        async fn wait_payment_event(
+            &self,
+        ) -> Result<Pin<Box<dyn Stream<Item = Event> + Send>>, Self::Err> {
+            Ok(futures::stream::empty().boxed())
+        }
+
+        fn is_wait_invoice_active(&self) -> bool {
+            false
+        }
+
+        fn cancel_wait_invoice(&self) {}
+
+        async fn check_incoming_payment_status(
+            &self,
+            payment_identifier: &PaymentIdentifier,
+        ) -> Result<Vec<WaitPaymentResponse>, Self::Err> {
+            let (unit, amount) = self.requested.lock().clone().expect("invoice created");
+
+            Ok(vec![WaitPaymentResponse {
+                payment_identifier: payment_identifier.clone(),
+                payment_amount: amount,
+                unit,
+                payment_id: "payment".to_string(),
+            }])
+        }
+
+        async fn check_outgoing_payment(
+            &self,
+            _payment_identifier: &PaymentIdentifier,
+        ) -> Result<MakePaymentResponse, Self::Err> {
+            Err(Error::UnsupportedPaymentOption)
+        }
+    }
+
+    fn bolt11_options(amount: u64) -> IncomingPaymentOptions {
+        IncomingPaymentOptions::Bolt11(Bolt11IncomingPaymentOptions {
+            amount: amount.into(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_fiat_mint_payment() {
+        let oracle = Arc::new(StaticPriceOracle::new(HashMap::from([(
+            CurrencyUnit::Usd,
+            100_000.0,
+        )])));
+        let backend = FiatMintPayment::new(
+            MockBackend::default(),
+            FiatConverter::new(oracle, 0.0).unwrap(),
+        );
+
+        // 10 USD are requested from the backend as 10,000 sat
+        let invoice = backend
+            .create_incoming_payment_request(&CurrencyUnit::Usd, bolt11_options(1_000))
+            .await
+            .unwrap();
+        assert_eq!(
+            *backend.inner().requested.lock(),
+            Some((CurrencyUnit::Msat, Amount::from(10_000_000)))
+        );
+
+        // The received msat are reported in the unit of the quote
+        let payments = backend
+            .check_incoming_payment_status(&invoice.request_lookup_id)
+            .await
+            .unwrap();
+        assert_eq!(payments[0].unit, CurrencyUnit::Usd);
+        assert_eq!(payments[0].payment_amount, Amount::from(1_000));
+
+        let bolt11: Bolt11Invoice = "lnbc330n1p5d85skpp5344v3ktclujsjl3h09wgsfm7zytumr7h7zhrl857f5w8nv0a52zqdqqcqzzsxqyz5vqrzjqvueefmrckfdwyyu39m0lf24sqzcr9vcrmxrvgfn6empxz7phrjxvrttncqq0lcqqyqqqqlgqqqqqqgq2qsp5j3rrg8kvpemqxtf86j8tjm90wq77c7ende4e5qmrerq4xsg02vhq9qxpqysgqjltywgyk6uc5qcgwh8xnzmawl2tjlhz8d28tgp3yx8xwtz76x0jqkfh6mmq70hervjxs0keun7ur0spldgll29l0dnz3md50d65sfqqqwrwpsu".parse().unwrap();
+        let quote = backend
+            .get_payment_quote(
+                &CurrencyUnit::Usd,
+                OutgoingPaymentOptions::Bolt11(Box::new(Bolt11OutgoingPaymentOptions {
+                    bolt11,
+                    max_fee_amount: None,
+                    timeout_secs: None,
+                    melt_options: None,
+                })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(quote.unit, CurrencyUnit::Usd);
+        assert_eq!(quote.amount, Amount::from(500));
+        assert_eq!(quote.fee, Amount::from(10));
+
+        // Bitcoin units are passed through
+        backend
+            .create_incoming_payment_request(&CurrencyUnit::Sat, bolt11_options(100))
+            .await
+            .unwrap();
+        assert_eq!(
+            *backend.inner().requested.lock(),
+            Some((CurrencyUnit::Sat, Amount::from(100)))
+        );
+    }
+}
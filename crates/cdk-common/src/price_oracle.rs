@@ -0,0 +1,517 @@
+//! Exchange rate oracles for fiat units
+//!
+//! A [`PriceOracle`] reports the price of one BTC in the major unit of a fiat
+//! currency (e.g. dollars for [`CurrencyUnit::Usd`]). Sources can be combined with
+//! [`MedianPriceOracle`] and cached with [`CachedPriceOracle`], and payment backends
+//! use a [`FiatConverter`] to quote fiat-unit mint and melt requests.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use crate::amount::to_unit;
+use crate::nuts::CurrencyUnit;
+use crate::util::unix_time;
+use crate::Amount;
+
+/// Satoshis in one BTC
+const SATS_PER_BTC: f64 = 100_000_000.0;
+
+/// Fiat minor units (cents) in one major unit
+const CENTS_PER_UNIT: f64 = 100.0;
+
+/// Price oracle error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No price for unit
+    #[error("No price for unit `{0}`")]
+    UnsupportedUnit(CurrencyUnit),
+    /// Price is older than the max staleness
+    #[error("Price for `{0}` is stale")]
+    StalePrice(CurrencyUnit),
+    /// Invalid price
+    #[error("Invalid price `{0}`")]
+    InvalidPrice(f64),
+    /// Invalid spread
+    #[error("Invalid spread `{0}`")]
+    InvalidSpread(f64),
+    /// Price not found in source response
+    #[error("Price not found at path `{0}`")]
+    PathNotFound(String),
+    /// All price sources failed
+    #[error("No price source available")]
+    NoSourceAvailable,
+    /// Amount Error
+    #[error(transparent)]
+    Amount(#[from] crate::amount::Error),
+    /// Serde Error
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// IO Error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// HTTP Error
+    #[cfg(feature = "http-price-oracle")]
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// Price of one BTC in the major unit of a fiat currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    /// Price of one BTC
+    pub rate: f64,
+    /// Unix time the price was observed
+    pub timestamp: u64,
+}
+
+impl Price {
+    /// Create a new [`Price`] observed now
+    pub fn new(rate: f64) -> Result<Self, Error> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(Error::InvalidPrice(rate));
+        }
+
+        Ok(Self {
+            rate,
+            timestamp: unix_time(),
+        })
+    }
+}
+
+/// Source of BTC prices for fiat units
+#[async_trait]
+pub trait PriceOracle {
+    /// Price of one BTC in the major unit of `unit`
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, Error>;
+}
+
+/// Type alias for Price Oracle trait
+pub type DynPriceOracle = Arc<dyn PriceOracle + Send + Sync>;
+
+/// Whether the unit is a fiat unit that needs an exchange rate
+pub fn is_fiat_unit(unit: &CurrencyUnit) -> bool {
+    matches!(unit, CurrencyUnit::Usd | CurrencyUnit::Eur)
+}
+
+/// Oracle with fixed prices
+#[derive(Debug, Clone, Default)]
+pub struct StaticPriceOracle {
+    prices: HashMap<CurrencyUnit, f64>,
+}
+
+impl StaticPriceOracle {
+    /// Create new [`StaticPriceOracle`]
+    pub fn new(prices: HashMap<CurrencyUnit, f64>) -> Self {
+        Self { prices }
+    }
+
+    /// Load prices from a JSON file mapping units to prices, e.g. `{"usd": 110000}`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        let prices: HashMap<String, f64> = serde_json::from_str(&content)?;
+
+        let prices = prices
+            .into_iter()
+            .map(|(unit, price)| {
+                // Unit parsing is infallible, unknown units become custom units
+                let unit = CurrencyUnit::from_str(&unit).unwrap_or(CurrencyUnit::Custom(unit));
+                (unit, price)
+            })
+            .collect();
+
+        Ok(Self { prices })
+    }
+}
+
+#[async_trait]
+impl PriceOracle for StaticPriceOracle {
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, Error> {
+        let rate = self
+            .prices
+            .get(unit)
+            .ok_or_else(|| Error::UnsupportedUnit(unit.clone()))?;
+
+        Price::new(*rate)
+    }
+}
+
+/// Oracle fetching prices from an HTTP JSON API
+///
+/// Each unit maps to a dot separated path into the response, e.g. `bitcoin.usd`
+/// for `{"bitcoin": {"usd": 110000}}`.
+#[cfg(feature = "http-price-oracle")]
+#[derive(Debug, Clone)]
+pub struct HttpJsonPriceOracle {
+    url: String,
+    paths: HashMap<CurrencyUnit, String>,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "http-price-oracle")]
+impl HttpJsonPriceOracle {
+    /// Create new [`HttpJsonPriceOracle`]
+    pub fn new(url: String, paths: HashMap<CurrencyUnit, String>) -> Self {
+        Self {
+            url,
+            paths,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Oracle using the mempool.space prices API
+    pub fn mempool_space() -> Self {
+        Self::new(
+            "https://mempool.space/api/v1/prices".to_string(),
+            HashMap::from([
+                (CurrencyUnit::Usd, "USD".to_string()),
+                (CurrencyUnit::Eur, "EUR".to_string()),
+            ]),
+        )
+    }
+}
+
+#[cfg(feature = "http-price-oracle")]
+#[async_trait]
+impl PriceOracle for HttpJsonPriceOracle {
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, Error> {
+        let path = self
+            .paths
+            .get(unit)
+            .ok_or_else(|| Error::UnsupportedUnit(unit.clone()))?;
+
+        let response: serde_json::Value = self
+            .client
+            .get(&self.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Price::new(json_path_price(&response, path)?)
+    }
+}
+
+/// Read a price at a dot separated path of a JSON value
+///
+/// Prices given as strings are parsed, as some APIs return them quoted.
+pub fn json_path_price(value: &serde_json::Value, path: &str) -> Result<f64, Error> {
+    let value = path
+        .split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(value, |value, key| match value {
+            serde_json::Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(key),
+        })
+        .ok_or_else(|| Error::PathNotFound(path.to_string()))?;
+
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(price) => price.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| Error::PathNotFound(path.to_string()))
+}
+
+/// Oracle using the median price of several sources
+///
+/// Sources that fail are skipped, at least `min_sources` must answer.
+pub struct MedianPriceOracle {
+    sources: Vec<DynPriceOracle>,
+    min_sources: usize,
+}
+
+impl MedianPriceOracle {
+    /// Create new [`MedianPriceOracle`]
+    pub fn new(sources: Vec<DynPriceOracle>, min_sources: usize) -> Self {
+        Self {
+            sources,
+            min_sources: min_sources.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceOracle for MedianPriceOracle {
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, Error> {
+        let results =
+            futures::future::join_all(self.sources.iter().map(|source| source.btc_price(unit)))
+                .await;
+
+        let mut prices: Vec<Price> = results
+            .into_iter()
+            .filter_map(|result| match result {
+                Ok(price) => Some(price),
+                Err(err) => {
+                    tracing::warn!("Price source failed for {}: {}", unit, err);
+                    None
+                }
+            })
+            .collect();
+
+        if prices.is_empty() || prices.len() < self.min_sources {
+            return Err(Error::NoSourceAvailable);
+        }
+
+        prices.sort_by(|a, b| a.rate.total_cmp(&b.rate));
+
+        let mid = prices.len() / 2;
+        let rate = if prices.len() % 2 == 0 {
+            (prices[mid - 1].rate + prices[mid].rate) / 2.0
+        } else {
+            prices[mid].rate
+        };
+
+        // The median is only as fresh as the oldest price it was computed from
+        let timestamp = prices
+            .iter()
+            .map(|price| price.timestamp)
+            .min()
+            .unwrap_or_default();
+
+        Ok(Price { rate, timestamp })
+    }
+}
+
+/// Oracle caching the prices of another oracle
+///
+/// Prices are refreshed once older than `refresh_interval`. If the refresh fails,
+/// the cached price is used until it is older than `max_staleness`.
+pub struct CachedPriceOracle {
+    inner: DynPriceOracle,
+    refresh_interval: Duration,
+    max_staleness: Duration,
+    cache: RwLock<HashMap<CurrencyUnit, Price>>,
+}
+
+impl CachedPriceOracle {
+    /// Create new [`CachedPriceOracle`]
+    pub fn new(inner: DynPriceOracle, refresh_interval: Duration, max_staleness: Duration) -> Self {
+        Self {
+            inner,
+            refresh_interval,
+            max_staleness,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceOracle for CachedPriceOracle {
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, Error> {
+        let cached = self.cache.read().await.get(unit).copied();
+        let age = |price: &Price| unix_time().saturating_sub(price.timestamp);
+
+        if let Some(price) = cached {
+            if age(&price) < self.refresh_interval.as_secs() {
+                return Ok(price);
+            }
+        }
+
+        match self.inner.btc_price(unit).await {
+            Ok(price) => {
+                if age(&price) > self.max_staleness.as_secs() {
+                    return Err(Error::StalePrice(unit.clone()));
+                }
+
+                self.cache.write().await.insert(unit.clone(), price);
+                Ok(price)
+            }
+            Err(err) => match cached {
+                Some(price) if age(&price) <= self.max_staleness.as_secs() => {
+                    tracing::warn!(
+                        "Could not refresh price for {}, using cached: {}",
+                        unit,
+                        err
+                    );
+                    Ok(price)
+                }
+                Some(_) => Err(Error::StalePrice(unit.clone())),
+                None => Err(err),
+            },
+        }
+    }
+}
+
+/// Direction of the payment a conversion is quoted for
+///
+/// The spread is always applied in favor of the mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionDirection {
+    /// The mint receives a payment (mint quotes)
+    Incoming,
+    /// The mint sends a payment (melt quotes)
+    Outgoing,
+}
+
+/// Converts amounts between bitcoin and fiat units using a [`PriceOracle`]
+#[derive(Clone)]
+pub struct FiatConverter {
+    oracle: DynPriceOracle,
+    spread: f64,
+}
+
+impl FiatConverter {
+    /// Create new [`FiatConverter`]
+    ///
+    /// `spread` is the fraction the price is moved in favor of the mint, e.g. `0.01` for 1%.
+    pub fn new(oracle: DynPriceOracle, spread: f64) -> Result<Self, Error> {
+        if !spread.is_finite() || !(0.0..1.0).contains(&spread) {
+            return Err(Error::InvalidSpread(spread));
+        }
+
+        Ok(Self { oracle, spread })
+    }
+
+    /// Price oracle used by the converter
+    pub fn oracle(&self) -> &DynPriceOracle {
+        &self.oracle
+    }
+
+    /// Price of one BTC in the major unit of `unit` with the spread applied
+    ///
+    /// The mint values bitcoin lower when receiving it and higher when sending it.
+    pub async fn btc_price(
+        &self,
+        unit: &CurrencyUnit,
+        direction: ConversionDirection,
+    ) -> Result<f64, Error> {
+        let price = self.oracle.btc_price(unit).await?;
+
+        Ok(match direction {
+            ConversionDirection::Incoming => price.rate * (1.0 - self.spread),
+            ConversionDirection::Outgoing => price.rate * (1.0 + self.spread),
+        })
+    }
+
+    /// Convert `amount` from `from_unit` to `target_unit`
+    ///
+    /// Conversions between bitcoin units do not use the oracle.
+    pub async fn convert(
+        &self,
+        amount: Amount,
+        from_unit: &CurrencyUnit,
+        target_unit: &CurrencyUnit,
+        direction: ConversionDirection,
+    ) -> Result<Amount, Error> {
+        if from_unit == target_unit {
+            return Ok(amount);
+        }
+
+        // Go through sats when converting from fiat
+        let (amount, from_unit) = if is_fiat_unit(from_unit) {
+            let price = self.btc_price(from_unit, direction).await?;
+            let sats = u64::from(amount) as f64 / CENTS_PER_UNIT / price * SATS_PER_BTC;
+
+            (Amount::from(sats.round() as u64), &CurrencyUnit::Sat)
+        } else {
+            (amount, from_unit)
+        };
+
+        if !is_fiat_unit(target_unit) {
+            return Ok(to_unit(amount, from_unit, target_unit)?);
+        }
+
+        let price = self.btc_price(target_unit, direction).await?;
+        let msats = to_unit(amount, from_unit, &CurrencyUnit::Msat)?;
+        let btc = u64::from(msats) as f64 / (SATS_PER_BTC * 1000.0);
+
+        Ok(Amount::from((btc * price * CENTS_PER_UNIT).round() as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn static_oracle(usd: f64) -> DynPriceOracle {
+        Arc::new(StaticPriceOracle::new(HashMap::from([(
+            CurrencyUnit::Usd,
+            usd,
+        )])))
+    }
+
+    #[tokio::test]
+    async fn test_median_price_oracle() {
+        let oracle = MedianPriceOracle::new(
+            vec![
+                static_oracle(100_000.0),
+                static_oracle(120_000.0),
+                static_oracle(101_000.0),
+                // Fails for usd and is skipped
+                Arc::new(StaticPriceOracle::default()),
+            ],
+            2,
+        );
+
+        let price = oracle.btc_price(&CurrencyUnit::Usd).await.unwrap();
+        assert_eq!(price.rate, 101_000.0);
+
+        let oracle = MedianPriceOracle::new(vec![static_oracle(100_000.0)], 2);
+        assert!(matches!(
+            oracle.btc_price(&CurrencyUnit::Usd).await,
+            Err(Error::NoSourceAvailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fiat_converter_spread() {
+        let converter = FiatConverter::new(static_oracle(100_000.0), 0.01).unwrap();
+
+        // 10 USD at 99,000 USD/BTC
+        let sats = converter
+            .convert(
+                Amount::from(1_000),
+                &CurrencyUnit::Usd,
+                &CurrencyUnit::Sat,
+                ConversionDirection::Incoming,
+            )
+            .await
+            .unwrap();
+        assert_eq!(sats, Amount::from(10_101));
+
+        // 10,000 sat at 101,000 USD/BTC
+        let cents = converter
+            .convert(
+                Amount::from(10_000),
+                &CurrencyUnit::Sat,
+                &CurrencyUnit::Usd,
+                ConversionDirection::Outgoing,
+            )
+            .await
+            .unwrap();
+        assert_eq!(cents, Amount::from(1_010));
+
+        let msats = converter
+            .convert(
+                Amount::from(5),
+                &CurrencyUnit::Sat,
+                &CurrencyUnit::Msat,
+                ConversionDirection::Incoming,
+            )
+            .await
+            .unwrap();
+        assert_eq!(msats, Amount::from(5_000));
+
+        assert!(FiatConverter::new(static_oracle(100_000.0), 1.5).is_err());
+    }
+
+    #[test]
+    fn test_json_path_price() {
+        let value = serde_json::json!({
+            "bitcoin": { "usd": 110000.5 },
+            "data": [{ "price": "95000" }]
+        });
+
+        assert_eq!(json_path_price(&value, "bitcoin.usd").unwrap(), 110000.5);
+        assert_eq!(json_path_price(&value, "data.0.price").unwrap(), 95000.0);
+        assert!(json_path_price(&value, "bitcoin.eur").is_err());
+    }
+}
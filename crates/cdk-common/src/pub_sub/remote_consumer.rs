@@ -8,11 +8,11 @@ use std::time::Duration;
 
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
-use tokio::time::{sleep, Instant};
+use web_time::Instant;
 
 use super::subscriber::{ActiveSubscription, SubscriptionRequest};
 use super::{Error, Event, Pubsub, Spec};
-use crate::task::spawn;
+use crate::task::{sleep, spawn};
 
 const STREAM_CONNECTION_BACKOFF: Duration = Duration::from_millis(2_000);
 
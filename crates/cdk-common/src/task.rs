@@ -1,6 +1,7 @@
 //! Thin wrapper for spawn and spawn_local for native and wasm.
 
 use std::future::Future;
+use std::time::Duration;
 
 use tokio::task::JoinHandle;
 
@@ -23,3 +24,17 @@ where
 {
     tokio::task::spawn_local(future)
 }
+
+/// Waits until the duration has elapsed
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Waits until the duration has elapsed
+///
+/// Tokio timers need a runtime with a time driver, which browsers do not have.
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
@@ -18,12 +18,12 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bitcoin::hashes::{sha256, Hash};
 use bitcoin::secp256k1::{Secp256k1, SecretKey};
-use cdk_common::amount::{to_unit, Amount};
+use cdk_common::amount::Amount;
 use cdk_common::common::FeeReserve;
 use cdk_common::ensure_cdk;
 use cdk_common::nuts::{CurrencyUnit, MeltOptions, MeltQuoteState};
@@ -32,6 +32,10 @@ use cdk_common::payment::{
     MakePaymentResponse, MintPayment, OutgoingPaymentOptions, PaymentIdentifier,
     PaymentQuoteResponse, WaitPaymentResponse,
 };
+use cdk_common::price_oracle::{
+    self, CachedPriceOracle, ConversionDirection, FiatConverter, HttpJsonPriceOracle, Price,
+    PriceOracle, StaticPriceOracle,
+};
 use error::Error;
 use futures::stream::StreamExt;
 use futures::Stream;
@@ -54,134 +58,43 @@ const DEFAULT_REPAY_QUEUE_MAX_SIZE: usize = 100;
 /// Cache duration for exchange rate (5 minutes)
 const RATE_CACHE_DURATION: Duration = Duration::from_secs(300);
 
-/// Mempool.space prices API response structure
-#[derive(Debug, Deserialize)]
-struct MempoolPricesResponse {
-    #[serde(rename = "USD")]
-    usd: f64,
-    #[serde(rename = "EUR")]
-    eur: f64,
+/// Price oracle using mempool.space with built-in fallback rates
+struct FallbackPriceOracle {
+    oracle: CachedPriceOracle,
+    fallback: StaticPriceOracle,
 }
 
-/// Exchange rate cache with built-in fallback rates
-#[derive(Debug, Clone)]
-struct ExchangeRateCache {
-    rates: Arc<Mutex<Option<(MempoolPricesResponse, Instant)>>>,
-}
-
-impl ExchangeRateCache {
+impl FallbackPriceOracle {
     fn new() -> Self {
         Self {
-            rates: Arc::new(Mutex::new(None)),
+            oracle: CachedPriceOracle::new(
+                Arc::new(HttpJsonPriceOracle::mempool_space()),
+                RATE_CACHE_DURATION,
+                RATE_CACHE_DURATION,
+            ),
+            fallback: StaticPriceOracle::new(HashMap::from([
+                (CurrencyUnit::Usd, 110_000.0), // $110k per BTC
+                (CurrencyUnit::Eur, 95_000.0),  // €95k per BTC
+            ])),
         }
     }
+}
 
-    /// Get current BTC rate for the specified currency with caching and fallback
-    async fn get_btc_rate(&self, currency: &CurrencyUnit) -> Result<f64, Error> {
-        // Return cached rate if still valid
-        {
-            let cached_rates = self.rates.lock().await;
-            if let Some((rates, timestamp)) = &*cached_rates {
-                if timestamp.elapsed() < RATE_CACHE_DURATION {
-                    return Self::rate_for_currency(rates, currency);
-                }
-            }
-        }
-
-        // Try to fetch fresh rates, fallback on error
-        match self.fetch_fresh_rate(currency).await {
-            Ok(rate) => Ok(rate),
+#[async_trait]
+impl PriceOracle for FallbackPriceOracle {
+    async fn btc_price(&self, unit: &CurrencyUnit) -> Result<Price, price_oracle::Error> {
+        match self.oracle.btc_price(unit).await {
+            Ok(price) => Ok(price),
             Err(e) => {
                 tracing::warn!(
                     "Failed to fetch exchange rates, using fallback for {:?}: {}",
-                    currency,
+                    unit,
                     e
                 );
-                Self::fallback_rate(currency)
+                self.fallback.btc_price(unit).await
             }
         }
     }
-
-    /// Fetch fresh rate and update cache
-    async fn fetch_fresh_rate(&self, currency: &CurrencyUnit) -> Result<f64, Error> {
-        let url = "https://mempool.space/api/v1/prices";
-        let response = reqwest::get(url)
-            .await
-            .map_err(|_| Error::UnknownInvoiceAmount)?
-            .json::<MempoolPricesResponse>()
-            .await
-            .map_err(|_| Error::UnknownInvoiceAmount)?;
-
-        let rate = Self::rate_for_currency(&response, currency)?;
-        *self.rates.lock().await = Some((response, Instant::now()));
-        Ok(rate)
-    }
-
-    fn rate_for_currency(
-        rates: &MempoolPricesResponse,
-        currency: &CurrencyUnit,
-    ) -> Result<f64, Error> {
-        match currency {
-            CurrencyUnit::Usd => Ok(rates.usd),
-            CurrencyUnit::Eur => Ok(rates.eur),
-            _ => Err(Error::UnknownInvoiceAmount),
-        }
-    }
-
-    fn fallback_rate(currency: &CurrencyUnit) -> Result<f64, Error> {
-        match currency {
-            CurrencyUnit::Usd => Ok(110_000.0), // $110k per BTC
-            CurrencyUnit::Eur => Ok(95_000.0),  // €95k per BTC
-            _ => Err(Error::UnknownInvoiceAmount),
-        }
-    }
-}
-
-async fn convert_currency_amount(
-    amount: u64,
-    from_unit: &CurrencyUnit,
-    target_unit: &CurrencyUnit,
-    rate_cache: &ExchangeRateCache,
-) -> Result<Amount, Error> {
-    use CurrencyUnit::*;
-
-    // Try basic unit conversion first (handles SAT/MSAT and same-unit conversions)
-    if let Ok(converted) = to_unit(amount, from_unit, target_unit) {
-        return Ok(converted);
-    }
-
-    // Handle fiat <-> bitcoin conversions that require exchange rates
-    match (from_unit, target_unit) {
-        // Fiat to Bitcoin conversions
-        (Usd | Eur, Sat) => {
-            let rate = rate_cache.get_btc_rate(from_unit).await?;
-            let fiat_amount = amount as f64 / 100.0; // cents to dollars/euros
-            Ok(Amount::from(
-                (fiat_amount / rate * 100_000_000.0).round() as u64
-            )) // to sats
-        }
-        (Usd | Eur, Msat) => {
-            let rate = rate_cache.get_btc_rate(from_unit).await?;
-            let fiat_amount = amount as f64 / 100.0; // cents to dollars/euros
-            Ok(Amount::from(
-                (fiat_amount / rate * 100_000_000_000.0).round() as u64,
-            )) // to msats
-        }
-
-        // Bitcoin to fiat conversions
-        (Sat, Usd | Eur) => {
-            let rate = rate_cache.get_btc_rate(target_unit).await?;
-            let btc_amount = amount as f64 / 100_000_000.0; // sats to BTC
-            Ok(Amount::from((btc_amount * rate * 100.0).round() as u64)) // to cents
-        }
-        (Msat, Usd | Eur) => {
-            let rate = rate_cache.get_btc_rate(target_unit).await?;
-            let btc_amount = amount as f64 / 100_000_000_000.0; // msats to BTC
-            Ok(Amount::from((btc_amount * rate * 100.0).round() as u64)) // to cents
-        }
-
-        _ => Err(Error::UnknownInvoiceAmount), // Unsupported conversion
-    }
 }
 
 /// Secondary repayment queue manager for any-amount invoices
@@ -335,7 +248,7 @@ pub struct FakeWallet {
     incoming_payments: Arc<RwLock<HashMap<PaymentIdentifier, Vec<WaitPaymentResponse>>>>,
     unit: CurrencyUnit,
     secondary_repayment_queue: SecondaryRepaymentQueue,
-    exchange_rate_cache: ExchangeRateCache,
+    fiat_converter: FiatConverter,
 }
 
 impl FakeWallet {
@@ -384,9 +297,16 @@ impl FakeWallet {
             incoming_payments,
             unit,
             secondary_repayment_queue,
-            exchange_rate_cache: ExchangeRateCache::new(),
+            fiat_converter: FiatConverter::new(Arc::new(FallbackPriceOracle::new()), 0.0)
+                .expect("Zero spread is valid"),
         }
     }
+
+    /// Use `fiat_converter` to quote fiat units instead of the mempool.space rates
+    pub fn with_fiat_converter(mut self, fiat_converter: FiatConverter) -> Self {
+        self.fiat_converter = fiat_converter;
+        self
+    }
 }
 
 /// Struct for signaling what methods should respond via invoice description
@@ -423,7 +343,8 @@ impl MintPayment for FakeWallet {
             mpp: true,
             unit: self.unit.clone(),
             invoice_description: true,
-            amountless: false,
+            invoice_description_hash: true,
+            amountless: true,
             bolt12: true,
         })?)
     }
@@ -512,13 +433,15 @@ impl MintPayment for FakeWallet {
             }
         };
 
-        let amount = convert_currency_amount(
-            amount_msat,
-            &CurrencyUnit::Msat,
-            unit,
-            &self.exchange_rate_cache,
-        )
-        .await?;
+        let amount = self
+            .fiat_converter
+            .convert(
+                amount_msat.into(),
+                &CurrencyUnit::Msat,
+                unit,
+                ConversionDirection::Outgoing,
+            )
+            .await?;
 
         let relative_fee_reserve =
             (self.fee_reserve.percent_fee_reserve * u64::from(amount) as f32) as u64;
@@ -589,13 +512,15 @@ impl MintPayment for FakeWallet {
                     ensure_cdk!(!description.pay_err, Error::UnknownInvoice.into());
                 }
 
-                let total_spent = convert_currency_amount(
-                    amount_msat,
-                    &CurrencyUnit::Msat,
-                    unit,
-                    &self.exchange_rate_cache,
-                )
-                .await?;
+                let total_spent = self
+                    .fiat_converter
+                    .convert(
+                        amount_msat.into(),
+                        &CurrencyUnit::Msat,
+                        unit,
+                        ConversionDirection::Outgoing,
+                    )
+                    .await?;
 
                 Ok(MakePaymentResponse {
                     payment_proof: Some("".to_string()),
@@ -620,13 +545,15 @@ impl MintPayment for FakeWallet {
                     }
                 };
 
-                let total_spent = convert_currency_amount(
-                    amount_msat,
-                    &CurrencyUnit::Msat,
-                    unit,
-                    &self.exchange_rate_cache,
-                )
-                .await?;
+                let total_spent = self
+                    .fiat_converter
+                    .convert(
+                        amount_msat.into(),
+                        &CurrencyUnit::Msat,
+                        unit,
+                        ConversionDirection::Outgoing,
+                    )
+                    .await?;
 
                 Ok(MakePaymentResponse {
                     payment_proof: Some("".to_string()),
@@ -659,13 +586,15 @@ impl MintPayment for FakeWallet {
 
                 let offer_builder = match amount {
                     Some(amount) => {
-                        let amount_msat = convert_currency_amount(
-                            u64::from(amount),
-                            unit,
-                            &CurrencyUnit::Msat,
-                            &self.exchange_rate_cache,
-                        )
-                        .await?;
+                        let amount_msat = self
+                            .fiat_converter
+                            .convert(
+                                amount,
+                                unit,
+                                &CurrencyUnit::Msat,
+                                ConversionDirection::Incoming,
+                            )
+                            .await?;
                         offer_builder.amount_msats(amount_msat.into())
                     }
                     None => offer_builder,
@@ -685,16 +614,23 @@ impl MintPayment for FakeWallet {
                 let amount = bolt11_options.amount;
                 let expiry = bolt11_options.unix_expiry;
 
-                let amount_msat = convert_currency_amount(
-                    u64::from(amount),
-                    unit,
-                    &CurrencyUnit::Msat,
-                    &self.exchange_rate_cache,
-                )
-                .await?
-                .into();
-
-                let invoice = create_fake_invoice(amount_msat, description.clone());
+                let amount_msat = self
+                    .fiat_converter
+                    .convert(
+                        amount,
+                        unit,
+                        &CurrencyUnit::Msat,
+                        ConversionDirection::Incoming,
+                    )
+                    .await?
+                    .into();
+
+                let invoice = match bolt11_options.description_hash {
+                    Some(description_hash) => {
+                        create_fake_invoice_with_description_hash(amount_msat, description_hash)
+                    }
+                    None => create_fake_invoice(amount_msat, description.clone()),
+                };
                 let payment_hash = invoice.payment_hash();
 
                 (
@@ -811,31 +747,55 @@ impl MintPayment for FakeWallet {
 /// Create fake invoice
 #[instrument]
 pub fn create_fake_invoice(amount_msat: u64, description: String) -> Bolt11Invoice {
-    let private_key = SecretKey::from_slice(
+    let private_key = fake_invoice_private_key();
+
+    InvoiceBuilder::new(Currency::Bitcoin)
+        .description(description)
+        .payment_hash(random_payment_hash())
+        .payment_secret(PaymentSecret([42u8; 32]))
+        .amount_milli_satoshis(amount_msat)
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
+        .unwrap()
+}
+
+/// Create fake invoice committing to a description hash
+#[instrument]
+pub fn create_fake_invoice_with_description_hash(
+    amount_msat: u64,
+    description_hash: sha256::Hash,
+) -> Bolt11Invoice {
+    let private_key = fake_invoice_private_key();
+
+    InvoiceBuilder::new(Currency::Bitcoin)
+        .description_hash(description_hash)
+        .payment_hash(random_payment_hash())
+        .payment_secret(PaymentSecret([42u8; 32]))
+        .amount_milli_satoshis(amount_msat)
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
+        .unwrap()
+}
+
+fn fake_invoice_private_key() -> SecretKey {
+    SecretKey::from_slice(
         &[
             0xe1, 0x26, 0xf6, 0x8f, 0x7e, 0xaf, 0xcc, 0x8b, 0x74, 0xf5, 0x4d, 0x26, 0x9f, 0xe2,
             0x06, 0xbe, 0x71, 0x50, 0x00, 0xf9, 0x4d, 0xac, 0x06, 0x7d, 0x1c, 0x04, 0xa8, 0xca,
             0x3b, 0x2d, 0xb7, 0x34,
         ][..],
     )
-    .unwrap();
+    .unwrap()
+}
 
+fn random_payment_hash() -> sha256::Hash {
     use bitcoin::secp256k1::rand::rngs::OsRng;
     use bitcoin::secp256k1::rand::Rng;
     let mut rng = OsRng;
     let mut random_bytes = [0u8; 32];
     rng.fill(&mut random_bytes);
 
-    let payment_hash = sha256::Hash::from_slice(&random_bytes).unwrap();
-    let payment_secret = PaymentSecret([42u8; 32]);
-
-    InvoiceBuilder::new(Currency::Bitcoin)
-        .description(description)
-        .payment_hash(payment_hash)
-        .payment_secret(payment_secret)
-        .amount_milli_satoshis(amount_msat)
-        .current_timestamp()
-        .min_final_cltv_expiry_delta(144)
-        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
-        .unwrap()
+    sha256::Hash::from_slice(&random_bytes).unwrap()
 }
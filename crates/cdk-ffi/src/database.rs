@@ -378,6 +378,9 @@ impl CdkWalletDatabase for WalletDatabaseBridge {
 
     // Keys Management
     async fn add_keys(&self, keyset: cdk::nuts::KeySet) -> Result<(), Self::Err> {
+        // Host databases are not expected to verify keys, so check the id before storing them
+        keyset.verify_id()?;
+
         let ffi_keyset: KeySet = keyset.into();
         self.ffi_db
             .add_keys(ffi_keyset)
@@ -615,3 +618,332 @@ pub fn create_cdk_database_from_ffi(
 ) -> Arc<dyn CdkWalletDatabase<Err = cdk::cdk_database::Error> + Send + Sync> {
     Arc::new(WalletDatabaseBridge::new(ffi_db))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use cdk_common::wallet_db_test;
+
+    use super::*;
+
+    /// In-memory database working only on FFI types, like a host-language implementation
+    #[derive(Default)]
+    struct MockWalletDatabase {
+        mints: Mutex<HashMap<MintUrl, Option<MintInfo>>>,
+        keysets: Mutex<Vec<(MintUrl, KeySetInfo)>>,
+        mint_quotes: Mutex<HashMap<String, MintQuote>>,
+        melt_quotes: Mutex<HashMap<String, MeltQuote>>,
+        keys: Mutex<HashMap<String, Keys>>,
+        proofs: Mutex<HashMap<String, ProofInfo>>,
+        counters: Mutex<HashMap<String, u32>>,
+        transactions: Mutex<HashMap<String, Transaction>>,
+    }
+
+    fn proof_matches(
+        info: &ProofInfo,
+        mint_url: &Option<MintUrl>,
+        unit: &Option<CurrencyUnit>,
+        state: &Option<Vec<ProofState>>,
+    ) -> bool {
+        mint_url.as_ref().is_none_or(|url| &info.mint_url == url)
+            && unit.as_ref().is_none_or(|unit| &info.unit == unit)
+            && state
+                .as_ref()
+                .is_none_or(|states| states.is_empty() || states.contains(&info.state))
+    }
+
+    #[async_trait::async_trait]
+    impl WalletDatabase for MockWalletDatabase {
+        async fn add_mint(
+            &self,
+            mint_url: MintUrl,
+            mint_info: Option<MintInfo>,
+        ) -> Result<(), FfiError> {
+            self.mints.lock().unwrap().insert(mint_url, mint_info);
+            Ok(())
+        }
+
+        async fn remove_mint(&self, mint_url: MintUrl) -> Result<(), FfiError> {
+            self.mints.lock().unwrap().remove(&mint_url);
+            Ok(())
+        }
+
+        async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, FfiError> {
+            Ok(self.mints.lock().unwrap().get(&mint_url).cloned().flatten())
+        }
+
+        async fn get_mints(&self) -> Result<HashMap<MintUrl, Option<MintInfo>>, FfiError> {
+            Ok(self.mints.lock().unwrap().clone())
+        }
+
+        async fn update_mint_url(
+            &self,
+            old_mint_url: MintUrl,
+            new_mint_url: MintUrl,
+        ) -> Result<(), FfiError> {
+            for quote in self.mint_quotes.lock().unwrap().values_mut() {
+                if quote.mint_url == old_mint_url {
+                    quote.mint_url = new_mint_url.clone();
+                }
+            }
+            for info in self.proofs.lock().unwrap().values_mut() {
+                if info.mint_url == old_mint_url {
+                    info.mint_url = new_mint_url.clone();
+                }
+            }
+            Ok(())
+        }
+
+        async fn add_mint_keysets(
+            &self,
+            mint_url: MintUrl,
+            keysets: Vec<KeySetInfo>,
+        ) -> Result<(), FfiError> {
+            let mut stored = self.keysets.lock().unwrap();
+            for keyset in keysets {
+                match stored.iter_mut().find(|(_, k)| k.id == keyset.id) {
+                    Some((_, existing)) => {
+                        existing.active = keyset.active;
+                        existing.input_fee_ppk = keyset.input_fee_ppk;
+                    }
+                    None => stored.push((mint_url.clone(), keyset)),
+                }
+            }
+            Ok(())
+        }
+
+        async fn get_mint_keysets(
+            &self,
+            mint_url: MintUrl,
+        ) -> Result<Option<Vec<KeySetInfo>>, FfiError> {
+            let keysets = self
+                .keysets
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(url, _)| url == &mint_url)
+                .map(|(_, keyset)| keyset.clone())
+                .collect::<Vec<_>>();
+            Ok((!keysets.is_empty()).then_some(keysets))
+        }
+
+        async fn get_keyset_by_id(&self, keyset_id: Id) -> Result<Option<KeySetInfo>, FfiError> {
+            Ok(self
+                .keysets
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(_, keyset)| keyset.id == keyset_id.hex)
+                .map(|(_, keyset)| keyset.clone()))
+        }
+
+        async fn add_mint_quote(&self, quote: MintQuote) -> Result<(), FfiError> {
+            self.mint_quotes
+                .lock()
+                .unwrap()
+                .insert(quote.id.clone(), quote);
+            Ok(())
+        }
+
+        async fn get_mint_quote(&self, quote_id: String) -> Result<Option<MintQuote>, FfiError> {
+            Ok(self.mint_quotes.lock().unwrap().get(&quote_id).cloned())
+        }
+
+        async fn get_mint_quotes(&self) -> Result<Vec<MintQuote>, FfiError> {
+            Ok(self.mint_quotes.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn remove_mint_quote(&self, quote_id: String) -> Result<(), FfiError> {
+            self.mint_quotes.lock().unwrap().remove(&quote_id);
+            Ok(())
+        }
+
+        async fn add_melt_quote(&self, quote: MeltQuote) -> Result<(), FfiError> {
+            self.melt_quotes
+                .lock()
+                .unwrap()
+                .insert(quote.id.clone(), quote);
+            Ok(())
+        }
+
+        async fn get_melt_quote(&self, quote_id: String) -> Result<Option<MeltQuote>, FfiError> {
+            Ok(self.melt_quotes.lock().unwrap().get(&quote_id).cloned())
+        }
+
+        async fn get_melt_quotes(&self) -> Result<Vec<MeltQuote>, FfiError> {
+            Ok(self.melt_quotes.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn remove_melt_quote(&self, quote_id: String) -> Result<(), FfiError> {
+            self.melt_quotes.lock().unwrap().remove(&quote_id);
+            Ok(())
+        }
+
+        async fn add_keys(&self, keyset: KeySet) -> Result<(), FfiError> {
+            self.keys.lock().unwrap().insert(
+                keyset.id.clone(),
+                Keys {
+                    id: keyset.id,
+                    unit: keyset.unit,
+                    keys: keyset.keys,
+                },
+            );
+            Ok(())
+        }
+
+        async fn get_keys(&self, id: Id) -> Result<Option<Keys>, FfiError> {
+            Ok(self.keys.lock().unwrap().get(&id.hex).cloned())
+        }
+
+        async fn remove_keys(&self, id: Id) -> Result<(), FfiError> {
+            self.keys.lock().unwrap().remove(&id.hex);
+            Ok(())
+        }
+
+        async fn update_proofs(
+            &self,
+            added: Vec<ProofInfo>,
+            removed_ys: Vec<PublicKey>,
+        ) -> Result<(), FfiError> {
+            let mut proofs = self.proofs.lock().unwrap();
+            for info in added {
+                proofs.insert(info.y.hex.clone(), info);
+            }
+            for y in removed_ys {
+                proofs.remove(&y.hex);
+            }
+            Ok(())
+        }
+
+        async fn get_proofs(
+            &self,
+            mint_url: Option<MintUrl>,
+            unit: Option<CurrencyUnit>,
+            state: Option<Vec<ProofState>>,
+            spending_conditions: Option<Vec<SpendingConditions>>,
+        ) -> Result<Vec<ProofInfo>, FfiError> {
+            // Hosts have no way to compare conditions other than their serialized form
+            let conditions = spending_conditions
+                .map(|conditions| {
+                    conditions
+                        .iter()
+                        .map(serde_json::to_string)
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?;
+
+            let mut result = Vec::new();
+            for info in self.proofs.lock().unwrap().values() {
+                if !proof_matches(info, &mint_url, &unit, &state) {
+                    continue;
+                }
+                if let Some(conditions) = &conditions {
+                    let Some(condition) = &info.spending_condition else {
+                        continue;
+                    };
+                    if !conditions.contains(&serde_json::to_string(condition)?) {
+                        continue;
+                    }
+                }
+                result.push(info.clone());
+            }
+            Ok(result)
+        }
+
+        async fn get_balance(
+            &self,
+            mint_url: Option<MintUrl>,
+            unit: Option<CurrencyUnit>,
+            state: Option<Vec<ProofState>>,
+        ) -> Result<u64, FfiError> {
+            Ok(self
+                .proofs
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|info| proof_matches(info, &mint_url, &unit, &state))
+                .map(|info| info.proof.amount.value)
+                .sum())
+        }
+
+        async fn update_proofs_state(
+            &self,
+            ys: Vec<PublicKey>,
+            state: ProofState,
+        ) -> Result<(), FfiError> {
+            let mut proofs = self.proofs.lock().unwrap();
+            for y in ys {
+                if let Some(info) = proofs.get_mut(&y.hex) {
+                    info.state = state.clone();
+                }
+            }
+            Ok(())
+        }
+
+        async fn increment_keyset_counter(
+            &self,
+            keyset_id: Id,
+            count: u32,
+        ) -> Result<u32, FfiError> {
+            let mut counters = self.counters.lock().unwrap();
+            let counter = counters.entry(keyset_id.hex).or_default();
+            *counter += count;
+            Ok(*counter)
+        }
+
+        async fn add_transaction(&self, transaction: Transaction) -> Result<(), FfiError> {
+            self.transactions
+                .lock()
+                .unwrap()
+                .insert(transaction.id.hex.clone(), transaction);
+            Ok(())
+        }
+
+        async fn get_transaction(
+            &self,
+            transaction_id: TransactionId,
+        ) -> Result<Option<Transaction>, FfiError> {
+            Ok(self
+                .transactions
+                .lock()
+                .unwrap()
+                .get(&transaction_id.hex)
+                .cloned())
+        }
+
+        async fn list_transactions(
+            &self,
+            mint_url: Option<MintUrl>,
+            direction: Option<TransactionDirection>,
+            unit: Option<CurrencyUnit>,
+        ) -> Result<Vec<Transaction>, FfiError> {
+            Ok(self
+                .transactions
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|tx| {
+                    mint_url.as_ref().is_none_or(|url| &tx.mint_url == url)
+                        && direction.as_ref().is_none_or(|d| &tx.direction == d)
+                        && unit.as_ref().is_none_or(|unit| &tx.unit == unit)
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn remove_transaction(&self, transaction_id: TransactionId) -> Result<(), FfiError> {
+            self.transactions
+                .lock()
+                .unwrap()
+                .remove(&transaction_id.hex);
+            Ok(())
+        }
+    }
+
+    async fn provide_db(_test_name: String) -> WalletDatabaseBridge {
+        WalletDatabaseBridge::new(Arc::new(MockWalletDatabase::default()))
+    }
+
+    wallet_db_test!(provide_db);
+}
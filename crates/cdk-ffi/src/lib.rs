@@ -289,4 +289,84 @@ mod tests {
         let invalid_result = mnemonic_to_entropy("invalid mnemonic".to_string());
         assert!(invalid_result.is_err());
     }
+
+    #[test]
+    fn test_send_options_with_invalid_conditions() {
+        let options = SendOptions {
+            conditions: Some(SpendingConditions::P2PK {
+                pubkey: "not-a-pubkey".to_string(),
+                conditions: None,
+            }),
+            ..Default::default()
+        };
+
+        // Must fail instead of sending an unlocked token
+        let result: Result<cdk::wallet::SendOptions, FfiError> = options.try_into();
+        assert!(result.is_err());
+
+        let options = MultiMintSendOptions {
+            send_options: SendOptions {
+                conditions: Some(SpendingConditions::P2PK {
+                    pubkey: "not-a-pubkey".to_string(),
+                    conditions: None,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result: Result<cdk::wallet::multi_mint_wallet::MultiMintSendOptions, FfiError> =
+            options.try_into();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_payment_request_round_trip() {
+        use cdk::nuts::nut18::{
+            PaymentRequest as CdkPaymentRequest, Transport as CdkTransport,
+            TransportType as CdkTransportType,
+        };
+
+        let cdk_request = CdkPaymentRequest {
+            payment_id: Some("b7a90176".to_string()),
+            amount: Some(10.into()),
+            unit: Some(cdk::nuts::CurrencyUnit::Sat),
+            single_use: Some(true),
+            mints: Some(vec!["https://mint.example.com".parse().unwrap()]),
+            description: Some("Coffee".to_string()),
+            transports: vec![CdkTransport {
+                _type: CdkTransportType::HttpPost,
+                target: "https://example.com/pay".to_string(),
+                tags: None,
+            }],
+            nut10: None,
+        };
+
+        let request = PaymentRequest::from_string(cdk_request.to_string()).unwrap();
+        assert_eq!(request.payment_id(), Some("b7a90176".to_string()));
+        assert_eq!(request.amount(), Some(Amount::new(10)));
+        assert_eq!(request.unit(), Some(CurrencyUnit::Sat));
+        assert_eq!(request.single_use(), Some(true));
+        assert_eq!(request.mints().unwrap()[0].url, "https://mint.example.com");
+        assert_eq!(request.description(), Some("Coffee".to_string()));
+
+        let transports = request.transports();
+        assert_eq!(transports.len(), 1);
+        assert_eq!(transports[0].transport_type, TransportType::HttpPost);
+        assert_eq!(transports[0].target, "https://example.com/pay");
+
+        assert_eq!(request.encode(), cdk_request.to_string());
+        assert!(PaymentRequest::from_string("creqAnot-a-request".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_create_request_params_default() {
+        let params = CreateRequestParams::default();
+        assert!(params.amount.is_none());
+        assert_eq!(params.unit, "sat");
+        assert_eq!(params.num_sigs, 1);
+        assert_eq!(params.transport, "none");
+
+        let cdk_params: cdk::payment_request::CreateRequestParams = params.into();
+        assert!(cdk_params.nostr_relays.is_none());
+    }
 }
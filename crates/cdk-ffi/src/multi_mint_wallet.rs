@@ -10,6 +10,7 @@ use cdk::wallet::multi_mint_wallet::{
     MultiMintSendOptions as CdkMultiMintSendOptions, MultiMintWallet as CdkMultiMintWallet,
     TransferMode as CdkTransferMode, TransferResult as CdkTransferResult,
 };
+use futures::StreamExt;
 
 use crate::error::FfiError;
 use crate::token::Token;
@@ -211,7 +212,7 @@ impl MultiMintWallet {
         let cdk_mint_url: cdk::mint_url::MintUrl = mint_url.try_into()?;
         let prepared = self
             .inner
-            .prepare_send(cdk_mint_url, amount.into(), options.into())
+            .prepare_send(cdk_mint_url, amount.into(), options.try_into()?)
             .await?;
         Ok(Arc::new(prepared.into()))
     }
@@ -393,6 +394,290 @@ impl MultiMintWallet {
     }
 }
 
+impl MultiMintWallet {
+    /// Get the wallet of a mint that has been added
+    async fn get_wallet(&self, mint_url: MintUrl) -> Result<cdk::Wallet, FfiError> {
+        let cdk_mint_url: cdk::mint_url::MintUrl = mint_url.try_into()?;
+        self.inner.get_wallet(&cdk_mint_url).await.ok_or_else(|| {
+            cdk::Error::UnknownMint {
+                mint_url: cdk_mint_url.to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+/// Bolt12, MPP, payment request and subscription methods for MultiMintWallet
+#[uniffi::export(async_runtime = "tokio")]
+impl MultiMintWallet {
+    /// Get a bolt12 mint quote from a specific mint
+    pub async fn mint_bolt12_quote(
+        &self,
+        mint_url: MintUrl,
+        amount: Option<Amount>,
+        description: Option<String>,
+    ) -> Result<MintQuote, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let quote = wallet
+            .mint_bolt12_quote(amount.map(Into::into), description)
+            .await?;
+        Ok(quote.into())
+    }
+
+    /// Mint tokens using a bolt12 quote at a specific mint
+    pub async fn mint_bolt12(
+        &self,
+        mint_url: MintUrl,
+        quote_id: String,
+        amount: Option<Amount>,
+        amount_split_target: SplitTarget,
+        spending_conditions: Option<SpendingConditions>,
+    ) -> Result<Proofs, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let conditions = spending_conditions.map(|sc| sc.try_into()).transpose()?;
+
+        let proofs = wallet
+            .mint_bolt12(
+                &quote_id,
+                amount.map(Into::into),
+                amount_split_target.into(),
+                conditions,
+            )
+            .await?;
+        Ok(proofs.into_iter().map(|p| p.into()).collect())
+    }
+
+    /// Get a bolt12 melt quote from a specific mint
+    pub async fn melt_bolt12_quote(
+        &self,
+        mint_url: MintUrl,
+        request: String,
+        options: Option<MeltOptions>,
+    ) -> Result<MeltQuote, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let quote = wallet
+            .melt_bolt12_quote(request, options.map(Into::into))
+            .await?;
+        Ok(quote.into())
+    }
+
+    /// Melt using a quote from a specific mint
+    pub async fn melt_with_mint(
+        &self,
+        mint_url: MintUrl,
+        quote_id: String,
+    ) -> Result<Melted, FfiError> {
+        let cdk_mint_url: cdk::mint_url::MintUrl = mint_url.try_into()?;
+        let melted = self.inner.melt_with_mint(&cdk_mint_url, &quote_id).await?;
+        Ok(melted.into())
+    }
+
+    /// Get MPP melt quotes for a bolt11 invoice, splitting it across mints
+    pub async fn mpp_melt_quote(
+        &self,
+        bolt11: String,
+        mint_amounts: Vec<MintAmount>,
+    ) -> Result<Vec<MppMeltQuote>, FfiError> {
+        let mint_amounts = mint_amounts
+            .into_iter()
+            .map(|mint_amount| {
+                let mint_url: cdk::mint_url::MintUrl = mint_amount.mint_url.try_into()?;
+                Ok((mint_url, mint_amount.amount.into()))
+            })
+            .collect::<Result<Vec<_>, FfiError>>()?;
+
+        let quotes = self.inner.mpp_melt_quote(bolt11, mint_amounts).await?;
+        Ok(quotes
+            .into_iter()
+            .map(|(mint_url, quote)| MppMeltQuote {
+                mint_url: mint_url.into(),
+                quote: quote.into(),
+            })
+            .collect())
+    }
+
+    /// Pay the MPP melt quotes returned by `mpp_melt_quote`
+    pub async fn mpp_melt(&self, quotes: Vec<MppMeltQuote>) -> Result<Vec<MppMelted>, FfiError> {
+        let quotes = quotes
+            .into_iter()
+            .map(|mpp_quote| {
+                let mint_url: cdk::mint_url::MintUrl = mpp_quote.mint_url.try_into()?;
+                Ok((mint_url, mpp_quote.quote.id))
+            })
+            .collect::<Result<Vec<_>, FfiError>>()?;
+
+        let melted = self.inner.mpp_melt(quotes).await?;
+        Ok(melted
+            .into_iter()
+            .map(|(mint_url, melted)| MppMelted {
+                mint_url: mint_url.into(),
+                melted: melted.into(),
+            })
+            .collect())
+    }
+
+    /// Verify that a token is locked to the given P2PK conditions
+    pub async fn verify_token_p2pk(
+        &self,
+        token: Arc<Token>,
+        conditions: SpendingConditions,
+    ) -> Result<(), FfiError> {
+        let cdk_token = token.inner.clone();
+        self.inner
+            .verify_token_p2pk(&cdk_token, conditions.try_into()?)
+            .await?;
+        Ok(())
+    }
+
+    /// Revert a transaction of a specific mint
+    pub async fn revert_transaction(
+        &self,
+        mint_url: MintUrl,
+        id: TransactionId,
+    ) -> Result<(), FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        wallet.revert_transaction(id.try_into()?).await?;
+        Ok(())
+    }
+
+    /// Subscribe to events of a specific mint
+    pub async fn subscribe(
+        &self,
+        mint_url: MintUrl,
+        params: SubscribeParams,
+    ) -> Result<Arc<ActiveSubscription>, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let cdk_params: cdk::nuts::nut17::Params<Arc<String>> = params.into();
+        let sub_id = cdk_params.id.to_string();
+        let active_sub = wallet.subscribe(cdk_params).await;
+        Ok(Arc::new(ActiveSubscription::new(active_sub, sub_id)))
+    }
+
+    /// Mint the proofs of paid mint quotes as they get paid
+    ///
+    /// The quotes must have been created by this wallet. Proofs are passed to the listener as
+    /// each quote is minted, until the stream errors or the returned handle is cancelled.
+    pub async fn mint_proof_stream(
+        &self,
+        mint_url: MintUrl,
+        quote_ids: Vec<String>,
+        amount_split_target: SplitTarget,
+        spending_conditions: Option<SpendingConditions>,
+        listener: Arc<dyn ProofStreamListener>,
+    ) -> Result<Arc<ProofStreamHandle>, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let conditions = spending_conditions.map(|sc| sc.try_into()).transpose()?;
+
+        let mut quotes = Vec::with_capacity(quote_ids.len());
+        for quote_id in quote_ids {
+            let quote = wallet
+                .localstore
+                .get_mint_quote(&quote_id)
+                .await
+                .map_err(|e| FfiError::Database { msg: e.to_string() })?
+                .ok_or(cdk::Error::UnknownQuote)?;
+            quotes.push(quote);
+        }
+
+        let split_target = amount_split_target.into();
+        let task = tokio::spawn(async move {
+            let mut stream = wallet.mints_proof_stream(quotes, split_target, conditions);
+
+            while let Some(result) = stream.next().await {
+                match result {
+                    Ok((quote, proofs)) => {
+                        listener
+                            .on_proofs(quote.id, proofs.into_iter().map(Into::into).collect())
+                            .await;
+                    }
+                    Err(err) => {
+                        listener.on_error(err.to_string()).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Arc::new(ProofStreamHandle {
+            task: task.abort_handle(),
+        }))
+    }
+
+    /// Create a NUT-18 payment request for the mints of this wallet
+    ///
+    /// Only the "http" and "none" transports are supported, as the bindings are built without
+    /// nostr.
+    pub async fn create_request(
+        &self,
+        params: CreateRequestParams,
+    ) -> Result<Arc<PaymentRequest>, FfiError> {
+        if params.transport.eq_ignore_ascii_case("nostr") {
+            return Err(FfiError::Generic {
+                msg: "Nostr transport is not supported".to_string(),
+            });
+        }
+
+        let payment_request = self
+            .inner
+            .create_request(params.into())
+            .await?
+            .into_payment_request();
+        Ok(Arc::new(payment_request.into()))
+    }
+
+    /// Pay a NUT-18 payment request
+    ///
+    /// Pays from the first wallet whose mint and unit match the request and which can send the
+    /// amount with its fees, and returns its mint. `custom_amount` is required when the request
+    /// has no amount.
+    pub async fn pay_request(
+        &self,
+        payment_request: Arc<PaymentRequest>,
+        custom_amount: Option<Amount>,
+    ) -> Result<MintUrl, FfiError> {
+        let mint_url = self
+            .inner
+            .pay_request(payment_request.inner.clone(), custom_amount.map(Into::into))
+            .await?;
+        Ok(mint_url.into())
+    }
+}
+
+/// BIP353 methods for MultiMintWallet
+#[cfg(not(target_arch = "wasm32"))]
+#[uniffi::export(async_runtime = "tokio")]
+impl MultiMintWallet {
+    /// Get a quote for a BIP353 melt from a specific mint
+    pub async fn melt_bip353_quote(
+        &self,
+        mint_url: MintUrl,
+        bip353_address: String,
+        amount_msat: Amount,
+    ) -> Result<MeltQuote, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let cdk_amount: cdk::Amount = amount_msat.into();
+        let quote = wallet
+            .melt_bip353_quote(&bip353_address, cdk_amount)
+            .await?;
+        Ok(quote.into())
+    }
+
+    /// Get a quote for a Lightning address melt from a specific mint
+    pub async fn melt_lightning_address_quote(
+        &self,
+        mint_url: MintUrl,
+        lightning_address: String,
+        amount_msat: Amount,
+    ) -> Result<MeltQuote, FfiError> {
+        let wallet = self.get_wallet(mint_url).await?;
+        let cdk_amount: cdk::Amount = amount_msat.into();
+        let quote = wallet
+            .melt_lightning_address_quote(&lightning_address, cdk_amount)
+            .await?;
+        Ok(quote.into())
+    }
+}
+
 /// Auth methods for MultiMintWallet
 #[uniffi::export(async_runtime = "tokio")]
 impl MultiMintWallet {
@@ -448,6 +733,83 @@ impl MultiMintWallet {
     }
 }
 
+/// Amount to melt from a mint in an MPP payment
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct MintAmount {
+    /// Mint to melt from
+    pub mint_url: MintUrl,
+    /// Amount to melt
+    pub amount: Amount,
+}
+
+/// Melt quote of a mint in an MPP payment
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct MppMeltQuote {
+    /// Mint of the quote
+    pub mint_url: MintUrl,
+    /// Melt quote
+    pub quote: MeltQuote,
+}
+
+/// Result of the melt of a mint in an MPP payment
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct MppMelted {
+    /// Mint that melted
+    pub mint_url: MintUrl,
+    /// Melt result
+    pub melted: Melted,
+}
+
+/// Listener of a mint proof stream, implemented by the foreign language
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait ProofStreamListener: Send + Sync {
+    /// Called with the proofs minted for a quote
+    async fn on_proofs(&self, quote_id: String, proofs: Proofs);
+
+    /// Called when the stream fails, no more proofs follow
+    async fn on_error(&self, msg: String);
+}
+
+/// Handle of a running mint proof stream
+#[derive(uniffi::Object)]
+pub struct ProofStreamHandle {
+    task: tokio::task::AbortHandle,
+}
+
+#[uniffi::export]
+impl ProofStreamHandle {
+    /// Stop the stream
+    pub fn cancel(&self) {
+        self.task.abort();
+    }
+
+    /// Whether the stream has stopped
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// Payment request returned by `cdk::MultiMintWallet::create_request`
+///
+/// Builds that unify cdk with the `nostr` feature, e.g. the workspace, also return the nostr
+/// wait info, which the bindings never need as they reject the nostr transport.
+trait IntoPaymentRequest {
+    fn into_payment_request(self) -> cdk::nuts::PaymentRequest;
+}
+
+impl IntoPaymentRequest for cdk::nuts::PaymentRequest {
+    fn into_payment_request(self) -> cdk::nuts::PaymentRequest {
+        self
+    }
+}
+
+impl<T> IntoPaymentRequest for (cdk::nuts::PaymentRequest, Option<T>) {
+    fn into_payment_request(self) -> cdk::nuts::PaymentRequest {
+        self.0
+    }
+}
+
 /// Transfer mode for mint-to-mint transfers
 #[derive(Debug, Clone, uniffi::Enum)]
 pub enum TransferMode {
@@ -529,23 +891,25 @@ pub struct MultiMintSendOptions {
     pub send_options: SendOptions,
 }
 
-impl From<MultiMintSendOptions> for CdkMultiMintSendOptions {
-    fn from(options: MultiMintSendOptions) -> Self {
+impl TryFrom<MultiMintSendOptions> for CdkMultiMintSendOptions {
+    type Error = FfiError;
+
+    fn try_from(options: MultiMintSendOptions) -> Result<Self, Self::Error> {
         let mut opts = CdkMultiMintSendOptions::new();
         opts.allow_transfer = options.allow_transfer;
         opts.max_transfer_amount = options.max_transfer_amount.map(Into::into);
         opts.allowed_mints = options
             .allowed_mints
             .into_iter()
-            .filter_map(|url| url.try_into().ok())
-            .collect();
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
         opts.excluded_mints = options
             .excluded_mints
             .into_iter()
-            .filter_map(|url| url.try_into().ok())
-            .collect();
-        opts.send_options = options.send_options.into();
-        opts
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        opts.send_options = options.send_options.try_into()?;
+        Ok(opts)
     }
 }
 
@@ -170,13 +170,29 @@ pub struct MintMethodSettings {
     pub max_amount: Option<Amount>,
     /// For bolt11, whether mint supports setting invoice description
     pub description: Option<bool>,
+    /// For bolt11, whether mint supports setting invoice description hash
+    pub description_hash: Option<bool>,
+    /// For bolt11, min quote expiry in seconds a wallet can request
+    pub min_expiry: Option<u64>,
+    /// For bolt11, max quote expiry in seconds a wallet can request
+    pub max_expiry: Option<u64>,
 }
 
 impl From<cdk::nuts::nut04::MintMethodSettings> for MintMethodSettings {
     fn from(s: cdk::nuts::nut04::MintMethodSettings) -> Self {
-        let description = match s.options {
-            Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 { description }) => Some(description),
-            _ => None,
+        let (description, description_hash, min_expiry, max_expiry) = match s.options {
+            Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 {
+                description,
+                description_hash,
+                min_expiry,
+                max_expiry,
+            }) => (
+                Some(description),
+                Some(description_hash),
+                min_expiry,
+                max_expiry,
+            ),
+            _ => (None, None, None, None),
         };
         Self {
             method: s.method.into(),
@@ -184,6 +200,9 @@ impl From<cdk::nuts::nut04::MintMethodSettings> for MintMethodSettings {
             min_amount: s.min_amount.map(Into::into),
             max_amount: s.max_amount.map(Into::into),
             description,
+            description_hash,
+            min_expiry,
+            max_expiry,
         }
     }
 }
@@ -192,9 +211,18 @@ impl TryFrom<MintMethodSettings> for cdk::nuts::nut04::MintMethodSettings {
     type Error = FfiError;
 
     fn try_from(s: MintMethodSettings) -> Result<Self, Self::Error> {
-        let options = match (s.method.clone(), s.description) {
-            (PaymentMethod::Bolt11, Some(description)) => {
-                Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 { description })
+        let has_options = s.description.is_some()
+            || s.description_hash.is_some()
+            || s.min_expiry.is_some()
+            || s.max_expiry.is_some();
+        let options = match &s.method {
+            PaymentMethod::Bolt11 if has_options => {
+                Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 {
+                    description: s.description.unwrap_or_default(),
+                    description_hash: s.description_hash.unwrap_or_default(),
+                    min_expiry: s.min_expiry,
+                    max_expiry: s.max_expiry,
+                })
             }
             _ => None,
         };
@@ -560,6 +588,7 @@ impl TryFrom<Nuts> for cdk::nuts::Nuts {
             },
             nut21: n.nut21.map(|s| s.try_into()).transpose()?,
             nut22: n.nut22.map(|s| s.try_into()).transpose()?,
+            nut29: Default::default(),
         })
     }
 }
@@ -689,6 +718,9 @@ mod tests {
                     max_amount: Some(cdk::Amount::from(100000)),
                     options: Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 {
                         description: true,
+                        description_hash: false,
+                        min_expiry: None,
+                        max_expiry: None,
                     }),
                 }],
                 disabled: false,
@@ -730,6 +762,7 @@ mod tests {
                     cdk::nuts::RoutePath::MintBolt11,
                 )],
             }),
+            nut29: Default::default(),
         }
     }
 
@@ -869,6 +902,7 @@ mod tests {
             nut20: cdk::nuts::nut06::SupportedSettings { supported: false },
             nut21: None,
             nut22: None,
+            nut29: Default::default(),
         };
 
         let ffi_nuts: Nuts = cdk_nuts.into();
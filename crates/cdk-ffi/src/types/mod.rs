@@ -8,6 +8,7 @@ pub mod amount;
 pub mod invoice;
 pub mod keys;
 pub mod mint;
+pub mod payment_request;
 pub mod proof;
 pub mod quote;
 pub mod subscription;
@@ -19,6 +20,7 @@ pub use amount::*;
 pub use invoice::*;
 pub use keys::*;
 pub use mint::*;
+pub use payment_request::*;
 pub use proof::*;
 pub use quote::*;
 pub use subscription::*;
@@ -0,0 +1,177 @@
+//! Payment request (NUT-18) types
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::amount::{Amount, CurrencyUnit};
+use super::mint::MintUrl;
+use crate::error::FfiError;
+
+/// FFI-compatible PaymentRequest
+#[derive(Debug, uniffi::Object)]
+pub struct PaymentRequest {
+    pub(crate) inner: cdk::nuts::nut18::PaymentRequest,
+}
+
+impl std::fmt::Display for PaymentRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+
+impl From<cdk::nuts::nut18::PaymentRequest> for PaymentRequest {
+    fn from(payment_request: cdk::nuts::nut18::PaymentRequest) -> Self {
+        Self {
+            inner: payment_request,
+        }
+    }
+}
+
+#[uniffi::export]
+impl PaymentRequest {
+    /// Decode a PaymentRequest from its `creq` string
+    #[uniffi::constructor]
+    pub fn from_string(encoded: String) -> Result<PaymentRequest, FfiError> {
+        let inner = cdk::nuts::nut18::PaymentRequest::from_str(&encoded)
+            .map_err(|e| FfiError::Serialization { msg: e.to_string() })?;
+        Ok(Self { inner })
+    }
+
+    /// Encode the PaymentRequest as a `creq` string
+    pub fn encode(&self) -> String {
+        self.to_string()
+    }
+
+    /// Payment id
+    pub fn payment_id(&self) -> Option<String> {
+        self.inner.payment_id.clone()
+    }
+
+    /// Requested amount, `None` lets the payer choose
+    pub fn amount(&self) -> Option<Amount> {
+        self.inner.amount.map(Into::into)
+    }
+
+    /// Requested unit
+    pub fn unit(&self) -> Option<CurrencyUnit> {
+        self.inner.unit.clone().map(Into::into)
+    }
+
+    /// Whether the request can only be paid once
+    pub fn single_use(&self) -> Option<bool> {
+        self.inner.single_use
+    }
+
+    /// Mints the payee accepts, `None` accepts any mint
+    pub fn mints(&self) -> Option<Vec<MintUrl>> {
+        self.inner
+            .mints
+            .clone()
+            .map(|mints| mints.into_iter().map(Into::into).collect())
+    }
+
+    /// Description
+    pub fn description(&self) -> Option<String> {
+        self.inner.description.clone()
+    }
+
+    /// Transports the payment can be delivered with
+    pub fn transports(&self) -> Vec<Transport> {
+        self.inner
+            .transports
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect()
+    }
+}
+
+/// FFI-compatible TransportType
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, uniffi::Enum)]
+pub enum TransportType {
+    /// Nostr
+    Nostr,
+    /// HTTP POST
+    HttpPost,
+}
+
+/// FFI-compatible Transport of a PaymentRequest
+#[derive(Debug, Clone, Serialize, Deserialize, uniffi::Record)]
+pub struct Transport {
+    /// Transport type
+    pub transport_type: TransportType,
+    /// Target, an nprofile for nostr or a URL for HTTP POST
+    pub target: String,
+    /// Tags
+    pub tags: Option<Vec<Vec<String>>>,
+}
+
+impl From<cdk::nuts::nut18::Transport> for Transport {
+    fn from(transport: cdk::nuts::nut18::Transport) -> Self {
+        Self {
+            transport_type: match transport._type {
+                cdk::nuts::nut18::TransportType::Nostr => TransportType::Nostr,
+                cdk::nuts::nut18::TransportType::HttpPost => TransportType::HttpPost,
+            },
+            target: transport.target,
+            tags: transport.tags,
+        }
+    }
+}
+
+/// FFI-compatible parameters to create a PaymentRequest
+#[derive(Debug, Clone, Serialize, Deserialize, uniffi::Record)]
+pub struct CreateRequestParams {
+    /// Amount to request, `None` lets the payer choose
+    pub amount: Option<u64>,
+    /// Currency unit (e.g. "sat")
+    pub unit: String,
+    /// Description
+    pub description: Option<String>,
+    /// P2PK pubkeys the payment must be locked to
+    pub pubkeys: Option<Vec<String>>,
+    /// Required number of signatures when `pubkeys` is set
+    pub num_sigs: u64,
+    /// HTLC hash the payment must be locked to
+    pub hash: Option<String>,
+    /// HTLC preimage the payment must be locked to the hash of
+    pub preimage: Option<String>,
+    /// Transport: "http" or "none"
+    pub transport: String,
+    /// Target URL of the "http" transport
+    pub http_url: Option<String>,
+}
+
+impl Default for CreateRequestParams {
+    fn default() -> Self {
+        Self {
+            amount: None,
+            unit: "sat".to_string(),
+            description: None,
+            pubkeys: None,
+            num_sigs: 1,
+            hash: None,
+            preimage: None,
+            transport: "none".to_string(),
+            http_url: None,
+        }
+    }
+}
+
+impl From<CreateRequestParams> for cdk::payment_request::CreateRequestParams {
+    fn from(params: CreateRequestParams) -> Self {
+        Self {
+            amount: params.amount,
+            unit: params.unit,
+            description: params.description,
+            pubkeys: params.pubkeys,
+            num_sigs: params.num_sigs,
+            hash: params.hash,
+            preimage: params.preimage,
+            transport: params.transport,
+            http_url: params.http_url,
+            nostr_relays: None,
+        }
+    }
+}
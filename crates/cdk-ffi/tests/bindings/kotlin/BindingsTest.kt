@@ -0,0 +1,182 @@
+// Test of the generated Kotlin bindings, run with `just ffi-test-bindings kotlin`
+//
+// With `just ffi-test-bindings-mint kotlin` it also runs the wallet flows against a fake mint
+// at `CDK_TEST_MINT_URL`.
+
+import kotlinx.coroutines.channels.Channel
+import kotlinx.coroutines.delay
+import kotlinx.coroutines.runBlocking
+import kotlinx.coroutines.withTimeout
+import org.cashudevkit.*
+
+const val PAYMENT_REQUEST =
+    "creqApWF0gaNhdGVub3N0cmFheKlucHJvZmlsZTFxeTI4d3VtbjhnaGo3dW45ZDNzaGp0bnl2OWtoMnVld2Q5aHN6OW1od2RlbjV0ZTB3ZmprY2N0ZTljdXJ4dmVuOWVlaHFjdHJ2NWhzenJ0aHdkZW41dGUwZGVoaHh0bnZkYWtxcWd5ZGFxeTdjdXJrNDM5eWtwdGt5c3Y3dWRoZGh1NjhzdWNtMjk1YWtxZWZkZWhrZjBkNDk1Y3d1bmw1YWeBgmFuYjE3YWloYjdhOTAxNzZhYQphdWNzYXRhbYF4Imh0dHBzOi8vbm9mZWVzLnRlc3RudXQuY2FzaHUuc3BhY2U="
+
+// Public key of the secret key 1
+const val PUBKEY = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+
+// Public key of the secret key 2
+const val OTHER_PUBKEY = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+
+class NoopListener : ProofStreamListener {
+    override suspend fun onProofs(quoteId: String, proofs: List<Proof>) {}
+
+    override suspend fun onError(msg: String) {}
+}
+
+class ChannelListener : ProofStreamListener {
+    val minted = Channel<List<Proof>>(Channel.UNLIMITED)
+
+    override suspend fun onProofs(quoteId: String, proofs: List<Proof>) {
+        minted.send(proofs)
+    }
+
+    override suspend fun onError(msg: String) {
+        minted.close(IllegalStateException(msg))
+    }
+}
+
+fun total(proofs: List<Proof>): ULong = proofs.sumOf { it.amount.value }
+
+fun sendOptions(conditions: SpendingConditions?) = MultiMintSendOptions(
+    allowTransfer = false,
+    maxTransferAmount = null,
+    allowedMints = listOf(),
+    excludedMints = listOf(),
+    sendOptions = SendOptions(
+        memo = null,
+        conditions = conditions,
+        amountSplitTarget = SplitTarget.None,
+        sendKind = SendKind.OnlineExact,
+        includeFee = false,
+        maxProofs = null,
+        metadata = mapOf(),
+    ),
+)
+
+// Retries until the fake mint has paid the quote
+suspend fun <T> retry(block: suspend () -> T): T {
+    repeat(29) {
+        try {
+            return block()
+        } catch (e: FfiException) {
+            delay(1000)
+        }
+    }
+    return block()
+}
+
+suspend fun walletFlows(mintUrl: String) {
+    val mint = MintUrl(mintUrl)
+    val wallet = MultiMintWallet(CurrencyUnit.Sat, generateMnemonic(), WalletSqliteDatabase.newInMemory())
+    wallet.addMint(mint, null)
+
+    // Proof streams mint the quotes as they get paid
+    val quote = wallet.mintQuote(mint, Amount(100UL), null)
+    val listener = ChannelListener()
+    val stream = wallet.mintProofStream(mint, listOf(quote.id), SplitTarget.None, null, listener)
+    check(total(withTimeout(30_000) { listener.minted.receive() }) == 100UL)
+    stream.cancel()
+    check(wallet.totalBalance().value == 100UL)
+
+    // Bolt12
+    val bolt12Quote = wallet.mintBolt12Quote(mint, Amount(50UL), null)
+    check(bolt12Quote.paymentMethod == PaymentMethod.Bolt12)
+    val bolt12Proofs = retry { wallet.mintBolt12(mint, bolt12Quote.id, null, SplitTarget.None, null) }
+    check(total(bolt12Proofs) == 50UL)
+    check(wallet.totalBalance().value == 150UL)
+
+    // P2PK
+    val locked = SpendingConditions.P2pk(PUBKEY, null)
+    val token = wallet.prepareSend(mint, Amount(10UL), sendOptions(locked)).confirm(null)
+    wallet.verifyTokenP2pk(token, locked)
+    try {
+        wallet.verifyTokenP2pk(token, SpendingConditions.P2pk(OTHER_PUBKEY, null))
+        error("Token verified against another key")
+    } catch (e: FfiException) {
+    }
+
+    // MPP, for an invoice of the mint
+    val invoice = wallet.mintQuote(mint, Amount(20UL), null).request
+    val mppQuotes = wallet.mppMeltQuote(invoice, listOf(MintAmount(mint, Amount(10UL))))
+    check(mppQuotes.size == 1)
+    check(mppQuotes.first().quote.amount.value == 10UL)
+
+    // Melt
+    val meltQuote = wallet.meltQuote(mint, wallet.mintQuote(mint, Amount(5UL), null).request, null)
+    val melted = wallet.meltWithMint(mint, meltQuote.id)
+    check(melted.state == QuoteState.PAID)
+    check(melted.amount.value == 5UL)
+
+    // Payment requests are paid from a mint that covers them
+    val request = wallet.createRequest(
+        CreateRequestParams(
+            amount = 1_000UL,
+            unit = "sat",
+            description = null,
+            pubkeys = null,
+            numSigs = 1UL,
+            hash = null,
+            preimage = null,
+            transport = "none",
+            httpUrl = null,
+        )
+    )
+    check(request.mints()?.map { it.url } == listOf(mint.url))
+    try {
+        wallet.payRequest(request, null)
+        error("Paid a request above the balance")
+    } catch (e: FfiException) {
+    }
+}
+
+fun main() = runBlocking {
+    // Payment requests
+    val request = PaymentRequest.fromString(PAYMENT_REQUEST)
+    check(request.paymentId() == "b7a90176")
+    check(request.amount()?.value == 10UL)
+    check(request.unit() == CurrencyUnit.Sat)
+    check(request.mints()?.first()?.url == "https://nofees.testnut.cashu.space")
+    check(request.transports().first().transportType == TransportType.NOSTR)
+    check(PaymentRequest.fromString(request.encode()).paymentId() == "b7a90176")
+
+    try {
+        PaymentRequest.fromString("creqAinvalid")
+        error("Invalid payment request was decoded")
+    } catch (e: FfiException.Serialization) {
+    }
+
+    // MultiMintWallet
+    val wallet = MultiMintWallet(CurrencyUnit.Sat, generateMnemonic(), WalletSqliteDatabase.newInMemory())
+    val created = wallet.createRequest(
+        CreateRequestParams(
+            amount = 21UL,
+            unit = "sat",
+            description = "Coffee",
+            pubkeys = null,
+            numSigs = 1UL,
+            hash = null,
+            preimage = null,
+            transport = "none",
+            httpUrl = null,
+        )
+    )
+    check(created.amount()?.value == 21UL)
+    check(created.singleUse() == true)
+
+    val unknownMint = MintUrl("https://mint.example.com")
+    try {
+        wallet.mintBolt12Quote(unknownMint, null, null)
+        error("Quote from a mint that was not added")
+    } catch (e: FfiException) {
+    }
+    try {
+        wallet.mintProofStream(unknownMint, listOf("quote"), SplitTarget.None, null, NoopListener())
+        error("Proof stream from a mint that was not added")
+    } catch (e: FfiException) {
+    }
+
+    System.getenv("CDK_TEST_MINT_URL")?.let { walletFlows(it) }
+
+    println("✅ Kotlin bindings work!")
+}
@@ -0,0 +1,226 @@
+// Test of the generated Swift bindings, run with `just ffi-test-bindings swift`
+//
+// With `just ffi-test-bindings-mint swift` it also runs the wallet flows against a fake mint
+// at `CDK_TEST_MINT_URL`.
+
+import CashuDevKit
+import Foundation
+
+let paymentRequest =
+    "creqApWF0gaNhdGVub3N0cmFheKlucHJvZmlsZTFxeTI4d3VtbjhnaGo3dW45ZDNzaGp0bnl2OWtoMnVld2Q5aHN6OW1od2RlbjV0ZTB3ZmprY2N0ZTljdXJ4dmVuOWVlaHFjdHJ2NWhzenJ0aHdkZW41dGUwZGVoaHh0bnZkYWtxcWd5ZGFxeTdjdXJrNDM5eWtwdGt5c3Y3dWRoZGh1NjhzdWNtMjk1YWtxZWZkZWhrZjBkNDk1Y3d1bmw1YWeBgmFuYjE3YWloYjdhOTAxNzZhYQphdWNzYXRhbYF4Imh0dHBzOi8vbm9mZWVzLnRlc3RudXQuY2FzaHUuc3BhY2U="
+
+// Public key of the secret key 1
+let pubkey = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+
+// Public key of the secret key 2
+let otherPubkey = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+
+final class NoopListener: ProofStreamListener {
+    func onProofs(quoteId: String, proofs: [Proof]) async {}
+
+    func onError(msg: String) async {}
+}
+
+final class StreamListener: ProofStreamListener {
+    let minted: AsyncStream<[Proof]>
+    private let continuation: AsyncStream<[Proof]>.Continuation
+
+    init() {
+        var continuation: AsyncStream<[Proof]>.Continuation!
+        minted = AsyncStream { continuation = $0 }
+        self.continuation = continuation
+    }
+
+    func onProofs(quoteId: String, proofs: [Proof]) async {
+        continuation.yield(proofs)
+    }
+
+    func onError(msg: String) async {
+        print("Proof stream failed: \(msg)")
+        continuation.finish()
+    }
+}
+
+func total(_ proofs: [Proof]) -> UInt64 {
+    proofs.reduce(0) { $0 + $1.amount.value }
+}
+
+func sendOptions(conditions: SpendingConditions?) -> MultiMintSendOptions {
+    MultiMintSendOptions(
+        allowTransfer: false,
+        maxTransferAmount: nil,
+        allowedMints: [],
+        excludedMints: [],
+        sendOptions: SendOptions(
+            memo: nil,
+            conditions: conditions,
+            amountSplitTarget: .none,
+            sendKind: .onlineExact,
+            includeFee: false,
+            maxProofs: nil,
+            metadata: [:]
+        ))
+}
+
+// Retries until the fake mint has paid the quote
+func retry<T>(_ block: () async throws -> T) async throws -> T {
+    for _ in 0..<29 {
+        do {
+            return try await block()
+        } catch is FfiError {
+            try await Task.sleep(nanoseconds: 1_000_000_000)
+        }
+    }
+    return try await block()
+}
+
+func walletFlows(mintUrl: String) async throws {
+    let mint = MintUrl(url: mintUrl)
+    let wallet = try MultiMintWallet(
+        unit: .sat, mnemonic: try generateMnemonic(), db: try WalletSqliteDatabase.newInMemory())
+    try await wallet.addMint(mintUrl: mint, targetProofCount: nil)
+
+    // Proof streams mint the quotes as they get paid
+    let quote = try await wallet.mintQuote(mintUrl: mint, amount: Amount(value: 100), description: nil)
+    let listener = StreamListener()
+    let stream = try await wallet.mintProofStream(
+        mintUrl: mint, quoteIds: [quote.id], amountSplitTarget: .none, spendingConditions: nil,
+        listener: listener)
+    var minted = listener.minted.makeAsyncIterator()
+    guard let proofs = await minted.next() else {
+        preconditionFailure("Proof stream ended before minting")
+    }
+    precondition(total(proofs) == 100)
+    stream.cancel()
+    precondition(try await wallet.totalBalance().value == 100)
+
+    // Bolt12
+    let bolt12Quote = try await wallet.mintBolt12Quote(
+        mintUrl: mint, amount: Amount(value: 50), description: nil)
+    precondition(bolt12Quote.paymentMethod == .bolt12)
+    let bolt12Proofs = try await retry {
+        try await wallet.mintBolt12(
+            mintUrl: mint, quoteId: bolt12Quote.id, amount: nil, amountSplitTarget: .none,
+            spendingConditions: nil)
+    }
+    precondition(total(bolt12Proofs) == 50)
+    precondition(try await wallet.totalBalance().value == 150)
+
+    // P2PK
+    let locked = SpendingConditions.p2pk(pubkey: pubkey, conditions: nil)
+    let token = try await wallet.prepareSend(
+        mintUrl: mint, amount: Amount(value: 10), options: sendOptions(conditions: locked)
+    ).confirm(memo: nil)
+    try await wallet.verifyTokenP2pk(token: token, conditions: locked)
+    do {
+        try await wallet.verifyTokenP2pk(
+            token: token, conditions: .p2pk(pubkey: otherPubkey, conditions: nil))
+        preconditionFailure("Token verified against another key")
+    } catch is FfiError {
+    }
+
+    // MPP, for an invoice of the mint
+    let invoice = try await wallet.mintQuote(
+        mintUrl: mint, amount: Amount(value: 20), description: nil
+    ).request
+    let mppQuotes = try await wallet.mppMeltQuote(
+        bolt11: invoice, mintAmounts: [MintAmount(mintUrl: mint, amount: Amount(value: 10))])
+    precondition(mppQuotes.count == 1)
+    precondition(mppQuotes[0].quote.amount.value == 10)
+
+    // Melt
+    let meltInvoice = try await wallet.mintQuote(
+        mintUrl: mint, amount: Amount(value: 5), description: nil
+    ).request
+    let meltQuote = try await wallet.meltQuote(mintUrl: mint, request: meltInvoice, options: nil)
+    let melted = try await wallet.meltWithMint(mintUrl: mint, quoteId: meltQuote.id)
+    precondition(melted.state == .paid)
+    precondition(melted.amount.value == 5)
+
+    // Payment requests are paid from a mint that covers them
+    let request = try await wallet.createRequest(
+        params: CreateRequestParams(
+            amount: 1_000,
+            unit: "sat",
+            description: nil,
+            pubkeys: nil,
+            numSigs: 1,
+            hash: nil,
+            preimage: nil,
+            transport: "none",
+            httpUrl: nil
+        ))
+    precondition(request.mints()?.map { $0.url } == [mint.url])
+    do {
+        _ = try await wallet.payRequest(paymentRequest: request, customAmount: nil)
+        preconditionFailure("Paid a request above the balance")
+    } catch is FfiError {
+    }
+}
+
+func run() async throws {
+    // Payment requests
+    let request = try PaymentRequest.fromString(encoded: paymentRequest)
+    precondition(request.paymentId() == "b7a90176")
+    precondition(request.amount()?.value == 10)
+    precondition(request.unit() == .sat)
+    precondition(request.mints()?.first?.url == "https://nofees.testnut.cashu.space")
+    precondition(request.transports().first?.transportType == .nostr)
+    precondition(try PaymentRequest.fromString(encoded: request.encode()).paymentId() == "b7a90176")
+
+    do {
+        _ = try PaymentRequest.fromString(encoded: "creqAinvalid")
+        preconditionFailure("Invalid payment request was decoded")
+    } catch FfiError.Serialization(_) {
+    }
+
+    // MultiMintWallet
+    let wallet = try MultiMintWallet(
+        unit: .sat, mnemonic: try generateMnemonic(), db: try WalletSqliteDatabase.newInMemory())
+    let created = try await wallet.createRequest(
+        params: CreateRequestParams(
+            amount: 21,
+            unit: "sat",
+            description: "Coffee",
+            pubkeys: nil,
+            numSigs: 1,
+            hash: nil,
+            preimage: nil,
+            transport: "none",
+            httpUrl: nil
+        ))
+    precondition(created.amount()?.value == 21)
+    precondition(created.singleUse() == true)
+
+    let unknownMint = MintUrl(url: "https://mint.example.com")
+    do {
+        _ = try await wallet.mintBolt12Quote(mintUrl: unknownMint, amount: nil, description: nil)
+        preconditionFailure("Quote from a mint that was not added")
+    } catch is FfiError {
+    }
+    do {
+        _ = try await wallet.mintProofStream(
+            mintUrl: unknownMint, quoteIds: ["quote"], amountSplitTarget: .none,
+            spendingConditions: nil, listener: NoopListener())
+        preconditionFailure("Proof stream from a mint that was not added")
+    } catch is FfiError {
+    }
+
+    if let mintUrl = ProcessInfo.processInfo.environment["CDK_TEST_MINT_URL"] {
+        try await walletFlows(mintUrl: mintUrl)
+    }
+
+    print("✅ Swift bindings work!")
+}
+
+let done = DispatchSemaphore(value: 0)
+Task {
+    do {
+        try await run()
+    } catch {
+        print("❌ \(error)")
+        exit(1)
+    }
+    done.signal()
+}
+done.wait()
@@ -274,6 +274,8 @@ fn create_ldk_settings(
             mnemonic: Some(mnemonic),
             signatory_url: None,
             signatory_certs: None,
+            signatory_threshold: None,
+            signatory_nodes: Vec::new(),
             input_fee_ppk: None,
             http_cache: cdk_axum::cache::Config::default(),
             enable_swagger_ui: None,
@@ -287,6 +289,7 @@ fn create_ldk_settings(
             max_mint: 500_000.into(),
             min_melt: 1.into(),
             max_melt: 500_000.into(),
+            ..Default::default()
         },
         cln: None,
         lnbits: None,
@@ -299,6 +302,9 @@ fn create_ldk_settings(
         mint_management_rpc: None,
         prometheus: None,
         auth: None,
+        lnurl_pay: None,
+        price_oracle: None,
+        limits: None,
     }
 }
 
@@ -15,8 +15,9 @@ use cdk::cdk_database::{self, WalletDatabase};
 use cdk::mint::{MintBuilder, MintMeltLimits};
 use cdk::nuts::nut00::ProofsMethods;
 use cdk::nuts::{
-    CheckStateRequest, CheckStateResponse, CurrencyUnit, Id, KeySet, KeysetResponse,
-    MeltQuoteBolt11Request, MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
+    BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, CheckStateRequest,
+    CheckStateResponse, CurrencyUnit, Id, KeySet, KeysetResponse, MeltQuoteBolt11Request,
+    MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
     MintQuoteBolt11Response, MintRequest, MintResponse, PaymentMethod, RestoreRequest,
     RestoreResponse, SwapRequest, SwapResponse,
 };
@@ -110,6 +111,25 @@ impl MintConnector for DirectMintConnection {
         self.mint.process_mint_request(request_id).await
     }
 
+    async fn post_check_mint_quotes(
+        &self,
+        request: BatchCheckMintQuoteRequest<String>,
+    ) -> Result<Vec<MintQuoteBolt11Response<String>>, Error> {
+        let request: BatchCheckMintQuoteRequest<QuoteId> = request.try_into()?;
+        self.mint
+            .check_mint_quotes(&request.quotes)
+            .await
+            .map(|quotes| quotes.into_iter().map(Into::into).collect())
+    }
+
+    async fn post_mint_batch(
+        &self,
+        request: BatchMintRequest<String>,
+    ) -> Result<BatchMintResponse, Error> {
+        let request: BatchMintRequest<QuoteId> = request.try_into().unwrap();
+        self.mint.process_batch_mint_request(request).await
+    }
+
     async fn post_melt_quote(
         &self,
         request: MeltQuoteBolt11Request,
@@ -191,6 +211,11 @@ impl MintConnector for DirectMintConnection {
         Ok(quote.into())
     }
 
+    async fn post_mint_bolt12(&self, request: MintRequest<String>) -> Result<MintResponse, Error> {
+        let request_id: MintRequest<QuoteId> = request.try_into().unwrap();
+        self.mint.process_mint_request(request_id).await
+    }
+
     /// Melt Quote [NUT-23]
     async fn post_melt_bolt12_quote(
         &self,
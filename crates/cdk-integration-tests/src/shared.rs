@@ -191,6 +191,8 @@ pub fn create_fake_wallet_settings(
             signatory_certs: signatory_config
                 .as_ref()
                 .map(|(_, certs_dir)| certs_dir.clone()),
+            signatory_threshold: None,
+            signatory_nodes: Vec::new(),
             input_fee_ppk: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
@@ -208,6 +210,7 @@ pub fn create_fake_wallet_settings(
             max_mint: DEFAULT_MAX_MINT.into(),
             min_melt: DEFAULT_MIN_MELT.into(),
             max_melt: DEFAULT_MAX_MELT.into(),
+            ..Default::default()
         },
         cln: None,
         lnbits: None,
@@ -223,6 +226,9 @@ pub fn create_fake_wallet_settings(
         mint_management_rpc: None,
         auth: None,
         prometheus: Some(Default::default()),
+        lnurl_pay: None,
+        price_oracle: None,
+        limits: None,
     }
 }
 
@@ -244,6 +250,8 @@ pub fn create_cln_settings(
             mnemonic: Some(mnemonic),
             signatory_url: None,
             signatory_certs: None,
+            signatory_threshold: None,
+            signatory_nodes: Vec::new(),
             input_fee_ppk: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
@@ -261,6 +269,7 @@ pub fn create_cln_settings(
             max_mint: DEFAULT_MAX_MINT.into(),
             min_melt: DEFAULT_MIN_MELT.into(),
             max_melt: DEFAULT_MAX_MELT.into(),
+            ..Default::default()
         },
         cln: Some(cln_config),
         lnbits: None,
@@ -273,6 +282,9 @@ pub fn create_cln_settings(
         mint_management_rpc: None,
         auth: None,
         prometheus: Some(Default::default()),
+        lnurl_pay: None,
+        price_oracle: None,
+        limits: None,
     }
 }
 
@@ -292,6 +304,8 @@ pub fn create_lnd_settings(
             mnemonic: Some(mnemonic),
             signatory_url: None,
             signatory_certs: None,
+            signatory_threshold: None,
+            signatory_nodes: Vec::new(),
             input_fee_ppk: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
@@ -309,6 +323,7 @@ pub fn create_lnd_settings(
             max_mint: DEFAULT_MAX_MINT.into(),
             min_melt: DEFAULT_MIN_MELT.into(),
             max_melt: DEFAULT_MAX_MELT.into(),
+            ..Default::default()
         },
         cln: None,
         lnbits: None,
@@ -321,5 +336,8 @@ pub fn create_lnd_settings(
         mint_management_rpc: None,
         auth: None,
         prometheus: Some(Default::default()),
+        lnurl_pay: None,
+        price_oracle: None,
+        limits: None,
     }
 }
@@ -97,6 +97,8 @@ async fn test_mint_without_auth() {
             amount: 10.into(),
             description: None,
             pubkey: None,
+            description_hash: None,
+            expiry_secs: None,
         };
 
         let quote_res = client.post_mint_quote(request).await;
@@ -576,6 +578,8 @@ async fn test_melt_with_invalid_auth() {
             amount: 10.into(),
             description: None,
             pubkey: None,
+            description_hash: None,
+            expiry_secs: None,
         };
 
         let quote_res = client.post_mint_quote(request).await;
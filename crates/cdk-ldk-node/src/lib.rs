@@ -16,11 +16,11 @@ use cdk_common::payment::{self, *};
 use cdk_common::util::{hex, unix_time};
 use cdk_common::{Amount, CurrencyUnit, MeltOptions, MeltQuoteState};
 use futures::{Stream, StreamExt};
-use ldk_node::bitcoin::hashes::Hash;
+use ldk_node::bitcoin::hashes::{sha256, Hash};
 use ldk_node::bitcoin::Network;
 use ldk_node::lightning::ln::channelmanager::PaymentId;
 use ldk_node::lightning::ln::msgs::SocketAddress;
-use ldk_node::lightning_invoice::{Bolt11InvoiceDescription, Description};
+use ldk_node::lightning_invoice::{Bolt11InvoiceDescription, Description, Sha256};
 use ldk_node::lightning_types::payment::PaymentHash;
 use ldk_node::payment::{PaymentDirection, PaymentKind, PaymentStatus, SendingParameters};
 use ldk_node::{Builder, Event, Node};
@@ -472,6 +472,7 @@ impl MintPayment for CdkLdkNode {
             mpp: false,
             unit: CurrencyUnit::Msat,
             invoice_description: true,
+            invoice_description_hash: true,
             amountless: true,
             bolt12: true,
         };
@@ -494,9 +495,14 @@ impl MintPayment for CdkLdkNode {
                     .map(|t| t - unix_time())
                     .unwrap_or(36000);
 
-                let description = Bolt11InvoiceDescription::Direct(
-                    Description::new(description).map_err(|_| Error::InvalidDescription)?,
-                );
+                let description = match bolt11_options.description_hash {
+                    Some(hash) => Bolt11InvoiceDescription::Hash(Sha256(
+                        sha256::Hash::from_byte_array(hash.to_byte_array()),
+                    )),
+                    None => Bolt11InvoiceDescription::Direct(
+                        Description::new(description).map_err(|_| Error::InvalidDescription)?,
+                    ),
+                };
 
                 let payment = self
                     .inner
@@ -570,6 +576,14 @@ impl MintPayment for CdkLdkNode {
                 let bolt11 = bolt11_options.bolt11;
 
                 let amount_msat = match bolt11_options.melt_options {
+                    Some(MeltOptions::Amountless { amountless }) => {
+                        if let Some(invoice_amount) = bolt11.amount_milli_satoshis() {
+                            if invoice_amount != u64::from(amountless.amount_msat) {
+                                return Err(payment::Error::AmountMismatch);
+                            }
+                        }
+                        amountless.amount_msat
+                    }
                     Some(melt_options) => melt_options.amount_msat(),
                     None => bolt11
                         .amount_milli_satoshis()
@@ -60,6 +60,7 @@ impl LNbits {
                 mpp: false,
                 unit: CurrencyUnit::Sat,
                 invoice_description: true,
+                invoice_description_hash: false,
                 amountless: false,
                 bolt12: false,
             },
@@ -333,6 +334,11 @@ impl MintPayment for LNbits {
     ) -> Result<CreateIncomingPaymentResponse, Self::Err> {
         match options {
             IncomingPaymentOptions::Bolt11(bolt11_options) => {
+                // LNbits does not accept a description hash when creating invoices
+                if bolt11_options.description_hash.is_some() {
+                    return Err(payment::Error::UnsupportedPaymentOption);
+                }
+
                 let description = bolt11_options.description.unwrap_or_default();
                 let amount = bolt11_options.amount;
                 let unix_expiry = bolt11_options.unix_expiry;
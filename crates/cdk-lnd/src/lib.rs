@@ -22,12 +22,13 @@ use cdk_common::database::mint::DynMintKVStore;
 use cdk_common::nuts::{CurrencyUnit, MeltOptions, MeltQuoteState};
 use cdk_common::payment::{
     self, Bolt11Settings, CreateIncomingPaymentResponse, Event, IncomingPaymentOptions,
-    MakePaymentResponse, MintPayment, OutgoingPaymentOptions, PaymentIdentifier,
+    LiquidityResponse, MakePaymentResponse, MintPayment, OutgoingPaymentOptions, PaymentIdentifier,
     PaymentQuoteResponse, WaitPaymentResponse,
 };
-use cdk_common::util::hex;
+use cdk_common::util::{hex, unix_time};
 use cdk_common::Bolt11Invoice;
 use error::Error;
+use futures::stream::BoxStream;
 use futures::{Stream, StreamExt};
 use lnrpc::fee_limit::Limit;
 use lnrpc::payment::PaymentStatus;
@@ -120,6 +121,7 @@ impl Lnd {
                 mpp: true,
                 unit: CurrencyUnit::Msat,
                 invoice_description: true,
+                invoice_description_hash: true,
                 amountless: true,
                 bolt12: false,
             },
@@ -226,12 +228,28 @@ impl MintPayment for Lnd {
             })?
             .into_inner();
 
+        // Final updates of outgoing payments so pending melts settle without polling
+        let payments: BoxStream<'static, Result<lnrpc::Payment, tonic::Status>> = match lnd_client
+            .router()
+            .track_payments(routerrpc::TrackPaymentsRequest {
+                no_inflight_updates: true,
+            })
+            .await
+        {
+            Ok(payments) => payments.into_inner().boxed(),
+            Err(err) => {
+                tracing::warn!("LND: Could not subscribe to outgoing payments: {}", err);
+                futures::stream::pending().boxed()
+            }
+        };
+
         let cancel_token = self.wait_invoice_cancel_token.clone();
         let kv_store = self.kv_store.clone();
 
         let event_stream = futures::stream::unfold(
             (
                 stream,
+                payments,
                 cancel_token,
                 Arc::clone(&self.wait_invoice_is_active),
                 kv_store,
@@ -240,6 +258,7 @@ impl MintPayment for Lnd {
             ),
             |(
                 mut stream,
+                mut payments,
                 cancel_token,
                 is_active,
                 kv_store,
@@ -256,6 +275,28 @@ impl MintPayment for Lnd {
                             tracing::info!("Waiting for lnd invoice ending");
                             return None;
                         }
+                        payment = payments.next() => {
+                            match payment {
+                                Some(Ok(payment)) => {
+                                    if let Some(response) = outgoing_payment_update(payment) {
+                                        let event = Event::OutgoingPaymentUpdate(response);
+                                        return Some((event, (stream, payments, cancel_token, is_active, kv_store, current_add_index, current_settle_index)));
+                                    }
+                                    continue;
+                                }
+                                Some(Err(err)) => {
+                                    is_active.store(false, Ordering::SeqCst);
+                                    tracing::warn!("Encountered error in LND payment stream. Stream ending");
+                                    tracing::error!("{:?}", err);
+                                    return None;
+                                }
+                                None => {
+                                    is_active.store(false, Ordering::SeqCst);
+                                    tracing::info!("LND payment stream ended.");
+                                    return None;
+                                }
+                            }
+                        }
                         msg = stream.message() => {
                             match msg {
                                 Ok(Some(msg)) => {
@@ -307,7 +348,7 @@ impl MintPayment for Lnd {
                                                 payment_id: hash,
                                             };
                                             let event = Event::PaymentReceived(wait_response);
-                                            return Some((event, (stream, cancel_token, is_active, kv_store, current_add_index, current_settle_index)));
+                                            return Some((event, (stream, payments, cancel_token, is_active, kv_store, current_add_index, current_settle_index)));
                                         } else {
                                             // Invalid hash, skip this message but continue streaming
                                             tracing::error!("LND returned invalid payment hash");
@@ -351,6 +392,15 @@ impl MintPayment for Lnd {
         match options {
             OutgoingPaymentOptions::Bolt11(bolt11_options) => {
                 let amount_msat = match bolt11_options.melt_options {
+                    Some(MeltOptions::Amountless { amountless }) => {
+                        if let Some(invoice_amount) = bolt11_options.bolt11.amount_milli_satoshis()
+                        {
+                            if invoice_amount != u64::from(amountless.amount_msat) {
+                                return Err(payment::Error::AmountMismatch);
+                            }
+                        }
+                        amountless.amount_msat
+                    }
                     Some(amount) => amount.amount_msat(),
                     None => bolt11_options
                         .bolt11
@@ -523,12 +573,17 @@ impl MintPayment for Lnd {
 
                         let max_fee: Option<Amount> = bolt11_options.max_fee_amount;
 
-                        let amount_msat = u64::from(
-                            bolt11_options
-                                .melt_options
-                                .map(|a| a.amount_msat())
-                                .unwrap_or_default(),
-                        );
+                        // LND rejects an explicit amount for invoices that already
+                        // carry one, so it is only set when paying amountless invoices
+                        let amount_msat = match bolt11.amount_milli_satoshis() {
+                            Some(_) => 0,
+                            None => u64::from(
+                                bolt11_options
+                                    .melt_options
+                                    .map(|a| a.amount_msat())
+                                    .ok_or(Error::UnknownInvoiceAmount)?,
+                            ),
+                        };
 
                         let pay_req = lnrpc::SendRequest {
                             payment_request: bolt11.to_string(),
@@ -599,6 +654,13 @@ impl MintPayment for Lnd {
                 let invoice_request = lnrpc::Invoice {
                     value_msat: u64::from(amount_msat) as i64,
                     memo: description,
+                    description_hash: bolt11_options
+                        .description_hash
+                        .map(|hash| hash.to_byte_array().to_vec())
+                        .unwrap_or_default(),
+                    expiry: unix_expiry
+                        .map(|t| t.saturating_sub(unix_time()) as i64)
+                        .unwrap_or_default(),
                     ..Default::default()
                 };
 
@@ -744,4 +806,66 @@ impl MintPayment for Lnd {
         // If the stream is exhausted without a final status
         Err(Error::UnknownPaymentStatus.into())
     }
+
+    #[instrument(skip(self))]
+    async fn get_liquidity(&self) -> Result<LiquidityResponse, Self::Err> {
+        let mut lnd_client = self.lnd_client.clone();
+
+        let balance = lnd_client
+            .lightning()
+            .channel_balance(tonic::Request::new(lnrpc::ChannelBalanceRequest {}))
+            .await
+            .map_err(|e| payment::Error::Anyhow(anyhow!(e)))?
+            .into_inner();
+
+        let outbound = balance.local_balance.map(|b| b.msat).unwrap_or_default();
+        let inbound = balance.remote_balance.map(|b| b.msat).unwrap_or_default();
+
+        Ok(LiquidityResponse {
+            outbound: to_unit(outbound, &CurrencyUnit::Msat, &self.settings.unit)?,
+            inbound: to_unit(inbound, &CurrencyUnit::Msat, &self.settings.unit)?,
+            unit: self.settings.unit.clone(),
+        })
+    }
+}
+
+/// Convert a final LND payment update to a [`MakePaymentResponse`]
+///
+/// Returns `None` for payments that are still in flight.
+fn outgoing_payment_update(payment: lnrpc::Payment) -> Option<MakePaymentResponse> {
+    let status = payment.status();
+
+    let payment_hash: [u8; 32] = match hex::decode(&payment.payment_hash)
+        .ok()
+        .and_then(|hash| hash.try_into().ok())
+    {
+        Some(hash) => hash,
+        None => {
+            tracing::error!("LND returned invalid payment hash");
+            return None;
+        }
+    };
+
+    let (status, total_spent) = match status {
+        PaymentStatus::Succeeded => (
+            MeltQuoteState::Paid,
+            Amount::from(payment.value_sat.checked_add(payment.fee_sat)? as u64),
+        ),
+        PaymentStatus::Failed => (MeltQuoteState::Failed, Amount::ZERO),
+        PaymentStatus::Unknown | PaymentStatus::InFlight | PaymentStatus::Initiated => return None,
+    };
+
+    tracing::info!(
+        "LND: Outgoing payment {} is {}",
+        payment.payment_hash,
+        status
+    );
+
+    Some(MakePaymentResponse {
+        payment_lookup_id: PaymentIdentifier::PaymentHash(payment_hash),
+        payment_proof: (status == MeltQuoteState::Paid).then_some(payment.payment_preimage),
+        status,
+        total_spent,
+        unit: CurrencyUnit::Sat,
+    })
 }
@@ -98,6 +98,32 @@ enum Commands {
     UpdateNut04QuoteState(subcommands::UpdateNut04QuoteCommand),
     /// Rotate next keyset
     RotateNextKeyset(subcommands::RotateNextKeysetCommand),
+    /// List mint quotes
+    ListMintQuotes(subcommands::ListMintQuotesCommand),
+    /// Get melt quote
+    GetMeltQuote(subcommands::GetMeltQuoteCommand),
+    /// List pending melts
+    ListPendingMelts(subcommands::ListPendingMeltsCommand),
+    /// List incomplete sagas
+    ListSagas(subcommands::ListSagasCommand),
+    /// Get proof states
+    GetProofStates(subcommands::GetProofStatesCommand),
+    /// Get issued and redeemed totals
+    GetTotals(subcommands::GetTotalsCommand),
+    /// Check the payment of a stuck melt
+    ForceCheckMelt(subcommands::ForceCheckMeltCommand),
+    /// Mark a stuck melt as paid
+    MarkMeltPaid(subcommands::MarkMeltPaidCommand),
+    /// Mark a stuck melt as failed
+    MarkMeltFailed(subcommands::MarkMeltFailedCommand),
+    /// List the audit log of administrative actions
+    ListAuditLog(subcommands::ListAuditLogCommand),
+    /// Get the input fee schedule
+    GetFeeSchedule,
+    /// Set the input fee of a unit
+    SetUnitFee(subcommands::SetUnitFeeCommand),
+    /// Cancel the pending input fee of a unit
+    CancelUnitFee(subcommands::CancelUnitFeeCommand),
 }
 
 #[tokio::main]
@@ -227,6 +253,45 @@ async fn main() -> Result<()> {
         Commands::RotateNextKeyset(sub_command_args) => {
             subcommands::rotate_next_keyset(&mut client, &sub_command_args).await?;
         }
+        Commands::ListMintQuotes(sub_command_args) => {
+            subcommands::list_mint_quotes(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetMeltQuote(sub_command_args) => {
+            subcommands::get_melt_quote(&mut client, &sub_command_args).await?;
+        }
+        Commands::ListPendingMelts(sub_command_args) => {
+            subcommands::list_pending_melts(&mut client, &sub_command_args).await?;
+        }
+        Commands::ListSagas(sub_command_args) => {
+            subcommands::list_sagas(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetProofStates(sub_command_args) => {
+            subcommands::get_proof_states(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetTotals(sub_command_args) => {
+            subcommands::get_totals(&mut client, &sub_command_args).await?;
+        }
+        Commands::ForceCheckMelt(sub_command_args) => {
+            subcommands::force_check_melt(&mut client, &sub_command_args).await?;
+        }
+        Commands::MarkMeltPaid(sub_command_args) => {
+            subcommands::mark_melt_paid(&mut client, &sub_command_args).await?;
+        }
+        Commands::MarkMeltFailed(sub_command_args) => {
+            subcommands::mark_melt_failed(&mut client, &sub_command_args).await?;
+        }
+        Commands::ListAuditLog(sub_command_args) => {
+            subcommands::list_audit_log(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetFeeSchedule => {
+            subcommands::get_fee_schedule(&mut client).await?;
+        }
+        Commands::SetUnitFee(sub_command_args) => {
+            subcommands::set_unit_fee(&mut client, &sub_command_args).await?;
+        }
+        Commands::CancelUnitFee(sub_command_args) => {
+            subcommands::cancel_unit_fee(&mut client, &sub_command_args).await?;
+        }
     }
 
     Ok(())
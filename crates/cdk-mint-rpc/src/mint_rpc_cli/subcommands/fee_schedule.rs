@@ -0,0 +1,110 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::{CancelUnitFeeRequest, GetFeeScheduleRequest, SetUnitFeeRequest};
+
+/// Executes the get_fee_schedule command against the mint server
+///
+/// Prints the input fee of the active keyset of each unit and its pending fee.
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+pub async fn get_fee_schedule(client: &mut CdkMintClient<Channel>) -> Result<()> {
+    let response = client
+        .get_fee_schedule(Request::new(GetFeeScheduleRequest {}))
+        .await?
+        .into_inner();
+
+    for unit in &response.units {
+        println!(
+            "{}: keyset {} with {} ppk",
+            unit.unit, unit.active_keyset_id, unit.input_fee_ppk
+        );
+
+        if let Some(pending_fee_ppk) = unit.pending_fee_ppk {
+            match unit.rotate_at {
+                Some(rotate_at) => println!("  pending {pending_fee_ppk} ppk at {rotate_at}"),
+                None => println!("  pending {pending_fee_ppk} ppk at next rotation"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Command to set the input fee of a unit
+///
+/// The fee of a keyset cannot change, so the fee is applied when the keyset of
+/// the unit is rotated: right away, at a scheduled time or at the next manual
+/// rotation.
+#[derive(Args)]
+pub struct SetUnitFeeCommand {
+    /// The currency unit
+    #[arg(short, long)]
+    unit: String,
+    /// The input fee in parts per thousand
+    #[arg(long)]
+    input_fee_ppk: u64,
+    /// Rotate the keyset with the fee at this unix timestamp
+    #[arg(long, conflicts_with = "rotate_now")]
+    rotate_at: Option<u64>,
+    /// Rotate the keyset with the fee right away
+    #[arg(long)]
+    rotate_now: bool,
+}
+
+/// Executes the set_unit_fee command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The unit, its fee and when to apply it
+pub async fn set_unit_fee(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &SetUnitFeeCommand,
+) -> Result<()> {
+    let response = client
+        .set_unit_fee(Request::new(SetUnitFeeRequest {
+            unit: sub_command_args.unit.clone(),
+            input_fee_ppk: sub_command_args.input_fee_ppk,
+            rotate_at: sub_command_args.rotate_at,
+            rotate_now: sub_command_args.rotate_now,
+        }))
+        .await?
+        .into_inner();
+
+    match response.keyset_id {
+        Some(keyset_id) => println!("Rotated to keyset {keyset_id}"),
+        None => println!("Pending fee recorded"),
+    }
+
+    Ok(())
+}
+
+/// Command to cancel the pending input fee of a unit
+#[derive(Args)]
+pub struct CancelUnitFeeCommand {
+    /// The currency unit
+    #[arg(short, long)]
+    unit: String,
+}
+
+/// Executes the cancel_unit_fee command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The unit whose pending fee is cancelled
+pub async fn cancel_unit_fee(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &CancelUnitFeeCommand,
+) -> Result<()> {
+    client
+        .cancel_unit_fee(Request::new(CancelUnitFeeRequest {
+            unit: sub_command_args.unit.clone(),
+        }))
+        .await?;
+
+    Ok(())
+}
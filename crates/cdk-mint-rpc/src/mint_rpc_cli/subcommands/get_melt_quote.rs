@@ -0,0 +1,74 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::{GetMeltQuoteRequest, MeltQuote};
+
+/// Command to look up a melt quote (NUT-05) by id
+#[derive(Args)]
+pub struct GetMeltQuoteCommand {
+    /// The ID of the quote
+    quote_id: String,
+}
+
+/// Executes the get_melt_quote command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The ID of the quote to look up
+pub async fn get_melt_quote(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &GetMeltQuoteCommand,
+) -> Result<()> {
+    let response = client
+        .get_melt_quote(Request::new(GetMeltQuoteRequest {
+            quote_id: sub_command_args.quote_id.clone(),
+        }))
+        .await?;
+
+    let quote = response.into_inner();
+
+    println!("quote id:          {}", quote.id);
+    println!("state:             {}", quote.state);
+    println!("method:            {}", quote.method);
+    println!("unit:              {}", quote.unit);
+    println!("amount:            {}", quote.amount);
+    println!("fee reserve:       {}", quote.fee_reserve);
+    println!("request:           {}", quote.request);
+    println!(
+        "request lookup id: {}",
+        quote.request_lookup_id.unwrap_or("None".to_string())
+    );
+    println!(
+        "payment preimage:  {}",
+        quote.payment_preimage.unwrap_or("None".to_string())
+    );
+    println!("created:           {}", quote.created_time);
+    println!("expiry:            {}", quote.expiry);
+    println!(
+        "paid:              {}",
+        quote
+            .paid_time
+            .map(|t| t.to_string())
+            .unwrap_or("None".to_string())
+    );
+
+    Ok(())
+}
+
+/// Prints a melt quote as a single line
+pub(super) fn print_melt_quote_line(quote: &MeltQuote) {
+    println!(
+        "{} {} {} {}: amount {}, fee reserve {}, created {}, expiry {}",
+        quote.id,
+        quote.method,
+        quote.unit,
+        quote.state,
+        quote.amount,
+        quote.fee_reserve,
+        quote.created_time,
+        quote.expiry
+    );
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::GetProofStatesRequest;
+
+/// Command to look up the state of proofs by their Y values
+#[derive(Args)]
+pub struct GetProofStatesCommand {
+    /// Hex encoded Y values of the proofs
+    #[arg(required = true)]
+    ys: Vec<String>,
+}
+
+/// Executes the get_proof_states command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The Y values of the proofs to look up
+pub async fn get_proof_states(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &GetProofStatesCommand,
+) -> Result<()> {
+    let response = client
+        .get_proof_states(Request::new(GetProofStatesRequest {
+            ys: sub_command_args.ys.clone(),
+        }))
+        .await?;
+
+    for state in response.into_inner().states {
+        match (state.keyset_id, state.amount) {
+            (Some(keyset_id), Some(amount)) => println!(
+                "{}: {} (keyset {}, amount {})",
+                state.y, state.state, keyset_id, amount
+            ),
+            _ => println!("{}: {}", state.y, state.state),
+        }
+    }
+
+    Ok(())
+}
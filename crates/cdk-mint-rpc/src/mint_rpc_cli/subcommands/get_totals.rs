@@ -0,0 +1,58 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::GetTotalsRequest;
+
+/// Command to get the issued and redeemed totals of the mint
+///
+/// Totals are shown per keyset and summed per unit.
+#[derive(Args)]
+pub struct GetTotalsCommand {
+    /// Only show totals for this unit (e.g., "sat")
+    #[arg(long)]
+    unit: Option<String>,
+}
+
+/// Executes the get_totals command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The unit to show totals for
+pub async fn get_totals(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &GetTotalsCommand,
+) -> Result<()> {
+    let response = client
+        .get_totals(Request::new(GetTotalsRequest {
+            unit: sub_command_args.unit.clone(),
+        }))
+        .await?;
+
+    let response = response.into_inner();
+
+    for keyset in response.keysets {
+        println!(
+            "keyset {} ({}{}): issued {}, redeemed {}",
+            keyset.keyset_id,
+            keyset.unit,
+            if keyset.active { ", active" } else { "" },
+            keyset.total_issued,
+            keyset.total_redeemed
+        );
+    }
+
+    for unit in response.units {
+        println!(
+            "total {}: issued {}, redeemed {}, outstanding {}",
+            unit.unit,
+            unit.total_issued,
+            unit.total_redeemed,
+            unit.total_issued.saturating_sub(unit.total_redeemed)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::ListAuditLogRequest;
+
+/// Command to list the administrative actions recorded by the mint
+///
+/// Every change made over the management RPC is recorded with its old and new
+/// value and the fingerprint of the client certificate that made it.
+#[derive(Args)]
+pub struct ListAuditLogCommand {
+    /// Only list entries of this action (e.g. "update_motd")
+    #[arg(long)]
+    action: Option<String>,
+    /// Only list entries created at or after this unix timestamp
+    #[arg(long)]
+    created_after: Option<u64>,
+    /// Number of entries to skip
+    #[arg(long, default_value_t = 0)]
+    offset: u64,
+    /// Max number of entries to list
+    #[arg(long)]
+    limit: Option<u64>,
+}
+
+/// Executes the list_audit_log command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The filters and pagination of the listing
+pub async fn list_audit_log(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &ListAuditLogCommand,
+) -> Result<()> {
+    let response = client
+        .list_audit_log(Request::new(ListAuditLogRequest {
+            action: sub_command_args.action.clone(),
+            created_after: sub_command_args.created_after,
+            offset: sub_command_args.offset,
+            limit: sub_command_args.limit,
+        }))
+        .await?;
+
+    let response = response.into_inner();
+
+    for entry in &response.entries {
+        println!(
+            "{} {} by {}: {} -> {}",
+            entry.created_at,
+            entry.action,
+            entry.caller.as_deref().unwrap_or("unknown"),
+            entry.old_value.as_deref().unwrap_or("None"),
+            entry.new_value.as_deref().unwrap_or("None")
+        );
+    }
+
+    println!(
+        "Showing {} of {} audit log entries",
+        response.entries.len(),
+        response.total
+    );
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::ListMintQuotesRequest;
+
+/// Command to list the mint quotes (NUT-04) of the mint
+///
+/// Quotes are listed newest first and can be filtered by unit, state, payment
+/// method and creation time.
+#[derive(Args)]
+pub struct ListMintQuotesCommand {
+    /// Only list quotes for this unit (e.g., "sat")
+    #[arg(long)]
+    unit: Option<String>,
+    /// Only list quotes in this state (e.g., "PAID")
+    #[arg(long)]
+    state: Option<String>,
+    /// Only list quotes for this payment method (e.g., "bolt11")
+    #[arg(long)]
+    method: Option<String>,
+    /// Only list quotes created at or after this unix timestamp
+    #[arg(long)]
+    created_after: Option<u64>,
+    /// Only list quotes created before this unix timestamp
+    #[arg(long)]
+    created_before: Option<u64>,
+    /// Number of quotes to skip
+    #[arg(long, default_value_t = 0)]
+    offset: u64,
+    /// Max number of quotes to list
+    #[arg(long)]
+    limit: Option<u64>,
+}
+
+/// Executes the list_mint_quotes command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The filters and pagination of the listing
+pub async fn list_mint_quotes(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &ListMintQuotesCommand,
+) -> Result<()> {
+    let response = client
+        .list_mint_quotes(Request::new(ListMintQuotesRequest {
+            unit: sub_command_args.unit.clone(),
+            state: sub_command_args.state.clone(),
+            method: sub_command_args.method.clone(),
+            created_after: sub_command_args.created_after,
+            created_before: sub_command_args.created_before,
+            offset: sub_command_args.offset,
+            limit: sub_command_args.limit,
+        }))
+        .await?;
+
+    let response = response.into_inner();
+
+    for quote in &response.quotes {
+        println!(
+            "{} {} {} {}: amount {}, paid {}, issued {}, created {}, expiry {}",
+            quote.id,
+            quote.method,
+            quote.unit,
+            quote.state,
+            quote
+                .amount
+                .map(|a| a.to_string())
+                .unwrap_or("None".to_string()),
+            quote.amount_paid,
+            quote.amount_issued,
+            quote.created_time,
+            quote.expiry
+        );
+    }
+
+    println!(
+        "Showing {} of {} quotes",
+        response.quotes.len(),
+        response.total
+    );
+
+    Ok(())
+}
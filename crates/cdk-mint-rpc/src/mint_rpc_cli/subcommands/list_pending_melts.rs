@@ -0,0 +1,57 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use super::get_melt_quote::print_melt_quote_line;
+use crate::cdk_mint_client::CdkMintClient;
+use crate::ListPendingMeltsRequest;
+
+/// Command to list the melt quotes whose payment is still pending
+///
+/// Quotes are listed oldest first, so melts that have been stuck the longest
+/// are shown at the top.
+#[derive(Args)]
+pub struct ListPendingMeltsCommand {
+    /// Only list quotes for this unit (e.g., "sat")
+    #[arg(long)]
+    unit: Option<String>,
+    /// Number of quotes to skip
+    #[arg(long, default_value_t = 0)]
+    offset: u64,
+    /// Max number of quotes to list
+    #[arg(long)]
+    limit: Option<u64>,
+}
+
+/// Executes the list_pending_melts command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The filters and pagination of the listing
+pub async fn list_pending_melts(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &ListPendingMeltsCommand,
+) -> Result<()> {
+    let response = client
+        .list_pending_melts(Request::new(ListPendingMeltsRequest {
+            unit: sub_command_args.unit.clone(),
+            offset: sub_command_args.offset,
+            limit: sub_command_args.limit,
+        }))
+        .await?;
+
+    let response = response.into_inner();
+
+    for quote in &response.quotes {
+        print_melt_quote_line(quote);
+    }
+
+    println!(
+        "Showing {} of {} pending melts",
+        response.quotes.len(),
+        response.total
+    );
+
+    Ok(())
+}
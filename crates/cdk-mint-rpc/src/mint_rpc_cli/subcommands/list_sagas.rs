@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::ListSagasRequest;
+
+/// Command to list the incomplete sagas of the mint
+///
+/// Sagas track swap and melt operations in progress. A saga that stays around
+/// is an operation that has not been finalized or compensated yet.
+#[derive(Args)]
+pub struct ListSagasCommand {
+    /// Only list sagas of this operation kind ("swap" or "melt")
+    #[arg(long)]
+    operation_kind: Option<String>,
+    /// Number of sagas to skip
+    #[arg(long, default_value_t = 0)]
+    offset: u64,
+    /// Max number of sagas to list
+    #[arg(long)]
+    limit: Option<u64>,
+}
+
+/// Executes the list_sagas command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The filters and pagination of the listing
+pub async fn list_sagas(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &ListSagasCommand,
+) -> Result<()> {
+    let response = client
+        .list_sagas(Request::new(ListSagasRequest {
+            operation_kind: sub_command_args.operation_kind.clone(),
+            offset: sub_command_args.offset,
+            limit: sub_command_args.limit,
+        }))
+        .await?;
+
+    let response = response.into_inner();
+
+    for saga in &response.sagas {
+        println!(
+            "{} {} {}: quote {}, {} inputs, {} outputs, created {}, updated {}",
+            saga.operation_id,
+            saga.operation_kind,
+            saga.state,
+            saga.quote_id.as_deref().unwrap_or("None"),
+            saga.input_ys.len(),
+            saga.blinded_secrets.len(),
+            saga.created_at,
+            saga.updated_at
+        );
+    }
+
+    println!(
+        "Showing {} of {} sagas",
+        response.sagas.len(),
+        response.total
+    );
+
+    Ok(())
+}
@@ -1,3 +1,21 @@
+/// Module for managing keyset input fees
+mod fee_schedule;
+/// Module for looking up a melt quote
+mod get_melt_quote;
+/// Module for looking up proof states
+mod get_proof_states;
+/// Module for getting issued and redeemed totals
+mod get_totals;
+/// Module for listing the audit log
+mod list_audit_log;
+/// Module for listing mint quotes
+mod list_mint_quotes;
+/// Module for listing pending melt quotes
+mod list_pending_melts;
+/// Module for listing incomplete sagas
+mod list_sagas;
+/// Module for manually resolving stuck melts
+mod resolve_melt;
 /// Module for rotating to the next keyset
 mod rotate_next_keyset;
 /// Module for updating mint contact information
@@ -23,6 +41,20 @@ mod update_ttl;
 /// Module for managing mint URLs
 mod update_urls;
 
+pub use fee_schedule::{
+    cancel_unit_fee, get_fee_schedule, set_unit_fee, CancelUnitFeeCommand, SetUnitFeeCommand,
+};
+pub use get_melt_quote::{get_melt_quote, GetMeltQuoteCommand};
+pub use get_proof_states::{get_proof_states, GetProofStatesCommand};
+pub use get_totals::{get_totals, GetTotalsCommand};
+pub use list_audit_log::{list_audit_log, ListAuditLogCommand};
+pub use list_mint_quotes::{list_mint_quotes, ListMintQuotesCommand};
+pub use list_pending_melts::{list_pending_melts, ListPendingMeltsCommand};
+pub use list_sagas::{list_sagas, ListSagasCommand};
+pub use resolve_melt::{
+    force_check_melt, mark_melt_failed, mark_melt_paid, ForceCheckMeltCommand,
+    MarkMeltFailedCommand, MarkMeltPaidCommand,
+};
 pub use rotate_next_keyset::{rotate_next_keyset, RotateNextKeysetCommand};
 pub use update_contact::{add_contact, remove_contact, AddContactCommand, RemoveContactCommand};
 pub use update_icon_url::{update_icon_url, UpdateIconUrlCommand};
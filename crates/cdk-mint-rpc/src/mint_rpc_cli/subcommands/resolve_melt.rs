@@ -0,0 +1,104 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::cdk_mint_client::CdkMintClient;
+use crate::{ForceCheckMeltRequest, MarkMeltFailedRequest, MarkMeltPaidRequest};
+
+/// Command to check the payment of a stuck melt with the payment backend
+///
+/// The melt is finalized if the payment is paid and rolled back if it failed.
+#[derive(Args)]
+pub struct ForceCheckMeltCommand {
+    /// The ID of the melt quote
+    quote_id: String,
+}
+
+/// Executes the force_check_melt command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The ID of the melt quote to check
+pub async fn force_check_melt(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &ForceCheckMeltCommand,
+) -> Result<()> {
+    let response = client
+        .force_check_melt(Request::new(ForceCheckMeltRequest {
+            quote_id: sub_command_args.quote_id.clone(),
+        }))
+        .await?;
+
+    let quote = response.into_inner();
+
+    println!("Melt quote {} is {}", quote.id, quote.state);
+
+    Ok(())
+}
+
+/// Command to mark a stuck melt as paid
+///
+/// For bolt11 melts the preimage must match the payment hash of the invoice.
+#[derive(Args)]
+pub struct MarkMeltPaidCommand {
+    /// The ID of the melt quote
+    quote_id: String,
+    /// The hex encoded preimage of the payment
+    payment_preimage: String,
+}
+
+/// Executes the mark_melt_paid command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The ID of the melt quote and the payment preimage
+pub async fn mark_melt_paid(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &MarkMeltPaidCommand,
+) -> Result<()> {
+    let response = client
+        .mark_melt_paid(Request::new(MarkMeltPaidRequest {
+            quote_id: sub_command_args.quote_id.clone(),
+            payment_preimage: sub_command_args.payment_preimage.clone(),
+        }))
+        .await?;
+
+    let quote = response.into_inner();
+
+    println!("Melt quote {} is {}", quote.id, quote.state);
+
+    Ok(())
+}
+
+/// Command to mark a stuck melt as failed
+///
+/// The proofs of the melt are released so the wallet can spend them again. Only
+/// use this once the payment is known to have failed.
+#[derive(Args)]
+pub struct MarkMeltFailedCommand {
+    /// The ID of the melt quote
+    quote_id: String,
+}
+
+/// Executes the mark_melt_failed command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The ID of the melt quote to fail
+pub async fn mark_melt_failed(
+    client: &mut CdkMintClient<Channel>,
+    sub_command_args: &MarkMeltFailedCommand,
+) -> Result<()> {
+    let response = client
+        .mark_melt_failed(Request::new(MarkMeltFailedRequest {
+            quote_id: sub_command_args.quote_id.clone(),
+        }))
+        .await?;
+
+    let quote = response.into_inner();
+
+    println!("Melt quote {} is {}", quote.id, quote.state);
+
+    Ok(())
+}
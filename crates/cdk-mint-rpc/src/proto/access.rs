@@ -0,0 +1,419 @@
+//! Role based access control for the mint management RPC
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use cdk_common::bitcoin::hashes::{sha256, Hash};
+use serde::{Deserialize, Serialize};
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Role granted to a management RPC client
+///
+/// Every role can read the state of the mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// Can only read the mint info, quotes, sagas, totals and audit log
+    ReadOnly,
+    /// Can edit the public mint info
+    InfoEditor,
+    /// Can change limits, fees, quote ttls and resolve quotes
+    Treasury,
+    /// Can rotate keysets
+    KeyAdmin,
+}
+
+impl Role {
+    /// All roles
+    pub const ALL: [Role; 4] = [
+        Role::ReadOnly,
+        Role::InfoEditor,
+        Role::Treasury,
+        Role::KeyAdmin,
+    ];
+
+    /// Whether the role grants the permission
+    pub(crate) fn grants(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::Read => true,
+            Permission::EditInfo => *self == Role::InfoEditor,
+            Permission::Treasury => *self == Role::Treasury,
+            Permission::RotateKeys => *self == Role::KeyAdmin,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::ReadOnly => write!(f, "read-only"),
+            Role::InfoEditor => write!(f, "info-editor"),
+            Role::Treasury => write!(f, "treasury"),
+            Role::KeyAdmin => write!(f, "key-admin"),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read-only" => Ok(Role::ReadOnly),
+            "info-editor" => Ok(Role::InfoEditor),
+            "treasury" => Ok(Role::Treasury),
+            "key-admin" => Ok(Role::KeyAdmin),
+            _ => Err(format!("Unknown role: {s}")),
+        }
+    }
+}
+
+/// Permission required to call an RPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Permission {
+    /// Read the state of the mint
+    Read,
+    /// Edit the public mint info
+    EditInfo,
+    /// Change limits, fees and quotes
+    Treasury,
+    /// Rotate keysets
+    RotateKeys,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Permission::Read => write!(f, "read the mint state"),
+            Permission::EditInfo => write!(f, "edit the mint info"),
+            Permission::Treasury => write!(f, "manage limits, fees and quotes"),
+            Permission::RotateKeys => write!(f, "rotate keysets"),
+        }
+    }
+}
+
+/// Maps management RPC clients to their roles
+///
+/// Clients are identified by the common name of their TLS client certificate
+/// subject or by an API token sent as `authorization: Bearer <token>`. Only the
+/// SHA-256 hashes of the tokens are kept, and they are compared in constant time.
+#[derive(Debug, Clone, Default)]
+pub struct AccessControl {
+    subjects: HashMap<String, HashSet<Role>>,
+    tokens: Vec<(sha256::Hash, HashSet<Role>)>,
+}
+
+impl AccessControl {
+    /// Creates an access control without any clients
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grants roles to clients whose certificate subject has the common name
+    pub fn add_subject<I>(&mut self, common_name: String, roles: I)
+    where
+        I: IntoIterator<Item = Role>,
+    {
+        self.subjects.entry(common_name).or_default().extend(roles);
+    }
+
+    /// Grants roles to clients sending the API token
+    pub fn add_token<I>(&mut self, token: String, roles: I)
+    where
+        I: IntoIterator<Item = Role>,
+    {
+        let token_hash = sha256::Hash::hash(token.as_bytes());
+
+        match self.tokens.iter_mut().find(|(hash, _)| *hash == token_hash) {
+            Some((_, token_roles)) => token_roles.extend(roles),
+            None => self.tokens.push((token_hash, roles.into_iter().collect())),
+        }
+    }
+
+    /// Roles granted to the API token
+    ///
+    /// Every token is compared, so the time taken does not depend on which token
+    /// matches or on how much of a token matches.
+    fn token_roles(&self, token: &str) -> Option<&HashSet<Role>> {
+        let token_hash = sha256::Hash::hash(token.as_bytes());
+
+        self.tokens.iter().fold(None, |found, (hash, roles)| {
+            if constant_time_eq(hash.as_byte_array(), token_hash.as_byte_array()) {
+                Some(roles)
+            } else {
+                found
+            }
+        })
+    }
+
+    /// Whether any client has been added
+    pub fn is_empty(&self) -> bool {
+        self.subjects.is_empty() && self.tokens.is_empty()
+    }
+
+    /// Resolves the roles of the caller, `None` if it is not a known client
+    fn roles<T>(&self, request: &Request<T>) -> Option<HashSet<Role>> {
+        let subject_roles = certificate_common_name(request)
+            .and_then(|common_name| self.subjects.get(&common_name));
+        let token_roles = match bearer_token(request) {
+            // An unknown token is rejected even if the certificate is known
+            Some(token) => Some(self.token_roles(token)?),
+            None => None,
+        };
+
+        if subject_roles.is_none() && token_roles.is_none() {
+            return None;
+        }
+
+        Some(
+            subject_roles
+                .into_iter()
+                .chain(token_roles)
+                .flatten()
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Caller of an RPC, added to the request by the [`AccessInterceptor`]
+#[derive(Debug, Clone)]
+struct Caller {
+    roles: HashSet<Role>,
+    identity: Option<String>,
+}
+
+/// Interceptor resolving the caller of each RPC
+///
+/// Without an [`AccessControl`] every client trusted by the TLS config has all
+/// roles.
+#[derive(Debug, Clone)]
+pub(crate) struct AccessInterceptor {
+    access_control: Option<Arc<AccessControl>>,
+}
+
+impl AccessInterceptor {
+    /// Creates a new interceptor
+    pub(crate) fn new(access_control: Option<Arc<AccessControl>>) -> Self {
+        Self { access_control }
+    }
+}
+
+impl Interceptor for AccessInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let roles = match &self.access_control {
+            Some(access_control) => access_control.roles(&request).ok_or_else(|| {
+                Status::unauthenticated(
+                    "Client is not allowed to use the management RPC".to_string(),
+                )
+            })?,
+            None => HashSet::from(Role::ALL),
+        };
+
+        let identity = certificate_fingerprint(&request).or_else(|| {
+            bearer_token(&request).map(|token| {
+                let hash = sha256::Hash::hash(token.as_bytes()).to_string();
+                format!("token:{}", &hash[..16])
+            })
+        });
+
+        request.extensions_mut().insert(Caller { roles, identity });
+
+        Ok(request)
+    }
+}
+
+/// The caller lacks the permission required by an RPC
+#[derive(Debug)]
+pub(crate) struct PermissionDenied(Permission);
+
+impl From<PermissionDenied> for Status {
+    fn from(err: PermissionDenied) -> Self {
+        Status::permission_denied(format!("Client is not allowed to {}", err.0))
+    }
+}
+
+/// Checks that the caller was granted a role with the permission
+pub(crate) fn authorize<T>(
+    request: &Request<T>,
+    permission: Permission,
+) -> Result<(), PermissionDenied> {
+    let granted = request
+        .extensions()
+        .get::<Caller>()
+        .is_some_and(|caller| caller.roles.iter().any(|role| role.grants(permission)));
+
+    if granted {
+        Ok(())
+    } else {
+        Err(PermissionDenied(permission))
+    }
+}
+
+/// Identifies the caller by its TLS client certificate fingerprint or API token
+pub(crate) fn caller_identity<T>(request: &Request<T>) -> Option<String> {
+    request
+        .extensions()
+        .get::<Caller>()
+        .and_then(|caller| caller.identity.clone())
+}
+
+/// SHA-256 fingerprint of the caller's TLS client certificate
+fn certificate_fingerprint<T>(request: &Request<T>) -> Option<String> {
+    let certs = request.peer_certs()?;
+    let cert = certs.first()?;
+
+    Some(format!("sha256:{}", sha256::Hash::hash(cert.as_ref())))
+}
+
+/// Common name of the subject of the caller's TLS client certificate
+fn certificate_common_name<T>(request: &Request<T>) -> Option<String> {
+    let certs = request.peer_certs()?;
+    let (_, cert) = X509Certificate::from_der(certs.first()?.as_ref()).ok()?;
+    let common_name = cert.subject().iter_common_name().next()?;
+
+    common_name.as_str().ok().map(str::to_string)
+}
+
+/// Compares two hashes without stopping at the first differing byte
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let difference = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b));
+
+    std::hint::black_box(difference) == 0
+}
+
+/// API token sent as `authorization: Bearer <token>`
+fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get("authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the interceptor on a request sending the API token
+    fn intercept(
+        access_control: Option<AccessControl>,
+        authorization: Option<&str>,
+    ) -> Result<Request<()>, tonic::Code> {
+        let mut request = Request::new(());
+        if let Some(authorization) = authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", authorization.parse().unwrap());
+        }
+
+        AccessInterceptor::new(access_control.map(Arc::new))
+            .call(request)
+            .map_err(|status| status.code())
+    }
+
+    fn access_control() -> AccessControl {
+        let mut access_control = AccessControl::new();
+        access_control.add_token("reader".to_string(), [Role::ReadOnly]);
+        access_control.add_token("operator".to_string(), [Role::InfoEditor]);
+        access_control.add_token("operator".to_string(), [Role::Treasury]);
+        access_control
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        let permissions = [
+            Permission::Read,
+            Permission::EditInfo,
+            Permission::Treasury,
+            Permission::RotateKeys,
+        ];
+
+        for role in Role::ALL {
+            for permission in permissions {
+                let expected = match permission {
+                    Permission::Read => true,
+                    Permission::EditInfo => role == Role::InfoEditor,
+                    Permission::Treasury => role == Role::Treasury,
+                    Permission::RotateKeys => role == Role::KeyAdmin,
+                };
+                assert_eq!(role.grants(permission), expected, "{role} {permission}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_token_roles() {
+        let request = intercept(Some(access_control()), Some("Bearer reader")).unwrap();
+        assert!(authorize(&request, Permission::Read).is_ok());
+        assert!(authorize(&request, Permission::EditInfo).is_err());
+        assert!(authorize(&request, Permission::Treasury).is_err());
+        assert!(authorize(&request, Permission::RotateKeys).is_err());
+
+        let request = intercept(Some(access_control()), Some("Bearer operator")).unwrap();
+        assert!(authorize(&request, Permission::Read).is_ok());
+        assert!(authorize(&request, Permission::EditInfo).is_ok());
+        assert!(authorize(&request, Permission::Treasury).is_ok());
+        assert!(authorize(&request, Permission::RotateKeys).is_err());
+
+        let identity = caller_identity(&request).unwrap();
+        assert!(identity.starts_with("token:"));
+        assert_eq!(identity.len(), "token:".len() + 16);
+    }
+
+    #[test]
+    fn test_missing_or_invalid_credentials() {
+        for authorization in [
+            None,
+            Some("Bearer unknown"),
+            Some("Bearer reade"),
+            Some("Bearer "),
+            Some("reader"),
+            Some("Basic reader"),
+        ] {
+            let code = intercept(Some(access_control()), authorization).unwrap_err();
+            assert_eq!(code, tonic::Code::Unauthenticated, "{authorization:?}");
+        }
+    }
+
+    #[test]
+    fn test_without_access_control() {
+        let request = intercept(None, None).unwrap();
+
+        assert!(authorize(&request, Permission::Read).is_ok());
+        assert!(authorize(&request, Permission::EditInfo).is_ok());
+        assert!(authorize(&request, Permission::Treasury).is_ok());
+        assert!(authorize(&request, Permission::RotateKeys).is_ok());
+        assert_eq!(caller_identity(&request), None);
+    }
+
+    #[test]
+    fn test_authorize_requires_intercepted_caller() {
+        let request = Request::new(());
+
+        let status: Status = authorize(&request, Permission::Read).unwrap_err().into();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        let a = [1u8; 32];
+        let mut b = a;
+        assert!(constant_time_eq(&a, &b));
+
+        b[31] = 0;
+        assert!(!constant_time_eq(&a, &b));
+
+        b = a;
+        b[0] = 0;
+        assert!(!constant_time_eq(&a, &b));
+    }
+}
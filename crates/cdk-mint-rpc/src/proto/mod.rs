@@ -2,6 +2,8 @@
 
 tonic::include_proto!("cdk_mint_rpc");
 
+mod access;
 mod server;
 
+pub use access::{AccessControl, Role};
 pub use server::MintRPCServer;
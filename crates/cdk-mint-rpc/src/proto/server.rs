@@ -1,14 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use cdk::mint::{Mint, MintQuote};
+use cdk::mint::{MeltQuote, Mint, MintQuote, PendingFee};
 use cdk::nuts::nut04::MintMethodSettings;
 use cdk::nuts::nut05::MeltMethodSettings;
-use cdk::nuts::{CurrencyUnit, MintQuoteState, PaymentMethod};
+use cdk::nuts::{CurrencyUnit, MeltQuoteState, MintQuoteState, PaymentMethod, PublicKey, State};
 use cdk::types::QuoteTTL;
 use cdk::Amount;
+use cdk_common::database::mint::{AuditLogFilter, MeltQuoteFilter, MintQuoteFilter};
+use cdk_common::mint::{self as common_mint, OperationKind, Saga};
 use cdk_common::payment::WaitPaymentResponse;
 use thiserror::Error;
 use tokio::sync::Notify;
@@ -17,15 +20,26 @@ use tokio::time::Duration;
 use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 
+use super::access::{authorize, caller_identity, AccessControl, AccessInterceptor, Permission};
 use crate::cdk_mint_server::{CdkMint, CdkMintServer};
 use crate::{
-    ContactInfo, GetInfoRequest, GetInfoResponse, GetQuoteTtlRequest, GetQuoteTtlResponse,
-    RotateNextKeysetRequest, RotateNextKeysetResponse, UpdateContactRequest,
-    UpdateDescriptionRequest, UpdateIconUrlRequest, UpdateMotdRequest, UpdateNameRequest,
-    UpdateNut04QuoteRequest, UpdateNut04Request, UpdateNut05Request, UpdateQuoteTtlRequest,
-    UpdateResponse, UpdateUrlRequest,
+    CancelUnitFeeRequest, ContactInfo, ForceCheckMeltRequest, GetFeeScheduleRequest,
+    GetFeeScheduleResponse, GetInfoRequest, GetInfoResponse, GetMeltQuoteRequest,
+    GetProofStatesRequest, GetProofStatesResponse, GetQuoteTtlRequest, GetQuoteTtlResponse,
+    GetTotalsRequest, GetTotalsResponse, KeysetTotals, ListAuditLogRequest, ListAuditLogResponse,
+    ListMeltQuotesResponse, ListMintQuotesRequest, ListMintQuotesResponse, ListPendingMeltsRequest,
+    ListSagasRequest, ListSagasResponse, MarkMeltFailedRequest, MarkMeltPaidRequest, ProofState,
+    RotateNextKeysetRequest, RotateNextKeysetResponse, SetUnitFeeRequest, SetUnitFeeResponse,
+    UnitFee, UnitTotals, UpdateContactRequest, UpdateDescriptionRequest, UpdateIconUrlRequest,
+    UpdateMotdRequest, UpdateNameRequest, UpdateNut04QuoteRequest, UpdateNut04Request,
+    UpdateNut05Request, UpdateQuoteTtlRequest, UpdateResponse, UpdateUrlRequest,
 };
 
+/// Page size used when a list request does not set a limit
+const DEFAULT_PAGE_LIMIT: u64 = 100;
+/// Largest page a list request can ask for
+const MAX_PAGE_LIMIT: u64 = 1000;
+
 /// Error
 #[derive(Debug, Error)]
 pub enum Error {
@@ -47,6 +61,7 @@ pub struct MintRPCServer {
     mint: Arc<Mint>,
     shutdown: Arc<Notify>,
     handle: Option<Arc<JoinHandle<Result<(), Error>>>>,
+    access_control: Option<Arc<AccessControl>>,
 }
 
 impl MintRPCServer {
@@ -62,9 +77,18 @@ impl MintRPCServer {
             mint,
             shutdown: Arc::new(Notify::new()),
             handle: None,
+            access_control: None,
         })
     }
 
+    /// Restricts the RPCs each client may call to the roles granted to it
+    ///
+    /// Without access control every client trusted by the TLS config has all roles.
+    pub fn with_access_control(mut self, access_control: AccessControl) -> Self {
+        self.access_control = Some(Arc::new(access_control));
+        self
+    }
+
     /// Starts the RPC server
     ///
     /// # Arguments
@@ -82,6 +106,11 @@ impl MintRPCServer {
             let _ = rustls::crypto::ring::default_provider().install_default();
         }
 
+        let service = CdkMintServer::with_interceptor(
+            self.clone(),
+            AccessInterceptor::new(self.access_control.clone()),
+        );
+
         let server = match tls_dir {
             Some(tls_dir) => {
                 tracing::info!("TLS configuration found, starting secure server");
@@ -136,11 +165,11 @@ impl MintRPCServer {
 
                 Server::builder()
                     .tls_config(tls_config)?
-                    .add_service(CdkMintServer::new(self.clone()))
+                    .add_service(service)
             }
             None => {
                 tracing::warn!("No valid TLS configuration found, starting insecure server");
-                Server::builder().add_service(CdkMintServer::new(self.clone()))
+                Server::builder().add_service(service)
             }
         };
 
@@ -181,13 +210,124 @@ impl Drop for MintRPCServer {
     }
 }
 
+/// Page size of a list request, capped at [`MAX_PAGE_LIMIT`]
+fn page_limit(limit: Option<u64>) -> u64 {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)
+}
+
+/// Creates the audit log entry of an administrative action, to be recorded in the
+/// transaction making the change
+fn audit_entry(
+    caller: Option<String>,
+    action: &str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+) -> common_mint::AuditLogEntry {
+    tracing::info!(
+        "Mint rpc action {} by {}",
+        action,
+        caller.as_deref().unwrap_or("unknown caller")
+    );
+
+    common_mint::AuditLogEntry::new(action.to_string(), old_value, new_value, caller)
+}
+
+/// Serializes a value for the audit log, skipping unset values
+fn json_value<T: serde::Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::Null) | Err(_) => None,
+        Ok(value) => Some(value.to_string()),
+    }
+}
+
+/// Maps errors of the manual melt resolution to a status
+fn melt_resolution_status(err: cdk::Error) -> Status {
+    match err {
+        cdk::Error::UnknownQuote => Status::not_found(err.to_string()),
+        cdk::Error::InvalidPaymentPreimage => Status::invalid_argument(err.to_string()),
+        cdk::Error::PaidQuote
+        | cdk::Error::UnpaidQuote
+        | cdk::Error::PendingQuote
+        | cdk::Error::PaymentFailed
+        | cdk::Error::UnknownPaymentState => Status::failed_precondition(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
+
+impl From<MintQuote> for crate::MintQuote {
+    fn from(quote: MintQuote) -> Self {
+        Self {
+            state: quote.state().to_string(),
+            amount_paid: quote.amount_paid().into(),
+            amount_issued: quote.amount_issued().into(),
+            id: quote.id.to_string(),
+            request: quote.request,
+            amount: quote.amount.map(|a| a.into()),
+            unit: quote.unit.to_string(),
+            method: quote.payment_method.to_string(),
+            expiry: quote.expiry,
+            request_lookup_id: quote.request_lookup_id.to_string(),
+            pubkey: quote.pubkey.map(|p| p.to_hex()),
+            created_time: quote.created_time,
+        }
+    }
+}
+
+impl From<MeltQuote> for crate::MeltQuote {
+    fn from(quote: MeltQuote) -> Self {
+        Self {
+            id: quote.id.to_string(),
+            request: quote.request.to_string(),
+            amount: quote.amount.into(),
+            fee_reserve: quote.fee_reserve.into(),
+            unit: quote.unit.to_string(),
+            state: quote.state.to_string(),
+            method: quote.payment_method.to_string(),
+            expiry: quote.expiry,
+            request_lookup_id: quote.request_lookup_id.map(|id| id.to_string()),
+            payment_preimage: quote.payment_preimage,
+            created_time: quote.created_time,
+            paid_time: quote.paid_time,
+        }
+    }
+}
+
+impl From<common_mint::AuditLogEntry> for crate::AuditLogEntry {
+    fn from(entry: common_mint::AuditLogEntry) -> Self {
+        Self {
+            id: entry.id.to_string(),
+            action: entry.action,
+            old_value: entry.old_value,
+            new_value: entry.new_value,
+            caller: entry.caller,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+impl From<Saga> for crate::Saga {
+    fn from(saga: Saga) -> Self {
+        Self {
+            operation_id: saga.operation_id.to_string(),
+            operation_kind: saga.operation_kind.to_string(),
+            state: saga.state.state().to_string(),
+            quote_id: saga.quote_id,
+            input_ys: saga.input_ys.iter().map(|y| y.to_hex()).collect(),
+            blinded_secrets: saga.blinded_secrets.iter().map(|b| b.to_hex()).collect(),
+            created_at: saga.created_at,
+            updated_at: saga.updated_at,
+        }
+    }
+}
+
 #[tonic::async_trait]
 impl CdkMint for MintRPCServer {
     /// Returns information about the mint
     async fn get_info(
         &self,
-        _request: Request<GetInfoRequest>,
+        request: Request<GetInfoRequest>,
     ) -> Result<Response<GetInfoResponse>, Status> {
+        authorize(&request, Permission::Read)?;
         let info = self
             .mint
             .mint_info()
@@ -241,16 +381,23 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateMotdRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let motd = request.into_inner().motd;
         let mut info = self
             .mint
             .mint_info()
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+        let old_value = json_value(&info.motd);
         info.motd = Some(motd);
+        let new_value = json_value(&info.motd);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "update_motd", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
@@ -262,6 +409,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateDescriptionRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let description = request.into_inner().description;
         let mut info = self
             .mint
@@ -269,12 +418,23 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.description);
         info.description = Some(description);
+        let new_value = json_value(&info.description);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(
+                    caller,
+                    "update_short_description",
+                    old_value,
+                    new_value,
+                )),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -283,6 +443,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateDescriptionRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let description = request.into_inner().description;
         let mut info = self
             .mint
@@ -290,12 +452,23 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.description_long);
         info.description_long = Some(description);
+        let new_value = json_value(&info.description_long);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(
+                    caller,
+                    "update_long_description",
+                    old_value,
+                    new_value,
+                )),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -304,6 +477,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateNameRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let name = request.into_inner().name;
         let mut info = self
             .mint
@@ -311,12 +486,18 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.name);
         info.name = Some(name);
+        let new_value = json_value(&info.name);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "update_name", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -325,6 +506,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateIconUrlRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let icon_url = request.into_inner().icon_url;
 
         let mut info = self
@@ -333,12 +516,18 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.icon_url);
         info.icon_url = Some(icon_url);
+        let new_value = json_value(&info.icon_url);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "update_icon_url", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -347,21 +536,30 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateUrlRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let url = request.into_inner().url;
         let mut info = self
             .mint
             .mint_info()
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+        let old_value = json_value(&info.urls);
         let mut urls = info.urls.unwrap_or_default();
         urls.push(url);
 
         info.urls = Some(urls.clone());
 
+        let new_value = json_value(&info.urls);
+
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "add_url", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -370,12 +568,15 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateUrlRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let url = request.into_inner().url;
         let mut info = self
             .mint
             .mint_info()
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+        let old_value = json_value(&info.urls);
         let urls = info.urls;
         let mut urls = urls.clone().unwrap_or_default();
 
@@ -385,10 +586,16 @@ impl CdkMint for MintRPCServer {
 
         info.urls = urls;
 
+        let new_value = json_value(&info.urls);
+
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "remove_url", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
 
@@ -397,6 +604,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateContactRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let request_inner = request.into_inner();
         let mut info = self
             .mint
@@ -404,6 +613,8 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.contact);
+
         info.contact
             .get_or_insert_with(Vec::new)
             .push(cdk::nuts::ContactInfo::new(
@@ -411,10 +622,16 @@ impl CdkMint for MintRPCServer {
                 request_inner.info,
             ));
 
+        let new_value = json_value(&info.contact);
+
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "add_contact", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
+
         Ok(Response::new(UpdateResponse {}))
     }
     /// Removes a contact method from the mint's contact information
@@ -422,6 +639,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateContactRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::EditInfo)?;
+        let caller = caller_identity(&request);
         let request_inner = request.into_inner();
         let mut info = self
             .mint
@@ -429,13 +648,19 @@ impl CdkMint for MintRPCServer {
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
+        let old_value = json_value(&info.contact);
+
         if let Some(contact) = info.contact.as_mut() {
             let contact_info =
                 cdk::nuts::ContactInfo::new(request_inner.method, request_inner.info);
             contact.retain(|x| x != &contact_info);
+            let new_value = json_value(&info.contact);
 
             self.mint
-                .set_mint_info(info)
+                .set_mint_info_with_audit(
+                    info,
+                    Some(audit_entry(caller, "remove_contact", old_value, new_value)),
+                )
                 .await
                 .map_err(|err| Status::internal(err.to_string()))?;
         }
@@ -447,6 +672,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateNut04Request>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
         let mut info = self
             .mint
             .mint_info()
@@ -454,6 +681,7 @@ impl CdkMint for MintRPCServer {
             .map_err(|err| Status::internal(err.to_string()))?;
 
         let mut nut04_settings = info.nuts.nut04.clone();
+        let old_value = json_value(&nut04_settings);
 
         let request_inner = request.into_inner();
 
@@ -473,8 +701,25 @@ impl CdkMint for MintRPCServer {
 
         // Create options from the request
         let options = if let Some(options) = request_inner.options {
+            // Only the description flag is set over rpc, keep the rest of the current options
+            let (description_hash, min_expiry, max_expiry) = match current_nut04_settings
+                .as_ref()
+                .and_then(|s| s.options.clone())
+            {
+                Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 {
+                    description_hash,
+                    min_expiry,
+                    max_expiry,
+                    ..
+                }) => (description_hash, min_expiry, max_expiry),
+                None => (false, None, None),
+            };
+
             Some(cdk::nuts::nut04::MintMethodOptions::Bolt11 {
                 description: options.description,
+                description_hash,
+                min_expiry,
+                max_expiry,
             })
         } else if let Some(current_settings) = current_nut04_settings.as_ref() {
             current_settings.options.clone()
@@ -505,9 +750,13 @@ impl CdkMint for MintRPCServer {
         }
 
         info.nuts.nut04 = nut04_settings;
+        let new_value = json_value(&info.nuts.nut04);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "update_nut04", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
@@ -519,12 +768,15 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateNut05Request>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
         let mut info = self
             .mint
             .mint_info()
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
         let mut nut05_settings = info.nuts.nut05.clone();
+        let old_value = json_value(&nut05_settings);
 
         let request_inner = request.into_inner();
 
@@ -575,9 +827,13 @@ impl CdkMint for MintRPCServer {
         }
 
         info.nuts.nut05 = nut05_settings;
+        let new_value = json_value(&info.nuts.nut05);
 
         self.mint
-            .set_mint_info(info)
+            .set_mint_info_with_audit(
+                info,
+                Some(audit_entry(caller, "update_nut05", old_value, new_value)),
+            )
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
@@ -589,6 +845,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateQuoteTtlRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
         let current_ttl = self
             .mint
             .quote_ttl()
@@ -602,8 +860,15 @@ impl CdkMint for MintRPCServer {
             melt_ttl: request.melt_ttl.unwrap_or(current_ttl.melt_ttl),
         };
 
+        let audit = audit_entry(
+            caller,
+            "update_quote_ttl",
+            json_value(&current_ttl),
+            json_value(&quote_ttl),
+        );
+
         self.mint
-            .set_quote_ttl(quote_ttl)
+            .set_quote_ttl_with_audit(quote_ttl, Some(audit))
             .await
             .map_err(|err| Status::internal(err.to_string()))?;
 
@@ -613,8 +878,9 @@ impl CdkMint for MintRPCServer {
     /// Gets the mint's quote time-to-live settings
     async fn get_quote_ttl(
         &self,
-        _request: Request<GetQuoteTtlRequest>,
+        request: Request<GetQuoteTtlRequest>,
     ) -> Result<Response<GetQuoteTtlResponse>, Status> {
+        authorize(&request, Permission::Read)?;
         let ttl = self
             .mint
             .quote_ttl()
@@ -632,6 +898,8 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<UpdateNut04QuoteRequest>,
     ) -> Result<Response<UpdateNut04QuoteRequest>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
         let request = request.into_inner();
         let quote_id = request
             .quote_id
@@ -649,6 +917,14 @@ impl CdkMint for MintRPCServer {
             .map_err(|_| Status::invalid_argument("Could not find quote".to_string()))?
             .ok_or(Status::invalid_argument("Could not find quote".to_string()))?;
 
+        let old_state = mint_quote.state();
+
+        let localstore = self.mint.localstore();
+        let mut tx = localstore
+            .begin_transaction()
+            .await
+            .map_err(|_| Status::internal("Could not start db transaction".to_string()))?;
+
         match state {
             MintQuoteState::Paid => {
                 // Create a dummy payment response
@@ -659,20 +935,10 @@ impl CdkMint for MintRPCServer {
                     payment_identifier: mint_quote.request_lookup_id.clone(),
                 };
 
-                let localstore = self.mint.localstore();
-                let mut tx = localstore
-                    .begin_transaction()
-                    .await
-                    .map_err(|_| Status::internal("Could not start db transaction".to_string()))?;
-
                 self.mint
                     .pay_mint_quote(&mut tx, &mint_quote, response)
                     .await
                     .map_err(|_| Status::internal("Could not process payment".to_string()))?;
-
-                tx.commit()
-                    .await
-                    .map_err(|_| Status::internal("Could not commit db transaction".to_string()))?;
             }
             _ => {
                 // Create a new quote with the same values
@@ -692,28 +958,32 @@ impl CdkMint for MintRPCServer {
                     vec![],                               // payment_ids
                 );
 
-                let mint_store = self.mint.localstore();
-                let mut tx = mint_store
-                    .begin_transaction()
-                    .await
-                    .map_err(|_| Status::internal("Could not update quote".to_string()))?;
-                tx.add_mint_quote(quote.clone())
-                    .await
-                    .map_err(|_| Status::internal("Could not update quote".to_string()))?;
-                tx.commit()
+                tx.add_mint_quote(quote)
                     .await
                     .map_err(|_| Status::internal("Could not update quote".to_string()))?;
             }
         }
 
-        let mint_quote = self
-            .mint
-            .localstore()
+        let mint_quote = tx
             .get_mint_quote(&quote_id)
             .await
-            .map_err(|_| Status::invalid_argument("Could not find quote".to_string()))?
+            .map_err(|_| Status::internal("Could not update quote".to_string()))?
             .ok_or(Status::invalid_argument("Could not find quote".to_string()))?;
 
+        let audit = audit_entry(
+            caller,
+            "update_nut04_quote",
+            Some(format!("{} {}", mint_quote.id, old_state)),
+            Some(format!("{} {}", mint_quote.id, mint_quote.state())),
+        );
+        tx.add_audit_log_entry(&audit)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Status::internal("Could not commit db transaction".to_string()))?;
+
         Ok(Response::new(UpdateNut04QuoteRequest {
             state: mint_quote.state().to_string(),
             quote_id: mint_quote.id.to_string(),
@@ -725,17 +995,37 @@ impl CdkMint for MintRPCServer {
         &self,
         request: Request<RotateNextKeysetRequest>,
     ) -> Result<Response<RotateNextKeysetResponse>, Status> {
+        authorize(&request, Permission::RotateKeys)?;
+        let caller = caller_identity(&request);
         let request = request.into_inner();
 
         let unit = CurrencyUnit::from_str(&request.unit)
             .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
 
+        let old_value = self
+            .mint
+            .keysets()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.active && keyset.unit == unit)
+            .map(|keyset| keyset.id.to_string());
+
+        // Without an explicit fee the pending fee of the unit is applied
+        let pending_fee_ppk = self
+            .mint
+            .pending_fee(&unit)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map(|pending| pending.input_fee_ppk)
+            .unwrap_or(0);
+
         let keyset_info = self
             .mint
-            .rotate_keyset(
+            .rotate_keyset_with_audit(
                 unit,
                 request.max_order.map(|a| a as u8).unwrap_or(32),
-                request.input_fee_ppk.unwrap_or(0),
+                request.input_fee_ppk.unwrap_or(pending_fee_ppk),
+                Some(audit_entry(caller, "rotate_next_keyset", old_value, None)),
             )
             .await
             .map_err(|_| Status::invalid_argument("Could not rotate keyset".to_string()))?;
@@ -747,4 +1037,830 @@ impl CdkMint for MintRPCServer {
             input_fee_ppk: keyset_info.input_fee_ppk,
         }))
     }
+
+    /// Lists mint quotes matching the request filters
+    async fn list_mint_quotes(
+        &self,
+        request: Request<ListMintQuotesRequest>,
+    ) -> Result<Response<ListMintQuotesResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let unit = request
+            .unit
+            .map(|unit| CurrencyUnit::from_str(&unit))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+        let state = request
+            .state
+            .map(|state| {
+                MintQuoteState::from_str(&state)
+                    .map_err(|_| Status::invalid_argument("Invalid quote state".to_string()))
+            })
+            .transpose()?;
+        let method = request
+            .method
+            .map(|method| PaymentMethod::from_str(&method))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid payment method".to_string()))?;
+
+        let filter = MintQuoteFilter {
+            unit,
+            state,
+            payment_method: method,
+            created_after: request.created_after,
+            created_before: request.created_before,
+            pubkey: None,
+        };
+
+        let (quotes, total) = self
+            .mint
+            .localstore()
+            .list_mint_quotes(&filter, request.offset, page_limit(request.limit))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ListMintQuotesResponse {
+            quotes: quotes.into_iter().map(|quote| quote.into()).collect(),
+            total,
+        }))
+    }
+
+    /// Gets a melt quote by id
+    async fn get_melt_quote(
+        &self,
+        request: Request<GetMeltQuoteRequest>,
+    ) -> Result<Response<crate::MeltQuote>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+        let quote_id = request
+            .quote_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid quote id".to_string()))?;
+
+        let quote = self
+            .mint
+            .localstore()
+            .get_melt_quote(&quote_id)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or(Status::not_found("Could not find quote".to_string()))?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    /// Lists melt quotes whose payment is still pending
+    async fn list_pending_melts(
+        &self,
+        request: Request<ListPendingMeltsRequest>,
+    ) -> Result<Response<ListMeltQuotesResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let unit = request
+            .unit
+            .map(|unit| CurrencyUnit::from_str(&unit))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+
+        let filter = MeltQuoteFilter {
+            unit,
+            state: Some(MeltQuoteState::Pending),
+        };
+
+        let (quotes, total) = self
+            .mint
+            .localstore()
+            .list_melt_quotes(&filter, request.offset, page_limit(request.limit))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ListMeltQuotesResponse {
+            quotes: quotes.into_iter().map(|quote| quote.into()).collect(),
+            total,
+        }))
+    }
+
+    /// Lists sagas that have not completed
+    async fn list_sagas(
+        &self,
+        request: Request<ListSagasRequest>,
+    ) -> Result<Response<ListSagasResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let operation_kind = request
+            .operation_kind
+            .map(|kind| OperationKind::from_str(&kind))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid operation kind".to_string()))?;
+
+        let (sagas, total) = self
+            .mint
+            .localstore()
+            .list_incomplete_sagas(operation_kind, request.offset, page_limit(request.limit))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ListSagasResponse {
+            sagas: sagas.into_iter().map(|saga| saga.into()).collect(),
+            total,
+        }))
+    }
+
+    /// Gets the state of proofs by their Y values
+    async fn get_proof_states(
+        &self,
+        request: Request<GetProofStatesRequest>,
+    ) -> Result<Response<GetProofStatesResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let ys = request
+            .ys
+            .iter()
+            .map(PublicKey::from_hex)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Status::invalid_argument("Invalid Y".to_string()))?;
+
+        let localstore = self.mint.localstore();
+
+        let states = localstore
+            .get_proofs_states(&ys)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let proofs = localstore
+            .get_proofs_by_ys(&ys)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let states = ys
+            .iter()
+            .zip(states)
+            .zip(proofs)
+            .map(|((y, state), proof)| ProofState {
+                y: y.to_hex(),
+                // Proofs the mint has no record of are unspent
+                state: state.unwrap_or(State::Unspent).to_string(),
+                keyset_id: proof.as_ref().map(|p| p.keyset_id.to_string()),
+                amount: proof.map(|p| p.amount.into()),
+            })
+            .collect();
+
+        Ok(Response::new(GetProofStatesResponse { states }))
+    }
+
+    /// Gets the issued and redeemed totals per keyset and per unit
+    async fn get_totals(
+        &self,
+        request: Request<GetTotalsRequest>,
+    ) -> Result<Response<GetTotalsResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let unit = request
+            .unit
+            .map(|unit| CurrencyUnit::from_str(&unit))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+
+        let total_issued = self
+            .mint
+            .total_issued()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let total_redeemed = self
+            .mint
+            .total_redeemed()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let keyset_ids: HashSet<_> = total_issued.keys().chain(total_redeemed.keys()).collect();
+
+        let mut keysets = Vec::new();
+        let mut units: HashMap<CurrencyUnit, (Amount, Amount)> = HashMap::new();
+
+        for keyset_id in keyset_ids {
+            let keyset_info = self
+                .mint
+                .get_keyset_info(keyset_id)
+                .ok_or(Status::internal(format!("Unknown keyset {keyset_id}")))?;
+
+            if unit.as_ref().is_some_and(|unit| unit != &keyset_info.unit) {
+                continue;
+            }
+
+            let issued = total_issued.get(keyset_id).copied().unwrap_or_default();
+            let redeemed = total_redeemed.get(keyset_id).copied().unwrap_or_default();
+
+            let unit_totals = units.entry(keyset_info.unit.clone()).or_default();
+            unit_totals.0 = unit_totals
+                .0
+                .checked_add(issued)
+                .ok_or(Status::internal("Overflow".to_string()))?;
+            unit_totals.1 = unit_totals
+                .1
+                .checked_add(redeemed)
+                .ok_or(Status::internal("Overflow".to_string()))?;
+
+            keysets.push(KeysetTotals {
+                keyset_id: keyset_id.to_string(),
+                unit: keyset_info.unit.to_string(),
+                active: keyset_info.active,
+                total_issued: issued.into(),
+                total_redeemed: redeemed.into(),
+            });
+        }
+
+        keysets.sort_by(|a, b| (&a.unit, &a.keyset_id).cmp(&(&b.unit, &b.keyset_id)));
+
+        let mut units: Vec<UnitTotals> = units
+            .into_iter()
+            .map(|(unit, (issued, redeemed))| UnitTotals {
+                unit: unit.to_string(),
+                total_issued: issued.into(),
+                total_redeemed: redeemed.into(),
+            })
+            .collect();
+
+        units.sort_by(|a, b| a.unit.cmp(&b.unit));
+
+        Ok(Response::new(GetTotalsResponse { keysets, units }))
+    }
+
+    /// Checks the payment of a stuck melt with the payment backend and resolves it
+    async fn force_check_melt(
+        &self,
+        request: Request<ForceCheckMeltRequest>,
+    ) -> Result<Response<crate::MeltQuote>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
+        let request = request.into_inner();
+        let quote_id = request
+            .quote_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid quote id".to_string()))?;
+
+        let audit = audit_entry(
+            caller,
+            "force_check_melt",
+            Some(format!("{} {}", quote_id, MeltQuoteState::Pending)),
+            None,
+        );
+
+        let quote = self
+            .mint
+            .force_check_melt_quote(&quote_id, Some(audit))
+            .await
+            .map_err(melt_resolution_status)?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    /// Marks a stuck melt as paid
+    async fn mark_melt_paid(
+        &self,
+        request: Request<MarkMeltPaidRequest>,
+    ) -> Result<Response<crate::MeltQuote>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
+        let request = request.into_inner();
+        let quote_id = request
+            .quote_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid quote id".to_string()))?;
+
+        let audit = audit_entry(
+            caller,
+            "mark_melt_paid",
+            Some(format!("{} {}", quote_id, MeltQuoteState::Pending)),
+            Some(format!("{} {}", quote_id, MeltQuoteState::Paid)),
+        );
+
+        let quote = self
+            .mint
+            .mark_melt_quote_paid(&quote_id, request.payment_preimage, Some(audit))
+            .await
+            .map_err(melt_resolution_status)?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    /// Marks a stuck melt as failed
+    async fn mark_melt_failed(
+        &self,
+        request: Request<MarkMeltFailedRequest>,
+    ) -> Result<Response<crate::MeltQuote>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
+        let request = request.into_inner();
+        let quote_id = request
+            .quote_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid quote id".to_string()))?;
+
+        let audit = audit_entry(
+            caller,
+            "mark_melt_failed",
+            Some(format!("{} {}", quote_id, MeltQuoteState::Pending)),
+            Some(format!("{} {}", quote_id, MeltQuoteState::Unpaid)),
+        );
+
+        let quote = self
+            .mint
+            .mark_melt_quote_failed(&quote_id, Some(audit))
+            .await
+            .map_err(melt_resolution_status)?;
+
+        Ok(Response::new(quote.into()))
+    }
+
+    /// Lists recorded administrative actions, newest first
+    async fn list_audit_log(
+        &self,
+        request: Request<ListAuditLogRequest>,
+    ) -> Result<Response<ListAuditLogResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+        let request = request.into_inner();
+
+        let filter = AuditLogFilter {
+            action: request.action,
+            created_after: request.created_after,
+        };
+
+        let (entries, total) = self
+            .mint
+            .localstore()
+            .list_audit_log(&filter, request.offset, page_limit(request.limit))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ListAuditLogResponse {
+            entries: entries.into_iter().map(Into::into).collect(),
+            total,
+        }))
+    }
+
+    /// Gets the input fee of each unit along with its pending fee
+    async fn get_fee_schedule(
+        &self,
+        request: Request<GetFeeScheduleRequest>,
+    ) -> Result<Response<GetFeeScheduleResponse>, Status> {
+        authorize(&request, Permission::Read)?;
+
+        let schedule = self
+            .mint
+            .fee_schedule()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let units = self
+            .mint
+            .keysets()
+            .keysets
+            .into_iter()
+            .filter(|keyset| keyset.active)
+            .map(|keyset| {
+                let pending = schedule.iter().find(|p| p.unit == keyset.unit);
+
+                UnitFee {
+                    unit: keyset.unit.to_string(),
+                    active_keyset_id: keyset.id.to_string(),
+                    input_fee_ppk: keyset.input_fee_ppk,
+                    pending_fee_ppk: pending.map(|p| p.input_fee_ppk),
+                    rotate_at: pending.and_then(|p| p.rotate_at),
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetFeeScheduleResponse { units }))
+    }
+
+    /// Records a pending input fee for a unit, optionally rotating to it
+    async fn set_unit_fee(
+        &self,
+        request: Request<SetUnitFeeRequest>,
+    ) -> Result<Response<SetUnitFeeResponse>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        // Rotating to the fee creates a keyset, which needs the key admin role as well
+        if request.get_ref().rotate_now || request.get_ref().rotate_at.is_some() {
+            authorize(&request, Permission::RotateKeys)?;
+        }
+        let caller = caller_identity(&request);
+        let request = request.into_inner();
+
+        let unit = CurrencyUnit::from_str(&request.unit)
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+
+        if request.rotate_now && request.rotate_at.is_some() {
+            return Err(Status::invalid_argument(
+                "Cannot both rotate now and schedule a rotation".to_string(),
+            ));
+        }
+
+        let pending = PendingFee {
+            unit: unit.clone(),
+            input_fee_ppk: request.input_fee_ppk,
+            rotate_at: request.rotate_at,
+        };
+
+        let old_value = self
+            .mint
+            .pending_fee(&unit)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let audit = audit_entry(
+            caller,
+            "set_unit_fee",
+            json_value(&old_value),
+            json_value(&pending),
+        );
+
+        let keyset_id = if request.rotate_now {
+            let keyset_info = self
+                .mint
+                .rotate_keyset_fee(unit, request.input_fee_ppk, Some(audit))
+                .await
+                .map_err(|err| match err {
+                    cdk::Error::UnsupportedUnit => Status::invalid_argument(err.to_string()),
+                    err => Status::internal(err.to_string()),
+                })?;
+
+            Some(keyset_info.id.to_string())
+        } else {
+            self.mint
+                .set_pending_fee(pending, Some(audit))
+                .await
+                .map_err(|err| match err {
+                    cdk::Error::UnsupportedUnit => Status::invalid_argument(err.to_string()),
+                    err => Status::internal(err.to_string()),
+                })?;
+
+            None
+        };
+
+        Ok(Response::new(SetUnitFeeResponse { keyset_id }))
+    }
+
+    /// Removes the pending input fee of a unit
+    async fn cancel_unit_fee(
+        &self,
+        request: Request<CancelUnitFeeRequest>,
+    ) -> Result<Response<UpdateResponse>, Status> {
+        authorize(&request, Permission::Treasury)?;
+        let caller = caller_identity(&request);
+        let request = request.into_inner();
+
+        let unit = CurrencyUnit::from_str(&request.unit)
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+
+        self.mint
+            .remove_pending_fee(
+                &unit,
+                Some(audit_entry(caller, "cancel_unit_fee", None, None)),
+            )
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or_else(|| Status::not_found("No pending fee for unit".to_string()))?;
+
+        Ok(Response::new(UpdateResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk::mint::{MintBuilder, MintMeltLimits};
+    use cdk::types::FeeReserve;
+    use cdk_fake_wallet::FakeWallet;
+    use tonic::service::Interceptor;
+
+    use super::*;
+    use crate::proto::access::Role;
+
+    async fn test_server() -> MintRPCServer {
+        let localstore = Arc::new(cdk_sqlite::mint::memory::empty().await.unwrap());
+        let mut mint_builder = MintBuilder::new(localstore.clone());
+
+        let fee_reserve = FeeReserve {
+            min_fee_reserve: 1.into(),
+            percent_fee_reserve: 1.0,
+        };
+        let fake_wallet = FakeWallet::new(
+            fee_reserve,
+            HashMap::default(),
+            HashSet::default(),
+            0,
+            CurrencyUnit::Sat,
+        );
+        mint_builder
+            .add_payment_processor(
+                CurrencyUnit::Sat,
+                PaymentMethod::Bolt11,
+                MintMeltLimits::new(1, 10_000),
+                Arc::new(fake_wallet),
+            )
+            .await
+            .unwrap();
+
+        let mint = mint_builder
+            .build_with_seed(localstore, &[1; 64])
+            .await
+            .unwrap();
+
+        MintRPCServer::new("127.0.0.1", 0, Arc::new(mint)).unwrap()
+    }
+
+    /// Request of a client granted only the role
+    fn request_as<T: Default>(role: Role) -> Request<T> {
+        request_with(T::default(), [role])
+    }
+
+    /// Request of a client granted the roles
+    fn request_with<T>(message: T, roles: impl IntoIterator<Item = Role>) -> Request<T> {
+        let mut access_control = AccessControl::new();
+        access_control.add_token("token".to_string(), roles);
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer token".parse().unwrap());
+        let request = AccessInterceptor::new(Some(Arc::new(access_control)))
+            .call(request)
+            .unwrap();
+
+        let (metadata, extensions, ()) = request.into_parts();
+        Request::from_parts(metadata, extensions, message)
+    }
+
+    /// Asserts the RPC is denied to the role exactly when it lacks the permission
+    macro_rules! assert_rpc_permission {
+        ($server:expr, $role:expr, $rpc:ident, $request:ty, $permission:expr) => {
+            let denied = matches!(
+                $server.$rpc(request_as::<$request>($role)).await,
+                Err(status) if status.code() == tonic::Code::PermissionDenied
+            );
+            assert_eq!(
+                denied,
+                !$role.grants($permission),
+                "{} as {}",
+                stringify!($rpc),
+                $role
+            );
+        };
+    }
+
+    #[tokio::test]
+    async fn test_rpc_permissions() {
+        let server = test_server().await;
+
+        for role in Role::ALL {
+            assert_rpc_permission!(server, role, get_info, GetInfoRequest, Permission::Read);
+            assert_rpc_permission!(
+                server,
+                role,
+                update_motd,
+                UpdateMotdRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_short_description,
+                UpdateDescriptionRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_long_description,
+                UpdateDescriptionRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_name,
+                UpdateNameRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_icon_url,
+                UpdateIconUrlRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                add_url,
+                UpdateUrlRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                remove_url,
+                UpdateUrlRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                add_contact,
+                UpdateContactRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                remove_contact,
+                UpdateContactRequest,
+                Permission::EditInfo
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_nut04,
+                UpdateNut04Request,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_nut05,
+                UpdateNut05Request,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_quote_ttl,
+                UpdateQuoteTtlRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                get_quote_ttl,
+                GetQuoteTtlRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                update_nut04_quote,
+                UpdateNut04QuoteRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                rotate_next_keyset,
+                RotateNextKeysetRequest,
+                Permission::RotateKeys
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                list_mint_quotes,
+                ListMintQuotesRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                get_melt_quote,
+                GetMeltQuoteRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                list_pending_melts,
+                ListPendingMeltsRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(server, role, list_sagas, ListSagasRequest, Permission::Read);
+            assert_rpc_permission!(
+                server,
+                role,
+                get_proof_states,
+                GetProofStatesRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(server, role, get_totals, GetTotalsRequest, Permission::Read);
+            assert_rpc_permission!(
+                server,
+                role,
+                force_check_melt,
+                ForceCheckMeltRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                mark_melt_paid,
+                MarkMeltPaidRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                mark_melt_failed,
+                MarkMeltFailedRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                list_audit_log,
+                ListAuditLogRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                get_fee_schedule,
+                GetFeeScheduleRequest,
+                Permission::Read
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                set_unit_fee,
+                SetUnitFeeRequest,
+                Permission::Treasury
+            );
+            assert_rpc_permission!(
+                server,
+                role,
+                cancel_unit_fee,
+                CancelUnitFeeRequest,
+                Permission::Treasury
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_unit_fee_rotation_needs_rotate_keys() {
+        let server = test_server().await;
+
+        let rotations = [
+            SetUnitFeeRequest {
+                unit: "sat".to_string(),
+                input_fee_ppk: 100,
+                rotate_now: true,
+                ..Default::default()
+            },
+            SetUnitFeeRequest {
+                unit: "sat".to_string(),
+                input_fee_ppk: 100,
+                rotate_at: Some(u64::MAX),
+                ..Default::default()
+            },
+        ];
+
+        for rotation in rotations {
+            let status = server
+                .set_unit_fee(request_with(rotation.clone(), [Role::Treasury]))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+            let status = server
+                .set_unit_fee(request_with(rotation.clone(), [Role::KeyAdmin]))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+            server
+                .set_unit_fee(request_with(rotation, [Role::Treasury, Role::KeyAdmin]))
+                .await
+                .unwrap();
+        }
+
+        // Recording a pending fee without rotating stays a treasury operation
+        server
+            .set_unit_fee(request_with(
+                SetUnitFeeRequest {
+                    unit: "sat".to_string(),
+                    input_fee_ppk: 200,
+                    ..Default::default()
+                },
+                [Role::Treasury],
+            ))
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_page_limit() {
+        assert_eq!(page_limit(None), DEFAULT_PAGE_LIMIT);
+        assert_eq!(page_limit(Some(5)), 5);
+        assert_eq!(page_limit(Some(u64::MAX)), MAX_PAGE_LIMIT);
+    }
 }
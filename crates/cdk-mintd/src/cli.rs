@@ -32,4 +32,11 @@ pub struct CLIArgs {
         default_value = "true"
     )]
     pub enable_logging: bool,
+    #[cfg(feature = "swagger")]
+    #[arg(
+        long,
+        help = "Write the OpenAPI document of the mint API to <file> and exit",
+        required = false
+    )]
+    pub openapi: Option<PathBuf>,
 }
@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use bitcoin::hashes::{sha256, Hash};
 use cdk::nuts::{CurrencyUnit, PublicKey};
 use cdk::Amount;
 use cdk_axum::cache;
+use cdk_axum::limits::{ClientKey, RouteBudget, WsLimits};
 use cdk_common::common::QuoteTTL;
 use config::{Config, ConfigError, File};
 use serde::{Deserialize, Serialize};
@@ -56,6 +58,11 @@ pub struct Info {
     pub mnemonic: Option<String>,
     pub signatory_url: Option<String>,
     pub signatory_certs: Option<String>,
+    /// Number of `signatory_nodes` needed to sign with a threshold signatory
+    pub signatory_threshold: Option<u8>,
+    /// Urls of the threshold signatory nodes, which tell the index of their shares
+    #[serde(default)]
+    pub signatory_nodes: Vec<String>,
     pub input_fee_ppk: Option<u64>,
 
     pub http_cache: cache::Config,
@@ -87,6 +94,8 @@ impl Default for Info {
             mnemonic: None,
             signatory_url: None,
             signatory_certs: None,
+            signatory_threshold: None,
+            signatory_nodes: Vec::new(),
             input_fee_ppk: None,
             http_cache: cache::Config::default(),
             enable_swagger_ui: None,
@@ -165,11 +174,28 @@ impl std::str::FromStr for LnBackend {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ln {
     pub ln_backend: LnBackend,
+    /// Invoice description used when the wallet does not set one
+    ///
+    /// `{amount}` and `{unit}` are replaced with the quote amount and unit
     pub invoice_description: Option<String>,
     pub min_mint: Amount,
     pub max_mint: Amount,
     pub min_melt: Amount,
     pub max_melt: Amount,
+    /// Allow wallets to set the invoice description
+    #[serde(default = "default_allow_invoice_description")]
+    pub allow_wallet_description: bool,
+    /// Allow wallets to set the invoice description hash
+    #[serde(default = "default_allow_invoice_description")]
+    pub allow_description_hash: bool,
+    /// Min mint quote expiry in seconds a wallet can request
+    pub min_quote_expiry: Option<u64>,
+    /// Max mint quote expiry in seconds a wallet can request, defaults to the mint quote ttl
+    pub max_quote_expiry: Option<u64>,
+}
+
+fn default_allow_invoice_description() -> bool {
+    true
 }
 
 impl Default for Ln {
@@ -181,6 +207,10 @@ impl Default for Ln {
             max_mint: 500_000.into(),
             min_melt: 1.into(),
             max_melt: 500_000.into(),
+            allow_wallet_description: true,
+            allow_description_hash: true,
+            min_quote_expiry: None,
+            max_quote_expiry: None,
         }
     }
 }
@@ -572,6 +602,9 @@ pub struct Settings {
     pub auth: Option<Auth>,
     #[cfg(feature = "prometheus")]
     pub prometheus: Option<Prometheus>,
+    pub lnurl_pay: Option<LnurlPay>,
+    pub price_oracle: Option<PriceOracle>,
+    pub limits: Option<Limits>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -604,6 +637,149 @@ pub struct MintInfo {
     pub tos_url: Option<String>,
 }
 
+/// Lightning address (LNURL-pay) server
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LnurlPay {
+    pub enabled: bool,
+    /// Domain of the lightning addresses, defaults to the host of the mint url
+    pub domain: Option<String>,
+    /// Unit of the mint quotes created for payments
+    #[serde(default)]
+    pub unit: CurrencyUnit,
+    /// Min amount in msat that can be sent, defaults to the min mint amount
+    pub min_sendable: Option<Amount>,
+    /// Max amount in msat that can be sent, defaults to the max mint amount
+    pub max_sendable: Option<Amount>,
+    /// Usernames and the NUT-20 pubkey their mint quotes are locked to
+    #[serde(default)]
+    pub users: HashMap<String, PublicKey>,
+}
+
+/// Per-client rate limits and max batch sizes of the HTTP API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Limits {
+    /// Identity the budgets are keyed by, `ip` or `auth`
+    #[serde(default)]
+    pub key: ClientKey,
+    /// Use the last `X-Forwarded-For` address as the IP of the client, only behind a single
+    /// reverse proxy appending it
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+    /// Budget of the routes without their own budget
+    pub default: Option<RouteBudget>,
+    /// Budgets by route path
+    #[serde(default)]
+    pub routes: HashMap<String, RouteBudget>,
+    /// Max number of inputs of a swap or melt
+    pub max_inputs: Option<usize>,
+    /// Max number of outputs of a swap, mint, melt or restore
+    pub max_outputs: Option<usize>,
+    /// Max number of Ys of a state check
+    pub max_ys: Option<usize>,
+    /// Max number of quotes of a batch quote check or batch mint
+    #[serde(default = "default_max_quotes")]
+    pub max_quotes: usize,
+    /// Limits of the websocket connections
+    #[serde(default)]
+    pub websocket: WsLimits,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            key: ClientKey::default(),
+            trust_forwarded_for: false,
+            default: None,
+            routes: HashMap::new(),
+            max_inputs: None,
+            max_outputs: None,
+            max_ys: None,
+            max_quotes: default_max_quotes(),
+            websocket: WsLimits::default(),
+        }
+    }
+}
+
+pub(crate) fn default_max_quotes() -> usize {
+    100
+}
+
+impl From<Limits> for cdk_axum::limits::Limits {
+    fn from(limits: Limits) -> Self {
+        Self {
+            key: limits.key,
+            trust_forwarded_for: limits.trust_forwarded_for,
+            default_budget: limits.default,
+            routes: limits.routes,
+            max_inputs: limits.max_inputs,
+            max_outputs: limits.max_outputs,
+            max_ys: limits.max_ys,
+            websocket: limits.websocket,
+        }
+    }
+}
+
+/// Exchange rate oracle used to quote fiat units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceOracle {
+    /// Fiat units offered alongside sat with the lightning backend
+    #[serde(default)]
+    pub units: Vec<CurrencyUnit>,
+    /// Price sources, the median of the sources that answer is used
+    #[serde(default)]
+    pub sources: Vec<PriceSource>,
+    /// Min number of sources that must answer for a price to be used
+    #[serde(default = "default_price_min_sources")]
+    pub min_sources: usize,
+    /// Seconds a price is cached before it is refreshed
+    #[serde(default = "default_price_refresh_interval")]
+    pub refresh_interval: u64,
+    /// Max age in seconds of a price before quotes are refused
+    #[serde(default = "default_price_max_staleness")]
+    pub max_staleness: u64,
+    /// Spread applied to the price in favour of the mint (0.01 = 1%)
+    #[serde(default)]
+    pub spread: f64,
+}
+
+impl Default for PriceOracle {
+    fn default() -> Self {
+        Self {
+            units: Vec::new(),
+            sources: Vec::new(),
+            min_sources: default_price_min_sources(),
+            refresh_interval: default_price_refresh_interval(),
+            max_staleness: default_price_max_staleness(),
+            spread: 0.0,
+        }
+    }
+}
+
+fn default_price_min_sources() -> usize {
+    1
+}
+
+fn default_price_refresh_interval() -> u64 {
+    60
+}
+
+fn default_price_max_staleness() -> u64 {
+    600
+}
+
+/// Source of BTC prices
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PriceSource {
+    /// JSON file mapping units to the price of one BTC
+    File { path: PathBuf },
+    /// HTTP endpoint returning JSON, with the dot separated path to the price of each unit
+    Http {
+        url: String,
+        paths: HashMap<CurrencyUnit, String>,
+    },
+}
+
 #[cfg(feature = "management-rpc")]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MintManagementRpc {
@@ -613,6 +789,34 @@ pub struct MintManagementRpc {
     pub address: Option<String>,
     pub port: Option<u16>,
     pub tls_dir_path: Option<PathBuf>,
+    /// Clients allowed to use the RPC and their roles.
+    /// When empty every client with a certificate signed by the CA has all roles.
+    #[serde(default)]
+    pub clients: Vec<ManagementRpcClient>,
+}
+
+/// Client of the management RPC
+#[cfg(feature = "management-rpc")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManagementRpcClient {
+    /// Common name of the subject of the client's TLS certificate
+    pub subject: Option<String>,
+    /// API token the client sends as `authorization: Bearer <token>`
+    pub token: Option<String>,
+    pub roles: Vec<cdk_mint_rpc::Role>,
+}
+
+#[cfg(feature = "management-rpc")]
+impl MintManagementRpc {
+    /// Whether a client may rotate keysets, directly or by scheduling a fee
+    pub fn rotates_keysets(&self) -> bool {
+        self.enabled
+            && (self.clients.is_empty()
+                || self
+                    .clients
+                    .iter()
+                    .any(|client| client.roles.contains(&cdk_mint_rpc::Role::KeyAdmin)))
+    }
 }
 
 impl Settings {
@@ -1103,4 +1307,229 @@ max_melt = 500000
         // Cleanup test file
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_lnurl_pay_config() {
+        use std::{env, fs};
+
+        let temp_dir = env::temp_dir().join("cdk_test_lnurl_pay_config");
+        fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+        let config_path = temp_dir.join("config.toml");
+
+        let config_content = r#"
+[lnurl_pay]
+enabled = true
+domain = "mint.example.com"
+max_sendable = 100000000
+
+[lnurl_pay.users]
+alice = "02a9acc1e48c25eeeb9289b5031cc57da9fe72f3fe2861d264bdc074209b107ba2"
+"#;
+        fs::write(&config_path, config_content).expect("Failed to write config file");
+
+        let settings = Settings::new(Some(&config_path));
+
+        let lnurl_pay = settings.lnurl_pay.expect("lnurl pay config");
+        assert!(lnurl_pay.enabled);
+        assert_eq!(lnurl_pay.domain, Some("mint.example.com".to_string()));
+        assert_eq!(lnurl_pay.min_sendable, None);
+        assert_eq!(lnurl_pay.max_sendable, Some(100_000_000.into()));
+        assert!(lnurl_pay.users.contains_key("alice"));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_price_oracle_config() {
+        use std::{env, fs};
+
+        let temp_dir = env::temp_dir().join("cdk_test_price_oracle_config");
+        fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+        let config_path = temp_dir.join("config.toml");
+
+        let config_content = r#"
+[price_oracle]
+units = ["usd", "eur"]
+spread = 0.01
+max_staleness = 300
+
+[[price_oracle.sources]]
+type = "file"
+path = "/tmp/prices.json"
+
+[[price_oracle.sources]]
+type = "http"
+url = "https://mempool.space/api/v1/prices"
+paths = { usd = "USD", eur = "EUR" }
+"#;
+        fs::write(&config_path, config_content).expect("Failed to write config file");
+
+        let settings = Settings::new(Some(&config_path));
+
+        let price_oracle = settings.price_oracle.expect("price oracle config");
+        assert_eq!(
+            price_oracle.units,
+            vec![CurrencyUnit::Usd, CurrencyUnit::Eur]
+        );
+        assert_eq!(price_oracle.spread, 0.01);
+        assert_eq!(price_oracle.max_staleness, 300);
+        assert_eq!(price_oracle.refresh_interval, 60);
+        assert_eq!(price_oracle.min_sources, 1);
+        assert_eq!(price_oracle.sources.len(), 2);
+        assert_eq!(
+            price_oracle.sources[0],
+            PriceSource::File {
+                path: PathBuf::from("/tmp/prices.json")
+            }
+        );
+        match &price_oracle.sources[1] {
+            PriceSource::Http { url, paths } => {
+                assert_eq!(url, "https://mempool.space/api/v1/prices");
+                assert_eq!(paths.get(&CurrencyUnit::Usd), Some(&"USD".to_string()));
+                assert_eq!(paths.get(&CurrencyUnit::Eur), Some(&"EUR".to_string()));
+            }
+            source => panic!("Unexpected source {source:?}"),
+        }
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_limits_config() {
+        use std::{env, fs};
+
+        let temp_dir = env::temp_dir().join("cdk_test_limits_config");
+        fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+        let config_path = temp_dir.join("config.toml");
+
+        let config_content = r#"
+[limits]
+key = "auth"
+max_inputs = 100
+max_ys = 500
+max_quotes = 50
+
+[limits.default]
+requests = 120
+per_secs = 60
+
+[limits.routes."/v1/mint/quote/bolt11"]
+requests = 10
+per_secs = 60
+
+[limits.websocket]
+max_subscriptions = 20
+overflow = "disconnect"
+ping_interval = 0
+"#;
+        fs::write(&config_path, config_content).expect("Failed to write config file");
+
+        let settings = Settings::new(Some(&config_path));
+
+        let limits = settings.limits.expect("limits config");
+        assert_eq!(
+            limits.websocket,
+            WsLimits {
+                max_subscriptions: Some(20),
+                overflow: cdk_axum::limits::OverflowPolicy::Disconnect,
+                ping_interval: 0,
+                ..Default::default()
+            }
+        );
+        assert_eq!(limits.key, ClientKey::Auth);
+        assert!(!limits.trust_forwarded_for);
+        assert_eq!(limits.max_inputs, Some(100));
+        assert_eq!(limits.max_outputs, None);
+        assert_eq!(limits.max_ys, Some(500));
+        assert_eq!(limits.max_quotes, 50);
+        assert_eq!(
+            limits.default,
+            Some(RouteBudget {
+                requests: 120,
+                per_secs: 60
+            })
+        );
+        assert_eq!(
+            limits.routes.get("/v1/mint/quote/bolt11"),
+            Some(&RouteBudget {
+                requests: 10,
+                per_secs: 60
+            })
+        );
+
+        // Batches of quotes are bounded unless configured
+        fs::write(&config_path, "[limits]\nmax_ys = 500\n").expect("Failed to write config file");
+        let limits = Settings::new(Some(&config_path))
+            .limits
+            .expect("limits config");
+        assert_eq!(limits.max_quotes, 100);
+        assert_eq!(Limits::default().max_quotes, 100);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[cfg(feature = "management-rpc")]
+    #[test]
+    fn test_management_rpc_clients_config() {
+        use std::{env, fs};
+
+        use cdk_mint_rpc::Role;
+
+        let temp_dir = env::temp_dir().join("cdk_test_management_rpc_clients_config");
+        fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+        let config_path = temp_dir.join("config.toml");
+
+        let config_content = r#"
+[mint_management_rpc]
+enabled = true
+
+[[mint_management_rpc.clients]]
+subject = "client"
+roles = ["info-editor", "key-admin"]
+
+[[mint_management_rpc.clients]]
+token = "monitoring-token"
+roles = ["read-only"]
+"#;
+        fs::write(&config_path, config_content).expect("Failed to write config file");
+
+        let settings = Settings::new(Some(&config_path));
+
+        let rpc = settings.mint_management_rpc.expect("management rpc config");
+        assert_eq!(
+            rpc.clients,
+            vec![
+                ManagementRpcClient {
+                    subject: Some("client".to_string()),
+                    token: None,
+                    roles: vec![Role::InfoEditor, Role::KeyAdmin],
+                },
+                ManagementRpcClient {
+                    subject: None,
+                    token: Some("monitoring-token".to_string()),
+                    roles: vec![Role::ReadOnly],
+                },
+            ]
+        );
+        assert!(rpc.rotates_keysets());
+
+        let read_only = MintManagementRpc {
+            enabled: true,
+            clients: vec![ManagementRpcClient {
+                subject: Some("client".to_string()),
+                token: None,
+                roles: vec![Role::ReadOnly, Role::Treasury],
+            }],
+            ..Default::default()
+        };
+        assert!(!read_only.rotates_keysets());
+        // Without clients every client has all roles
+        assert!(MintManagementRpc {
+            enabled: true,
+            ..Default::default()
+        }
+        .rotates_keysets());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
 }
@@ -33,6 +33,20 @@ impl Info {
             self.signatory_certs = Some(signatory_certs);
         }
 
+        if let Ok(threshold_str) = env::var(ENV_SIGNATORY_THRESHOLD) {
+            if let Ok(threshold) = threshold_str.parse() {
+                self.signatory_threshold = Some(threshold);
+            }
+        }
+
+        if let Ok(nodes_str) = env::var(ENV_SIGNATORY_NODES) {
+            self.signatory_nodes = nodes_str
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect();
+        }
+
         if let Ok(seed) = env::var(ENV_SEED) {
             self.seed = Some(seed);
         }
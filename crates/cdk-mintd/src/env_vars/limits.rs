@@ -0,0 +1,140 @@
+//! Rate limit environment variables
+
+use std::env;
+
+use cdk_axum::limits::{ClientKey, OverflowPolicy, RouteBudget};
+
+use crate::config::Limits;
+
+/// `ip` or `auth`
+pub const ENV_LIMITS_KEY: &str = "CDK_MINTD_LIMITS_KEY";
+pub const ENV_LIMITS_TRUST_FORWARDED_FOR: &str = "CDK_MINTD_LIMITS_TRUST_FORWARDED_FOR";
+/// Budget of the routes without their own budget as `requests/per_secs`
+pub const ENV_LIMITS_DEFAULT: &str = "CDK_MINTD_LIMITS_DEFAULT";
+/// Comma separated list of `path=requests/per_secs` budgets
+pub const ENV_LIMITS_ROUTES: &str = "CDK_MINTD_LIMITS_ROUTES";
+pub const ENV_LIMITS_MAX_INPUTS: &str = "CDK_MINTD_LIMITS_MAX_INPUTS";
+pub const ENV_LIMITS_MAX_OUTPUTS: &str = "CDK_MINTD_LIMITS_MAX_OUTPUTS";
+pub const ENV_LIMITS_MAX_YS: &str = "CDK_MINTD_LIMITS_MAX_YS";
+pub const ENV_LIMITS_MAX_QUOTES: &str = "CDK_MINTD_LIMITS_MAX_QUOTES";
+pub const ENV_LIMITS_WS_MAX_SUBSCRIPTIONS: &str = "CDK_MINTD_LIMITS_WS_MAX_SUBSCRIPTIONS";
+pub const ENV_LIMITS_WS_MAX_FILTERS: &str = "CDK_MINTD_LIMITS_WS_MAX_FILTERS";
+pub const ENV_LIMITS_WS_SEND_QUEUE: &str = "CDK_MINTD_LIMITS_WS_SEND_QUEUE";
+/// `drop` or `disconnect`
+pub const ENV_LIMITS_WS_OVERFLOW: &str = "CDK_MINTD_LIMITS_WS_OVERFLOW";
+pub const ENV_LIMITS_WS_PING_INTERVAL: &str = "CDK_MINTD_LIMITS_WS_PING_INTERVAL";
+pub const ENV_LIMITS_WS_IDLE_TIMEOUT: &str = "CDK_MINTD_LIMITS_WS_IDLE_TIMEOUT";
+
+impl Limits {
+    pub fn from_env(mut self) -> Self {
+        if let Ok(key_str) = env::var(ENV_LIMITS_KEY) {
+            match key_str.to_lowercase().as_str() {
+                "ip" => self.key = ClientKey::Ip,
+                "auth" => self.key = ClientKey::Auth,
+                _ => tracing::warn!("Invalid rate limit key in env var: {key_str}"),
+            }
+        }
+
+        if let Ok(trust_str) = env::var(ENV_LIMITS_TRUST_FORWARDED_FOR) {
+            if let Ok(trust) = trust_str.parse() {
+                self.trust_forwarded_for = trust;
+            }
+        }
+
+        if let Ok(budget_str) = env::var(ENV_LIMITS_DEFAULT) {
+            match parse_budget(&budget_str) {
+                Some(budget) => self.default = Some(budget),
+                None => tracing::warn!("Invalid default rate limit in env var: {budget_str}"),
+            }
+        }
+
+        if let Ok(routes_str) = env::var(ENV_LIMITS_ROUTES) {
+            for entry in routes_str.split(',').filter(|s| !s.is_empty()) {
+                match entry
+                    .trim()
+                    .split_once('=')
+                    .and_then(|(path, budget)| Some((path, parse_budget(budget)?)))
+                {
+                    Some((path, budget)) => {
+                        self.routes.insert(path.trim().to_string(), budget);
+                    }
+                    None => tracing::warn!("Invalid route rate limit in env var: {entry}"),
+                }
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_MAX_INPUTS) {
+            if let Ok(max) = max_str.parse() {
+                self.max_inputs = Some(max);
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_MAX_OUTPUTS) {
+            if let Ok(max) = max_str.parse() {
+                self.max_outputs = Some(max);
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_MAX_YS) {
+            if let Ok(max) = max_str.parse() {
+                self.max_ys = Some(max);
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_MAX_QUOTES) {
+            if let Ok(max) = max_str.parse() {
+                self.max_quotes = max;
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_WS_MAX_SUBSCRIPTIONS) {
+            if let Ok(max) = max_str.parse() {
+                self.websocket.max_subscriptions = Some(max);
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIMITS_WS_MAX_FILTERS) {
+            if let Ok(max) = max_str.parse() {
+                self.websocket.max_filters = Some(max);
+            }
+        }
+
+        if let Ok(size_str) = env::var(ENV_LIMITS_WS_SEND_QUEUE) {
+            if let Ok(size) = size_str.parse() {
+                self.websocket.send_queue = size;
+            }
+        }
+
+        if let Ok(overflow_str) = env::var(ENV_LIMITS_WS_OVERFLOW) {
+            match overflow_str.to_lowercase().as_str() {
+                "drop" => self.websocket.overflow = OverflowPolicy::Drop,
+                "disconnect" => self.websocket.overflow = OverflowPolicy::Disconnect,
+                _ => tracing::warn!("Invalid websocket overflow policy in env var: {overflow_str}"),
+            }
+        }
+
+        if let Ok(secs_str) = env::var(ENV_LIMITS_WS_PING_INTERVAL) {
+            if let Ok(secs) = secs_str.parse() {
+                self.websocket.ping_interval = secs;
+            }
+        }
+
+        if let Ok(secs_str) = env::var(ENV_LIMITS_WS_IDLE_TIMEOUT) {
+            if let Ok(secs) = secs_str.parse() {
+                self.websocket.idle_timeout = secs;
+            }
+        }
+
+        self
+    }
+}
+
+/// Parses a `requests/per_secs` budget
+fn parse_budget(budget: &str) -> Option<RouteBudget> {
+    let (requests, per_secs) = budget.trim().split_once('/')?;
+
+    Some(RouteBudget {
+        requests: requests.trim().parse().ok()?,
+        per_secs: per_secs.trim().parse().ok()?,
+    })
+}
@@ -11,6 +11,10 @@ pub const ENV_LN_MIN_MINT: &str = "CDK_MINTD_LN_MIN_MINT";
 pub const ENV_LN_MAX_MINT: &str = "CDK_MINTD_LN_MAX_MINT";
 pub const ENV_LN_MIN_MELT: &str = "CDK_MINTD_LN_MIN_MELT";
 pub const ENV_LN_MAX_MELT: &str = "CDK_MINTD_LN_MAX_MELT";
+pub const ENV_LN_ALLOW_WALLET_DESCRIPTION: &str = "CDK_MINTD_LN_ALLOW_WALLET_DESCRIPTION";
+pub const ENV_LN_ALLOW_DESCRIPTION_HASH: &str = "CDK_MINTD_LN_ALLOW_DESCRIPTION_HASH";
+pub const ENV_LN_MIN_QUOTE_EXPIRY: &str = "CDK_MINTD_LN_MIN_QUOTE_EXPIRY";
+pub const ENV_LN_MAX_QUOTE_EXPIRY: &str = "CDK_MINTD_LN_MAX_QUOTE_EXPIRY";
 
 impl Ln {
     pub fn from_env(mut self) -> Self {
@@ -53,6 +57,31 @@ impl Ln {
             }
         }
 
+        // Mint quote controls
+        if let Ok(allow_str) = env::var(ENV_LN_ALLOW_WALLET_DESCRIPTION) {
+            if let Ok(allow) = allow_str.parse::<bool>() {
+                self.allow_wallet_description = allow;
+            }
+        }
+
+        if let Ok(allow_str) = env::var(ENV_LN_ALLOW_DESCRIPTION_HASH) {
+            if let Ok(allow) = allow_str.parse::<bool>() {
+                self.allow_description_hash = allow;
+            }
+        }
+
+        if let Ok(expiry_str) = env::var(ENV_LN_MIN_QUOTE_EXPIRY) {
+            if let Ok(expiry) = expiry_str.parse::<u64>() {
+                self.min_quote_expiry = Some(expiry);
+            }
+        }
+
+        if let Ok(expiry_str) = env::var(ENV_LN_MAX_QUOTE_EXPIRY) {
+            if let Ok(expiry) = expiry_str.parse::<u64>() {
+                self.max_quote_expiry = Some(expiry);
+            }
+        }
+
         self
     }
 }
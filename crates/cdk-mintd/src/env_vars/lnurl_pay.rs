@@ -0,0 +1,66 @@
+//! LNURL-pay environment variables
+
+use std::env;
+use std::str::FromStr;
+
+use cdk::nuts::{CurrencyUnit, PublicKey};
+
+use crate::config::LnurlPay;
+
+pub const ENV_LNURL_PAY_ENABLED: &str = "CDK_MINTD_LNURL_PAY_ENABLED";
+pub const ENV_LNURL_PAY_DOMAIN: &str = "CDK_MINTD_LNURL_PAY_DOMAIN";
+pub const ENV_LNURL_PAY_UNIT: &str = "CDK_MINTD_LNURL_PAY_UNIT";
+pub const ENV_LNURL_PAY_MIN_SENDABLE: &str = "CDK_MINTD_LNURL_PAY_MIN_SENDABLE";
+pub const ENV_LNURL_PAY_MAX_SENDABLE: &str = "CDK_MINTD_LNURL_PAY_MAX_SENDABLE";
+/// Comma separated list of `username:pubkey` pairs
+pub const ENV_LNURL_PAY_USERS: &str = "CDK_MINTD_LNURL_PAY_USERS";
+
+impl LnurlPay {
+    pub fn from_env(mut self) -> Self {
+        if let Ok(enabled_str) = env::var(ENV_LNURL_PAY_ENABLED) {
+            if let Ok(enabled) = enabled_str.parse() {
+                self.enabled = enabled;
+            }
+        }
+
+        if let Ok(domain) = env::var(ENV_LNURL_PAY_DOMAIN) {
+            self.domain = Some(domain);
+        }
+
+        if let Ok(unit_str) = env::var(ENV_LNURL_PAY_UNIT) {
+            if let Ok(unit) = CurrencyUnit::from_str(&unit_str) {
+                self.unit = unit;
+            }
+        }
+
+        if let Ok(min_str) = env::var(ENV_LNURL_PAY_MIN_SENDABLE) {
+            if let Ok(amount) = min_str.parse::<u64>() {
+                self.min_sendable = Some(amount.into());
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LNURL_PAY_MAX_SENDABLE) {
+            if let Ok(amount) = max_str.parse::<u64>() {
+                self.max_sendable = Some(amount.into());
+            }
+        }
+
+        if let Ok(users_str) = env::var(ENV_LNURL_PAY_USERS) {
+            for entry in users_str.split(',').filter(|s| !s.is_empty()) {
+                match entry.trim().split_once(':') {
+                    Some((user, pubkey)) => match PublicKey::from_str(pubkey.trim()) {
+                        Ok(pubkey) => {
+                            self.users.insert(user.trim().to_string(), pubkey);
+                        }
+                        Err(_) => {
+                            tracing::warn!("Invalid pubkey for lnurl pay user {user} in env var")
+                        }
+                    },
+                    None => tracing::warn!("Invalid lnurl pay user entry in env var: {entry}"),
+                }
+            }
+        }
+
+        self
+    }
+}
@@ -2,13 +2,15 @@
 
 use std::env;
 
-use crate::config::MintManagementRpc;
+use crate::config::{ManagementRpcClient, MintManagementRpc};
 
 // Mint RPC Server environment variables
 pub const ENV_MINT_MANAGEMENT_ENABLED: &str = "CDK_MINTD_MINT_MANAGEMENT_ENABLED";
 pub const ENV_MINT_MANAGEMENT_ADDRESS: &str = "CDK_MINTD_MANAGEMENT_ADDRESS";
 pub const ENV_MINT_MANAGEMENT_PORT: &str = "CDK_MINTD_MANAGEMENT_PORT";
 pub const ENV_MINT_MANAGEMENT_TLS_DIR_PATH: &str = "CDK_MINTD_MANAGEMENT_TLS_DIR_PATH";
+/// Clients and their roles as `subject:<common name>=<role>,<role>;token:<token>=<role>`
+pub const ENV_MINT_MANAGEMENT_CLIENTS: &str = "CDK_MINTD_MANAGEMENT_CLIENTS";
 
 impl MintManagementRpc {
     pub fn from_env(mut self) -> Self {
@@ -32,6 +34,39 @@ impl MintManagementRpc {
             self.tls_dir_path = Some(tls_path.into());
         }
 
+        if let Ok(clients) = env::var(ENV_MINT_MANAGEMENT_CLIENTS) {
+            self.clients = clients
+                .split(';')
+                .filter_map(|client| {
+                    let (identity, roles) = client.trim().split_once('=')?;
+                    let roles = roles
+                        .split(',')
+                        .filter_map(|role| match role.trim().parse() {
+                            Ok(role) => Some(role),
+                            Err(err) => {
+                                tracing::warn!("Ignoring management rpc role: {}", err);
+                                None
+                            }
+                        })
+                        .collect();
+
+                    match identity.split_once(':')? {
+                        ("subject", subject) => Some(ManagementRpcClient {
+                            subject: Some(subject.to_string()),
+                            token: None,
+                            roles,
+                        }),
+                        ("token", token) => Some(ManagementRpcClient {
+                            subject: None,
+                            token: Some(token.to_string()),
+                            roles,
+                        }),
+                        _ => None,
+                    }
+                })
+                .collect();
+        }
+
         self
     }
 }
@@ -6,8 +6,11 @@
 mod common;
 mod database;
 mod info;
+mod limits;
 mod ln;
+mod lnurl_pay;
 mod mint_info;
+mod price_oracle;
 
 #[cfg(feature = "auth")]
 mod auth;
@@ -44,14 +47,17 @@ pub use fake_wallet::*;
 pub use grpc_processor::*;
 #[cfg(feature = "ldk-node")]
 pub use ldk_node::*;
+pub use limits::*;
 pub use ln::*;
 #[cfg(feature = "lnbits")]
 pub use lnbits::*;
 #[cfg(feature = "lnd")]
 pub use lnd::*;
+pub use lnurl_pay::*;
 #[cfg(feature = "management-rpc")]
 pub use management_rpc::*;
 pub use mint_info::*;
+pub use price_oracle::*;
 #[cfg(feature = "prometheus")]
 pub use prometheus::*;
 
@@ -122,6 +128,18 @@ impl Settings {
             self.prometheus = Some(self.prometheus.clone().unwrap_or_default().from_env());
         }
 
+        let lnurl_pay = self.lnurl_pay.clone().unwrap_or_default().from_env();
+        if lnurl_pay.enabled {
+            self.lnurl_pay = Some(lnurl_pay);
+        }
+
+        self.limits = Some(self.limits.clone().unwrap_or_default().from_env());
+
+        let price_oracle = self.price_oracle.clone().unwrap_or_default().from_env();
+        if !price_oracle.sources.is_empty() {
+            self.price_oracle = Some(price_oracle);
+        }
+
         match self.ln.ln_backend {
             #[cfg(feature = "cln")]
             LnBackend::Cln => {
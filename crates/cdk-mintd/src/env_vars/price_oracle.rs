@@ -0,0 +1,81 @@
+//! Price oracle environment variables
+
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use cdk::nuts::CurrencyUnit;
+
+use crate::config::{PriceOracle, PriceSource};
+
+pub const ENV_PRICE_ORACLE_SPREAD: &str = "CDK_MINTD_PRICE_ORACLE_SPREAD";
+pub const ENV_PRICE_ORACLE_MIN_SOURCES: &str = "CDK_MINTD_PRICE_ORACLE_MIN_SOURCES";
+pub const ENV_PRICE_ORACLE_REFRESH_INTERVAL: &str = "CDK_MINTD_PRICE_ORACLE_REFRESH_INTERVAL";
+pub const ENV_PRICE_ORACLE_MAX_STALENESS: &str = "CDK_MINTD_PRICE_ORACLE_MAX_STALENESS";
+/// Path to a JSON file of unit to price, added as a price source
+pub const ENV_PRICE_ORACLE_FILE: &str = "CDK_MINTD_PRICE_ORACLE_FILE";
+/// URL of an HTTP JSON price source
+pub const ENV_PRICE_ORACLE_HTTP_URL: &str = "CDK_MINTD_PRICE_ORACLE_HTTP_URL";
+/// Comma separated list of `unit:path` pairs for the HTTP price source
+pub const ENV_PRICE_ORACLE_HTTP_PATHS: &str = "CDK_MINTD_PRICE_ORACLE_HTTP_PATHS";
+
+impl PriceOracle {
+    pub fn from_env(mut self) -> Self {
+        if let Ok(spread_str) = env::var(ENV_PRICE_ORACLE_SPREAD) {
+            if let Ok(spread) = spread_str.parse() {
+                self.spread = spread;
+            }
+        }
+
+        if let Ok(min_str) = env::var(ENV_PRICE_ORACLE_MIN_SOURCES) {
+            if let Ok(min_sources) = min_str.parse() {
+                self.min_sources = min_sources;
+            }
+        }
+
+        if let Ok(interval_str) = env::var(ENV_PRICE_ORACLE_REFRESH_INTERVAL) {
+            if let Ok(interval) = interval_str.parse() {
+                self.refresh_interval = interval;
+            }
+        }
+
+        if let Ok(staleness_str) = env::var(ENV_PRICE_ORACLE_MAX_STALENESS) {
+            if let Ok(staleness) = staleness_str.parse() {
+                self.max_staleness = staleness;
+            }
+        }
+
+        if let Ok(path) = env::var(ENV_PRICE_ORACLE_FILE) {
+            self.sources.push(PriceSource::File {
+                path: PathBuf::from(path),
+            });
+        }
+
+        if let Ok(url) = env::var(ENV_PRICE_ORACLE_HTTP_URL) {
+            let mut paths = HashMap::new();
+
+            if let Ok(paths_str) = env::var(ENV_PRICE_ORACLE_HTTP_PATHS) {
+                for entry in paths_str.split(',').filter(|s| !s.is_empty()) {
+                    match entry.trim().split_once(':') {
+                        Some((unit, path)) => match CurrencyUnit::from_str(unit.trim()) {
+                            Ok(unit) => {
+                                paths.insert(unit, path.trim().to_string());
+                            }
+                            Err(_) => {
+                                tracing::warn!("Invalid unit {unit} for price oracle in env var")
+                            }
+                        },
+                        None => {
+                            tracing::warn!("Invalid price oracle path entry in env var: {entry}")
+                        }
+                    }
+                }
+            }
+
+            self.sources.push(PriceSource::Http { url, paths });
+        }
+
+        self
+    }
+}
@@ -14,7 +14,7 @@ use anyhow::{anyhow, bail, Result};
 use axum::Router;
 use bip39::Mnemonic;
 use cdk::cdk_database::{self, MintDatabase, MintKVStore, MintKeysDatabase};
-use cdk::mint::{Mint, MintBuilder, MintMeltLimits};
+use cdk::mint::{Bolt11MintQuoteSettings, Mint, MintBuilder, MintMeltLimits};
 #[cfg(any(
     feature = "cln",
     feature = "lnbits",
@@ -42,7 +42,8 @@ use cdk_common::database::DynMintDatabase;
 // internal crate modules
 #[cfg(feature = "prometheus")]
 use cdk_common::payment::MetricsMintPayment;
-use cdk_common::payment::MintPayment;
+use cdk_common::payment::{FiatMintPayment, MintPayment};
+use cdk_common::price_oracle::is_fiat_unit;
 #[cfg(all(feature = "auth", feature = "postgres"))]
 use cdk_postgres::MintPgAuthDatabase;
 #[cfg(feature = "postgres")]
@@ -253,6 +254,14 @@ pub fn load_settings(work_dir: &Path, config_path: Option<PathBuf>) -> Result<co
     settings.from_env()
 }
 
+/// Writes the OpenAPI document of the mint API, as served on `/api-docs/openapi.json`, to a file.
+#[cfg(feature = "swagger")]
+pub fn write_openapi(path: &Path) -> Result<()> {
+    let doc = cdk_axum::ApiDoc::openapi().to_pretty_json()?;
+    std::fs::write(path, doc)?;
+    Ok(())
+}
+
 async fn setup_database(
     settings: &config::Settings,
     _work_dir: &Path,
@@ -408,7 +417,7 @@ async fn configure_lightning_backend(
     mut mint_builder: MintBuilder,
     _runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
     work_dir: &Path,
-    _kv_store: Option<Arc<dyn MintKVStore<Err = cdk::cdk_database::Error> + Send + Sync>>,
+    kv_store: Option<Arc<dyn MintKVStore<Err = cdk::cdk_database::Error> + Send + Sync>>,
 ) -> Result<MintBuilder> {
     let mint_melt_limits = MintMeltLimits {
         mint_min: settings.ln.min_mint,
@@ -417,8 +426,22 @@ async fn configure_lightning_backend(
         melt_max: settings.ln.max_melt,
     };
 
+    mint_builder = mint_builder.with_bolt11_mint_quote_settings(Bolt11MintQuoteSettings {
+        default_description: settings.ln.invoice_description.clone(),
+        allow_description: settings.ln.allow_wallet_description,
+        allow_description_hash: settings.ln.allow_description_hash,
+        min_expiry: settings.ln.min_quote_expiry,
+        max_expiry: settings.ln.max_quote_expiry,
+    });
+
     tracing::debug!("Ln backend: {:?}", settings.ln.ln_backend);
 
+    if let Some(price_oracle) = &settings.price_oracle {
+        if let Some(unit) = price_oracle.units.iter().find(|unit| !is_fiat_unit(unit)) {
+            bail!("price_oracle unit {unit} is not a fiat unit");
+        }
+    }
+
     match settings.ln.ln_backend {
         #[cfg(feature = "cln")]
         LnBackend::Cln => {
@@ -427,17 +450,24 @@ async fn configure_lightning_backend(
                 .clone()
                 .expect("Config checked at load that cln is some");
             let cln = cln_settings
-                .setup(settings, CurrencyUnit::Msat, None, work_dir, _kv_store)
+                .setup(
+                    settings,
+                    CurrencyUnit::Msat,
+                    None,
+                    work_dir,
+                    kv_store.clone(),
+                )
                 .await?;
             #[cfg(feature = "prometheus")]
             let cln = MetricsMintPayment::new(cln);
 
-            mint_builder = configure_backend_for_unit(
+            mint_builder = configure_backend(
                 settings,
                 mint_builder,
                 CurrencyUnit::Sat,
                 mint_melt_limits,
-                Arc::new(cln),
+                cln,
+                kv_store,
             )
             .await?;
         }
@@ -450,12 +480,13 @@ async fn configure_lightning_backend(
             #[cfg(feature = "prometheus")]
             let lnbits = MetricsMintPayment::new(lnbits);
 
-            mint_builder = configure_backend_for_unit(
+            mint_builder = configure_backend(
                 settings,
                 mint_builder,
                 CurrencyUnit::Sat,
                 mint_melt_limits,
-                Arc::new(lnbits),
+                lnbits,
+                kv_store,
             )
             .await?;
         }
@@ -463,17 +494,24 @@ async fn configure_lightning_backend(
         LnBackend::Lnd => {
             let lnd_settings = settings.clone().lnd.expect("Checked at config load");
             let lnd = lnd_settings
-                .setup(settings, CurrencyUnit::Msat, None, work_dir, _kv_store)
+                .setup(
+                    settings,
+                    CurrencyUnit::Msat,
+                    None,
+                    work_dir,
+                    kv_store.clone(),
+                )
                 .await?;
             #[cfg(feature = "prometheus")]
             let lnd = MetricsMintPayment::new(lnd);
 
-            mint_builder = configure_backend_for_unit(
+            mint_builder = configure_backend(
                 settings,
                 mint_builder,
                 CurrencyUnit::Sat,
                 mint_melt_limits,
-                Arc::new(lnd),
+                lnd,
+                kv_store,
             )
             .await?;
         }
@@ -484,17 +522,18 @@ async fn configure_lightning_backend(
 
             for unit in fake_wallet.clone().supported_units {
                 let fake = fake_wallet
-                    .setup(settings, unit.clone(), None, work_dir, _kv_store.clone())
+                    .setup(settings, unit.clone(), None, work_dir, kv_store.clone())
                     .await?;
                 #[cfg(feature = "prometheus")]
                 let fake = MetricsMintPayment::new(fake);
 
-                mint_builder = configure_backend_for_unit(
+                mint_builder = configure_backend(
                     settings,
                     mint_builder,
                     unit.clone(),
                     mint_melt_limits,
-                    Arc::new(fake),
+                    fake,
+                    kv_store.clone(),
                 )
                 .await?;
             }
@@ -520,12 +559,13 @@ async fn configure_lightning_backend(
                 #[cfg(feature = "prometheus")]
                 let processor = MetricsMintPayment::new(processor);
 
-                mint_builder = configure_backend_for_unit(
+                mint_builder = configure_backend(
                     settings,
                     mint_builder,
                     unit.clone(),
                     mint_melt_limits,
-                    Arc::new(processor),
+                    processor,
+                    kv_store.clone(),
                 )
                 .await?;
             }
@@ -539,12 +579,13 @@ async fn configure_lightning_backend(
                 .setup(settings, CurrencyUnit::Sat, _runtime, work_dir, None)
                 .await?;
 
-            mint_builder = configure_backend_for_unit(
+            mint_builder = configure_backend(
                 settings,
                 mint_builder,
                 CurrencyUnit::Sat,
                 mint_melt_limits,
-                Arc::new(ldk_node),
+                ldk_node,
+                kv_store,
             )
             .await?;
         }
@@ -560,6 +601,67 @@ async fn configure_lightning_backend(
     Ok(mint_builder)
 }
 
+/// Configures a lightning backend for `unit`
+///
+/// When a price oracle is configured the backend registered for sat also serves
+/// the fiat units of the `[price_oracle]`, with amounts converted by a [`FiatMintPayment`].
+async fn configure_backend<T>(
+    settings: &config::Settings,
+    mut mint_builder: MintBuilder,
+    unit: CurrencyUnit,
+    mint_melt_limits: MintMeltLimits,
+    backend: T,
+    kv_store: Option<Arc<dyn MintKVStore<Err = cdk::cdk_database::Error> + Send + Sync>>,
+) -> Result<MintBuilder>
+where
+    T: MintPayment<Err = cdk_common::payment::Error> + Send + Sync + 'static,
+{
+    let price_oracle = match &settings.price_oracle {
+        Some(price_oracle) if unit == CurrencyUnit::Sat && !price_oracle.units.is_empty() => {
+            price_oracle
+        }
+        _ => {
+            return configure_backend_for_unit(
+                settings,
+                mint_builder,
+                unit,
+                mint_melt_limits,
+                Arc::new(backend),
+            )
+            .await;
+        }
+    };
+
+    let mut backend = FiatMintPayment::new(backend, price_oracle.fiat_converter()?);
+    if let Some(kv_store) = kv_store {
+        backend = backend.with_kv_store(kv_store);
+    }
+    let backend: Arc<dyn MintPayment<Err = cdk_common::payment::Error> + Send + Sync> =
+        Arc::new(backend);
+
+    mint_builder = configure_backend_for_unit(
+        settings,
+        mint_builder,
+        unit,
+        mint_melt_limits,
+        Arc::clone(&backend),
+    )
+    .await?;
+
+    for fiat_unit in &price_oracle.units {
+        mint_builder = configure_backend_for_unit(
+            settings,
+            mint_builder,
+            fiat_unit.clone(),
+            mint_melt_limits,
+            Arc::clone(&backend),
+        )
+        .await?;
+    }
+
+    Ok(mint_builder)
+}
+
 /// Helper function to configure a mint builder with a lightning backend for a specific currency unit
 async fn configure_backend_for_unit(
     settings: &config::Settings,
@@ -609,7 +711,14 @@ async fn configure_backend_for_unit(
     ))]
     {
         let nut17_supported = SupportedMethods::default_bolt11(unit);
-        mint_builder = mint_builder.with_supported_websockets(nut17_supported);
+        let max_quotes = settings
+            .limits
+            .as_ref()
+            .map_or_else(config::default_max_quotes, |limits| limits.max_quotes);
+        mint_builder = mint_builder
+            .with_supported_websockets(nut17_supported)
+            .with_sse_subscriptions()
+            .with_batch_minting(PaymentMethod::Bolt11, Some(max_quotes as u64));
     }
 
     Ok(mint_builder)
@@ -619,6 +728,7 @@ async fn configure_backend_for_unit(
 fn configure_cache(settings: &config::Settings, mint_builder: MintBuilder) -> MintBuilder {
     let cached_endpoints = vec![
         CachedEndpoint::new(NUT19Method::Post, NUT19Path::MintBolt11),
+        CachedEndpoint::new(NUT19Method::Post, NUT19Path::MintBolt11Batch),
         CachedEndpoint::new(NUT19Method::Post, NUT19Path::MeltBolt11),
         CachedEndpoint::new(NUT19Method::Post, NUT19Path::Swap),
     ];
@@ -627,6 +737,56 @@ fn configure_cache(settings: &config::Settings, mint_builder: MintBuilder) -> Mi
     mint_builder.with_cache(Some(cache.ttl.as_secs()), cached_endpoints)
 }
 
+/// Builds the lightning address server settings from the config
+fn configure_lnurl_pay(
+    settings: &config::Settings,
+    lnurl_pay: &config::LnurlPay,
+) -> Result<cdk_axum::LnurlPaySettings> {
+    let domain = match &lnurl_pay.domain {
+        Some(domain) => domain.clone(),
+        None => settings
+            .info
+            .url
+            .parse::<axum::http::Uri>()?
+            .host()
+            .ok_or_else(|| anyhow!("Mint url has no host to use as lightning address domain"))?
+            .to_string(),
+    };
+
+    if !settings.ln.allow_description_hash {
+        bail!("Lnurl pay requires `ln.allow_description_hash` to be enabled");
+    }
+
+    let to_msat = |amount: cdk::Amount| {
+        cdk::amount::to_unit(amount, &lnurl_pay.unit, &cdk::nuts::CurrencyUnit::Msat).map_err(
+            |err| {
+                anyhow!(
+                    "Set lnurl pay min and max sendable for {}: {err}",
+                    lnurl_pay.unit
+                )
+            },
+        )
+    };
+
+    let min_sendable = match lnurl_pay.min_sendable {
+        Some(min_sendable) => min_sendable,
+        None => to_msat(settings.ln.min_mint)?,
+    };
+    let max_sendable = match lnurl_pay.max_sendable {
+        Some(max_sendable) => max_sendable,
+        None => to_msat(settings.ln.max_mint)?,
+    };
+
+    Ok(cdk_axum::LnurlPaySettings {
+        mint_url: settings.info.url.clone(),
+        domain,
+        unit: lnurl_pay.unit.clone(),
+        min_sendable,
+        max_sendable,
+        users: lnurl_pay.users.clone(),
+    })
+}
+
 #[cfg(feature = "auth")]
 async fn setup_authentication(
     settings: &config::Settings,
@@ -822,7 +982,68 @@ async fn build_mint(
     keystore: Arc<dyn MintKeysDatabase<Err = cdk_database::Error> + Send + Sync>,
     mint_builder: MintBuilder,
 ) -> Result<Mint> {
-    if let Some(signatory_url) = settings.info.signatory_url.clone() {
+    if let Some(threshold) = settings.info.signatory_threshold {
+        // The mint cannot deal new shares to the nodes, so it cannot rotate a keyset
+        #[cfg(feature = "management-rpc")]
+        if settings
+            .mint_management_rpc
+            .as_ref()
+            .is_some_and(|rpc| rpc.rotates_keysets())
+        {
+            bail!(
+                "A threshold signatory cannot rotate keysets: give no mint_management_rpc client \
+                 the key-admin role, and rotate by dealing new shares to the signatory nodes"
+            );
+        }
+
+        tracing::info!(
+            "Connecting to {} threshold signatory nodes, {} of which are needed to sign",
+            settings.info.signatory_nodes.len(),
+            threshold
+        );
+
+        let mut nodes = Vec::with_capacity(settings.info.signatory_nodes.len());
+        for url in &settings.info.signatory_nodes {
+            let signatory = cdk_signatory::SignatoryRpcClient::new(
+                url.clone(),
+                settings.info.signatory_certs.clone(),
+            )
+            .await?;
+
+            // Each node tells the index of the share it serves
+            let keysets = tokio::time::timeout(
+                cdk_signatory::threshold::DEFAULT_NODE_TIMEOUT,
+                cdk_signatory::signatory::Signatory::keysets(&signatory),
+            )
+            .await;
+            let index = match keysets {
+                Ok(Ok(keysets)) => match keysets.share_index {
+                    Some(index) => index,
+                    None => bail!("Signatory {url} does not serve a threshold share"),
+                },
+                Ok(Err(err)) => {
+                    tracing::warn!("Threshold signatory node {} is unavailable: {}", url, err);
+                    continue;
+                }
+                Err(_) => {
+                    tracing::warn!("Threshold signatory node {} timed out", url);
+                    continue;
+                }
+            };
+
+            tracing::debug!("Threshold signatory node {} serves share {}", url, index);
+            nodes.push(cdk_signatory::threshold::ThresholdNode {
+                index,
+                signatory: Arc::new(signatory),
+            });
+        }
+
+        Ok(mint_builder
+            .build_with_signatory(Arc::new(
+                cdk_signatory::threshold::ThresholdSignatory::new(threshold, nodes).await?,
+            ))
+            .await?)
+    } else if let Some(signatory_url) = settings.info.signatory_url.clone() {
         tracing::info!(
             "Connecting to remote signatory to {} with certs {:?}",
             signatory_url,
@@ -884,6 +1105,19 @@ async fn start_services_with_shutdown(
                 let port = rpc_settings.port.unwrap_or(8086);
                 let mut mint_rpc = cdk_mint_rpc::MintRPCServer::new(&addr, port, mint.clone())?;
 
+                if !rpc_settings.clients.is_empty() {
+                    let mut access_control = cdk_mint_rpc::AccessControl::new();
+                    for client in rpc_settings.clients {
+                        if let Some(subject) = client.subject {
+                            access_control.add_subject(subject, client.roles.clone());
+                        }
+                        if let Some(token) = client.token {
+                            access_control.add_token(token, client.roles);
+                        }
+                    }
+                    mint_rpc = mint_rpc.with_access_control(access_control);
+                }
+
                 let tls_dir = rpc_settings.tls_dir_path.unwrap_or(work_dir.join("tls"));
 
                 let tls_dir = if tls_dir.exists() {
@@ -952,9 +1186,22 @@ async fn start_services_with_shutdown(
     let bolt12_supported = nut04_methods.contains(&&PaymentMethod::Bolt12)
         || nut05_methods.contains(&&PaymentMethod::Bolt12);
 
-    let v1_service =
-        cdk_axum::create_mint_router_with_custom_cache(Arc::clone(&mint), cache, bolt12_supported)
-            .await?;
+    let limits: cdk_axum::limits::Limits = settings.limits.clone().unwrap_or_default().into();
+    if limits.is_rate_limited() {
+        tracing::info!(
+            "Rate limiting clients by {:?} on {} routes",
+            limits.key,
+            limits.routes.len()
+        );
+    }
+
+    let v1_service = cdk_axum::create_mint_router_with_limits(
+        Arc::clone(&mint),
+        cache,
+        bolt12_supported,
+        limits,
+    )
+    .await?;
 
     let mut mint_service = Router::new()
         .merge(v1_service)
@@ -969,6 +1216,17 @@ async fn start_services_with_shutdown(
         mint_service = mint_service.merge(router);
     }
 
+    if let Some(lnurl_pay) = settings.lnurl_pay.as_ref().filter(|l| l.enabled) {
+        let lnurl_pay_settings = configure_lnurl_pay(settings, lnurl_pay)?;
+        tracing::info!(
+            "Serving lightning addresses for {} users at {}",
+            lnurl_pay_settings.users.len(),
+            lnurl_pay_settings.domain
+        );
+        mint_service = mint_service
+            .merge(cdk_axum::create_lnurl_pay_router(Arc::clone(&mint), lnurl_pay_settings).await?);
+    }
+
     #[cfg(feature = "swagger")]
     {
         if settings.info.enable_swagger_ui.unwrap_or(false) {
@@ -1046,7 +1304,11 @@ async fn start_services_with_shutdown(
     };
 
     // Wait for axum server to complete with custom shutdown signal
-    let axum_result = axum::serve(listener, mint_service).with_graceful_shutdown(axum_shutdown);
+    let axum_result = axum::serve(
+        listener,
+        mint_service.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(axum_shutdown);
 
     match axum_result.await {
         Ok(_) => {
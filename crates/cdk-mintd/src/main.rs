@@ -17,6 +17,12 @@ fn main() -> Result<()> {
 
     rt.block_on(async {
         let args = CLIArgs::parse();
+
+        #[cfg(feature = "swagger")]
+        if let Some(path) = &args.openapi {
+            return cdk_mintd::write_openapi(path);
+        }
+
         let work_dir = get_work_directory(&args).await?;
         let settings = load_settings(&work_dir, args.config)?;
 
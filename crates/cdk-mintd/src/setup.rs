@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[cfg(feature = "cln")]
 use anyhow::anyhow;
@@ -23,6 +24,10 @@ use cdk::nuts::CurrencyUnit;
     feature = "fakewallet"
 ))]
 use cdk::types::FeeReserve;
+use cdk_common::price_oracle::{
+    CachedPriceOracle, DynPriceOracle, FiatConverter, HttpJsonPriceOracle, MedianPriceOracle,
+    StaticPriceOracle,
+};
 
 use crate::config::{self, Settings};
 #[cfg(feature = "cln")]
@@ -179,7 +184,7 @@ impl LnBackendSetup for config::Lnd {
 impl LnBackendSetup for config::FakeWallet {
     async fn setup(
         &self,
-        _settings: &Settings,
+        settings: &Settings,
         unit: CurrencyUnit,
         _runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
         _work_dir: &Path,
@@ -194,7 +199,7 @@ impl LnBackendSetup for config::FakeWallet {
         let mut rng = thread_rng();
         let delay_time = rng.gen_range(self.min_delay_time..=self.max_delay_time);
 
-        let fake_wallet = cdk_fake_wallet::FakeWallet::new(
+        let mut fake_wallet = cdk_fake_wallet::FakeWallet::new(
             fee_reserve,
             HashMap::default(),
             HashSet::default(),
@@ -202,10 +207,42 @@ impl LnBackendSetup for config::FakeWallet {
             unit,
         );
 
+        if let Some(price_oracle) = &settings.price_oracle {
+            fake_wallet = fake_wallet.with_fiat_converter(price_oracle.fiat_converter()?);
+        }
+
         Ok(fake_wallet)
     }
 }
 
+impl config::PriceOracle {
+    /// Median of the configured sources, cached and with the spread applied
+    pub fn fiat_converter(&self) -> anyhow::Result<FiatConverter> {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| -> anyhow::Result<DynPriceOracle> {
+                Ok(match source {
+                    config::PriceSource::File { path } => {
+                        Arc::new(StaticPriceOracle::from_file(path)?)
+                    }
+                    config::PriceSource::Http { url, paths } => {
+                        Arc::new(HttpJsonPriceOracle::new(url.clone(), paths.clone()))
+                    }
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let oracle = CachedPriceOracle::new(
+            Arc::new(MedianPriceOracle::new(sources, self.min_sources)),
+            Duration::from_secs(self.refresh_interval),
+            Duration::from_secs(self.max_staleness),
+        );
+
+        Ok(FiatConverter::new(Arc::new(oracle), self.spread)?)
+    }
+}
+
 #[cfg(feature = "grpc-processor")]
 #[async_trait]
 impl LnBackendSetup for config::GrpcProcessor {
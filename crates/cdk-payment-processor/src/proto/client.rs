@@ -1,33 +1,49 @@
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::anyhow;
 use cdk_common::payment::{
-    CreateIncomingPaymentResponse, IncomingPaymentOptions as CdkIncomingPaymentOptions,
+    Bolt11Settings, CreateIncomingPaymentResponse, Event,
+    IncomingPaymentOptions as CdkIncomingPaymentOptions, LiquidityResponse as CdkLiquidityResponse,
     MakePaymentResponse as CdkMakePaymentResponse, MintPayment,
     PaymentQuoteResponse as CdkPaymentQuoteResponse, WaitPaymentResponse,
 };
 use futures::{Stream, StreamExt};
 use serde_json::Value;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::sleep;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
-use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
-use tonic::{async_trait, Request};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::{async_trait, Code, Request};
 use tracing::instrument;
 
 use crate::proto::cdk_payment_processor_client::CdkPaymentProcessorClient;
 use crate::proto::{
     CheckIncomingPaymentRequest, CheckOutgoingPaymentRequest, CreatePaymentRequest, EmptyRequest,
-    IncomingPaymentOptions, MakePaymentRequest, OutgoingPaymentRequestType, PaymentQuoteRequest,
+    HealthRequest, IncomingPaymentOptions, MakePaymentRequest, OutgoingPaymentRequestType,
+    PaymentQuoteRequest, KEEP_ALIVE_INTERVAL,
 };
 
+/// Time to wait for a keepalive ping to be acknowledged
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Initial delay before resubscribing to a dropped stream
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Max delay between resubscription attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
 /// Payment Processor
 #[derive(Clone)]
 pub struct PaymentProcessorClient {
     inner: CdkPaymentProcessorClient<Channel>,
     wait_incoming_payment_stream_is_active: Arc<AtomicBool>,
     cancel_incoming_payment_listener: CancellationToken,
+    healthy: Arc<AtomicBool>,
+    cancel_health_check: Arc<Mutex<Option<CancellationToken>>>,
+    settings_changed: broadcast::Sender<Bolt11Settings>,
 }
 
 impl PaymentProcessorClient {
@@ -64,13 +80,10 @@ impl PaymentProcessorClient {
                 }
                 false => ClientTlsConfig::new().ca_certificate(server_root_ca_cert),
             };
-            Channel::from_shared(addr)?
-                .tls_config(tls)?
-                .connect()
-                .await?
+            Self::endpoint(addr)?.tls_config(tls)?.connect().await?
         } else {
             // No TLS directory, skip TLS configuration
-            Channel::from_shared(addr)?.connect().await?
+            Self::endpoint(addr)?.connect().await?
         };
 
         let client = CdkPaymentProcessorClient::new(channel);
@@ -79,8 +92,192 @@ impl PaymentProcessorClient {
             inner: client,
             wait_incoming_payment_stream_is_active: Arc::new(AtomicBool::new(false)),
             cancel_incoming_payment_listener: CancellationToken::new(),
+            healthy: Arc::new(AtomicBool::new(true)),
+            cancel_health_check: Arc::new(Mutex::new(None)),
+            settings_changed: broadcast::channel(8).0,
         })
     }
+
+    /// Endpoint with keepalive, the channel reconnects on its own once the connection drops
+    fn endpoint(addr: String) -> anyhow::Result<Endpoint> {
+        Ok(Channel::from_shared(addr)?
+            .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+            .keep_alive_timeout(KEEP_ALIVE_TIMEOUT)
+            .keep_alive_while_idle(true))
+    }
+
+    /// Whether the payment processor answered the last health check
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::SeqCst)
+    }
+
+    /// Keep a health stream open with the payment processor
+    ///
+    /// Pings the processor every [`KEEP_ALIVE_INTERVAL`] and resubscribes with backoff
+    /// when the stream drops or a ping is not answered in time. Settings changes reported
+    /// by the processor are sent to `settings_changed`.
+    async fn health_check(
+        mut inner: CdkPaymentProcessorClient<Channel>,
+        healthy: Arc<AtomicBool>,
+        settings_changed: broadcast::Sender<Bolt11Settings>,
+        cancel: CancellationToken,
+    ) {
+        let mut delay = RECONNECT_DELAY;
+        let mut last_settings: Option<String> = None;
+
+        loop {
+            let (ping_tx, ping_rx) = mpsc::channel(1);
+
+            let response = tokio::select! {
+                _ = cancel.cancelled() => return,
+                response = inner.health(ReceiverStream::new(ping_rx)) => response,
+            };
+
+            match response {
+                Ok(response) => {
+                    let mut pongs = response.into_inner();
+                    let mut interval = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+                    let mut sequence = 0;
+                    let mut last_pong = 0;
+
+                    loop {
+                        tokio::select! {
+                            _ = cancel.cancelled() => return,
+                            _ = interval.tick() => {
+                                if sequence > last_pong {
+                                    tracing::warn!("Payment processor did not answer health check {}", sequence);
+                                    break;
+                                }
+
+                                sequence += 1;
+                                if ping_tx.send(HealthRequest { sequence }).await.is_err() {
+                                    break;
+                                }
+                            }
+                            pong = pongs.next() => match pong {
+                                Some(Ok(pong)) => {
+                                    last_pong = pong.sequence;
+                                    delay = RECONNECT_DELAY;
+
+                                    if !healthy.swap(true, Ordering::SeqCst) {
+                                        tracing::info!("Payment processor is healthy");
+                                    }
+
+                                    if let Some(settings) = pong.settings {
+                                        if last_settings.as_ref().is_some_and(|last| last != &settings) {
+                                            tracing::info!("Payment processor settings changed: {}", settings);
+
+                                            match serde_json::from_str::<Bolt11Settings>(&settings) {
+                                                Ok(settings) => {
+                                                    // No receiver only means the mint is not waiting for payments yet
+                                                    let _ = settings_changed.send(settings);
+                                                }
+                                                Err(err) => {
+                                                    tracing::warn!("Could not parse payment processor settings: {}", err);
+                                                }
+                                            }
+                                        }
+                                        last_settings = Some(settings);
+                                    }
+                                }
+                                Some(Err(err)) => {
+                                    tracing::warn!("Payment processor health stream error: {}", err);
+                                    break;
+                                }
+                                None => break,
+                            }
+                        }
+                    }
+                }
+                Err(err) if err.code() == Code::Unimplemented => {
+                    tracing::debug!("Payment processor does not support health checks");
+                    return;
+                }
+                Err(err) => {
+                    tracing::warn!("Could not open payment processor health stream: {}", err);
+                }
+            }
+
+            if healthy.swap(false, Ordering::SeqCst) {
+                tracing::warn!("Payment processor is unhealthy");
+            }
+
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = sleep(delay) => {}
+            }
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    /// Forward incoming payments, outgoing payment updates and settings changes to `tx`
+    ///
+    /// Returns `true` if the streams dropped and should be resubscribed.
+    async fn forward_payment_events(
+        mut inner: CdkPaymentProcessorClient<Channel>,
+        tx: &mpsc::Sender<Event>,
+        settings_changed: &mut broadcast::Receiver<Bolt11Settings>,
+        cancel: &CancellationToken,
+    ) -> Result<bool, tonic::Status> {
+        let mut incoming = inner
+            .wait_incoming_payment(EmptyRequest {})
+            .await?
+            .into_inner();
+
+        // Processors without outgoing updates are still usable, the mint checks the
+        // payment state instead
+        let mut outgoing = match inner.outgoing_payment_updates(EmptyRequest {}).await {
+            Ok(response) => response.into_inner().boxed(),
+            Err(err) if err.code() == Code::Unimplemented => {
+                tracing::debug!("Payment processor does not support outgoing payment updates");
+                futures::stream::pending().boxed()
+            }
+            Err(err) => return Err(err),
+        };
+
+        loop {
+            let event = tokio::select! {
+                _ = cancel.cancelled() => return Ok(false),
+                payment = incoming.next() => match payment {
+                    Some(Ok(payment)) => match payment.try_into() {
+                        Ok(payment) => Event::PaymentReceived(payment),
+                        Err(err) => {
+                            tracing::error!("Error converting payment response: {}", err);
+                            continue;
+                        }
+                    },
+                    Some(Err(err)) => {
+                        tracing::error!("Error in payment stream: {}", err);
+                        return Ok(true);
+                    }
+                    None => return Ok(true),
+                },
+                update = outgoing.next() => match update {
+                    Some(Ok(update)) => match update.try_into() {
+                        Ok(update) => Event::OutgoingPaymentUpdate(update),
+                        Err(err) => {
+                            tracing::error!("Error converting outgoing payment update: {}", err);
+                            continue;
+                        }
+                    },
+                    Some(Err(err)) => {
+                        tracing::error!("Error in outgoing payment stream: {}", err);
+                        return Ok(true);
+                    }
+                    None => return Ok(true),
+                },
+                settings = settings_changed.recv() => match settings {
+                    Ok(settings) => Event::SettingsChanged(settings),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return Ok(false),
+                },
+            };
+
+            if tx.send(event).await.is_err() {
+                return Ok(false);
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -117,6 +314,7 @@ impl MintPayment for PaymentProcessorClient {
                         description: opts.description,
                         amount: opts.amount.into(),
                         unix_expiry: opts.unix_expiry,
+                        description_hash: opts.description_hash.map(|hash| hash.to_string()),
                     },
                 )),
             },
@@ -253,52 +451,89 @@ impl MintPayment for PaymentProcessorClient {
         })?)
     }
 
+    async fn start(&self) -> Result<(), Self::Err> {
+        let cancel = CancellationToken::new();
+
+        if let Some(previous) = self
+            .cancel_health_check
+            .lock()
+            .map_err(|_| cdk_common::payment::Error::Custom("Poisoned lock".to_string()))?
+            .replace(cancel.clone())
+        {
+            previous.cancel();
+        }
+
+        tokio::spawn(Self::health_check(
+            self.inner.clone(),
+            self.healthy.clone(),
+            self.settings_changed.clone(),
+            cancel,
+        ));
+
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<(), Self::Err> {
+        if let Some(cancel) = self
+            .cancel_health_check
+            .lock()
+            .map_err(|_| cdk_common::payment::Error::Custom("Poisoned lock".to_string()))?
+            .take()
+        {
+            cancel.cancel();
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn wait_payment_event(
         &self,
-    ) -> Result<Pin<Box<dyn Stream<Item = cdk_common::payment::Event> + Send>>, Self::Err> {
+    ) -> Result<Pin<Box<dyn Stream<Item = Event> + Send>>, Self::Err> {
         self.wait_incoming_payment_stream_is_active
             .store(true, Ordering::SeqCst);
         tracing::debug!("Client waiting for payment");
-        let mut inner = self.inner.clone();
-        let stream = inner
-            .wait_incoming_payment(EmptyRequest {})
-            .await
-            .map_err(|err| {
-                tracing::error!("Could not check incoming payment stream: {}", err);
-                cdk_common::payment::Error::Custom(err.to_string())
-            })?
-            .into_inner();
 
+        let (tx, rx) = mpsc::channel(128);
+        let inner = self.inner.clone();
         let cancel_token = self.cancel_incoming_payment_listener.clone();
-        let cancel_fut = cancel_token.cancelled_owned();
         let active_flag = self.wait_incoming_payment_stream_is_active.clone();
-
-        let transformed_stream = stream
-            .take_until(cancel_fut)
-            .filter_map(|item| async {
-                match item {
-                    Ok(value) => match value.try_into() {
-                        Ok(payment_response) => Some(cdk_common::payment::Event::PaymentReceived(
-                            payment_response,
-                        )),
-                        Err(e) => {
-                            tracing::error!("Error converting payment response: {}", e);
-                            None
-                        }
-                    },
-                    Err(e) => {
-                        tracing::error!("Error in payment stream: {}", e);
-                        None
+        let mut settings_changed = self.settings_changed.subscribe();
+
+        tokio::spawn(async move {
+            let mut delay = RECONNECT_DELAY;
+
+            loop {
+                match Self::forward_payment_events(
+                    inner.clone(),
+                    &tx,
+                    &mut settings_changed,
+                    &cancel_token,
+                )
+                .await
+                {
+                    Ok(false) => break,
+                    Ok(true) => {
+                        tracing::warn!("Payment stream closed, resubscribing");
+                        delay = RECONNECT_DELAY;
+                    }
+                    Err(err) => {
+                        tracing::error!("Could not subscribe to payment streams: {}", err);
                     }
                 }
-            })
-            .inspect(move |_| {
-                active_flag.store(false, Ordering::SeqCst);
-                tracing::info!("Payment stream inactive");
-            });
 
-        Ok(Box::pin(transformed_stream))
+                tokio::select! {
+                    _ = cancel_token.cancelled() => break,
+                    _ = sleep(delay) => {}
+                }
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+
+            active_flag.store(false, Ordering::SeqCst);
+            tracing::info!("Payment stream inactive");
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
     }
 
     /// Is wait invoice active
@@ -356,4 +591,23 @@ impl MintPayment for PaymentProcessorClient {
             .try_into()
             .map_err(|_| cdk_common::payment::Error::UnknownPaymentState)?)
     }
+
+    async fn get_liquidity(&self) -> Result<CdkLiquidityResponse, Self::Err> {
+        let mut inner = self.inner.clone();
+        let response = inner
+            .get_liquidity(Request::new(EmptyRequest {}))
+            .await
+            .map_err(|err| {
+                if err.code() == Code::Unimplemented {
+                    return cdk_common::payment::Error::UnsupportedPaymentOption;
+                }
+
+                tracing::error!("Could not get liquidity: {}", err);
+                cdk_common::payment::Error::Custom(err.to_string())
+            })?;
+
+        Ok(response.into_inner().try_into().map_err(|_| {
+            cdk_common::payment::Error::Anyhow(anyhow!("Could not convert liquidity response"))
+        })?)
+    }
 }
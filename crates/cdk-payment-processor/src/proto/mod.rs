@@ -1,8 +1,10 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use cdk_common::payment::{
-    CreateIncomingPaymentResponse, MakePaymentResponse as CdkMakePaymentResponse,
-    PaymentIdentifier as CdkPaymentIdentifier, WaitPaymentResponse,
+    CreateIncomingPaymentResponse, LiquidityResponse as CdkLiquidityResponse,
+    MakePaymentResponse as CdkMakePaymentResponse, PaymentIdentifier as CdkPaymentIdentifier,
+    WaitPaymentResponse,
 };
 use cdk_common::{CurrencyUnit, MeltOptions as CdkMeltOptions};
 
@@ -14,6 +16,9 @@ pub use server::PaymentProcessorServer;
 
 tonic::include_proto!("cdk_payment_processor");
 
+/// Interval of the HTTP/2 keepalive pings between client and server
+pub(crate) const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
 impl From<CdkPaymentIdentifier> for PaymentIdentifier {
     fn from(value: CdkPaymentIdentifier) -> Self {
         match value {
@@ -113,6 +118,28 @@ impl From<CdkMakePaymentResponse> for MakePaymentResponse {
     }
 }
 
+impl TryFrom<LiquidityResponse> for CdkLiquidityResponse {
+    type Error = crate::error::Error;
+
+    fn try_from(value: LiquidityResponse) -> Result<Self, Self::Error> {
+        Ok(Self {
+            outbound: value.outbound.into(),
+            inbound: value.inbound.into(),
+            unit: CurrencyUnit::from_str(&value.unit)?,
+        })
+    }
+}
+
+impl From<CdkLiquidityResponse> for LiquidityResponse {
+    fn from(value: CdkLiquidityResponse) -> Self {
+        Self {
+            outbound: value.outbound.into(),
+            inbound: value.inbound.into(),
+            unit: value.unit.to_string(),
+        }
+    }
+}
+
 impl From<CreateIncomingPaymentResponse> for CreatePaymentResponse {
     fn from(value: CreateIncomingPaymentResponse) -> Self {
         Self {
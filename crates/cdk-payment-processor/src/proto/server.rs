@@ -5,25 +5,28 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use cdk_common::payment::{IncomingPaymentOptions, MintPayment};
+use cdk_common::bitcoin::hashes::sha256::Hash as Sha256;
+use cdk_common::payment::{Event, IncomingPaymentOptions, MintPayment};
 use cdk_common::CurrencyUnit;
 use futures::{Stream, StreamExt};
 use lightning::offers::offer::Offer;
 use serde_json::Value;
-use tokio::sync::{mpsc, Notify};
+use tokio::sync::{broadcast, mpsc, Notify};
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, Instant};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
-use tonic::{async_trait, Request, Response, Status};
+use tonic::{async_trait, Request, Response, Status, Streaming};
 use tracing::instrument;
 
 use super::cdk_payment_processor_server::{CdkPaymentProcessor, CdkPaymentProcessorServer};
 use crate::error::Error;
 use crate::proto::*;
 
-type ResponseStream =
-    Pin<Box<dyn Stream<Item = Result<WaitIncomingPaymentResponse, Status>> + Send>>;
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Number of payment events buffered for slow clients
+const EVENT_CHANNEL_CAPACITY: usize = 128;
 
 /// Payment Processor
 #[derive(Clone)]
@@ -32,6 +35,10 @@ pub struct PaymentProcessorServer {
     socket_addr: SocketAddr,
     shutdown: Arc<Notify>,
     handle: Option<Arc<JoinHandle<anyhow::Result<()>>>>,
+    /// Payment events of the backend, shared by all client streams
+    events: broadcast::Sender<Event>,
+    /// Notified when a client subscribes to payment events
+    subscribed: Arc<Notify>,
 }
 
 impl PaymentProcessorServer {
@@ -47,6 +54,8 @@ impl PaymentProcessorServer {
             socket_addr,
             shutdown: Arc::new(Notify::new()),
             handle: None,
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            subscribed: Arc::new(Notify::new()),
         })
     }
 
@@ -54,6 +63,8 @@ impl PaymentProcessorServer {
     pub async fn start(&mut self, tls_dir: Option<PathBuf>) -> anyhow::Result<()> {
         tracing::info!("Starting RPC server {}", self.socket_addr);
 
+        let mut builder = Server::builder().http2_keepalive_interval(Some(KEEP_ALIVE_INTERVAL));
+
         let server = match tls_dir {
             Some(tls_dir) => {
                 tracing::info!("TLS configuration found, starting secure server");
@@ -96,16 +107,23 @@ impl PaymentProcessorServer {
                     .identity(server_identity)
                     .client_ca_root(client_ca_cert);
 
-                Server::builder()
+                builder
                     .tls_config(tls_config)?
                     .add_service(CdkPaymentProcessorServer::new(self.clone()))
             }
             None => {
                 tracing::warn!("No valid TLS configuration found, starting insecure server");
-                Server::builder().add_service(CdkPaymentProcessorServer::new(self.clone()))
+                builder.add_service(CdkPaymentProcessorServer::new(self.clone()))
             }
         };
 
+        tokio::spawn(Self::forward_payment_events(
+            self.inner.clone(),
+            self.events.clone(),
+            self.subscribed.clone(),
+            self.shutdown.clone(),
+        ));
+
         let shutdown = self.shutdown.clone();
         let addr = self.socket_addr;
 
@@ -121,6 +139,85 @@ impl PaymentProcessorServer {
         Ok(())
     }
 
+    /// Forward the payment events of the backend to the subscribed clients
+    ///
+    /// The backend stream is only drained while a client is subscribed, so events
+    /// are not lost while the mint is reconnecting.
+    async fn forward_payment_events(
+        ln: Arc<dyn MintPayment<Err = cdk_common::payment::Error> + Send + Sync>,
+        events: broadcast::Sender<Event>,
+        subscribed: Arc<Notify>,
+        shutdown: Arc<Notify>,
+    ) {
+        loop {
+            while events.receiver_count() == 0 {
+                tokio::select! {
+                    _ = shutdown.notified() => return,
+                    _ = subscribed.notified() => {}
+                }
+            }
+
+            tokio::select! {
+                _ = shutdown.notified() => {
+                    tracing::info!("Shutdown signal received, stopping task");
+                    ln.cancel_wait_invoice();
+                    break;
+                }
+                result = ln.wait_payment_event() => {
+                    match result {
+                        Ok(mut stream) => {
+                            while let Some(event) = stream.next().await {
+                                if let Err(err) = events.send(event) {
+                                    tracing::warn!("No client subscribed, dropping payment event: {:?}", err.0);
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            tracing::warn!("Could not get invoice stream: {}", err);
+                            sleep(Duration::from_secs(5)).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Subscribe to the payment events of the backend
+    ///
+    /// Events for which `filter` returns `None` are skipped.
+    fn subscribe<T, F>(&self, filter: F) -> ResponseStream<T>
+    where
+        T: Send + 'static,
+        F: Fn(Event) -> Option<T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let mut events = self.events.subscribe();
+        self.subscribed.notify_one();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        let Some(item) = filter(event) else {
+                            continue;
+                        };
+
+                        if let Err(err) = tx.send(Ok(item)).await {
+                            tracing::debug!("Client stream closed: {}", err);
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Client stream lagged, skipped {} payment events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Box::pin(ReceiverStream::new(rx))
+    }
+
     /// Stop fake wallet grpc server
     pub async fn stop(&self) -> anyhow::Result<()> {
         const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
@@ -194,8 +291,15 @@ impl CdkPaymentProcessor for PaymentProcessorServer {
             .ok_or_else(|| Status::invalid_argument("Missing options"))?
         {
             incoming_payment_options::Options::Bolt11(opts) => {
+                let description_hash = opts
+                    .description_hash
+                    .map(|hash| Sha256::from_str(&hash))
+                    .transpose()
+                    .map_err(|_| Status::invalid_argument("Invalid description hash"))?;
+
                 IncomingPaymentOptions::Bolt11(cdk_common::payment::Bolt11IncomingPaymentOptions {
                     description: opts.description,
+                    description_hash,
                     amount: opts.amount.into(),
                     unix_expiry: opts.unix_expiry,
                 })
@@ -380,61 +484,93 @@ impl CdkPaymentProcessor for PaymentProcessorServer {
         Ok(Response::new(check_response.into()))
     }
 
-    type WaitIncomingPaymentStream = ResponseStream;
+    type WaitIncomingPaymentStream = ResponseStream<WaitIncomingPaymentResponse>;
 
-    #[allow(clippy::incompatible_msrv)]
     #[instrument(skip_all)]
     async fn wait_incoming_payment(
         &self,
         _request: Request<EmptyRequest>,
     ) -> Result<Response<Self::WaitIncomingPaymentStream>, Status> {
         tracing::debug!("Server waiting for payment stream");
-        let (tx, rx) = mpsc::channel(128);
 
-        let shutdown_clone = self.shutdown.clone();
+        Ok(Response::new(self.subscribe(|event| match event {
+            Event::PaymentReceived(payment_response) => Some(payment_response.into()),
+            Event::OutgoingPaymentUpdate(_) | Event::SettingsChanged(_) => None,
+        })))
+    }
+
+    type OutgoingPaymentUpdatesStream = ResponseStream<MakePaymentResponse>;
+
+    #[instrument(skip_all)]
+    async fn outgoing_payment_updates(
+        &self,
+        _request: Request<EmptyRequest>,
+    ) -> Result<Response<Self::OutgoingPaymentUpdatesStream>, Status> {
+        tracing::debug!("Server waiting for outgoing payment updates");
+
+        Ok(Response::new(self.subscribe(|event| match event {
+            Event::OutgoingPaymentUpdate(payment_response) => Some(payment_response.into()),
+            Event::PaymentReceived(_) | Event::SettingsChanged(_) => None,
+        })))
+    }
+
+    async fn get_liquidity(
+        &self,
+        _request: Request<EmptyRequest>,
+    ) -> Result<Response<LiquidityResponse>, Status> {
+        let liquidity = self.inner.get_liquidity().await.map_err(|err| match err {
+            cdk_common::payment::Error::UnsupportedPaymentOption => {
+                Status::unimplemented("Liquidity not supported by backend")
+            }
+            err => {
+                tracing::error!("Could not get liquidity: {}", err);
+                Status::internal("Could not get liquidity")
+            }
+        })?;
+
+        Ok(Response::new(liquidity.into()))
+    }
+
+    type HealthStream = ResponseStream<HealthResponse>;
+
+    #[instrument(skip_all)]
+    async fn health(
+        &self,
+        request: Request<Streaming<HealthRequest>>,
+    ) -> Result<Response<Self::HealthStream>, Status> {
+        let mut pings = request.into_inner();
+        let (tx, rx) = mpsc::channel(1);
         let ln = self.inner.clone();
+
         tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    _ = shutdown_clone.notified() => {
-                        tracing::info!("Shutdown signal received, stopping task");
-                        ln.cancel_wait_invoice();
+            let mut last_settings: Option<String> = None;
+
+            while let Some(Ok(ping)) = pings.next().await {
+                let settings = match ln.get_settings().await {
+                    Ok(settings) => settings.to_string(),
+                    Err(err) => {
+                        tracing::error!("Could not get settings for health check: {}", err);
+                        let _ = tx
+                            .send(Err(Status::unavailable("Backend unavailable")))
+                            .await;
                         break;
                     }
-                    result = ln.wait_payment_event() => {
-                        match result {
-                            Ok(mut stream) => {
-                                while let Some(event) = stream.next().await {
-                                    match event {
-                                        cdk_common::payment::Event::PaymentReceived(payment_response) => {
-                                            match tx.send(Result::<_, Status>::Ok(payment_response.into()))
-                                            .await
-                                            {
-                                                Ok(_) => {
-                                                    // Response was queued to be sent to client
-                                                }
-                                                Err(item) => {
-                                                    tracing::error!("Error adding incoming payment to stream: {}", item);
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            Err(err) => {
-                                tracing::warn!("Could not get invoice stream: {}", err);
-                                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                            }
-                        }
-                    }
+                };
+
+                // Only send the settings when they changed since the last response
+                let changed = last_settings.as_ref() != Some(&settings);
+                let response = HealthResponse {
+                    sequence: ping.sequence,
+                    settings: changed.then(|| settings.clone()),
+                };
+                last_settings = Some(settings);
+
+                if tx.send(Ok(response)).await.is_err() {
+                    break;
                 }
             }
         });
 
-        let output_stream = ReceiverStream::new(rx);
-        Ok(Response::new(
-            Box::pin(output_stream) as Self::WaitIncomingPaymentStream
-        ))
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
     }
 }
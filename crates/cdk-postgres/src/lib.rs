@@ -348,3 +348,24 @@ mod test {
 
     mint_db_test!(provide_db);
 }
+
+#[cfg(test)]
+mod wallet_test {
+    use cdk_common::wallet_db_test;
+
+    use super::*;
+
+    async fn provide_db(test_id: String) -> WalletPgDatabase {
+        let db_url = std::env::var("CDK_MINTD_DATABASE_URL")
+            .or_else(|_| std::env::var("PG_DB_URL")) // Fallback for compatibility
+            .unwrap_or("host=localhost user=test password=test dbname=testdb port=5433".to_owned());
+
+        let db_url = format!("{db_url} schema={test_id}");
+
+        new_wallet_pg_database(db_url.as_str())
+            .await
+            .expect("database")
+    }
+
+    wallet_db_test!(provide_db);
+}
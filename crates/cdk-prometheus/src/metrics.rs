@@ -37,6 +37,12 @@ pub struct CdkMetrics {
     mint_operations_total: IntCounterVec,
     mint_in_flight_requests: IntGaugeVec,
     mint_operation_duration: HistogramVec,
+
+    // Websocket metrics
+    ws_connections_active: IntGauge,
+    ws_subscriptions_active: IntGauge,
+    ws_notifications_dropped_total: IntCounter,
+    ws_disconnects_total: IntCounterVec,
 }
 
 impl CdkMetrics {
@@ -68,6 +74,14 @@ impl CdkMetrics {
         let (mint_operations_total, mint_operation_duration, mint_in_flight_requests) =
             Self::create_mint_metrics(&registry)?;
 
+        // Create and register websocket metrics
+        let (
+            ws_connections_active,
+            ws_subscriptions_active,
+            ws_notifications_dropped_total,
+            ws_disconnects_total,
+        ) = Self::create_ws_metrics(&registry)?;
+
         Ok(Self {
             registry,
             http_requests_total,
@@ -84,6 +98,10 @@ impl CdkMetrics {
             mint_operations_total,
             mint_in_flight_requests,
             mint_operation_duration,
+            ws_connections_active,
+            ws_subscriptions_active,
+            ws_notifications_dropped_total,
+            ws_disconnects_total,
         })
     }
 
@@ -267,6 +285,48 @@ impl CdkMetrics {
         ))
     }
 
+    /// Create and register websocket metrics
+    ///
+    /// # Errors
+    /// Returns an error if any of the metrics cannot be created or registered
+    fn create_ws_metrics(
+        registry: &Registry,
+    ) -> crate::Result<(IntGauge, IntGauge, IntCounter, IntCounterVec)> {
+        let ws_connections_active = IntGauge::new(
+            "cdk_ws_connections_active",
+            "Number of open websocket connections",
+        )?;
+        registry.register(Box::new(ws_connections_active.clone()))?;
+
+        let ws_subscriptions_active = IntGauge::new(
+            "cdk_ws_subscriptions_active",
+            "Number of active websocket subscriptions",
+        )?;
+        registry.register(Box::new(ws_subscriptions_active.clone()))?;
+
+        let ws_notifications_dropped_total = IntCounter::new(
+            "cdk_ws_notifications_dropped_total",
+            "Total websocket notifications dropped because the send queue was full",
+        )?;
+        registry.register(Box::new(ws_notifications_dropped_total.clone()))?;
+
+        let ws_disconnects_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "cdk_ws_disconnects_total",
+                "Total websocket connections closed by the mint",
+            ),
+            &["reason"],
+        )?;
+        registry.register(Box::new(ws_disconnects_total.clone()))?;
+
+        Ok((
+            ws_connections_active,
+            ws_subscriptions_active,
+            ws_notifications_dropped_total,
+            ws_disconnects_total,
+        ))
+    }
+
     /// Get the metrics registry
     #[must_use]
     pub fn registry(&self) -> Arc<Registry> {
@@ -348,6 +408,27 @@ impl CdkMetrics {
             .with_label_values(&[operation])
             .dec();
     }
+
+    // Websocket metrics methods
+    pub fn inc_ws_connections(&self) {
+        self.ws_connections_active.inc();
+    }
+
+    pub fn dec_ws_connections(&self) {
+        self.ws_connections_active.dec();
+    }
+
+    pub fn add_ws_subscriptions(&self, count: i64) {
+        self.ws_subscriptions_active.add(count);
+    }
+
+    pub fn record_ws_notification_dropped(&self) {
+        self.ws_notifications_dropped_total.inc();
+    }
+
+    pub fn record_ws_disconnect(&self, reason: &str) {
+        self.ws_disconnects_total.with_label_values(&[reason]).inc();
+    }
 }
 
 impl Default for CdkMetrics {
@@ -420,6 +501,31 @@ pub mod global {
         METRICS.dec_in_flight_requests(operation);
     }
 
+    /// Increment open websocket connections using the global metrics instance
+    pub fn inc_ws_connections() {
+        METRICS.inc_ws_connections();
+    }
+
+    /// Decrement open websocket connections using the global metrics instance
+    pub fn dec_ws_connections() {
+        METRICS.dec_ws_connections();
+    }
+
+    /// Add to the active websocket subscriptions using the global metrics instance
+    pub fn add_ws_subscriptions(count: i64) {
+        METRICS.add_ws_subscriptions(count);
+    }
+
+    /// Record a dropped websocket notification using the global metrics instance
+    pub fn record_ws_notification_dropped() {
+        METRICS.record_ws_notification_dropped();
+    }
+
+    /// Record a websocket connection closed by the mint using the global metrics instance
+    pub fn record_ws_disconnect(reason: &str) {
+        METRICS.record_ws_disconnect(reason);
+    }
+
     /// Get the metrics registry from the global instance
     pub fn registry() -> std::sync::Arc<prometheus::Registry> {
         METRICS.registry()
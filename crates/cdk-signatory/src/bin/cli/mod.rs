@@ -2,8 +2,9 @@
 //!
 //! This logic is in this file to be excluded for wasm
 use std::collections::HashMap;
+use std::io::Write;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{env, fs};
@@ -11,12 +12,18 @@ use std::{env, fs};
 use anyhow::{bail, Result};
 use bip39::rand::{thread_rng, Rng};
 use bip39::Mnemonic;
-use cdk_common::database::MintKeysDatabase;
-use cdk_common::CurrencyUnit;
+use cdk_common::database::{MintKVStore, MintKeysDatabase};
+use cdk_common::{CurrencyUnit, Id};
+#[cfg(feature = "pkcs11")]
+use cdk_signatory::hsm;
+use cdk_signatory::issuance::{IssuanceLimiter, IssuanceLimits};
+use cdk_signatory::signatory::{IssuanceLimit, KeysetIdentifier, Signatory};
+use cdk_signatory::threshold::{self, DealtKeysets, NodeShares, ShareSignatory};
 use cdk_signatory::{db_signatory, start_grpc_server};
 #[cfg(feature = "sqlite")]
 use cdk_sqlite::MintSqliteDatabase;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
 use tracing_subscriber::EnvFilter;
 
 /// Common CLI arguments for CDK binaries
@@ -56,6 +63,8 @@ pub fn init_logging(enable_logging: bool, log_level: tracing::Level) {
 
 const DEFAULT_WORK_DIR: &str = ".cdk-signatory";
 const ENV_MNEMONIC: &str = "CDK_MINTD_MNEMONIC";
+#[cfg(feature = "pkcs11")]
+const ENV_PKCS11_PIN: &str = "CDK_SIGNATORY_PKCS11_PIN";
 
 /// Simple CLI application to interact with cashu
 #[derive(Parser)]
@@ -85,6 +94,49 @@ struct Cli {
     /// Supported units with the format of name,fee and max_order
     #[arg(long, short, default_value = "sat,0,32")]
     units: Vec<String>,
+    /// Limits of the amount signed, with the format of keyset id or unit, max signed, max per
+    /// window and window in seconds (e.g. `sat,,100000,3600`). Swaps and melt change count too
+    #[arg(long)]
+    issuance_limits: Vec<String>,
+    /// Path to a PKCS#11 module holding the keys instead of deriving them from a seed.
+    /// The user PIN is read from CDK_SIGNATORY_PKCS11_PIN. Signatures carry no NUT-12 DLEQ proof
+    #[cfg(feature = "pkcs11")]
+    #[arg(long)]
+    pkcs11_module: Option<PathBuf>,
+    /// Label of the PKCS#11 token holding the keys
+    #[cfg(feature = "pkcs11")]
+    #[arg(long, default_value = "cdk-signatory")]
+    pkcs11_token: String,
+    /// Path to the share file of a threshold signatory node to serve instead of full keys
+    #[arg(long)]
+    shares: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deal the shares of threshold signatory nodes with a new keyset of each unit
+    ///
+    /// Each share is written to its own destination, only readable by its owner, which should be
+    /// storage of its node alone (e.g. one removable drive per node).
+    Deal {
+        /// Number of nodes needed to sign
+        #[arg(long)]
+        threshold: u8,
+        /// Destination of the shares of each node, in the order of their indices
+        #[arg(long = "out", required = true)]
+        out: Vec<PathBuf>,
+        /// Deal new keysets to nodes already holding shares, to add with `import-keysets` on each
+        /// node, instead of new share files
+        #[arg(long)]
+        keysets_only: bool,
+    },
+    /// Add the keysets dealt with `deal --keysets-only` to the share file of the node (`--shares`)
+    ImportKeysets {
+        /// Path of the keysets dealt to the node, deleted once imported
+        keysets: PathBuf,
+    },
 }
 
 /// Main function for the signatory standalone binary
@@ -96,7 +148,7 @@ pub async fn cli_main() -> Result<()> {
 
     let supported_units = args
         .units
-        .into_iter()
+        .iter()
         .map(|unit| {
             let mut parts = unit.split(",").collect::<Vec<_>>();
             parts.reverse();
@@ -111,6 +163,27 @@ pub async fn cli_main() -> Result<()> {
         })
         .collect::<Result<HashMap<_, _>, _>>()?;
 
+    let mut issuance_limits = IssuanceLimits::new();
+    for limit in &args.issuance_limits {
+        let (keyset, limit) = parse_issuance_limit(limit)?;
+        issuance_limits.add(keyset, limit);
+    }
+
+    match &args.command {
+        Some(Command::Deal {
+            threshold,
+            out,
+            keysets_only,
+        }) => return deal(out, *threshold, *keysets_only, supported_units),
+        Some(Command::ImportKeysets { keysets }) => {
+            let Some(shares) = &args.shares else {
+                bail!("The share file of the node is required to import keysets");
+            };
+            return import_keysets(shares, keysets);
+        }
+        None => {}
+    }
+
     let work_dir = match &args.work_dir {
         Some(work_dir) => work_dir.clone(),
         None => {
@@ -121,37 +194,78 @@ pub async fn cli_main() -> Result<()> {
 
     let certs = Some(
         args.certs
+            .clone()
             .map(|x| x.into())
             .unwrap_or_else(|| work_dir.clone()),
     );
 
     fs::create_dir_all(&work_dir)?;
 
-    let localstore: Arc<dyn MintKeysDatabase<Err = cdk_common::database::Error> + Send + Sync> =
-        match args.engine.as_str() {
-            "sqlite" => {
-                #[cfg(feature = "sqlite")]
-                {
-                    let sql_path = work_dir.join("cdk-cli.sqlite");
-                    #[cfg(not(feature = "sqlcipher"))]
-                    let db = MintSqliteDatabase::new(&sql_path).await?;
-                    #[cfg(feature = "sqlcipher")]
-                    let db = {
-                        match args.password {
-                            Some(pass) => MintSqliteDatabase::new((&sql_path, pass)).await?,
-                            None => bail!("Missing database password"),
-                        }
-                    };
-
-                    Arc::new(db)
-                }
-                #[cfg(not(feature = "sqlite"))]
-                {
-                    bail!("sqlite feature not enabled");
-                }
+    let socket_addr = SocketAddr::from_str(&format!("{}:{}", args.listen_addr, args.listen_port))?;
+
+    let (localstore, kv_store): (
+        Arc<dyn MintKeysDatabase<Err = cdk_common::database::Error> + Send + Sync>,
+        Arc<dyn MintKVStore<Err = cdk_common::database::Error> + Send + Sync>,
+    ) = match args.engine.as_str() {
+        "sqlite" => {
+            #[cfg(feature = "sqlite")]
+            {
+                let sql_path = work_dir.join("cdk-cli.sqlite");
+                #[cfg(not(feature = "sqlcipher"))]
+                let db = MintSqliteDatabase::new(&sql_path).await?;
+                #[cfg(feature = "sqlcipher")]
+                let db = {
+                    match args.password.clone() {
+                        Some(pass) => MintSqliteDatabase::new((&sql_path, pass)).await?,
+                        None => bail!("Missing database password"),
+                    }
+                };
+
+                let db = Arc::new(db);
+                (db.clone(), db)
             }
-            _ => bail!("Unknown DB engine"),
-        };
+            #[cfg(not(feature = "sqlite"))]
+            {
+                bail!("sqlite feature not enabled");
+            }
+        }
+        _ => bail!("Unknown DB engine"),
+    };
+
+    let signatory = signatory(&args, &work_dir, localstore, supported_units).await?;
+    let signatory = IssuanceLimiter::new(signatory, kv_store, issuance_limits).await?;
+
+    start_grpc_server(Arc::new(signatory), socket_addr, certs).await?;
+
+    Ok(())
+}
+
+/// Signatory holding the keys: shares of a threshold signatory, a PKCS#11 token or a seed
+async fn signatory(
+    args: &Cli,
+    work_dir: &Path,
+    localstore: Arc<dyn MintKeysDatabase<Err = cdk_common::database::Error> + Send + Sync>,
+    supported_units: HashMap<CurrencyUnit, (u64, u8)>,
+) -> Result<Arc<dyn Signatory + Send + Sync>> {
+    if let Some(shares) = &args.shares {
+        let shares: NodeShares = serde_json::from_str(&fs::read_to_string(shares)?)?;
+        tracing::info!(
+            "Serving the shares of threshold signatory node {}",
+            shares.index
+        );
+
+        return Ok(Arc::new(ShareSignatory::new(shares)));
+    }
+
+    #[cfg(feature = "pkcs11")]
+    if let Some(module) = &args.pkcs11_module {
+        let pin = env::var(ENV_PKCS11_PIN)
+            .map_err(|_| anyhow::anyhow!("{ENV_PKCS11_PIN} is required with a PKCS#11 module"))?;
+        let key_store = Arc::new(hsm::Pkcs11KeyStore::new(module, &args.pkcs11_token, &pin)?);
+        let signatory = hsm::HsmSignatory::new(localstore, key_store, supported_units).await?;
+
+        return Ok(Arc::new(signatory));
+    }
 
     let seed_path = work_dir.join("seed");
 
@@ -182,9 +296,128 @@ pub async fn cli_main() -> Result<()> {
         db_signatory::DbSignatory::new(localstore, &seed, supported_units, Default::default())
             .await?;
 
-    let socket_addr = SocketAddr::from_str(&format!("{}:{}", args.listen_addr, args.listen_port))?;
+    Ok(Arc::new(signatory))
+}
 
-    start_grpc_server(Arc::new(signatory), socket_addr, certs).await?;
+/// Parses an issuance limit with the format of keyset id or unit, max signed, max per window and
+/// window in seconds, leaving out the limits not enforced (e.g. `sat,,100000,3600`)
+fn parse_issuance_limit(limit: &str) -> Result<(KeysetIdentifier, IssuanceLimit)> {
+    let parts = limit.split(',').map(str::trim).collect::<Vec<_>>();
+    let [keyset, max_signed, max_per_window, window_secs] = parts[..] else {
+        bail!("Invalid issuance limit: {limit}");
+    };
+
+    let keyset = match Id::from_str(keyset) {
+        Ok(id) => KeysetIdentifier::Id(id),
+        Err(_) => KeysetIdentifier::Unit(keyset.parse()?),
+    };
+    let optional = |value: &str| -> Result<Option<u64>> {
+        Ok((!value.is_empty()).then(|| value.parse()).transpose()?)
+    };
+
+    let limit = IssuanceLimit {
+        max_signed: optional(max_signed)?,
+        max_per_window: optional(max_per_window)?,
+        window_secs: optional(window_secs)?.unwrap_or_default(),
+    };
+    if limit.max_per_window.is_some() && limit.window_secs == 0 {
+        bail!("Invalid issuance limit: {limit:?} has a rate without a window");
+    }
+
+    Ok((keyset, limit))
+}
+
+/// Deals the shares of the nodes with a new keyset of each unit to the destinations
+///
+/// The first dealing creates the share files of the nodes. Later ones, with `keysets_only`, only
+/// need the indices of the nodes, so the shares already dealt never have to be brought together.
+fn deal(
+    out: &[PathBuf],
+    threshold: u8,
+    keysets_only: bool,
+    mut supported_units: HashMap<CurrencyUnit, (u64, u8)>,
+) -> Result<()> {
+    if let Some(path) = out.iter().find(|path| path.exists()) {
+        bail!("{} already exists", path.display());
+    }
+    let nodes = u8::try_from(out.len())?;
+
+    let mut dealt = (1..=nodes)
+        .map(|index| DealtKeysets {
+            index,
+            threshold,
+            keysets: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    let indices = (1..=nodes).collect::<Vec<_>>();
+
+    if !keysets_only {
+        supported_units.entry(CurrencyUnit::Auth).or_insert((0, 1));
+    }
+
+    for (unit, (fee, max_order)) in supported_units {
+        let amounts = (0..max_order)
+            .map(|i| 2_u64.pow(i as u32))
+            .collect::<Vec<_>>();
+        let (id, mut shares) =
+            threshold::deal_keyset_shares(threshold, &indices, unit.clone(), &amounts, fee)?;
+        for node in dealt.iter_mut() {
+            node.keysets.extend(shares.remove(&node.index));
+        }
+        println!("Dealt keyset {id} for {unit}");
+    }
+
+    if keysets_only {
+        for (path, dealt) in out.iter().zip(&dealt) {
+            write_shares(path, dealt)?;
+        }
+        return Ok(());
+    }
+
+    let mut shares = threshold::deal_nodes(threshold, nodes)?;
+    for (node, dealt) in shares.iter_mut().zip(dealt) {
+        node.add_keysets(dealt)?;
+    }
+    for (path, shares) in out.iter().zip(&shares) {
+        write_shares(path, shares)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the keysets dealt to the node to its share file
+fn import_keysets(shares_path: &Path, keysets_path: &Path) -> Result<()> {
+    let mut shares: NodeShares = serde_json::from_str(&fs::read_to_string(shares_path)?)?;
+    let dealt: DealtKeysets = serde_json::from_str(&fs::read_to_string(keysets_path)?)?;
+
+    let ids = dealt
+        .keysets
+        .iter()
+        .map(|keyset| keyset.id.to_string())
+        .collect::<Vec<_>>();
+    shares.add_keysets(dealt)?;
+    write_shares(shares_path, &shares)?;
+    fs::remove_file(keysets_path)?;
+
+    println!("Imported keysets {}", ids.join(", "));
+
+    Ok(())
+}
+
+/// Writes the shares to the path, readable by the owner only
+///
+/// The shares are written to a temporary file first, so an existing file is replaced at once.
+fn write_shares<T: Serialize>(path: &Path, shares: &T) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(&tmp)?;
+    file.write_all(serde_json::to_string_pretty(shares)?.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(tmp, path)?;
 
     Ok(())
 }
@@ -212,6 +212,7 @@ impl Signatory for DbSignatory {
                 .values()
                 .map(|k| k.into())
                 .collect::<Vec<_>>(),
+            share_index: None,
         })
     }
 
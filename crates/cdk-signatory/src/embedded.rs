@@ -6,7 +6,9 @@ use cdk_common::{BlindSignature, BlindedMessage, Error, Proof};
 use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
-use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
+use crate::signatory::{
+    RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets, SignatoryStats,
+};
 
 enum Request {
     BlindSign(
@@ -23,6 +25,7 @@ enum Request {
             oneshot::Sender<Result<SignatoryKeySet, Error>>,
         ),
     ),
+    Stats(oneshot::Sender<Result<SignatoryStats, Error>>),
 }
 
 /// Creates a service-like to wrap an implementation of the Signatory
@@ -87,6 +90,12 @@ impl Service {
                         tracing::error!("Error sending response: {:?}", err);
                     }
                 }
+                Request::Stats(response) => {
+                    let output = handler.stats().await;
+                    if let Err(err) = response.send(output) {
+                        tracing::error!("Error sending response: {:?}", err);
+                    }
+                }
             }
         }
     }
@@ -144,4 +153,15 @@ impl Signatory for Service {
 
         rx.await.map_err(|e| Error::RecvError(e.to_string()))?
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn stats(&self) -> Result<SignatoryStats, Error> {
+        let (tx, rx) = oneshot::channel();
+        self.pipeline
+            .send(Request::Stats(tx))
+            .await
+            .map_err(|e| Error::SendError(e.to_string()))?;
+
+        rx.await.map_err(|e| Error::RecvError(e.to_string()))?
+    }
 }
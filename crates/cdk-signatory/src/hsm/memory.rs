@@ -0,0 +1,63 @@
+//! In memory key store
+//!
+//! Keeps the private keys in the process memory. Useful for tests and development, use a
+//! hardware backed key store in production.
+use std::collections::HashMap;
+
+use bitcoin::secp256k1::{Scalar, Secp256k1};
+use cdk_common::nuts::SecretKey;
+use cdk_common::{Error, PublicKey};
+use tokio::sync::RwLock;
+
+use super::KeyStore;
+
+/// Key store holding the private keys in memory
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    keys: RwLock<HashMap<String, SecretKey>>,
+}
+
+impl MemoryKeyStore {
+    /// Creates an empty key store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyStore for MemoryKeyStore {
+    async fn generate_key(&self, label: &str) -> Result<PublicKey, Error> {
+        let mut keys = self.keys.write().await;
+        if keys.contains_key(label) {
+            return Err(Error::Custom(format!("Key {label} already exists")));
+        }
+
+        let secret_key = SecretKey::generate();
+        let public_key = secret_key.public_key();
+        keys.insert(label.to_string(), secret_key);
+
+        Ok(public_key)
+    }
+
+    async fn public_key(&self, label: &str) -> Result<Option<PublicKey>, Error> {
+        Ok(self
+            .keys
+            .read()
+            .await
+            .get(label)
+            .map(|key| key.public_key()))
+    }
+
+    async fn ecdh(&self, label: &str, point: &PublicKey) -> Result<[u8; 32], Error> {
+        let keys = self.keys.read().await;
+        let secret_key = keys
+            .get(label)
+            .ok_or_else(|| Error::Custom(format!("Unknown key {label}")))?;
+
+        let shared = point
+            .mul_tweak(&Secp256k1::new(), &Scalar::from(**secret_key))
+            .map_err(|_| Error::Internal)?;
+
+        Ok(shared.x_only_public_key().0.serialize())
+    }
+}
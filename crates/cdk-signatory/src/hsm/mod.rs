@@ -0,0 +1,536 @@
+//! Signatory with keys isolated behind a key store
+//!
+//! Unlike [`crate::db_signatory::DbSignatory`], which derives every key from a seed held in
+//! memory, this signatory never sees a private key. Keys are generated inside a [`KeyStore`], one
+//! per keyset amount, and blind signatures are computed by the key store through raw ECDH, so the
+//! keys can live in an HSM and never be exported.
+//!
+//! ECDH only returns the x coordinate of `k * P`. The full point is recovered with a second ECDH
+//! on `P + G`: of the two points with that x coordinate, `k * P` is the one for which
+//! `k * P + K` has the x coordinate of `k * (P + G)`.
+//!
+//! Blind signatures are returned without a NUT-12 DLEQ proof. The proof needs `s = r + e * k`,
+//! which is arithmetic on the private key itself, while a token only exposes ECDH with it, whose
+//! result is a point. Wallets cannot check these signatures offline, so a mint signing with this
+//! signatory should not rely on DLEQ proofs for its users.
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use bitcoin::secp256k1::{self, Secp256k1};
+use cdk_common::dhke::hash_to_curve;
+use cdk_common::mint::MintKeySetInfo;
+use cdk_common::nuts::{BlindSignature, BlindedMessage, CurrencyUnit, Id, Keys, Proof};
+use cdk_common::util::unix_time;
+use cdk_common::{database, Amount, Error, PublicKey};
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
+
+mod memory;
+#[cfg(all(feature = "pkcs11", not(target_arch = "wasm32")))]
+mod pkcs11;
+
+pub use memory::MemoryKeyStore;
+#[cfg(all(feature = "pkcs11", not(target_arch = "wasm32")))]
+pub use pkcs11::Pkcs11KeyStore;
+
+/// Label of the key identifying the signatory
+const IDENTITY_KEY_LABEL: &str = "cdk-signatory";
+
+/// Storage of secp256k1 private keys that never leave it
+#[async_trait::async_trait]
+pub trait KeyStore {
+    /// Generates a new private key stored under the label and returns its public key
+    async fn generate_key(&self, label: &str) -> Result<PublicKey, Error>;
+
+    /// Public key of the private key stored under the label
+    async fn public_key(&self, label: &str) -> Result<Option<PublicKey>, Error>;
+
+    /// x coordinate of the point multiplied by the private key stored under the label
+    async fn ecdh(&self, label: &str, point: &PublicKey) -> Result<[u8; 32], Error>;
+}
+
+/// Signatory whose private keys live in a [`KeyStore`]
+pub struct HsmSignatory {
+    keysets: RwLock<HashMap<Id, (MintKeySetInfo, Keys)>>,
+    localstore: Arc<dyn database::MintKeysDatabase<Err = database::Error> + Send + Sync>,
+    key_store: Arc<dyn KeyStore + Send + Sync>,
+    generator: secp256k1::PublicKey,
+    pubkey: PublicKey,
+}
+
+impl HsmSignatory {
+    /// Creates a new HsmSignatory instance
+    ///
+    /// A keyset is created for each supported unit without an active keyset matching its fee
+    /// and max order.
+    pub async fn new(
+        localstore: Arc<dyn database::MintKeysDatabase<Err = database::Error> + Send + Sync>,
+        key_store: Arc<dyn KeyStore + Send + Sync>,
+        mut supported_units: HashMap<CurrencyUnit, (u64, u8)>,
+    ) -> Result<Self, Error> {
+        let secp_ctx = Secp256k1::new();
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let generator = secp256k1::PublicKey::from_secret_key(
+            &secp_ctx,
+            &secp256k1::SecretKey::from_slice(&one).expect("one is a valid secret key"),
+        );
+
+        let pubkey = match key_store.public_key(IDENTITY_KEY_LABEL).await? {
+            Some(pubkey) => pubkey,
+            None => key_store.generate_key(IDENTITY_KEY_LABEL).await?,
+        };
+
+        let signatory = Self {
+            keysets: Default::default(),
+            localstore,
+            key_store,
+            generator,
+            pubkey,
+        };
+
+        let active_keysets = signatory.localstore.get_active_keysets().await?;
+        let keyset_infos = signatory.localstore.get_keyset_infos().await?;
+
+        supported_units.entry(CurrencyUnit::Auth).or_insert((0, 1));
+
+        for (unit, (fee, max_order)) in supported_units {
+            let amounts = (0..max_order)
+                .map(|i| 2_u64.pow(i as u32))
+                .collect::<Vec<_>>();
+
+            let is_current = active_keysets
+                .get(&unit)
+                .and_then(|id| keyset_infos.iter().find(|info| &info.id == id))
+                .is_some_and(|info| info.input_fee_ppk == fee && info.amounts == amounts);
+
+            if !is_current {
+                signatory.create_keyset(unit, &amounts, fee).await?;
+            }
+        }
+
+        signatory.reload_keys_from_db().await?;
+
+        Ok(signatory)
+    }
+
+    /// Load all the keysets from the database along with their public keys from the key store
+    ///
+    /// Keysets whose keys are not in the key store (e.g. created by another signatory) are
+    /// skipped, as they cannot be used to sign nor verify.
+    async fn reload_keys_from_db(&self) -> Result<(), Error> {
+        let db_active_keysets = self.localstore.get_active_keysets().await?;
+        let mut loaded = HashMap::new();
+
+        'keysets: for mut info in self.localstore.get_keyset_infos().await? {
+            let Some(index) = info.derivation_path_index else {
+                continue;
+            };
+
+            let mut keys = BTreeMap::new();
+            for amount in &info.amounts {
+                let label = key_label(&info.unit, index, *amount);
+                match self.key_store.public_key(&label).await? {
+                    Some(pubkey) => {
+                        keys.insert(Amount::from(*amount), pubkey);
+                    }
+                    None => {
+                        tracing::warn!("Keyset {} has no key {} in the key store", info.id, label);
+                        continue 'keysets;
+                    }
+                }
+            }
+
+            info.active = db_active_keysets.get(&info.unit) == Some(&info.id);
+            loaded.insert(info.id, (info, Keys::new(keys)));
+        }
+
+        *self.keysets.write().await = loaded;
+
+        Ok(())
+    }
+
+    /// Creates a keyset with a key per amount and sets it as active
+    async fn create_keyset(
+        &self,
+        unit: CurrencyUnit,
+        amounts: &[u64],
+        input_fee_ppk: u64,
+    ) -> Result<MintKeySetInfo, Error> {
+        let index = self
+            .localstore
+            .get_keyset_infos()
+            .await?
+            .iter()
+            .filter(|info| info.unit == unit)
+            .filter_map(|info| info.derivation_path_index)
+            .max()
+            .map_or(0, |index| index + 1);
+
+        let mut keys = BTreeMap::new();
+        for amount in amounts {
+            let label = key_label(&unit, index, *amount);
+            // A key left behind by an interrupted rotation has never signed anything
+            let pubkey = match self.key_store.public_key(&label).await? {
+                Some(pubkey) => pubkey,
+                None => self.key_store.generate_key(&label).await?,
+            };
+            keys.insert(Amount::from(*amount), pubkey);
+        }
+
+        let info = MintKeySetInfo {
+            id: Id::v1_from_keys(&Keys::new(keys)),
+            unit: unit.clone(),
+            active: true,
+            valid_from: unix_time(),
+            final_expiry: None,
+            derivation_path: Default::default(),
+            derivation_path_index: Some(index),
+            max_order: 0,
+            amounts: amounts.to_owned(),
+            input_fee_ppk,
+        };
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        tx.add_keyset_info(info.clone()).await?;
+        tx.set_active_keyset(unit, info.id).await?;
+        tx.commit().await?;
+
+        Ok(info)
+    }
+
+    /// Multiplies the point by the private key of `pubkey` stored under the label
+    async fn multiply(
+        &self,
+        label: &str,
+        pubkey: &PublicKey,
+        point: &PublicKey,
+    ) -> Result<PublicKey, Error> {
+        let x = self.key_store.ecdh(label, point).await?;
+        let shifted: PublicKey = point
+            .combine(&self.generator)
+            .map_err(|_| Error::Internal)?
+            .into();
+        let shifted_x = self.key_store.ecdh(label, &shifted).await?;
+
+        for parity in [0x02, 0x03] {
+            let mut bytes = [parity; 33];
+            bytes[1..].copy_from_slice(&x);
+            let candidate =
+                secp256k1::PublicKey::from_slice(&bytes).map_err(|_| Error::Internal)?;
+
+            let matches = candidate
+                .combine(pubkey)
+                .is_ok_and(|sum| sum.x_only_public_key().0.serialize() == shifted_x);
+            if matches {
+                return Ok(candidate.into());
+            }
+        }
+
+        tracing::error!(
+            "Key store returned an inconsistent ECDH result for {}",
+            label
+        );
+        Err(Error::Internal)
+    }
+
+    /// Key label and public key of the amount in the keyset
+    async fn amount_key(
+        &self,
+        keyset_id: &Id,
+        amount: Amount,
+    ) -> Result<(String, PublicKey, bool), Error> {
+        let keysets = self.keysets.read().await;
+        let (info, keys) = keysets.get(keyset_id).ok_or(Error::UnknownKeySet)?;
+        let pubkey = keys.amount_key(amount).ok_or(Error::UnknownKeySet)?;
+        let index = info.derivation_path_index.ok_or(Error::UnknownKeySet)?;
+
+        Ok((
+            key_label(&info.unit, index, amount.into()),
+            pubkey,
+            info.active,
+        ))
+    }
+}
+
+/// Label of the key of an amount in the keyset with the index
+fn key_label(unit: &CurrencyUnit, index: u32, amount: u64) -> String {
+    format!("cdk-{unit}-{index}-{amount}")
+}
+
+#[async_trait::async_trait]
+impl Signatory for HsmSignatory {
+    fn name(&self) -> String {
+        format!("HSM Signatory {}", env!("CARGO_PKG_VERSION"))
+    }
+
+    #[instrument(skip_all)]
+    async fn blind_sign(
+        &self,
+        blinded_messages: Vec<BlindedMessage>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        let mut signatures = Vec::with_capacity(blinded_messages.len());
+
+        for blinded_message in blinded_messages {
+            let (label, pubkey, active) = self
+                .amount_key(&blinded_message.keyset_id, blinded_message.amount)
+                .await?;
+            if !active {
+                return Err(Error::InactiveKeyset);
+            }
+
+            let c = self
+                .multiply(&label, &pubkey, &blinded_message.blinded_secret)
+                .await?;
+
+            signatures.push(BlindSignature {
+                amount: blinded_message.amount,
+                keyset_id: blinded_message.keyset_id,
+                c,
+                dleq: None,
+            });
+        }
+
+        Ok(signatures)
+    }
+
+    #[instrument(skip_all)]
+    async fn verify_proofs(&self, proofs: Vec<Proof>) -> Result<(), Error> {
+        for proof in proofs {
+            let (label, pubkey, _) = self.amount_key(&proof.keyset_id, proof.amount).await?;
+            let y = hash_to_curve(proof.secret.as_bytes())?;
+
+            if self.multiply(&label, &pubkey, &y).await? != proof.c {
+                return Err(cdk_common::dhke::Error::TokenNotVerified.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+        Ok(SignatoryKeysets {
+            pubkey: self.pubkey,
+            keysets: self
+                .keysets
+                .read()
+                .await
+                .values()
+                .map(|(info, keys)| SignatoryKeySet {
+                    id: info.id,
+                    unit: info.unit.clone(),
+                    active: info.active,
+                    keys: keys.clone(),
+                    amounts: info.amounts.clone(),
+                    input_fee_ppk: info.input_fee_ppk,
+                    final_expiry: info.final_expiry,
+                })
+                .collect(),
+            share_index: None,
+        })
+    }
+
+    /// Add current keyset to inactive keysets
+    /// Generate new keyset
+    #[instrument(skip(self))]
+    async fn rotate_keyset(&self, args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+        let info = self
+            .create_keyset(args.unit, &args.amounts, args.input_fee_ppk)
+            .await?;
+
+        self.reload_keys_from_db().await?;
+
+        let keysets = self.keysets.read().await;
+        let (info, keys) = keysets.get(&info.id).ok_or(Error::UnknownKeySet)?;
+
+        Ok(SignatoryKeySet {
+            id: info.id,
+            unit: info.unit.clone(),
+            active: info.active,
+            keys: keys.clone(),
+            amounts: info.amounts.clone(),
+            input_fee_ppk: info.input_fee_ppk,
+            final_expiry: info.final_expiry,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod test {
+    use bitcoin::hashes::sha256::Hash as Sha256;
+    use bitcoin::hashes::Hash;
+    use bitcoin::secp256k1::ecdh::shared_secret_point;
+    use cdk_common::dhke::{blind_message, sign_message, unblind_message};
+    use cdk_common::nuts::SecretKey;
+    use cdk_common::secret::Secret;
+
+    use super::*;
+
+    /// Key store deriving the key of a label from its hash, with the ECDH of a PKCS#11 token:
+    /// the x coordinate of the shared point without a key derivation function
+    struct SoftwareKeyStore;
+
+    impl SoftwareKeyStore {
+        fn secret_key(label: &str) -> SecretKey {
+            SecretKey::from_slice(&Sha256::hash(label.as_bytes()).to_byte_array()).unwrap()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl KeyStore for SoftwareKeyStore {
+        async fn generate_key(&self, label: &str) -> Result<PublicKey, Error> {
+            Ok(Self::secret_key(label).public_key())
+        }
+
+        async fn public_key(&self, label: &str) -> Result<Option<PublicKey>, Error> {
+            Ok(Some(Self::secret_key(label).public_key()))
+        }
+
+        async fn ecdh(&self, label: &str, point: &PublicKey) -> Result<[u8; 32], Error> {
+            let shared = shared_secret_point(point, &Self::secret_key(label));
+            Ok(shared[..32].try_into().expect("32 bytes"))
+        }
+    }
+
+    async fn signatory(
+        key_store: Arc<dyn KeyStore + Send + Sync>,
+    ) -> (
+        HsmSignatory,
+        Arc<dyn database::MintKeysDatabase<Err = database::Error> + Send + Sync>,
+    ) {
+        let localstore: Arc<dyn database::MintKeysDatabase<Err = database::Error> + Send + Sync> =
+            Arc::new(cdk_sqlite::mint::memory::empty().await.expect("memory db"));
+
+        let signatory = HsmSignatory::new(
+            localstore.clone(),
+            key_store,
+            HashMap::from([(CurrencyUnit::Sat, (0, 4))]),
+        )
+        .await
+        .expect("signatory");
+
+        (signatory, localstore)
+    }
+
+    /// Signs a secret for the amount and returns the unblinded proof
+    async fn sign_proof(signatory: &HsmSignatory, keyset: &SignatoryKeySet, amount: u64) -> Proof {
+        let secret = Secret::generate();
+        let (blinded_secret, r) = blind_message(secret.as_bytes(), None).unwrap();
+
+        let signature = signatory
+            .blind_sign(vec![BlindedMessage::new(
+                Amount::from(amount),
+                keyset.id,
+                blinded_secret,
+            )])
+            .await
+            .unwrap()
+            .remove(0);
+
+        let mint_pubkey = keyset.keys.amount_key(Amount::from(amount)).unwrap();
+        let c = unblind_message(&signature.c, &r, &mint_pubkey).unwrap();
+
+        Proof::new(Amount::from(amount), keyset.id, secret, c)
+    }
+
+    async fn active_keyset(signatory: &HsmSignatory) -> SignatoryKeySet {
+        signatory
+            .keysets()
+            .await
+            .unwrap()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.active && keyset.unit == CurrencyUnit::Sat)
+            .expect("active sat keyset")
+    }
+
+    #[tokio::test]
+    async fn test_blind_sign_through_key_store() {
+        let (signatory, _) = signatory(Arc::new(MemoryKeyStore::new())).await;
+        let keyset = active_keyset(&signatory).await;
+        assert_eq!(keyset.amounts, vec![1, 2, 4, 8]);
+
+        for amount in [1, 2, 4, 8] {
+            let proof = sign_proof(&signatory, &keyset, amount).await;
+            signatory.verify_proofs(vec![proof]).await.unwrap();
+        }
+
+        let mut forged = sign_proof(&signatory, &keyset, 1).await;
+        forged.c = SecretKey::generate().public_key();
+        assert!(signatory.verify_proofs(vec![forged]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_blind_signature_matches_software_key() {
+        let (signatory, _) = signatory(Arc::new(SoftwareKeyStore)).await;
+        let keyset = active_keyset(&signatory).await;
+
+        for amount in [1, 2, 4, 8] {
+            let k = SoftwareKeyStore::secret_key(&key_label(&CurrencyUnit::Sat, 0, amount));
+            assert_eq!(
+                keyset.keys.amount_key(Amount::from(amount)),
+                Some(k.public_key())
+            );
+
+            let (blinded_secret, _) = blind_message(Secret::generate().as_bytes(), None).unwrap();
+            let signature = signatory
+                .blind_sign(vec![BlindedMessage::new(
+                    Amount::from(amount),
+                    keyset.id,
+                    blinded_secret,
+                )])
+                .await
+                .unwrap()
+                .remove(0);
+
+            // The point recovered from the two ECDH results is the signature of the key itself
+            assert_eq!(signature.c, sign_message(&k, &blinded_secret).unwrap());
+            assert!(signature.dleq.is_none());
+        }
+
+        let proof = sign_proof(&signatory, &keyset, 4).await;
+        signatory.verify_proofs(vec![proof]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_old_keysets_verifiable() {
+        let key_store = Arc::new(MemoryKeyStore::new());
+        let (signatory, localstore) = signatory(key_store.clone()).await;
+        let old_keyset = active_keyset(&signatory).await;
+        let proof = sign_proof(&signatory, &old_keyset, 2).await;
+
+        let new_keyset = signatory
+            .rotate_keyset(RotateKeyArguments {
+                unit: CurrencyUnit::Sat,
+                amounts: vec![1, 2],
+                input_fee_ppk: 100,
+            })
+            .await
+            .unwrap();
+        assert_ne!(new_keyset.id, old_keyset.id);
+        assert_eq!(active_keyset(&signatory).await.id, new_keyset.id);
+
+        // A restart with the fee and max order of the new keyset keeps it active
+        let restarted = HsmSignatory::new(
+            localstore,
+            key_store,
+            HashMap::from([(CurrencyUnit::Sat, (100, 2))]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(active_keyset(&restarted).await.id, new_keyset.id);
+        restarted.verify_proofs(vec![proof]).await.unwrap();
+
+        let err = restarted
+            .blind_sign(vec![BlindedMessage::new(
+                Amount::from(2),
+                old_keyset.id,
+                SecretKey::generate().public_key(),
+            )])
+            .await;
+        assert!(matches!(err, Err(Error::InactiveKeyset)));
+    }
+}
@@ -0,0 +1,268 @@
+//! PKCS#11 key store
+//!
+//! Generates the keys on a PKCS#11 token as sensitive, non extractable secp256k1 keys, so they
+//! never leave the token, and signs through `CKM_ECDH1_DERIVE`. The test against a token is
+//! ignored unless one is configured (e.g. SoftHSM), the signing math on top of the ECDH is tested
+//! with a software key in [`super`].
+//!
+//! The cryptoki calls block on the token, so they run on the blocking thread pool.
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use cdk_common::{Error, PublicKey};
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::mechanism::elliptic_curve::{EcKdf, Ecdh1DeriveParams};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+
+use super::KeyStore;
+
+/// DER encoded OID of the secp256k1 curve
+const SECP256K1_OID: [u8; 7] = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+/// Key store backed by a PKCS#11 token
+pub struct Pkcs11KeyStore {
+    session: Arc<Mutex<Session>>,
+}
+
+impl Pkcs11KeyStore {
+    /// Opens a session on the token with the label and logs in as user
+    ///
+    /// # Arguments
+    /// * `module` - Path to the PKCS#11 module of the token (e.g. `libsofthsm2.so`)
+    /// * `token_label` - Label of the token holding the keys
+    /// * `pin` - User PIN of the token
+    pub fn new<P: AsRef<Path>>(module: P, token_label: &str, pin: &str) -> Result<Self, Error> {
+        let pkcs11 = Pkcs11::new(module).map_err(pkcs11_error)?;
+        pkcs11
+            .initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK))
+            .map_err(pkcs11_error)?;
+
+        let slot = pkcs11
+            .get_slots_with_token()
+            .map_err(pkcs11_error)?
+            .into_iter()
+            .find(|slot| {
+                pkcs11
+                    .get_token_info(*slot)
+                    .is_ok_and(|info| info.label().trim() == token_label)
+            })
+            .ok_or_else(|| Error::Custom(format!("PKCS#11 token {token_label} not found")))?;
+
+        let session = pkcs11.open_rw_session(slot).map_err(pkcs11_error)?;
+        session
+            .login(UserType::User, Some(&AuthPin::from(pin.to_string())))
+            .map_err(pkcs11_error)?;
+
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+        })
+    }
+
+    /// Runs the blocking calls on the session on the blocking thread pool
+    async fn with_session<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Session) -> Result<T, Error> + Send + 'static,
+    {
+        let session = self.session.clone();
+        tokio::task::spawn_blocking(move || {
+            let session = session.lock().map_err(|_| Error::Internal)?;
+            f(&session)
+        })
+        .await
+        .map_err(|err| Error::Custom(format!("PKCS#11 task failed: {err}")))?
+    }
+}
+
+/// Finds the key of the class stored under the label
+fn find_key(
+    session: &Session,
+    class: ObjectClass,
+    label: &str,
+) -> Result<Option<ObjectHandle>, Error> {
+    Ok(session
+        .find_objects(&[
+            Attribute::Class(class),
+            Attribute::Label(label.as_bytes().to_vec()),
+        ])
+        .map_err(pkcs11_error)?
+        .into_iter()
+        .next())
+}
+
+/// Reads the point of a public key object
+fn read_public_key(session: &Session, handle: ObjectHandle) -> Result<PublicKey, Error> {
+    let attributes = session
+        .get_attributes(handle, &[AttributeType::EcPoint])
+        .map_err(pkcs11_error)?;
+
+    let Some(Attribute::EcPoint(point)) = attributes.into_iter().next() else {
+        return Err(Error::Custom(
+            "PKCS#11 public key without point".to_string(),
+        ));
+    };
+
+    parse_ec_point(&point)
+}
+
+/// Parses a `CKA_EC_POINT`
+///
+/// Tokens return the uncompressed point either raw or wrapped in a DER octet string.
+fn parse_ec_point(point: &[u8]) -> Result<PublicKey, Error> {
+    let point = match point {
+        [0x04, 0x41, rest @ ..] if rest.len() == 65 => rest,
+        point => point,
+    };
+
+    PublicKey::from_slice(point).map_err(|_| Error::Custom("Invalid PKCS#11 EC point".to_string()))
+}
+
+fn pkcs11_error(err: cryptoki::error::Error) -> Error {
+    Error::Custom(format!("PKCS#11: {err}"))
+}
+
+#[async_trait::async_trait]
+impl KeyStore for Pkcs11KeyStore {
+    async fn generate_key(&self, label: &str) -> Result<PublicKey, Error> {
+        let label = label.to_string();
+        self.with_session(move |session| generate_key(session, &label))
+            .await
+    }
+
+    async fn public_key(&self, label: &str) -> Result<Option<PublicKey>, Error> {
+        let label = label.to_string();
+        self.with_session(move |session| {
+            find_key(session, ObjectClass::PUBLIC_KEY, &label)?
+                .map(|handle| read_public_key(session, handle))
+                .transpose()
+        })
+        .await
+    }
+
+    async fn ecdh(&self, label: &str, point: &PublicKey) -> Result<[u8; 32], Error> {
+        let label = label.to_string();
+        let point = *point;
+        self.with_session(move |session| ecdh(session, &label, &point))
+            .await
+    }
+}
+
+/// Generates a secp256k1 key pair stored on the token under the label
+fn generate_key(session: &Session, label: &str) -> Result<PublicKey, Error> {
+    if find_key(session, ObjectClass::PRIVATE_KEY, label)?.is_some() {
+        return Err(Error::Custom(format!("Key {label} already exists")));
+    }
+
+    let label = label.as_bytes().to_vec();
+    let public_template = [
+        Attribute::Token(true),
+        Attribute::Private(false),
+        Attribute::EcParams(SECP256K1_OID.to_vec()),
+        Attribute::Label(label.clone()),
+    ];
+    let private_template = [
+        Attribute::Token(true),
+        Attribute::Private(true),
+        Attribute::Sensitive(true),
+        Attribute::Extractable(false),
+        Attribute::Derive(true),
+        Attribute::Label(label),
+    ];
+
+    let (public_key, _) = session
+        .generate_key_pair(
+            &Mechanism::EccKeyPairGen,
+            &public_template,
+            &private_template,
+        )
+        .map_err(pkcs11_error)?;
+
+    read_public_key(session, public_key)
+}
+
+/// Derives the x coordinate of `k * point` with the private key `k` stored under the label
+fn ecdh(session: &Session, label: &str, point: &PublicKey) -> Result<[u8; 32], Error> {
+    let private_key = find_key(session, ObjectClass::PRIVATE_KEY, label)?
+        .ok_or_else(|| Error::Custom(format!("Unknown key {label}")))?;
+
+    let point = point.serialize_uncompressed();
+    let mechanism = Mechanism::Ecdh1Derive(Ecdh1DeriveParams::new(EcKdf::null(), &point));
+    // The shared secret is a session object that is destroyed right after being read
+    let template = [
+        Attribute::Class(ObjectClass::SECRET_KEY),
+        Attribute::KeyType(KeyType::GENERIC_SECRET),
+        Attribute::ValueLen(32.into()),
+        Attribute::Token(false),
+        Attribute::Sensitive(false),
+        Attribute::Extractable(true),
+    ];
+
+    let shared = session
+        .derive_key(&mechanism, private_key, &template)
+        .map_err(pkcs11_error)?;
+    let attributes = session.get_attributes(shared, &[AttributeType::Value]);
+    session.destroy_object(shared).map_err(pkcs11_error)?;
+
+    match attributes.map_err(pkcs11_error)?.into_iter().next() {
+        Some(Attribute::Value(value)) => value
+            .try_into()
+            .map_err(|_| Error::Custom("Invalid PKCS#11 ECDH result".to_string())),
+        _ => Err(Error::Custom("PKCS#11 ECDH without value".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use bitcoin::secp256k1::{Scalar, Secp256k1};
+    use cdk_common::nuts::SecretKey;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_ec_point() {
+        let public_key = SecretKey::generate().public_key();
+        let raw = public_key.serialize_uncompressed();
+        let wrapped = [[0x04, 0x41].as_slice(), raw.as_slice()].concat();
+
+        assert_eq!(parse_ec_point(&raw).unwrap(), public_key);
+        assert_eq!(parse_ec_point(&wrapped).unwrap(), public_key);
+        assert!(parse_ec_point(&raw[1..]).is_err());
+    }
+
+    /// Runs against the token configured with `CDK_TEST_PKCS11_MODULE`, `CDK_TEST_PKCS11_TOKEN`
+    /// and `CDK_TEST_PKCS11_PIN` (e.g. a SoftHSM token)
+    #[tokio::test]
+    #[ignore = "requires a PKCS#11 token configured with CDK_TEST_PKCS11_*"]
+    async fn test_pkcs11_ecdh_matches_public_key() {
+        let module = env::var("CDK_TEST_PKCS11_MODULE").expect("CDK_TEST_PKCS11_MODULE not set");
+        let token = env::var("CDK_TEST_PKCS11_TOKEN").expect("CDK_TEST_PKCS11_TOKEN not set");
+        let pin = env::var("CDK_TEST_PKCS11_PIN").expect("CDK_TEST_PKCS11_PIN not set");
+
+        let key_store = Pkcs11KeyStore::new(module, &token, &pin).unwrap();
+        let label = format!("cdk-test-{}", SecretKey::generate().to_secret_hex());
+        let public_key = key_store.generate_key(&label).await.unwrap();
+        assert_eq!(
+            key_store.public_key(&label).await.unwrap(),
+            Some(public_key)
+        );
+
+        // k * (t * G) and t * (k * G) share the x coordinate
+        let t = SecretKey::generate();
+        let expected = public_key
+            .mul_tweak(&Secp256k1::new(), &Scalar::from(*t))
+            .unwrap()
+            .x_only_public_key()
+            .0
+            .serialize();
+
+        assert_eq!(
+            key_store.ecdh(&label, &t.public_key()).await.unwrap(),
+            expected
+        );
+    }
+}
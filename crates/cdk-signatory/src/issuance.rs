@@ -0,0 +1,617 @@
+//! Issuance limits enforced by the signatory
+//!
+//! [`IssuanceLimiter`] wraps a signatory and refuses blind signatures past a cap on the total
+//! amount signed by a keyset, or past a max amount signed by a keyset over a sliding window. The
+//! amounts signed are recorded in a ledger in the signatory's own key-value store, so a
+//! compromised mint can neither inflate the supply past the limits nor reset them.
+//!
+//! The limits of a unit are counted against a ledger of the unit, which all its keysets share, so
+//! rotating the keyset of the unit does not reset them.
+//!
+//! The limits cap the signing volume rather than the net issuance. Every blind signature counts,
+//! including the outputs of swaps and the change of melts, and redeemed proofs are never
+//! subtracted, so the limits must leave room for the regular traffic of the mint. Counting
+//! redemptions would let a compromised mint lower the counters by asking to verify proofs again.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cdk_common::nuts::{BlindSignature, BlindedMessage, CurrencyUnit, Id, Proof};
+use cdk_common::util::{hex, unix_time};
+use cdk_common::{amount, database, Error};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+use tracing::instrument;
+
+use crate::signatory::{
+    IssuanceLimit, KeysetIdentifier, KeysetStats, RotateKeyArguments, Signatory, SignatoryKeySet,
+    SignatoryKeysets, SignatoryStats,
+};
+
+/// KV store primary namespace of the issuance ledger
+const ISSUANCE_PRIMARY_NAMESPACE: &str = "cdk_signatory";
+/// KV store secondary namespace of the issuance ledger of the keysets
+const ISSUANCE_SECONDARY_NAMESPACE: &str = "issuance";
+/// KV store secondary namespace of the issuance ledger of the units
+const UNIT_ISSUANCE_SECONDARY_NAMESPACE: &str = "unit_issuance";
+/// Number of buckets the window of a rate limit is split into
+const WINDOW_BUCKETS: u64 = 60;
+
+/// Limits of the keysets, by keyset id or by unit
+#[derive(Debug, Clone, Default)]
+pub struct IssuanceLimits {
+    keysets: HashMap<Id, IssuanceLimit>,
+    units: HashMap<CurrencyUnit, IssuanceLimit>,
+}
+
+impl IssuanceLimits {
+    /// Creates issuance limits without any limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the keyset, or every keyset of the unit
+    ///
+    /// The limit of a keyset takes precedence over the limit of its unit.
+    pub fn add(&mut self, keyset: KeysetIdentifier, limit: IssuanceLimit) {
+        match keyset {
+            KeysetIdentifier::Id(id) => {
+                self.keysets.insert(id, limit);
+            }
+            KeysetIdentifier::Unit(unit) => {
+                self.units.insert(unit, limit);
+            }
+        }
+    }
+
+    fn get(&self, id: &Id, unit: &CurrencyUnit) -> Option<IssuanceLimit> {
+        self.keysets
+            .get(id)
+            .or_else(|| self.units.get(unit))
+            .copied()
+    }
+}
+
+/// Ledger of the amounts signed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum LedgerKey {
+    /// Amounts signed by the keyset
+    Keyset(Id),
+    /// Amounts signed by every keyset of the unit
+    Unit(CurrencyUnit),
+}
+
+impl LedgerKey {
+    /// Secondary namespace and key of the ledger in the KV store
+    fn kv_key(&self) -> (&'static str, String) {
+        match self {
+            Self::Keyset(id) => (ISSUANCE_SECONDARY_NAMESPACE, id.to_string()),
+            // Custom units may hold characters the KV store does not allow in keys
+            Self::Unit(unit) => (
+                UNIT_ISSUANCE_SECONDARY_NAMESPACE,
+                hex::encode(unit.to_string()),
+            ),
+        }
+    }
+}
+
+/// Amounts signed by a keyset or a unit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ledger {
+    /// Total amount signed, stored as `issued` by earlier versions
+    #[serde(alias = "issued")]
+    signed: u64,
+    signatures: u64,
+    /// Start time and amount signed of the recent buckets
+    buckets: Vec<(u64, u64)>,
+}
+
+impl Ledger {
+    /// Amount signed over the window ending now
+    fn window_signed(&self, now: u64, window_secs: u64) -> u64 {
+        let bucket_secs = (window_secs / WINDOW_BUCKETS).max(1);
+
+        self.buckets
+            .iter()
+            .filter(|(start, _)| start + bucket_secs > now.saturating_sub(window_secs))
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// Whether signing the amount stays within the limit
+    fn allows(&self, limit: &IssuanceLimit, amount: u64, now: u64) -> bool {
+        let within_cap = limit
+            .max_signed
+            .is_none_or(|max| self.signed.saturating_add(amount) <= max);
+        let within_rate = limit.max_per_window.is_none_or(|max| {
+            self.window_signed(now, limit.window_secs)
+                .saturating_add(amount)
+                <= max
+        });
+
+        within_cap && within_rate
+    }
+
+    /// Records the signatures, keeping the buckets of the window of the limit
+    fn record(&mut self, amount: u64, signatures: u64, now: u64, limit: Option<&IssuanceLimit>) {
+        self.signed = self.signed.saturating_add(amount);
+        self.signatures = self.signatures.saturating_add(signatures);
+
+        let Some(window_secs) = limit
+            .filter(|limit| limit.max_per_window.is_some())
+            .map(|limit| limit.window_secs)
+        else {
+            self.buckets.clear();
+            return;
+        };
+
+        let bucket_secs = (window_secs / WINDOW_BUCKETS).max(1);
+        let start = now - now % bucket_secs;
+
+        self.buckets
+            .retain(|(bucket, _)| bucket + bucket_secs > now.saturating_sub(window_secs));
+        match self.buckets.last_mut() {
+            Some((bucket, bucket_amount)) if *bucket == start => {
+                *bucket_amount = bucket_amount.saturating_add(amount);
+            }
+            _ => self.buckets.push((start, amount)),
+        }
+    }
+}
+
+/// Signatory enforcing issuance limits on the signatory it wraps
+pub struct IssuanceLimiter {
+    inner: Arc<dyn Signatory + Send + Sync>,
+    kv_store: Arc<dyn database::MintKVStore<Err = database::Error> + Send + Sync>,
+    limits: IssuanceLimits,
+    units: RwLock<HashMap<Id, CurrencyUnit>>,
+    /// Serializes the check and update of the ledger
+    ledger_lock: Mutex<()>,
+}
+
+impl IssuanceLimiter {
+    /// Creates a new IssuanceLimiter instance
+    pub async fn new(
+        inner: Arc<dyn Signatory + Send + Sync>,
+        kv_store: Arc<dyn database::MintKVStore<Err = database::Error> + Send + Sync>,
+        limits: IssuanceLimits,
+    ) -> Result<Self, Error> {
+        let limiter = Self {
+            inner,
+            kv_store,
+            limits,
+            units: Default::default(),
+            ledger_lock: Mutex::new(()),
+        };
+
+        limiter.reload_units().await?;
+
+        Ok(limiter)
+    }
+
+    async fn reload_units(&self) -> Result<(), Error> {
+        let keysets = self.inner.keysets().await?;
+        *self.units.write().await = keysets
+            .keysets
+            .into_iter()
+            .map(|keyset| (keyset.id, keyset.unit))
+            .collect();
+
+        Ok(())
+    }
+
+    /// Unit of the keyset, reloading the keysets of the signatory if it is unknown
+    async fn unit(&self, id: &Id) -> Result<CurrencyUnit, Error> {
+        if let Some(unit) = self.units.read().await.get(id) {
+            return Ok(unit.clone());
+        }
+
+        self.reload_units().await?;
+        self.units
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or(Error::UnknownKeySet)
+    }
+
+    async fn ledger(&self, key: &LedgerKey) -> Result<Ledger, Error> {
+        let (secondary_namespace, kv_key) = key.kv_key();
+        let ledger = self
+            .kv_store
+            .kv_read(ISSUANCE_PRIMARY_NAMESPACE, secondary_namespace, &kv_key)
+            .await?;
+
+        match (ledger, key) {
+            (Some(bytes), _) => Ok(serde_json::from_slice(&bytes)?),
+            // Ledgers kept before the units had their own start from the keysets of the unit
+            (None, LedgerKey::Unit(unit)) => {
+                let ids = self
+                    .units
+                    .read()
+                    .await
+                    .iter()
+                    .filter(|(_, keyset_unit)| *keyset_unit == unit)
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>();
+
+                let mut ledger = Ledger::default();
+                for id in ids {
+                    let keyset = Box::pin(self.ledger(&LedgerKey::Keyset(id))).await?;
+                    ledger.signed = ledger.signed.saturating_add(keyset.signed);
+                    ledger.signatures = ledger.signatures.saturating_add(keyset.signatures);
+                }
+                Ok(ledger)
+            }
+            (None, LedgerKey::Keyset(_)) => Ok(Ledger::default()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Signatory for IssuanceLimiter {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    #[instrument(skip_all)]
+    async fn blind_sign(
+        &self,
+        blinded_messages: Vec<BlindedMessage>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        let mut requested: HashMap<LedgerKey, (u64, u64)> = HashMap::new();
+        for blinded_message in &blinded_messages {
+            let unit = self.unit(&blinded_message.keyset_id).await?;
+            for key in [
+                LedgerKey::Keyset(blinded_message.keyset_id),
+                LedgerKey::Unit(unit),
+            ] {
+                let (amount, signatures) = requested.entry(key).or_default();
+                *amount = amount
+                    .checked_add(blinded_message.amount.into())
+                    .ok_or(amount::Error::AmountOverflow)?;
+                *signatures += 1;
+            }
+        }
+
+        let _guard = self.ledger_lock.lock().await;
+        let now = unix_time();
+
+        let mut ledgers = Vec::with_capacity(requested.len());
+        for (key, (amount, signatures)) in requested {
+            // A keyset is only held to its own limit, the limit of the unit is checked against
+            // the ledger of the unit
+            let limit = match &key {
+                LedgerKey::Keyset(id) => self.limits.keysets.get(id),
+                LedgerKey::Unit(unit) => self.limits.units.get(unit),
+            }
+            .copied();
+            let ledger = self.ledger(&key).await?;
+
+            if let Some(limit) = &limit {
+                if !ledger.allows(limit, amount, now) {
+                    tracing::warn!(
+                        "Refusing to sign {} with {:?} past its issuance limit",
+                        amount,
+                        key
+                    );
+                    return Err(Error::IssuanceLimitReached);
+                }
+            }
+
+            ledgers.push((key, ledger, limit, amount, signatures));
+        }
+
+        let blind_signatures = self.inner.blind_sign(blinded_messages).await?;
+
+        // The signatures are only released once recorded
+        let mut tx = self.kv_store.begin_transaction().await?;
+        for (key, mut ledger, limit, amount, signatures) in ledgers {
+            ledger.record(amount, signatures, now, limit.as_ref());
+            let (secondary_namespace, kv_key) = key.kv_key();
+            tx.kv_write(
+                ISSUANCE_PRIMARY_NAMESPACE,
+                secondary_namespace,
+                &kv_key,
+                &serde_json::to_vec(&ledger)?,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(blind_signatures)
+    }
+
+    async fn verify_proofs(&self, proofs: Vec<Proof>) -> Result<(), Error> {
+        self.inner.verify_proofs(proofs).await
+    }
+
+    async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+        self.inner.keysets().await
+    }
+
+    async fn rotate_keyset(&self, args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+        let keyset = self.inner.rotate_keyset(args).await?;
+        self.units
+            .write()
+            .await
+            .insert(keyset.id, keyset.unit.clone());
+
+        Ok(keyset)
+    }
+
+    #[instrument(skip_all)]
+    async fn stats(&self) -> Result<SignatoryStats, Error> {
+        self.reload_units().await?;
+        let units = self.units.read().await.clone();
+        let now = unix_time();
+
+        let mut keysets = Vec::with_capacity(units.len());
+        for (id, unit) in units {
+            let ledger = self.ledger(&LedgerKey::Keyset(id)).await?;
+            let limit = self.limits.get(&id, &unit);
+
+            // The window of a limit of the unit is counted by the ledger of the unit
+            let window_signed = match limit {
+                Some(limit) if self.limits.keysets.contains_key(&id) => {
+                    ledger.window_signed(now, limit.window_secs)
+                }
+                Some(limit) => self
+                    .ledger(&LedgerKey::Unit(unit.clone()))
+                    .await?
+                    .window_signed(now, limit.window_secs),
+                None => 0,
+            };
+
+            keysets.push(KeysetStats {
+                keyset_id: id,
+                window_signed,
+                unit,
+                signed: ledger.signed,
+                signatures: ledger.signatures,
+                limit,
+            });
+        }
+
+        Ok(SignatoryStats { keysets })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod test {
+    use cdk_common::nuts::SecretKey;
+    use cdk_common::Amount;
+
+    use super::*;
+    use crate::db_signatory::DbSignatory;
+
+    async fn limiter(limits: IssuanceLimits) -> (IssuanceLimiter, Id) {
+        let db = Arc::new(cdk_sqlite::mint::memory::empty().await.expect("memory db"));
+        let signatory = DbSignatory::new(
+            db.clone(),
+            &[0u8; 32],
+            HashMap::from([(CurrencyUnit::Sat, (0, 8))]),
+            Default::default(),
+        )
+        .await
+        .expect("signatory");
+
+        let limiter = IssuanceLimiter::new(Arc::new(signatory), db, limits)
+            .await
+            .expect("limiter");
+        let id = limiter
+            .keysets()
+            .await
+            .unwrap()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.active && keyset.unit == CurrencyUnit::Sat)
+            .expect("active sat keyset")
+            .id;
+
+        (limiter, id)
+    }
+
+    fn messages(id: Id, amounts: &[u64]) -> Vec<BlindedMessage> {
+        amounts
+            .iter()
+            .map(|amount| {
+                BlindedMessage::new(
+                    Amount::from(*amount),
+                    id,
+                    SecretKey::generate().public_key(),
+                )
+            })
+            .collect()
+    }
+
+    async fn keyset_stats(limiter: &IssuanceLimiter, id: Id) -> KeysetStats {
+        limiter
+            .stats()
+            .await
+            .unwrap()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.keyset_id == id)
+            .expect("keyset stats")
+    }
+
+    #[tokio::test]
+    async fn test_issuance_cap() {
+        let mut limits = IssuanceLimits::new();
+        limits.add(
+            CurrencyUnit::Sat.into(),
+            IssuanceLimit {
+                max_signed: Some(100),
+                ..Default::default()
+            },
+        );
+        let (limiter, id) = limiter(limits).await;
+
+        limiter.blind_sign(messages(id, &[64, 32])).await.unwrap();
+        // Nothing is signed when the batch goes past the cap
+        let err = limiter.blind_sign(messages(id, &[4, 1])).await;
+        assert!(matches!(err, Err(Error::IssuanceLimitReached)));
+        limiter.blind_sign(messages(id, &[4])).await.unwrap();
+
+        let stats = keyset_stats(&limiter, id).await;
+        assert_eq!(stats.signed, 100);
+        assert_eq!(stats.signatures, 3);
+        assert_eq!(stats.limit.and_then(|limit| limit.max_signed), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_issuance_rate_limit() {
+        let mut limits = IssuanceLimits::new();
+        limits.add(
+            CurrencyUnit::Sat.into(),
+            IssuanceLimit {
+                max_per_window: Some(10),
+                window_secs: 3600,
+                ..Default::default()
+            },
+        );
+        let (limiter, id) = limiter(limits).await;
+
+        limiter.blind_sign(messages(id, &[8, 2])).await.unwrap();
+        let err = limiter.blind_sign(messages(id, &[1])).await;
+        assert!(matches!(err, Err(Error::IssuanceLimitReached)));
+
+        let stats = keyset_stats(&limiter, id).await;
+        assert_eq!(stats.window_signed, 10);
+        assert_eq!(stats.signed, 10);
+    }
+
+    #[tokio::test]
+    async fn test_issuance_unit_limit_survives_rotation() {
+        let mut limits = IssuanceLimits::new();
+        limits.add(
+            CurrencyUnit::Sat.into(),
+            IssuanceLimit {
+                max_signed: Some(100),
+                max_per_window: Some(80),
+                window_secs: 3600,
+            },
+        );
+        let (limiter, old_id) = limiter(limits).await;
+
+        limiter.blind_sign(messages(old_id, &[64])).await.unwrap();
+
+        let new_id = limiter
+            .rotate_keyset(RotateKeyArguments {
+                unit: CurrencyUnit::Sat,
+                amounts: (0..8).map(|n| 2u64.pow(n)).collect(),
+                input_fee_ppk: 0,
+            })
+            .await
+            .unwrap()
+            .id;
+        assert_ne!(old_id, new_id);
+
+        // The new keyset counts against the amounts signed by the old one
+        let err = limiter.blind_sign(messages(new_id, &[32])).await;
+        assert!(matches!(err, Err(Error::IssuanceLimitReached)));
+        limiter.blind_sign(messages(new_id, &[16])).await.unwrap();
+
+        let stats = keyset_stats(&limiter, new_id).await;
+        assert_eq!(stats.signed, 16);
+        assert_eq!(stats.window_signed, 80);
+    }
+
+    #[tokio::test]
+    async fn test_issuance_keyset_limit() {
+        let (limiter, id) = limiter(IssuanceLimits::new()).await;
+        let mut limits = IssuanceLimits::new();
+        limits.add(
+            id.into(),
+            IssuanceLimit {
+                max_signed: Some(10),
+                ..Default::default()
+            },
+        );
+        limits.add(
+            CurrencyUnit::Sat.into(),
+            IssuanceLimit {
+                max_signed: Some(100),
+                ..Default::default()
+            },
+        );
+        let limiter = IssuanceLimiter::new(limiter.inner, limiter.kv_store, limits)
+            .await
+            .unwrap();
+
+        limiter.blind_sign(messages(id, &[8, 2])).await.unwrap();
+        let err = limiter.blind_sign(messages(id, &[1])).await;
+        assert!(matches!(err, Err(Error::IssuanceLimitReached)));
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn test_issuance_limit_over_grpc() {
+        use tokio::net::TcpListener;
+        use tokio_stream::wrappers::TcpListenerStream;
+
+        use crate::{start_grpc_server_with_incoming, SignatoryRpcClient};
+
+        let mut limits = IssuanceLimits::new();
+        limits.add(
+            CurrencyUnit::Sat.into(),
+            IssuanceLimit {
+                max_signed: Some(8),
+                ..Default::default()
+            },
+        );
+        let (limiter, id) = limiter(limits).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(start_grpc_server_with_incoming(
+            Arc::new(limiter),
+            TcpListenerStream::new(listener),
+        ));
+        let client = SignatoryRpcClient::new(format!("http://{addr}"), None::<String>)
+            .await
+            .unwrap();
+
+        client.blind_sign(messages(id, &[8])).await.unwrap();
+        let err = client.blind_sign(messages(id, &[1])).await;
+        assert!(matches!(err, Err(Error::IssuanceLimitReached)));
+
+        let stats = client
+            .stats()
+            .await
+            .unwrap()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.keyset_id == id)
+            .expect("keyset stats");
+        assert_eq!(stats.unit, CurrencyUnit::Sat);
+        assert_eq!(stats.signed, 8);
+        assert_eq!(stats.limit.and_then(|limit| limit.max_signed), Some(8));
+    }
+
+    #[test]
+    fn test_window_drops_old_buckets() {
+        let limit = IssuanceLimit {
+            max_per_window: Some(10),
+            window_secs: 600,
+            ..Default::default()
+        };
+        let mut ledger = Ledger::default();
+
+        ledger.record(10, 1, 1_000, Some(&limit));
+        assert!(!ledger.allows(&limit, 1, 1_500));
+        assert!(ledger.allows(&limit, 10, 1_610));
+
+        ledger.record(10, 1, 1_610, Some(&limit));
+        assert_eq!(ledger.buckets.len(), 1);
+        assert_eq!(ledger.signed, 20);
+    }
+
+    #[test]
+    fn test_ledger_reads_issued() {
+        let ledger: Ledger =
+            serde_json::from_str(r#"{"issued":64,"signatures":2,"buckets":[]}"#).unwrap();
+        assert_eq!(ledger.signed, 64);
+        assert_eq!(ledger.signatures, 2);
+    }
+}
@@ -21,4 +21,7 @@ mod common;
 
 pub mod db_signatory;
 pub mod embedded;
+pub mod hsm;
+pub mod issuance;
 pub mod signatory;
+pub mod threshold;
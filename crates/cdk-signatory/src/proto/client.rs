@@ -5,7 +5,9 @@ use cdk_common::{BlindSignature, BlindedMessage, Proof};
 use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
 
 use crate::proto::signatory_client::SignatoryClient;
-use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
+use crate::signatory::{
+    RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets, SignatoryStats,
+};
 
 /// A client for the Signatory service.
 pub struct SignatoryRpcClient {
@@ -160,4 +162,14 @@ impl Signatory for SignatoryRpcClient {
             .map(|response| handle_error!(response, keyset).try_into())
             .map_err(|e| Error::Custom(e.to_string()))?
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn stats(&self) -> Result<SignatoryStats, Error> {
+        self.client
+            .clone()
+            .stats(super::EmptyRequest {})
+            .await
+            .map(|response| handle_error!(response, stats).try_into())
+            .map_err(|e| Error::Custom(e.to_string()))?
+    }
 }
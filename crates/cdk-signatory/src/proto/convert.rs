@@ -19,6 +19,7 @@ impl From<crate::signatory::SignatoryKeysets> for SignatoryKeysets {
                 .into_iter()
                 .map(|keyset| keyset.into())
                 .collect(),
+            share_index: keyset.share_index.map(Into::into),
         }
     }
 }
@@ -35,6 +36,11 @@ impl TryInto<crate::signatory::SignatoryKeysets> for SignatoryKeysets {
                 .into_iter()
                 .map(|keyset| keyset.try_into())
                 .collect::<Result<Vec<_>, _>>()?,
+            share_index: self
+                .share_index
+                .map(u8::try_from)
+                .transpose()
+                .map_err(|_| cdk_common::Error::Custom(INTERNAL_ERROR.to_owned()))?,
         })
     }
 }
@@ -87,6 +93,62 @@ impl From<crate::signatory::SignatoryKeySet> for KeySet {
     }
 }
 
+impl From<crate::signatory::SignatoryStats> for SignatoryStats {
+    fn from(stats: crate::signatory::SignatoryStats) -> Self {
+        Self {
+            keysets: stats
+                .keysets
+                .into_iter()
+                .map(|keyset| KeysetStats {
+                    keyset_id: keyset.keyset_id.to_bytes(),
+                    unit: Some(keyset.unit.into()),
+                    signed: keyset.signed,
+                    signatures: keyset.signatures,
+                    window_signed: keyset.window_signed,
+                    limit: keyset.limit.map(|limit| IssuanceLimit {
+                        max_signed: limit.max_signed,
+                        max_per_window: limit.max_per_window,
+                        window_secs: limit.window_secs,
+                    }),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryInto<crate::signatory::SignatoryStats> for SignatoryStats {
+    type Error = cdk_common::Error;
+
+    fn try_into(self) -> Result<crate::signatory::SignatoryStats, Self::Error> {
+        Ok(crate::signatory::SignatoryStats {
+            keysets: self
+                .keysets
+                .into_iter()
+                .map(|keyset| {
+                    Ok(crate::signatory::KeysetStats {
+                        keyset_id: Id::from_bytes(&keyset.keyset_id)?,
+                        unit: keyset
+                            .unit
+                            .ok_or(cdk_common::Error::Custom(INTERNAL_ERROR.to_owned()))?
+                            .try_into()
+                            .map_err(|_| {
+                                cdk_common::Error::Custom("Invalid currency unit".to_owned())
+                            })?,
+                        signed: keyset.signed,
+                        signatures: keyset.signatures,
+                        window_signed: keyset.window_signed,
+                        limit: keyset.limit.map(|limit| crate::signatory::IssuanceLimit {
+                            max_signed: limit.max_signed,
+                            max_per_window: limit.max_per_window,
+                            window_secs: limit.window_secs,
+                        }),
+                    })
+                })
+                .collect::<Result<Vec<_>, cdk_common::Error>>()?,
+        })
+    }
+}
+
 impl From<cdk_common::Error> for Error {
     fn from(err: cdk_common::Error) -> Self {
         let code = match err {
@@ -95,6 +157,7 @@ impl From<cdk_common::Error> for Error {
             cdk_common::Error::DuplicateOutputs => ErrorCode::DuplicateInputsProvided,
             cdk_common::Error::UnknownKeySet => ErrorCode::KeysetNotKnown,
             cdk_common::Error::InactiveKeyset => ErrorCode::KeysetInactive,
+            cdk_common::Error::IssuanceLimitReached => ErrorCode::IssuanceLimitReached,
             _ => ErrorCode::Unspecified,
         };
 
@@ -114,6 +177,7 @@ impl From<Error> for cdk_common::Error {
             ErrorCode::DuplicateInputsProvided => cdk_common::Error::DuplicateInputs,
             ErrorCode::KeysetNotKnown => cdk_common::Error::UnknownKeySet,
             ErrorCode::KeysetInactive => cdk_common::Error::InactiveKeyset,
+            ErrorCode::IssuanceLimitReached => cdk_common::Error::IssuanceLimitReached,
             ErrorCode::Unspecified => cdk_common::Error::Custom(val.detail),
             _ => todo!(),
         }
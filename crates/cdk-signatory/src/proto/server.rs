@@ -163,6 +163,26 @@ where
 
         Ok(Response::new(mint_keyset_info))
     }
+
+    async fn stats(
+        &self,
+        request: Request<proto::EmptyRequest>,
+    ) -> Result<Response<proto::StatsResponse>, Status> {
+        let metadata = request.metadata();
+        let signatory = self.load_signatory(metadata).await?;
+        let result = match signatory.stats().await {
+            Ok(result) => proto::StatsResponse {
+                stats: Some(result.into()),
+                ..Default::default()
+            },
+            Err(err) => proto::StatsResponse {
+                error: Some(err.into()),
+                ..Default::default()
+            },
+        };
+
+        Ok(Response::new(result))
+    }
 }
 
 /// Trait for loading a signatory instance from gRPC metadata
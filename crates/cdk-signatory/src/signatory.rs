@@ -55,6 +55,8 @@ pub struct SignatoryKeysets {
     pub pubkey: PublicKey,
     /// The list of keysets
     pub keysets: Vec<SignatoryKeySet>,
+    /// Index of the share served by a threshold signatory node, none for full keys
+    pub share_index: Option<u8>,
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +135,46 @@ impl From<&(MintKeySetInfo, MintKeySet)> for SignatoryKeySet {
     }
 }
 
+/// Limits of the amount a keyset can sign
+///
+/// The limits cap the signing volume, not the net issuance: the signatory cannot tell the
+/// outputs of a mint from the outputs of a swap or the change of a melt, so every blind
+/// signature counts and redeemed proofs are never subtracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IssuanceLimit {
+    /// Max total amount signed by the keyset
+    pub max_signed: Option<u64>,
+    /// Max amount signed by the keyset over the window
+    pub max_per_window: Option<u64>,
+    /// Length of the window in seconds
+    pub window_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+/// Issuance counters of a keyset
+pub struct KeysetStats {
+    /// The keyset Id
+    pub keyset_id: Id,
+    /// The Currency Unit
+    pub unit: CurrencyUnit,
+    /// Total amount signed
+    pub signed: u64,
+    /// Number of blind signatures
+    pub signatures: u64,
+    /// Amount signed over the window of the limit, by every keyset of the unit for a limit of
+    /// the unit
+    pub window_signed: u64,
+    /// Limit enforced on the keyset
+    pub limit: Option<IssuanceLimit>,
+}
+
+#[derive(Debug, Clone, Default)]
+/// Signatory stats
+pub struct SignatoryStats {
+    /// Issuance counters of each keyset
+    pub keysets: Vec<KeysetStats>,
+}
+
 #[async_trait::async_trait]
 /// Signatory trait
 pub trait Signatory {
@@ -157,4 +199,11 @@ pub trait Signatory {
     /// Add current keyset to inactive keysets
     /// Generate new keyset
     async fn rotate_keyset(&self, args: RotateKeyArguments) -> Result<SignatoryKeySet, Error>;
+
+    /// Issuance counters of the keysets
+    ///
+    /// Signatories without an issuance ledger have no counters.
+    async fn stats(&self) -> Result<SignatoryStats, Error> {
+        Ok(SignatoryStats::default())
+    }
 }
@@ -0,0 +1,803 @@
+//! Threshold signatory
+//!
+//! Splits every amount key with Shamir shares over several signatory nodes, usually
+//! [`ShareSignatory`] instances behind their own gRPC servers, so no single host holds a full key.
+//! A blind signature is `k * B_`, and since it is linear in `k`, the partial signatures
+//! `k_i * B_` of any `threshold` nodes combine into it with Lagrange coefficients.
+//!
+//! Every partial signature comes with a DLEQ proof against the public key of the share, so a node
+//! returning a wrong partial signature is ignored as long as `threshold` other nodes answer.
+//!
+//! Proofs are verified the same way, by combining the partial signatures of `Y`. As the full keys
+//! are never available, the blind signatures are returned without a DLEQ proof.
+//!
+//! The keysets cannot be rotated through the signatory, since that would need a dealer holding the
+//! full keys. New keysets are dealt to the nodes offline with `signatory deal --keysets-only`, and
+//! mintd refuses to start a threshold signatory with a management RPC that can rotate keysets.
+//!
+//! Every node is given [`ThresholdSignatory::with_timeout`] to answer, and signing returns as soon
+//! as `threshold` valid partial signatures arrived, so slow nodes do not hold it up.
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk_common::dhke::hash_to_curve;
+use cdk_common::nuts::{BlindSignature, BlindedMessage, Id, Keys, Proof};
+use cdk_common::{Amount, Error, PublicKey};
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
+
+mod shamir;
+mod share;
+
+pub use share::{
+    deal_keyset, deal_keyset_shares, deal_nodes, DealtKeysets, KeysetShare, NodeShares,
+    ShareSignatory,
+};
+
+/// Time a node has to answer before it is considered unavailable
+pub const DEFAULT_NODE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Answer of a node to a request
+type NodeAnswer<'a, T> = BoxFuture<'a, (u8, Result<T, Error>)>;
+
+/// Signatory node holding a share of every key
+#[derive(Clone)]
+pub struct ThresholdNode {
+    /// Index of the node, the x coordinate of its shares
+    pub index: u8,
+    /// Signatory of the node
+    pub signatory: Arc<dyn Signatory + Send + Sync>,
+}
+
+/// Keyset combined from the shares of the nodes
+#[derive(Debug, Clone)]
+struct ThresholdKeySet {
+    keyset: SignatoryKeySet,
+    /// Public keys of the shares of each node consistent with the keyset
+    shares: HashMap<u8, Keys>,
+}
+
+/// Signatory combining the partial signatures of its nodes
+pub struct ThresholdSignatory {
+    threshold: usize,
+    nodes: Vec<ThresholdNode>,
+    timeout: Duration,
+    keysets: RwLock<HashMap<Id, ThresholdKeySet>>,
+    pubkey: RwLock<Option<PublicKey>>,
+}
+
+impl ThresholdSignatory {
+    /// Creates a new ThresholdSignatory instance
+    ///
+    /// At least `threshold` nodes must be reachable to load the keysets.
+    pub async fn new(threshold: u8, nodes: Vec<ThresholdNode>) -> Result<Self, Error> {
+        Self::with_timeout(threshold, nodes, DEFAULT_NODE_TIMEOUT).await
+    }
+
+    /// Creates a new ThresholdSignatory instance giving each node `timeout` to answer
+    pub async fn with_timeout(
+        threshold: u8,
+        nodes: Vec<ThresholdNode>,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let mut indices = nodes.iter().map(|node| node.index).collect::<Vec<_>>();
+        indices.sort();
+        indices.dedup();
+
+        if threshold == 0 || indices.contains(&0) || indices.len() != nodes.len() {
+            return Err(Error::Custom(
+                "Signatory nodes must have unique non zero indices".to_string(),
+            ));
+        }
+        if nodes.len() < threshold as usize {
+            return Err(Error::Custom(format!(
+                "A threshold of {} needs at least as many signatory nodes, got {}",
+                threshold,
+                nodes.len()
+            )));
+        }
+
+        let signatory = Self {
+            threshold: threshold as usize,
+            nodes,
+            timeout,
+            keysets: Default::default(),
+            pubkey: Default::default(),
+        };
+
+        signatory.reload_keysets().await?;
+
+        Ok(signatory)
+    }
+
+    /// Asks every node in parallel, yielding the answers as they arrive
+    ///
+    /// A node not answering within the timeout answers with an error.
+    fn ask_nodes<'a, T, F, Fut>(&'a self, ask: F) -> FuturesUnordered<NodeAnswer<'a, T>>
+    where
+        T: Send + 'a,
+        F: Fn(Arc<dyn Signatory + Send + Sync>) -> Fut,
+        Fut: Future<Output = Result<T, Error>> + Send + 'a,
+    {
+        self.nodes
+            .iter()
+            .map(|node| {
+                let index = node.index;
+                let answer = tokio::time::timeout(self.timeout, ask(node.signatory.clone()));
+                async move {
+                    let answer = answer.await.unwrap_or_else(|_| {
+                        Err(Error::Custom("Signatory node timed out".to_string()))
+                    });
+                    (index, answer)
+                }
+                .boxed()
+            })
+            .collect()
+    }
+
+    /// Reloads the keysets from the nodes, to pick up newly dealt keysets
+    pub async fn reload(&self) -> Result<(), Error> {
+        self.reload_keysets().await
+    }
+
+    /// Loads the keysets of the nodes and combines their public keys
+    ///
+    /// Unlike signing, it waits for every node within the timeout, to cross-check as many shares
+    /// as possible. Shares inconsistent with the ones the keys are combined from are dropped.
+    /// Keysets whose combined keys do not match their id are skipped.
+    async fn reload_keysets(&self) -> Result<(), Error> {
+        let mut answers = Vec::with_capacity(self.nodes.len());
+        let mut asked = self.ask_nodes(|signatory| async move { signatory.keysets().await });
+        while let Some((index, keysets)) = asked.next().await {
+            match keysets {
+                Ok(keysets) if keysets.share_index.is_some_and(|i| i != index) => {
+                    tracing::warn!(
+                        "Signatory node {} serves the shares of node {:?}",
+                        index,
+                        keysets.share_index
+                    );
+                }
+                Ok(keysets) => answers.push((index, keysets)),
+                Err(err) => tracing::warn!("Signatory node {} is unavailable: {}", index, err),
+            }
+        }
+
+        if answers.len() < self.threshold {
+            return Err(Error::Custom(format!(
+                "Only {} of the {} required signatory nodes are available",
+                answers.len(),
+                self.threshold
+            )));
+        }
+
+        let pubkey = self.combine_pubkey(&answers)?;
+        if let Some(known) = *self.pubkey.read().await {
+            if known != pubkey {
+                return Err(Error::Custom(
+                    "Signatory nodes changed the mint public key".to_string(),
+                ));
+            }
+        }
+
+        let mut node_keysets: HashMap<Id, Vec<(u8, SignatoryKeySet)>> = HashMap::new();
+        for (index, keysets) in answers {
+            for keyset in keysets.keysets {
+                node_keysets
+                    .entry(keyset.id)
+                    .or_default()
+                    .push((index, keyset));
+            }
+        }
+
+        let mut loaded = HashMap::new();
+        for (id, shares) in node_keysets {
+            if shares.len() < self.threshold {
+                tracing::warn!(
+                    "Keyset {} is only known by {} signatory nodes",
+                    id,
+                    shares.len()
+                );
+                continue;
+            }
+
+            // A node with a wrong share spoils the combination, so each node starts in turn
+            let combined = (0..shares.len()).find_map(|start| {
+                let mut shares = shares.clone();
+                shares.rotate_left(start);
+                self.combine_keyset(&shares)
+                    .ok()
+                    .filter(|keyset| keyset.keyset.id == id)
+            });
+
+            match combined {
+                Some(keyset) => {
+                    for (index, _) in shares
+                        .iter()
+                        .filter(|(index, _)| !keyset.shares.contains_key(index))
+                    {
+                        tracing::warn!(
+                            "Signatory node {} has an inconsistent share of keyset {}",
+                            index,
+                            id
+                        );
+                    }
+                    loaded.insert(id, keyset);
+                }
+                None => tracing::error!("Shares of keyset {} do not match its id", id),
+            }
+        }
+
+        *self.pubkey.write().await = Some(pubkey);
+        *self.keysets.write().await = loaded;
+
+        Ok(())
+    }
+
+    /// Combines the identity shares of the nodes into the mint public key
+    ///
+    /// The shares past the threshold must agree with the ones the key is combined from, as there
+    /// is no keyset id to tell which nodes are honest.
+    fn combine_pubkey(&self, answers: &[(u8, SignatoryKeysets)]) -> Result<PublicKey, Error> {
+        let identities = answers
+            .iter()
+            .map(|(index, keysets)| (*index, keysets.pubkey))
+            .collect::<Vec<_>>();
+        let (base, others) = identities.split_at(self.threshold);
+
+        for (index, share) in others {
+            if shamir::interpolate(base, *index)? != *share {
+                return Err(Error::Custom(format!(
+                    "Signatory node {index} has an inconsistent share of the mint key"
+                )));
+            }
+        }
+
+        shamir::interpolate(base, 0)
+    }
+
+    /// Combines the share public keys of the nodes into the keyset
+    fn combine_keyset(&self, shares: &[(u8, SignatoryKeySet)]) -> Result<ThresholdKeySet, Error> {
+        let (base, others) = shares.split_at(self.threshold);
+        let reference = &base[0].1;
+
+        let mut keys = BTreeMap::new();
+        let mut consistent = shares
+            .iter()
+            .map(|(index, share)| (*index, share.keys.clone()))
+            .collect::<HashMap<_, _>>();
+
+        for amount in &reference.amounts {
+            let amount = Amount::from(*amount);
+            let points = base
+                .iter()
+                .map(|(index, share)| {
+                    share
+                        .keys
+                        .amount_key(amount)
+                        .map(|key| (*index, key))
+                        .ok_or(Error::UnknownKeySet)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            keys.insert(amount, shamir::interpolate(&points, 0)?);
+
+            for (index, share) in others {
+                let expected = shamir::interpolate(&points, *index)?;
+                if share.keys.amount_key(amount) != Some(expected) {
+                    consistent.remove(index);
+                }
+            }
+        }
+
+        let keys = Keys::new(keys);
+        let active = shares.iter().filter(|(_, share)| share.active).count() >= self.threshold;
+
+        Ok(ThresholdKeySet {
+            keyset: SignatoryKeySet {
+                id: Id::v1_from_keys(&keys),
+                unit: reference.unit.clone(),
+                active,
+                keys,
+                amounts: reference.amounts.clone(),
+                input_fee_ppk: reference.input_fee_ppk,
+                final_expiry: reference.final_expiry,
+            },
+            shares: consistent,
+        })
+    }
+
+    /// Multiplies each blinded secret by the key of its amount through the nodes
+    async fn evaluate(
+        &self,
+        blinded_messages: Vec<BlindedMessage>,
+    ) -> Result<Vec<PublicKey>, Error> {
+        let keysets = self.keysets.read().await;
+
+        let mut answers = self.ask_nodes(|signatory| {
+            let blinded_messages = blinded_messages.clone();
+            async move { signatory.blind_sign(blinded_messages).await }
+        });
+
+        let mut partials: Vec<(u8, Vec<PublicKey>)> = Vec::with_capacity(self.threshold);
+
+        // The nodes still signing are dropped once enough partial signatures are valid
+        while let Some((index, signatures)) = answers.next().await {
+            let signatures = match signatures {
+                Ok(signatures) => signatures,
+                Err(err) => {
+                    tracing::warn!("Signatory node {} could not sign: {}", index, err);
+                    continue;
+                }
+            };
+
+            let valid = signatures.len() == blinded_messages.len()
+                && signatures
+                    .iter()
+                    .zip(&blinded_messages)
+                    .all(|(signature, message)| {
+                        keysets
+                            .get(&message.keyset_id)
+                            .and_then(|keyset| keyset.shares.get(&index))
+                            .and_then(|keys| keys.amount_key(message.amount))
+                            .is_some_and(|share_key| {
+                                signature.keyset_id == message.keyset_id
+                                    && signature.amount == message.amount
+                                    && signature
+                                        .verify_dleq(share_key, message.blinded_secret)
+                                        .is_ok()
+                            })
+                    });
+
+            if !valid {
+                tracing::warn!(
+                    "Signatory node {} returned invalid partial signatures",
+                    index
+                );
+                continue;
+            }
+
+            partials.push((index, signatures.into_iter().map(|sig| sig.c).collect()));
+            if partials.len() == self.threshold {
+                break;
+            }
+        }
+
+        if partials.len() < self.threshold {
+            return Err(Error::Custom(format!(
+                "Only {} of the {} required signatory nodes signed",
+                partials.len(),
+                self.threshold
+            )));
+        }
+
+        (0..blinded_messages.len())
+            .map(|i| {
+                let points = partials
+                    .iter()
+                    .map(|(index, signatures)| (*index, signatures[i]))
+                    .collect::<Vec<_>>();
+                shamir::interpolate(&points, 0)
+            })
+            .collect()
+    }
+
+    /// Whether the keyset is known and active
+    async fn keyset_active(&self, id: &Id, amount: Amount) -> Result<bool, Error> {
+        self.keysets
+            .read()
+            .await
+            .get(id)
+            .filter(|keyset| keyset.keyset.keys.amount_key(amount).is_some())
+            .map(|keyset| keyset.keyset.active)
+            .ok_or(Error::UnknownKeySet)
+    }
+}
+
+#[async_trait::async_trait]
+impl Signatory for ThresholdSignatory {
+    fn name(&self) -> String {
+        format!(
+            "Threshold Signatory {}-of-{} {}",
+            self.threshold,
+            self.nodes.len(),
+            env!("CARGO_PKG_VERSION")
+        )
+    }
+
+    #[instrument(skip_all)]
+    async fn blind_sign(
+        &self,
+        blinded_messages: Vec<BlindedMessage>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        for blinded_message in &blinded_messages {
+            if !self
+                .keyset_active(&blinded_message.keyset_id, blinded_message.amount)
+                .await?
+            {
+                return Err(Error::InactiveKeyset);
+            }
+        }
+
+        let signatures = self.evaluate(blinded_messages.clone()).await?;
+
+        Ok(blinded_messages
+            .into_iter()
+            .zip(signatures)
+            .map(|(blinded_message, c)| BlindSignature {
+                amount: blinded_message.amount,
+                keyset_id: blinded_message.keyset_id,
+                c,
+                dleq: None,
+            })
+            .collect())
+    }
+
+    #[instrument(skip_all)]
+    async fn verify_proofs(&self, proofs: Vec<Proof>) -> Result<(), Error> {
+        let mut blinded_messages = Vec::with_capacity(proofs.len());
+        for proof in &proofs {
+            self.keyset_active(&proof.keyset_id, proof.amount).await?;
+            blinded_messages.push(BlindedMessage::new(
+                proof.amount,
+                proof.keyset_id,
+                hash_to_curve(proof.secret.as_bytes())?,
+            ));
+        }
+
+        let expected = self.evaluate(blinded_messages).await?;
+
+        if proofs.iter().zip(expected).any(|(proof, c)| proof.c != c) {
+            return Err(cdk_common::dhke::Error::TokenNotVerified.into());
+        }
+
+        Ok(())
+    }
+
+    /// Keysets loaded from the nodes, see [`ThresholdSignatory::reload`]
+    #[instrument(skip_all)]
+    async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+        Ok(SignatoryKeysets {
+            pubkey: self.pubkey.read().await.ok_or(Error::Internal)?,
+            keysets: self
+                .keysets
+                .read()
+                .await
+                .values()
+                .map(|keyset| keyset.keyset.clone())
+                .collect(),
+            share_index: None,
+        })
+    }
+
+    async fn rotate_keyset(&self, _args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+        Err(Error::Custom(
+            "Keysets of a threshold signatory are rotated by dealing new shares to the nodes"
+                .to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cdk_common::dhke::{blind_message, unblind_message};
+    use cdk_common::nuts::{CurrencyUnit, SecretKey};
+    use cdk_common::secret::Secret;
+
+    use super::*;
+
+    fn nodes(shares: &[NodeShares]) -> Vec<ThresholdNode> {
+        shares
+            .iter()
+            .map(|shares| ThresholdNode {
+                index: shares.index,
+                signatory: Arc::new(ShareSignatory::new(shares.clone())),
+            })
+            .collect()
+    }
+
+    /// Signs a secret for the amount and returns the unblinded proof
+    async fn sign_proof(signatory: &ThresholdSignatory, keyset: &SignatoryKeySet) -> Proof {
+        let amount = Amount::from(4);
+        let secret = Secret::generate();
+        let (blinded_secret, r) = blind_message(secret.as_bytes(), None).unwrap();
+
+        let signature = signatory
+            .blind_sign(vec![BlindedMessage::new(amount, keyset.id, blinded_secret)])
+            .await
+            .unwrap()
+            .remove(0);
+
+        let mint_pubkey = keyset.keys.amount_key(amount).unwrap();
+        let c = unblind_message(&signature.c, &r, &mint_pubkey).unwrap();
+
+        Proof::new(amount, keyset.id, secret, c)
+    }
+
+    async fn keyset(signatory: &ThresholdSignatory, id: Id) -> SignatoryKeySet {
+        signatory
+            .keysets()
+            .await
+            .unwrap()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.id == id)
+            .expect("dealt keyset")
+    }
+
+    #[tokio::test]
+    async fn test_threshold_sign_with_any_nodes() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        let signatory = ThresholdSignatory::new(2, nodes(&shares)).await.unwrap();
+        let keyset = keyset(&signatory, id).await;
+        assert!(keyset.active);
+        assert_eq!(keyset.amounts, vec![1, 2, 4, 8]);
+
+        let proof = sign_proof(&signatory, &keyset).await;
+        signatory.verify_proofs(vec![proof.clone()]).await.unwrap();
+
+        // Any two nodes sign and verify with the same keys
+        for pair in [[0, 1], [1, 2], [0, 2]] {
+            let pair = pair.map(|i| shares[i].clone());
+            let subset = ThresholdSignatory::new(2, nodes(&pair)).await.unwrap();
+            let subset_keysets = subset.keysets().await.unwrap();
+            assert_eq!(
+                subset_keysets.pubkey,
+                signatory.keysets().await.unwrap().pubkey
+            );
+
+            subset.verify_proofs(vec![proof.clone()]).await.unwrap();
+            let proof = sign_proof(&subset, &keyset).await;
+            signatory.verify_proofs(vec![proof]).await.unwrap();
+        }
+
+        let mut forged = proof;
+        forged.c = SecretKey::generate().public_key();
+        assert!(signatory.verify_proofs(vec![forged]).await.is_err());
+
+        // A single node is not enough
+        assert!(ThresholdSignatory::new(2, nodes(&shares[..1]))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_ignores_wrong_shares() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        // The third node gets the shares of another dealing
+        let mut other = deal_nodes(2, 3).unwrap();
+        deal_keyset(&mut other, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+        let mut faulty = shares.clone();
+        faulty[2].keysets[0].keys = other[2].keysets[0].keys.clone();
+
+        let signatory = ThresholdSignatory::new(2, nodes(&faulty)).await.unwrap();
+        let keyset = keyset(&signatory, id).await;
+        let proof = sign_proof(&signatory, &keyset).await;
+        signatory.verify_proofs(vec![proof]).await.unwrap();
+
+        // The faulty node cannot replace an honest one
+        let signatory = ThresholdSignatory::new(2, nodes(&faulty[1..]))
+            .await
+            .unwrap();
+        assert!(signatory.keysets().await.unwrap().keysets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_new_dealing_deactivates_keyset() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let old_id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+        let signatory = ThresholdSignatory::new(2, nodes(&shares)).await.unwrap();
+        let old_keyset = keyset(&signatory, old_id).await;
+        let proof = sign_proof(&signatory, &old_keyset).await;
+
+        let new_id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4], 100).unwrap();
+        let signatory = ThresholdSignatory::new(2, nodes(&shares)).await.unwrap();
+
+        let new_keyset = keyset(&signatory, new_id).await;
+        assert!(new_keyset.active);
+        assert_eq!(new_keyset.input_fee_ppk, 100);
+        assert!(!keyset(&signatory, old_id).await.active);
+
+        signatory.verify_proofs(vec![proof]).await.unwrap();
+        let err = signatory
+            .blind_sign(vec![BlindedMessage::new(
+                Amount::from(4),
+                old_id,
+                SecretKey::generate().public_key(),
+            )])
+            .await;
+        assert!(matches!(err, Err(Error::InactiveKeyset)));
+    }
+
+    /// Node signing after a delay
+    struct SlowSignatory {
+        inner: ShareSignatory,
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Signatory for SlowSignatory {
+        fn name(&self) -> String {
+            self.inner.name()
+        }
+
+        async fn blind_sign(
+            &self,
+            blinded_messages: Vec<BlindedMessage>,
+        ) -> Result<Vec<BlindSignature>, Error> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.blind_sign(blinded_messages).await
+        }
+
+        async fn verify_proofs(&self, proofs: Vec<Proof>) -> Result<(), Error> {
+            self.inner.verify_proofs(proofs).await
+        }
+
+        async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+            self.inner.keysets().await
+        }
+
+        async fn rotate_keyset(&self, args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+            self.inner.rotate_keyset(args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_threshold_does_not_wait_for_slow_nodes() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        let slow = |shares: &NodeShares| ThresholdNode {
+            index: shares.index,
+            signatory: Arc::new(SlowSignatory {
+                inner: ShareSignatory::new(shares.clone()),
+                delay: Duration::from_secs(3600),
+            }),
+        };
+
+        // Two nodes answering are enough, the slow one is not waited for
+        let mut with_slow = nodes(&shares[..2]);
+        with_slow.push(slow(&shares[2]));
+        let signatory = ThresholdSignatory::with_timeout(2, with_slow, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        let keyset = keyset(&signatory, id).await;
+        let proof = tokio::time::timeout(Duration::from_secs(10), sign_proof(&signatory, &keyset))
+            .await
+            .expect("signed without the slow node");
+        signatory.verify_proofs(vec![proof]).await.unwrap();
+
+        // With two slow nodes the signing fails after the timeout
+        let mut with_slow = nodes(&shares[..1]);
+        with_slow.extend(shares[1..].iter().map(slow));
+        let signatory = ThresholdSignatory::with_timeout(2, with_slow, Duration::from_millis(100))
+            .await
+            .unwrap();
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            signatory.blind_sign(vec![BlindedMessage::new(
+                Amount::from(4),
+                id,
+                SecretKey::generate().public_key(),
+            )]),
+        )
+        .await
+        .expect("timed out nodes are given up");
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_cross_checks_mint_key() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        let mut faulty = shares.clone();
+        faulty[2].identity = SecretKey::generate();
+
+        assert!(ThresholdSignatory::new(2, nodes(&faulty)).await.is_err());
+        assert!(ThresholdSignatory::new(2, nodes(&faulty[..2]))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_ignores_nodes_with_another_index() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        // The shares of nodes 2 and 3 are configured under each other's index
+        let mut nodes = nodes(&shares);
+        nodes[1].index = 3;
+        nodes[2].index = 2;
+
+        assert!(ThresholdSignatory::new(2, nodes).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_dealt_keysets_are_added_per_node() {
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let old_id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        let indices = shares.iter().map(|node| node.index).collect::<Vec<_>>();
+        let (new_id, mut dealt) =
+            deal_keyset_shares(2, &indices, CurrencyUnit::Sat, &[1, 2, 4], 0).unwrap();
+        for node in shares.iter_mut() {
+            let keyset = dealt.remove(&node.index).unwrap();
+            node.add_keysets(DealtKeysets {
+                index: node.index,
+                threshold: 2,
+                keysets: vec![keyset.clone()],
+            })
+            .unwrap();
+
+            // Shares of another node or a keyset already dealt are refused
+            let mut other = node.clone();
+            other.index += 1;
+            assert!(other
+                .add_keysets(DealtKeysets {
+                    index: node.index,
+                    threshold: 2,
+                    keysets: vec![keyset.clone()],
+                })
+                .is_err());
+            assert!(node
+                .add_keysets(DealtKeysets {
+                    index: node.index,
+                    threshold: 2,
+                    keysets: vec![keyset],
+                })
+                .is_err());
+        }
+
+        let signatory = ThresholdSignatory::new(2, nodes(&shares)).await.unwrap();
+        assert!(keyset(&signatory, new_id).await.active);
+        assert!(!keyset(&signatory, old_id).await.active);
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn test_threshold_sign_over_grpc() {
+        use tokio::net::TcpListener;
+        use tokio_stream::wrappers::TcpListenerStream;
+
+        use crate::{start_grpc_server_with_incoming, SignatoryRpcClient};
+
+        let mut shares = deal_nodes(2, 3).unwrap();
+        let id = deal_keyset(&mut shares, CurrencyUnit::Sat, &[1, 2, 4, 8], 0).unwrap();
+
+        let mut nodes = Vec::new();
+        for shares in &shares {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let signatory = Arc::new(ShareSignatory::new(shares.clone()));
+            tokio::spawn(start_grpc_server_with_incoming(
+                signatory,
+                TcpListenerStream::new(listener),
+            ));
+
+            let client = SignatoryRpcClient::new(format!("http://{addr}"), None::<String>)
+                .await
+                .unwrap();
+            nodes.push(ThresholdNode {
+                index: shares.index,
+                signatory: Arc::new(client),
+            });
+        }
+
+        let signatory = ThresholdSignatory::new(2, nodes).await.unwrap();
+        let keyset = keyset(&signatory, id).await;
+
+        let proof = sign_proof(&signatory, &keyset).await;
+        signatory.verify_proofs(vec![proof]).await.unwrap();
+    }
+}
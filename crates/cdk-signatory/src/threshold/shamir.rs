@@ -0,0 +1,176 @@
+//! Shamir secret sharing over the secp256k1 scalar field
+//!
+//! A secret `k` is split as the points `(x, f(x))` of a random polynomial of degree
+//! `threshold - 1` with `f(0) = k`. Since Lagrange interpolation is linear, a point multiplied by
+//! `k` is interpolated from the points multiplied by the shares, so signatures are combined
+//! without ever recombining the secret.
+use bitcoin::secp256k1::{self, Scalar, SecretKey};
+use cdk_common::{Error, PublicKey, SECP256K1};
+
+/// Order of the secp256k1 group minus two, the exponent of the modular inverse
+const ORDER_MINUS_TWO: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x3f,
+];
+
+/// Splits the secret into a share for each index, any `threshold` of which recover it
+pub(crate) fn split_secret(
+    secret: &SecretKey,
+    threshold: u8,
+    indices: &[u8],
+) -> Result<Vec<(u8, SecretKey)>, Error> {
+    if threshold == 0 || indices.len() < threshold as usize || indices.contains(&0) {
+        return Err(Error::Custom(format!(
+            "Cannot split a secret with a threshold of {} into {} shares",
+            threshold,
+            indices.len()
+        )));
+    }
+
+    let coefficients = (1..threshold)
+        .map(|_| *cdk_common::nuts::SecretKey::generate())
+        .collect::<Vec<_>>();
+
+    indices
+        .iter()
+        .map(|index| {
+            // Horner's method, from the highest degree coefficient down to the secret
+            let x = Scalar::from(small(*index as i64));
+            let mut share: Option<SecretKey> = None;
+            for coefficient in coefficients.iter().rev().chain([secret]) {
+                share = Some(match share {
+                    Some(share) => share
+                        .mul_tweak(&x)
+                        .and_then(|share| share.add_tweak(&Scalar::from(*coefficient)))
+                        .map_err(|_| Error::Internal)?,
+                    None => *coefficient,
+                });
+            }
+
+            Ok((*index, share.ok_or(Error::Internal)?))
+        })
+        .collect()
+}
+
+/// Interpolates the points multiplied by shares at `at`
+///
+/// At 0 the points multiplied by the shares of a secret combine into the point multiplied by the
+/// secret. Elsewhere it computes the point the share of another index should give.
+pub(crate) fn interpolate(points: &[(u8, PublicKey)], at: u8) -> Result<PublicKey, Error> {
+    if let Some((_, point)) = points.iter().find(|(index, _)| *index == at) {
+        return Ok(*point);
+    }
+
+    let indices = points.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+
+    let terms = points
+        .iter()
+        .map(|(index, point)| {
+            let coefficient = lagrange_coefficient(*index, &indices, at)?;
+            point
+                .mul_tweak(&SECP256K1, &Scalar::from(coefficient))
+                .map_err(|_| Error::Internal)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let terms = terms.iter().collect::<Vec<_>>();
+
+    secp256k1::PublicKey::combine_keys(&terms)
+        .map(Into::into)
+        .map_err(|_| Error::Internal)
+}
+
+/// Lagrange coefficient of the index among the indices, evaluated at `at`
+fn lagrange_coefficient(index: u8, indices: &[u8], at: u8) -> Result<SecretKey, Error> {
+    let mut numerator = small(1);
+    let mut denominator = small(1);
+
+    for other in indices.iter().filter(|other| **other != index) {
+        numerator = numerator
+            .mul_tweak(&Scalar::from(small(at as i64 - *other as i64)))
+            .map_err(|_| Error::Internal)?;
+        denominator = denominator
+            .mul_tweak(&Scalar::from(small(index as i64 - *other as i64)))
+            .map_err(|_| Error::Internal)?;
+    }
+
+    numerator
+        .mul_tweak(&Scalar::from(invert(&denominator)?))
+        .map_err(|_| Error::Internal)
+}
+
+/// Modular inverse through Fermat's little theorem
+fn invert(value: &SecretKey) -> Result<SecretKey, Error> {
+    let value = Scalar::from(*value);
+    let mut result = small(1);
+
+    for byte in ORDER_MINUS_TWO {
+        for bit in (0..8).rev() {
+            result = result
+                .mul_tweak(&Scalar::from(result))
+                .map_err(|_| Error::Internal)?;
+            if (byte >> bit) & 1 == 1 {
+                result = result.mul_tweak(&value).map_err(|_| Error::Internal)?;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Scalar of a small non zero integer
+fn small(value: i64) -> SecretKey {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&value.unsigned_abs().to_be_bytes());
+    let scalar = SecretKey::from_slice(&bytes).expect("non zero integer");
+
+    if value < 0 {
+        scalar.negate()
+    } else {
+        scalar
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_any_threshold_of_shares_combine() {
+        let secret = *cdk_common::nuts::SecretKey::generate();
+        let point = cdk_common::nuts::SecretKey::generate().public_key();
+        let expected = point.mul_tweak(&SECP256K1, &Scalar::from(secret)).unwrap();
+
+        let shares = split_secret(&secret, 3, &[1, 2, 3, 4, 5]).unwrap();
+        let partials = shares
+            .iter()
+            .map(|(index, share)| {
+                let partial: PublicKey = point
+                    .mul_tweak(&SECP256K1, &Scalar::from(*share))
+                    .unwrap()
+                    .into();
+                (*index, partial)
+            })
+            .collect::<Vec<_>>();
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let subset = subset.map(|i| partials[i]);
+            assert_eq!(*interpolate(&subset, 0).unwrap(), expected);
+            // The subset also predicts the partials of the other shares
+            for (index, partial) in &partials {
+                assert_eq!(interpolate(&subset, *index).unwrap(), *partial);
+            }
+        }
+
+        // Fewer shares than the threshold give an unrelated point
+        assert_ne!(*interpolate(&partials[..2], 0).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_invalid_split() {
+        let secret = *cdk_common::nuts::SecretKey::generate();
+        assert!(split_secret(&secret, 3, &[1, 2]).is_err());
+        assert!(split_secret(&secret, 0, &[1, 2]).is_err());
+        assert!(split_secret(&secret, 1, &[0, 1]).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Share signatory and dealer
+//!
+//! A share signatory holds one Shamir share of every amount key. Its blind signatures are only
+//! partial signatures, each with a DLEQ proof against the public key of the share, which a
+//! [`super::ThresholdSignatory`] combines.
+//!
+//! The shares are created by a trusted dealer, [`deal_keyset`], during a key ceremony, so the keys
+//! only exist in full on the dealing host and for the duration of the ceremony. New keysets only
+//! depend on the indices and the threshold of the nodes, so they are dealt as [`DealtKeysets`]
+//! without the shares the nodes already hold.
+use std::collections::{BTreeMap, HashMap};
+
+use cdk_common::dhke::sign_message;
+use cdk_common::nuts::{BlindSignature, BlindedMessage, CurrencyUnit, Id, Keys, Proof, SecretKey};
+use cdk_common::{Amount, Error, PublicKey};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::shamir::split_secret;
+use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
+
+/// Shares of the keys of a keyset held by a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeysetShare {
+    /// Id of the keyset, derived from the full public keys
+    pub id: Id,
+    /// Unit of the keyset
+    pub unit: CurrencyUnit,
+    /// Whether the keyset signs new outputs
+    pub active: bool,
+    /// Input fee of the keyset
+    pub input_fee_ppk: u64,
+    /// Final expiry of the keyset
+    pub final_expiry: Option<u64>,
+    /// Share of the key of each amount
+    pub keys: BTreeMap<u64, SecretKey>,
+}
+
+/// Shares held by a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeShares {
+    /// Index of the node, the x coordinate of its shares
+    pub index: u8,
+    /// Number of nodes needed to sign
+    pub threshold: u8,
+    /// Share of the key identifying the mint
+    pub identity: SecretKey,
+    /// Shares of every keyset
+    pub keysets: Vec<KeysetShare>,
+}
+
+/// Shares of new keysets dealt to a node, to add to its [`NodeShares`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DealtKeysets {
+    /// Index of the node the shares are dealt to
+    pub index: u8,
+    /// Number of nodes needed to sign
+    pub threshold: u8,
+    /// Shares of the new keysets
+    pub keysets: Vec<KeysetShare>,
+}
+
+impl NodeShares {
+    /// Adds the dealt keysets, which become the active keysets of their units
+    pub fn add_keysets(&mut self, dealt: DealtKeysets) -> Result<(), Error> {
+        if dealt.index != self.index || dealt.threshold != self.threshold {
+            return Err(Error::Custom(format!(
+                "Shares dealt to node {} with a threshold of {} cannot be added to node {} with a threshold of {}",
+                dealt.index, dealt.threshold, self.index, self.threshold
+            )));
+        }
+
+        for keyset in dealt.keysets {
+            if self.keysets.iter().any(|known| known.id == keyset.id) {
+                return Err(Error::Custom(format!(
+                    "Keyset {} is already dealt",
+                    keyset.id
+                )));
+            }
+
+            for known in self
+                .keysets
+                .iter_mut()
+                .filter(|known| known.unit == keyset.unit)
+            {
+                known.active = false;
+            }
+            self.keysets.push(keyset);
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the shares of `nodes` nodes, `threshold` of which are needed to sign
+///
+/// The nodes are given the indices `1..=nodes` and share a new identity key, but no keyset.
+pub fn deal_nodes(threshold: u8, nodes: u8) -> Result<Vec<NodeShares>, Error> {
+    let indices = (1..=nodes).collect::<Vec<_>>();
+    let identity = SecretKey::generate();
+
+    Ok(split_secret(&identity, threshold, &indices)?
+        .into_iter()
+        .map(|(index, identity)| NodeShares {
+            index,
+            threshold,
+            identity: identity.into(),
+            keysets: Vec::new(),
+        })
+        .collect())
+}
+
+/// Deals the shares of a new keyset to the nodes and sets it as the active keyset of its unit
+///
+/// The shares of all the nodes must be dealt at once, as the keys are discarded afterwards.
+pub fn deal_keyset(
+    nodes: &mut [NodeShares],
+    unit: CurrencyUnit,
+    amounts: &[u64],
+    input_fee_ppk: u64,
+) -> Result<Id, Error> {
+    let threshold = nodes.first().map(|node| node.threshold).unwrap_or_default();
+    if nodes.iter().any(|node| node.threshold != threshold) {
+        return Err(Error::Custom(
+            "Nodes do not share the same threshold".to_string(),
+        ));
+    }
+    let indices = nodes.iter().map(|node| node.index).collect::<Vec<_>>();
+
+    let (id, mut dealt) = deal_keyset_shares(threshold, &indices, unit, amounts, input_fee_ppk)?;
+
+    for node in nodes.iter_mut() {
+        let keyset = dealt.remove(&node.index).ok_or(Error::Internal)?;
+        node.add_keysets(DealtKeysets {
+            index: node.index,
+            threshold,
+            keysets: vec![keyset],
+        })?;
+    }
+
+    Ok(id)
+}
+
+/// Deals the shares of a new keyset to the nodes with the indices, without their current shares
+///
+/// The shares of all the nodes must be dealt at once, as the keys are discarded afterwards.
+pub fn deal_keyset_shares(
+    threshold: u8,
+    indices: &[u8],
+    unit: CurrencyUnit,
+    amounts: &[u64],
+    input_fee_ppk: u64,
+) -> Result<(Id, HashMap<u8, KeysetShare>), Error> {
+    if (1..indices.len()).any(|i| indices[i..].contains(&indices[i - 1])) {
+        return Err(Error::Custom(
+            "Nodes do not have unique indices".to_string(),
+        ));
+    }
+
+    let mut public_keys = BTreeMap::new();
+    let mut shares: HashMap<u8, BTreeMap<u64, SecretKey>> = HashMap::new();
+
+    for amount in amounts {
+        let key = SecretKey::generate();
+        public_keys.insert(Amount::from(*amount), key.public_key());
+
+        for (index, share) in split_secret(&key, threshold, indices)? {
+            shares
+                .entry(index)
+                .or_default()
+                .insert(*amount, share.into());
+        }
+    }
+
+    let id = Id::v1_from_keys(&Keys::new(public_keys));
+
+    let dealt = indices
+        .iter()
+        .map(|index| {
+            (
+                *index,
+                KeysetShare {
+                    id,
+                    unit: unit.clone(),
+                    active: true,
+                    input_fee_ppk,
+                    final_expiry: None,
+                    keys: shares.remove(index).unwrap_or_default(),
+                },
+            )
+        })
+        .collect();
+
+    Ok((id, dealt))
+}
+
+/// Signatory holding the shares of a node
+pub struct ShareSignatory {
+    index: u8,
+    keysets: HashMap<Id, KeysetShare>,
+    pubkey: PublicKey,
+}
+
+impl ShareSignatory {
+    /// Creates a new ShareSignatory instance
+    pub fn new(shares: NodeShares) -> Self {
+        Self {
+            index: shares.index,
+            pubkey: shares.identity.public_key(),
+            keysets: shares
+                .keysets
+                .into_iter()
+                .map(|keyset| (keyset.id, keyset))
+                .collect(),
+        }
+    }
+
+    fn amount_share(&self, keyset_id: &Id, amount: Amount) -> Result<&SecretKey, Error> {
+        self.keysets
+            .get(keyset_id)
+            .and_then(|keyset| keyset.keys.get(&amount.into()))
+            .ok_or(Error::UnknownKeySet)
+    }
+}
+
+#[async_trait::async_trait]
+impl Signatory for ShareSignatory {
+    fn name(&self) -> String {
+        format!("Share Signatory {}", env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Partially signs the messages with the shares
+    ///
+    /// Inactive keysets are signed too: the threshold signatory only asks for them to verify
+    /// proofs, which requires the partial signatures of their secrets.
+    #[instrument(skip_all)]
+    async fn blind_sign(
+        &self,
+        blinded_messages: Vec<BlindedMessage>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        blinded_messages
+            .into_iter()
+            .map(|blinded_message| {
+                let share =
+                    self.amount_share(&blinded_message.keyset_id, blinded_message.amount)?;
+                let c = sign_message(share, &blinded_message.blinded_secret)?;
+
+                Ok(BlindSignature::new(
+                    blinded_message.amount,
+                    c,
+                    blinded_message.keyset_id,
+                    &blinded_message.blinded_secret,
+                    share.clone(),
+                )?)
+            })
+            .collect()
+    }
+
+    async fn verify_proofs(&self, _proofs: Vec<Proof>) -> Result<(), Error> {
+        Err(Error::Custom(
+            "A share signatory cannot verify proofs on its own".to_string(),
+        ))
+    }
+
+    #[instrument(skip_all)]
+    async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+        Ok(SignatoryKeysets {
+            pubkey: self.pubkey,
+            keysets: self
+                .keysets
+                .values()
+                .map(|keyset| SignatoryKeySet {
+                    id: keyset.id,
+                    unit: keyset.unit.clone(),
+                    active: keyset.active,
+                    keys: Keys::new(
+                        keyset
+                            .keys
+                            .iter()
+                            .map(|(amount, share)| (Amount::from(*amount), share.public_key()))
+                            .collect(),
+                    ),
+                    amounts: keyset.keys.keys().copied().collect(),
+                    input_fee_ppk: keyset.input_fee_ppk,
+                    final_expiry: keyset.final_expiry,
+                })
+                .collect(),
+            share_index: Some(self.index),
+        })
+    }
+
+    async fn rotate_keyset(&self, _args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+        Err(Error::Custom(
+            "Keysets of a share signatory are rotated by dealing new shares".to_string(),
+        ))
+    }
+}
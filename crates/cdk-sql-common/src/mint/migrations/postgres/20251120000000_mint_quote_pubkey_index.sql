@@ -0,0 +1,2 @@
+-- Look up the mint quotes locked to a NUT-20 pubkey
+CREATE INDEX IF NOT EXISTS idx_mint_quote_pubkey ON mint_quote(pubkey);
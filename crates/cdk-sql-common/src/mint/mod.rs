@@ -15,7 +15,10 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bitcoin::bip32::DerivationPath;
-use cdk_common::database::mint::{validate_kvstore_params, SagaDatabase, SagaTransaction};
+use cdk_common::database::mint::{
+    validate_kvstore_params, AuditLogDatabase, AuditLogFilter, AuditLogTransaction,
+    MeltQuoteFilter, MintQuoteFilter, SagaDatabase, SagaTransaction,
+};
 use cdk_common::database::{
     self, ConversionError, Error, MintDatabase, MintDbWriterFinalizer, MintKeyDatabaseTransaction,
     MintKeysDatabase, MintProofsDatabase, MintQuotesDatabase, MintQuotesTransaction,
@@ -33,7 +36,7 @@ use cdk_common::state::{check_melt_quote_state_transition, check_state_transitio
 use cdk_common::util::unix_time;
 use cdk_common::{
     Amount, BlindSignature, BlindSignatureDleq, BlindedMessage, CurrencyUnit, Id, MeltQuoteState,
-    PaymentMethod, Proof, Proofs, PublicKey, SecretKey, State,
+    MintQuoteState, PaymentMethod, Proof, Proofs, PublicKey, SecretKey, State,
 };
 use lightning_invoice::Bolt11Invoice;
 use migrations::MIGRATIONS;
@@ -42,7 +45,7 @@ use tracing::instrument;
 use crate::common::migrate;
 use crate::database::{ConnectionWithTransaction, DatabaseExecutor};
 use crate::pool::{DatabasePool, Pool, PooledResource};
-use crate::stmt::{query, Column};
+use crate::stmt::{query, Column, Statement};
 use crate::{
     column_as_nullable_number, column_as_nullable_string, column_as_number, column_as_string,
     unpack_into,
@@ -329,6 +332,130 @@ where
 }
 
 #[inline(always)]
+/// `WHERE` clause matching the quotes of a [`MintQuoteFilter`], bound by [`bind_mint_quote_filter`]
+fn mint_quote_filter_sql(filter: &MintQuoteFilter) -> String {
+    let mut conditions = Vec::new();
+
+    if filter.unit.is_some() {
+        conditions.push("unit = :unit");
+    }
+    if let Some(state) = filter.state {
+        // Mirrors how the state of a quote is derived from the paid and issued amounts
+        conditions.push(match state {
+            MintQuoteState::Unpaid => "(amount_paid = 0 AND amount_issued = 0)",
+            MintQuoteState::Paid => "amount_paid > amount_issued",
+            MintQuoteState::Issued => {
+                "(amount_paid <= amount_issued AND (amount_paid > 0 OR amount_issued > 0))"
+            }
+        });
+    }
+    if filter.payment_method.is_some() {
+        conditions.push("payment_method = :payment_method");
+    }
+    if filter.created_after.is_some() {
+        conditions.push("created_time >= :created_after");
+    }
+    if filter.created_before.is_some() {
+        conditions.push("created_time < :created_before");
+    }
+    if filter.pubkey.is_some() {
+        conditions.push("pubkey = :pubkey");
+    }
+
+    where_clause(&conditions)
+}
+
+fn bind_mint_quote_filter(mut statement: Statement, filter: &MintQuoteFilter) -> Statement {
+    if let Some(unit) = &filter.unit {
+        statement = statement.bind("unit", unit.to_string());
+    }
+    if let Some(payment_method) = &filter.payment_method {
+        statement = statement.bind("payment_method", payment_method.to_string());
+    }
+    if let Some(created_after) = filter.created_after {
+        statement = statement.bind("created_after", created_after as i64);
+    }
+    if let Some(created_before) = filter.created_before {
+        statement = statement.bind("created_before", created_before as i64);
+    }
+    if let Some(pubkey) = &filter.pubkey {
+        statement = statement.bind("pubkey", pubkey.to_string());
+    }
+
+    statement
+}
+
+/// `WHERE` clause matching the quotes of a [`MeltQuoteFilter`], bound by [`bind_melt_quote_filter`]
+fn melt_quote_filter_sql(filter: &MeltQuoteFilter) -> String {
+    let mut conditions = Vec::new();
+
+    if filter.unit.is_some() {
+        conditions.push("unit = :unit");
+    }
+    if filter.state.is_some() {
+        conditions.push("state = :state");
+    }
+
+    where_clause(&conditions)
+}
+
+fn bind_melt_quote_filter(mut statement: Statement, filter: &MeltQuoteFilter) -> Statement {
+    if let Some(unit) = &filter.unit {
+        statement = statement.bind("unit", unit.to_string());
+    }
+    if let Some(state) = filter.state {
+        statement = statement.bind("state", state.to_string());
+    }
+
+    statement
+}
+
+/// `WHERE` clause matching the entries of an [`AuditLogFilter`], bound by [`bind_audit_log_filter`]
+fn audit_log_filter_sql(filter: &AuditLogFilter) -> String {
+    let mut conditions = Vec::new();
+
+    if filter.action.is_some() {
+        conditions.push("action = :action");
+    }
+    if filter.created_after.is_some() {
+        conditions.push("created_at >= :created_after");
+    }
+
+    where_clause(&conditions)
+}
+
+fn bind_audit_log_filter(mut statement: Statement, filter: &AuditLogFilter) -> Statement {
+    if let Some(action) = &filter.action {
+        statement = statement.bind("action", action.clone());
+    }
+    if let Some(created_after) = filter.created_after {
+        statement = statement.bind("created_after", created_after as i64);
+    }
+
+    statement
+}
+
+fn where_clause(conditions: &[&str]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// Reads the result of a `COUNT(*)` query
+async fn count<C>(statement: Statement, conn: &C) -> Result<u64, Error>
+where
+    C: DatabaseExecutor + Send + Sync,
+{
+    Ok(statement
+        .pluck(conn)
+        .await?
+        .map(|total| -> Result<u64, Error> { Ok(column_as_number!(total)) })
+        .transpose()?
+        .unwrap_or_default())
+}
+
 async fn get_mint_quote_payments<C>(
     conn: &C,
     quote_id: &QuoteId,
@@ -1438,6 +1565,65 @@ where
         Ok(mint_quotes)
     }
 
+    async fn list_mint_quotes(
+        &self,
+        filter: &MintQuoteFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<MintQuote>, u64), Self::Err> {
+        let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
+        let where_clause = mint_quote_filter_sql(filter);
+
+        let total = count(
+            bind_mint_quote_filter(
+                query(&format!("SELECT COUNT(*) FROM mint_quote {where_clause}"))?,
+                filter,
+            ),
+            &*conn,
+        )
+        .await?;
+
+        let mut mint_quotes = bind_mint_quote_filter(
+            query(&format!(
+                r#"
+                SELECT
+                    id,
+                    amount,
+                    unit,
+                    request,
+                    expiry,
+                    request_lookup_id,
+                    pubkey,
+                    created_time,
+                    amount_paid,
+                    amount_issued,
+                    payment_method,
+                    request_lookup_id_kind
+                FROM
+                    mint_quote
+                {where_clause}
+                ORDER BY created_time DESC, id DESC
+                LIMIT :limit OFFSET :offset
+                "#
+            ))?,
+            filter,
+        )
+        .bind("limit", limit as i64)
+        .bind("offset", offset as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(|row| sql_row_to_mint_quote(row, vec![], vec![]))
+        .collect::<Result<Vec<_>, _>>()?;
+
+        for quote in mint_quotes.as_mut_slice() {
+            quote.payments = get_mint_quote_payments(&*conn, &quote.id).await?;
+            quote.issuance = get_mint_quote_issuance(&*conn, &quote.id).await?;
+        }
+
+        Ok((mint_quotes, total))
+    }
+
     async fn get_melt_quote(
         &self,
         quote_id: &QuoteId,
@@ -1497,6 +1683,42 @@ where
         result
     }
 
+    async fn get_melt_quote_by_request_lookup_id(
+        &self,
+        request_lookup_id: &PaymentIdentifier,
+    ) -> Result<Option<mint::MeltQuote>, Self::Err> {
+        let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
+        query(
+            r#"
+            SELECT
+                id,
+                unit,
+                amount,
+                request,
+                fee_reserve,
+                expiry,
+                state,
+                payment_preimage,
+                request_lookup_id,
+                created_time,
+                paid_time,
+                payment_method,
+                options,
+                request_lookup_id_kind
+            FROM
+                melt_quote
+            WHERE request_lookup_id = :request_lookup_id
+            AND request_lookup_id_kind = :request_lookup_id_kind
+            "#,
+        )?
+        .bind("request_lookup_id", request_lookup_id.to_string())
+        .bind("request_lookup_id_kind", request_lookup_id.kind())
+        .fetch_one(&*conn)
+        .await?
+        .map(sql_row_to_melt_quote)
+        .transpose()
+    }
+
     async fn get_melt_quotes(&self) -> Result<Vec<mint::MeltQuote>, Self::Err> {
         let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
         Ok(query(
@@ -1526,6 +1748,62 @@ where
         .map(sql_row_to_melt_quote)
         .collect::<Result<Vec<_>, _>>()?)
     }
+
+    async fn list_melt_quotes(
+        &self,
+        filter: &MeltQuoteFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::MeltQuote>, u64), Self::Err> {
+        let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
+        let where_clause = melt_quote_filter_sql(filter);
+
+        let total = count(
+            bind_melt_quote_filter(
+                query(&format!("SELECT COUNT(*) FROM melt_quote {where_clause}"))?,
+                filter,
+            ),
+            &*conn,
+        )
+        .await?;
+
+        let quotes = bind_melt_quote_filter(
+            query(&format!(
+                r#"
+                SELECT
+                    id,
+                    unit,
+                    amount,
+                    request,
+                    fee_reserve,
+                    expiry,
+                    state,
+                    payment_preimage,
+                    request_lookup_id,
+                    created_time,
+                    paid_time,
+                    payment_method,
+                    options,
+                    request_lookup_id_kind
+                FROM
+                    melt_quote
+                {where_clause}
+                ORDER BY created_time ASC, id ASC
+                LIMIT :limit OFFSET :offset
+                "#
+            ))?,
+            filter,
+        )
+        .bind("limit", limit as i64)
+        .bind("offset", offset as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_melt_quote)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((quotes, total))
+    }
 }
 
 #[async_trait]
@@ -2010,6 +2288,7 @@ where
             WHERE primary_namespace = :primary_namespace
             AND secondary_namespace = :secondary_namespace
             AND key = :key
+            FOR UPDATE
             "#,
         )?
         .bind("primary_namespace", primary_namespace.to_owned())
@@ -2350,6 +2629,144 @@ where
         .map(sql_row_to_saga)
         .collect::<Result<Vec<_>, _>>()?)
     }
+
+    async fn list_incomplete_sagas(
+        &self,
+        operation_kind: Option<mint::OperationKind>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::Saga>, u64), Self::Err> {
+        let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
+        let where_clause = match operation_kind {
+            Some(_) => "WHERE operation_kind = :operation_kind",
+            None => "",
+        };
+        let bind_kind = |statement: Statement| match operation_kind {
+            Some(operation_kind) => statement.bind("operation_kind", operation_kind.to_string()),
+            None => statement,
+        };
+
+        let total = count(
+            bind_kind(query(&format!(
+                "SELECT COUNT(*) FROM saga_state {where_clause}"
+            ))?),
+            &*conn,
+        )
+        .await?;
+
+        let sagas = bind_kind(query(&format!(
+            r#"
+            SELECT
+                operation_id,
+                operation_kind,
+                state,
+                blinded_secrets,
+                input_ys,
+                quote_id,
+                created_at,
+                updated_at
+            FROM
+                saga_state
+            {where_clause}
+            ORDER BY created_at ASC, operation_id ASC
+            LIMIT :limit OFFSET :offset
+            "#
+        ))?)
+        .bind("limit", limit as i64)
+        .bind("offset", offset as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_saga)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((sagas, total))
+    }
+}
+
+#[async_trait]
+impl<RM> AuditLogTransaction<'_> for SQLTransaction<RM>
+where
+    RM: DatabasePool + 'static,
+{
+    type Err = Error;
+
+    async fn add_audit_log_entry(&mut self, entry: &mint::AuditLogEntry) -> Result<(), Self::Err> {
+        query(
+            r#"
+            INSERT INTO audit_log
+            (id, action, old_value, new_value, caller, created_at)
+            VALUES
+            (:id, :action, :old_value, :new_value, :caller, :created_at)
+            "#,
+        )?
+        .bind("id", entry.id.to_string())
+        .bind("action", entry.action.clone())
+        .bind("old_value", entry.old_value.clone())
+        .bind("new_value", entry.new_value.clone())
+        .bind("caller", entry.caller.clone())
+        .bind("created_at", entry.created_at as i64)
+        .execute(&self.inner)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<RM> AuditLogDatabase for SQLMintDatabase<RM>
+where
+    RM: DatabasePool + 'static,
+{
+    type Err = Error;
+
+    async fn list_audit_log(
+        &self,
+        filter: &AuditLogFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<mint::AuditLogEntry>, u64), Self::Err> {
+        let conn = self.pool.get().map_err(|e| Error::Database(Box::new(e)))?;
+        let where_clause = audit_log_filter_sql(filter);
+
+        let total = count(
+            bind_audit_log_filter(
+                query(&format!("SELECT COUNT(*) FROM audit_log {where_clause}"))?,
+                filter,
+            ),
+            &*conn,
+        )
+        .await?;
+
+        let entries = bind_audit_log_filter(
+            query(&format!(
+                r#"
+                SELECT
+                    id,
+                    action,
+                    old_value,
+                    new_value,
+                    caller,
+                    created_at
+                FROM
+                    audit_log
+                {where_clause}
+                ORDER BY created_at DESC, id DESC
+                LIMIT :limit OFFSET :offset
+                "#
+            ))?,
+            filter,
+        )
+        .bind("limit", limit as i64)
+        .bind("offset", offset as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_audit_log_entry)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((entries, total))
+    }
 }
 
 #[async_trait]
@@ -2690,6 +3107,32 @@ fn sql_row_to_saga(row: Vec<Column>) -> Result<mint::Saga, Error> {
     })
 }
 
+fn sql_row_to_audit_log_entry(row: Vec<Column>) -> Result<mint::AuditLogEntry, Error> {
+    unpack_into!(
+        let (
+            id,
+            action,
+            old_value,
+            new_value,
+            caller,
+            created_at
+        ) = row
+    );
+
+    let id_str = column_as_string!(&id);
+    let id = uuid::Uuid::parse_str(&id_str)
+        .map_err(|e| Error::Internal(format!("Invalid audit log id UUID: {e}")))?;
+
+    Ok(mint::AuditLogEntry {
+        id,
+        action: column_as_string!(action),
+        old_value: column_as_nullable_string!(old_value),
+        new_value: column_as_nullable_string!(new_value),
+        caller: column_as_nullable_string!(caller),
+        created_at: column_as_number!(created_at),
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
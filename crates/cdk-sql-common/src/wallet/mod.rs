@@ -585,9 +585,9 @@ ON CONFLICT(id) DO UPDATE SET
         query(
             r#"
 INSERT INTO melt_quote
-(id, unit, amount, request, fee_reserve, state, expiry, payment_method)
+(id, unit, amount, request, fee_reserve, state, expiry, payment_preimage, payment_method)
 VALUES
-(:id, :unit, :amount, :request, :fee_reserve, :state, :expiry, :payment_method)
+(:id, :unit, :amount, :request, :fee_reserve, :state, :expiry, :payment_preimage, :payment_method)
 ON CONFLICT(id) DO UPDATE SET
     unit = excluded.unit,
     amount = excluded.amount,
@@ -595,6 +595,7 @@ ON CONFLICT(id) DO UPDATE SET
     fee_reserve = excluded.fee_reserve,
     state = excluded.state,
     expiry = excluded.expiry,
+    payment_preimage = excluded.payment_preimage,
     payment_method = excluded.payment_method
 ;
         "#,
@@ -606,6 +607,7 @@ ON CONFLICT(id) DO UPDATE SET
         .bind("fee_reserve", u64::from(quote.fee_reserve) as i64)
         .bind("state", quote.state.to_string())
         .bind("expiry", quote.expiry as i64)
+        .bind("payment_preimage", quote.payment_preimage)
         .bind("payment_method", quote.payment_method.to_string())
         .execute(&*conn)
         .await?;
@@ -0,0 +1,506 @@
+//! IndexedDB wallet database
+//!
+//! Every record is stored as its JSON encoding, keyed by the record id, with one object store per
+//! kind of record.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cdk_common::common::ProofInfo;
+use cdk_common::database::{self, WalletDatabase};
+use cdk_common::mint_url::MintUrl;
+use cdk_common::wallet::{self, MintQuote, Transaction, TransactionDirection, TransactionId};
+use cdk_common::{
+    CurrencyUnit, Id, KeySet, KeySetInfo, Keys, MintInfo, PublicKey, SpendingConditions, State,
+};
+use indexed_db_futures::prelude::*;
+use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use wasm_bindgen::JsValue;
+
+use crate::error::Error;
+
+const DATABASE_VERSION: u32 = 1;
+
+const MINTS: &str = "mints";
+const KEYSETS: &str = "keysets";
+const MINT_QUOTES: &str = "mint_quotes";
+const MELT_QUOTES: &str = "melt_quotes";
+const KEYS: &str = "keys";
+const PROOFS: &str = "proofs";
+const KEYSET_COUNTERS: &str = "keyset_counters";
+const TRANSACTIONS: &str = "transactions";
+
+const STORES: [&str; 8] = [
+    MINTS,
+    KEYSETS,
+    MINT_QUOTES,
+    MELT_QUOTES,
+    KEYS,
+    PROOFS,
+    KEYSET_COUNTERS,
+    TRANSACTIONS,
+];
+
+#[derive(Serialize, Deserialize)]
+struct StoredMint {
+    mint_url: MintUrl,
+    info: Option<MintInfo>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredKeyset {
+    mint_url: MintUrl,
+    keyset: KeySetInfo,
+}
+
+/// Wallet IndexedDB Database
+///
+/// The browser runs the wallet on a single thread, so the connection is wrapped to satisfy the
+/// `Send + Sync` bounds of the wallet.
+#[derive(Debug, Clone)]
+pub struct WalletIndexedDb {
+    db: Arc<SendWrapper<IdbDatabase>>,
+}
+
+impl WalletIndexedDb {
+    /// Open or create the IndexedDB database with the given name
+    pub async fn new(name: &str) -> Result<Self, Error> {
+        let mut request = IdbDatabase::open_u32(name, DATABASE_VERSION)?;
+
+        request.set_on_upgrade_needed(Some(
+            |event: &IdbVersionChangeEvent| -> Result<(), JsValue> {
+                let existing = event.db().object_store_names().collect::<Vec<_>>();
+
+                for store in STORES {
+                    if !existing.iter().any(|name| name == store) {
+                        event.db().create_object_store(store)?;
+                    }
+                }
+
+                Ok(())
+            },
+        ));
+
+        let db = request.await?;
+
+        Ok(Self {
+            db: Arc::new(SendWrapper::new(db)),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, store: &str, key: &str) -> Result<Option<T>, Error> {
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(store, IdbTransactionMode::Readonly)?;
+        let object_store = tx.object_store(store)?;
+
+        object_store
+            .get_owned(key)?
+            .await?
+            .map(|value| decode(&value))
+            .transpose()
+    }
+
+    async fn get_all<T: DeserializeOwned>(&self, store: &str) -> Result<Vec<T>, Error> {
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(store, IdbTransactionMode::Readonly)?;
+        let object_store = tx.object_store(store)?;
+
+        object_store
+            .get_all()?
+            .await?
+            .iter()
+            .map(|value| decode(&value))
+            .collect()
+    }
+
+    async fn put_all<T: Serialize>(
+        &self,
+        store: &str,
+        records: &[(String, T)],
+    ) -> Result<(), Error> {
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(store, IdbTransactionMode::Readwrite)?;
+
+        {
+            let object_store = tx.object_store(store)?;
+            for (key, record) in records {
+                object_store.put_key_val_owned(key.as_str(), &encode(record)?)?;
+            }
+        }
+
+        tx.await.into_result()?;
+
+        Ok(())
+    }
+
+    async fn put<T: Serialize>(&self, store: &str, key: String, record: T) -> Result<(), Error> {
+        self.put_all(store, &[(key, record)]).await
+    }
+
+    async fn delete(&self, store: &str, key: &str) -> Result<(), Error> {
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(store, IdbTransactionMode::Readwrite)?;
+
+        {
+            let object_store = tx.object_store(store)?;
+            object_store.delete_owned(key)?;
+        }
+
+        tx.await.into_result()?;
+
+        Ok(())
+    }
+}
+
+fn encode<T: Serialize>(record: &T) -> Result<JsValue, Error> {
+    Ok(JsValue::from_str(&serde_json::to_string(record)?))
+}
+
+fn decode<T: DeserializeOwned>(value: &JsValue) -> Result<T, Error> {
+    let json = value.as_string().ok_or(Error::InvalidRecord)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+#[async_trait(?Send)]
+impl WalletDatabase for WalletIndexedDb {
+    type Err = database::Error;
+
+    #[instrument(skip(self))]
+    async fn add_mint(
+        &self,
+        mint_url: MintUrl,
+        mint_info: Option<MintInfo>,
+    ) -> Result<(), Self::Err> {
+        let key = mint_url.to_string();
+
+        Ok(self
+            .put(
+                MINTS,
+                key,
+                StoredMint {
+                    mint_url,
+                    info: mint_info,
+                },
+            )
+            .await?)
+    }
+
+    #[instrument(skip(self))]
+    async fn remove_mint(&self, mint_url: MintUrl) -> Result<(), Self::Err> {
+        Ok(self.delete(MINTS, &mint_url.to_string()).await?)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, Self::Err> {
+        Ok(self
+            .get::<StoredMint>(MINTS, &mint_url.to_string())
+            .await?
+            .and_then(|mint| mint.info))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_mints(&self) -> Result<HashMap<MintUrl, Option<MintInfo>>, Self::Err> {
+        Ok(self
+            .get_all::<StoredMint>(MINTS)
+            .await?
+            .into_iter()
+            .map(|mint| (mint.mint_url, mint.info))
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn update_mint_url(
+        &self,
+        old_mint_url: MintUrl,
+        new_mint_url: MintUrl,
+    ) -> Result<(), Self::Err> {
+        let proofs = self
+            .get_proofs(Some(old_mint_url.clone()), None, None, None)
+            .await?
+            .into_iter()
+            .map(|mut proof| {
+                proof.mint_url = new_mint_url.clone();
+                (proof.y.to_hex(), proof)
+            })
+            .collect::<Vec<_>>();
+        self.put_all(PROOFS, &proofs).await?;
+
+        let quotes = self
+            .get_mint_quotes()
+            .await?
+            .into_iter()
+            .filter(|quote| quote.mint_url == old_mint_url)
+            .map(|mut quote| {
+                quote.mint_url = new_mint_url.clone();
+                (quote.id.clone(), quote)
+            })
+            .collect::<Vec<_>>();
+        self.put_all(MINT_QUOTES, &quotes).await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, keysets))]
+    async fn add_mint_keysets(
+        &self,
+        mint_url: MintUrl,
+        keysets: Vec<KeySetInfo>,
+    ) -> Result<(), Self::Err> {
+        let mut records = Vec::with_capacity(keysets.len());
+
+        for keyset in keysets {
+            let key = keyset.id.to_string();
+
+            let record = match self.get::<StoredKeyset>(KEYSETS, &key).await? {
+                Some(mut existing) => {
+                    existing.keyset.active = keyset.active;
+                    existing.keyset.input_fee_ppk = keyset.input_fee_ppk;
+                    existing
+                }
+                None => StoredKeyset {
+                    mint_url: mint_url.clone(),
+                    keyset,
+                },
+            };
+
+            records.push((key, record));
+        }
+
+        Ok(self.put_all(KEYSETS, &records).await?)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_mint_keysets(
+        &self,
+        mint_url: MintUrl,
+    ) -> Result<Option<Vec<KeySetInfo>>, Self::Err> {
+        let keysets = self
+            .get_all::<StoredKeyset>(KEYSETS)
+            .await?
+            .into_iter()
+            .filter(|stored| stored.mint_url == mint_url)
+            .map(|stored| stored.keyset)
+            .collect::<Vec<_>>();
+
+        Ok((!keysets.is_empty()).then_some(keysets))
+    }
+
+    #[instrument(skip(self), fields(keyset_id = %keyset_id))]
+    async fn get_keyset_by_id(&self, keyset_id: &Id) -> Result<Option<KeySetInfo>, Self::Err> {
+        Ok(self
+            .get::<StoredKeyset>(KEYSETS, &keyset_id.to_string())
+            .await?
+            .map(|stored| stored.keyset))
+    }
+
+    #[instrument(skip_all)]
+    async fn add_mint_quote(&self, quote: MintQuote) -> Result<(), Self::Err> {
+        Ok(self.put(MINT_QUOTES, quote.id.clone(), quote).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_mint_quote(&self, quote_id: &str) -> Result<Option<MintQuote>, Self::Err> {
+        Ok(self.get(MINT_QUOTES, quote_id).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_mint_quotes(&self) -> Result<Vec<MintQuote>, Self::Err> {
+        Ok(self.get_all(MINT_QUOTES).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn remove_mint_quote(&self, quote_id: &str) -> Result<(), Self::Err> {
+        Ok(self.delete(MINT_QUOTES, quote_id).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn add_melt_quote(&self, quote: wallet::MeltQuote) -> Result<(), Self::Err> {
+        Ok(self.put(MELT_QUOTES, quote.id.clone(), quote).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_melt_quote(&self, quote_id: &str) -> Result<Option<wallet::MeltQuote>, Self::Err> {
+        Ok(self.get(MELT_QUOTES, quote_id).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_melt_quotes(&self) -> Result<Vec<wallet::MeltQuote>, Self::Err> {
+        Ok(self.get_all(MELT_QUOTES).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn remove_melt_quote(&self, quote_id: &str) -> Result<(), Self::Err> {
+        Ok(self.delete(MELT_QUOTES, quote_id).await?)
+    }
+
+    #[instrument(skip_all)]
+    async fn add_keys(&self, keyset: KeySet) -> Result<(), Self::Err> {
+        keyset.verify_id()?;
+
+        Ok(self.put(KEYS, keyset.id.to_string(), keyset.keys).await?)
+    }
+
+    #[instrument(skip(self), fields(keyset_id = %keyset_id))]
+    async fn get_keys(&self, keyset_id: &Id) -> Result<Option<Keys>, Self::Err> {
+        Ok(self.get(KEYS, &keyset_id.to_string()).await?)
+    }
+
+    #[instrument(skip(self), fields(keyset_id = %keyset_id))]
+    async fn remove_keys(&self, keyset_id: &Id) -> Result<(), Self::Err> {
+        Ok(self.delete(KEYS, &keyset_id.to_string()).await?)
+    }
+
+    #[instrument(skip(self, added, deleted_ys))]
+    async fn update_proofs(
+        &self,
+        added: Vec<ProofInfo>,
+        deleted_ys: Vec<PublicKey>,
+    ) -> Result<(), Self::Err> {
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(PROOFS, IdbTransactionMode::Readwrite)
+            .map_err(Error::from)?;
+
+        {
+            let object_store = tx.object_store(PROOFS).map_err(Error::from)?;
+
+            for proof_info in added.iter() {
+                object_store
+                    .put_key_val_owned(proof_info.y.to_hex(), &encode(proof_info)?)
+                    .map_err(Error::from)?;
+            }
+
+            for y in deleted_ys.iter() {
+                object_store.delete_owned(y.to_hex()).map_err(Error::from)?;
+            }
+        }
+
+        tx.await.into_result().map_err(Error::from)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    async fn get_proofs(
+        &self,
+        mint_url: Option<MintUrl>,
+        unit: Option<CurrencyUnit>,
+        state: Option<Vec<State>>,
+        spending_conditions: Option<Vec<SpendingConditions>>,
+    ) -> Result<Vec<ProofInfo>, Self::Err> {
+        Ok(self
+            .get_all::<ProofInfo>(PROOFS)
+            .await?
+            .into_iter()
+            .filter(|proof_info| {
+                proof_info.matches_conditions(&mint_url, &unit, &state, &spending_conditions)
+            })
+            .collect())
+    }
+
+    async fn get_balance(
+        &self,
+        mint_url: Option<MintUrl>,
+        unit: Option<CurrencyUnit>,
+        state: Option<Vec<State>>,
+    ) -> Result<u64, Self::Err> {
+        let proofs = self.get_proofs(mint_url, unit, state, None).await?;
+        Ok(proofs.iter().map(|p| u64::from(p.proof.amount)).sum())
+    }
+
+    async fn update_proofs_state(&self, ys: Vec<PublicKey>, state: State) -> Result<(), Self::Err> {
+        let mut updated = Vec::with_capacity(ys.len());
+
+        for y in ys {
+            let key = y.to_hex();
+            if let Some(mut proof_info) = self.get::<ProofInfo>(PROOFS, &key).await? {
+                proof_info.state = state;
+                updated.push((key, proof_info));
+            }
+        }
+
+        Ok(self.put_all(PROOFS, &updated).await?)
+    }
+
+    #[instrument(skip(self), fields(keyset_id = %keyset_id))]
+    async fn increment_keyset_counter(&self, keyset_id: &Id, count: u32) -> Result<u32, Self::Err> {
+        let key = keyset_id.to_string();
+
+        // Read and write in the same transaction so concurrent increments are serialized
+        let tx = self
+            .db
+            .transaction_on_one_with_mode(KEYSET_COUNTERS, IdbTransactionMode::Readwrite)
+            .map_err(Error::from)?;
+
+        let new_counter = {
+            let object_store = tx.object_store(KEYSET_COUNTERS).map_err(Error::from)?;
+
+            let current_counter = match object_store
+                .get_owned(key.as_str())
+                .map_err(Error::from)?
+                .await
+                .map_err(Error::from)?
+            {
+                Some(value) => decode::<u32>(&value)?,
+                None => 0,
+            };
+
+            let new_counter = current_counter + count;
+
+            object_store
+                .put_key_val_owned(key.as_str(), &encode(&new_counter)?)
+                .map_err(Error::from)?;
+
+            new_counter
+        };
+
+        tx.await.into_result().map_err(Error::from)?;
+
+        Ok(new_counter)
+    }
+
+    #[instrument(skip(self))]
+    async fn add_transaction(&self, transaction: Transaction) -> Result<(), Self::Err> {
+        Ok(self
+            .put(TRANSACTIONS, transaction.id().to_string(), transaction)
+            .await?)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_transaction(
+        &self,
+        transaction_id: TransactionId,
+    ) -> Result<Option<Transaction>, Self::Err> {
+        Ok(self.get(TRANSACTIONS, &transaction_id.to_string()).await?)
+    }
+
+    #[instrument(skip(self))]
+    async fn list_transactions(
+        &self,
+        mint_url: Option<MintUrl>,
+        direction: Option<TransactionDirection>,
+        unit: Option<CurrencyUnit>,
+    ) -> Result<Vec<Transaction>, Self::Err> {
+        Ok(self
+            .get_all::<Transaction>(TRANSACTIONS)
+            .await?
+            .into_iter()
+            .filter(|tx| tx.matches_conditions(&mint_url, &direction, &unit))
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn remove_transaction(&self, transaction_id: TransactionId) -> Result<(), Self::Err> {
+        Ok(self
+            .delete(TRANSACTIONS, &transaction_id.to_string())
+            .await?)
+    }
+}
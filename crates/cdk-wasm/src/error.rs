@@ -0,0 +1,38 @@
+//! IndexedDB Error
+
+use indexed_db_futures::web_sys::DomException;
+use thiserror::Error;
+
+/// IndexedDB Database Error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// IndexedDB Error
+    #[error("IndexedDB error {name}: {message}")]
+    IndexedDb {
+        /// DOM exception name
+        name: String,
+        /// DOM exception message
+        message: String,
+    },
+    /// Serde Json Error
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    /// Stored record is not a JSON string
+    #[error("Invalid record")]
+    InvalidRecord,
+}
+
+impl From<DomException> for Error {
+    fn from(e: DomException) -> Self {
+        Self::IndexedDb {
+            name: e.name(),
+            message: e.message(),
+        }
+    }
+}
+
+impl From<Error> for cdk_common::database::Error {
+    fn from(e: Error) -> Self {
+        Self::Database(Box::new(e))
+    }
+}
@@ -0,0 +1,23 @@
+//! CDK WebAssembly Bindings
+//!
+//! wasm-bindgen bindings for the CDK multi mint wallet, with an IndexedDB wallet database for
+//! browsers.
+#![cfg(target_arch = "wasm32")]
+#![warn(missing_docs)]
+#![warn(rustdoc::bare_urls)]
+
+pub mod database;
+pub mod error;
+pub mod multi_mint_wallet;
+mod runtime;
+pub mod subscription;
+
+pub use database::WalletIndexedDb;
+pub use multi_mint_wallet::MultiMintWallet;
+pub use subscription::Subscription;
+use wasm_bindgen::{JsError, JsValue};
+
+/// Encode a value as a plain JS object
+fn to_js<T: serde::Serialize>(value: &T) -> Result<JsValue, JsError> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
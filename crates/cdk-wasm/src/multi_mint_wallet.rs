@@ -0,0 +1,278 @@
+//! JS bindings for the multi mint wallet
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bip39::Mnemonic;
+use cdk::mint_url::MintUrl;
+use cdk::nuts::nut00::ProofsMethods;
+use cdk::nuts::nut17::{Kind, Params};
+use cdk::nuts::{CurrencyUnit, NotificationPayload};
+use cdk::wallet::{
+    MultiMintReceiveOptions, MultiMintSendOptions, MultiMintWallet as CdkMultiMintWallet, SendMemo,
+};
+use cdk::Amount;
+use wasm_bindgen::prelude::*;
+
+use crate::database::WalletIndexedDb;
+use crate::runtime::run;
+use crate::subscription::Subscription;
+use crate::to_js;
+
+/// Generate a new 12 word mnemonic
+#[wasm_bindgen(js_name = generateMnemonic)]
+pub fn generate_mnemonic() -> Result<String, JsError> {
+    Ok(Mnemonic::generate(12)?.to_string())
+}
+
+/// Wallet holding the proofs of many mints in one unit, stored in IndexedDB
+#[wasm_bindgen]
+pub struct MultiMintWallet {
+    inner: Arc<CdkMultiMintWallet>,
+}
+
+#[wasm_bindgen]
+impl MultiMintWallet {
+    /// Open the wallet stored in the IndexedDB database with the given name
+    ///
+    /// The database is created if it does not exist yet.
+    pub async fn create(
+        mnemonic: String,
+        unit: String,
+        database_name: String,
+    ) -> Result<MultiMintWallet, JsError> {
+        let seed = Mnemonic::from_str(&mnemonic)?.to_seed_normalized("");
+        let unit = CurrencyUnit::from_str(&unit)?;
+        let localstore = Arc::new(WalletIndexedDb::new(&database_name).await?);
+
+        let inner =
+            run(async move { CdkMultiMintWallet::new(localstore, seed, unit).await }).await??;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Currency unit of the wallet
+    #[wasm_bindgen(getter)]
+    pub fn unit(&self) -> String {
+        self.inner.unit().to_string()
+    }
+
+    /// Add a mint to the wallet
+    #[wasm_bindgen(js_name = addMint)]
+    pub async fn add_mint(&self, mint_url: String) -> Result<(), JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+
+        Ok(run(async move { inner.add_mint(mint_url).await }).await??)
+    }
+
+    /// Remove a mint from the wallet
+    #[wasm_bindgen(js_name = removeMint)]
+    pub async fn remove_mint(&self, mint_url: String) -> Result<(), JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+
+        run(async move { inner.remove_mint(&mint_url).await }).await
+    }
+
+    /// Balance of every mint, as an object keyed by mint url
+    #[wasm_bindgen(js_name = getBalances)]
+    pub async fn get_balances(&self) -> Result<JsValue, JsError> {
+        let inner = Arc::clone(&self.inner);
+        let balances = run(async move { inner.get_balances().await }).await??;
+
+        to_js(&balances)
+    }
+
+    /// Balance across all mints
+    #[wasm_bindgen(js_name = totalBalance)]
+    pub async fn total_balance(&self) -> Result<u64, JsError> {
+        let inner = Arc::clone(&self.inner);
+        let balance = run(async move { inner.total_balance().await }).await??;
+
+        Ok(balance.into())
+    }
+
+    /// Request a mint quote
+    #[wasm_bindgen(js_name = mintQuote)]
+    pub async fn mint_quote(
+        &self,
+        mint_url: String,
+        amount: u64,
+        description: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let quote = run(async move {
+            inner
+                .mint_quote(&mint_url, Amount::from(amount), description)
+                .await
+        })
+        .await??;
+
+        to_js(&quote)
+    }
+
+    /// Check the state of a mint quote
+    #[wasm_bindgen(js_name = checkMintQuote)]
+    pub async fn check_mint_quote(
+        &self,
+        mint_url: String,
+        quote_id: String,
+    ) -> Result<JsValue, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let quote =
+            run(async move { inner.check_mint_quote(&mint_url, &quote_id).await }).await??;
+
+        to_js(&quote)
+    }
+
+    /// Mint the proofs of a paid quote, returning the minted amount
+    pub async fn mint(&self, mint_url: String, quote_id: String) -> Result<u64, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let proofs = run(async move { inner.mint(&mint_url, &quote_id, None).await }).await??;
+
+        Ok(proofs.total_amount()?.into())
+    }
+
+    /// Request a melt quote for a bolt11 invoice
+    #[wasm_bindgen(js_name = meltQuote)]
+    pub async fn melt_quote(&self, mint_url: String, bolt11: String) -> Result<JsValue, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let quote = run(async move { inner.melt_quote(&mint_url, bolt11, None).await }).await??;
+
+        to_js(&quote)
+    }
+
+    /// Pay a melt quote
+    pub async fn melt(&self, mint_url: String, quote_id: String) -> Result<JsValue, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let melted = run(async move { inner.melt_with_mint(&mint_url, &quote_id).await }).await??;
+
+        to_js(&melted)
+    }
+
+    /// Send an amount from a mint, returning the encoded token
+    pub async fn send(
+        &self,
+        mint_url: String,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<String, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let token = run(async move {
+            let prepared = inner
+                .prepare_send(
+                    mint_url,
+                    Amount::from(amount),
+                    MultiMintSendOptions::default(),
+                )
+                .await?;
+
+            prepared
+                .confirm(memo.as_deref().map(SendMemo::for_token))
+                .await
+        })
+        .await??;
+
+        Ok(token.to_string())
+    }
+
+    /// Receive an encoded token, returning the received amount
+    ///
+    /// Tokens from mints that are not in the wallet are rejected.
+    pub async fn receive(&self, token: String) -> Result<u64, JsError> {
+        let inner = Arc::clone(&self.inner);
+        let amount = run(async move {
+            inner
+                .receive(&token, MultiMintReceiveOptions::default())
+                .await
+        })
+        .await??;
+
+        Ok(amount.into())
+    }
+
+    /// Restore the proofs of a mint from the seed, returning the restored amount
+    pub async fn restore(&self, mint_url: String) -> Result<u64, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let inner = Arc::clone(&self.inner);
+        let amount = run(async move { inner.restore(&mint_url).await }).await??;
+
+        Ok(amount.into())
+    }
+
+    /// Subscribe to NUT-17 notifications of a mint
+    ///
+    /// `kind` is one of `bolt11_mint_quote`, `bolt11_melt_quote`, `bolt12_mint_quote` or
+    /// `proof_state`, and `filters` are the quote ids or proof Ys to watch. The callback is called
+    /// with every notification payload until the subscription is closed.
+    pub async fn subscribe(
+        &self,
+        mint_url: String,
+        kind: String,
+        filters: Vec<String>,
+        callback: js_sys::Function,
+    ) -> Result<Subscription, JsError> {
+        let mint_url = MintUrl::from_str(&mint_url)?;
+        let kind: Kind = serde_json::from_value(serde_json::Value::String(kind))?;
+        let inner = Arc::clone(&self.inner);
+
+        let (subscription, mut closed) = Subscription::new();
+        let id = subscription.id();
+
+        run(async move {
+            let wallet = inner
+                .get_wallet(&mint_url)
+                .await
+                .ok_or(cdk::Error::UnknownMint {
+                    mint_url: mint_url.to_string(),
+                })?;
+
+            let mut active = wallet
+                .subscribe(Params {
+                    kind,
+                    filters,
+                    id: Arc::new(id),
+                })
+                .await;
+
+            tokio::task::spawn_local(async move {
+                loop {
+                    tokio::select! {
+                        _ = &mut closed => break,
+                        event = active.recv() => {
+                            let Some(event) = event else {
+                                break;
+                            };
+
+                            let payload: NotificationPayload<String> = event.into();
+                            match to_js(&payload) {
+                                Ok(payload) => {
+                                    if let Err(err) = callback.call1(&JsValue::NULL, &payload) {
+                                        tracing::warn!("Subscription callback failed: {:?}", err);
+                                    }
+                                }
+                                Err(err) => {
+                                    tracing::warn!("Could not encode notification: {:?}", err);
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+
+            Ok::<_, cdk::Error>(())
+        })
+        .await??;
+
+        Ok(subscription)
+    }
+}
@@ -0,0 +1,36 @@
+//! Task runtime
+//!
+//! The wallet spawns its background tasks with `tokio::task::spawn_local`, which needs a
+//! [`LocalSet`]. Browsers have no tokio runtime, so a single set lives for the whole page and is
+//! driven by the browser event loop. Every call into the wallet runs inside it.
+
+use std::future::Future;
+use std::rc::Rc;
+
+use tokio::task::LocalSet;
+use wasm_bindgen::JsError;
+
+thread_local! {
+    static LOCAL_SET: Rc<LocalSet> = {
+        let local_set = Rc::new(LocalSet::new());
+        let driver = Rc::clone(&local_set);
+
+        wasm_bindgen_futures::spawn_local(async move {
+            driver.run_until(std::future::pending::<()>()).await;
+        });
+
+        local_set
+    };
+}
+
+/// Runs the future on the page [`LocalSet`] and waits for its output
+pub(crate) async fn run<F>(future: F) -> Result<F::Output, JsError>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    LOCAL_SET
+        .with(|local_set| local_set.spawn_local(future))
+        .await
+        .map_err(|err| JsError::new(&format!("Wallet task failed: {err}")))
+}
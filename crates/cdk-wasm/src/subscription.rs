@@ -0,0 +1,43 @@
+//! JS handle of a NUT-17 subscription
+
+use tokio::sync::oneshot;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+
+/// Active subscription, notifications stop once it is closed or garbage collected
+#[wasm_bindgen]
+pub struct Subscription {
+    id: String,
+    close: Option<oneshot::Sender<()>>,
+}
+
+impl Subscription {
+    /// New subscription and the receiver resolving when it is closed
+    pub(crate) fn new() -> (Self, oneshot::Receiver<()>) {
+        let (close, closed) = oneshot::channel();
+
+        (
+            Self {
+                id: Uuid::new_v4().to_string(),
+                close: Some(close),
+            },
+            closed,
+        )
+    }
+}
+
+#[wasm_bindgen]
+impl Subscription {
+    /// Subscription id
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Stop the notifications and unsubscribe from the mint
+    pub fn close(&mut self) {
+        if let Some(close) = self.close.take() {
+            let _ = close.send(());
+        }
+    }
+}
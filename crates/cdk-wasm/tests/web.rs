@@ -0,0 +1,197 @@
+//! Browser tests
+//!
+//! Run headless with `just wasm-test`, or with `just wasm-test-mint` to also run the wallet flows
+//! against a fake mint.
+#![cfg(target_arch = "wasm32")]
+
+use std::str::FromStr;
+
+use cdk_common::common::Melted;
+use cdk_common::database::WalletDatabase;
+use cdk_common::mint_url::MintUrl;
+use cdk_common::nuts::{MeltQuoteState, MintQuoteState};
+use cdk_common::wallet::{MeltQuote, MintQuote};
+use cdk_common::{wallet_db_test, MintInfo};
+use cdk_wasm::multi_mint_wallet::generate_mnemonic;
+use cdk_wasm::{MultiMintWallet, WalletIndexedDb};
+use serde::de::DeserializeOwned;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Url of the fake mint of `just wasm-test-mint`, the wallet flows are skipped without it
+const MINT_URL: Option<&str> = option_env!("CDK_TEST_MINT_URL");
+
+fn database_name(test_name: &str) -> String {
+    format!("cdk_wasm_{}_{}", test_name, js_sys::Date::now())
+}
+
+async fn provide_db(test_id: String) -> WalletIndexedDb {
+    WalletIndexedDb::new(&test_id).await.expect("database")
+}
+
+wallet_db_test!(
+    #[wasm_bindgen_test]
+    provide_db
+);
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> T {
+    serde_wasm_bindgen::from_value(value).expect("value")
+}
+
+async fn create_wallet(mnemonic: &str, test_name: &str, mint_url: &str) -> MultiMintWallet {
+    let wallet = MultiMintWallet::create(
+        mnemonic.to_owned(),
+        "sat".to_owned(),
+        database_name(test_name),
+    )
+    .await
+    .unwrap();
+    wallet.add_mint(mint_url.to_owned()).await.unwrap();
+
+    wallet
+}
+
+/// Mints `amount` once the subscription notifies that its quote is paid
+async fn fund(wallet: &MultiMintWallet, mint_url: &str, amount: u64) {
+    let quote: MintQuote = from_js(
+        wallet
+            .mint_quote(mint_url.to_owned(), amount, None)
+            .await
+            .unwrap(),
+    );
+
+    let (sender, mut notifications) = tokio::sync::mpsc::unbounded_channel();
+    let callback = Closure::<dyn FnMut(JsValue)>::new(move |payload: JsValue| {
+        let _ = sender.send(from_js::<serde_json::Value>(payload));
+    });
+    let mut subscription = wallet
+        .subscribe(
+            mint_url.to_owned(),
+            "bolt11_mint_quote".to_owned(),
+            vec![quote.id.clone()],
+            callback
+                .as_ref()
+                .unchecked_ref::<js_sys::Function>()
+                .clone(),
+        )
+        .await
+        .unwrap();
+
+    while let Some(payload) = notifications.recv().await {
+        assert_eq!(payload["quote"], quote.id);
+        if payload["state"] == serde_json::json!(MintQuoteState::Paid) {
+            break;
+        }
+    }
+    subscription.close();
+
+    assert_eq!(
+        wallet.mint(mint_url.to_owned(), quote.id).await.unwrap(),
+        amount
+    );
+}
+
+#[wasm_bindgen_test]
+async fn mint_send_receive_melt_and_restore() {
+    let Some(mint_url) = MINT_URL else {
+        return;
+    };
+    let mnemonic = generate_mnemonic().unwrap();
+    let wallet = create_wallet(&mnemonic, "flows", mint_url).await;
+
+    fund(&wallet, mint_url, 100).await;
+    assert_eq!(wallet.total_balance().await.unwrap(), 100);
+
+    // Send to another wallet
+    let token = wallet
+        .send(mint_url.to_owned(), 10, Some("coffee".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(wallet.total_balance().await.unwrap(), 90);
+
+    let receiver = create_wallet(&generate_mnemonic().unwrap(), "receiver", mint_url).await;
+    assert_eq!(receiver.receive(token.clone()).await.unwrap(), 10);
+    assert_eq!(receiver.total_balance().await.unwrap(), 10);
+    assert!(receiver.receive(token).await.is_err());
+
+    // Pay an invoice of the receiver
+    let invoice: MintQuote = from_js(
+        receiver
+            .mint_quote(mint_url.to_owned(), 5, None)
+            .await
+            .unwrap(),
+    );
+    let melt_quote: MeltQuote = from_js(
+        wallet
+            .melt_quote(mint_url.to_owned(), invoice.request)
+            .await
+            .unwrap(),
+    );
+    assert_eq!(u64::from(melt_quote.amount), 5);
+    let melted: Melted = from_js(
+        wallet
+            .melt(mint_url.to_owned(), melt_quote.id)
+            .await
+            .unwrap(),
+    );
+    assert_eq!(melted.state, MeltQuoteState::Paid);
+    let balance = wallet.total_balance().await.unwrap();
+    assert_eq!(balance, 85 - u64::from(melted.fee_paid));
+
+    // The seed restores the balance in a new database
+    let restored = create_wallet(&mnemonic, "restored", mint_url).await;
+    assert_eq!(
+        restored.restore(mint_url.to_owned()).await.unwrap(),
+        balance
+    );
+    assert_eq!(restored.total_balance().await.unwrap(), balance);
+}
+
+#[wasm_bindgen_test]
+async fn reopened_database_keeps_records() {
+    let name = database_name("reopen");
+    let mint_url = MintUrl::from_str("https://mint.example.com").unwrap();
+    let mint_info = MintInfo {
+        name: Some("Test mint".to_owned()),
+        ..Default::default()
+    };
+
+    let db = WalletIndexedDb::new(&name).await.expect("database");
+    db.add_mint(mint_url.clone(), Some(mint_info.clone()))
+        .await
+        .unwrap();
+    drop(db);
+
+    let db = WalletIndexedDb::new(&name).await.expect("database");
+    assert_eq!(db.get_mint(mint_url).await.unwrap(), Some(mint_info));
+}
+
+#[wasm_bindgen_test]
+async fn new_wallet_is_empty() {
+    let wallet = MultiMintWallet::create(
+        generate_mnemonic().unwrap(),
+        "sat".to_owned(),
+        database_name("wallet"),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(wallet.unit(), "sat");
+    assert_eq!(wallet.total_balance().await.unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn invalid_mint_url_is_rejected() {
+    let wallet = MultiMintWallet::create(
+        generate_mnemonic().unwrap(),
+        "sat".to_owned(),
+        database_name("invalid_mint"),
+    )
+    .await
+    .unwrap();
+
+    assert!(wallet.add_mint("not a url".to_owned()).await.is_err());
+}
@@ -50,6 +50,8 @@ pub use cdk_common::{
 };
 #[cfg(all(any(feature = "wallet", feature = "mint"), feature = "auth"))]
 pub use oidc_client::OidcClient;
+#[cfg(all(feature = "wallet", feature = "auth"))]
+pub use oidc_client::PkceChallenge;
 
 #[cfg(any(feature = "wallet", feature = "mint"))]
 pub mod event;
@@ -36,6 +36,9 @@ pub struct MintBuilder {
     payment_processors: HashMap<PaymentProcessorKey, DynMintPayment>,
     supported_units: HashMap<CurrencyUnit, (u64, u8)>,
     custom_paths: HashMap<CurrencyUnit, DerivationPath>,
+    bolt11_mint_quote_settings: Bolt11MintQuoteSettings,
+    /// Settings reported by each payment processor when it was added
+    processor_settings: HashMap<PaymentProcessorKey, Bolt11Settings>,
 }
 
 impl MintBuilder {
@@ -62,6 +65,8 @@ impl MintBuilder {
             payment_processors: HashMap::new(),
             supported_units: HashMap::new(),
             custom_paths: HashMap::new(),
+            bolt11_mint_quote_settings: Bolt11MintQuoteSettings::default(),
+            processor_settings: HashMap::new(),
         }
     }
 
@@ -214,6 +219,30 @@ impl MintBuilder {
         self
     }
 
+    /// Advertise the server-sent events transport of the NUT-17 notifications
+    pub fn with_sse_subscriptions(mut self) -> Self {
+        self.mint_info.nuts.nut17.sse = true;
+
+        self
+    }
+
+    /// Advertise batch quote checks and batch minting [NUT-29] for a payment method
+    ///
+    /// The mint rejects batches of more than `max_batch_size` quotes.
+    pub fn with_batch_minting(
+        mut self,
+        method: PaymentMethod,
+        max_batch_size: Option<u64>,
+    ) -> Self {
+        let nut29 = &mut self.mint_info.nuts.nut29;
+        if !nut29.supports(&method) {
+            nut29.methods.push(method);
+        }
+        nut29.max_batch_size = max_batch_size;
+
+        self
+    }
+
     /// Add support for NUT19
     pub fn with_cache(mut self, ttl: Option<u64>, cached_endpoints: Vec<CachedEndpoint>) -> Self {
         let nut19_settings = nut19::Settings {
@@ -235,6 +264,15 @@ impl MintBuilder {
         self
     }
 
+    /// Set bolt11 mint quote settings
+    ///
+    /// The advertised NUT-04 options of every payment processor follow these settings when the
+    /// mint is built, whether the processors are added before or after.
+    pub fn with_bolt11_mint_quote_settings(mut self, settings: Bolt11MintQuoteSettings) -> Self {
+        self.bolt11_mint_quote_settings = settings;
+        self
+    }
+
     /// Add payment processor
     pub async fn add_payment_processor(
         &mut self,
@@ -270,9 +308,10 @@ impl MintBuilder {
             unit: unit.clone(),
             min_amount: Some(limits.mint_min),
             max_amount: Some(limits.mint_max),
-            options: Some(MintMethodOptions::Bolt11 {
-                description: settings.invoice_description,
-            }),
+            options: Some(
+                self.bolt11_mint_quote_settings
+                    .mint_method_options(&settings),
+            ),
         };
 
         self.mint_info.nuts.nut04.methods.push(mint_method_settings);
@@ -295,6 +334,7 @@ impl MintBuilder {
         supported_units.insert(key.unit.clone(), (0, 32));
         self.supported_units = supported_units;
 
+        self.processor_settings.insert(key.clone(), settings);
         self.payment_processors.insert(key, payment_processor);
         Ok(())
     }
@@ -314,27 +354,44 @@ impl MintBuilder {
 
     /// Build the mint with the provided signatory
     pub async fn build_with_signatory(
-        self,
+        mut self,
         signatory: Arc<dyn Signatory + Send + Sync>,
     ) -> Result<Mint, Error> {
+        let bolt11_mint_quote_settings = self.bolt11_mint_quote_settings;
+
+        // The quote settings may have been set after the payment processors were added
+        for method in self.mint_info.nuts.nut04.methods.iter_mut() {
+            let key = PaymentProcessorKey {
+                unit: method.unit.clone(),
+                method: method.method.clone(),
+            };
+            if let Some(settings) = self.processor_settings.get(&key) {
+                method.options = Some(bolt11_mint_quote_settings.mint_method_options(settings));
+            }
+        }
+
         #[cfg(feature = "auth")]
         if let Some(auth_localstore) = self.auth_localstore {
-            return Mint::new_with_auth(
+            let mut mint = Mint::new_with_auth(
                 self.mint_info,
                 signatory,
                 self.localstore,
                 auth_localstore,
                 self.payment_processors,
             )
-            .await;
+            .await?;
+            mint.bolt11_mint_quote_settings = bolt11_mint_quote_settings;
+            return Ok(mint);
         }
-        Mint::new(
+        let mut mint = Mint::new(
             self.mint_info,
             signatory,
             self.localstore,
             self.payment_processors,
         )
-        .await
+        .await?;
+        mint.bolt11_mint_quote_settings = bolt11_mint_quote_settings;
+        Ok(mint)
     }
 
     /// Build the mint with the provided keystore and seed
@@ -384,6 +441,49 @@ impl MintMeltLimits {
     }
 }
 
+/// Bolt11 mint quote settings
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bolt11MintQuoteSettings {
+    /// Description used when the wallet does not provide one
+    ///
+    /// `{amount}` and `{unit}` are replaced with the quote amount and unit
+    pub default_description: Option<String>,
+    /// Allow wallets to set the invoice description
+    pub allow_description: bool,
+    /// Allow wallets to set the invoice description hash
+    pub allow_description_hash: bool,
+    /// Min quote expiry in seconds a wallet can request
+    pub min_expiry: Option<u64>,
+    /// Max quote expiry in seconds a wallet can request
+    pub max_expiry: Option<u64>,
+}
+
+impl Bolt11MintQuoteSettings {
+    /// NUT-04 options of a payment processor with the settings
+    ///
+    /// Wallet descriptions are only offered when both the backend and the settings allow them.
+    pub(crate) fn mint_method_options(&self, settings: &Bolt11Settings) -> MintMethodOptions {
+        MintMethodOptions::Bolt11 {
+            description: settings.invoice_description && self.allow_description,
+            description_hash: settings.invoice_description_hash && self.allow_description_hash,
+            min_expiry: self.min_expiry,
+            max_expiry: self.max_expiry,
+        }
+    }
+}
+
+impl Default for Bolt11MintQuoteSettings {
+    fn default() -> Self {
+        Self {
+            default_description: None,
+            allow_description: true,
+            allow_description_hash: true,
+            min_expiry: None,
+            max_expiry: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -431,4 +531,57 @@ mod tests {
             "NUT-20 should be supported by default"
         );
     }
+
+    #[tokio::test]
+    async fn test_bolt11_mint_quote_settings_after_processors() {
+        use std::collections::HashSet;
+
+        use cdk_fake_wallet::FakeWallet;
+
+        use crate::types::FeeReserve;
+
+        let localstore = Arc::new(memory::empty().await.unwrap());
+        let mut builder = MintBuilder::new(localstore.clone());
+
+        let fake_wallet = FakeWallet::new(
+            FeeReserve {
+                min_fee_reserve: 1.into(),
+                percent_fee_reserve: 1.0,
+            },
+            HashMap::default(),
+            HashSet::default(),
+            0,
+            CurrencyUnit::Sat,
+        );
+        builder
+            .add_payment_processor(
+                CurrencyUnit::Sat,
+                PaymentMethod::Bolt11,
+                MintMeltLimits::new(1, 10_000),
+                Arc::new(fake_wallet),
+            )
+            .await
+            .unwrap();
+
+        let mint = builder
+            .with_bolt11_mint_quote_settings(Bolt11MintQuoteSettings {
+                allow_description: false,
+                max_expiry: Some(600),
+                ..Default::default()
+            })
+            .build_with_seed(localstore, &[0u8; 32])
+            .await
+            .unwrap();
+
+        let mint_info = mint.mint_info().await.unwrap();
+        assert_eq!(
+            mint_info.nuts.nut04.methods[0].options,
+            Some(MintMethodOptions::Bolt11 {
+                description: false,
+                description_hash: true,
+                min_expiry: None,
+                max_expiry: Some(600),
+            })
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use cdk_common::mint::{MintQuote, Operation};
 use cdk_common::payment::{
     Bolt11IncomingPaymentOptions, Bolt11Settings, Bolt12IncomingPaymentOptions,
@@ -6,9 +8,10 @@ use cdk_common::payment::{
 use cdk_common::quote_id::QuoteId;
 use cdk_common::util::unix_time;
 use cdk_common::{
-    database, ensure_cdk, Amount, CurrencyUnit, Error, MintQuoteBolt11Request,
-    MintQuoteBolt11Response, MintQuoteBolt12Request, MintQuoteBolt12Response, MintQuoteState,
-    MintRequest, MintResponse, NotificationPayload, PaymentMethod, PublicKey,
+    database, ensure_cdk, Amount, BatchMintRequest, BatchMintResponse, BlindSignature,
+    CurrencyUnit, Error, MintQuoteBolt11Request, MintQuoteBolt11Response, MintQuoteBolt12Request,
+    MintQuoteBolt12Response, MintQuoteState, MintRequest, MintResponse, NotificationPayload,
+    PaymentMethod, PublicKey,
 };
 #[cfg(feature = "prometheus")]
 use cdk_prometheus::METRICS;
@@ -19,6 +22,8 @@ use crate::Mint;
 
 #[cfg(feature = "auth")]
 mod auth;
+#[cfg(test)]
+mod tests;
 
 /// Request for creating a mint quote
 ///
@@ -204,9 +209,58 @@ impl Mint {
             );
         }
 
+        if let MintQuoteRequest::Bolt11(bolt11_request) = mint_quote_request {
+            let options = settings.options.as_ref();
+
+            ensure_cdk!(
+                bolt11_request.description.is_none() || bolt11_request.description_hash.is_none(),
+                Error::InvoiceDescriptionAndHash
+            );
+
+            if bolt11_request.description.is_some() {
+                ensure_cdk!(
+                    options.is_some_and(|options| options.description()),
+                    Error::InvoiceDescriptionUnsupported
+                );
+            }
+
+            if bolt11_request.description_hash.is_some() {
+                ensure_cdk!(
+                    options.is_some_and(|options| options.description_hash()),
+                    Error::InvoiceDescriptionHashUnsupported
+                );
+            }
+
+            if let Some(expiry_secs) = bolt11_request.expiry_secs {
+                // Without a configured max expiry quotes cannot outlive the mint quote ttl
+                let max_expiry = match options.and_then(|options| options.max_expiry()) {
+                    Some(max_expiry) => max_expiry,
+                    None => self.quote_ttl().await?.mint_ttl,
+                };
+
+                ensure_cdk!(
+                    expiry_secs <= max_expiry
+                        && options.is_none_or(|options| options.expiry_allowed(expiry_secs)),
+                    Error::QuoteExpiryOutOfRange(expiry_secs)
+                );
+            }
+        }
+
         Ok(())
     }
 
+    /// Fill in the invoice description template with the quote details
+    fn default_invoice_description(&self, amount: Amount, unit: &CurrencyUnit) -> Option<String> {
+        self.bolt11_mint_quote_settings
+            .default_description
+            .as_ref()
+            .map(|template| {
+                template
+                    .replace("{amount}", &amount.to_string())
+                    .replace("{unit}", &unit.to_string())
+            })
+    }
+
     /// Creates a new mint quote for the specified payment request
     ///
     /// Handles both Bolt11 and Bolt12 payment requests by:
@@ -248,20 +302,36 @@ impl Mint {
                 MintQuoteRequest::Bolt11(bolt11_request) => {
                     let mint_ttl = self.quote_ttl().await?.mint_ttl;
 
-                    let quote_expiry = unix_time() + mint_ttl;
+                    let expiry_secs = bolt11_request.expiry_secs.unwrap_or(mint_ttl);
+                    let quote_expiry = unix_time()
+                        .checked_add(expiry_secs)
+                        .ok_or(Error::QuoteExpiryOutOfRange(expiry_secs))?;
 
                     let settings = ln.get_settings().await?;
                     let settings: Bolt11Settings = serde_json::from_value(settings)?;
 
-                    let description = bolt11_request.description;
-
-                    if description.is_some() && !settings.invoice_description {
+                    if bolt11_request.description.is_some() && !settings.invoice_description {
                         tracing::error!("Backend does not support invoice description");
                         return Err(Error::InvoiceDescriptionUnsupported);
                     }
 
+                    if bolt11_request.description_hash.is_some()
+                        && !settings.invoice_description_hash
+                    {
+                        tracing::error!("Backend does not support invoice description hash");
+                        return Err(Error::InvoiceDescriptionHashUnsupported);
+                    }
+
+                    let description = match bolt11_request.description_hash {
+                        Some(_) => None,
+                        None => bolt11_request.description.or_else(|| {
+                            self.default_invoice_description(bolt11_request.amount, &unit)
+                        }),
+                    };
+
                     let bolt11_options = Bolt11IncomingPaymentOptions {
                         description,
+                        description_hash: bolt11_request.description_hash,
                         amount: bolt11_request.amount,
                         unix_expiry: Some(quote_expiry),
                     };
@@ -556,14 +626,218 @@ impl Mint {
             if mint_quote.payment_method == PaymentMethod::Bolt11 {
                 self.check_mint_quote_paid(&mut mint_quote).await?;
             }
-        // get the blind signatures before having starting the db transaction, if there are any
-        // rollbacks this blind_signatures will be lost, and the signature is stateless. It is not a
-        // good idea to call an external service (which is really a trait, it could be anything
-        // anywhere) while keeping a database transaction on-going
-        let blind_signatures = self.blind_sign(mint_request.outputs.clone()).await?;
 
-        let mut tx = self.localstore.begin_transaction().await?;
+            // get the blind signatures before having starting the db transaction, if there are any
+            // rollbacks this blind_signatures will be lost, and the signature is stateless. It is not a
+            // good idea to call an external service (which is really a trait, it could be anything
+            // anywhere) while keeping a database transaction on-going
+            let blind_signatures = self.blind_sign(mint_request.outputs.clone()).await?;
+
+            let mut tx = self.localstore.begin_transaction().await?;
+
+            let (mint_quote, total_issued) = self
+                .issue_mint_request(&mut tx, &mint_request, &blind_signatures)
+                .await?;
+
+            tx.commit().await?;
+
+            self.pubsub_manager
+                .mint_quote_issue(&mint_quote, total_issued);
+
+            Ok(MintResponse {
+                signatures: blind_signatures,
+            })
+        }
+        .await;
+
+        #[cfg(feature = "prometheus")]
+        {
+            METRICS.dec_in_flight_requests("process_mint_request");
+            METRICS.record_mint_operation("process_mint_request", result.is_ok());
+            if result.is_err() {
+                METRICS.record_error();
+            }
+        }
+        result
+    }
+
+    /// Checks the status of many mint quotes at once [NUT-29]
+    ///
+    /// Unknown quotes are left out of the response, the others are checked like
+    /// [`Mint::check_mint_quote`] and returned in the order of the request.
+    #[instrument(skip_all)]
+    pub async fn check_mint_quotes(
+        &self,
+        quote_ids: &[QuoteId],
+    ) -> Result<Vec<MintQuoteResponse>, Error> {
+        self.check_batch_size(quote_ids.len()).await?;
+
+        let mut responses = Vec::with_capacity(quote_ids.len());
+        for quote_id in quote_ids {
+            match self.check_mint_quote(quote_id).await {
+                Ok(response) => responses.push(response),
+                Err(Error::UnknownQuote) => {
+                    tracing::debug!("Skipping unknown quote {} of batch check", quote_id);
+                }
+                Err(err) => return Err(err),
+            }
+        }
 
+        Ok(responses)
+    }
+
+    /// Paid bolt11 mint quotes of `unit` locked to `pubkey`, newest first
+    ///
+    /// Lets the owner of a NUT-20 key find the quotes it did not create itself, like the
+    /// quotes of a lightning address. Returns at most `limit` quotes.
+    #[instrument(skip(self))]
+    pub async fn paid_mint_quotes_by_pubkey(
+        &self,
+        pubkey: &PublicKey,
+        unit: &CurrencyUnit,
+        limit: u64,
+    ) -> Result<Vec<MintQuoteBolt11Response<QuoteId>>, Error> {
+        let filter = database::mint::MintQuoteFilter {
+            unit: Some(unit.clone()),
+            state: Some(MintQuoteState::Paid),
+            payment_method: Some(PaymentMethod::Bolt11),
+            pubkey: Some(*pubkey),
+            ..Default::default()
+        };
+
+        let (quotes, _) = self.localstore.list_mint_quotes(&filter, 0, limit).await?;
+
+        Ok(quotes.into_iter().map(Into::into).collect())
+    }
+
+    /// Processes a batch of mint requests [NUT-29]
+    ///
+    /// Every request is validated like in [`Mint::process_mint_request`], including its own
+    /// NUT-20 signature, and all of them are issued in a single database transaction, so either
+    /// every quote of the batch is minted or none is.
+    ///
+    /// # Returns
+    /// * `BatchMintResponse` - One response per request, in the order of the request
+    /// * `Error` if any of the requests is invalid
+    #[instrument(skip_all)]
+    pub async fn process_batch_mint_request(
+        &self,
+        batch: BatchMintRequest<QuoteId>,
+    ) -> Result<BatchMintResponse, Error> {
+        #[cfg(feature = "prometheus")]
+        METRICS.inc_in_flight_requests("process_batch_mint_request");
+        let result = async {
+            ensure_cdk!(!batch.requests.is_empty(), Error::EmptyBatch);
+            self.check_batch_size(batch.requests.len()).await?;
+
+            let mut quote_ids = HashSet::new();
+            ensure_cdk!(
+                batch
+                    .requests
+                    .iter()
+                    .all(|request| quote_ids.insert(request.quote.clone())),
+                Error::DuplicateQuote
+            );
+
+            // Outputs are only checked against the database one request at a time
+            let mut blinded_secrets = HashSet::new();
+            ensure_cdk!(
+                batch
+                    .requests
+                    .iter()
+                    .flat_map(|request| request.outputs.iter())
+                    .all(|output| blinded_secrets.insert(output.blinded_secret)),
+                Error::DuplicateOutputs
+            );
+
+            let supported_methods = self.mint_info().await?.nuts.nut29;
+            for request in &batch.requests {
+                let mut mint_quote = self
+                    .localstore
+                    .get_mint_quote(&request.quote)
+                    .await?
+                    .ok_or(Error::UnknownQuote)?;
+
+                ensure_cdk!(
+                    supported_methods.supports(&mint_quote.payment_method),
+                    Error::UnsupportedPaymentMethod
+                );
+
+                if mint_quote.payment_method == PaymentMethod::Bolt11 {
+                    self.check_mint_quote_paid(&mut mint_quote).await?;
+                }
+            }
+
+            // Signed in one call before the transaction, see `process_mint_request`
+            let mut blind_signatures = self
+                .blind_sign(
+                    batch
+                        .requests
+                        .iter()
+                        .flat_map(|request| request.outputs.iter().cloned())
+                        .collect(),
+                )
+                .await?;
+
+            let mut tx = self.localstore.begin_transaction().await?;
+
+            let mut issued = Vec::with_capacity(batch.requests.len());
+            let mut responses = Vec::with_capacity(batch.requests.len());
+            for request in &batch.requests {
+                let rest = blind_signatures.split_off(request.outputs.len());
+                let signatures = std::mem::replace(&mut blind_signatures, rest);
+
+                issued.push(
+                    self.issue_mint_request(&mut tx, request, &signatures)
+                        .await?,
+                );
+                responses.push(MintResponse { signatures });
+            }
+
+            tx.commit().await?;
+
+            for (mint_quote, total_issued) in issued {
+                self.pubsub_manager
+                    .mint_quote_issue(&mint_quote, total_issued);
+            }
+
+            Ok(BatchMintResponse { responses })
+        }
+        .await;
+
+        #[cfg(feature = "prometheus")]
+        {
+            METRICS.dec_in_flight_requests("process_batch_mint_request");
+            METRICS.record_mint_operation("process_batch_mint_request", result.is_ok());
+            if result.is_err() {
+                METRICS.record_error();
+            }
+        }
+        result
+    }
+
+    /// Rejects batches larger than the advertised maximum batch size
+    async fn check_batch_size(&self, size: usize) -> Result<(), Error> {
+        if let Some(max) = self.mint_info().await?.nuts.nut29.max_batch_size {
+            let max = usize::try_from(max).unwrap_or(usize::MAX);
+            if size > max {
+                return Err(Error::MaxBatchSizeExceeded(size, max));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a mint request against its quote and stores its outputs and signatures
+    ///
+    /// Returns the quote and its new total issued amount, to notify subscribers once the
+    /// transaction is committed.
+    async fn issue_mint_request(
+        &self,
+        tx: &mut Box<dyn database::MintTransaction<'_, database::Error> + Send + Sync + '_>,
+        mint_request: &MintRequest<QuoteId>,
+        blind_signatures: &[BlindSignature],
+    ) -> Result<(MintQuote, Amount), Error> {
         let mint_quote = tx
             .get_mint_quote(&mint_request.quote)
             .await?
@@ -595,19 +869,23 @@ impl Mint {
                 let quote_amount = mint_quote.amount.ok_or(Error::AmountUndefined)?;
 
                 if quote_amount != mint_quote.amount_mintable() {
-                    tracing::error!("The quote amount {} does not equal the amount paid {}.", quote_amount, mint_quote.amount_mintable());
+                    tracing::error!(
+                        "The quote amount {} does not equal the amount paid {}.",
+                        quote_amount,
+                        mint_quote.amount_mintable()
+                    );
                     return Err(Error::IncorrectQuoteAmount);
                 }
 
                 quote_amount
-            },
+            }
             PaymentMethod::Bolt12 => {
-                if mint_quote.amount_mintable() == Amount::ZERO{
+                if mint_quote.amount_mintable() == Amount::ZERO {
                     tracing::error!(
-                            "Quote state should not be issued if issued {} is => paid {}.",
-                            mint_quote.amount_issued(),
-                            mint_quote.amount_paid()
-                        );
+                        "Quote state should not be issued if issued {} is => paid {}.",
+                        mint_quote.amount_issued(),
+                        mint_quote.amount_paid()
+                    );
                     return Err(Error::UnpaidQuote);
                 }
 
@@ -625,7 +903,7 @@ impl Mint {
         let Verification {
             amount: outputs_amount,
             unit,
-        } = match self.verify_outputs(&mut tx, &mint_request.outputs).await {
+        } = match self.verify_outputs(tx, &mint_request.outputs).await {
             Ok(verification) => verification,
             Err(err) => {
                 tracing::debug!("Could not verify mint outputs");
@@ -659,7 +937,8 @@ impl Mint {
 
         let operation = Operation::new_mint();
 
-        tx.add_blinded_messages(Some(&mint_request.quote), &mint_request.outputs, &operation).await?;
+        tx.add_blinded_messages(Some(&mint_request.quote), &mint_request.outputs, &operation)
+            .await?;
 
         tx.add_blind_signatures(
             &mint_request
@@ -667,10 +946,10 @@ impl Mint {
                 .iter()
                 .map(|p| p.blinded_secret)
                 .collect::<Vec<PublicKey>>(),
-            &blind_signatures,
+            blind_signatures,
             Some(mint_request.quote.clone()),
         )
-            .await?;
+        .await?;
 
         let amount_issued = mint_request.total_amount()?;
 
@@ -678,25 +957,6 @@ impl Mint {
             .increment_mint_quote_amount_issued(&mint_request.quote, amount_issued)
             .await?;
 
-        tx.commit().await?;
-
-        self.pubsub_manager
-            .mint_quote_issue(&mint_quote, total_issued);
-
-        Ok(MintResponse {
-            signatures: blind_signatures,
-        })
-    }
-    .await;
-
-        #[cfg(feature = "prometheus")]
-        {
-            METRICS.dec_in_flight_requests("process_mint_request");
-            METRICS.record_mint_operation("process_mint_request", result.is_ok());
-            if result.is_err() {
-                METRICS.record_error();
-            }
-        }
-        result
+        Ok((mint_quote, total_issued))
     }
 }
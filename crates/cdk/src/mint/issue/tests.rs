@@ -0,0 +1,457 @@
+//! Bolt11 mint quote option and batch minting tests
+//!
+//! These tests verify that the mint enforces its configured invoice description,
+//! description hash and quote expiry settings when creating bolt11 mint quotes,
+//! and that batches of quotes are checked and minted as a whole.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use bitcoin::hashes::{sha256, Hash};
+use cdk_common::nut05::MeltMethodOptions;
+use cdk_common::payment::Bolt11Settings;
+use cdk_common::util::unix_time;
+use cdk_common::{
+    BatchMintRequest, Bolt11Invoice, CurrencyUnit, MintQuoteBolt11Request, MintQuoteBolt11Response,
+    MintQuoteState, MintRequest, NUT29Settings, PaymentMethod, QuoteId,
+};
+use lightning_invoice::Bolt11InvoiceDescriptionRef;
+
+use crate::mint::Bolt11MintQuoteSettings;
+use crate::test_helpers::mint::{
+    create_test_blinded_messages, create_test_mint, create_test_mint_with_bolt11_settings,
+};
+use crate::{Error, Mint};
+
+fn mint_quote_request() -> MintQuoteBolt11Request {
+    MintQuoteBolt11Request {
+        amount: 100.into(),
+        unit: CurrencyUnit::Sat,
+        description: None,
+        pubkey: None,
+        description_hash: None,
+        expiry_secs: None,
+    }
+}
+
+/// Test: Description hash is committed to in the invoice
+#[tokio::test]
+async fn test_mint_quote_description_hash() {
+    let mint = create_test_mint().await.unwrap();
+    let description_hash = sha256::Hash::hash(b"lnurl metadata");
+
+    let quote: MintQuoteBolt11Response<String> = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                description_hash: Some(description_hash),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await
+        .unwrap()
+        .into();
+
+    let invoice = Bolt11Invoice::from_str(&quote.request).unwrap();
+
+    match invoice.description() {
+        Bolt11InvoiceDescriptionRef::Hash(hash) => assert_eq!(hash.0, description_hash),
+        Bolt11InvoiceDescriptionRef::Direct(_) => panic!("Expected description hash"),
+    }
+}
+
+/// Test: Description and description hash cannot both be set
+#[tokio::test]
+async fn test_mint_quote_description_and_hash() {
+    let mint = create_test_mint().await.unwrap();
+
+    let result = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                description: Some("memo".to_string()),
+                description_hash: Some(sha256::Hash::hash(b"memo")),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::InvoiceDescriptionAndHash)));
+}
+
+/// Test: Wallet description and description hash rejected when disallowed
+#[tokio::test]
+async fn test_mint_quote_description_disallowed() {
+    let mint = create_test_mint_with_bolt11_settings(Bolt11MintQuoteSettings {
+        allow_description: false,
+        allow_description_hash: false,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let result = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                description: Some("memo".to_string()),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::InvoiceDescriptionUnsupported)));
+
+    let result = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                description_hash: Some(sha256::Hash::hash(b"memo")),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::InvoiceDescriptionHashUnsupported)
+    ));
+}
+
+/// Test: Default description template is used when the wallet sets none
+#[tokio::test]
+async fn test_mint_quote_default_description() {
+    let mint = create_test_mint_with_bolt11_settings(Bolt11MintQuoteSettings {
+        default_description: Some("Mint {amount} {unit}".to_string()),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let quote: MintQuoteBolt11Response<String> = mint
+        .get_mint_quote(mint_quote_request().into())
+        .await
+        .unwrap()
+        .into();
+
+    let invoice = Bolt11Invoice::from_str(&quote.request).unwrap();
+
+    match invoice.description() {
+        Bolt11InvoiceDescriptionRef::Direct(description) => {
+            assert_eq!(description.to_string(), "Mint 100 sat")
+        }
+        Bolt11InvoiceDescriptionRef::Hash(_) => panic!("Expected direct description"),
+    }
+}
+
+/// Test: Requested expiry is applied within bounds and rejected outside them
+#[tokio::test]
+async fn test_mint_quote_expiry_bounds() {
+    let mint = create_test_mint_with_bolt11_settings(Bolt11MintQuoteSettings {
+        min_expiry: Some(60),
+        max_expiry: Some(3600),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let result = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                expiry_secs: Some(30),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::QuoteExpiryOutOfRange(30))));
+
+    let result = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                expiry_secs: Some(7200),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::QuoteExpiryOutOfRange(7200))));
+
+    let before = unix_time();
+    let quote: MintQuoteBolt11Response<String> = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                expiry_secs: Some(600),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await
+        .unwrap()
+        .into();
+
+    let expiry = quote.expiry.unwrap();
+    assert!(expiry >= before + 600 && expiry <= unix_time() + 600);
+}
+
+/// Test: Without a configured max expiry the mint quote ttl bounds the requested expiry
+#[tokio::test]
+async fn test_mint_quote_expiry_defaults_to_mint_ttl() {
+    let mint = create_test_mint().await.unwrap();
+    let mint_ttl = mint.quote_ttl().await.unwrap().mint_ttl;
+
+    for expiry_secs in [mint_ttl + 1, u64::MAX] {
+        let result = mint
+            .get_mint_quote(
+                MintQuoteBolt11Request {
+                    expiry_secs: Some(expiry_secs),
+                    ..mint_quote_request()
+                }
+                .into(),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::QuoteExpiryOutOfRange(secs)) if secs == expiry_secs
+        ));
+    }
+
+    let quote: MintQuoteBolt11Response<String> = mint
+        .get_mint_quote(
+            MintQuoteBolt11Request {
+                expiry_secs: Some(mint_ttl),
+                ..mint_quote_request()
+            }
+            .into(),
+        )
+        .await
+        .unwrap()
+        .into();
+    assert!(quote.expiry.is_some());
+}
+
+/// Test: Backend settings changes update the advertised bolt11 options
+#[tokio::test]
+async fn test_payment_settings_changed() {
+    let mint = create_test_mint_with_bolt11_settings(Bolt11MintQuoteSettings {
+        allow_description_hash: false,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let processor = mint
+        .get_payment_processor(CurrencyUnit::Sat, PaymentMethod::Bolt11)
+        .unwrap();
+
+    let settings = Bolt11Settings {
+        mpp: false,
+        unit: CurrencyUnit::Sat,
+        invoice_description: false,
+        invoice_description_hash: true,
+        amountless: true,
+        bolt12: false,
+    };
+    mint.handle_payment_settings_changed(&processor, settings.clone())
+        .await
+        .unwrap();
+
+    let mint_info = mint.mint_info().await.unwrap();
+    let options = mint_info
+        .nuts
+        .nut04
+        .get_settings(&CurrencyUnit::Sat, &PaymentMethod::Bolt11)
+        .and_then(|settings| settings.options);
+    assert!(!options.as_ref().is_some_and(|o| o.description()));
+    // Still disabled by the mint quote settings
+    assert!(!options.as_ref().is_some_and(|o| o.description_hash()));
+
+    let mut settings = settings;
+    settings.invoice_description = true;
+    mint.handle_payment_settings_changed(&processor, settings)
+        .await
+        .unwrap();
+
+    let options = mint
+        .mint_info()
+        .await
+        .unwrap()
+        .nuts
+        .nut04
+        .get_settings(&CurrencyUnit::Sat, &PaymentMethod::Bolt11)
+        .and_then(|settings| settings.options);
+    assert!(options.as_ref().is_some_and(|o| o.description()));
+    assert!(!options.as_ref().is_some_and(|o| o.description_hash()));
+
+    assert!(matches!(
+        mint_info
+            .nuts
+            .nut05
+            .get_settings(&CurrencyUnit::Sat, &PaymentMethod::Bolt11)
+            .and_then(|settings| settings.options),
+        Some(MeltMethodOptions::Bolt11 { amountless: true })
+    ));
+    assert!(mint_info.nuts.nut15.methods.is_empty());
+}
+
+/// Creates a bolt11 mint quote and waits for the fake backend to pay it
+async fn paid_mint_quote(mint: &Mint) -> QuoteId {
+    let quote: MintQuoteBolt11Response<String> = mint
+        .get_mint_quote(mint_quote_request().into())
+        .await
+        .unwrap()
+        .into();
+    let quote_id = QuoteId::from_str(&quote.quote).unwrap();
+
+    loop {
+        let check: MintQuoteBolt11Response<QuoteId> = mint
+            .check_mint_quote(&quote_id)
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        if check.state == MintQuoteState::Paid {
+            return quote_id;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Creates the mint request of a paid quote
+async fn mint_request(mint: &Mint, quote: &QuoteId) -> MintRequest<QuoteId> {
+    let (outputs, _) = create_test_blinded_messages(mint, 100.into())
+        .await
+        .unwrap();
+
+    MintRequest {
+        quote: quote.clone(),
+        outputs,
+        signature: None,
+    }
+}
+
+/// Test: Batch quote check returns the known quotes in order
+#[tokio::test]
+async fn test_check_mint_quotes() {
+    let mint = create_test_mint().await.unwrap();
+
+    let first = paid_mint_quote(&mint).await;
+    let second = paid_mint_quote(&mint).await;
+    let unknown = QuoteId::new_uuid();
+
+    let quotes = mint
+        .check_mint_quotes(&[second.clone(), unknown, first.clone()])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|quote| MintQuoteBolt11Response::<QuoteId>::try_from(quote).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(quotes[0].quote, second);
+    assert_eq!(quotes[1].quote, first);
+}
+
+/// Test: A batch mint issues every quote of the batch
+#[tokio::test]
+async fn test_batch_mint() {
+    let mint = create_test_mint().await.unwrap();
+    let mut mint_info = mint.mint_info().await.unwrap();
+    mint_info.nuts.nut29 = NUT29Settings {
+        methods: vec![PaymentMethod::Bolt11],
+        max_batch_size: Some(2),
+    };
+    mint.set_mint_info(mint_info).await.unwrap();
+
+    let first = paid_mint_quote(&mint).await;
+    let second = paid_mint_quote(&mint).await;
+    let third = paid_mint_quote(&mint).await;
+
+    let requests = vec![
+        mint_request(&mint, &first).await,
+        mint_request(&mint, &second).await,
+    ];
+
+    let result = mint
+        .process_batch_mint_request(BatchMintRequest {
+            requests: vec![
+                requests[0].clone(),
+                requests[1].clone(),
+                mint_request(&mint, &third).await,
+            ],
+        })
+        .await;
+    assert!(matches!(result, Err(Error::MaxBatchSizeExceeded(3, 2))));
+
+    let response = mint
+        .process_batch_mint_request(BatchMintRequest {
+            requests: requests.clone(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.responses.len(), 2);
+    for (response, request) in response.responses.iter().zip(&requests) {
+        assert_eq!(response.signatures.len(), request.outputs.len());
+    }
+
+    for quote in [first, second] {
+        let quote: MintQuoteBolt11Response<QuoteId> = mint
+            .check_mint_quote(&quote)
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(quote.state, MintQuoteState::Issued);
+    }
+}
+
+/// Test: Invalid batches are rejected without issuing any quote
+#[tokio::test]
+async fn test_batch_mint_rejected() {
+    let mint = create_test_mint().await.unwrap();
+
+    let result = mint
+        .process_batch_mint_request(BatchMintRequest { requests: vec![] })
+        .await;
+    assert!(matches!(result, Err(Error::EmptyBatch)));
+
+    let first = paid_mint_quote(&mint).await;
+    let second = paid_mint_quote(&mint).await;
+    let first_request = mint_request(&mint, &first).await;
+
+    let result = mint
+        .process_batch_mint_request(BatchMintRequest {
+            requests: vec![first_request.clone(), first_request.clone()],
+        })
+        .await;
+    assert!(matches!(result, Err(Error::DuplicateQuote)));
+
+    let result = mint
+        .process_batch_mint_request(BatchMintRequest {
+            requests: vec![
+                first_request.clone(),
+                MintRequest {
+                    quote: second.clone(),
+                    ..first_request.clone()
+                },
+            ],
+        })
+        .await;
+    assert!(matches!(result, Err(Error::DuplicateOutputs)));
+
+    // Batch minting is not advertised by this mint
+    let result = mint
+        .process_batch_mint_request(BatchMintRequest {
+            requests: vec![first_request, mint_request(&mint, &second).await],
+        })
+        .await;
+    assert!(matches!(result, Err(Error::UnsupportedPaymentMethod)));
+
+    let quote: MintQuoteBolt11Response<QuoteId> = mint
+        .check_mint_quote(&first)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(quote.state, MintQuoteState::Paid);
+}
@@ -0,0 +1,452 @@
+//! Pending input fees and scheduled keyset rotations
+//!
+//! The input fee of a keyset is fixed when the keyset is created, so changing
+//! the fee of a unit requires rotating to a new keyset. A pending fee is applied
+//! at the next rotation of its unit, either triggered manually or at the
+//! scheduled time by the mint's background task.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk_common::database;
+use cdk_common::util::unix_time;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::instrument;
+
+use crate::mint::{
+    AuditLogEntry, CurrencyUnit, Mint, MintKeySetInfo, CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+    CDK_MINT_FEE_SCHEDULE_KV_KEY, CDK_MINT_PRIMARY_NAMESPACE,
+};
+use crate::Error;
+
+/// Interval at which the background task checks for due rotations
+const FEE_SCHEDULE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Input fee waiting to be applied to a unit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingFee {
+    /// Unit the fee applies to
+    pub unit: CurrencyUnit,
+    /// Input fee of the next keyset of the unit
+    pub input_fee_ppk: u64,
+    /// Unix timestamp at which the keyset is rotated with the fee.
+    /// Without it the fee waits for the next manual rotation.
+    pub rotate_at: Option<u64>,
+}
+
+impl Mint {
+    /// Pending input fees, one per unit at most
+    #[instrument(skip_all)]
+    pub async fn fee_schedule(&self) -> Result<Vec<PendingFee>, Error> {
+        let schedule = self
+            .localstore
+            .kv_read(
+                CDK_MINT_PRIMARY_NAMESPACE,
+                CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+                CDK_MINT_FEE_SCHEDULE_KV_KEY,
+            )
+            .await?;
+
+        match schedule {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Pending input fee of the unit
+    pub async fn pending_fee(&self, unit: &CurrencyUnit) -> Result<Option<PendingFee>, Error> {
+        Ok(self
+            .fee_schedule()
+            .await?
+            .into_iter()
+            .find(|pending| &pending.unit == unit))
+    }
+
+    /// Records a pending input fee, replacing any previous one of the unit
+    ///
+    /// The schedule is read and written in one transaction, in which the audit log
+    /// entry, if given, is recorded too. Returns the replaced pending fee.
+    #[instrument(skip(self, audit))]
+    pub async fn set_pending_fee(
+        &self,
+        pending: PendingFee,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<Option<PendingFee>, Error> {
+        if self.active_keyset_order(&pending.unit).is_none() {
+            return Err(Error::UnsupportedUnit);
+        }
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        let mut schedule = read_fee_schedule(&mut tx).await?;
+        let replaced = schedule
+            .iter()
+            .position(|p| p.unit == pending.unit)
+            .map(|index| schedule.remove(index));
+        schedule.push(pending);
+
+        write_fee_schedule(&mut tx, &schedule).await?;
+        if let Some(entry) = audit {
+            tx.add_audit_log_entry(&entry).await?;
+        }
+        tx.commit().await?;
+
+        Ok(replaced)
+    }
+
+    /// Removes the pending input fee of the unit
+    ///
+    /// The schedule is read and written in one transaction, in which the audit log
+    /// entry, if given, is recorded too with the removed fee as its old value. Nothing
+    /// is recorded if the unit has no pending fee.
+    #[instrument(skip(self, audit))]
+    pub async fn remove_pending_fee(
+        &self,
+        unit: &CurrencyUnit,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<Option<PendingFee>, Error> {
+        let mut tx = self.localstore.begin_transaction().await?;
+        let mut schedule = read_fee_schedule(&mut tx).await?;
+        let Some(index) = schedule.iter().position(|p| &p.unit == unit) else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
+        let removed = schedule.remove(index);
+
+        write_fee_schedule(&mut tx, &schedule).await?;
+        if let Some(mut entry) = audit {
+            entry.old_value = Some(serde_json::to_string(&removed)?);
+            tx.add_audit_log_entry(&entry).await?;
+        }
+        tx.commit().await?;
+
+        Ok(Some(removed))
+    }
+
+    /// Rotates the active keyset of the unit to one with the same amounts and
+    /// the given input fee
+    ///
+    /// The audit log entry, if given, is recorded as described in
+    /// [`Mint::rotate_keyset_with_audit`].
+    #[instrument(skip(self, audit))]
+    pub async fn rotate_keyset_fee(
+        &self,
+        unit: CurrencyUnit,
+        input_fee_ppk: u64,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<MintKeySetInfo, Error> {
+        let max_order = self
+            .active_keyset_order(&unit)
+            .ok_or(Error::UnsupportedUnit)?;
+
+        self.rotate_keyset_with_audit(unit, max_order, input_fee_ppk, audit)
+            .await
+    }
+
+    /// Rotates the keysets of the units whose pending fee is due
+    ///
+    /// A unit that fails to rotate is logged and retried on the next call, without
+    /// holding back the other units.
+    #[instrument(skip_all)]
+    pub async fn rotate_due_fees(&self) -> Result<Vec<MintKeySetInfo>, Error> {
+        let now = unix_time();
+        let mut rotated = Vec::new();
+
+        for pending in self.fee_schedule().await? {
+            if pending.rotate_at.is_none_or(|rotate_at| rotate_at > now) {
+                continue;
+            }
+
+            let keyset = match self
+                .rotate_keyset_fee(pending.unit.clone(), pending.input_fee_ppk, None)
+                .await
+            {
+                Ok(keyset) => keyset,
+                Err(err) => {
+                    tracing::error!(
+                        "Could not rotate {} keyset with scheduled input fee of {} ppk: {}",
+                        pending.unit,
+                        pending.input_fee_ppk,
+                        err
+                    );
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                "Rotated {} keyset to {} with scheduled input fee of {} ppk",
+                pending.unit,
+                keyset.id,
+                pending.input_fee_ppk
+            );
+
+            rotated.push(keyset);
+        }
+
+        Ok(rotated)
+    }
+
+    /// Periodically rotates keysets with a due pending fee until shutdown
+    pub(crate) async fn run_fee_schedule(&self, shutdown: Arc<Notify>) {
+        let shutdown = shutdown.notified();
+        tokio::pin!(shutdown);
+        // Register for the notification right away so it is not missed while rotating
+        shutdown.as_mut().enable();
+
+        let mut interval = tokio::time::interval(FEE_SCHEDULE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    tracing::debug!("Stopping fee schedule task");
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Err(err) = self.rotate_due_fees().await {
+                        tracing::error!("Could not rotate keysets with scheduled fees: {}", err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Max order of the active keyset of the unit
+    fn active_keyset_order(&self, unit: &CurrencyUnit) -> Option<u8> {
+        self.keysets
+            .load()
+            .iter()
+            .find(|keyset| keyset.active && &keyset.unit == unit)
+            .map(|keyset| keyset.amounts.len() as u8)
+    }
+
+    /// Clears the pending fee of the unit superseded by a new keyset, recording the
+    /// audit log entry in the same transaction
+    pub(super) async fn clear_pending_fee(
+        &self,
+        unit: &CurrencyUnit,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<(), Error> {
+        let mut tx = self.localstore.begin_transaction().await?;
+        let mut schedule = read_fee_schedule(&mut tx).await?;
+        schedule.retain(|pending| &pending.unit != unit);
+
+        write_fee_schedule(&mut tx, &schedule).await?;
+        if let Some(entry) = audit {
+            tx.add_audit_log_entry(&entry).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Reads the fee schedule, locking it until the end of the transaction
+async fn read_fee_schedule(
+    tx: &mut Box<dyn database::MintTransaction<'_, database::Error> + Send + Sync + '_>,
+) -> Result<Vec<PendingFee>, Error> {
+    let schedule = tx
+        .kv_read(
+            CDK_MINT_PRIMARY_NAMESPACE,
+            CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+            CDK_MINT_FEE_SCHEDULE_KV_KEY,
+        )
+        .await?;
+
+    match schedule {
+        Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn write_fee_schedule(
+    tx: &mut Box<dyn database::MintTransaction<'_, database::Error> + Send + Sync + '_>,
+    schedule: &[PendingFee],
+) -> Result<(), Error> {
+    let schedule_bytes = serde_json::to_vec(schedule)?;
+    tx.kv_write(
+        CDK_MINT_PRIMARY_NAMESPACE,
+        CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+        CDK_MINT_FEE_SCHEDULE_KV_KEY,
+        &schedule_bytes,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::database::mint::AuditLogFilter;
+
+    use super::*;
+    use crate::test_helpers::mint::create_test_mint;
+
+    fn active_keyset(mint: &Mint, unit: &CurrencyUnit) -> cdk_common::nut02::KeySetInfo {
+        mint.keysets()
+            .keysets
+            .into_iter()
+            .find(|keyset| keyset.active && &keyset.unit == unit)
+            .expect("active keyset")
+    }
+
+    #[tokio::test]
+    async fn test_pending_fee_applied_on_rotation() {
+        let mint = create_test_mint().await.unwrap();
+        let before = active_keyset(&mint, &CurrencyUnit::Sat);
+
+        mint.set_pending_fee(
+            PendingFee {
+                unit: CurrencyUnit::Sat,
+                input_fee_ppk: 100,
+                rotate_at: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Without a rotation time nothing is due
+        assert!(mint.rotate_due_fees().await.unwrap().is_empty());
+        assert_eq!(active_keyset(&mint, &CurrencyUnit::Sat).id, before.id);
+
+        let keyset = mint
+            .rotate_keyset_fee(CurrencyUnit::Sat, 100, None)
+            .await
+            .unwrap();
+
+        let after = active_keyset(&mint, &CurrencyUnit::Sat);
+        assert_eq!(after.id, keyset.id);
+        assert_eq!(after.input_fee_ppk, 100);
+        assert!(mint.fee_schedule().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_fee_rotates_when_due() {
+        let mint = create_test_mint().await.unwrap();
+        let before = active_keyset(&mint, &CurrencyUnit::Sat);
+
+        mint.set_pending_fee(
+            PendingFee {
+                unit: CurrencyUnit::Sat,
+                input_fee_ppk: 200,
+                rotate_at: Some(unix_time() + 3600),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(mint.rotate_due_fees().await.unwrap().is_empty());
+
+        let replaced = mint
+            .set_pending_fee(
+                PendingFee {
+                    unit: CurrencyUnit::Sat,
+                    input_fee_ppk: 200,
+                    rotate_at: Some(unix_time() - 1),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(replaced.is_some());
+
+        let rotated = mint.rotate_due_fees().await.unwrap();
+        assert_eq!(rotated.len(), 1);
+
+        let after = active_keyset(&mint, &CurrencyUnit::Sat);
+        assert_ne!(after.id, before.id);
+        assert_eq!(after.input_fee_ppk, 200);
+        assert!(mint.fee_schedule().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_rotation_does_not_hold_back_other_units() {
+        let mint = create_test_mint().await.unwrap();
+        let before = active_keyset(&mint, &CurrencyUnit::Sat);
+
+        // A unit without keysets cannot be rotated
+        let schedule = vec![
+            PendingFee {
+                unit: CurrencyUnit::Eur,
+                input_fee_ppk: 100,
+                rotate_at: Some(unix_time() - 1),
+            },
+            PendingFee {
+                unit: CurrencyUnit::Sat,
+                input_fee_ppk: 100,
+                rotate_at: Some(unix_time() - 1),
+            },
+        ];
+        let mut tx = mint.localstore.begin_transaction().await.unwrap();
+        write_fee_schedule(&mut tx, &schedule).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let rotated = mint.rotate_due_fees().await.unwrap();
+        assert_eq!(rotated.len(), 1);
+        assert_ne!(active_keyset(&mint, &CurrencyUnit::Sat).id, before.id);
+
+        // The unit that failed is retried on the next call
+        assert_eq!(
+            mint.fee_schedule().await.unwrap(),
+            vec![schedule[0].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_fee_requires_active_keyset() {
+        let mint = create_test_mint().await.unwrap();
+
+        let result = mint
+            .set_pending_fee(
+                PendingFee {
+                    unit: CurrencyUnit::Eur,
+                    input_fee_ppk: 100,
+                    rotate_at: None,
+                },
+                None,
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::UnsupportedUnit)));
+    }
+
+    #[tokio::test]
+    async fn test_pending_fee_records_audit_entry() {
+        let mint = create_test_mint().await.unwrap();
+
+        let entry = AuditLogEntry::new("set_unit_fee".to_string(), None, None, None);
+        mint.set_pending_fee(
+            PendingFee {
+                unit: CurrencyUnit::Sat,
+                input_fee_ppk: 100,
+                rotate_at: None,
+            },
+            Some(entry.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Nothing is removed, so nothing is recorded
+        let unit = CurrencyUnit::Usd;
+        let removed = mint
+            .remove_pending_fee(
+                &unit,
+                Some(AuditLogEntry::new(
+                    "cancel_unit_fee".to_string(),
+                    None,
+                    None,
+                    None,
+                )),
+            )
+            .await
+            .unwrap();
+        assert!(removed.is_none());
+
+        let (entries, _) = mint
+            .localstore
+            .list_audit_log(&AuditLogFilter::default(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(entries, vec![entry]);
+    }
+}
@@ -2,12 +2,16 @@ use cdk_signatory::signatory::RotateKeyArguments;
 use tracing::instrument;
 
 use super::{
-    CurrencyUnit, Id, KeySet, KeySetInfo, KeysResponse, KeysetResponse, Mint, MintKeySetInfo,
+    AuditLogEntry, CurrencyUnit, Id, KeySet, KeySetInfo, KeysResponse, KeysetResponse, Mint,
+    MintKeySetInfo,
 };
 use crate::Error;
 
 #[cfg(feature = "auth")]
 mod auth;
+mod fee_schedule;
+
+pub use fee_schedule::PendingFee;
 
 impl Mint {
     /// Retrieve the public keys of the active keyset for distribution to wallet
@@ -71,17 +75,39 @@ impl Mint {
 
     /// Add current keyset to inactive keysets
     /// Generate new keyset
+    ///
+    /// The pending fee of the unit, if any, is cleared as the new keyset
+    /// supersedes it.
     #[instrument(skip(self))]
     pub async fn rotate_keyset(
         &self,
         unit: CurrencyUnit,
         max_order: u8,
         input_fee_ppk: u64,
+    ) -> Result<MintKeySetInfo, Error> {
+        self.rotate_keyset_with_audit(unit, max_order, input_fee_ppk, None)
+            .await
+    }
+
+    /// Add current keyset to inactive keysets
+    /// Generate new keyset
+    ///
+    /// The keys are generated by the signatory, outside of the mint's database. The
+    /// audit log entry, if given, is recorded in the transaction clearing the pending
+    /// fee of the unit once the new keyset exists. An unset new value of the entry is
+    /// set to the id of the new keyset.
+    #[instrument(skip(self, audit))]
+    pub async fn rotate_keyset_with_audit(
+        &self,
+        unit: CurrencyUnit,
+        max_order: u8,
+        input_fee_ppk: u64,
+        audit: Option<AuditLogEntry>,
     ) -> Result<MintKeySetInfo, Error> {
         let result = self
             .signatory
             .rotate_keyset(RotateKeyArguments {
-                unit,
+                unit: unit.clone(),
                 amounts: (0..max_order).map(|n| 2u64.pow(n.into())).collect(),
                 input_fee_ppk,
             })
@@ -90,6 +116,22 @@ impl Mint {
         let new_keyset = self.signatory.keysets().await?;
         self.keysets.store(new_keyset.keysets.into());
 
+        let audit = audit.map(|mut entry| {
+            entry.new_value.get_or_insert_with(|| result.id.to_string());
+            entry
+        });
+
+        // The keyset is rotated already, so failing to clear the pending fee must not
+        // fail the rotation
+        if let Err(err) = self.clear_pending_fee(&unit, audit).await {
+            tracing::error!(
+                "Rotated {} keyset to {} but could not clear its pending fee: {}",
+                unit,
+                result.id,
+                err
+            );
+        }
+
         Ok(result.into())
     }
 }
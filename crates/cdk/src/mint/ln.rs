@@ -5,7 +5,9 @@ use cdk_common::amount::to_unit;
 use cdk_common::common::PaymentProcessorKey;
 use cdk_common::database::DynMintDatabase;
 use cdk_common::mint::MintQuote;
-use cdk_common::payment::DynMintPayment;
+use cdk_common::nut05::MeltMethodOptions;
+use cdk_common::nut15::MppMethodSettings;
+use cdk_common::payment::{Bolt11Settings, DynMintPayment};
 use cdk_common::util::unix_time;
 use cdk_common::{database, Amount, MintQuoteState, PaymentMethod};
 use tracing::instrument;
@@ -126,4 +128,64 @@ impl Mint {
         )
         .await
     }
+
+    /// Update the mint info after the settings of a payment processor changed
+    ///
+    /// Only the bolt11 options the backend reports are changed, options disabled in the mint
+    /// quote settings stay disabled.
+    #[instrument(skip_all)]
+    pub(crate) async fn handle_payment_settings_changed(
+        &self,
+        processor: &DynMintPayment,
+        settings: Bolt11Settings,
+    ) -> Result<(), Error> {
+        let keys: Vec<&PaymentProcessorKey> = self
+            .payment_processors
+            .iter()
+            .filter(|(key, ln)| key.method == PaymentMethod::Bolt11 && Arc::ptr_eq(ln, processor))
+            .map(|(key, _)| key)
+            .collect();
+
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let mut mint_info = self.mint_info().await?;
+
+        for key in keys {
+            tracing::info!("Payment processor settings for {:?} changed", key);
+
+            for method in mint_info.nuts.nut04.methods.iter_mut() {
+                if method.unit != key.unit || method.method != key.method {
+                    continue;
+                }
+
+                method.options = Some(
+                    self.bolt11_mint_quote_settings
+                        .mint_method_options(&settings),
+                );
+            }
+
+            for method in mint_info.nuts.nut05.methods.iter_mut() {
+                if method.unit != key.unit || method.method != key.method {
+                    continue;
+                }
+
+                method.options = Some(MeltMethodOptions::Bolt11 {
+                    amountless: settings.amountless,
+                });
+            }
+
+            let mpp = &mut mint_info.nuts.nut15.methods;
+            mpp.retain(|m| m.unit != key.unit || m.method != key.method);
+            if settings.mpp {
+                mpp.push(MppMethodSettings {
+                    method: key.method.clone(),
+                    unit: key.unit.clone(),
+                });
+            }
+        }
+
+        self.set_mint_info(mint_info).await
+    }
 }
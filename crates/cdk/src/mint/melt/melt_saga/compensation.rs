@@ -55,6 +55,8 @@ impl CompensatingAction for RemoveMeltSetup {
             &self.quote_id,
             &self.input_ys,
             &self.blinded_secrets,
+            None,
+            None,
         )
         .await
     }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use cdk_common::amount::to_unit;
 use cdk_common::database::mint::MeltRequestInfo;
 use cdk_common::database::DynMintDatabase;
-use cdk_common::mint::{MeltSagaState, Operation, Saga};
+use cdk_common::mint::{MeltSagaState, Operation, Saga, SagaStateEnum};
 use cdk_common::nuts::MeltQuoteState;
 use cdk_common::{Amount, Error, ProofsMethods, PublicKey, QuoteId, State};
 #[cfg(feature = "prometheus")]
@@ -662,6 +662,7 @@ impl MeltSaga<SetupComplete> {
                             "LN payment unknown, proofs remain pending for quote: {}",
                             self.state_data.quote.id
                         );
+                        self.mark_payment_sent().await?;
                         return Err(Error::PaymentFailed);
                     }
                     MeltQuoteState::Pending => {
@@ -669,6 +670,7 @@ impl MeltSaga<SetupComplete> {
                             "LN payment pending, proofs remain pending for quote: {}",
                             self.state_data.quote.id
                         );
+                        self.mark_payment_sent().await?;
                         return Err(Error::PendingQuote);
                     }
                 }
@@ -695,6 +697,29 @@ impl MeltSaga<SetupComplete> {
         })
     }
 
+    /// Persists that the payment was sent but is not settled yet
+    ///
+    /// Recovery and outgoing payment updates then resolve the melt from the
+    /// payment state instead of compensating it.
+    async fn mark_payment_sent(&self) -> Result<(), Error> {
+        let mut tx = self.db.begin_transaction().await?;
+
+        if let Err(err) = tx
+            .update_saga(
+                self.operation.id(),
+                SagaStateEnum::Melt(MeltSagaState::PaymentSent),
+            )
+            .await
+        {
+            tx.rollback().await?;
+            return Err(err.into());
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     /// Helper to check payment state with LN backend
     async fn check_payment_state(
         &self,
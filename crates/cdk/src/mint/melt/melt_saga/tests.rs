@@ -8,12 +8,16 @@
 //! - Concurrent operations
 //! - Failure handling
 
-use cdk_common::mint::{MeltSagaState, OperationKind, Saga};
+use cdk_common::database::mint::AuditLogFilter;
+use cdk_common::mint::{AuditLogEntry, MeltSagaState, OperationKind, Saga, SagaStateEnum};
 use cdk_common::nuts::MeltQuoteState;
-use cdk_common::{Amount, ProofsMethods, State};
+use cdk_common::payment::MakePaymentResponse;
+use cdk_common::{Amount, CurrencyUnit, ProofsMethods, State};
 
 use crate::mint::melt::melt_saga::MeltSaga;
 use crate::test_helpers::mint::{create_test_mint, mint_test_proofs};
+use crate::util::hex;
+use crate::Error;
 
 // ============================================================================
 // Basic State Transition Tests
@@ -2002,6 +2006,201 @@ async fn test_saga_drop_after_payment() {
     // SUCCESS: Drop after payment is recoverable!
 }
 
+// ============================================================================
+// Outgoing Payment Update Tests
+// ============================================================================
+
+/// Test: Outgoing payment updates do not touch melts still in progress
+#[tokio::test]
+async fn test_outgoing_payment_update_ignores_melt_in_progress() {
+    let mint = create_test_mint().await.unwrap();
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let input_ys = proofs.ys().unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let melt_request = create_test_melt_request(&proofs, &quote);
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+    let setup_saga = saga.setup_melt(&melt_request, verification).await.unwrap();
+    let operation_id = *setup_saga.operation.id();
+
+    mint.handle_outgoing_payment_update(MakePaymentResponse {
+        payment_lookup_id: quote.request_lookup_id.clone().unwrap(),
+        payment_proof: None,
+        status: MeltQuoteState::Failed,
+        total_spent: Amount::ZERO,
+        unit: CurrencyUnit::Sat,
+    })
+    .await
+    .unwrap();
+
+    // The saga has not sent the payment yet, so the update is ignored
+    assert_proofs_state(&mint, &input_ys, Some(State::Pending)).await;
+    assert_saga_exists(&mint, &operation_id).await;
+}
+
+/// Test: A failed outgoing payment update compensates a pending melt
+#[tokio::test]
+async fn test_outgoing_payment_update_compensates_failed_payment() {
+    let mint = create_test_mint().await.unwrap();
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let input_ys = proofs.ys().unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let melt_request = create_test_melt_request(&proofs, &quote);
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+    let setup_saga = saga.setup_melt(&melt_request, verification).await.unwrap();
+    let operation_id = *setup_saga.operation.id();
+
+    // Simulate the payment being left pending by the backend
+    setup_saga.mark_payment_sent().await.unwrap();
+    let saga = assert_saga_exists(&mint, &operation_id).await;
+    assert_eq!(saga.state, SagaStateEnum::Melt(MeltSagaState::PaymentSent));
+    drop(setup_saga);
+
+    mint.handle_outgoing_payment_update(MakePaymentResponse {
+        payment_lookup_id: quote.request_lookup_id.clone().unwrap(),
+        payment_proof: None,
+        status: MeltQuoteState::Failed,
+        total_spent: Amount::ZERO,
+        unit: CurrencyUnit::Sat,
+    })
+    .await
+    .unwrap();
+
+    assert_proofs_state(&mint, &input_ys, None).await;
+    assert_saga_not_exists(&mint, &operation_id).await;
+
+    let quote = mint
+        .localstore
+        .get_melt_quote(&quote.id)
+        .await
+        .unwrap()
+        .expect("Quote should still exist");
+    assert_eq!(quote.state, MeltQuoteState::Unpaid);
+}
+
+// ============================================================================
+// Manual Resolution Tests
+// ============================================================================
+
+/// Helper: Set up a melt whose payment was sent but never resolved
+async fn setup_stuck_melt(
+    mint: &crate::mint::Mint,
+) -> (
+    cdk_common::mint::MeltQuote,
+    Vec<cdk_common::PublicKey>,
+    uuid::Uuid,
+) {
+    let proofs = mint_test_proofs(mint, Amount::from(10_000)).await.unwrap();
+    let input_ys = proofs.ys().unwrap();
+    let quote = create_test_melt_quote(mint, Amount::from(9_000)).await;
+    let melt_request = create_test_melt_request(&proofs, &quote);
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+    let setup_saga = saga.setup_melt(&melt_request, verification).await.unwrap();
+    let operation_id = *setup_saga.operation.id();
+    setup_saga.mark_payment_sent().await.unwrap();
+    drop(setup_saga);
+
+    (quote, input_ys, operation_id)
+}
+
+/// Test: Melts still being processed cannot be resolved manually
+#[tokio::test]
+async fn test_manual_resolution_rejects_melt_in_progress() {
+    let mint = create_test_mint().await.unwrap();
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let melt_request = create_test_melt_request(&proofs, &quote);
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+    let _setup_saga = saga.setup_melt(&melt_request, verification).await.unwrap();
+
+    let result = mint.mark_melt_quote_failed(&quote.id, None).await;
+    assert!(matches!(result, Err(Error::PendingQuote)));
+}
+
+/// Test: Force checking a melt whose payment is still unknown leaves it pending
+#[tokio::test]
+async fn test_force_check_keeps_unresolved_melt_pending() {
+    let mint = create_test_mint().await.unwrap();
+    let (quote, input_ys, operation_id) = setup_stuck_melt(&mint).await;
+
+    let quote = mint.force_check_melt_quote(&quote.id, None).await.unwrap();
+
+    assert_eq!(quote.state, MeltQuoteState::Pending);
+    assert_proofs_state(&mint, &input_ys, Some(State::Pending)).await;
+    assert_saga_exists(&mint, &operation_id).await;
+}
+
+/// Test: Marking a stuck melt failed releases its proofs and records the audit entry
+#[tokio::test]
+async fn test_mark_melt_failed_releases_proofs() {
+    let mint = create_test_mint().await.unwrap();
+    let (quote, input_ys, operation_id) = setup_stuck_melt(&mint).await;
+
+    let entry = AuditLogEntry::new("mark_melt_failed".to_string(), None, None, None);
+    let quote = mint
+        .mark_melt_quote_failed(&quote.id, Some(entry.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(quote.state, MeltQuoteState::Unpaid);
+    assert_proofs_state(&mint, &input_ys, None).await;
+    assert_saga_not_exists(&mint, &operation_id).await;
+
+    let (entries, _) = mint
+        .localstore()
+        .list_audit_log(&AuditLogFilter::default(), 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(entries, vec![entry]);
+
+    // The quote is no longer pending so it cannot be resolved again
+    let result = mint.mark_melt_quote_failed(&quote.id, None).await;
+    assert!(matches!(result, Err(Error::UnpaidQuote)));
+}
+
+/// Test: Marking a stuck melt paid requires the preimage of the invoice
+#[tokio::test]
+async fn test_mark_melt_paid_rejects_invalid_preimage() {
+    let mint = create_test_mint().await.unwrap();
+    let (quote, input_ys, operation_id) = setup_stuck_melt(&mint).await;
+
+    let result = mint
+        .mark_melt_quote_paid(&quote.id, hex::encode([1u8; 32]), None)
+        .await;
+    assert!(matches!(result, Err(Error::InvalidPaymentPreimage)));
+
+    let result = mint
+        .mark_melt_quote_paid(&quote.id, "not hex".to_string(), None)
+        .await;
+    assert!(matches!(result, Err(Error::InvalidPaymentPreimage)));
+
+    assert_proofs_state(&mint, &input_ys, Some(State::Pending)).await;
+    assert_saga_exists(&mint, &operation_id).await;
+}
+
 // ============================================================================
 // Test Helpers
 // ============================================================================
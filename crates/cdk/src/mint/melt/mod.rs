@@ -3,7 +3,6 @@ use std::str::FromStr;
 use cdk_common::amount::amount_for_offer;
 use cdk_common::melt::MeltQuoteRequest;
 use cdk_common::mint::MeltPaymentRequest;
-use cdk_common::nut05::MeltMethodOptions;
 use cdk_common::payment::{
     Bolt11OutgoingPaymentOptions, Bolt12OutgoingPaymentOptions, OutgoingPaymentOptions,
 };
@@ -73,12 +72,7 @@ impl Mint {
                 amount
             }
             Some(MeltOptions::Amountless { amountless: _ }) => {
-                if method == PaymentMethod::Bolt11
-                    && !matches!(
-                        settings.options,
-                        Some(MeltMethodOptions::Bolt11 { amountless: true })
-                    )
-                {
+                if method == PaymentMethod::Bolt11 && !nut05.amountless_supported(&unit) {
                     return Err(Error::AmountlessInvoiceNotSupported(unit, method));
                 }
 
@@ -141,6 +135,21 @@ impl Mint {
             ..
         } = melt_request;
 
+        // Make sure the amount to pay is defined exactly once, either by the
+        // invoice itself or by the amountless option, before asking the backend
+        match (options, request.amount_milli_satoshis()) {
+            (None, None) => return Err(Error::InvoiceAmountUndefined),
+            (Some(MeltOptions::Amountless { amountless }), Some(invoice_amount_msat))
+                if amountless.amount_msat != Amount::from(invoice_amount_msat) =>
+            {
+                return Err(Error::AmountlessAmountMismatch(
+                    amountless.amount_msat,
+                    invoice_amount_msat.into(),
+                ));
+            }
+            _ => (),
+        }
+
         let ln = self
             .payment_processors
             .get(&PaymentProcessorKey::new(
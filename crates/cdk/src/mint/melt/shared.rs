@@ -12,7 +12,7 @@ use cdk_common::{Amount, Error, PublicKey, QuoteId};
 use cdk_signatory::signatory::SignatoryKeySet;
 
 use crate::mint::subscription::PubSubManager;
-use crate::mint::MeltQuote;
+use crate::mint::{AuditLogEntry, MeltQuote};
 
 /// Retrieves fee and amount configuration for the keyset matching the change outputs.
 ///
@@ -58,6 +58,7 @@ pub fn get_keyset_fee_and_amounts(
 /// 2. Removes change output blinded messages
 /// 3. Resets quote state from Pending to Unpaid
 /// 4. Deletes melt request tracking record
+/// 5. Deletes the saga of the melt and records the audit log entry, if given
 ///
 /// This restores the database to its pre-melt state, allowing retry.
 ///
@@ -67,6 +68,8 @@ pub fn get_keyset_fee_and_amounts(
 /// * `quote_id` - ID of the quote to rollback
 /// * `input_ys` - Y values (public keys) from input proofs
 /// * `blinded_secrets` - Blinded secrets from change outputs
+/// * `saga_id` - Operation id of the saga to delete with the rollback
+/// * `audit` - Audit log entry to record with the rollback
 ///
 /// # Errors
 ///
@@ -76,8 +79,10 @@ pub async fn rollback_melt_quote(
     quote_id: &QuoteId,
     input_ys: &[PublicKey],
     blinded_secrets: &[PublicKey],
+    saga_id: Option<&uuid::Uuid>,
+    audit: Option<&AuditLogEntry>,
 ) -> Result<(), Error> {
-    if input_ys.is_empty() && blinded_secrets.is_empty() {
+    if input_ys.is_empty() && blinded_secrets.is_empty() && saga_id.is_none() && audit.is_none() {
         return Ok(());
     }
 
@@ -115,6 +120,14 @@ pub async fn rollback_melt_quote(
     // Delete melt request tracking record
     tx.delete_melt_request(quote_id).await?;
 
+    if let Some(saga_id) = saga_id {
+        tx.delete_saga(saga_id).await?;
+    }
+
+    if let Some(entry) = audit {
+        tx.add_audit_log_entry(entry).await?;
+    }
+
     tx.commit().await?;
 
     tracing::info!("Successfully rolled back melt quote {}", quote_id);
@@ -335,7 +348,8 @@ pub async fn finalize_melt_core(
 /// 2. Getting input proof Y values
 /// 3. Processing change (if needed)
 /// 4. Core finalization operations
-/// 5. Transaction commit
+/// 5. Transaction commit, deleting the saga of the melt with the melt request and
+///    recording the audit log entry
 /// 6. Pubsub notification
 ///
 /// # Arguments
@@ -347,10 +361,13 @@ pub async fn finalize_melt_core(
 /// * `total_spent` - Amount spent on payment
 /// * `payment_preimage` - Payment preimage (if any)
 /// * `payment_lookup_id` - Payment lookup identifier
+/// * `saga_id` - Operation id of the saga to delete with the finalization
+/// * `audit` - Audit log entry to record with the finalization
 ///
 /// # Returns
 ///
 /// `Option<Vec<BlindSignature>>` - Change signatures (if any)
+#[allow(clippy::too_many_arguments)]
 pub async fn finalize_melt_quote(
     mint: &super::super::Mint,
     db: &DynMintDatabase,
@@ -359,6 +376,8 @@ pub async fn finalize_melt_quote(
     total_spent: Amount,
     payment_preimage: Option<String>,
     payment_lookup_id: &cdk_common::payment::PaymentIdentifier,
+    saga_id: Option<&uuid::Uuid>,
+    audit: Option<&AuditLogEntry>,
 ) -> Result<Option<Vec<BlindSignature>>, Error> {
     use cdk_common::amount::to_unit;
 
@@ -377,7 +396,7 @@ pub async fn finalize_melt_quote(
                 "No melt request found for quote {} - may have been completed already",
                 quote.id
             );
-            tx.rollback().await?;
+            finish_completed_melt(tx, saga_id, audit).await?;
             return Ok(None);
         }
     };
@@ -390,7 +409,7 @@ pub async fn finalize_melt_quote(
             "No input proofs found for quote {} - may have been completed already",
             quote.id
         );
-        tx.rollback().await?;
+        finish_completed_melt(tx, saga_id, audit).await?;
         return Ok(None);
     }
 
@@ -426,6 +445,14 @@ pub async fn finalize_melt_quote(
     // Delete melt request tracking
     tx.delete_melt_request(&quote.id).await?;
 
+    if let Some(saga_id) = saga_id {
+        tx.delete_saga(saga_id).await?;
+    }
+
+    if let Some(entry) = audit {
+        tx.add_audit_log_entry(entry).await?;
+    }
+
     // Commit transaction
     tx.commit().await?;
 
@@ -441,3 +468,28 @@ pub async fn finalize_melt_quote(
 
     Ok(change_sigs)
 }
+
+/// Ends the transaction of a melt that was already finalized, deleting its leftover saga
+/// and recording the audit log entry
+async fn finish_completed_melt(
+    mut tx: Box<dyn database::MintTransaction<'_, database::Error> + Send + Sync + '_>,
+    saga_id: Option<&uuid::Uuid>,
+    audit: Option<&AuditLogEntry>,
+) -> Result<(), Error> {
+    if saga_id.is_none() && audit.is_none() {
+        tx.rollback().await?;
+        return Ok(());
+    }
+
+    if let Some(saga_id) = saga_id {
+        tx.delete_saga(saga_id).await?;
+    }
+
+    if let Some(entry) = audit {
+        tx.add_audit_log_entry(entry).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Amountless bolt11 melt quote tests
+//!
+//! These tests verify that the mint accepts amountless bolt11 invoices when the
+//! wallet provides an amount, and rejects requests where the amount to pay is
+//! missing or contradicts the invoice.
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use cdk_common::melt::MeltQuoteRequest;
+use cdk_common::{Amount, Bolt11Invoice, CurrencyUnit, MeltOptions, MeltQuoteBolt11Request};
+use cdk_fake_wallet::create_fake_invoice;
+use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+
+use crate::test_helpers::mint::create_test_mint;
+use crate::Error;
+
+/// Build a signed bolt11 invoice without an amount
+fn create_amountless_invoice() -> Bolt11Invoice {
+    let private_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+
+    InvoiceBuilder::new(Currency::Bitcoin)
+        .description("amountless".to_string())
+        .payment_hash(sha256::Hash::hash(b"amountless melt test"))
+        .payment_secret(PaymentSecret([42u8; 32]))
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
+        .unwrap()
+}
+
+/// Test: Amountless invoice paid with a wallet chosen amount
+#[tokio::test]
+async fn test_melt_quote_amountless_invoice() {
+    let mint = create_test_mint().await.unwrap();
+
+    let mint_info = mint.mint_info().await.unwrap();
+    assert!(mint_info
+        .nuts
+        .nut05
+        .amountless_supported(&CurrencyUnit::Sat));
+
+    let melt_quote_request = MeltQuoteBolt11Request {
+        request: create_amountless_invoice(),
+        unit: CurrencyUnit::Sat,
+        options: Some(MeltOptions::new_amountless(5_000)),
+    };
+
+    let melt_quote = mint
+        .get_melt_quote(MeltQuoteRequest::Bolt11(melt_quote_request))
+        .await
+        .unwrap();
+
+    assert_eq!(melt_quote.amount, Amount::from(5));
+}
+
+/// Test: Amountless invoice without an amount option is rejected
+#[tokio::test]
+async fn test_melt_quote_amountless_invoice_without_amount() {
+    let mint = create_test_mint().await.unwrap();
+
+    let melt_quote_request = MeltQuoteBolt11Request {
+        request: create_amountless_invoice(),
+        unit: CurrencyUnit::Sat,
+        options: None,
+    };
+
+    let result = mint
+        .get_melt_quote(MeltQuoteRequest::Bolt11(melt_quote_request))
+        .await;
+
+    assert!(matches!(result, Err(Error::InvoiceAmountUndefined)));
+}
+
+/// Test: Amountless option that contradicts the invoice amount is rejected
+#[tokio::test]
+async fn test_melt_quote_amountless_option_amount_mismatch() {
+    let mint = create_test_mint().await.unwrap();
+
+    let melt_quote_request = MeltQuoteBolt11Request {
+        request: create_fake_invoice(10_000, "".to_string()),
+        unit: CurrencyUnit::Sat,
+        options: Some(MeltOptions::new_amountless(5_000)),
+    };
+
+    let result = mint
+        .get_melt_quote(MeltQuoteRequest::Bolt11(melt_quote_request))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::AmountlessAmountMismatch(requested, invoice))
+            if requested == Amount::from(5_000) && invoice == Amount::from(10_000)
+    ));
+}
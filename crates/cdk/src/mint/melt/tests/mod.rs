@@ -1,3 +1,4 @@
+mod amountless_tests;
 mod htlc_sigall_spending_conditions_tests;
 mod htlc_spending_conditions_tests;
 mod locktime_spending_conditions_tests;
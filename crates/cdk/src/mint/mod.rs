@@ -44,14 +44,16 @@ mod subscription;
 mod swap;
 mod verification;
 
-pub use builder::{MintBuilder, MintMeltLimits};
-pub use cdk_common::mint::{MeltQuote, MintKeySetInfo, MintQuote};
+pub use builder::{Bolt11MintQuoteSettings, MintBuilder, MintMeltLimits};
+pub use cdk_common::mint::{AuditLogEntry, MeltQuote, MintKeySetInfo, MintQuote};
+pub use keysets::PendingFee;
 pub use verification::Verification;
 
 const CDK_MINT_PRIMARY_NAMESPACE: &str = "cdk_mint";
 const CDK_MINT_CONFIG_SECONDARY_NAMESPACE: &str = "config";
 const CDK_MINT_CONFIG_KV_KEY: &str = "mint_info";
 const CDK_MINT_QUOTE_TTL_KV_KEY: &str = "quote_ttl";
+const CDK_MINT_FEE_SCHEDULE_KV_KEY: &str = "fee_schedule";
 
 /// Cashu Mint
 #[derive(Clone)]
@@ -76,6 +78,8 @@ pub struct Mint {
     keysets: Arc<ArcSwap<Vec<SignatoryKeySet>>>,
     /// Background task management
     task_state: Arc<Mutex<TaskState>>,
+    /// Bolt11 mint quote settings of the mint
+    bolt11_mint_quote_settings: Bolt11MintQuoteSettings,
 }
 
 /// State for managing background tasks
@@ -85,6 +89,8 @@ struct TaskState {
     shutdown_notify: Option<Arc<Notify>>,
     /// Handle to the main supervisor task
     supervisor_handle: Option<JoinHandle<Result<(), Error>>>,
+    /// Handle to the task rotating keysets with scheduled fees
+    fee_schedule_handle: Option<JoinHandle<()>>,
 }
 
 impl Mint {
@@ -219,6 +225,7 @@ impl Mint {
             auth_localstore,
             keysets: Arc::new(ArcSwap::new(keysets.keysets.into())),
             task_state: Arc::new(Mutex::new(TaskState::default())),
+            bolt11_mint_quote_settings: Bolt11MintQuoteSettings::default(),
         })
     }
 
@@ -292,25 +299,23 @@ impl Mint {
         let shutdown_notify = Arc::new(Notify::new());
 
         // Clone required components for the background task
-        let payment_processors = self.payment_processors.clone();
-        let localstore = Arc::clone(&self.localstore);
-        let pubsub_manager = Arc::clone(&self.pubsub_manager);
+        let mint = self.clone();
         let shutdown_clone = shutdown_notify.clone();
 
         // Spawn the supervisor task
-        let supervisor_handle = tokio::spawn(async move {
-            Self::wait_for_paid_invoices(
-                &payment_processors,
-                localstore,
-                pubsub_manager,
-                shutdown_clone,
-            )
-            .await
-        });
+        let supervisor_handle =
+            tokio::spawn(async move { mint.wait_for_paid_invoices(shutdown_clone).await });
+
+        // Spawn the task rotating keysets with scheduled fees
+        let mint = self.clone();
+        let shutdown_clone = shutdown_notify.clone();
+        let fee_schedule_handle =
+            tokio::spawn(async move { mint.run_fee_schedule(shutdown_clone).await });
 
         // Store the handles
         task_state.shutdown_notify = Some(shutdown_notify);
         task_state.supervisor_handle = Some(supervisor_handle);
+        task_state.fee_schedule_handle = Some(fee_schedule_handle);
 
         // Give the background task a tiny bit of time to start waiting
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
@@ -334,6 +339,7 @@ impl Mint {
         // Take the handles out of the state
         let shutdown_notify = task_state.shutdown_notify.take();
         let supervisor_handle = task_state.supervisor_handle.take();
+        let fee_schedule_handle = task_state.fee_schedule_handle.take();
 
         // If nothing to stop, return early
         let (shutdown_notify, supervisor_handle) = match (shutdown_notify, supervisor_handle) {
@@ -353,6 +359,12 @@ impl Mint {
         // Signal shutdown
         shutdown_notify.notify_waiters();
 
+        if let Some(fee_schedule_handle) = fee_schedule_handle {
+            if let Err(join_error) = fee_schedule_handle.await {
+                tracing::error!("Fee schedule task panicked: {:?}", join_error);
+            }
+        }
+
         // Wait for supervisor to complete
         let result = match supervisor_handle.await {
             Ok(result) => {
@@ -480,6 +492,16 @@ impl Mint {
     /// Set mint info
     #[instrument(skip_all)]
     pub async fn set_mint_info(&self, mint_info: MintInfo) -> Result<(), Error> {
+        self.set_mint_info_with_audit(mint_info, None).await
+    }
+
+    /// Set mint info, recording the audit log entry in the same transaction
+    #[instrument(skip_all)]
+    pub async fn set_mint_info_with_audit(
+        &self,
+        mint_info: MintInfo,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<(), Error> {
         tracing::info!("Updating mint info");
         let mint_info_bytes = serde_json::to_vec(&mint_info)?;
         let mut tx = self.localstore.begin_transaction().await?;
@@ -490,6 +512,9 @@ impl Mint {
             &mint_info_bytes,
         )
         .await?;
+        if let Some(entry) = audit {
+            tx.add_audit_log_entry(&entry).await?;
+        }
         tx.commit().await?;
         Ok(())
     }
@@ -521,6 +546,16 @@ impl Mint {
     /// Set quote ttl
     #[instrument(skip_all)]
     pub async fn set_quote_ttl(&self, quote_ttl: QuoteTTL) -> Result<(), Error> {
+        self.set_quote_ttl_with_audit(quote_ttl, None).await
+    }
+
+    /// Set quote ttl, recording the audit log entry in the same transaction
+    #[instrument(skip_all)]
+    pub async fn set_quote_ttl_with_audit(
+        &self,
+        quote_ttl: QuoteTTL,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<(), Error> {
         let quote_ttl_bytes = serde_json::to_vec(&quote_ttl)?;
         let mut tx = self.localstore.begin_transaction().await?;
         tx.kv_write(
@@ -530,6 +565,9 @@ impl Mint {
             &quote_ttl_bytes,
         )
         .await?;
+        if let Some(entry) = audit {
+            tx.add_audit_log_entry(&entry).await?;
+        }
         tx.commit().await?;
         Ok(())
     }
@@ -553,17 +591,12 @@ impl Mint {
     }
 
     #[instrument(skip_all)]
-    async fn wait_for_paid_invoices(
-        payment_processors: &HashMap<PaymentProcessorKey, DynMintPayment>,
-        localstore: DynMintDatabase,
-        pubsub_manager: Arc<PubSubManager>,
-        shutdown: Arc<Notify>,
-    ) -> Result<(), Error> {
+    async fn wait_for_paid_invoices(&self, shutdown: Arc<Notify>) -> Result<(), Error> {
         let mut join_set = JoinSet::new();
 
         // Group processors by unique instance (using Arc pointer equality)
         let mut seen_processors = Vec::new();
-        for (key, processor) in payment_processors {
+        for (key, processor) in self.payment_processors.iter() {
             // Skip if processor is already active
             if processor.is_wait_invoice_active() {
                 continue;
@@ -579,19 +612,12 @@ impl Mint {
             tracing::info!("Starting payment wait task for {:?}", key);
 
             // Clone for the spawned task
+            let mint = self.clone();
             let processor = Arc::clone(processor);
-            let localstore = Arc::clone(&localstore);
-            let pubsub_manager = Arc::clone(&pubsub_manager);
             let shutdown = Arc::clone(&shutdown);
 
             join_set.spawn(async move {
-                let result = Self::wait_for_processor_payments(
-                    processor,
-                    localstore,
-                    pubsub_manager,
-                    shutdown,
-                )
-                .await;
+                let result = mint.wait_for_processor_payments(processor, shutdown).await;
 
                 if let Err(e) = result {
                     tracing::error!("Payment processor task failed: {:?}", e);
@@ -627,9 +653,8 @@ impl Mint {
     /// Handles payment waiting for a single processor
     #[instrument(skip_all)]
     async fn wait_for_processor_payments(
+        &self,
         processor: DynMintPayment,
-        localstore: DynMintDatabase,
-        pubsub_manager: Arc<PubSubManager>,
         shutdown: Arc<Notify>,
     ) -> Result<(), Error> {
         loop {
@@ -645,13 +670,29 @@ impl Mint {
                                 match event {
                                     cdk_common::payment::Event::PaymentReceived(wait_payment_response) => {
                                         if let Err(e) = Self::handle_payment_notification(
-                                            &localstore,
-                                            &pubsub_manager,
+                                            &self.localstore,
+                                            &self.pubsub_manager,
                                             wait_payment_response,
                                         ).await {
                                             tracing::warn!("Payment notification error: {:?}", e);
                                         }
                                     }
+                                    cdk_common::payment::Event::OutgoingPaymentUpdate(make_payment_response) => {
+                                        if let Err(e) = self
+                                            .handle_outgoing_payment_update(make_payment_response)
+                                            .await
+                                        {
+                                            tracing::warn!("Outgoing payment update error: {:?}", e);
+                                        }
+                                    }
+                                    cdk_common::payment::Event::SettingsChanged(settings) => {
+                                        if let Err(e) = self
+                                            .handle_payment_settings_changed(&processor, settings)
+                                            .await
+                                        {
+                                            tracing::warn!("Payment settings update error: {:?}", e);
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -5,13 +5,18 @@
 
 use std::str::FromStr;
 
-use cdk_common::mint::OperationKind;
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use cdk_common::amount::to_unit;
+use cdk_common::mint::{MeltPaymentRequest, MeltSagaState, OperationKind, Saga, SagaStateEnum};
+use cdk_common::payment::{MakePaymentResponse, PaymentIdentifier};
 use cdk_common::QuoteId;
 
 use super::{Error, Mint};
 use crate::mint::swap::swap_saga::compensation::{CompensatingAction, RemoveSwapSetup};
-use crate::mint::{MeltQuote, MeltQuoteState};
+use crate::mint::{AuditLogEntry, MeltQuote, MeltQuoteState};
 use crate::types::PaymentProcessorKey;
+use crate::util::hex;
 
 impl Mint {
     /// Checks the payment status of a melt quote with the LN backend
@@ -77,6 +82,7 @@ impl Mint {
         total_spent: cdk_common::Amount,
         payment_preimage: Option<String>,
         payment_lookup_id: &cdk_common::payment::PaymentIdentifier,
+        saga_id: Option<&uuid::Uuid>,
     ) -> Result<(), Error> {
         tracing::info!("Finalizing paid melt quote {} during startup", quote.id);
 
@@ -89,6 +95,8 @@ impl Mint {
             total_spent,
             payment_preimage,
             payment_lookup_id,
+            saga_id,
+            None,
         )
         .await?;
 
@@ -368,38 +376,10 @@ impl Mint {
                                     quote_id
                                 );
 
-                                if let Err(err) = self
-                                    .finalize_paid_melt_quote(
-                                        &quote,
-                                        payment_response.total_spent,
-                                        payment_response.payment_proof,
-                                        &payment_response.payment_lookup_id,
-                                    )
-                                    .await
-                                {
-                                    tracing::error!(
-                                        "Failed to finalize paid melt saga {}: {}",
-                                        saga.operation_id,
-                                        err
-                                    );
-                                }
-
-                                // Delete saga after successful finalization
-                                let mut tx = self.localstore.begin_transaction().await?;
-                                if let Err(e) = tx.delete_saga(&saga.operation_id).await {
-                                    tracing::error!(
-                                        "Failed to delete saga for {}: {}",
-                                        saga.operation_id,
-                                        e
-                                    );
-                                    tx.rollback().await?;
-                                } else {
-                                    tx.commit().await?;
-                                    tracing::info!(
-                                        "Successfully recovered and finalized melt saga {}",
-                                        saga.operation_id
-                                    );
-                                }
+                                // A failed finalization keeps the saga, it is retried on the next start
+                                let _ = self
+                                    .finalize_melt_saga(&saga, &quote, payment_response)
+                                    .await;
 
                                 continue; // Skip compensation, saga handled
                             }
@@ -440,88 +420,574 @@ impl Mint {
 
             // Compensate if needed
             if should_compensate {
-                // Use saga data directly for compensation (like swap does)
-                tracing::info!(
-                    "Compensating melt saga {} (removing {} proofs, {} change outputs)",
-                    saga.operation_id,
-                    saga.input_ys.len(),
-                    saga.blinded_secrets.len()
-                );
-
-                // Compensate using saga data only - don't rely on quote state
-                let mut tx = self.localstore.begin_transaction().await?;
-
-                // Remove blinded messages (change outputs)
-                if !saga.blinded_secrets.is_empty() {
-                    if let Err(e) = tx.delete_blinded_messages(&saga.blinded_secrets).await {
-                        tracing::error!(
-                            "Failed to delete blinded messages for saga {}: {}",
-                            saga.operation_id,
-                            e
-                        );
-                        tx.rollback().await?;
-                        continue;
-                    }
-                }
-
-                // Remove proofs (inputs) - use None for quote_id like swap does
-                if !saga.input_ys.is_empty() {
-                    if let Err(e) = tx.remove_proofs(&saga.input_ys, None).await {
-                        tracing::error!(
-                            "Failed to remove proofs for saga {}: {}",
-                            saga.operation_id,
-                            e
-                        );
-                        tx.rollback().await?;
-                        continue;
-                    }
-                }
-
-                // Reset quote state to Unpaid (melt-specific, unlike swap)
-                if let Err(e) = tx
-                    .update_melt_quote_state(&quote_id_parsed, MeltQuoteState::Unpaid, None)
-                    .await
-                {
+                if let Err(e) = self.compensate_melt_saga(&saga, &quote_id_parsed).await {
                     tracing::error!(
-                        "Failed to reset quote state for saga {}: {}",
+                        "Failed to compensate melt saga {}: {}",
                         saga.operation_id,
                         e
                     );
-                    tx.rollback().await?;
-                    continue;
                 }
+            }
+        }
 
-                // Delete melt request tracking record
-                if let Err(e) = tx.delete_melt_request(&quote_id_parsed).await {
-                    tracing::error!(
-                        "Failed to delete melt request for saga {}: {}",
-                        saga.operation_id,
-                        e
-                    );
-                    // Don't fail if melt request doesn't exist - it might not have been created yet
-                }
+        tracing::info!(
+            "Successfully recovered {} incomplete melt sagas.",
+            total_sagas
+        );
 
-                // Delete saga after successful compensation
-                if let Err(e) = tx.delete_saga(&saga.operation_id).await {
-                    tracing::error!("Failed to delete saga for {}: {}", saga.operation_id, e);
-                    tx.rollback().await?;
-                    continue;
-                }
+        Ok(())
+    }
 
-                tx.commit().await?;
+    /// Finalizes a melt saga whose payment was confirmed as paid and deletes the saga
+    ///
+    /// The saga is kept if the finalization fails so it is retried.
+    async fn finalize_melt_saga(
+        &self,
+        saga: &Saga,
+        quote: &MeltQuote,
+        payment_response: MakePaymentResponse,
+    ) -> Result<(), Error> {
+        let total_spent = to_unit(
+            payment_response.total_spent,
+            &payment_response.unit,
+            &quote.unit,
+        )?;
 
-                tracing::info!(
-                    "Successfully recovered and compensated melt saga {}",
-                    saga.operation_id
-                );
+        self.finalize_paid_melt_quote(
+            quote,
+            total_spent,
+            payment_response.payment_proof,
+            &payment_response.payment_lookup_id,
+            Some(&saga.operation_id),
+        )
+        .await
+        .inspect_err(|err| {
+            tracing::error!(
+                "Failed to finalize paid melt saga {}: {}",
+                saga.operation_id,
+                err
+            )
+        })?;
+
+        tracing::info!(
+            "Successfully recovered and finalized melt saga {}",
+            saga.operation_id
+        );
+
+        Ok(())
+    }
+
+    /// Compensates a melt saga by removing its proofs and change outputs and
+    /// resetting the quote to unpaid
+    async fn compensate_melt_saga(&self, saga: &Saga, quote_id: &QuoteId) -> Result<(), Error> {
+        // Use saga data directly for compensation (like swap does)
+        tracing::info!(
+            "Compensating melt saga {} (removing {} proofs, {} change outputs)",
+            saga.operation_id,
+            saga.input_ys.len(),
+            saga.blinded_secrets.len()
+        );
+
+        // Compensate using saga data only - don't rely on quote state
+        let mut tx = self.localstore.begin_transaction().await?;
+
+        // Remove blinded messages (change outputs)
+        if !saga.blinded_secrets.is_empty() {
+            if let Err(e) = tx.delete_blinded_messages(&saga.blinded_secrets).await {
+                tx.rollback().await?;
+                return Err(e.into());
             }
         }
 
+        // Remove proofs (inputs) - use None for quote_id like swap does
+        if !saga.input_ys.is_empty() {
+            if let Err(e) = tx.remove_proofs(&saga.input_ys, None).await {
+                tx.rollback().await?;
+                return Err(e.into());
+            }
+        }
+
+        // Reset quote state to Unpaid (melt-specific, unlike swap)
+        if let Err(e) = tx
+            .update_melt_quote_state(quote_id, MeltQuoteState::Unpaid, None)
+            .await
+        {
+            tx.rollback().await?;
+            return Err(e.into());
+        }
+
+        // Delete melt request tracking record
+        if let Err(e) = tx.delete_melt_request(quote_id).await {
+            tracing::error!(
+                "Failed to delete melt request for saga {}: {}",
+                saga.operation_id,
+                e
+            );
+            // Don't fail if melt request doesn't exist - it might not have been created yet
+        }
+
+        // Delete saga after successful compensation
+        if let Err(e) = tx.delete_saga(&saga.operation_id).await {
+            tx.rollback().await?;
+            return Err(e.into());
+        }
+
+        tx.commit().await?;
+
         tracing::info!(
-            "Successfully recovered {} incomplete melt sagas.",
-            total_sagas
+            "Successfully recovered and compensated melt saga {}",
+            saga.operation_id
         );
 
         Ok(())
     }
+
+    /// Resolves a pending melt from an outgoing payment update of the payment processor
+    ///
+    /// Only melts whose saga recorded the payment as sent are handled, melts still
+    /// in progress are resolved by their own saga.
+    pub(crate) async fn handle_outgoing_payment_update(
+        &self,
+        payment_response: MakePaymentResponse,
+    ) -> Result<(), Error> {
+        let quote = self
+            .localstore
+            .get_melt_quote_by_request_lookup_id(&payment_response.payment_lookup_id)
+            .await?
+            .filter(|quote| quote.state == MeltQuoteState::Pending);
+
+        let Some(quote) = quote else {
+            tracing::debug!(
+                "No pending melt quote for outgoing payment {}",
+                payment_response.payment_lookup_id
+            );
+            return Ok(());
+        };
+
+        let quote_id = quote.id.to_string();
+        let saga = self
+            .localstore
+            .get_incomplete_sagas(OperationKind::Melt)
+            .await?
+            .into_iter()
+            .find(|saga| {
+                saga.quote_id.as_ref() == Some(&quote_id)
+                    && matches!(saga.state, SagaStateEnum::Melt(MeltSagaState::PaymentSent))
+            });
+
+        let Some(saga) = saga else {
+            tracing::debug!("Melt quote {} is still being processed", quote.id);
+            return Ok(());
+        };
+
+        tracing::info!(
+            "Outgoing payment for melt quote {} is {}",
+            quote.id,
+            payment_response.status
+        );
+
+        match payment_response.status {
+            MeltQuoteState::Paid => {
+                self.finalize_melt_saga(&saga, &quote, payment_response)
+                    .await
+            }
+            MeltQuoteState::Unpaid | MeltQuoteState::Failed => {
+                self.compensate_melt_saga(&saga, &quote.id).await
+            }
+            MeltQuoteState::Pending | MeltQuoteState::Unknown => Ok(()),
+        }
+    }
+
+    /// Gets a pending melt quote and the saga of its sent payment for manual resolution
+    ///
+    /// Melts whose saga has not sent the payment yet are still being processed and
+    /// cannot be resolved manually.
+    async fn get_stuck_melt(&self, quote_id: &QuoteId) -> Result<(MeltQuote, Saga), Error> {
+        let quote = self
+            .localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)?;
+
+        match quote.state {
+            MeltQuoteState::Pending => (),
+            MeltQuoteState::Paid => return Err(Error::PaidQuote),
+            MeltQuoteState::Unpaid | MeltQuoteState::Failed | MeltQuoteState::Unknown => {
+                return Err(Error::UnpaidQuote)
+            }
+        }
+
+        let quote_id = quote.id.to_string();
+        let saga = self
+            .localstore
+            .get_incomplete_sagas(OperationKind::Melt)
+            .await?
+            .into_iter()
+            .find(|saga| saga.quote_id.as_ref() == Some(&quote_id));
+
+        match saga {
+            Some(saga) if saga.state == SagaStateEnum::Melt(MeltSagaState::PaymentSent) => {
+                Ok((quote, saga))
+            }
+            Some(_) => Err(Error::PendingQuote),
+            None => {
+                tracing::error!("Pending melt quote {} has no saga", quote.id);
+                Err(Error::Internal)
+            }
+        }
+    }
+
+    /// Marks a stuck melt as paid, finalizing its quote, deleting its saga and recording
+    /// the audit log entry in the same transaction
+    async fn resolve_stuck_melt_paid(
+        &self,
+        saga: &Saga,
+        quote: &MeltQuote,
+        total_spent: cdk_common::Amount,
+        payment_preimage: Option<String>,
+        payment_lookup_id: &cdk_common::payment::PaymentIdentifier,
+        audit: Option<&AuditLogEntry>,
+    ) -> Result<(), Error> {
+        super::melt::shared::finalize_melt_quote(
+            self,
+            &self.localstore,
+            &self.pubsub_manager,
+            quote,
+            total_spent,
+            payment_preimage,
+            payment_lookup_id,
+            Some(&saga.operation_id),
+            audit,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks a stuck melt as failed, releasing its proofs, deleting its saga and recording
+    /// the audit log entry in the same transaction
+    async fn resolve_stuck_melt_failed(
+        &self,
+        saga: &Saga,
+        quote: &MeltQuote,
+        audit: Option<&AuditLogEntry>,
+    ) -> Result<(), Error> {
+        super::melt::shared::rollback_melt_quote(
+            &self.localstore,
+            &quote.id,
+            &saga.input_ys,
+            &saga.blinded_secrets,
+            Some(&saga.operation_id),
+            audit,
+        )
+        .await?;
+
+        self.pubsub_manager
+            .melt_quote_status(quote, None, None, MeltQuoteState::Unpaid);
+
+        Ok(())
+    }
+
+    /// Checks the payment of a stuck melt with the payment backend and resolves it
+    ///
+    /// The melt is finalized if the payment is paid and rolled back if it failed,
+    /// a payment that is still pending leaves the melt untouched. The audit log entry,
+    /// if given, is recorded with the resolution, its new value set to the resolved
+    /// state of the quote.
+    pub async fn force_check_melt_quote(
+        &self,
+        quote_id: &QuoteId,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<MeltQuote, Error> {
+        let (quote, saga) = self.get_stuck_melt(quote_id).await?;
+
+        let payment_response = self.check_melt_payment_status(&quote).await?;
+
+        tracing::info!(
+            "Manual check of melt quote {}: payment is {}",
+            quote.id,
+            payment_response.status
+        );
+
+        let audit = |state: MeltQuoteState| {
+            audit.clone().map(|mut entry| {
+                entry.new_value = Some(format!("{} {}", quote.id, state));
+                entry
+            })
+        };
+
+        match payment_response.status {
+            MeltQuoteState::Paid => {
+                let total_spent = to_unit(
+                    payment_response.total_spent,
+                    &payment_response.unit,
+                    &quote.unit,
+                )?;
+
+                self.resolve_stuck_melt_paid(
+                    &saga,
+                    &quote,
+                    total_spent,
+                    payment_response.payment_proof,
+                    &payment_response.payment_lookup_id,
+                    audit(MeltQuoteState::Paid).as_ref(),
+                )
+                .await?;
+            }
+            MeltQuoteState::Unpaid | MeltQuoteState::Failed => {
+                self.resolve_stuck_melt_failed(
+                    &saga,
+                    &quote,
+                    audit(MeltQuoteState::Unpaid).as_ref(),
+                )
+                .await?;
+            }
+            MeltQuoteState::Pending | MeltQuoteState::Unknown => (),
+        }
+
+        self.localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)
+    }
+
+    /// Marks a stuck melt as paid with the preimage of its payment
+    ///
+    /// For bolt11 requests the preimage must match the payment hash of the invoice,
+    /// which proves the payment was made. If the payment backend does not report the
+    /// payment as paid the actual routing fee is unknown, so the whole fee reserve is
+    /// kept. Other requests are only marked paid if the payment backend reports the
+    /// payment as paid and the preimage matches the payment it reports. The audit log
+    /// entry, if given, is recorded with the finalization.
+    pub async fn mark_melt_quote_paid(
+        &self,
+        quote_id: &QuoteId,
+        payment_preimage: String,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<MeltQuote, Error> {
+        let (quote, saga) = self.get_stuck_melt(quote_id).await?;
+
+        let preimage = hex::decode(&payment_preimage).map_err(|_| Error::InvalidPaymentPreimage)?;
+        let preimage_hash = Sha256Hash::hash(&preimage);
+
+        let invoice_verified = match &quote.request {
+            MeltPaymentRequest::Bolt11 { bolt11 } => {
+                if preimage_hash.as_byte_array() != bolt11.payment_hash().as_byte_array() {
+                    return Err(Error::InvalidPaymentPreimage);
+                }
+                true
+            }
+            MeltPaymentRequest::Bolt12 { .. } => false,
+        };
+
+        let payment_response = match self.check_melt_payment_status(&quote).await {
+            Ok(payment_response) => Some(payment_response),
+            Err(err) if invoice_verified => {
+                tracing::warn!(
+                    "Could not check payment of melt quote {} before marking it paid: {}",
+                    quote.id,
+                    err
+                );
+                None
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (total_spent, payment_lookup_id) = match payment_response {
+            Some(payment_response) if payment_response.status == MeltQuoteState::Paid => {
+                if !invoice_verified
+                    && !preimage_matches_payment(
+                        &payment_preimage,
+                        &preimage_hash,
+                        &payment_response,
+                    )
+                {
+                    return Err(Error::InvalidPaymentPreimage);
+                }
+
+                let total_spent = to_unit(
+                    payment_response.total_spent,
+                    &payment_response.unit,
+                    &quote.unit,
+                )?;
+
+                (total_spent, payment_response.payment_lookup_id)
+            }
+            Some(payment_response) if !invoice_verified => {
+                return Err(match payment_response.status {
+                    MeltQuoteState::Unpaid | MeltQuoteState::Failed => Error::PaymentFailed,
+                    _ => Error::UnknownPaymentState,
+                });
+            }
+            _ => {
+                let total_spent = quote
+                    .amount
+                    .checked_add(quote.fee_reserve)
+                    .ok_or(Error::AmountOverflow)?;
+
+                (
+                    total_spent,
+                    quote.request_lookup_id.clone().ok_or(Error::Internal)?,
+                )
+            }
+        };
+
+        tracing::info!("Manually marking melt quote {} as paid", quote.id);
+
+        self.resolve_stuck_melt_paid(
+            &saga,
+            &quote,
+            total_spent,
+            Some(payment_preimage),
+            &payment_lookup_id,
+            audit.as_ref(),
+        )
+        .await?;
+
+        self.localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)
+    }
+
+    /// Marks a stuck melt as failed, returning its proofs to the wallet
+    ///
+    /// Refused if the payment backend reports the payment as paid. A payment that is
+    /// still pending can succeed after the melt is marked failed, so this should only
+    /// be used once the payment is known to have failed. The audit log entry, if given,
+    /// is recorded with the rollback.
+    pub async fn mark_melt_quote_failed(
+        &self,
+        quote_id: &QuoteId,
+        audit: Option<AuditLogEntry>,
+    ) -> Result<MeltQuote, Error> {
+        let (quote, saga) = self.get_stuck_melt(quote_id).await?;
+
+        match self.check_melt_payment_status(&quote).await {
+            Ok(payment_response) if payment_response.status == MeltQuoteState::Paid => {
+                return Err(Error::PaidQuote);
+            }
+            Ok(_) => (),
+            Err(err) => tracing::warn!(
+                "Could not check payment of melt quote {} before marking it failed: {}",
+                quote.id,
+                err
+            ),
+        }
+
+        tracing::info!("Manually marking melt quote {} as failed", quote.id);
+
+        self.resolve_stuck_melt_failed(&saga, &quote, audit.as_ref())
+            .await?;
+
+        self.localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)
+    }
+}
+
+/// Checks a preimage against the proof and payment hash reported by the payment backend
+///
+/// At least one of them must be reported and every reported one must match.
+fn preimage_matches_payment(
+    payment_preimage: &str,
+    preimage_hash: &Sha256Hash,
+    payment_response: &MakePaymentResponse,
+) -> bool {
+    let proof_matches = payment_response
+        .payment_proof
+        .as_ref()
+        .map(|proof| proof.eq_ignore_ascii_case(payment_preimage));
+
+    let hash_matches = match &payment_response.payment_lookup_id {
+        PaymentIdentifier::PaymentHash(hash) | PaymentIdentifier::Bolt12PaymentHash(hash) => {
+            Some(preimage_hash.as_byte_array() == hash)
+        }
+        _ => None,
+    };
+
+    match (proof_matches, hash_matches) {
+        (None, None) => false,
+        (proof_matches, hash_matches) => {
+            proof_matches.unwrap_or(true) && hash_matches.unwrap_or(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::nuts::CurrencyUnit;
+    use cdk_common::Amount;
+
+    use super::*;
+
+    fn payment_response(
+        payment_lookup_id: PaymentIdentifier,
+        payment_proof: Option<String>,
+    ) -> MakePaymentResponse {
+        MakePaymentResponse {
+            payment_lookup_id,
+            payment_proof,
+            status: MeltQuoteState::Paid,
+            total_spent: Amount::from(100),
+            unit: CurrencyUnit::Sat,
+        }
+    }
+
+    #[test]
+    fn test_preimage_matches_payment() {
+        let preimage = hex::encode([7u8; 32]);
+        let preimage_hash = Sha256Hash::hash(&[7u8; 32]);
+        let payment_hash = *preimage_hash.as_byte_array();
+        let other = hex::encode([8u8; 32]);
+
+        // Reported payment hash
+        let response = payment_response(PaymentIdentifier::Bolt12PaymentHash(payment_hash), None);
+        assert!(preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+        let response = payment_response(PaymentIdentifier::PaymentHash([8u8; 32]), None);
+        assert!(!preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+
+        // Reported payment proof
+        let response = payment_response(
+            PaymentIdentifier::PaymentId([0u8; 32]),
+            Some(preimage.to_uppercase()),
+        );
+        assert!(preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+        let response =
+            payment_response(PaymentIdentifier::PaymentId([0u8; 32]), Some(other.clone()));
+        assert!(!preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+
+        // Both reported, both must match
+        let response = payment_response(PaymentIdentifier::PaymentHash(payment_hash), Some(other));
+        assert!(!preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+
+        // Nothing to verify against
+        let response = payment_response(PaymentIdentifier::PaymentId([0u8; 32]), None);
+        assert!(!preimage_matches_payment(
+            &preimage,
+            &preimage_hash,
+            &response
+        ));
+    }
 }
@@ -4,6 +4,12 @@ use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
+#[cfg(feature = "wallet")]
+use bitcoin::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+#[cfg(feature = "wallet")]
+use bitcoin::base64::Engine;
+#[cfg(feature = "wallet")]
+use bitcoin::hashes::{sha256, Hash};
 use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
 use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
 use reqwest::Client;
@@ -35,6 +41,12 @@ pub enum Error {
     /// Invalid Client ID
     #[error("Invalid Client ID")]
     InvalidClientId,
+    /// Provider does not support the authorization code flow
+    #[error("Missing authorization endpoint")]
+    MissingAuthorizationEndpoint,
+    /// Invalid url
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
 }
 
 impl From<Error> for cdk_common::error::Error {
@@ -51,6 +63,8 @@ pub struct OidcConfig {
     pub issuer: String,
     pub token_endpoint: String,
     pub device_authorization_endpoint: String,
+    #[serde(default)]
+    pub authorization_endpoint: Option<String>,
 }
 
 /// Http Client
@@ -68,6 +82,7 @@ pub struct OidcClient {
 #[serde(rename_all = "snake_case")]
 pub enum GrantType {
     RefreshToken,
+    AuthorizationCode,
 }
 
 #[cfg(feature = "wallet")]
@@ -78,6 +93,54 @@ pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
+#[cfg(feature = "wallet")]
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorizationCodeRequest {
+    pub grant_type: GrantType,
+    pub client_id: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+/// PKCE (RFC 7636) code verifier and its `S256` challenge
+#[cfg(feature = "wallet")]
+#[derive(Debug, Clone)]
+pub struct PkceChallenge {
+    /// Code verifier, sent with the authorization code
+    pub verifier: String,
+    /// Code challenge, sent with the authorization request
+    pub challenge: String,
+}
+
+#[cfg(feature = "wallet")]
+impl PkceChallenge {
+    /// Create a [`PkceChallenge`] with a random verifier
+    pub fn new() -> Self {
+        let mut bytes = [0u8; 32];
+        getrandom::getrandom(&mut bytes).expect("Failed to generate random bytes");
+
+        Self::from_verifier(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Create a [`PkceChallenge`] for the verifier
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(sha256::Hash::hash(verifier.as_bytes()));
+
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+#[cfg(feature = "wallet")]
+impl Default for PkceChallenge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(feature = "wallet")]
 #[derive(Debug, Clone, Deserialize)]
 pub struct TokenResponse {
@@ -259,4 +322,65 @@ impl OidcClient {
 
         Ok(response)
     }
+    /// Url to open in the browser to start the authorization code flow with PKCE
+    ///
+    /// The provider redirects to `redirect_uri` with the `code` to exchange and the `state`.
+    #[cfg(feature = "wallet")]
+    pub async fn authorization_url(
+        &self,
+        client_id: &str,
+        redirect_uri: &str,
+        pkce: &PkceChallenge,
+        state: &str,
+    ) -> Result<url::Url, Error> {
+        let authorization_endpoint = self
+            .get_oidc_config()
+            .await?
+            .authorization_endpoint
+            .ok_or(Error::MissingAuthorizationEndpoint)?;
+
+        let mut url = url::Url::parse(&authorization_endpoint)?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("scope", "openid")
+            .append_pair("code_challenge", &pkce.challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", state);
+
+        Ok(url)
+    }
+
+    /// Exchange an authorization code for tokens with the PKCE verifier
+    #[cfg(feature = "wallet")]
+    pub async fn exchange_authorization_code(
+        &self,
+        client_id: String,
+        code: String,
+        redirect_uri: String,
+        pkce: &PkceChallenge,
+    ) -> Result<TokenResponse, Error> {
+        let token_url = self.get_oidc_config().await?.token_endpoint;
+
+        let request = AuthorizationCodeRequest {
+            grant_type: GrantType::AuthorizationCode,
+            client_id,
+            code,
+            redirect_uri,
+            code_verifier: pkce.verifier.clone(),
+        };
+
+        let response = self
+            .client
+            .post(token_url)
+            .form(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TokenResponse>()
+            .await?;
+
+        Ok(response)
+    }
 }
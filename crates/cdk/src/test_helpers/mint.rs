@@ -16,7 +16,7 @@ use cdk_common::{
 use cdk_fake_wallet::FakeWallet;
 use tokio::time::sleep;
 
-use crate::mint::{Mint, MintBuilder, MintMeltLimits};
+use crate::mint::{Bolt11MintQuoteSettings, Mint, MintBuilder, MintMeltLimits};
 use crate::types::{FeeReserve, QuoteTTL};
 use crate::Error;
 
@@ -51,9 +51,17 @@ pub(crate) fn should_fail_for(operation: &str) -> bool {
 /// }
 /// ```
 pub async fn create_test_mint() -> Result<Mint, Error> {
+    create_test_mint_with_bolt11_settings(Bolt11MintQuoteSettings::default()).await
+}
+
+/// Creates and starts a test mint with the given bolt11 mint quote settings.
+pub async fn create_test_mint_with_bolt11_settings(
+    bolt11_settings: Bolt11MintQuoteSettings,
+) -> Result<Mint, Error> {
     let db = Arc::new(cdk_sqlite::mint::memory::empty().await?);
 
-    let mut mint_builder = MintBuilder::new(db.clone());
+    let mut mint_builder =
+        MintBuilder::new(db.clone()).with_bolt11_mint_quote_settings(bolt11_settings);
 
     let fee_reserve = FeeReserve {
         min_fee_reserve: 1.into(),
@@ -117,6 +125,8 @@ pub async fn mint_test_proofs(mint: &Mint, amount: Amount) -> Result<Proofs, Err
                 unit: CurrencyUnit::Sat,
                 description: None,
                 pubkey: None,
+                description_hash: None,
+                expiry_secs: None,
             }
             .into(),
         )
@@ -3,11 +3,14 @@ use std::sync::Arc;
 
 use cdk_common::database::{self, WalletDatabase};
 use cdk_common::mint_url::MintUrl;
+use cdk_common::util::unix_time;
 use cdk_common::{AuthProof, Id, Keys, MintInfo};
+use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::instrument;
 
+use super::bat_pool::BatPool;
 use super::AuthMintConnector;
 use crate::amount::SplitTarget;
 use crate::dhke::construct_proofs;
@@ -21,9 +24,12 @@ use crate::wallet::mint_connector::AuthHttpClient;
 use crate::wallet::mint_metadata_cache::MintMetadataCache;
 use crate::{Amount, Error, OidcClient};
 
+/// Seconds before its expiry from which a CAT is refreshed
+const CAT_EXPIRY_LEEWAY: u64 = 30;
+
 /// JWT Claims structure for decoding tokens
 #[derive(Debug, Serialize, Deserialize)]
-struct _Claims {
+struct Claims {
     /// Subject
     sub: Option<String>,
     /// Expiration time (as UTC timestamp)
@@ -31,6 +37,24 @@ struct _Claims {
     /// Issued at (as UTC timestamp)
     iat: Option<u64>,
 }
+
+/// Whether the CAT expires within [`CAT_EXPIRY_LEEWAY`]
+///
+/// The claims are read without verifying the signature, which is only used to decide when to
+/// refresh. A CAT that cannot be decoded is not considered expired.
+fn cat_expired(cat: &str) -> bool {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+
+    decode::<Claims>(cat, &DecodingKey::from_secret(&[]), &validation)
+        .ok()
+        .and_then(|data| data.claims.exp)
+        .is_some_and(|exp| exp <= unix_time() + CAT_EXPIRY_LEEWAY)
+}
+
 /// CDK Auth Wallet
 ///
 /// A [`AuthWallet`] is for auth operations with a single mint.
@@ -46,9 +70,11 @@ pub struct AuthWallet {
     pub protected_endpoints: Arc<RwLock<HashMap<ProtectedEndpoint, AuthRequired>>>,
     /// Refresh token for auth
     refresh_token: Arc<RwLock<Option<String>>>,
-    auth_client: Arc<dyn AuthMintConnector + Send + Sync>,
+    pub(super) auth_client: Arc<dyn AuthMintConnector + Send + Sync>,
     /// OIDC client for authentication
     oidc_client: Arc<RwLock<Option<OidcClient>>>,
+    /// Blind auth token pool
+    pub(super) bat_pool: Arc<BatPool>,
 }
 
 impl AuthWallet {
@@ -70,6 +96,7 @@ impl AuthWallet {
             refresh_token: Arc::new(RwLock::new(None)),
             auth_client: http_client,
             oidc_client: Arc::new(RwLock::new(oidc_client)),
+            bat_pool: Arc::new(BatPool::default()),
         }
     }
 
@@ -284,9 +311,20 @@ impl AuthWallet {
     }
 
     /// Get Auth Token
+    ///
+    /// With a BAT pool, waits for a refill when the wallet has no BAT left and starts a background
+    /// refill when the BAT taken leaves the wallet below the low water mark.
     #[instrument(skip(self))]
     pub async fn get_blind_auth_token(&self) -> Result<Option<BlindAuthToken>, Error> {
-        let unspent = self.get_unspent_auth_proofs().await?;
+        // Concurrent requests would otherwise take the same first unspent BAT
+        let _guard = self.bat_pool.take_lock.lock().await;
+        let mut unspent = self.get_unspent_auth_proofs().await?;
+
+        if unspent.is_empty() && self.bat_pool_config().await.is_some() {
+            tracing::debug!("BAT pool is empty, waiting for a refill");
+            self.refill_bat_pool().await?;
+            unspent = self.get_unspent_auth_proofs().await?;
+        }
 
         let auth_proof = match unspent.first() {
             Some(proof) => {
@@ -298,6 +336,8 @@ impl AuthWallet {
             None => return Ok(None),
         };
 
+        self.spawn_bat_pool_refill(unspent.len() as u64 - 1).await;
+
         Ok(Some(BlindAuthToken {
             auth_proof: auth_proof.clone(),
         }))
@@ -356,23 +396,31 @@ impl AuthWallet {
                     return Err(Error::ClearAuthRequired);
                 }
 
-                if let Err(err) = self.verify_cat(auth_token).await {
+                let cat_valid = if cat_expired(cat) {
+                    tracing::info!("Current cat has expired");
+                    false
+                } else if let Err(err) = self.verify_cat(auth_token.clone()).await {
                     tracing::warn!("Current cat is invalid {}", err);
-                }
+                    false
+                } else {
+                    true
+                };
 
-                let has_refresh;
+                if !cat_valid {
+                    let has_refresh;
 
-                {
-                    has_refresh = self.refresh_token.read().await.is_some();
-                }
+                    {
+                        has_refresh = self.refresh_token.read().await.is_some();
+                    }
 
-                if has_refresh {
-                    tracing::info!("Attempting to refresh using refresh token");
-                    self.refresh_access_token().await?;
-                } else {
-                    tracing::warn!(
-                        "Wallet cat is invalid and there is no refresh token please reauth"
-                    );
+                    if has_refresh {
+                        tracing::info!("Attempting to refresh using refresh token");
+                        self.refresh_access_token().await?;
+                    } else {
+                        tracing::warn!(
+                            "Wallet cat is invalid and there is no refresh token please reauth"
+                        );
+                    }
                 }
             }
             AuthToken::BlindAuth(_) => {
@@ -468,3 +516,33 @@ impl AuthWallet {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use super::*;
+
+    fn cat(exp: Option<u64>) -> String {
+        let claims = Claims {
+            sub: Some("wallet".to_string()),
+            exp,
+            iat: Some(unix_time()),
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cat_expired() {
+        assert!(cat_expired(&cat(Some(unix_time() - 10))));
+        assert!(cat_expired(&cat(Some(unix_time() + CAT_EXPIRY_LEEWAY / 2))));
+        assert!(!cat_expired(&cat(Some(unix_time() + 3600))));
+        assert!(!cat_expired(&cat(None)));
+        assert!(!cat_expired("not a jwt"));
+    }
+}
@@ -0,0 +1,394 @@
+//! Blind auth token pool
+//!
+//! Keeps the unspent blind auth tokens (BATs) of an [`AuthWallet`] between a low and a high water
+//! mark. Spending a BAT that leaves fewer than `low_water` in the wallet starts a background
+//! refill up to `high_water`, so protected requests only wait for BATs to be minted once the pool
+//! is empty.
+//!
+//! Refills mint with the cached clear auth token (CAT), refreshed with the refresh token once it
+//! has expired, and request at most the `bat_max_mint` of the mint at a time.
+use std::sync::Arc;
+
+use cdk_common::task::spawn;
+use tokio::sync::{Mutex, RwLock};
+use tracing::instrument;
+
+use super::AuthWallet;
+use crate::{Amount, Error};
+
+/// Low and high water marks of the blind auth token pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatPoolConfig {
+    /// Number of unspent BATs below which a refill starts
+    pub low_water: u64,
+    /// Number of unspent BATs a refill mints up to
+    pub high_water: u64,
+}
+
+impl Default for BatPoolConfig {
+    fn default() -> Self {
+        Self {
+            low_water: 5,
+            high_water: 20,
+        }
+    }
+}
+
+/// Shared state of the pool of an [`AuthWallet`] and its clones
+#[derive(Debug, Default)]
+pub(super) struct BatPool {
+    config: RwLock<Option<BatPoolConfig>>,
+    /// Held for the duration of a refill
+    refill_lock: Arc<Mutex<()>>,
+    /// Held while a BAT is taken from the wallet, so it is never handed out twice
+    pub(super) take_lock: Mutex<()>,
+}
+
+impl AuthWallet {
+    /// Set the water marks of the blind auth token pool, `None` disables it
+    ///
+    /// Starts a background refill if the wallet holds fewer BATs than the new low water mark.
+    #[instrument(skip(self))]
+    pub async fn set_bat_pool(&self, config: Option<BatPoolConfig>) -> Result<(), Error> {
+        if let Some(config) = config {
+            if config.high_water == 0 || config.low_water > config.high_water {
+                return Err(Error::Custom(format!(
+                    "Invalid BAT pool water marks: low {} high {}",
+                    config.low_water, config.high_water
+                )));
+            }
+        }
+
+        *self.bat_pool.config.write().await = config;
+
+        let unspent = self.get_unspent_auth_proofs().await?.len() as u64;
+        self.spawn_bat_pool_refill(unspent).await;
+
+        Ok(())
+    }
+
+    /// Get the water marks of the blind auth token pool if it is enabled
+    #[instrument(skip(self))]
+    pub async fn bat_pool_config(&self) -> Option<BatPoolConfig> {
+        *self.bat_pool.config.read().await
+    }
+
+    /// Mint BATs up to the high water mark of the pool
+    ///
+    /// Waits for a refill already in progress instead of starting another one. Returns the number
+    /// of BATs minted, zero if the pool is disabled or already full.
+    #[instrument(skip(self))]
+    pub async fn refill_bat_pool(&self) -> Result<Amount, Error> {
+        let _guard = self.bat_pool.refill_lock.lock().await;
+        self.fill_bat_pool().await
+    }
+
+    /// Start a background refill if `unspent` is below the low water mark and no refill is running
+    pub(super) async fn spawn_bat_pool_refill(&self, unspent: u64) {
+        let Some(config) = self.bat_pool_config().await else {
+            return;
+        };
+
+        if unspent >= config.low_water {
+            return;
+        }
+
+        let Ok(guard) = self.bat_pool.refill_lock.clone().try_lock_owned() else {
+            tracing::trace!("BAT pool refill already in progress");
+            return;
+        };
+
+        let wallet = self.clone();
+        spawn(async move {
+            let _guard = guard;
+            match wallet.fill_bat_pool().await {
+                Ok(minted) => tracing::debug!("Refilled BAT pool with {} tokens", minted),
+                Err(err) => tracing::warn!("Could not refill BAT pool: {}", err),
+            }
+        });
+    }
+
+    /// Mint BATs up to the high water mark, the caller holds the refill lock
+    async fn fill_bat_pool(&self) -> Result<Amount, Error> {
+        let Some(config) = self.bat_pool_config().await else {
+            return Ok(Amount::ZERO);
+        };
+
+        let unspent = self.get_unspent_auth_proofs().await?.len() as u64;
+        let mut missing = config.high_water.saturating_sub(unspent);
+        if missing == 0 {
+            return Ok(Amount::ZERO);
+        }
+
+        // The auth metadata does not hold the mint info unless the wallet loaded it
+        let bat_max_mint = match self
+            .metadata_cache
+            .load_auth(&self.localstore, &self.auth_client)
+            .await?
+            .mint_info
+            .bat_max_mint()
+        {
+            Some(bat_max_mint) => Some(bat_max_mint),
+            None => self
+                .get_mint_info()
+                .await?
+                .and_then(|mint_info| mint_info.bat_max_mint()),
+        }
+        .filter(|max| *max > 0)
+        .unwrap_or(missing);
+
+        tracing::debug!(
+            "Refilling BAT pool from {} to {} tokens, at most {} per request",
+            unspent,
+            config.high_water,
+            bat_max_mint
+        );
+
+        let mut minted = Amount::ZERO;
+        while missing > 0 {
+            let amount = missing.min(bat_max_mint);
+            let proofs = self.mint_blind_auth(Amount::from(amount)).await?;
+            minted += Amount::from(proofs.len() as u64);
+            missing = missing.saturating_sub(amount);
+        }
+
+        Ok(minted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex as StdMutex;
+    use std::time::Duration;
+
+    use bitcoin::secp256k1::Secp256k1;
+    use cdk_common::dhke::sign_message;
+    use cdk_common::mint_url::MintUrl;
+    use cdk_common::nut02::KeySetVersion;
+    use cdk_common::nut22::Settings as BlindAuthSettings;
+    use cdk_common::{
+        AuthToken, BlindSignature, CurrencyUnit, Id, KeySet, KeySetInfo, KeysetResponse,
+        MintAuthRequest, MintInfo, MintKeySet, MintResponse,
+    };
+
+    use super::*;
+    use crate::wallet::auth::AuthMintConnector;
+    use crate::wallet::mint_metadata_cache::MintMetadataCache;
+
+    /// Mint signing BATs and recording the size of each mint request
+    #[derive(Debug)]
+    struct FakeAuthMint {
+        keyset: MintKeySet,
+        bat_max_mint: u64,
+        requests: StdMutex<Vec<usize>>,
+    }
+
+    impl FakeAuthMint {
+        fn new(bat_max_mint: u64) -> Self {
+            Self {
+                keyset: MintKeySet::generate_from_seed(
+                    &Secp256k1::new(),
+                    &[0u8; 32],
+                    &[1],
+                    CurrencyUnit::Auth,
+                    "m/0'/4'/0'".parse().unwrap(),
+                    None,
+                    KeySetVersion::Version00,
+                ),
+                bat_max_mint,
+                requests: StdMutex::new(Vec::new()),
+            }
+        }
+
+        fn requests(&self) -> Vec<usize> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AuthMintConnector for FakeAuthMint {
+        async fn get_auth_token(&self) -> Result<AuthToken, Error> {
+            Ok(AuthToken::ClearAuth("cat".to_string()))
+        }
+
+        async fn set_auth_token(&self, _token: AuthToken) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn get_mint_info(&self) -> Result<MintInfo, Error> {
+            let mut mint_info = MintInfo::default();
+            mint_info.nuts.nut22 = Some(BlindAuthSettings::new(self.bat_max_mint, Vec::new()));
+            Ok(mint_info)
+        }
+
+        async fn get_mint_blind_auth_keyset(&self, _keyset_id: Id) -> Result<KeySet, Error> {
+            Ok(self.keyset.clone().into())
+        }
+
+        async fn get_mint_blind_auth_keysets(&self) -> Result<KeysetResponse, Error> {
+            Ok(KeysetResponse {
+                keysets: vec![KeySetInfo {
+                    id: self.keyset.id,
+                    unit: CurrencyUnit::Auth,
+                    active: true,
+                    input_fee_ppk: 0,
+                    final_expiry: None,
+                }],
+            })
+        }
+
+        async fn post_mint_blind_auth(
+            &self,
+            request: MintAuthRequest,
+        ) -> Result<MintResponse, Error> {
+            self.requests.lock().unwrap().push(request.outputs.len());
+
+            let signatures = request
+                .outputs
+                .iter()
+                .map(|output| {
+                    let key = &self.keyset.keys[&output.amount].secret_key;
+                    let c = sign_message(key, &output.blinded_secret)?;
+                    Ok(BlindSignature::new(
+                        output.amount,
+                        c,
+                        output.keyset_id,
+                        &output.blinded_secret,
+                        key.clone(),
+                    )?)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            Ok(MintResponse { signatures })
+        }
+    }
+
+    async fn wallet(mint: Arc<FakeAuthMint>) -> AuthWallet {
+        let mint_url = "https://mint.example".parse::<MintUrl>().unwrap();
+        let localstore = Arc::new(cdk_sqlite::wallet::memory::empty().await.unwrap());
+        let mut wallet = AuthWallet::new(
+            mint_url.clone(),
+            None,
+            localstore,
+            Arc::new(MintMetadataCache::new(mint_url)),
+            HashMap::new(),
+            None,
+        );
+        wallet.auth_client = mint;
+        wallet
+    }
+
+    async fn unspent(wallet: &AuthWallet) -> u64 {
+        wallet.get_unspent_auth_proofs().await.unwrap().len() as u64
+    }
+
+    #[tokio::test]
+    async fn test_bat_pool_refills_in_chunks_of_bat_max_mint() {
+        let mint = Arc::new(FakeAuthMint::new(3));
+        let wallet = wallet(mint.clone()).await;
+
+        // Disabled pool mints nothing
+        assert_eq!(wallet.refill_bat_pool().await.unwrap(), Amount::ZERO);
+
+        *wallet.bat_pool.config.write().await = Some(BatPoolConfig {
+            low_water: 2,
+            high_water: 7,
+        });
+        assert_eq!(wallet.refill_bat_pool().await.unwrap(), Amount::from(7));
+        assert_eq!(mint.requests(), vec![3, 3, 1]);
+        assert_eq!(unspent(&wallet).await, 7);
+
+        // A full pool is not refilled
+        assert_eq!(wallet.refill_bat_pool().await.unwrap(), Amount::ZERO);
+        assert_eq!(mint.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_bat_pool_refills_below_low_water() {
+        let mint = Arc::new(FakeAuthMint::new(10));
+        let wallet = wallet(mint.clone()).await;
+
+        assert!(wallet
+            .set_bat_pool(Some(BatPoolConfig {
+                low_water: 5,
+                high_water: 2,
+            }))
+            .await
+            .is_err());
+
+        *wallet.bat_pool.config.write().await = Some(BatPoolConfig {
+            low_water: 2,
+            high_water: 4,
+        });
+
+        // An empty pool waits for the refill
+        assert!(wallet.get_blind_auth_token().await.unwrap().is_some());
+        assert_eq!(mint.requests(), vec![4]);
+        assert_eq!(unspent(&wallet).await, 3);
+
+        // Down to the low water mark, nothing is minted
+        wallet.get_blind_auth_token().await.unwrap().unwrap();
+        assert_eq!(unspent(&wallet).await, 2);
+        let _guard = wallet.bat_pool.refill_lock.lock().await;
+        assert_eq!(mint.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bat_pool_background_refill() {
+        let mint = Arc::new(FakeAuthMint::new(10));
+        let wallet = wallet(mint.clone()).await;
+
+        wallet
+            .set_bat_pool(Some(BatPoolConfig {
+                low_water: 2,
+                high_water: 3,
+            }))
+            .await
+            .unwrap();
+        wait_for_unspent(&wallet, 3).await;
+
+        // Taking a BAT below the low water mark refills in the background
+        wallet.get_blind_auth_token().await.unwrap().unwrap();
+        wallet.get_blind_auth_token().await.unwrap().unwrap();
+        wait_for_unspent(&wallet, 3).await;
+        assert_eq!(mint.requests(), vec![3, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_blind_auth_token_is_taken_once() {
+        let mint = Arc::new(FakeAuthMint::new(10));
+        let wallet = wallet(mint).await;
+        wallet.mint_blind_auth(Amount::from(10)).await.unwrap();
+
+        let tokens = futures::future::join_all(
+            (0..10).map(|_| async { wallet.get_blind_auth_token().await }),
+        )
+        .await
+        .into_iter()
+        .map(|token| {
+            token
+                .unwrap()
+                .expect("a BAT for each request")
+                .auth_proof
+                .y()
+        })
+        .collect::<Result<std::collections::HashSet<_>, _>>()
+        .unwrap();
+
+        assert_eq!(tokens.len(), 10);
+        assert_eq!(unspent(&wallet).await, 0);
+    }
+
+    async fn wait_for_unspent(wallet: &AuthWallet, expected: u64) {
+        for _ in 0..100 {
+            if unspent(wallet).await == expected {
+                // Let a refill holding the lock finish
+                let _guard = wallet.bat_pool.refill_lock.lock().await;
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("wallet did not reach {expected} unspent BATs");
+    }
+}
@@ -0,0 +1,382 @@
+//! Loopback redirect listener
+//!
+//! Receives the redirect of the browser at the end of the OAuth2 authorization code flow
+//! (RFC 8252), so desktop wallets can log in with the provider's own login page.
+use std::time::Duration;
+
+use bitcoin::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use bitcoin::base64::Engine;
+use getrandom::getrandom;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::instrument;
+
+use crate::Error;
+
+/// Path the provider redirects to
+const CALLBACK_PATH: &str = "/callback";
+
+/// Maximum size of the request of the browser
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Time a connection has to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time the user has to log in
+pub const DEFAULT_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Listener on `127.0.0.1` waiting for the authorization code
+#[derive(Debug)]
+pub struct LoopbackRedirect {
+    listener: TcpListener,
+    redirect_uri: String,
+    state: String,
+    timeout: Duration,
+}
+
+impl LoopbackRedirect {
+    /// Bind a listener on `127.0.0.1`, a `port` of 0 picks a free port
+    pub async fn bind(port: u16) -> Result<Self, Error> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|err| Error::Custom(format!("Could not bind loopback listener: {err}")))?;
+        let port = listener
+            .local_addr()
+            .map_err(|err| Error::Custom(err.to_string()))?
+            .port();
+
+        let mut state = [0u8; 16];
+        getrandom(&mut state).expect("Failed to generate random bytes");
+
+        Ok(Self {
+            listener,
+            redirect_uri: format!("http://127.0.0.1:{port}{CALLBACK_PATH}"),
+            state: URL_SAFE_NO_PAD.encode(state),
+            timeout: DEFAULT_LOGIN_TIMEOUT,
+        })
+    }
+
+    /// Set the time the user has to log in, [`DEFAULT_LOGIN_TIMEOUT`] by default
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Redirect uri to register in the authorization request
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Random state to send in the authorization request, checked on the redirect
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Wait for the redirect of the browser and return the authorization code
+    ///
+    /// Requests to other paths, such as the favicon, are answered with a 404 and ignored, as are
+    /// redirects without the state of the listener, which any local process could send. Fails if
+    /// no redirect arrives within the timeout.
+    #[instrument(skip(self))]
+    pub async fn wait_for_code(self) -> Result<String, Error> {
+        tokio::time::timeout(self.timeout, self.accept_redirects())
+            .await
+            .map_err(|_| Error::Custom("Timed out waiting for the login".to_string()))?
+    }
+
+    async fn accept_redirects(&self) -> Result<String, Error> {
+        loop {
+            let (mut stream, _) = self
+                .listener
+                .accept()
+                .await
+                .map_err(|err| Error::Custom(format!("Loopback listener failed: {err}")))?;
+
+            let target = tokio::time::timeout(READ_TIMEOUT, read_request_target(&mut stream))
+                .await
+                .ok()
+                .flatten();
+            let Some(target) = target else {
+                respond(&mut stream, "400 Bad Request", "Invalid request").await;
+                continue;
+            };
+
+            let url = match url::Url::parse(&format!("http://127.0.0.1{target}")) {
+                Ok(url) if url.path() == CALLBACK_PATH => url,
+                _ => {
+                    respond(&mut stream, "404 Not Found", "Not found").await;
+                    continue;
+                }
+            };
+
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.into_owned())
+            };
+
+            if param("state").as_deref() != Some(self.state.as_str()) {
+                tracing::warn!("Ignoring authorization redirect with invalid state");
+                respond(&mut stream, "400 Bad Request", "Invalid state").await;
+                continue;
+            }
+
+            if let Some(error) = param("error") {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Login failed, you can close this window",
+                )
+                .await;
+                return Err(Error::Custom(format!("Authorization failed: {error}")));
+            }
+
+            let Some(code) = param("code") else {
+                respond(&mut stream, "400 Bad Request", "Missing code").await;
+                return Err(Error::Custom(
+                    "Authorization redirect without code".to_string(),
+                ));
+            };
+
+            respond(
+                &mut stream,
+                "200 OK",
+                "Login complete, you can close this window",
+            )
+            .await;
+
+            return Ok(code);
+        }
+    }
+}
+
+/// Read the request head and return the target of a `GET` request
+async fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+            return None;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8(request).ok()?;
+    let mut request_line = request.lines().next()?.split(' ');
+
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!("<html><body><p>{message}</p></body></html>");
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    if let Err(err) = stream.write_all(response.as_bytes()).await {
+        tracing::debug!("Could not answer loopback request: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{OidcClient, PkceChallenge};
+
+    const CODE: &str = "authorization-code";
+
+    /// Mock OIDC provider answering the discovery and the authorization code exchange
+    async fn mock_provider(challenge: Arc<Mutex<Option<String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let config = serde_json::json!({
+            "issuer": base,
+            "jwks_uri": format!("{base}/jwks"),
+            "token_endpoint": format!("{base}/token"),
+            "device_authorization_endpoint": format!("{base}/device"),
+            "authorization_endpoint": format!("{base}/authorize"),
+        });
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                let (head, body) = loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|length| length.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or_default();
+                        if body.len() >= length {
+                            break (head.to_string(), body.to_string());
+                        }
+                    }
+                };
+
+                let form = url::form_urlencoded::parse(body.as_bytes())
+                    .into_owned()
+                    .collect::<HashMap<_, _>>();
+                let expected = challenge.lock().unwrap().clone();
+
+                let (status, response) = if head.starts_with("GET /.well-known") {
+                    ("200 OK", config.to_string())
+                } else if head.starts_with("POST /token")
+                    && form.get("grant_type").map(String::as_str) == Some("authorization_code")
+                    && form.get("code").map(String::as_str) == Some(CODE)
+                    && form
+                        .get("code_verifier")
+                        .map(|verifier| PkceChallenge::from_verifier(verifier.clone()).challenge)
+                        == expected
+                {
+                    (
+                        "200 OK",
+                        serde_json::json!({
+                            "access_token": "access",
+                            "refresh_token": "refresh",
+                            "token_type": "Bearer",
+                        })
+                        .to_string(),
+                    )
+                } else {
+                    (
+                        "400 Bad Request",
+                        r#"{"error":"invalid_grant"}"#.to_string(),
+                    )
+                };
+
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        format!("{base}/.well-known/openid-configuration")
+    }
+
+    /// Follows the authorization url like a browser after the user logged in
+    async fn authorize(url: &url::Url, challenge: &Arc<Mutex<Option<String>>>, state: &str) {
+        let params = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["code_challenge_method"], "S256");
+        *challenge.lock().unwrap() = Some(params["code_challenge"].clone());
+
+        let redirect = format!("{}?code={CODE}&state={state}", params["redirect_uri"]);
+        reqwest::get(redirect).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow() {
+        let challenge = Arc::new(Mutex::new(None));
+        let oidc = OidcClient::new(mock_provider(challenge.clone()).await, None);
+
+        let loopback = LoopbackRedirect::bind(0).await.unwrap();
+        let pkce = PkceChallenge::new();
+        let url = oidc
+            .authorization_url(
+                "cashu-client",
+                loopback.redirect_uri(),
+                &pkce,
+                loopback.state(),
+            )
+            .await
+            .unwrap();
+        let redirect_uri = loopback.redirect_uri().to_string();
+        let state = loopback.state().to_string();
+
+        let (code, _) = tokio::join!(
+            loopback.wait_for_code(),
+            authorize(&url, &challenge, &state)
+        );
+        let code = code.unwrap();
+        assert_eq!(code, CODE);
+
+        let tokens = oidc
+            .exchange_authorization_code(
+                "cashu-client".to_string(),
+                code.clone(),
+                redirect_uri.clone(),
+                &pkce,
+            )
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, "access");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("refresh"));
+
+        // The code is useless without the verifier
+        assert!(oidc
+            .exchange_authorization_code(
+                "cashu-client".to_string(),
+                code,
+                redirect_uri,
+                &PkceChallenge::new(),
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_with_wrong_state_is_ignored() {
+        let challenge = Arc::new(Mutex::new(None));
+        let oidc = OidcClient::new(mock_provider(challenge.clone()).await, None);
+
+        let loopback = LoopbackRedirect::bind(0).await.unwrap();
+        let url = oidc
+            .authorization_url(
+                "cashu-client",
+                loopback.redirect_uri(),
+                &PkceChallenge::new(),
+                loopback.state(),
+            )
+            .await
+            .unwrap();
+        let redirect_uri = loopback.redirect_uri().to_string();
+        let state = loopback.state().to_string();
+
+        let (code, _) = tokio::join!(loopback.wait_for_code(), async {
+            for query in ["code=forged&state=forged", "error=access_denied"] {
+                let response = reqwest::get(format!("{redirect_uri}?{query}"))
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+            }
+            authorize(&url, &challenge, &state).await
+        });
+        assert_eq!(code.unwrap(), CODE);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_code_times_out() {
+        let loopback = LoopbackRedirect::bind(0)
+            .await
+            .unwrap()
+            .with_timeout(Duration::from_millis(200));
+        let addr = loopback.listener.local_addr().unwrap();
+
+        // A connection that never sends its request does not hold the listener forever
+        let _idle = TcpStream::connect(addr).await.unwrap();
+
+        let result = tokio::time::timeout(Duration::from_secs(5), loopback.wait_for_code())
+            .await
+            .expect("wait_for_code gives up after its timeout");
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,15 @@
 mod auth_connector;
 mod auth_wallet;
+mod bat_pool;
+#[cfg(not(target_arch = "wasm32"))]
+mod loopback;
 
 pub use auth_connector::AuthMintConnector;
 pub use auth_wallet::AuthWallet;
+pub use bat_pool::BatPoolConfig;
 use cdk_common::{Amount, AuthProof, AuthToken, Proofs};
+#[cfg(not(target_arch = "wasm32"))]
+pub use loopback::LoopbackRedirect;
 use tracing::instrument;
 
 use super::Wallet;
@@ -66,6 +72,30 @@ impl Wallet {
         Ok(())
     }
 
+    /// Set the water marks of the blind auth token pool, `None` disables it
+    #[instrument(skip(self))]
+    pub async fn set_bat_pool(&self, config: Option<BatPoolConfig>) -> Result<(), Error> {
+        self.auth_wallet
+            .read()
+            .await
+            .as_ref()
+            .ok_or(Error::AuthSettingsUndefined)?
+            .set_bat_pool(config)
+            .await
+    }
+
+    /// Mint blind auth tokens up to the high water mark of the pool
+    #[instrument(skip_all)]
+    pub async fn refill_bat_pool(&self) -> Result<Amount, Error> {
+        self.auth_wallet
+            .read()
+            .await
+            .as_ref()
+            .ok_or(Error::AuthSettingsUndefined)?
+            .refill_bat_pool()
+            .await
+    }
+
     /// Set the auth client (AuthWallet) for this wallet
     ///
     /// This allows updating the auth wallet without recreating the wallet.
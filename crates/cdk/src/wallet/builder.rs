@@ -29,6 +29,7 @@ pub struct WalletBuilder {
     auth_wallet: Option<AuthWallet>,
     seed: Option<[u8; 64]>,
     use_http_subscription: bool,
+    use_sse_subscription: bool,
     client: Option<Arc<dyn MintConnector + Send + Sync>>,
     metadata_cache_ttl: Option<Duration>,
     metadata_cache: Option<Arc<MintMetadataCache>>,
@@ -48,6 +49,7 @@ impl Default for WalletBuilder {
             client: None,
             metadata_cache_ttl: None,
             use_http_subscription: false,
+            use_sse_subscription: false,
             metadata_cache: None,
             metadata_caches: HashMap::new(),
         }
@@ -76,6 +78,17 @@ impl WalletBuilder {
     /// subscriptions to mint events
     pub fn prefer_ws_subscription(mut self) -> Self {
         self.use_http_subscription = false;
+        self.use_sse_subscription = false;
+        self
+    }
+
+    /// If server-sent events are preferred for the wallet subscriptions to mint events
+    ///
+    /// Used when the mint advertises them in its info, with fallback to WS otherwise. Useful
+    /// behind proxies that do not forward websockets.
+    pub fn prefer_sse_subscription(mut self) -> Self {
+        self.use_http_subscription = false;
+        self.use_sse_subscription = true;
         self
     }
 
@@ -246,7 +259,8 @@ impl WalletBuilder {
             auth_wallet: Arc::new(TokioRwLock::new(self.auth_wallet)),
             seed,
             client: client.clone(),
-            subscription: SubscriptionManager::new(client, self.use_http_subscription),
+            subscription: SubscriptionManager::new(client, self.use_http_subscription)
+                .prefer_sse(self.use_sse_subscription),
             in_error_swap_reverted_proofs: Arc::new(false.into()),
         })
     }
@@ -0,0 +1,166 @@
+use cdk_common::wallet::MintQuote;
+use cdk_common::PaymentMethod;
+use tracing::instrument;
+
+use super::issue_bolt11::PreparedMint;
+use crate::amount::SplitTarget;
+use crate::nuts::nut00::ProofsMethods;
+use crate::nuts::{BatchCheckMintQuoteRequest, BatchMintRequest, Proofs, SpendingConditions};
+use crate::util::unix_time;
+use crate::wallet::MintQuoteState;
+use crate::{ensure_cdk, Amount, Error, Wallet};
+
+impl Wallet {
+    /// Maximum number of quotes of a batch, if the mint supports batch minting [NUT-29] of
+    /// bolt11 quotes
+    pub(super) async fn mint_batch_size(&self) -> Option<usize> {
+        let mint_info = match self.load_mint_info().await {
+            Ok(mint_info) => mint_info,
+            Err(err) => {
+                tracing::debug!("Could not load mint info, not batching: {}", err);
+                return None;
+            }
+        };
+
+        if !mint_info.nuts.nut29.supports(&PaymentMethod::Bolt11) {
+            return None;
+        }
+
+        Some(
+            mint_info
+                .nuts
+                .nut29
+                .max_batch_size
+                .map(|max| usize::try_from(max).unwrap_or(usize::MAX).max(1))
+                .unwrap_or(usize::MAX),
+        )
+    }
+
+    /// Mint several paid bolt11 quotes
+    ///
+    /// The quotes are minted in batches [NUT-29] when the mint supports it, where each batch is
+    /// issued by the mint as a whole, and one by one otherwise.
+    #[instrument(skip(self))]
+    pub async fn mint_batch(
+        &self,
+        quote_ids: &[String],
+        amount_split_target: SplitTarget,
+        spending_conditions: Option<SpendingConditions>,
+    ) -> Result<Proofs, Error> {
+        let Some(batch_size) = self.mint_batch_size().await else {
+            let mut proofs = Proofs::new();
+            for quote_id in quote_ids {
+                proofs.extend(
+                    self.mint(
+                        quote_id,
+                        amount_split_target.clone(),
+                        spending_conditions.clone(),
+                    )
+                    .await?,
+                );
+            }
+            return Ok(proofs);
+        };
+
+        let mut proofs = Proofs::new();
+        for quote_ids in quote_ids.chunks(batch_size) {
+            let mut prepared = Vec::with_capacity(quote_ids.len());
+            for quote_id in quote_ids {
+                prepared.push(
+                    self.prepare_mint(quote_id, &amount_split_target, spending_conditions.as_ref())
+                        .await?,
+                );
+            }
+
+            let response = self
+                .client
+                .post_mint_batch(BatchMintRequest {
+                    requests: prepared
+                        .iter()
+                        .map(|prepared| prepared.request.clone())
+                        .collect(),
+                })
+                .await?;
+
+            ensure_cdk!(
+                response.responses.len() == prepared.len(),
+                Error::BatchResponseMismatch
+            );
+
+            for (
+                PreparedMint {
+                    quote,
+                    premint_secrets,
+                    ..
+                },
+                response,
+            ) in prepared.into_iter().zip(response.responses)
+            {
+                proofs.extend(
+                    self.finish_mint(quote, premint_secrets, response.signatures)
+                        .await?,
+                );
+            }
+        }
+
+        Ok(proofs)
+    }
+
+    /// Check the pending bolt11 quotes of the mint in batches and mint the paid ones
+    pub(super) async fn check_all_mint_quotes_batched(
+        &self,
+        batch_size: usize,
+    ) -> Result<Amount, Error> {
+        let mint_quotes: Vec<MintQuote> = self
+            .localstore
+            .get_mint_quotes()
+            .await?
+            .into_iter()
+            .filter(|quote| {
+                quote.mint_url == self.mint_url && quote.payment_method == PaymentMethod::Bolt11
+            })
+            .collect();
+
+        let mut paid = Vec::new();
+        for mint_quotes in mint_quotes.chunks(batch_size) {
+            let responses = self
+                .client
+                .post_check_mint_quotes(BatchCheckMintQuoteRequest {
+                    quotes: mint_quotes.iter().map(|quote| quote.id.clone()).collect(),
+                })
+                .await?;
+
+            for mint_quote in mint_quotes {
+                let state = responses
+                    .iter()
+                    .find(|response| response.quote == mint_quote.id)
+                    .map(|response| response.state);
+
+                let expired = mint_quote.expiry.le(&unix_time());
+                let Some(state) = state else {
+                    if expired {
+                        self.localstore.remove_mint_quote(&mint_quote.id).await?;
+                    } else {
+                        tracing::info!("Quote mint {} unknown", mint_quote.id);
+                    }
+                    continue;
+                };
+
+                if state == MintQuoteState::Paid {
+                    paid.push(mint_quote.id.clone());
+                } else if expired {
+                    self.localstore.remove_mint_quote(&mint_quote.id).await?;
+                    continue;
+                }
+
+                let mut mint_quote = mint_quote.clone();
+                mint_quote.state = state;
+                self.localstore.add_mint_quote(mint_quote).await?;
+            }
+        }
+
+        let proofs = self.mint_batch(&paid, SplitTarget::default(), None).await?;
+
+        Ok(proofs.total_amount()?)
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use cdk_common::nut04::MintMethodOptions;
+use bitcoin::hashes::sha256::Hash as Sha256;
 use cdk_common::wallet::{MintQuote, Transaction, TransactionDirection};
 use cdk_common::PaymentMethod;
 use tracing::instrument;
@@ -9,13 +9,35 @@ use crate::amount::SplitTarget;
 use crate::dhke::construct_proofs;
 use crate::nuts::nut00::ProofsMethods;
 use crate::nuts::{
-    nut12, MintQuoteBolt11Request, MintQuoteBolt11Response, MintRequest, PreMintSecrets, Proofs,
-    SecretKey, SpendingConditions, State,
+    nut12, BlindSignature, MintQuoteBolt11Request, MintQuoteBolt11Response,
+    MintQuotesByPubkeyRequest, MintRequest, PreMintSecrets, Proofs, SecretKey, SpendingConditions,
+    State,
 };
 use crate::types::ProofInfo;
 use crate::util::unix_time;
 use crate::wallet::MintQuoteState;
-use crate::{Amount, Error, Wallet};
+use crate::{ensure_cdk, Amount, Error, Wallet};
+
+/// Signed mint request of a quote, with the secrets to unblind its signatures
+pub(super) struct PreparedMint {
+    /// Quote being minted
+    pub quote: MintQuote,
+    /// Mint request
+    pub request: MintRequest<String>,
+    /// Secrets of the outputs of the request
+    pub premint_secrets: PreMintSecrets,
+}
+
+/// Bolt11 mint quote options
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MintQuoteOptions {
+    /// Invoice description
+    pub description: Option<String>,
+    /// Invoice description hash
+    pub description_hash: Option<Sha256>,
+    /// Requested time in seconds until the quote expires
+    pub expiry_secs: Option<u64>,
+}
 
 impl Wallet {
     /// Mint Quote
@@ -48,12 +70,34 @@ impl Wallet {
         &self,
         amount: Amount,
         description: Option<String>,
+    ) -> Result<MintQuote, Error> {
+        self.mint_quote_with_options(
+            amount,
+            MintQuoteOptions {
+                description,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Mint Quote with description hash and expiry options
+    #[instrument(skip(self))]
+    pub async fn mint_quote_with_options(
+        &self,
+        amount: Amount,
+        options: MintQuoteOptions,
     ) -> Result<MintQuote, Error> {
         let mint_url = self.mint_url.clone();
         let unit = self.unit.clone();
 
-        // If we have a description, we check that the mint supports it.
-        if description.is_some() {
+        ensure_cdk!(
+            options.description.is_none() || options.description_hash.is_none(),
+            Error::InvoiceDescriptionAndHash
+        );
+
+        // If we have any options, we check that the mint supports them.
+        if options != MintQuoteOptions::default() {
             let settings = self
                 .localstore
                 .get_mint(mint_url.clone())
@@ -64,9 +108,27 @@ impl Wallet {
                 .get_settings(&unit, &crate::nuts::PaymentMethod::Bolt11)
                 .ok_or(Error::UnsupportedUnit)?;
 
-            match settings.options {
-                Some(MintMethodOptions::Bolt11 { description }) if description => (),
-                _ => return Err(Error::InvoiceDescriptionUnsupported),
+            let method_options = settings.options.as_ref();
+
+            if options.description.is_some() {
+                ensure_cdk!(
+                    method_options.is_some_and(|o| o.description()),
+                    Error::InvoiceDescriptionUnsupported
+                );
+            }
+
+            if options.description_hash.is_some() {
+                ensure_cdk!(
+                    method_options.is_some_and(|o| o.description_hash()),
+                    Error::InvoiceDescriptionHashUnsupported
+                );
+            }
+
+            if let Some(expiry_secs) = options.expiry_secs {
+                ensure_cdk!(
+                    method_options.is_none_or(|o| o.expiry_allowed(expiry_secs)),
+                    Error::QuoteExpiryOutOfRange(expiry_secs)
+                );
             }
         }
 
@@ -75,8 +137,10 @@ impl Wallet {
         let request = MintQuoteBolt11Request {
             amount,
             unit: unit.clone(),
-            description,
+            description: options.description,
             pubkey: Some(secret_key.public_key()),
+            description_hash: options.description_hash,
+            expiry_secs: options.expiry_secs,
         };
 
         let quote_res = self.client.post_mint_quote(request).await?;
@@ -121,8 +185,15 @@ impl Wallet {
     }
 
     /// Check status of pending mint quotes
+    ///
+    /// Mints supporting batch minting [NUT-29] are asked for the state of all the quotes at once
+    /// and the paid quotes are minted together.
     #[instrument(skip(self))]
     pub async fn check_all_mint_quotes(&self) -> Result<Amount, Error> {
+        if let Some(batch_size) = self.mint_batch_size().await {
+            return self.check_all_mint_quotes_batched(batch_size).await;
+        }
+
         let mint_quotes = self.localstore.get_mint_quotes().await?;
         let mut total_amount = Amount::ZERO;
 
@@ -155,6 +226,48 @@ impl Wallet {
         Ok(mint_quotes)
     }
 
+    /// Fetch the paid quotes of a lightning address served by the mint
+    ///
+    /// The mint creates a quote locked to the pubkey of `secret_key` for every payment to the
+    /// lightning address. The quotes are stored with the key, so they can be minted with
+    /// [`Wallet::mint`]. Quotes of another unit are skipped.
+    #[instrument(skip_all)]
+    pub async fn fetch_lnurl_pay_quotes(
+        &self,
+        secret_key: &SecretKey,
+    ) -> Result<Vec<MintQuote>, Error> {
+        let request = MintQuotesByPubkeyRequest::new(secret_key, unix_time())?;
+        let response = self.client.post_lnurl_pay_quotes(request).await?;
+
+        let mut quotes = Vec::new();
+        for quote_res in response.quotes {
+            if quote_res.unit.as_ref() != Some(&self.unit) {
+                tracing::debug!(
+                    "Skipping lnurl pay quote {} of another unit",
+                    quote_res.quote
+                );
+                continue;
+            }
+
+            let mut quote = MintQuote::new(
+                quote_res.quote,
+                self.mint_url.clone(),
+                PaymentMethod::Bolt11,
+                quote_res.amount,
+                self.unit.clone(),
+                quote_res.request,
+                quote_res.expiry.unwrap_or(0),
+                Some(secret_key.clone()),
+            );
+            quote.state = quote_res.state;
+
+            self.localstore.add_mint_quote(quote.clone()).await?;
+            quotes.push(quote);
+        }
+
+        Ok(quotes)
+    }
+
     /// Mint
     /// # Synopsis
     /// ```rust,no_run
@@ -194,6 +307,27 @@ impl Wallet {
         amount_split_target: SplitTarget,
         spending_conditions: Option<SpendingConditions>,
     ) -> Result<Proofs, Error> {
+        let PreparedMint {
+            quote,
+            request,
+            premint_secrets,
+        } = self
+            .prepare_mint(quote_id, &amount_split_target, spending_conditions.as_ref())
+            .await?;
+
+        let mint_res = self.client.post_mint(request).await?;
+
+        self.finish_mint(quote, premint_secrets, mint_res.signatures)
+            .await
+    }
+
+    /// Build the signed mint request of a paid bolt11 quote
+    pub(super) async fn prepare_mint(
+        &self,
+        quote_id: &str,
+        amount_split_target: &SplitTarget,
+        spending_conditions: Option<&SpendingConditions>,
+    ) -> Result<PreparedMint, Error> {
         let quote_info = self
             .localstore
             .get_mint_quote(quote_id)
@@ -211,9 +345,7 @@ impl Wallet {
             return Err(Error::AmountUndefined);
         }
 
-        let unix_time = unix_time();
-
-        if quote_info.expiry > unix_time {
+        if quote_info.expiry > unix_time() {
             tracing::warn!("Attempting to mint with expired quote.");
         }
 
@@ -222,18 +354,18 @@ impl Wallet {
             .get_keyset_fees_and_amounts_by_id(active_keyset_id)
             .await?;
 
-        let premint_secrets = match &spending_conditions {
+        let premint_secrets = match spending_conditions {
             Some(spending_conditions) => PreMintSecrets::with_conditions(
                 active_keyset_id,
                 amount_mintable,
-                &amount_split_target,
+                amount_split_target,
                 spending_conditions,
                 &fee_and_amounts,
             )?,
             None => {
                 // Calculate how many secrets we'll need
                 let amount_split =
-                    amount_mintable.split_targeted(&amount_split_target, &fee_and_amounts)?;
+                    amount_mintable.split_targeted(amount_split_target, &fee_and_amounts)?;
                 let num_secrets = amount_split.len() as u32;
 
                 tracing::debug!(
@@ -255,7 +387,7 @@ impl Wallet {
                     count,
                     &self.seed,
                     amount_mintable,
-                    &amount_split_target,
+                    amount_split_target,
                     &fee_and_amounts,
                 )?
             }
@@ -267,17 +399,29 @@ impl Wallet {
             signature: None,
         };
 
-        if let Some(secret_key) = quote_info.secret_key {
+        if let Some(secret_key) = quote_info.secret_key.clone() {
             request.sign(secret_key)?;
         }
 
-        let mint_res = self.client.post_mint(request).await?;
+        Ok(PreparedMint {
+            quote: quote_info,
+            request,
+            premint_secrets,
+        })
+    }
 
-        let keys = self.load_keyset_keys(active_keyset_id).await?;
+    /// Unblind the signatures of a mint request and store the proofs
+    pub(super) async fn finish_mint(
+        &self,
+        quote_info: MintQuote,
+        premint_secrets: PreMintSecrets,
+        signatures: Vec<BlindSignature>,
+    ) -> Result<Proofs, Error> {
+        let keys = self.load_keyset_keys(premint_secrets.keyset_id).await?;
 
         // Verify the signature DLEQ is valid
         {
-            for (sig, premint) in mint_res.signatures.iter().zip(&premint_secrets.secrets) {
+            for (sig, premint) in signatures.iter().zip(&premint_secrets.secrets) {
                 let keys = self.load_keyset_keys(sig.keyset_id).await?;
                 let key = keys.amount_key(sig.amount).ok_or(Error::AmountKey)?;
                 match sig.verify_dleq(key, premint.blinded_message.blinded_secret) {
@@ -288,7 +432,7 @@ impl Wallet {
         }
 
         let proofs = construct_proofs(
-            mint_res.signatures,
+            signatures,
             premint_secrets.rs(),
             premint_secrets.secrets(),
             &keys,
@@ -321,10 +465,10 @@ impl Wallet {
                 fee: Amount::ZERO,
                 unit: self.unit.clone(),
                 ys: proofs.ys()?,
-                timestamp: unix_time,
+                timestamp: unix_time(),
                 memo: None,
                 metadata: HashMap::new(),
-                quote_id: Some(quote_id.to_string()),
+                quote_id: Some(quote_info.id),
                 payment_request: Some(quote_info.request),
                 payment_proof: None,
             })
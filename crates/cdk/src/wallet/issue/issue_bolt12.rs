@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 
-use cdk_common::nut04::MintMethodOptions;
 use cdk_common::nut25::MintQuoteBolt12Request;
 use cdk_common::wallet::{Transaction, TransactionDirection};
 use cdk_common::{Proofs, SecretKey};
@@ -42,7 +41,7 @@ impl Wallet {
                 .ok_or(Error::UnsupportedUnit)?;
 
             match mint_method_settings.options {
-                Some(MintMethodOptions::Bolt11 { description }) if description => (),
+                Some(ref options) if options.description() => (),
                 _ => return Err(Error::InvoiceDescriptionUnsupported),
             }
         }
@@ -167,7 +166,7 @@ impl Wallet {
             return Err(Error::SignatureMissingOrInvalid);
         }
 
-        let mint_res = self.client.post_mint(request).await?;
+        let mint_res = self.client.post_mint_bolt12(request).await?;
 
         let keys = self.load_keyset_keys(active_keyset_id).await?;
 
@@ -1,2 +1,5 @@
+mod issue_batch;
 mod issue_bolt11;
 mod issue_bolt12;
+
+pub use issue_bolt11::MintQuoteOptions;
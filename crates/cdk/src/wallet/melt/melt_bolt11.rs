@@ -51,6 +51,23 @@ impl Wallet {
     ) -> Result<MeltQuote, Error> {
         let invoice = Bolt11Invoice::from_str(&request)?;
 
+        if invoice.amount_milli_satoshis().is_none() {
+            match options {
+                None => return Err(Error::InvoiceAmountUndefined),
+                Some(MeltOptions::Amountless { .. }) => {
+                    let mint_info = self.load_mint_info().await?;
+                    ensure_cdk!(
+                        mint_info.nuts.nut05.amountless_supported(&self.unit),
+                        Error::AmountlessInvoiceNotSupported(
+                            self.unit.clone(),
+                            PaymentMethod::Bolt11
+                        )
+                    );
+                }
+                Some(MeltOptions::Mpp { .. }) => (),
+            }
+        }
+
         let quote_request = MeltQuoteBolt11Request {
             request: invoice.clone(),
             unit: self.unit.clone(),
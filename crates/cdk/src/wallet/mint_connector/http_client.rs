@@ -3,6 +3,10 @@ use std::collections::HashSet;
 use std::sync::{Arc, RwLock as StdRwLock};
 
 use async_trait::async_trait;
+#[cfg(not(target_arch = "wasm32"))]
+use cdk_common::nut17::ws::{WsMessageOrResponse, WsRequest};
+#[cfg(not(target_arch = "wasm32"))]
+use cdk_common::subscription::WalletParams;
 use cdk_common::{nut19, MeltQuoteBolt12Request, MintQuoteBolt12Request, MintQuoteBolt12Response};
 #[cfg(feature = "auth")]
 use cdk_common::{Method, ProtectedEndpoint, RoutePath};
@@ -14,16 +18,19 @@ use tracing::instrument;
 use url::Url;
 use web_time::{Duration, Instant};
 
+#[cfg(not(target_arch = "wasm32"))]
+use super::transport::ByteStream;
 use super::transport::Transport;
 use super::{Error, MintConnector};
 use crate::mint_url::MintUrl;
 #[cfg(feature = "auth")]
 use crate::nuts::nut22::MintAuthRequest;
 use crate::nuts::{
-    AuthToken, CheckStateRequest, CheckStateResponse, Id, KeySet, KeysResponse, KeysetResponse,
-    MeltQuoteBolt11Request, MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
-    MintQuoteBolt11Response, MintRequest, MintResponse, RestoreRequest, RestoreResponse,
-    SwapRequest, SwapResponse,
+    AuthToken, BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, CheckStateRequest,
+    CheckStateResponse, Id, KeySet, KeysResponse, KeysetResponse, MeltQuoteBolt11Request,
+    MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
+    MintQuoteBolt11Response, MintQuotesByPubkeyRequest, MintQuotesByPubkeyResponse, MintRequest,
+    MintResponse, RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
 };
 #[cfg(feature = "auth")]
 use crate::wallet::auth::{AuthMintConnector, AuthWallet};
@@ -171,6 +178,7 @@ where
 
                 nut19::Path::MeltBolt12 => vec!["v1", "melt", "bolt12"],
                 nut19::Path::Swap => vec!["v1", "swap"],
+                nut19::Path::MintBolt11Batch => vec!["v1", "mint", "bolt11", "batch"],
             })?;
 
             let result = match method {
@@ -328,6 +336,48 @@ where
         .await
     }
 
+    /// Mint Quotes status [NUT-29]
+    #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
+    async fn post_check_mint_quotes(
+        &self,
+        request: BatchCheckMintQuoteRequest<String>,
+    ) -> Result<Vec<MintQuoteBolt11Response<String>>, Error> {
+        let url = self
+            .mint_url
+            .join_paths(&["v1", "mint", "quote", "bolt11", "check"])?;
+
+        #[cfg(feature = "auth")]
+        let auth_token = self
+            .get_auth_token(Method::Get, RoutePath::MintQuoteBolt11)
+            .await?;
+
+        #[cfg(not(feature = "auth"))]
+        let auth_token = None;
+        self.transport.http_post(url, auth_token, &request).await
+    }
+
+    /// Batch Mint Tokens [NUT-29]
+    #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
+    async fn post_mint_batch(
+        &self,
+        request: BatchMintRequest<String>,
+    ) -> Result<BatchMintResponse, Error> {
+        #[cfg(feature = "auth")]
+        let auth_token = self
+            .get_auth_token(Method::Post, RoutePath::MintBolt11)
+            .await?;
+
+        #[cfg(not(feature = "auth"))]
+        let auth_token = None;
+        self.retriable_http_request(
+            nut19::Method::Post,
+            nut19::Path::MintBolt11Batch,
+            auth_token,
+            &request,
+        )
+        .await
+    }
+
     /// Melt Quote [NUT-05]
     #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
     async fn post_melt_quote(
@@ -513,6 +563,25 @@ where
         self.transport.http_get(url, auth_token).await
     }
 
+    /// Mint Tokens [NUT-25]
+    #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
+    async fn post_mint_bolt12(&self, request: MintRequest<String>) -> Result<MintResponse, Error> {
+        #[cfg(feature = "auth")]
+        let auth_token = self
+            .get_auth_token(Method::Post, RoutePath::MintBolt12)
+            .await?;
+
+        #[cfg(not(feature = "auth"))]
+        let auth_token = None;
+        self.retriable_http_request(
+            nut19::Method::Post,
+            nut19::Path::MintBolt12,
+            auth_token,
+            &request,
+        )
+        .await
+    }
+
     /// Melt Quote Bolt12 [NUT-23]
     #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
     async fn post_melt_bolt12_quote(
@@ -573,6 +642,44 @@ where
         )
         .await
     }
+
+    /// Open an event stream [NUT-17]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[instrument(skip(self, subscriptions), fields(mint_url = %self.mint_url))]
+    async fn post_sse(&self, subscriptions: Vec<WalletParams>) -> Result<ByteStream, Error> {
+        let url = self.mint_url.join_paths(&["v1", "sse"])?;
+
+        #[cfg(feature = "auth")]
+        let auth_token = self.get_auth_token(Method::Get, RoutePath::Ws).await?;
+
+        #[cfg(not(feature = "auth"))]
+        let auth_token = None;
+        self.transport
+            .http_post_stream(url, auth_token, &subscriptions)
+            .await
+    }
+
+    /// Subscribe or unsubscribe on an event stream [NUT-17]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
+    async fn post_sse_request(
+        &self,
+        stream_id: &str,
+        request: WsRequest<String>,
+    ) -> Result<WsMessageOrResponse<String>, Error> {
+        let url = self.mint_url.join_paths(&["v1", "sse", stream_id])?;
+        self.transport.http_post(url, None, &request).await
+    }
+
+    /// Paid quotes of a lightning address served by the mint
+    #[instrument(skip(self, request), fields(mint_url = %self.mint_url))]
+    async fn post_lnurl_pay_quotes(
+        &self,
+        request: MintQuotesByPubkeyRequest,
+    ) -> Result<MintQuotesByPubkeyResponse<String>, Error> {
+        let url = self.mint_url.join_paths(&["lnurlp", "quotes"])?;
+        self.transport.http_post(url, None, &request).await
+    }
 }
 
 /// Http Client
@@ -3,16 +3,21 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
+#[cfg(not(target_arch = "wasm32"))]
+use cdk_common::nut17::ws::{WsMessageOrResponse, WsRequest};
+#[cfg(not(target_arch = "wasm32"))]
+use cdk_common::subscription::WalletParams;
 use cdk_common::{MeltQuoteBolt12Request, MintQuoteBolt12Request, MintQuoteBolt12Response};
 
 use super::Error;
 // Re-export Lightning address types for trait implementers
 pub use crate::lightning_address::{LnurlPayInvoiceResponse, LnurlPayResponse};
 use crate::nuts::{
-    CheckStateRequest, CheckStateResponse, Id, KeySet, KeysetResponse, MeltQuoteBolt11Request,
+    BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, CheckStateRequest,
+    CheckStateResponse, Id, KeySet, KeysetResponse, MeltQuoteBolt11Request,
     MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
-    MintQuoteBolt11Response, MintRequest, MintResponse, RestoreRequest, RestoreResponse,
-    SwapRequest, SwapResponse,
+    MintQuoteBolt11Response, MintQuotesByPubkeyRequest, MintQuotesByPubkeyResponse, MintRequest,
+    MintResponse, RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
 };
 #[cfg(feature = "auth")]
 use crate::wallet::AuthWallet;
@@ -67,6 +72,16 @@ pub trait MintConnector: Debug {
     ) -> Result<MintQuoteBolt11Response<String>, Error>;
     /// Mint Tokens [NUT-04]
     async fn post_mint(&self, request: MintRequest<String>) -> Result<MintResponse, Error>;
+    /// Mint Quotes status [NUT-29]
+    async fn post_check_mint_quotes(
+        &self,
+        request: BatchCheckMintQuoteRequest<String>,
+    ) -> Result<Vec<MintQuoteBolt11Response<String>>, Error>;
+    /// Batch Mint Tokens [NUT-29]
+    async fn post_mint_batch(
+        &self,
+        request: BatchMintRequest<String>,
+    ) -> Result<BatchMintResponse, Error>;
     /// Melt Quote [NUT-05]
     async fn post_melt_quote(
         &self,
@@ -112,6 +127,10 @@ pub trait MintConnector: Debug {
         &self,
         quote_id: &str,
     ) -> Result<MintQuoteBolt12Response<String>, Error>;
+    /// Mint Tokens [NUT-25]
+    async fn post_mint_bolt12(&self, request: MintRequest<String>) -> Result<MintResponse, Error> {
+        self.post_mint(request).await
+    }
     /// Melt Quote [NUT-23]
     async fn post_melt_bolt12_quote(
         &self,
@@ -127,4 +146,37 @@ pub trait MintConnector: Debug {
         &self,
         request: MeltRequest<String>,
     ) -> Result<MeltQuoteBolt11Response<String>, Error>;
+
+    /// Open a stream of NUT-17 notifications as server-sent events
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn post_sse(
+        &self,
+        _subscriptions: Vec<WalletParams>,
+    ) -> Result<transport::ByteStream, Error> {
+        Err(Error::Custom(
+            "Server-sent events are not supported by the connector".to_string(),
+        ))
+    }
+
+    /// Subscribe or unsubscribe on an open event stream
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn post_sse_request(
+        &self,
+        _stream_id: &str,
+        _request: WsRequest<String>,
+    ) -> Result<WsMessageOrResponse<String>, Error> {
+        Err(Error::Custom(
+            "Server-sent events are not supported by the connector".to_string(),
+        ))
+    }
+
+    /// Look up the paid quotes of a lightning address served by the mint
+    async fn post_lnurl_pay_quotes(
+        &self,
+        _request: MintQuotesByPubkeyRequest,
+    ) -> Result<MintQuotesByPubkeyResponse<String>, Error> {
+        Err(Error::Custom(
+            "Lightning address quotes are not supported by the connector".to_string(),
+        ))
+    }
 }
@@ -1,5 +1,7 @@
 //! HTTP Transport trait with a default implementation
 use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::pin::Pin;
 
 use cdk_common::AuthToken;
 #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
@@ -16,6 +18,10 @@ use url::Url;
 use super::Error;
 use crate::error::ErrorResponse;
 
+/// Body of a response read as it arrives, such as an event stream
+#[cfg(not(target_arch = "wasm32"))]
+pub type ByteStream = Pin<Box<dyn futures::Stream<Item = Result<Vec<u8>, Error>> + Send>>;
+
 /// Expected HTTP Transport
 #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
@@ -51,6 +57,22 @@ pub trait Transport: Default + Send + Sync + Debug + Clone {
     where
         P: serde::Serialize + ?Sized + Send + Sync,
         R: serde::de::DeserializeOwned;
+
+    /// HTTP Post request whose response body is streamed, such as server-sent events
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn http_post_stream<P>(
+        &self,
+        _url: url::Url,
+        _auth_token: Option<cdk_common::AuthToken>,
+        _payload: &P,
+    ) -> Result<ByteStream, super::Error>
+    where
+        P: serde::Serialize + ?Sized + Send + Sync,
+    {
+        Err(super::Error::Custom(
+            "Streamed responses are not supported by the transport".to_string(),
+        ))
+    }
 }
 
 /// Async transport for Http
@@ -215,6 +237,56 @@ impl Transport for Async {
             }
         })
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn http_post_stream<P>(
+        &self,
+        url: Url,
+        auth_token: Option<AuthToken>,
+        payload: &P,
+    ) -> Result<ByteStream, Error>
+    where
+        P: Serialize + ?Sized + Send + Sync,
+    {
+        let mut request = self.inner.post(url).json(&payload);
+
+        if let Some(auth) = auth_token {
+            request = request.header(auth.header_key(), auth.to_string());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            Error::HttpError(
+                e.status().map(|status_code| status_code.as_u16()),
+                e.to_string(),
+            )
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let response = response.text().await.unwrap_or_default();
+            return Err(match ErrorResponse::from_json(&response) {
+                Ok(ok) => ok.into(),
+                Err(_) => Error::HttpError(Some(status.as_u16()), response),
+            });
+        }
+
+        Ok(Box::pin(futures::stream::unfold(
+            response,
+            |mut response| async move {
+                match response.chunk().await {
+                    Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), response)),
+                    Ok(None) => None,
+                    Err(e) => Some((
+                        Err(Error::HttpError(
+                            e.status().map(|status_code| status_code.as_u16()),
+                            e.to_string(),
+                        )),
+                        response,
+                    )),
+                }
+            },
+        )))
+    }
 }
 
 #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
@@ -6,6 +6,7 @@ use arti_hyper::ArtiHttpConnector;
 use async_trait::async_trait;
 use cdk_common::AuthToken;
 use http::header::{self, HeaderName, HeaderValue};
+use hyper::body::HttpBody;
 use hyper::http::{Method, Request, Uri};
 use hyper::{Body, Client};
 use serde::de::DeserializeOwned;
@@ -15,7 +16,7 @@ use url::Url;
 
 use super::super::Error;
 use crate::wallet::getrandom;
-use crate::wallet::mint_connector::transport::{ErrorResponse, Transport};
+use crate::wallet::mint_connector::transport::{ByteStream, ErrorResponse, Transport};
 
 /// Fixed-size pool size
 pub const DEFAULT_TOR_POOL_SIZE: usize = 5;
@@ -144,16 +145,13 @@ impl TorAsync {
         (h as usize) % pool_len.max(1)
     }
 
-    async fn request<R>(
+    async fn send(
         &self,
         method: http::Method,
         url: Url,
         auth: Option<AuthToken>,
         mut body: Option<Vec<u8>>,
-    ) -> Result<R, Error>
-    where
-        R: DeserializeOwned,
-    {
+    ) -> Result<hyper::Response<Body>, Error> {
         let tls = tls_api_native_tls::TlsConnector::builder()
             .map_err(|e| Error::Custom(format!("{e:?}")))?
             .build()
@@ -195,10 +193,23 @@ impl TorAsync {
             );
         }
 
-        let resp = client
+        client
             .request(req)
             .await
-            .map_err(|e| Error::HttpError(None, e.to_string()))?;
+            .map_err(|e| Error::HttpError(None, e.to_string()))
+    }
+
+    async fn request<R>(
+        &self,
+        method: http::Method,
+        url: Url,
+        auth: Option<AuthToken>,
+        body: Option<Vec<u8>>,
+    ) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+    {
+        let resp = self.send(method, url, auth, body).await?;
 
         let status = resp.status().as_u16();
         let bytes = hyper::body::to_bytes(resp.into_body())
@@ -258,6 +269,44 @@ impl Transport for TorAsync {
             .await
     }
 
+    async fn http_post_stream<P>(
+        &self,
+        url: url::Url,
+        auth_token: Option<cdk_common::AuthToken>,
+        payload: &P,
+    ) -> Result<ByteStream, super::super::Error>
+    where
+        P: serde::Serialize + ?Sized + Send + Sync,
+    {
+        let body = serde_json::to_vec(payload).map_err(|e| Error::Custom(e.to_string()))?;
+        let resp = self.send(Method::POST, url, auth_token, Some(body)).await?;
+
+        let status = resp.status().as_u16();
+        if !(200..300).contains(&status) {
+            let bytes = hyper::body::to_bytes(resp.into_body())
+                .await
+                .map_err(|e| Error::HttpError(None, e.to_string()))?;
+            let text = String::from_utf8_lossy(&bytes).to_string();
+            return Err(match ErrorResponse::from_json(&text) {
+                Ok(ok) => ok.into(),
+                Err(_) => Error::HttpError(Some(status), text),
+            });
+        }
+
+        Ok(Box::pin(futures::stream::unfold(
+            resp.into_body(),
+            |mut body| async move {
+                let chunk = body.data().await?;
+                Some((
+                    chunk
+                        .map(|chunk| chunk.to_vec())
+                        .map_err(|e| Error::HttpError(None, e.to_string())),
+                    body,
+                ))
+            },
+        )))
+    }
+
     #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
     async fn resolve_dns_txt(&self, domain: &str) -> Result<Vec<String>, Error> {
         #[derive(serde::Deserialize)]
@@ -60,10 +60,13 @@ mod swap;
 mod transactions;
 pub mod util;
 
+#[cfg(all(feature = "auth", not(target_arch = "wasm32")))]
+pub use auth::LoopbackRedirect;
 #[cfg(feature = "auth")]
-pub use auth::{AuthMintConnector, AuthWallet};
+pub use auth::{AuthMintConnector, AuthWallet, BatPoolConfig};
 pub use builder::WalletBuilder;
 pub use cdk_common::wallet as types;
+pub use issue::MintQuoteOptions;
 #[cfg(feature = "auth")]
 pub use mint_connector::http_client::AuthHttpClient as BaseAuthHttpClient;
 pub use mint_connector::http_client::HttpClient as BaseHttpClient;
@@ -1047,6 +1047,27 @@ impl MultiMintWallet {
             .await
     }
 
+    /// Mint several paid quotes of a mint
+    ///
+    /// The quotes are minted in batches when the mint supports batch minting [NUT-29], and one
+    /// by one otherwise.
+    #[instrument(skip(self))]
+    pub async fn mint_batch(
+        &self,
+        mint_url: &MintUrl,
+        quote_ids: &[String],
+        conditions: Option<SpendingConditions>,
+    ) -> Result<Proofs, Error> {
+        let wallets = self.wallets.read().await;
+        let wallet = wallets.get(mint_url).ok_or(Error::UnknownMint {
+            mint_url: mint_url.to_string(),
+        })?;
+
+        wallet
+            .mint_batch(quote_ids, SplitTarget::default(), conditions)
+            .await
+    }
+
     /// Wait for a mint quote to be paid and automatically mint the proofs
     #[cfg(not(target_arch = "wasm32"))]
     #[instrument(skip(self))]
@@ -1434,13 +1455,14 @@ impl MultiMintWallet {
         options: Option<MeltOptions>,
         max_fee: Option<Amount>,
     ) -> Result<Melted, Error> {
-        // Parse the invoice to get the amount
+        // Parse the invoice to get the amount, amountless invoices take it from the options
         let invoice = bolt11
             .parse::<crate::Bolt11Invoice>()
             .map_err(Error::Invoice)?;
 
-        let amount = invoice
-            .amount_milli_satoshis()
+        let amount = options
+            .map(|opt| u64::from(opt.amount_msat()))
+            .or_else(|| invoice.amount_milli_satoshis())
             .map(|msats| Amount::from(msats / 1000))
             .ok_or(Error::InvoiceAmountUndefined)?;
 
@@ -1811,6 +1833,37 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_pay_request_without_matching_wallet() {
+        use std::str::FromStr;
+
+        use crate::nuts::PaymentRequest;
+
+        let multi_wallet = create_test_multi_wallet().await;
+        let mint_url = MintUrl::from_str("https://mint1.example.com").unwrap();
+        multi_wallet.add_mint(mint_url).await.unwrap();
+
+        let mut payment_request = PaymentRequest {
+            payment_id: None,
+            amount: None,
+            unit: Some(CurrencyUnit::Sat),
+            single_use: None,
+            mints: Some(vec![MintUrl::from_str("https://mint2.example.com").unwrap()]),
+            description: None,
+            transports: vec![],
+            nut10: None,
+        };
+
+        let result = multi_wallet
+            .pay_request(payment_request.clone(), None)
+            .await;
+        assert!(matches!(result, Err(Error::AmountUndefined)));
+
+        payment_request.amount = Some(Amount::from(10));
+        let result = multi_wallet.pay_request(payment_request, None).await;
+        assert!(matches!(result, Err(Error::InsufficientFunds)));
+    }
+
     #[tokio::test]
     async fn test_consolidate_empty() {
         let multi_wallet = create_test_multi_wallet().await;
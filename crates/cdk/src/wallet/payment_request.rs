@@ -18,12 +18,13 @@ use nostr_sdk::{Client as NostrClient, EventBuilder, FromBech32, Keys, ToBech32}
 use reqwest::Client;
 
 use crate::error::Error;
+use crate::mint_url::MintUrl;
 use crate::nuts::nut11::{Conditions, SigFlag, SpendingConditions};
 use crate::nuts::nut18::Nut10SecretRequest;
 use crate::nuts::{CurrencyUnit, Transport};
 #[cfg(feature = "nostr")]
 use crate::wallet::MultiMintReceiveOptions;
-use crate::wallet::{MultiMintWallet, SendOptions};
+use crate::wallet::{MultiMintWallet, PreparedSend, SendOptions};
 use crate::Wallet;
 
 impl Wallet {
@@ -45,28 +46,39 @@ impl Wallet {
             },
         };
 
+        let prepared_send = self
+            .prepare_send(
+                amount,
+                SendOptions {
+                    include_fee: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        self.pay_prepared_request(payment_request, prepared_send)
+            .await
+    }
+
+    /// Send the proofs of a prepared send to the transport of a payment request
+    async fn pay_prepared_request(
+        &self,
+        payment_request: PaymentRequest,
+        prepared_send: PreparedSend,
+    ) -> Result<(), Error> {
         let transports = payment_request.transports.clone();
 
         // Prefer Nostr to avoid revealing IP, fall back to HTTP POST.
+        // Builds without Nostr only use HTTP POST.
         let transport = transports
             .iter()
-            .find(|t| t._type == TransportType::Nostr)
+            .find(|t| cfg!(feature = "nostr") && t._type == TransportType::Nostr)
             .or_else(|| {
                 transports
                     .iter()
                     .find(|t| t._type == TransportType::HttpPost)
             });
 
-        let prepared_send = self
-            .prepare_send(
-                amount,
-                SendOptions {
-                    include_fee: true,
-                    ..Default::default()
-                },
-            )
-            .await?;
-
         let token = prepared_send.confirm(None).await?;
 
         // We need the keysets information to properly convert from token proof to proof
@@ -222,6 +234,66 @@ pub struct NostrWaitInfo {
 }
 
 impl MultiMintWallet {
+    /// Pay a NUT-18 PaymentRequest from the first wallet that can cover it.
+    ///
+    /// Only wallets whose mint and unit match the request are considered, and a wallet covers
+    /// the request when it can send the amount together with the fees of its inputs. Returns
+    /// the mint the request was paid from.
+    ///
+    /// If the request has no amount, a `custom_amount` must be provided.
+    pub async fn pay_request(
+        &self,
+        payment_request: PaymentRequest,
+        custom_amount: Option<Amount>,
+    ) -> Result<MintUrl, Error> {
+        let amount = payment_request
+            .amount
+            .or(custom_amount)
+            .ok_or(Error::AmountUndefined)?;
+
+        for wallet in self.get_wallets().await {
+            if let Some(mints) = &payment_request.mints {
+                if !mints.contains(&wallet.mint_url) {
+                    continue;
+                }
+            }
+
+            if let Some(unit) = &payment_request.unit {
+                if &wallet.unit != unit {
+                    continue;
+                }
+            }
+
+            // Skip the wallets that cannot cover the amount before selecting any proofs
+            if wallet.total_balance().await? < amount {
+                continue;
+            }
+
+            let prepared_send = match wallet
+                .prepare_send(
+                    amount,
+                    SendOptions {
+                        include_fee: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+            {
+                Ok(prepared_send) => prepared_send,
+                Err(Error::InsufficientFunds) => continue,
+                Err(err) => return Err(err),
+            };
+
+            wallet
+                .pay_prepared_request(payment_request, prepared_send)
+                .await?;
+
+            return Ok(wallet.mint_url.clone());
+        }
+
+        Err(Error::InsufficientFunds)
+    }
+
     /// Derive enforceable NUT-10 spending conditions from high-level request params.
     ///
     /// Why:
@@ -11,7 +11,7 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
 use cdk_common::nut17::ws::{WsMethodRequest, WsRequest, WsUnsubscribeRequest};
-use cdk_common::nut17::{Kind, NotificationId};
+use cdk_common::nut17::{Kind, NotificationId, Params};
 use cdk_common::parking_lot::RwLock;
 use cdk_common::pub_sub::remote_consumer::{
     Consumer, InternalRelay, RemoteActiveConsumer, StreamCtrl, SubscribeMessage, Transport,
@@ -26,6 +26,10 @@ use crate::event::MintEvent;
 use crate::mint_url::MintUrl;
 use crate::wallet::MintConnector;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod sse;
+#[cfg(target_arch = "wasm32")]
+mod wasm_ws;
 #[cfg(not(target_arch = "wasm32"))]
 mod ws;
 
@@ -55,6 +59,7 @@ pub struct SubscriptionManager {
     all_connections: Arc<RwLock<HashMap<MintUrl, Arc<Consumer<SubscriptionClient>>>>>,
     http_client: Arc<dyn MintConnector + Send + Sync>,
     prefer_http: bool,
+    prefer_sse: bool,
 }
 
 impl Debug for SubscriptionManager {
@@ -78,9 +83,16 @@ impl SubscriptionManager {
             all_connections: Arc::new(RwLock::new(HashMap::new())),
             http_client,
             prefer_http,
+            prefer_sse: false,
         }
     }
 
+    /// Prefer server-sent events over the websocket for the mints advertising them
+    pub fn prefer_sse(mut self, prefer_sse: bool) -> Self {
+        self.prefer_sse = prefer_sse;
+        self
+    }
+
     /// Subscribe to updates from a mint server with a given filter
     pub fn subscribe(
         &self,
@@ -96,6 +108,7 @@ impl SubscriptionManager {
                         mint_url,
                         http_client: self.http_client.clone(),
                         req_id: 0.into(),
+                        prefer_sse: self.prefer_sse,
                     },
                     self.prefer_http,
                     (),
@@ -144,15 +157,42 @@ pub struct SubscriptionClient {
     http_client: Arc<dyn MintConnector + Send + Sync>,
     mint_url: MintUrl,
     req_id: AtomicUsize,
+    prefer_sse: bool,
 }
 
 #[allow(dead_code)]
 impl SubscriptionClient {
-    fn get_sub_request(
-        &self,
-        id: String,
-        params: NotificationId<String>,
-    ) -> Option<(usize, String)> {
+    /// Auth token for the websocket or the event stream, if the mint protects them
+    #[cfg(feature = "auth")]
+    async fn auth_token(&self) -> Option<cdk_common::AuthToken> {
+        let auth_wallet = self.http_client.get_auth_wallet().await?;
+        let endpoint =
+            cdk_common::ProtectedEndpoint::new(cdk_common::Method::Get, cdk_common::RoutePath::Ws);
+
+        auth_wallet
+            .get_auth_for_request(&endpoint)
+            .await
+            .inspect_err(|err| tracing::warn!("Failed to get auth token: {:?}", err))
+            .ok()
+            .flatten()
+    }
+
+    /// Whether to stream the notifications as server-sent events instead of the websocket
+    async fn use_sse(&self) -> bool {
+        if !self.prefer_sse {
+            return false;
+        }
+
+        match self.http_client.get_mint_info().await {
+            Ok(info) => info.nuts.nut17.sse,
+            Err(err) => {
+                tracing::debug!("Could not get mint info, using the websocket: {}", err);
+                false
+            }
+        }
+    }
+
+    fn get_params(id: String, params: NotificationId<String>) -> WalletParams {
         let (kind, filter) = match params {
             NotificationId::ProofState(x) => (Kind::ProofState, x.to_string()),
             NotificationId::MeltQuoteBolt11(q) | NotificationId::MeltQuoteBolt12(q) => {
@@ -162,16 +202,42 @@ impl SubscriptionClient {
             NotificationId::MintQuoteBolt12(q) => (Kind::Bolt12MintQuote, q),
         };
 
-        let request: WsRequest<_> = (
-            WsMethodRequest::Subscribe(WalletParams {
-                kind,
-                filters: vec![filter],
-                id: id.into(),
+        WalletParams {
+            kind,
+            filters: vec![filter],
+            id: id.into(),
+        }
+    }
+
+    fn sub_request(&self, id: String, params: NotificationId<String>) -> WsRequest<String> {
+        let params = Self::get_params(id, params);
+        (
+            WsMethodRequest::Subscribe(Params {
+                kind: params.kind,
+                filters: params.filters,
+                id: params.id.to_string(),
             }),
             self.req_id
                 .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
         )
-            .into();
+            .into()
+    }
+
+    fn unsub_request(&self, sub_id: String) -> WsRequest<String> {
+        (
+            WsMethodRequest::Unsubscribe(WsUnsubscribeRequest { sub_id }),
+            self.req_id
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        )
+            .into()
+    }
+
+    fn get_sub_request(
+        &self,
+        id: String,
+        params: NotificationId<String>,
+    ) -> Option<(usize, String)> {
+        let request = self.sub_request(id, params);
 
         serde_json::to_string(&request)
             .inspect_err(|err| {
@@ -182,14 +248,7 @@ impl SubscriptionClient {
     }
 
     fn get_unsub_request(&self, sub_id: String) -> Option<String> {
-        let request: WsRequest<_> = (
-            WsMethodRequest::Unsubscribe(WsUnsubscribeRequest { sub_id }),
-            self.req_id
-                .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
-        )
-            .into();
-
-        match serde_json::to_string(&request) {
+        match serde_json::to_string(&self.unsub_request(sub_id)) {
             Ok(json) => Some(json),
             Err(err) => {
                 tracing::error!("Could not serialize unsubscribe message: {:?}", err);
@@ -215,10 +274,14 @@ impl Transport for SubscriptionClient {
         _reply_to: InternalRelay<Self::Spec>,
     ) -> Result<(), PubsubError> {
         #[cfg(not(target_arch = "wasm32"))]
-        let r = ws::stream_client(self, _ctrls, _topics, _reply_to).await;
+        let r = if self.use_sse().await {
+            sse::stream_client(self, _ctrls, _topics, _reply_to).await
+        } else {
+            ws::stream_client(self, _ctrls, _topics, _reply_to).await
+        };
 
         #[cfg(target_arch = "wasm32")]
-        let r = Err(PubsubError::NotSupported);
+        let r = wasm_ws::stream_client(self, _ctrls, _topics, _reply_to).await;
 
         r
     }
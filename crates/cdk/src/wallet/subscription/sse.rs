@@ -0,0 +1,546 @@
+//! Server-sent events transport
+//!
+//! The stream is opened with the current subscriptions through the [MintConnector] of the wallet,
+//! so it uses the same proxy, Tor and TLS settings as every other request to the mint. The first
+//! event of the stream carries its id, and the subscriptions added or removed afterwards are
+//! posted to the mint for that id, without reopening the stream. A stream closed by the mint is
+//! an error, so the consumer opens a new one with all of its subscriptions.
+//!
+//! [MintConnector]: crate::wallet::MintConnector
+use cdk_common::nut17::ws::{NotificationInner, WsMessageOrResponse, WsRequest};
+use cdk_common::pub_sub::remote_consumer::{InternalRelay, StreamCtrl, SubscribeMessage};
+use cdk_common::pub_sub::Error as PubsubError;
+use futures::StreamExt;
+use tokio::sync::mpsc;
+
+use super::{MintSubTopics, SubscriptionClient};
+use crate::Error;
+
+/// Maximum size of a single event
+const MAX_EVENT_SIZE: usize = 1024 * 1024;
+
+/// Name of the first event of a stream, whose data is the id of the stream
+const STREAM_EVENT: &str = "stream";
+
+/// Name of the events without an `event` field
+const DEFAULT_EVENT: &str = "message";
+
+#[inline(always)]
+pub(crate) async fn stream_client(
+    client: &SubscriptionClient,
+    mut ctrl: mpsc::Receiver<StreamCtrl<MintSubTopics>>,
+    topics: Vec<SubscribeMessage<MintSubTopics>>,
+    reply_to: InternalRelay<MintSubTopics>,
+) -> Result<(), PubsubError> {
+    let params = topics
+        .into_iter()
+        .map(|(id, topic)| SubscriptionClient::get_params(id, topic))
+        .collect::<Vec<_>>();
+
+    tracing::debug!(
+        "Streaming {} subscriptions from {}",
+        params.len(),
+        client.mint_url
+    );
+    let mut events = client
+        .http_client
+        .post_sse(params)
+        .await
+        .map_err(|err| match err {
+            Error::HttpError(Some(404 | 405), _) => PubsubError::NotSupported,
+            err => {
+                tracing::error!("Error connecting: {err:?}");
+                PubsubError::Internal(Box::new(err))
+            }
+        })?;
+
+    let mut buffer = Vec::new();
+    let mut stream_id = None;
+
+    loop {
+        tokio::select! {
+            msg = ctrl.recv(), if stream_id.is_some() => {
+                let request = match msg {
+                    Some(StreamCtrl::Subscribe((name, topic))) => client.sub_request(name, topic),
+                    Some(StreamCtrl::Unsubscribe(name)) => client.unsub_request(name),
+                    Some(StreamCtrl::Stop) | None => return Ok(()),
+                };
+                if let Some(stream_id) = stream_id.as_deref() {
+                    send(client, stream_id, request).await?;
+                }
+            }
+            chunk = events.next() => {
+                let Some(chunk) = chunk else {
+                    return Err(PubsubError::InternalStr(
+                        "Mint closed the event stream".to_string(),
+                    ));
+                };
+                let chunk = chunk.map_err(|err| PubsubError::Internal(Box::new(err)))?;
+                buffer.extend_from_slice(&chunk);
+
+                while let Some((event, data)) = next_event(&mut buffer) {
+                    if event == STREAM_EVENT {
+                        stream_id = Some(data);
+                        continue;
+                    }
+
+                    match serde_json::from_str::<NotificationInner<String, String>>(&data) {
+                        Ok(notification) => reply_to.send(notification.payload),
+                        Err(err) => tracing::debug!("Could not parse event: {}", err),
+                    }
+                }
+
+                if buffer.len() > MAX_EVENT_SIZE {
+                    return Err(PubsubError::InternalStr("Event too large".to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Post a subscribe or unsubscribe request for the open event stream
+async fn send(
+    client: &SubscriptionClient,
+    stream_id: &str,
+    request: WsRequest<String>,
+) -> Result<(), PubsubError> {
+    let response = client
+        .http_client
+        .post_sse_request(stream_id, request)
+        .await
+        .map_err(|err| {
+            tracing::error!("Error changing the subscriptions of the stream: {err:?}");
+            PubsubError::Internal(Box::new(err))
+        })?;
+
+    match response {
+        WsMessageOrResponse::ErrorResponse(error) => {
+            tracing::debug!("Received an error from server: {:?}", error);
+            Err(PubsubError::InternalStr(error.error.message))
+        }
+        response => {
+            tracing::debug!("Received response from server: {:?}", response);
+            Ok(())
+        }
+    }
+}
+
+/// Take the name and the data of the next complete event out of the buffer, skipping keep-alive
+/// comments
+fn next_event(buffer: &mut Vec<u8>) -> Option<(String, String)> {
+    loop {
+        let end = buffer.windows(2).position(|window| window == b"\n\n")?;
+        let event = buffer.drain(..end + 2).collect::<Vec<_>>();
+        let event = String::from_utf8_lossy(&event);
+
+        let mut name = DEFAULT_EVENT.to_string();
+        let mut data = Vec::new();
+        for line in event.lines().map(|line| line.trim_end_matches('\r')) {
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => name = value.to_string(),
+                "data" => data.push(value),
+                _ => {}
+            }
+        }
+
+        if !data.is_empty() {
+            return Some((name, data.join("\n")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex as StdMutex};
+    use std::time::Duration;
+
+    use cdk_common::nut17::ws::{WsMethodRequest, WsSubscribeResponse};
+    use cdk_common::nut17::Kind;
+    use cdk_common::subscription::WalletParams;
+    use cdk_common::{MeltQuoteBolt12Request, MintQuoteBolt12Request, MintQuoteBolt12Response};
+
+    use super::*;
+    use crate::nuts::{
+        BatchCheckMintQuoteRequest, BatchMintRequest, BatchMintResponse, CheckStateRequest,
+        CheckStateResponse, Id, KeySet, KeysetResponse, MeltQuoteBolt11Request,
+        MeltQuoteBolt11Response, MeltRequest, MintInfo, MintQuoteBolt11Request,
+        MintQuoteBolt11Response, MintRequest, MintResponse, ProofState, RestoreRequest,
+        RestoreResponse, SecretKey, State, SwapRequest, SwapResponse,
+    };
+    use crate::wallet::mint_connector::transport::ByteStream;
+    use crate::wallet::subscription::SubscriptionManager;
+    use crate::wallet::MintConnector;
+
+    type EventSender = mpsc::UnboundedSender<Result<Vec<u8>, Error>>;
+
+    /// Mint streaming server-sent events from the channels of the test
+    #[derive(Debug, Default)]
+    struct FakeMint {
+        sse: bool,
+        streams: StdMutex<Vec<(Vec<WalletParams>, EventSender)>>,
+        requests: StdMutex<Vec<WsRequest<String>>>,
+    }
+
+    impl FakeMint {
+        fn stream_subscriptions(&self) -> Vec<Vec<String>> {
+            self.streams
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(params, _)| {
+                    let mut filters = params
+                        .iter()
+                        .flat_map(|params| params.filters.clone())
+                        .collect::<Vec<_>>();
+                    filters.sort();
+                    filters
+                })
+                .collect()
+        }
+
+        fn send(&self, event: &str) {
+            let streams = self.streams.lock().unwrap();
+            let (_, sender) = streams.last().expect("open stream");
+            sender.send(Ok(event.as_bytes().to_vec())).unwrap();
+        }
+
+        fn close(&self) {
+            self.streams.lock().unwrap().clear();
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MintConnector for FakeMint {
+        #[cfg(feature = "bip353")]
+        async fn resolve_dns_txt(&self, _domain: &str) -> Result<Vec<String>, Error> {
+            unimplemented!()
+        }
+        async fn fetch_lnurl_pay_request(
+            &self,
+            _url: &str,
+        ) -> Result<crate::lightning_address::LnurlPayResponse, Error> {
+            unimplemented!()
+        }
+        async fn fetch_lnurl_invoice(
+            &self,
+            _url: &str,
+        ) -> Result<crate::lightning_address::LnurlPayInvoiceResponse, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_keys(&self) -> Result<Vec<KeySet>, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_keyset(&self, _keyset_id: Id) -> Result<KeySet, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_keysets(&self) -> Result<KeysetResponse, Error> {
+            unimplemented!()
+        }
+        async fn post_mint_quote(
+            &self,
+            _request: MintQuoteBolt11Request,
+        ) -> Result<MintQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_quote_status(
+            &self,
+            _quote_id: &str,
+        ) -> Result<MintQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn post_mint(&self, _request: MintRequest<String>) -> Result<MintResponse, Error> {
+            unimplemented!()
+        }
+        async fn post_check_mint_quotes(
+            &self,
+            _request: BatchCheckMintQuoteRequest<String>,
+        ) -> Result<Vec<MintQuoteBolt11Response<String>>, Error> {
+            unimplemented!()
+        }
+        async fn post_mint_batch(
+            &self,
+            _request: BatchMintRequest<String>,
+        ) -> Result<BatchMintResponse, Error> {
+            unimplemented!()
+        }
+        async fn post_melt_quote(
+            &self,
+            _request: MeltQuoteBolt11Request,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn get_melt_quote_status(
+            &self,
+            _quote_id: &str,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn post_melt(
+            &self,
+            _request: MeltRequest<String>,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn post_swap(&self, _request: SwapRequest) -> Result<SwapResponse, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_info(&self) -> Result<MintInfo, Error> {
+            let mut info = MintInfo::default();
+            info.nuts.nut17.sse = self.sse;
+            Ok(info)
+        }
+        async fn post_check_state(
+            &self,
+            _request: CheckStateRequest,
+        ) -> Result<CheckStateResponse, Error> {
+            // Polled while the stream reconnects
+            Err(Error::Custom("Not polled in tests".to_string()))
+        }
+        async fn post_restore(&self, _request: RestoreRequest) -> Result<RestoreResponse, Error> {
+            unimplemented!()
+        }
+        #[cfg(feature = "auth")]
+        async fn get_auth_wallet(&self) -> Option<crate::wallet::AuthWallet> {
+            None
+        }
+        #[cfg(feature = "auth")]
+        async fn set_auth_wallet(&self, _wallet: Option<crate::wallet::AuthWallet>) {}
+        async fn post_mint_bolt12_quote(
+            &self,
+            _request: MintQuoteBolt12Request,
+        ) -> Result<MintQuoteBolt12Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn get_mint_quote_bolt12_status(
+            &self,
+            _quote_id: &str,
+        ) -> Result<MintQuoteBolt12Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn post_mint_bolt12(
+            &self,
+            _request: MintRequest<String>,
+        ) -> Result<MintResponse, Error> {
+            unimplemented!()
+        }
+        async fn post_melt_bolt12_quote(
+            &self,
+            _request: MeltQuoteBolt12Request,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn get_melt_bolt12_quote_status(
+            &self,
+            _quote_id: &str,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+        async fn post_melt_bolt12(
+            &self,
+            _request: MeltRequest<String>,
+        ) -> Result<MeltQuoteBolt11Response<String>, Error> {
+            unimplemented!()
+        }
+
+        async fn post_sse(&self, subscriptions: Vec<WalletParams>) -> Result<ByteStream, Error> {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            let id = self.streams.lock().unwrap().len();
+            sender
+                .send(Ok(
+                    format!("event: stream\ndata: stream-{id}\n\n").into_bytes()
+                ))
+                .unwrap();
+            self.streams.lock().unwrap().push((subscriptions, sender));
+
+            Ok(Box::pin(futures::stream::unfold(
+                receiver,
+                |mut receiver| async move { Some((receiver.recv().await?, receiver)) },
+            )))
+        }
+
+        async fn post_sse_request(
+            &self,
+            _stream_id: &str,
+            request: WsRequest<String>,
+        ) -> Result<WsMessageOrResponse<String>, Error> {
+            let id = request.id;
+            let sub_id = match &request.method {
+                WsMethodRequest::Subscribe(params) => params.id.clone(),
+                WsMethodRequest::Unsubscribe(request) => request.sub_id.clone(),
+            };
+            self.requests.lock().unwrap().push(request);
+
+            Ok((
+                id,
+                Ok(WsSubscribeResponse {
+                    status: "OK".to_string(),
+                    sub_id,
+                }
+                .into()),
+            )
+                .into())
+        }
+    }
+
+    fn client(mint: Arc<FakeMint>, prefer_sse: bool) -> SubscriptionClient {
+        SubscriptionClient {
+            http_client: mint,
+            mint_url: "https://mint.example".parse().unwrap(),
+            req_id: 0.into(),
+            prefer_sse,
+        }
+    }
+
+    fn proof_state_params(id: &str, y: &crate::nuts::PublicKey) -> WalletParams {
+        WalletParams {
+            kind: Kind::ProofState,
+            filters: vec![y.to_hex()],
+            id: Arc::new(id.to_string()),
+        }
+    }
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("condition reached");
+    }
+
+    #[test]
+    fn test_next_event() {
+        let mut buffer = b": keep-alive\n\nevent: stream\ndata: abc\n\ndata: {\"a\":1}\n\ndata: first\r\ndata: second\r\n\r\n\ndata: partial"
+            .to_vec();
+
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(("stream".to_string(), "abc".to_string()))
+        );
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(("message".to_string(), "{\"a\":1}".to_string()))
+        );
+        assert_eq!(
+            next_event(&mut buffer).map(|(_, data)| data).as_deref(),
+            Some("first\nsecond")
+        );
+        assert_eq!(next_event(&mut buffer), None);
+        assert_eq!(buffer, b"data: partial");
+
+        buffer.extend_from_slice(b"\n\n");
+        assert_eq!(
+            next_event(&mut buffer).map(|(_, data)| data).as_deref(),
+            Some("partial")
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_use_sse() {
+        let mint = |sse| {
+            Arc::new(FakeMint {
+                sse,
+                ..Default::default()
+            })
+        };
+
+        assert!(client(mint(true), true).use_sse().await);
+        // Only when preferred
+        assert!(!client(mint(true), false).use_sse().await);
+        // Only when advertised by the mint
+        assert!(!client(mint(false), true).use_sse().await);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_change_on_the_open_stream() {
+        let mint = Arc::new(FakeMint {
+            sse: true,
+            ..Default::default()
+        });
+        let manager = SubscriptionManager::new(mint.clone(), false).prefer_sse(true);
+        let mint_url: crate::mint_url::MintUrl = "https://mint.example".parse().unwrap();
+
+        let y = SecretKey::generate().public_key();
+        let mut first = manager
+            .subscribe(mint_url.clone(), proof_state_params("first", &y))
+            .unwrap();
+        wait_for(|| mint.stream_subscriptions().len() == 1).await;
+
+        let other = SecretKey::generate().public_key();
+        let second = manager
+            .subscribe(mint_url.clone(), proof_state_params("second", &other))
+            .unwrap();
+        wait_for(|| !mint.requests.lock().unwrap().is_empty()).await;
+        drop(second);
+        wait_for(|| mint.requests.lock().unwrap().len() == 2).await;
+
+        // Both changes went to the open stream
+        assert_eq!(mint.stream_subscriptions().len(), 1);
+        let requests = mint.requests.lock().unwrap().clone();
+        assert!(matches!(
+            &requests[0].method,
+            WsMethodRequest::Subscribe(params) if params.filters == vec![other.to_hex()]
+        ));
+        assert!(matches!(
+            &requests[1].method,
+            WsMethodRequest::Unsubscribe(_)
+        ));
+
+        let notification = serde_json::json!({
+            "subId": "first",
+            "payload": ProofState { y, state: State::Spent, witness: None },
+        });
+        mint.send(&format!("data: {notification}\n\n"));
+
+        let event = tokio::time::timeout(Duration::from_secs(10), first.recv())
+            .await
+            .expect("notification")
+            .expect("event");
+        assert!(matches!(
+            event.into_inner(),
+            crate::nuts::NotificationPayload::ProofState(ProofState {
+                state: State::Spent,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closed_stream_reopens_with_all_subscriptions() {
+        let mint = Arc::new(FakeMint {
+            sse: true,
+            ..Default::default()
+        });
+        let manager = SubscriptionManager::new(mint.clone(), false).prefer_sse(true);
+        let mint_url: crate::mint_url::MintUrl = "https://mint.example".parse().unwrap();
+
+        let mut ys = [
+            SecretKey::generate().public_key(),
+            SecretKey::generate().public_key(),
+        ];
+        let _first = manager
+            .subscribe(mint_url.clone(), proof_state_params("first", &ys[0]))
+            .unwrap();
+        wait_for(|| mint.stream_subscriptions().len() == 1).await;
+
+        let _second = manager
+            .subscribe(mint_url.clone(), proof_state_params("second", &ys[1]))
+            .unwrap();
+        wait_for(|| mint.requests.lock().unwrap().len() == 1).await;
+
+        // The consumer reconnects after its backoff
+        ys.sort_by_key(|y| y.to_hex());
+        mint.close();
+        wait_for(|| mint.stream_subscriptions().len() == 1).await;
+        assert_eq!(
+            mint.stream_subscriptions(),
+            vec![ys.iter().map(|y| y.to_hex()).collect::<Vec<_>>()]
+        );
+    }
+}
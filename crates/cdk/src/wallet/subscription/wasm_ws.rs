@@ -0,0 +1,108 @@
+//! Browser WebSocket transport
+//!
+//! Browsers do not allow setting headers on the WebSocket upgrade, so no auth is sent to the mint.
+//! Mints protecting the websocket reject the connection, and the consumer falls back to polling.
+use cdk_common::nut17::ws::WsMessageOrResponse;
+use cdk_common::pub_sub::remote_consumer::{InternalRelay, StreamCtrl, SubscribeMessage};
+use cdk_common::pub_sub::Error as PubsubError;
+use futures::{SinkExt, StreamExt};
+use gloo_net::websocket::futures::WebSocket;
+use gloo_net::websocket::Message;
+use tokio::sync::mpsc;
+
+use super::{MintSubTopics, SubscriptionClient};
+
+pub(crate) async fn stream_client(
+    client: &SubscriptionClient,
+    mut ctrl: mpsc::Receiver<StreamCtrl<MintSubTopics>>,
+    topics: Vec<SubscribeMessage<MintSubTopics>>,
+    reply_to: InternalRelay<MintSubTopics>,
+) -> Result<(), PubsubError> {
+    let mut url = client
+        .mint_url
+        .join_paths(&["v1", "ws"])
+        .expect("Could not join paths");
+
+    if url.scheme() == "https" {
+        url.set_scheme("wss").expect("Could not set scheme");
+    } else {
+        url.set_scheme("ws").expect("Could not set scheme");
+    }
+
+    tracing::debug!("Connecting to {}", url);
+    let ws_stream = WebSocket::open(url.as_str()).map_err(|err| {
+        tracing::error!("Error connecting: {err:?}");
+
+        PubsubError::Internal(Box::new(err))
+    })?;
+
+    tracing::debug!("Connected to {}", url);
+    // Dropping both halves closes the socket
+    let (mut write, mut read) = ws_stream.split();
+
+    for (name, index) in topics {
+        let (_, req) = if let Some(req) = client.get_sub_request(name, index) {
+            req
+        } else {
+            continue;
+        };
+
+        let _ = write.send(Message::Text(req)).await;
+    }
+
+    loop {
+        tokio::select! {
+            Some(msg) = ctrl.recv() => {
+                match msg {
+                    StreamCtrl::Subscribe(msg) => {
+                        let (_, req) = if let Some(req) = client.get_sub_request(msg.0, msg.1) {
+                            req
+                        } else {
+                            continue;
+                        };
+                        let _ = write.send(Message::Text(req)).await;
+                    }
+                    StreamCtrl::Unsubscribe(msg) => {
+                        let req = if let Some(req) = client.get_unsub_request(msg) {
+                            req
+                        } else {
+                            continue;
+                        };
+                        let _ = write.send(Message::Text(req)).await;
+                    }
+                    StreamCtrl::Stop => break,
+                };
+            }
+            Some(msg) = read.next() => {
+                let msg = match msg {
+                    Ok(Message::Text(msg)) => msg,
+                    Ok(Message::Bytes(_)) => continue,
+                    Err(err) => {
+                        tracing::error!("WebSocket error {err:?}");
+                        break;
+                    }
+                };
+                let msg = match serde_json::from_str::<WsMessageOrResponse<String>>(&msg) {
+                    Ok(msg) => msg,
+                    Err(_) => continue,
+                };
+
+                match msg {
+                    WsMessageOrResponse::Notification(payload) => {
+                        reply_to.send(payload.params.payload);
+                    }
+                    WsMessageOrResponse::Response(response) => {
+                        tracing::debug!("Received response from server: {:?}", response);
+                    }
+                    WsMessageOrResponse::ErrorResponse(error) => {
+                        tracing::debug!("Received an error from server: {:?}", error);
+                        return Err(PubsubError::InternalStr(error.error.message));
+                    }
+                }
+            }
+            else => break,
+        }
+    }
+
+    Ok(())
+}
@@ -1,8 +1,6 @@
 use cdk_common::nut17::ws::WsMessageOrResponse;
 use cdk_common::pub_sub::remote_consumer::{InternalRelay, StreamCtrl, SubscribeMessage};
 use cdk_common::pub_sub::Error as PubsubError;
-#[cfg(feature = "auth")]
-use cdk_common::{Method, RoutePath};
 use futures::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio_tungstenite::connect_async;
@@ -44,35 +42,18 @@ pub(crate) async fn stream_client(
     })?;
 
     #[cfg(feature = "auth")]
-    {
-        let auth_wallet = client.http_client.get_auth_wallet().await;
-        let token = match auth_wallet.as_ref() {
-            Some(auth_wallet) => {
-                let endpoint = cdk_common::ProtectedEndpoint::new(Method::Get, RoutePath::Ws);
-                match auth_wallet.get_auth_for_request(&endpoint).await {
-                    Ok(token) => token,
-                    Err(err) => {
-                        tracing::warn!("Failed to get auth token: {:?}", err);
-                        None
-                    }
-                }
-            }
-            None => None,
+    if let Some(auth_token) = client.auth_token().await {
+        let header_key = match &auth_token {
+            cdk_common::AuthToken::ClearAuth(_) => "Clear-auth",
+            cdk_common::AuthToken::BlindAuth(_) => "Blind-auth",
         };
 
-        if let Some(auth_token) = token {
-            let header_key = match &auth_token {
-                cdk_common::AuthToken::ClearAuth(_) => "Clear-auth",
-                cdk_common::AuthToken::BlindAuth(_) => "Blind-auth",
-            };
-
-            match auth_token.to_string().parse() {
-                Ok(header_value) => {
-                    request.headers_mut().insert(header_key, header_value);
-                }
-                Err(err) => {
-                    tracing::warn!("Failed to parse auth token as header value: {:?}", err);
-                }
+        match auth_token.to_string().parse() {
+            Ok(header_value) => {
+                request.headers_mut().insert(header_key, header_value);
+            }
+            Err(err) => {
+                tracing::warn!("Failed to parse auth token as header value: {:?}", err);
             }
         }
     }
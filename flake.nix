@@ -114,10 +114,15 @@
             cargo-outdated
             cargo-mutants
 
+            # Headless browser tests of cdk-wasm
+            wasm-pack
+            geckodriver
+
             # Needed for github ci
             libz
           ]
-          ++ libsDarwin;
+          ++ libsDarwin
+          ++ lib.optionals (!isDarwin) [ firefox ];
 
         # Common arguments can be set here to avoid repeating them later
         nativeBuildInputs = [
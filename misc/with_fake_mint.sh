@@ -0,0 +1,41 @@
+#!/usr/bin/env bash
+
+# Runs a command against a fake mint
+#
+# Starts the fake mint binary in a temporary directory, exports its url as CDK_TEST_MINT_URL,
+# runs the command given as arguments and stops the mint once the command exits.
+
+set -euo pipefail
+
+cleanup() {
+    if [ -n "${FAKE_MINT_PID:-}" ]; then
+        kill -2 $FAKE_MINT_PID 2>/dev/null || true
+        wait $FAKE_MINT_PID 2>/dev/null || true
+    fi
+
+    if [ -n "${CDK_ITESTS_DIR:-}" ] && [ -d "$CDK_ITESTS_DIR" ]; then
+        rm -rf "$CDK_ITESTS_DIR"
+    fi
+}
+
+trap cleanup EXIT INT TERM
+
+export CDK_ITESTS_DIR=$(mktemp -d)
+
+cargo build --bin start_fake_mint
+cargo run --bin start_fake_mint -- sqlite "$CDK_ITESTS_DIR" &
+FAKE_MINT_PID=$!
+
+# The binary writes the .env file before starting the mint
+until [ -f "$CDK_ITESTS_DIR/.env" ]; do
+    sleep 1
+done
+source "$CDK_ITESTS_DIR/.env"
+export CDK_TEST_MINT_URL
+
+until curl -sf -o /dev/null "$CDK_TEST_MINT_URL/v1/info"; do
+    echo "Waiting for the fake mint at $CDK_TEST_MINT_URL"
+    sleep 1
+done
+
+"$@"